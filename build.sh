@@ -168,6 +168,6 @@ command -v strip >/dev/null 2>&1 && strip "build/Split Happens" || true && \
 cp LICENSE build/ && cp COPYING.md build/thirdparty.txt && \
 # Bundle the Big Picture helper so Steam users can add Split Happens quickly.
 cp split_happens_big_picture.sh build/ && chmod +x build/split_happens_big_picture.sh && \
-cp res/splitscreen_kwin.js res/splitscreen_kwin_vertical.js build/res && \
+cp res/splitscreen_kwin.js res/splitscreen_borders.qml build/res && \
 gsc=$(command -v gamescope || true) && \
 [ -n "$gsc" ] && cp "$gsc" build/bin/gamescope-kbm || true
@@ -0,0 +1,729 @@
+use crate::handler::Handler;
+use crate::input::DEFAULT_SPLIT_KEYS;
+use crate::layout::{FourPlayerLayout, LayoutRegion, ThreePlayerLayout, TwoPlayerSplit};
+use crate::paths::*;
+use crate::logging::log_warn;
+use crate::util::{
+    DEFAULT_SCREENSHOT_CHORD, DEFAULT_WEB_REMOTE_PORT, OutputRule, ResourceKind,
+    default_output_rules, msg, write_atomic, yesno,
+};
+
+use std::collections::{BTreeMap, HashMap};
+use std::error::Error;
+use std::fs::{self, File};
+use std::io::BufReader;
+use std::ops::RangeInclusive;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+#[derive(Serialize, Deserialize, Clone, PartialEq)]
+pub enum PadFilterType {
+    All,
+    NoSteamInput,
+    OnlySteamInput,
+}
+
+/// Order in which "Auto Assign" hands connected gamepads to player slots.
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Default)]
+pub enum AutoAssignOrder {
+    /// The pad connected first becomes player 1.
+    #[default]
+    ConnectionTime,
+    /// Pads grouped by vendor and product, then by serial or port.
+    Vendor,
+}
+
+impl AutoAssignOrder {
+    pub const ALL: [AutoAssignOrder; 2] =
+        [AutoAssignOrder::ConnectionTime, AutoAssignOrder::Vendor];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            AutoAssignOrder::ConnectionTime => "Connection order",
+            AutoAssignOrder::Vendor => "Vendor",
+        }
+    }
+}
+
+/// Launcher action a Steam Deck rear button triggers in the menus.
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Default)]
+pub enum RearButtonAction {
+    #[default]
+    None,
+    PreviousPage,
+    NextPage,
+    /// Rescans games, profiles and controllers.
+    Rescan,
+    /// Sets up the game launched last with its remembered players and starts it.
+    LaunchLast,
+}
+
+impl RearButtonAction {
+    pub const ALL: [RearButtonAction; 5] = [
+        RearButtonAction::None,
+        RearButtonAction::PreviousPage,
+        RearButtonAction::NextPage,
+        RearButtonAction::Rescan,
+        RearButtonAction::LaunchLast,
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            RearButtonAction::None => "Nothing",
+            RearButtonAction::PreviousPage => "Previous page",
+            RearButtonAction::NextPage => "Next page",
+            RearButtonAction::Rescan => "Rescan",
+            RearButtonAction::LaunchLast => "Launch last session",
+        }
+    }
+}
+
+/// Actions of the Steam Deck's L4, L5, R4 and R5 buttons in the launcher.
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq)]
+#[serde(default)]
+pub struct RearButtonBindings {
+    pub l4: RearButtonAction,
+    pub l5: RearButtonAction,
+    pub r4: RearButtonAction,
+    pub r5: RearButtonAction,
+}
+
+impl Default for RearButtonBindings {
+    fn default() -> Self {
+        RearButtonBindings {
+            l4: RearButtonAction::PreviousPage,
+            l5: RearButtonAction::None,
+            r4: RearButtonAction::NextPage,
+            r5: RearButtonAction::None,
+        }
+    }
+}
+
+/// Size of the game tiles on the home grid.
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Default)]
+pub enum TileDensity {
+    /// Small tiles, so large libraries fit on a TV without endless scrolling.
+    Compact,
+    #[default]
+    Normal,
+    Large,
+}
+
+impl TileDensity {
+    pub const ALL: [TileDensity; 3] =
+        [TileDensity::Compact, TileDensity::Normal, TileDensity::Large];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            TileDensity::Compact => "Compact",
+            TileDensity::Normal => "Normal",
+            TileDensity::Large => "Large",
+        }
+    }
+
+    /// Narrowest a tile gets before the grid drops a column.
+    pub fn min_tile_width(&self) -> f32 {
+        match self {
+            TileDensity::Compact => 140.0,
+            TileDensity::Normal => 200.0,
+            TileDensity::Large => 300.0,
+        }
+    }
+
+    /// Bounds of the artwork height, which otherwise follows the tile width.
+    pub fn image_height_range(&self) -> (f32, f32) {
+        match self {
+            TileDensity::Compact => (60.0, 110.0),
+            TileDensity::Normal => (100.0, 200.0),
+            TileDensity::Large => (150.0, 300.0),
+        }
+    }
+
+    pub fn title_size(&self) -> f32 {
+        match self {
+            TileDensity::Compact => 15.0,
+            TileDensity::Normal => 20.0,
+            TileDensity::Large => 24.0,
+        }
+    }
+}
+
+/// How many `--exec` launches the light UI remembers.
+const RECENT_EXECS_MAX: usize = 10;
+
+/// One `--exec` launch remembered for the light UI's Recent list.
+#[derive(Serialize, Deserialize, Clone, PartialEq)]
+pub struct RecentExec {
+    pub path: String,
+    #[serde(default)]
+    pub args: String,
+    /// Device identities per instance, as in `last_device_assignments`.
+    #[serde(default)]
+    pub devices: Vec<Vec<String>>,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct PartyConfig {
+    pub force_sdl: bool,
+    pub enable_kwin_script: bool,
+    pub gamescope_fix_lowres: bool,
+    pub gamescope_sdl_backend: bool,
+    pub kbm_support: bool,
+    pub proton_version: String,
+    pub proton_separate_pfxs: bool,
+    // Replaced by `two_player_split`; only read to carry old settings over.
+    #[serde(default, skip_serializing)]
+    pub vertical_two_player: bool,
+    #[serde(default)]
+    pub two_player_split: TwoPlayerSplit,
+    pub pad_filter_type: PadFilterType,
+    #[serde(default)]
+    pub last_profile_assignments: HashMap<String, Vec<String>>,
+    // Performance toggles that gate optional Steam Deck optimizations.
+    #[serde(default)]
+    pub performance_limit_40fps: bool,
+    #[serde(default)]
+    pub performance_gamescope_rt: bool,
+    #[serde(default)]
+    pub performance_enable_proton_fsr: bool,
+    // Comma-separated local accounts assigned to instance slots when a handler
+    // opts into running each player as a separate OS user.
+    #[serde(default)]
+    pub multiseat_users: String,
+    // Optional endpoint notified via HTTP POST on session start/end/crash.
+    #[serde(default)]
+    pub webhook_url: String,
+    // Announces this machine to other Split Happens hosts on the LAN and
+    // accepts synchronized launch countdowns from them.
+    #[serde(default)]
+    pub coordinator_enabled: bool,
+    // Draws a colored frame per player around each instance via KWin.
+    #[serde(default)]
+    pub kwin_player_borders: bool,
+    // Index URL the handler browser fetches available handlers from.
+    #[serde(default)]
+    pub handler_repository_url: String,
+    // Folds every extra gamepad assigned to an instance into its first pad so a
+    // helper can play alongside the main player as one controller.
+    #[serde(default)]
+    pub copilot_merge: bool,
+    // Tiles gamescope windows through X11 instead of the KWin script so
+    // automatic layout also works outside Plasma.
+    #[serde(default)]
+    pub native_layout: bool,
+    // Grid presets the native layout uses for three and four players.
+    #[serde(default)]
+    pub three_player_layout: ThreePlayerLayout,
+    #[serde(default)]
+    pub four_player_layout: FourPlayerLayout,
+    // Player regions drawn in the layout editor, keyed by player count. They
+    // replace the presets for that count.
+    #[serde(default)]
+    pub custom_layouts: HashMap<String, Vec<LayoutRegion>>,
+    // Launches with every optional extra turned off, to tell whether a failure
+    // comes from Split Happens or from the game and its handler.
+    #[serde(default)]
+    pub safe_mode: bool,
+    // Handler-recommended settings the user unticked, keyed by handler uid.
+    #[serde(default)]
+    pub declined_recommendations: HashMap<String, Vec<String>>,
+    // Ctrl+Shift hotkeys read from every keyboard while a session runs.
+    #[serde(default = "default_true")]
+    pub session_hotkeys: bool,
+    // Pad buttons that screenshot the pane of the player pressing them; empty
+    // turns it off. See `parse_screenshot_chord`.
+    #[serde(default = "default_screenshot_chord")]
+    pub screenshot_chord: String,
+    // Downloaded emulator builds picked per handler uid, keyed by resource kind.
+    // Handlers without an entry use the builds bundled in res/.
+    #[serde(default)]
+    pub resource_versions: HashMap<String, HashMap<ResourceKind, String>>,
+    #[serde(default)]
+    pub auto_assign_order: AutoAssignOrder,
+    #[serde(default)]
+    pub tile_density: TileDensity,
+    // Restores each game's last controller-to-slot assignment when its
+    // instances page opens.
+    #[serde(default)]
+    pub remember_device_assignments: bool,
+    // Device identities per slot of the last launch, keyed by game id.
+    #[serde(default)]
+    pub last_device_assignments: HashMap<String, Vec<Vec<String>>>,
+    // Extra environment variables per instance slot, keyed by game id. Set
+    // after the game-wide launch overrides, so they win over them.
+    #[serde(default)]
+    pub instance_env: HashMap<String, Vec<BTreeMap<String, String>>>,
+    // Suppresses every outbound request: update checks, Steam header art,
+    // handler and emulator downloads, and webhooks.
+    #[serde(default)]
+    pub offline_mode: bool,
+    // Keys the first player gets when a keyboard is split between two
+    // players; the rest go to the second. See `parse_key_ranges`.
+    #[serde(default = "default_split_keys")]
+    pub keyboard_split_keys: String,
+    #[serde(default)]
+    pub timings: Timings,
+    // Set once the first-run wizard was finished or skipped. Settings saved
+    // before the wizard existed count as set up.
+    #[serde(default = "default_true")]
+    pub setup_wizard_done: bool,
+    // Serves a page on the LAN where players pick their profile and ready up
+    // from their phones while the instances page is open.
+    #[serde(default)]
+    pub web_remote_enabled: bool,
+    #[serde(default = "default_web_remote_port")]
+    pub web_remote_port: u16,
+    // Removes the working trees under `run/` that no running session uses
+    // when the launcher starts. `None` asks the first time leftovers are found.
+    #[serde(default)]
+    pub clean_run_trees: Option<bool>,
+    // Lets a TV remote navigate the launcher over HDMI-CEC through libcec's
+    // cec-client.
+    #[serde(default)]
+    pub cec_remote_enabled: bool,
+    // Asks every player to press a button before a launch from the instances
+    // page goes ahead.
+    #[serde(default)]
+    pub ready_check: bool,
+    // Personal SteamGridDB API key; empty leaves artwork to the Steam headers.
+    #[serde(default)]
+    pub steamgriddb_api_key: String,
+    // Minutes without input on any device of a session before it counts as
+    // idle; 0 turns idle detection off.
+    #[serde(default)]
+    pub idle_timeout_minutes: u32,
+    // What happens to an idle session until someone presses a button.
+    #[serde(default = "default_true")]
+    pub idle_pause_instances: bool,
+    #[serde(default)]
+    pub idle_dim_display: bool,
+    // Keeps every pad of a session behind a proxy so one that disconnects
+    // mid-game can be rebound when it reconnects.
+    #[serde(default)]
+    pub hotplug_rebind: bool,
+    // Handler author mode: maximum log verbosity everywhere, a separate
+    // logs-dev folder and a "copy launch plan" button on every page.
+    #[serde(default)]
+    pub author_mode: bool,
+    // Fetches each handler's DLC list from the Steam store for Goldberg.
+    #[serde(default)]
+    pub goldberg_dlc_list: bool,
+    // Bundled Goldberg build every game uses instead of the one its handler
+    // asks for; empty follows the handler.
+    #[serde(default)]
+    pub goldberg_version: String,
+    // Clicks and rumble pulses when a controller moves or confirms in the menus.
+    #[serde(default)]
+    pub nav_sounds: bool,
+    #[serde(default)]
+    pub nav_haptics: bool,
+    // Launcher actions on the Steam Deck's rear buttons.
+    #[serde(default)]
+    pub rear_buttons: RearButtonBindings,
+    // Executables launched through --exec, most recent first.
+    #[serde(default)]
+    pub recent_execs: Vec<RecentExec>,
+    // Regex rules hiding or highlighting lines of instance output, in the
+    // console and the session logs. The first matching rule wins.
+    #[serde(default = "default_output_rules")]
+    pub output_rules: Vec<OutputRule>,
+}
+
+/// Waits the launcher uses between and around instances. Slow HDD systems
+/// need longer ones; fast NVMe rigs can shorten them. Values outside the
+/// ranges below are clamped when the settings load.
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Debug)]
+#[serde(default)]
+pub struct Timings {
+    /// Pause after starting one instance before starting the next.
+    pub instance_stagger_ms: u64,
+    /// Pause before respawning an instance the user restarted.
+    pub restart_delay_ms: u64,
+    /// How long the session loop sleeps when no instance changed.
+    pub session_poll_ms: u64,
+    /// How long the loading screen waits on a task, or a launch that stopped
+    /// reporting progress, before reporting a timeout.
+    pub task_timeout_secs: u64,
+}
+
+impl Default for Timings {
+    fn default() -> Self {
+        Timings {
+            instance_stagger_ms: 6000,
+            restart_delay_ms: 2000,
+            session_poll_ms: 250,
+            task_timeout_secs: 60,
+        }
+    }
+}
+
+impl Timings {
+    pub const INSTANCE_STAGGER_MS: RangeInclusive<u64> = 0..=60_000;
+    pub const RESTART_DELAY_MS: RangeInclusive<u64> = 0..=30_000;
+    pub const SESSION_POLL_MS: RangeInclusive<u64> = 50..=2000;
+    pub const TASK_TIMEOUT_SECS: RangeInclusive<u64> = 10..=600;
+
+    /// Clamps every value into its range and returns the names of the ones
+    /// that were out of bounds.
+    pub fn validate(&mut self) -> Vec<&'static str> {
+        let mut clamped = Vec::new();
+        for (name, value, range) in [
+            ("instance_stagger_ms", &mut self.instance_stagger_ms, Self::INSTANCE_STAGGER_MS),
+            ("restart_delay_ms", &mut self.restart_delay_ms, Self::RESTART_DELAY_MS),
+            ("session_poll_ms", &mut self.session_poll_ms, Self::SESSION_POLL_MS),
+            ("task_timeout_secs", &mut self.task_timeout_secs, Self::TASK_TIMEOUT_SECS),
+        ] {
+            let bounded = (*value).clamp(*range.start(), *range.end());
+            if bounded != *value {
+                *value = bounded;
+                clamped.push(name);
+            }
+        }
+        clamped
+    }
+
+    pub fn instance_stagger(&self) -> Duration {
+        Duration::from_millis(self.instance_stagger_ms)
+    }
+
+    pub fn restart_delay(&self) -> Duration {
+        Duration::from_millis(self.restart_delay_ms)
+    }
+
+    pub fn session_poll(&self) -> Duration {
+        Duration::from_millis(self.session_poll_ms)
+    }
+
+    pub fn task_timeout(&self) -> Duration {
+        Duration::from_secs(self.task_timeout_secs)
+    }
+}
+
+pub(crate) fn default_true() -> bool {
+    true
+}
+
+fn default_split_keys() -> String {
+    DEFAULT_SPLIT_KEYS.to_string()
+}
+
+fn default_screenshot_chord() -> String {
+    DEFAULT_SCREENSHOT_CHORD.to_string()
+}
+
+fn default_web_remote_port() -> u16 {
+    DEFAULT_WEB_REMOTE_PORT
+}
+
+impl Default for PartyConfig {
+    fn default() -> Self {
+        PartyConfig {
+            force_sdl: false,
+            enable_kwin_script: true,
+            gamescope_fix_lowres: true,
+            gamescope_sdl_backend: true,
+            kbm_support: true,
+            proton_version: "".to_string(),
+            proton_separate_pfxs: false,
+            vertical_two_player: false,
+            two_player_split: TwoPlayerSplit::default(),
+            pad_filter_type: PadFilterType::NoSteamInput,
+            last_profile_assignments: HashMap::new(),
+            performance_limit_40fps: false,
+            performance_gamescope_rt: false,
+            performance_enable_proton_fsr: false,
+            multiseat_users: String::new(),
+            webhook_url: String::new(),
+            coordinator_enabled: false,
+            kwin_player_borders: false,
+            handler_repository_url: String::new(),
+            copilot_merge: false,
+            native_layout: false,
+            three_player_layout: ThreePlayerLayout::default(),
+            four_player_layout: FourPlayerLayout::default(),
+            custom_layouts: HashMap::new(),
+            safe_mode: false,
+            declined_recommendations: HashMap::new(),
+            session_hotkeys: true,
+            screenshot_chord: default_screenshot_chord(),
+            resource_versions: HashMap::new(),
+            auto_assign_order: AutoAssignOrder::default(),
+            tile_density: TileDensity::default(),
+            remember_device_assignments: false,
+            last_device_assignments: HashMap::new(),
+            instance_env: HashMap::new(),
+            offline_mode: false,
+            keyboard_split_keys: default_split_keys(),
+            timings: Timings::default(),
+            setup_wizard_done: false,
+            web_remote_enabled: false,
+            web_remote_port: default_web_remote_port(),
+            clean_run_trees: None,
+            cec_remote_enabled: false,
+            ready_check: false,
+            steamgriddb_api_key: String::new(),
+            idle_timeout_minutes: 0,
+            idle_pause_instances: true,
+            idle_dim_display: false,
+            hotplug_rebind: false,
+            author_mode: false,
+            goldberg_dlc_list: false,
+            goldberg_version: String::new(),
+            nav_sounds: false,
+            nav_haptics: false,
+            rear_buttons: RearButtonBindings::default(),
+            recent_execs: Vec::new(),
+            output_rules: default_output_rules(),
+        }
+    }
+}
+
+/// Hover text for the safe mode toggle next to the Start buttons.
+pub const SAFE_MODE_INFO: &str = "Launch without the KWin/native layout, gamescope-kbm, forced SDL, controller proxies (calibration, gyro, assists and remaps), FSR, real-time scheduling, the FPS limit and CPU pinning. If the game works in safe mode, one of those extras is to blame; if it still fails, look at the game or its handler.";
+
+impl PartyConfig {
+    /// Settings a launch actually uses. In safe mode the window scripts,
+    /// gamescope-kbm, SDL forcing and performance tweaks are switched off; CPU
+    /// pinning, priority boosts and pad proxies are skipped by the launcher
+    /// itself.
+    pub fn launch_config(&self) -> PartyConfig {
+        let mut cfg = self.clone();
+        if cfg.safe_mode {
+            cfg.enable_kwin_script = false;
+            cfg.native_layout = false;
+            cfg.kwin_player_borders = false;
+            cfg.kbm_support = false;
+            cfg.force_sdl = false;
+            cfg.copilot_merge = false;
+            cfg.performance_limit_40fps = false;
+            cfg.performance_gamescope_rt = false;
+            cfg.performance_enable_proton_fsr = false;
+        }
+        cfg
+    }
+
+    /// Copy of these settings with `fields` written over the matching keys.
+    /// Unknown keys and values of another type are skipped.
+    pub fn with_fields(&self, fields: &Map<String, Value>) -> PartyConfig {
+        let Ok(Value::Object(mut current)) = serde_json::to_value(self) else {
+            return self.clone();
+        };
+        for (key, value) in fields {
+            if let Some(existing) = current.get_mut(key) {
+                if overridable(existing, value) {
+                    *existing = value.clone();
+                }
+            }
+        }
+        serde_json::from_value(Value::Object(current)).unwrap_or_else(|_| self.clone())
+    }
+
+    /// Settings `handler` recommends that map onto one of the
+    /// [`RECOMMENDABLE_SETTINGS`], in handler.json order.
+    pub fn handler_recommendations(&self, handler: &Handler) -> Map<String, Value> {
+        let Ok(Value::Object(current)) = serde_json::to_value(self) else {
+            return Map::new();
+        };
+        handler
+            .recommended_settings
+            .iter()
+            .filter(|(key, _)| RECOMMENDABLE_SETTINGS.contains(&key.as_str()))
+            .filter(|(key, value)| {
+                current
+                    .get(key.as_str())
+                    .is_some_and(|existing| overridable(existing, value))
+            })
+            .map(|(key, value)| (key.clone(), value.clone()))
+            .collect()
+    }
+
+    /// Whether the user unticked the recommendation `key` of `handler`.
+    pub fn recommendation_declined(&self, handler: &Handler, key: &str) -> bool {
+        self.declined_recommendations
+            .get(&handler.uid)
+            .is_some_and(|declined| declined.iter().any(|k| k == key))
+    }
+
+    /// Settings a launch of `handler` uses: its accepted recommendations on
+    /// top of these settings, then safe mode.
+    pub fn handler_launch_config(&self, handler: &Handler) -> PartyConfig {
+        let accepted: Map<String, Value> = self
+            .handler_recommendations(handler)
+            .into_iter()
+            .filter(|(key, _)| !self.recommendation_declined(handler, key))
+            .collect();
+        let mut cfg = self.with_fields(&accepted);
+        // Only the user turns these off, whatever a handler recommends.
+        cfg.safe_mode = self.safe_mode;
+        cfg.offline_mode = self.offline_mode;
+        cfg.launch_config()
+    }
+
+    /// Moves the per-game settings of handler uid `old` to `new`, keeping any
+    /// the new uid already has.
+    pub fn migrate_game_uid(&mut self, old: &str, new: &str) {
+        fn rekey<V>(map: &mut HashMap<String, V>, old: &str, new: &str) {
+            if let Some(value) = map.remove(old) {
+                map.entry(new.to_string()).or_insert(value);
+            }
+        }
+        let (old_game, new_game) = (format!("handler:{old}"), format!("handler:{new}"));
+        rekey(&mut self.last_profile_assignments, &old_game, &new_game);
+        rekey(&mut self.last_device_assignments, &old_game, &new_game);
+        rekey(&mut self.instance_env, &old_game, &new_game);
+        rekey(&mut self.declined_recommendations, old, new);
+        rekey(&mut self.resource_versions, old, new);
+    }
+
+    /// Environment variables the Instances page set for slot `index` of
+    /// `game_id`.
+    pub fn instance_env(&self, game_id: &str, index: usize) -> Option<&BTreeMap<String, String>> {
+        self.instance_env.get(game_id)?.get(index)
+    }
+
+    /// Puts `launch` at the top of the Recent list, replacing an earlier
+    /// launch of the same executable with the same arguments.
+    pub fn record_exec_launch(&mut self, launch: RecentExec) {
+        self.recent_execs
+            .retain(|recent| recent.path != launch.path || recent.args != launch.args);
+        self.recent_execs.insert(0, launch);
+        self.recent_execs.truncate(RECENT_EXECS_MAX);
+    }
+
+    /// Bundled Goldberg build under `res/goldberg/` for `handler`: the one
+    /// named in the settings, else the one the handler asks for. `None` is
+    /// the default build.
+    pub fn bundled_goldberg_version<'a>(&'a self, handler: &'a Handler) -> Option<&'a str> {
+        [self.goldberg_version.as_str(), handler.goldberg_version.as_str()]
+            .into_iter()
+            .find(|version| !version.is_empty())
+    }
+
+    /// Downloaded build of `kind` picked for `handler`, or `None` for the one
+    /// bundled in res/.
+    pub fn resource_version(&self, handler: &Handler, kind: ResourceKind) -> Option<&str> {
+        self.resource_versions
+            .get(&handler.uid)?
+            .get(&kind)
+            .map(String::as_str)
+    }
+}
+
+/// Settings a handler's `launcher.settings` may recommend: switches that tune
+/// how a game is launched. Handlers come from third-party repositories, so
+/// safe and offline mode, URLs, keys, accounts and the Proton build stay with
+/// the user.
+const RECOMMENDABLE_SETTINGS: &[&str] = &[
+    "copilot_merge",
+    "enable_kwin_script",
+    "force_sdl",
+    "gamescope_fix_lowres",
+    "gamescope_sdl_backend",
+    "goldberg_dlc_list",
+    "hotplug_rebind",
+    "kbm_support",
+    "kwin_player_borders",
+    "native_layout",
+    "performance_enable_proton_fsr",
+    "performance_gamescope_rt",
+    "performance_limit_40fps",
+    "proton_separate_pfxs",
+];
+
+/// Only plain values of the same JSON type can replace a setting, so handlers
+/// can't swap out maps like the saved profile assignments.
+fn overridable(existing: &Value, value: &Value) -> bool {
+    match (existing, value) {
+        (Value::Bool(_), Value::Bool(_)) => true,
+        (Value::String(_), Value::String(_)) => true,
+        (Value::Number(_), Value::Number(_)) => true,
+        _ => false,
+    }
+}
+
+fn cfg_path() -> PathBuf {
+    PATH_APP.join("settings.json")
+}
+
+/// Last settings file that parsed, kept by [`save_cfg`] before replacing it.
+fn cfg_backup_path() -> PathBuf {
+    PATH_APP.join("settings.json.bak")
+}
+
+fn read_cfg(path: &Path) -> Result<PartyConfig, Box<dyn Error>> {
+    let file = File::open(path)?;
+    let mut config: PartyConfig = serde_json::from_reader(BufReader::new(file))?;
+    if std::mem::take(&mut config.vertical_two_player) {
+        config.two_player_split = TwoPlayerSplit::Vertical;
+    }
+    let clamped = config.timings.validate();
+    if !clamped.is_empty() {
+        log_warn(format!("Clamped out-of-range timings: {}", clamped.join(", ")));
+    }
+    Ok(config)
+}
+
+/// The saved settings, or the defaults when they can't be read. Unlike
+/// [`load_cfg`] it neither asks anything nor moves a broken file aside, for
+/// callers that only look at the settings.
+pub fn peek_cfg() -> PartyConfig {
+    read_cfg(&cfg_path()).unwrap_or_default()
+}
+
+pub fn load_cfg() -> PartyConfig {
+    let path = cfg_path();
+    if !path.exists() {
+        return PartyConfig::default();
+    }
+    let err = match read_cfg(&path) {
+        Ok(config) => return config,
+        Err(err) => err,
+    };
+    log_warn(format!("Couldn't read {}: {err}", path.display()));
+
+    // Keep the broken file around instead of overwriting it on the next save.
+    let corrupt = PATH_APP.join("settings.json.corrupt");
+    let _ = fs::rename(&path, &corrupt);
+
+    if let Ok(backup) = read_cfg(&cfg_backup_path()) {
+        if yesno(
+            "Settings Error",
+            &format!(
+                "Your settings couldn't be read ({err}). Restore the backup from the last successful save?\n\nThe unreadable file was moved to {}.",
+                corrupt.display()
+            ),
+        ) {
+            if let Ok(contents) = fs::read(cfg_backup_path()) {
+                let _ = write_atomic(&path, contents);
+            }
+            return backup;
+        }
+    } else {
+        msg(
+            "Settings Error",
+            &format!(
+                "Your settings couldn't be read ({err}) and there is no backup, so the defaults are used.\n\nThe unreadable file was moved to {}.",
+                corrupt.display()
+            ),
+        );
+    }
+    PartyConfig::default()
+}
+
+/// Writes the settings to a temporary file and renames it over the old one,
+/// so a crash mid-save can't leave a half-written file behind. The previous
+/// file is kept the same way as one backup generation if it still parsed.
+pub fn save_cfg(config: &PartyConfig) -> Result<(), Box<dyn Error>> {
+    let path = cfg_path();
+    // A failed backup shouldn't stop the save itself.
+    if read_cfg(&path).is_ok() {
+        if let Ok(previous) = fs::read(&path) {
+            let _ = write_atomic(&cfg_backup_path(), previous);
+        }
+    }
+    write_atomic(&path, serde_json::to_vec_pretty(config)?)?;
+    Ok(())
+}
@@ -1,9 +1,10 @@
 use crate::handler::{Handler, install_handler_from_file, scan_handlers};
 use crate::paths::*;
-use crate::util::SanitizePath;
+use crate::util::{
+    ANTICHEAT_TITLE, ArtworkRequest, SanitizePath, anticheat_warning, msg, pe_arch, pick_file,
+    yesno,
+};
 
-use eframe::egui::{self, ImageSource};
-use rfd::FileDialog;
 use serde_json::Value;
 use std::error::Error;
 use std::fs::File;
@@ -62,14 +63,6 @@ impl Game {
             Game::HandlerRef(handler) => handler.display(),
         }
     }
-    pub fn icon(&self) -> ImageSource<'_> {
-        match self {
-            Game::ExecRef(_) => egui::include_image!("../res/executable_icon.png"),
-            Game::HandlerRef(handler) => {
-                format!("file://{}/icon.png", handler.path_handler.display()).into()
-            }
-        }
-    }
 
     /// Surfaces the optional Steam header artwork so the UI can render
     /// full-width tiles without duplicating handler lookup logic.
@@ -80,6 +73,24 @@ impl Game {
         }
     }
 
+    /// What SteamGridDB artwork for this game is looked up and cached by.
+    pub fn artwork_request(&self) -> ArtworkRequest {
+        let (steam_appid, title) = match self {
+            Game::ExecRef(exec) => {
+                let stem = exec.path().file_stem().unwrap_or_default().to_string_lossy();
+                (None, stem.to_string())
+            }
+            Game::HandlerRef(handler) => {
+                (handler.steam_appid.clone(), handler.display().to_string())
+            }
+        };
+        ArtworkRequest {
+            game_id: self.persistent_id(),
+            steam_appid,
+            title,
+        }
+    }
+
     /// Generates a stable identifier so per-game state (like remembered profile
     /// selections) can survive application restarts regardless of UI order.
     pub fn persistent_id(&self) -> String {
@@ -120,10 +131,7 @@ pub fn scan_all_games() -> Vec<Game> {
 }
 
 pub fn add_game() -> Result<(), Box<dyn Error>> {
-    let file = FileDialog::new()
-        .set_title("Select Linux/Windows Program or Split Happens Handler (.pdh)")
-        .set_directory(&*PATH_HOME)
-        .pick_file();
+    let file = pick_file("Select Linux/Windows Program or Split Happens Handler (.pdh)", &[]);
 
     if file.is_none() {
         return Ok(());
@@ -141,7 +149,36 @@ pub fn add_game() -> Result<(), Box<dyn Error>> {
     };
 
     if file.extension().unwrap_or_default() == "pdh" {
-        install_handler_from_file(&file)?;
+        if let Some(warning) = install_handler_from_file(&file)? {
+            msg(ANTICHEAT_TITLE, &warning);
+        }
+    }
+
+    // Proton only runs x86 and x64 Windows programs, whatever the extension says.
+    if file.extension().unwrap_or_default() == "exe" {
+        let problem = match pe_arch(&file) {
+            Ok(arch) if arch.is32bit().is_some() => None,
+            Ok(arch) => Some(format!("{} is built for {}.", file.display(), arch.label())),
+            Err(err) => Some(format!("{err}.")),
+        };
+        if let Some(problem) = problem {
+            if !yesno(
+                "Unsupported executable",
+                &format!("{problem} Proton can only run 32- and 64-bit x86 Windows programs. Add it anyway?"),
+            ) {
+                return Ok(());
+            }
+        }
+    }
+
+    // Programs are scanned from their own folder, where launchers keep anti-cheat.
+    if file.extension().unwrap_or_default() != "pdh" {
+        if let Some(dir) = file.parent() {
+            let name = file.file_stem().unwrap_or_default().to_string_lossy();
+            if let Some(warning) = anticheat_warning(&name, dir) {
+                msg(ANTICHEAT_TITLE, &warning);
+            }
+        }
     }
 
     // Add executable path to the paths.json file
@@ -205,6 +242,13 @@ pub fn remove_game(game: &Game) -> Result<(), Box<dyn Error>> {
         }
 
         Game::HandlerRef(h) => {
+            if h.read_only {
+                return Err(format!(
+                    "{} is installed system-wide and can't be removed from here.",
+                    h.display()
+                )
+                .into());
+            }
             std::fs::remove_dir_all(h.path_handler.clone())?;
         }
     }
@@ -0,0 +1,1315 @@
+use crate::config::PartyConfig;
+use crate::logging::{log_info, log_warn};
+use crate::paths::*;
+use crate::util::*;
+
+use serde_json::{Map, Value};
+use std::collections::HashMap;
+use std::error::Error;
+use std::fs::File;
+use std::io::BufReader;
+use std::path::{Path, PathBuf};
+use std::sync::{LazyLock, Mutex};
+use std::time::SystemTime;
+
+/// File in a handler folder whose values are merged into every generated
+/// Nemirtingas config, see [`Handler::nemirtingas_template`].
+pub const NEMIRTINGAS_TEMPLATE: &str = "nemirtingas_template.json";
+
+/// A script bundled with a handler that prepares the symlink folder, e.g. by
+/// patching config files. Runs sandboxed with the symlink folder as its
+/// working directory.
+#[derive(Clone)]
+pub struct HandlerScript {
+    /// Path inside the handler folder.
+    pub path: String,
+    pub args: Vec<String>,
+}
+
+impl HandlerScript {
+    fn from_json(value: &Value) -> Option<HandlerScript> {
+        let path = match value {
+            Value::String(path) => path.clone(),
+            _ => value["path"].as_str()?.to_string(),
+        };
+        Some(HandlerScript {
+            path: path.sanitize_path(),
+            args: value["args"]
+                .as_array()
+                .map(|arr| arr.iter().filter_map(|v| v.as_str().map(str::to_string)).collect())
+                .unwrap_or_default(),
+        })
+    }
+}
+
+/// Where the path of a [`ResolutionFile`] starts.
+#[derive(Clone, Copy, PartialEq)]
+pub enum ResolutionFileRoot {
+    /// The game folder. Only the instance sees the patched copy.
+    Game,
+    /// The profile's save folder for the handler, which holds `_AppData`,
+    /// `_Documents` and the `profiles.game_paths` folders.
+    Save,
+}
+
+/// How a [`ResolutionFile`] is changed.
+#[derive(Clone)]
+pub enum ResolutionEdit {
+    /// Every `key=...` line gets `value`; the key is matched ignoring case.
+    Key { key: String, value: String },
+    /// Every match of `pattern` is replaced with `value`, which can refer to
+    /// groups as `${1}`.
+    Regex { pattern: String, value: String },
+}
+
+/// A config file patched with each instance's resolution before it starts,
+/// for games that only read it from an INI. `$WIDTH`, `$HEIGHT` and
+/// `$WIDTHXHEIGHT` in the value are expanded.
+#[derive(Clone)]
+pub struct ResolutionFile {
+    pub path: String,
+    pub root: ResolutionFileRoot,
+    pub edit: ResolutionEdit,
+}
+
+impl ResolutionFile {
+    fn from_json(value: &Value) -> Option<ResolutionFile> {
+        let replacement = value["value"].as_str()?.to_string();
+        let edit = match value["regex"].as_str() {
+            Some(pattern) => ResolutionEdit::Regex {
+                pattern: pattern.to_string(),
+                value: replacement,
+            },
+            None => ResolutionEdit::Key {
+                key: value["key"].as_str()?.trim().to_string(),
+                value: replacement,
+            },
+        };
+        Some(ResolutionFile {
+            path: value["path"].as_str()?.to_string().sanitize_path(),
+            root: match value["root"].as_str() {
+                Some("save") => ResolutionFileRoot::Save,
+                _ => ResolutionFileRoot::Game,
+            },
+            edit,
+        })
+    }
+
+    /// `contents` patched for a `width` x `height` instance, with the number
+    /// of places that changed.
+    pub fn apply(
+        &self,
+        contents: &str,
+        width: u32,
+        height: u32,
+    ) -> Result<(String, usize), String> {
+        let expand = |value: &str| {
+            value
+                .replace("$WIDTHXHEIGHT", &format!("{width}x{height}"))
+                .replace("$WIDTH", &width.to_string())
+                .replace("$HEIGHT", &height.to_string())
+        };
+        match &self.edit {
+            ResolutionEdit::Regex { pattern, value } => {
+                let regex = regex::Regex::new(pattern).map_err(|err| err.to_string())?;
+                let changes = regex.find_iter(contents).count();
+                let patched = regex.replace_all(contents, expand(value).as_str());
+                Ok((patched.into_owned(), changes))
+            }
+            ResolutionEdit::Key { key, value } => {
+                let value = expand(value);
+                let mut changes = 0;
+                let mut patched = String::with_capacity(contents.len());
+                for line in contents.split_inclusive('\n') {
+                    let body = line.trim_end_matches(['\r', '\n']);
+                    let ending = &line[body.len()..];
+                    let trimmed = body.trim_start();
+                    let is_comment = trimmed.starts_with(';') || trimmed.starts_with('#');
+                    let entry = body.split_once('=').filter(|_| !is_comment);
+                    match entry {
+                        Some((name, old)) if name.trim().eq_ignore_ascii_case(key) => {
+                            // Keep the spacing around `=` the file already uses.
+                            let spacing = &old[..old.len() - old.trim_start().len()];
+                            patched.push_str(&format!("{name}={spacing}{value}{ending}"));
+                            changes += 1;
+                        }
+                        _ => patched.push_str(line),
+                    }
+                }
+                Ok((patched, changes))
+            }
+        }
+    }
+}
+
+/// Upscaling filter gamescope applies when an instance is scaled to its window.
+#[derive(Clone, Copy, PartialEq)]
+pub enum GamescopeUpscaler {
+    Fsr,
+    Nis,
+    Integer,
+}
+
+impl GamescopeUpscaler {
+    pub fn from_name(name: &str) -> Option<GamescopeUpscaler> {
+        match name.to_lowercase().as_str() {
+            "fsr" => Some(GamescopeUpscaler::Fsr),
+            "nis" => Some(GamescopeUpscaler::Nis),
+            "integer" => Some(GamescopeUpscaler::Integer),
+            _ => None,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            GamescopeUpscaler::Fsr => "fsr",
+            GamescopeUpscaler::Nis => "nis",
+            GamescopeUpscaler::Integer => "integer",
+        }
+    }
+
+    /// Gamescope arguments selecting this filter or scaler.
+    pub fn args(self) -> [&'static str; 2] {
+        match self {
+            GamescopeUpscaler::Fsr => ["-F", "fsr"],
+            GamescopeUpscaler::Nis => ["-F", "nis"],
+            GamescopeUpscaler::Integer => ["-S", "integer"],
+        }
+    }
+}
+
+/// Direct3D implementation Proton runs a Windows game with.
+#[derive(Clone, Copy, PartialEq)]
+pub enum GraphicsApi {
+    /// Direct3D over Vulkan, Proton's default.
+    Dxvk,
+    /// Wine's own Direct3D over OpenGL. Slower, but some older games only run
+    /// several instances reliably with it.
+    Wined3d,
+}
+
+impl GraphicsApi {
+    pub fn from_name(name: &str) -> Option<GraphicsApi> {
+        match name.to_lowercase().as_str() {
+            "dxvk" => Some(GraphicsApi::Dxvk),
+            "wined3d" => Some(GraphicsApi::Wined3d),
+            _ => None,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            GraphicsApi::Dxvk => "dxvk",
+            GraphicsApi::Wined3d => "wined3d",
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct Handler {
+    // Members that are determined by context
+    pub path_handler: PathBuf,
+    pub img_paths: Vec<PathBuf>,
+    pub steam_header: Option<PathBuf>,
+    // Set for handlers loaded from the system directory; those are never written to.
+    pub read_only: bool,
+    // Problems found in handler.json at load time. Mistyped and unknown keys
+    // fall back to defaults, so these are shown to the author instead.
+    pub schema_issues: Vec<HandlerIssue>,
+
+    pub uid: String,
+    pub name: String,
+    pub author: String,
+    pub version: String,
+    pub info: String,
+
+    pub symlink_dir: bool,
+    pub win: bool,
+    pub runtime: String,
+    pub is32bit: bool,
+    pub exec: String,
+    // Executable of the game's other build, relative to the game directory:
+    // the Linux binary for Windows handlers or the .exe for native ones. Lets
+    // single instances switch between native and Proton.
+    pub alt_exec: String,
+    pub args: Vec<String>,
+    pub copy_instead_paths: Vec<String>,
+    pub remove_paths: Vec<String>,
+    pub dll_overrides: Vec<String>,
+    // Winetricks verbs (vcrun2019, dotnet48, ...) installed into each instance's
+    // Proton prefix before its first launch.
+    pub winetricks: Vec<String>,
+    // Forces DXVK or WineD3D; `None` leaves the choice to Proton.
+    pub graphics_api: Option<GraphicsApi>,
+    // dxvk.conf lines written for each instance, with `$SLOT`, `$WIDTH`,
+    // `$HEIGHT` and `$FPS` filled in.
+    pub dxvk_conf: Vec<String>,
+    // Frame limits DXVK applies, one per player slot; slots past the end get
+    // the last one, and `0` means none. See `dxvk_fps_limit_for`.
+    pub dxvk_fps_limit: Vec<u32>,
+    // VKD3D-Proton feature flags, passed as VKD3D_CONFIG.
+    pub vkd3d_config: Vec<String>,
+    // Approximate resident memory (MiB) a single instance needs, used to warn
+    // before launching more instances than the host can hold.
+    pub memory_mb: Option<u64>,
+    // Advanced multi-seat mode: run each instance as the local account
+    // configured for its slot so saves and per-user state stay fully isolated.
+    pub separate_users: bool,
+    // Normalized `port[-end]/proto` specs the game needs reachable for LAN
+    // discovery; offered to be opened in the host firewall per session.
+    pub firewall_ports: Vec<String>,
+    // Launcher settings this game works best with, keyed like settings.json.
+    // Offered on the instances page and applied to this game's launches.
+    pub recommended_settings: Map<String, Value>,
+    // SHA1 of each executable the handler bundles, keyed by its path inside the
+    // handler folder. Bundled executables only run when their hash matches.
+    pub asset_sha1: HashMap<String, String>,
+    // Bundled scripts run in a sandbox once the symlink folder has been built.
+    pub scripts: Vec<HandlerScript>,
+    // Uids this handler was published under before. Saves and settings left
+    // under one of them are offered for migration on the game page.
+    pub former_uids: Vec<String>,
+    // Uids of other handlers for the same game, e.g. another store's release,
+    // whose saves use the same format. Profiles can copy or link saves
+    // between them from the game page.
+    pub save_compatible_uids: Vec<String>,
+    // Extra gamescope arguments the game needs, e.g. `--force-grab-cursor`,
+    // passed before the launcher's own command.
+    pub gamescope_args: Vec<String>,
+    pub gamescope_upscaler: Option<GamescopeUpscaler>,
+
+    pub path_goldberg: String,
+    // Path to Nemirtingas config relative to the game's root directory.
+    // The handler must also bundle the patched EOSSDK DLL (e.g. via
+    // copy_to_symdir) at the matching location; Split Happens does not ship it.
+    pub path_nemirtingas: String,
+    pub eos_per_instance: bool,
+    pub never_symlink_paths: Vec<String>,
+    pub steam_appid: Option<String>,
+    pub coldclient: bool,
+    // Bundled Goldberg build under res/goldberg/<version>/ the game works
+    // with; empty uses the default build.
+    pub goldberg_version: String,
+
+    pub win_unique_appdata: bool,
+    pub win_unique_documents: bool,
+    pub linux_unique_localshare: bool,
+    pub linux_unique_config: bool,
+    pub game_unique_paths: Vec<String>,
+    /// Config files patched with each instance's resolution.
+    pub resolution_files: Vec<ResolutionFile>,
+}
+
+impl Handler {
+    pub fn new(json_path: &PathBuf, read_only: bool) -> Result<Self, Box<dyn Error>> {
+        let text = std::fs::read_to_string(json_path)?;
+        let json: Value = serde_json::from_str(&text)?;
+
+        let mut handler = Self {
+            path_handler: PathBuf::new(),
+            img_paths: Vec::new(),
+            steam_header: None,
+            read_only,
+            schema_issues: validate_handler_json(&text),
+
+            uid: json["handler.uid"].as_str().unwrap_or_default().to_string(),
+            name: json["handler.name"]
+                .as_str()
+                .unwrap_or_default()
+                .to_string(),
+            info: json["handler.info"]
+                .as_str()
+                .unwrap_or_default()
+                .to_string(),
+            author: json["handler.author"]
+                .as_str()
+                .unwrap_or_default()
+                .to_string(),
+            version: json["handler.version"]
+                .as_str()
+                .unwrap_or_default()
+                .to_string(),
+
+            symlink_dir: json["game.symlink_dir"].as_bool().unwrap_or_default(),
+            win: json["game.win"].as_bool().unwrap_or_default(),
+            is32bit: json["game.32bit"].as_bool().unwrap_or_default(),
+            runtime: json["game.runtime"]
+                .as_str()
+                .unwrap_or_default()
+                .to_string(),
+            exec: json["game.exec"]
+                .as_str()
+                .unwrap_or_default()
+                .to_string()
+                .sanitize_path(),
+            alt_exec: json["game.alt_exec"]
+                .as_str()
+                .unwrap_or_default()
+                .to_string()
+                .sanitize_path(),
+            args: json["game.args"]
+                .as_array()
+                .map(|arr| {
+                    arr.iter()
+                        .map(|v| v.as_str().unwrap_or_default().to_string())
+                        .collect()
+                })
+                .unwrap_or_default(),
+            copy_instead_paths: json["game.copy_instead_paths"]
+                .as_array()
+                .map(|arr| {
+                    arr.iter()
+                        .map(|v| v.as_str().unwrap_or_default().to_string().sanitize_path())
+                        .collect()
+                })
+                .unwrap_or_default(),
+            remove_paths: json["game.remove_paths"]
+                .as_array()
+                .map(|arr| {
+                    arr.iter()
+                        .map(|v| v.as_str().unwrap_or_default().to_string().sanitize_path())
+                        .collect()
+                })
+                .unwrap_or_default(),
+            dll_overrides: json["game.dll_overrides"]
+                .as_array()
+                .map(|arr| {
+                    arr.iter()
+                        .map(|v| v.as_str().unwrap_or_default().to_string())
+                        .collect()
+                })
+                .unwrap_or_default(),
+            winetricks: json["proton.winetricks"]
+                .as_array()
+                .map(|arr| {
+                    arr.iter()
+                        .filter_map(|v| v.as_str())
+                        .filter(|verb| is_winetricks_verb(verb))
+                        .map(str::to_string)
+                        .collect()
+                })
+                .unwrap_or_default(),
+            graphics_api: None,
+            dxvk_conf: json["proton.dxvk_conf"]
+                .as_array()
+                .map(|arr| {
+                    arr.iter()
+                        .filter_map(|v| v.as_str())
+                        .map(|line| line.trim().to_string())
+                        .filter(|line| !line.is_empty())
+                        .collect()
+                })
+                .unwrap_or_default(),
+            dxvk_fps_limit: match &json["proton.dxvk_fps_limit"] {
+                Value::Array(limits) => limits.iter().filter_map(Value::as_u64).collect(),
+                limit => limit.as_u64().into_iter().collect(),
+            }
+            .into_iter()
+            .filter_map(|fps| u32::try_from(fps).ok())
+            .collect(),
+            vkd3d_config: json["proton.vkd3d_config"]
+                .as_array()
+                .map(|arr| {
+                    arr.iter()
+                        .filter_map(|v| v.as_str())
+                        .map(str::trim)
+                        .filter(|flag| is_vkd3d_flag(flag))
+                        .map(str::to_string)
+                        .collect()
+                })
+                .unwrap_or_default(),
+            memory_mb: json["game.memory_mb"].as_u64(),
+            separate_users: json["game.separate_users"].as_bool().unwrap_or_default(),
+            firewall_ports: json["game.firewall_ports"]
+                .as_array()
+                .map(|arr| {
+                    arr.iter()
+                        .filter_map(|v| v.as_str().and_then(parse_port_spec))
+                        .collect()
+                })
+                .unwrap_or_default(),
+            recommended_settings: json["launcher.settings"]
+                .as_object()
+                .cloned()
+                .unwrap_or_default(),
+            asset_sha1: json["handler.sha1"]
+                .as_object()
+                .map(|map| {
+                    map.iter()
+                        .filter_map(|(path, sha1)| {
+                            Some((path.clone().sanitize_path(), sha1.as_str()?.to_lowercase()))
+                        })
+                        .collect()
+                })
+                .unwrap_or_default(),
+            scripts: json["handler.scripts"]
+                .as_array()
+                .map(|arr| arr.iter().filter_map(HandlerScript::from_json).collect())
+                .unwrap_or_default(),
+            former_uids: json["handler.former_uids"]
+                .as_array()
+                .map(|arr| {
+                    arr.iter()
+                        .filter_map(|v| v.as_str())
+                        .filter(|uid| !uid.is_empty() && uid.chars().all(char::is_alphanumeric))
+                        .map(str::to_string)
+                        .collect()
+                })
+                .unwrap_or_default(),
+            save_compatible_uids: json["handler.save_compatible_uids"]
+                .as_array()
+                .map(|arr| {
+                    arr.iter()
+                        .filter_map(|v| v.as_str())
+                        .filter(|uid| !uid.is_empty() && uid.chars().all(char::is_alphanumeric))
+                        .map(str::to_string)
+                        .collect()
+                })
+                .unwrap_or_default(),
+            gamescope_args: json["gamescope.args"]
+                .as_array()
+                .map(|arr| {
+                    arr.iter()
+                        .filter_map(|v| v.as_str())
+                        // A bare `--` would end gamescope's options early and
+                        // turn the rest of the command line into its command.
+                        .filter(|arg| !arg.is_empty() && *arg != "--")
+                        .map(str::to_string)
+                        .collect()
+                })
+                .unwrap_or_default(),
+            gamescope_upscaler: None,
+
+            path_goldberg: json["steam.api_path"]
+                .as_str()
+                .unwrap_or_default()
+                .to_string()
+                .sanitize_path(),
+            path_nemirtingas: json["eos.config_path"]
+                .as_str()
+                .unwrap_or_default()
+                .to_string()
+                .sanitize_path(),
+            eos_per_instance: json["eos.per_instance"].as_bool().unwrap_or(false),
+            never_symlink_paths: json["game.never_symlink_paths"]
+                .as_array()
+                .map(|arr| {
+                    arr.iter()
+                        .map(|v| v.as_str().unwrap_or_default().to_string().sanitize_path())
+                        .collect()
+                })
+                .unwrap_or_default(),
+            steam_appid: json["steam.appid"]
+                .as_str()
+                .and_then(|s| Some(s.to_string())),
+            coldclient: json["steam.gb_coldclient"].as_bool().unwrap_or_default(),
+            goldberg_version: json["steam.gb_version"]
+                .as_str()
+                .unwrap_or_default()
+                .trim()
+                .to_string(),
+
+            win_unique_appdata: json["profiles.unique_appdata"]
+                .as_bool()
+                .unwrap_or_default(),
+            win_unique_documents: json["profiles.unique_documents"]
+                .as_bool()
+                .unwrap_or_default(),
+            linux_unique_localshare: json["profiles.unique_localshare"]
+                .as_bool()
+                .unwrap_or_default(),
+            linux_unique_config: json["profiles.unique_config"].as_bool().unwrap_or_default(),
+            game_unique_paths: json["profiles.game_paths"]
+                .as_array()
+                .map(|arr| {
+                    arr.iter()
+                        .map(|v| v.as_str().unwrap_or_default().to_string().sanitize_path())
+                        .collect()
+                })
+                .unwrap_or_default(),
+            resolution_files: json["game.resolution_files"]
+                .as_array()
+                .map(|arr| arr.iter().filter_map(ResolutionFile::from_json).collect())
+                .unwrap_or_default(),
+        };
+
+        if !handler.uid.chars().all(char::is_alphanumeric) {
+            return Err("uid must be alphanumeric!".into());
+        }
+
+        if let Some(name) = json["gamescope.upscaler"].as_str().filter(|s| !s.is_empty()) {
+            handler.gamescope_upscaler = GamescopeUpscaler::from_name(name);
+            if handler.gamescope_upscaler.is_none() {
+                log_warn(format!(
+                    "Handler {} has unknown gamescope.upscaler \"{name}\"; expected fsr, nis or integer",
+                    handler.uid
+                ));
+            }
+        }
+        if let Some(name) = json["proton.graphics"].as_str().filter(|s| !s.is_empty()) {
+            handler.graphics_api = GraphicsApi::from_name(name);
+            if handler.graphics_api.is_none() {
+                log_warn(format!(
+                    "Handler {} has unknown proton.graphics \"{name}\"; expected dxvk or wined3d",
+                    handler.uid
+                ));
+            }
+        }
+
+        handler.path_handler = json_path
+            .parent()
+            .ok_or_else(|| "Invalid path")?
+            .to_path_buf();
+        handler.img_paths = handler.get_imgs();
+        handler.ensure_steam_header_image();
+
+        Ok(handler)
+    }
+
+    pub fn display(&self) -> &str {
+        if self.name.is_empty() {
+            self.uid.as_str()
+        } else {
+            self.name.as_str()
+        }
+    }
+
+    fn get_imgs(&self) -> Vec<PathBuf> {
+        let mut out = Vec::new();
+        let imgs_path = self.path_handler.join("imgs");
+
+        let entries = match std::fs::read_dir(imgs_path) {
+            Ok(entries) => entries,
+            Err(_) => return out,
+        };
+
+        for entry_result in entries {
+            let entry = match entry_result {
+                Ok(entry) => entry,
+                Err(_) => continue,
+            };
+            let file_type = match entry.file_type() {
+                Ok(ft) => ft,
+                Err(_) => continue,
+            };
+            if !file_type.is_file() {
+                continue;
+            }
+            if let Some(path_str) = entry.path().to_str() {
+                if path_str.ends_with(".png") || path_str.ends_with(".jpg") {
+                    out.push(entry.path());
+                }
+            }
+        }
+
+        out.sort();
+        out
+    }
+
+    /// Returns the directory where generated handler assets are cached. System
+    /// handlers live on a read-only path, so their cache moves under the user's
+    /// data directory instead.
+    pub fn cache_dir(&self) -> PathBuf {
+        if self.read_only {
+            PATH_APP.join("handler_cache").join(&self.uid)
+        } else {
+            self.path_handler.clone()
+        }
+    }
+
+    /// Overrides for the generated Nemirtingas config, read from the
+    /// handler's `nemirtingas_template.json` when it ships one.
+    pub fn nemirtingas_template(&self) -> Result<Option<Value>, Box<dyn Error>> {
+        let path = self.path_handler.join(NEMIRTINGAS_TEMPLATE);
+        if !path.exists() {
+            return Ok(None);
+        }
+        let template: Value = serde_json::from_reader(BufReader::new(File::open(&path)?))
+            .map_err(|err| format!("{NEMIRTINGAS_TEMPLATE} of {} is invalid: {err}", self.uid))?;
+        if !template.is_object() {
+            let err = format!("{NEMIRTINGAS_TEMPLATE} of {} must be a JSON object", self.uid);
+            return Err(err.into());
+        }
+        Ok(Some(template))
+    }
+
+    /// Resolves an executable bundled with the handler and checks it against
+    /// the SHA1 declared under `handler.sha1`. Undeclared or modified files are
+    /// refused so they never run.
+    pub fn verified_asset(&self, path: &str) -> Result<PathBuf, Box<dyn Error>> {
+        let full_path = self.path_handler.join(path);
+        let Some(expected) = self.asset_sha1.get(path) else {
+            return Err(format!("{path} has no SHA1 declared in handler.sha1").into());
+        };
+        let actual = sha1_file(&full_path)?;
+        if actual != *expected {
+            return Err(format!("{path} doesn't match its declared SHA1 ({actual})").into());
+        }
+        Ok(full_path)
+    }
+
+    /// Builds the game folder is prepared for, as `game.win` values: the
+    /// handler's own, plus the other one when `game.alt_exec` names it.
+    pub fn builds(&self) -> Vec<bool> {
+        match self.alt_exec.is_empty() {
+            true => vec![self.win],
+            false => vec![self.win, !self.win],
+        }
+    }
+
+    /// DXVK frame limit of the player in slot `index` (from 0): its entry in
+    /// `proton.dxvk_fps_limit`, or the last entry for slots past the list.
+    pub fn dxvk_fps_limit_for(&self, index: usize) -> Option<u32> {
+        let limit = self.dxvk_fps_limit.get(index).or(self.dxvk_fps_limit.last());
+        limit.copied().filter(|fps| *fps > 0)
+    }
+
+    /// Executable of the Windows (`win`) or Linux build, `None` when the
+    /// handler has no such build.
+    pub fn exec_for(&self, win: bool) -> Option<&str> {
+        if win == self.win {
+            Some(&self.exec)
+        } else if !self.alt_exec.is_empty() {
+            Some(&self.alt_exec)
+        } else {
+            None
+        }
+    }
+
+    /// Architecture of the Windows executable in the game folder `path_root`,
+    /// or `None` for games without a Windows build and executables that can't
+    /// be read.
+    pub fn exe_arch(&self, path_root: &Path) -> Option<PeArch> {
+        cached_pe_arch(&path_root.join(self.exec_for(true)?))
+    }
+
+    /// Whether the 32-bit Goldberg and EOS builds fit the Windows (`win`) or
+    /// Linux build of the game. The header of the executable that build
+    /// launches decides when it can be read; `game.32bit` is only the
+    /// fallback.
+    pub fn emulator_32bit(&self, path_root: &Path, win: bool) -> bool {
+        let exec = self.exec_for(win).unwrap_or(&self.exec);
+        let detected = match win {
+            true => pe_arch(&path_root.join(exec)).ok().and_then(PeArch::is32bit),
+            false => elf_is32bit(&path_root.join(exec)).ok(),
+        };
+        let Some(detected) = detected else {
+            return self.is32bit;
+        };
+        // game.32bit describes the handler's own build.
+        if detected != self.is32bit && win == self.win {
+            log_warn(format!(
+                "{}: game.32bit is {} but {} is {}; using the matching emulator build",
+                self.uid,
+                self.is32bit,
+                exec,
+                if detected { "32-bit" } else { "64-bit" }
+            ));
+        }
+        detected
+    }
+
+    /// Warning for a `game.32bit` that disagrees with the executable in the
+    /// game's folder `path_root`. Only emulator builds depend on it, so
+    /// handlers without Goldberg or Nemirtingas are skipped.
+    pub fn arch_issue(&self, path_root: &Path) -> Option<HandlerIssue> {
+        if self.path_goldberg.is_empty() && self.path_nemirtingas.is_empty() {
+            return None;
+        }
+        let exec = self.exec_for(true)?;
+        let arch = self.exe_arch(path_root)?;
+        let message = match arch.is32bit() {
+            Some(detected) if detected == self.is32bit || !self.win => return None,
+            Some(_) => format!(
+                "game.32bit is {} but {} is a {} program; the matching emulator build is used instead",
+                self.is32bit,
+                exec,
+                arch.label()
+            ),
+            None => format!(
+                "{} is built for {}, which no Goldberg or Nemirtingas build supports",
+                exec,
+                arch.label()
+            ),
+        };
+        Some(HandlerIssue {
+            line: None,
+            severity: IssueSeverity::Warning,
+            message,
+        })
+    }
+
+    /// Ensures that each handler caches the Steam header artwork locally so the
+    /// UI can render large, responsive tiles without repeatedly downloading the
+    /// same image.
+    fn ensure_steam_header_image(&mut self) {
+        let Some(appid) = &self.steam_appid else {
+            self.steam_header = None;
+            return;
+        };
+
+        let header_path = self.cache_dir().join("steam_header.jpg");
+        if header_path.exists() {
+            self.steam_header = Some(header_path);
+            return;
+        }
+
+        let url = format!(
+            "https://shared.fastly.steamstatic.com/store_item_assets/steam/apps/{appid}/header.jpg"
+        );
+
+        if let Some(parent) = header_path.parent() {
+            let _ = std::fs::create_dir_all(parent);
+        }
+
+        // Offline mode leaves the header uncached; the next scan retries.
+        let Ok(mut curl) = curl_command() else {
+            self.steam_header = None;
+            return;
+        };
+        let download_status = curl
+            .arg("-sSfL")
+            .arg(&url)
+            .arg("-o")
+            .arg(&header_path)
+            .status();
+
+        if matches!(download_status, Ok(status) if status.success()) && header_path.exists() {
+            self.steam_header = Some(header_path);
+        } else {
+            let _ = std::fs::remove_file(&header_path);
+            self.steam_header = None;
+        }
+    }
+}
+
+/// Architectures read from executables' PE headers, by path, with the
+/// modification time of the file they were read from. Library rescans then
+/// only reopen executables a game update replaced.
+static EXE_ARCHS: LazyLock<Mutex<HashMap<PathBuf, (SystemTime, Option<PeArch>)>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+/// `pe_arch` of `path`, read again only once the file changed.
+fn cached_pe_arch(path: &Path) -> Option<PeArch> {
+    let modified = std::fs::metadata(path).and_then(|meta| meta.modified()).ok()?;
+    let mut cache = EXE_ARCHS.lock().unwrap();
+    if let Some((read_at, arch)) = cache.get(path) {
+        if *read_at == modified {
+            return *arch;
+        }
+    }
+    let arch = pe_arch(path).ok();
+    cache.insert(path.to_path_buf(), (modified, arch));
+    arch
+}
+
+pub fn scan_handlers() -> Vec<Handler> {
+    // Load the read-only system handlers first so per-user installs with the same
+    // uid replace them.
+    let mut by_uid: HashMap<String, Handler> = HashMap::new();
+    let rootpaths = saved_rootpaths();
+    scan_handler_dir(&PATH_SYSTEM_HANDLERS, true, &rootpaths, &mut by_uid);
+    scan_handler_dir(&PATH_APP.join("handlers"), false, &rootpaths, &mut by_uid);
+
+    let mut out: Vec<Handler> = by_uid.into_values().collect();
+    out.sort_by(|a, b| a.display().to_lowercase().cmp(&b.display().to_lowercase()));
+    out
+}
+
+/// Loads every `<dir>/<uid>/handler.json` below `handlers_path` into `out`,
+/// replacing earlier entries that share the same uid. `rootpaths` are the
+/// saved game folders, by uid.
+fn scan_handler_dir(
+    handlers_path: &PathBuf,
+    read_only: bool,
+    rootpaths: &HashMap<String, PathBuf>,
+    out: &mut HashMap<String, Handler>,
+) {
+    let entries = match std::fs::read_dir(handlers_path) {
+        Ok(entries) => entries,
+        Err(_) => return,
+    };
+
+    for entry_result in entries {
+        let entry = match entry_result {
+            Ok(entry) => entry,
+            Err(_) => continue,
+        };
+        let file_type = match entry.file_type() {
+            Ok(ft) => ft,
+            Err(_) => continue,
+        };
+        if !file_type.is_dir() {
+            continue;
+        }
+        let json_path = entry.path().join("handler.json");
+        if !json_path.exists() {
+            continue;
+        }
+        match Handler::new(&json_path, read_only) {
+            Ok(mut handler) => {
+                // Checked here rather than in the schema, which only sees the JSON.
+                let issue = rootpaths
+                    .get(&handler.uid)
+                    .and_then(|path_root| handler.arch_issue(path_root));
+                if let Some(issue) = issue {
+                    handler.schema_issues.push(issue);
+                }
+                if !handler.schema_issues.is_empty() {
+                    log_warn(format!(
+                        "Handler {} has problems in handler.json:\n{}",
+                        handler.uid,
+                        format_handler_issues(&handler.schema_issues)
+                    ));
+                }
+                out.insert(handler.uid.clone(), handler);
+            }
+            Err(err) => log_warn(format!("Couldn't load {}: {err}", json_path.display())),
+        }
+    }
+}
+
+/// Installs the `.pdh` archive `file`. Returns the anti-cheat warning of the
+/// game when its folder is already known and ships one.
+pub fn install_handler_from_file(file: &PathBuf) -> Result<Option<String>, Box<dyn Error>> {
+    if !file.exists() || !file.is_file() || file.extension().unwrap_or_default() != "pdh" {
+        return Err("Handler not valid!".into());
+    }
+
+    let dir_handlers = PATH_APP.join("handlers");
+    let dir_tmp = PATH_APP.join("tmp");
+    if !dir_tmp.exists() {
+        std::fs::create_dir_all(&dir_tmp)?;
+    }
+
+    let mut archive = zip::ZipArchive::new(File::open(&file)?)?;
+    archive.extract(&dir_tmp)?;
+
+    let handler_path = dir_tmp.join("handler.json");
+    if !handler_path.exists() {
+        return Err("handler.json not found in archive".into());
+    }
+
+    // Refuse handlers that wouldn't load as written; unknown keys only warn
+    // and are shown once the library reloads.
+    let handler_text = std::fs::read_to_string(&handler_path)?;
+    let errors: Vec<HandlerIssue> = validate_handler_json(&handler_text)
+        .into_iter()
+        .filter(|issue| issue.severity == IssueSeverity::Error)
+        .collect();
+    if !errors.is_empty() {
+        std::fs::remove_dir_all(&dir_tmp)?;
+        return Err(format!(
+            "handler.json has errors:\n{}",
+            format_handler_issues(&errors)
+        )
+        .into());
+    }
+    let handler_json: Value = serde_json::from_str(&handler_text)?;
+
+    let uid = handler_json
+        .get("handler.uid")
+        .and_then(|v| v.as_str())
+        .ok_or("No uid field found in handler.json")?;
+
+    if !uid.chars().all(char::is_alphanumeric) {
+        return Err("uid must be alphanumeric".into());
+    }
+
+    copy_dir_recursive(&dir_tmp, &dir_handlers.join(uid), false, true, None)?;
+    std::fs::remove_dir_all(&dir_tmp)?;
+
+    // Games installed through Steam can be checked for anti-cheat right away;
+    // others are checked once their folder is picked.
+    let handler = Handler::new(&dir_handlers.join(uid).join("handler.json"), false)?;
+    Ok(match known_rootpath_handler(&handler) {
+        Some(Ok(root)) => anticheat_warning(handler.display(), Path::new(&root)),
+        _ => None,
+    })
+}
+
+/// Describes a Goldberg `steam_settings` folder that already ships inside the
+/// game directory (pre-configured or previously cracked installs).
+struct ExistingGoldbergConfig {
+    steam_settings: PathBuf,
+    appid: Option<String>,
+    has_interfaces: bool,
+}
+
+/// Looks for a `steam_settings` folder next to the handler's Steam API path in
+/// the original game directory and extracts the settings worth importing.
+fn detect_existing_goldberg(path_root: &PathBuf, h: &Handler) -> Option<ExistingGoldbergConfig> {
+    let goldberg_dir = path_root.join(&h.path_goldberg);
+    let steam_settings = goldberg_dir.join("steam_settings");
+    if !steam_settings.is_dir() {
+        return None;
+    }
+
+    // Goldberg accepts steam_appid.txt either inside steam_settings or beside the DLL.
+    let appid = [
+        steam_settings.join("steam_appid.txt"),
+        goldberg_dir.join("steam_appid.txt"),
+    ]
+    .iter()
+    .filter_map(|path| std::fs::read_to_string(path).ok())
+    .map(|contents| contents.trim().to_string())
+    .find(|appid| !appid.is_empty() && appid.chars().all(|c| c.is_ascii_digit()));
+
+    Some(ExistingGoldbergConfig {
+        has_interfaces: steam_settings.join("steam_interfaces.txt").exists(),
+        steam_settings,
+        appid,
+    })
+}
+
+/// Persists an appid discovered in the game directory into the handler JSON so
+/// Steam artwork and later launches pick it up without manual edits.
+fn adopt_handler_appid(h: &Handler, appid: &str) -> Result<(), Box<dyn Error>> {
+    if h.read_only {
+        return Ok(());
+    }
+    let json_path = h.path_handler.join("handler.json");
+    let mut json: Value = serde_json::from_reader(BufReader::new(File::open(&json_path)?))?;
+    let Some(map) = json.as_object_mut() else {
+        return Ok(());
+    };
+    if map.contains_key("steam.appid") {
+        return Ok(());
+    }
+
+    log_info(format!("Importing appid {appid} into handler {}", h.uid));
+    map.insert("steam.appid".to_string(), Value::String(appid.to_string()));
+    write_atomic(&json_path, serde_json::to_string_pretty(&json)?)?;
+    Ok(())
+}
+
+/// Replaces `path` with a plain copy of its target when it is a symlink, so
+/// writing to it changes the symlink folder instead of the game install the
+/// link points into.
+fn detach_symlink(path: &Path) -> std::io::Result<()> {
+    let is_link = path.symlink_metadata().is_ok_and(|meta| meta.file_type().is_symlink());
+    if !is_link {
+        return Ok(());
+    }
+    // A dangling link has nothing to keep.
+    let contents = std::fs::read(path).ok();
+    std::fs::remove_file(path)?;
+    if let Some(contents) = contents {
+        std::fs::write(path, contents)?;
+    }
+    Ok(())
+}
+
+/// Writes the DLC list of the handler's Steam app into the Goldberg settings
+/// of its symlink folder. Handlers that ship their own list in
+/// `copy_to_symdir` keep it.
+pub fn generate_goldberg_dlc_list(h: &Handler) -> Result<(), Box<dyn Error>> {
+    let Some(appid) = &h.steam_appid else {
+        return Err("the handler sets no steam.appid".into());
+    };
+    let shipped = h
+        .path_handler
+        .join("copy_to_symdir")
+        .join(&h.path_goldberg)
+        .join("steam_settings");
+    if shipped.join("DLC.txt").exists() || shipped.join("configs.app.ini").exists() {
+        return Ok(());
+    }
+
+    let dlcs = steam_dlc_list(appid)?;
+    let steam_settings = PATH_APP
+        .join(format!("gamesyms/{}", h.uid))
+        .join(&h.path_goldberg)
+        .join("steam_settings");
+    write_goldberg_dlc_list(&steam_settings, &dlcs)?;
+    log_info(format!("Listed {} DLCs of app {appid} for Goldberg", dlcs.len()));
+    Ok(())
+}
+
+pub fn create_symlink_folder(h: &Handler, cfg: &PartyConfig) -> Result<(), Box<dyn Error>> {
+    let path_root = PathBuf::from(get_rootpath_handler(&h)?);
+    let path_sym = PATH_APP.join(format!("gamesyms/{}", h.uid));
+    if path_sym.exists() {
+        return Ok(());
+    }
+    std::fs::create_dir_all(path_sym.to_owned())?;
+    // Emulator builds follow the executable each build launches, even when
+    // game.32bit says otherwise.
+    let builds: Vec<(bool, bool)> = h
+        .builds()
+        .into_iter()
+        .map(|win| (win, h.emulator_32bit(&path_root, win)))
+        .collect();
+    let mut never_symlink: Vec<PathBuf> = h
+        .never_symlink_paths
+        .iter()
+        .map(|p| path_sym.join(p))
+        .collect();
+    if h.eos_per_instance || !h.path_nemirtingas.is_empty() {
+        never_symlink.push(path_sym.join(&h.path_nemirtingas));
+    }
+    copy_dir_recursive(&path_root, &path_sym, true, false, Some(&never_symlink))?;
+
+    // copy_instead_paths takes symlink files and replaces them with their real equivalents
+    for path in &h.copy_instead_paths {
+        let src = path_root.join(path);
+        if !src.exists() {
+            continue;
+        }
+        let dest = path_sym.join(path);
+        log_info(format!("src: {}, dest: {}", src.display(), dest.display()));
+        if src.is_dir() {
+            log_info(format!("Copying directory: {}", src.display()));
+            copy_dir_recursive(&src, &dest, false, true, None)?;
+        } else if src.is_file() {
+            log_info(format!("Copying file: {}", src.display()));
+            if dest.exists() {
+                std::fs::remove_file(&dest)?;
+            }
+            std::fs::copy(&src, &dest)?;
+        }
+    }
+    for path in h.remove_paths.iter().chain(h.game_unique_paths.iter()) {
+        let p = path_sym.join(path);
+        if !p.exists() {
+            continue;
+        }
+        if p.is_dir() {
+            std::fs::remove_dir_all(p)?;
+        } else if p.is_file() {
+            std::fs::remove_file(p)?;
+        }
+    }
+    let copypath = PathBuf::from(&h.path_handler).join("copy_to_symdir");
+    if copypath.exists() {
+        copy_dir_recursive(&copypath, &path_sym, false, true, None)?;
+    }
+
+    // Insert goldberg dll
+    if !h.path_goldberg.is_empty() {
+        let dest = path_sym.join(&h.path_goldberg);
+
+        let steam_settings = dest.join("steam_settings");
+        let existing = detect_existing_goldberg(&path_root, h);
+        if let Some(existing) = &existing {
+            log_info(format!(
+                "Reusing existing Goldberg config at {}",
+                existing.steam_settings.display()
+            ));
+            // Swap the symlinked files for real copies so the tweaks below never write
+            // through into the original game install.
+            copy_dir_recursive(&existing.steam_settings, &steam_settings, false, true, None)?;
+            if h.steam_appid.is_none() {
+                if let Some(appid) = &existing.appid {
+                    adopt_handler_appid(h, appid)?;
+                }
+            }
+        }
+        if !steam_settings.exists() {
+            std::fs::create_dir_all(steam_settings.clone())?;
+        }
+        // An adopted config keeps its own settings; only a missing save path is added.
+        let user_ini = steam_settings.join("configs.user.ini");
+        detach_symlink(&user_ini)?;
+        if read_config_value(&user_ini, "local_save_path").is_none() {
+            ensure_ini_setting(&user_ini, "[user::saves]", "local_save_path", "./goldbergsave")?;
+        }
+        let appid = h
+            .steam_appid
+            .clone()
+            .or_else(|| existing.as_ref().and_then(|e| e.appid.clone()));
+        if let Some(appid) = &appid {
+            let appid_path = steam_settings.join("steam_appid.txt");
+            detach_symlink(&appid_path)?;
+            std::fs::write(appid_path, appid.as_str())?;
+        }
+
+        // Provide the compatibility toggles that the Windows handler uses so Goldberg stays online-friendly.
+        std::fs::create_dir_all(steam_settings.join("mods"))?;
+        // disable_lan_only.txt lives next to the Goldberg DLL on Windows, so keep it beside the overrides too.
+        let lan_only = dest.join("disable_lan_only.txt");
+        detach_symlink(&lan_only)?;
+        std::fs::write(lan_only, "")?;
+        for (file_name, contents) in [
+            ("disable_overlay.txt", ""),
+            ("auto_accept_invite.txt", ""),
+            ("disable_overlay_warning_any.txt", ""),
+            ("gc_token.txt", "1"),
+            ("new_app_ticket.txt", "1"),
+        ] {
+            let toggle = steam_settings.join(file_name);
+            detach_symlink(&toggle)?;
+            std::fs::write(toggle, contents)?;
+        }
+
+        // Allow handler authors to bundle a patched Goldberg steam_api library that replaces the default template.
+        let handler_root = &h.path_handler;
+        for &(win, is32bit) in &builds {
+            let file_name = goldberg_lib_name(win, is32bit);
+            let override_path = handler_root.join(file_name);
+            if override_path.exists() {
+                let dest_path = dest.join(file_name);
+                if dest_path.exists() {
+                    std::fs::remove_file(&dest_path)?;
+                }
+                std::fs::copy(&override_path, &dest_path)?;
+            }
+        }
+
+        // A game that already ships Goldberg keeps its own DLLs and interface list so we
+        // don't layer a second emulator build on top of it.
+        if let Some(existing) = &existing {
+            if !existing.has_interfaces {
+                log_warn(format!(
+                    "Existing Goldberg config at {} has no steam_interfaces.txt",
+                    existing.steam_settings.display()
+                ));
+            }
+        }
+
+        // If the game uses goldberg coldclient, assume the handler owner has set up coldclient in the copy_to_symdir files
+        // And so we don't copy goldberg dlls or generate interfaces
+        if !&h.coldclient && existing.is_none() {
+            let goldberg_res = goldberg_build_dir(h, cfg)?;
+            // Both builds get their library when the handler has a second one, so
+            // instances switched to the other platform find Goldberg too.
+            for &(win, is32bit) in &builds {
+                let src = goldberg_res
+                    .join(if win { "win" } else { "linux" })
+                    .join(if is32bit { "x32" } else { "x64" });
+                copy_dir_recursive(&src, &dest, false, true, None)?;
+            }
+
+            // The interface list is shared, so it comes from the handler's own build.
+            let (win, is32bit) = builds[0];
+            let path_steamdll = path_root.join(&h.path_goldberg);
+            let steamdll = path_steamdll.join(goldberg_lib_name(win, is32bit));
+
+            // Handlers may bundle their own generate_interfaces build, which only runs
+            // when it matches the SHA1 the handler declares.
+            let gen_name = match is32bit {
+                true => "generate_interfaces_x32",
+                false => "generate_interfaces_x64",
+            };
+            let handler_gen = h.path_handler.join(gen_name).exists();
+            let gen_interfaces = match handler_gen {
+                true => h.verified_asset(gen_name)?,
+                false => goldberg_res.join(gen_name),
+            };
+            let status = if sandbox_available() {
+                run_sandboxed(
+                    &gen_interfaces,
+                    &[steamdll.to_string_lossy().to_string()],
+                    &steam_settings,
+                    &[&path_steamdll],
+                )?
+            } else if !handler_gen {
+                log_warn("bwrap not found, running generate_interfaces without a sandbox");
+                std::process::Command::new(gen_interfaces)
+                    .arg(steamdll)
+                    .current_dir(steam_settings)
+                    .status()?
+            } else {
+                return Err("bwrap is required to run executables bundled with handlers".into());
+            };
+            if !status.success() {
+                return Err("Generate interfaces failed".into());
+            }
+        }
+    }
+
+    // The EOS emulator only has Windows builds.
+    let eos_32bit = builds
+        .iter()
+        .find(|(win, _)| *win)
+        .map_or(h.is32bit, |&(_, is32bit)| is32bit);
+    // Drop the half-prepared folder when a late step fails so the next launch retries.
+    let finished = install_picked_nemirtingas(h, cfg, &path_sym, eos_32bit)
+        .and_then(|()| run_handler_scripts(h, &path_root, &path_sym));
+    if let Err(err) = finished {
+        let _ = std::fs::remove_dir_all(&path_sym);
+        return Err(err);
+    }
+
+    Ok(())
+}
+
+/// Steam API library Goldberg replaces in the Windows (`win`) or Linux build.
+fn goldberg_lib_name(win: bool, is32bit: bool) -> &'static str {
+    match (win, is32bit) {
+        (true, true) => "steam_api.dll",
+        (true, false) => "steam_api64.dll",
+        (false, _) => "libsteam_api.so",
+    }
+}
+
+/// Goldberg build copied into the game for `h`. A downloaded build picked on
+/// the game page wins, then the bundled build named in the settings, then the
+/// one the handler's `steam.gb_version` asks for, then the default build.
+fn goldberg_build_dir(h: &Handler, cfg: &PartyConfig) -> Result<PathBuf, Box<dyn Error>> {
+    if let Some(version) = cfg.resource_version(h, ResourceKind::Goldberg) {
+        return resource_dir(ResourceKind::Goldberg, version);
+    }
+    let version = cfg.bundled_goldberg_version(h);
+    let dir = bundled_goldberg_dir(version)?;
+    if let Some(version) = version {
+        log_info(format!("Using bundled Goldberg build {version} for {}", h.uid));
+    }
+    Ok(dir)
+}
+
+/// Swaps in the Nemirtingas build picked for this handler. The patched EOSSDK
+/// DLL sits next to the folder holding NemirtingasEpicEmu.json.
+fn install_picked_nemirtingas(
+    h: &Handler,
+    cfg: &PartyConfig,
+    path_sym: &PathBuf,
+    is32bit: bool,
+) -> Result<(), Box<dyn Error>> {
+    let Some(version) = cfg.resource_version(h, ResourceKind::Nemirtingas) else {
+        return Ok(());
+    };
+    if h.path_nemirtingas.is_empty() {
+        return Err("No eos.config_path to place the picked Nemirtingas build at".into());
+    }
+    let build = resource_dir(ResourceKind::Nemirtingas, version)?;
+    let dll_name = match is32bit {
+        true => "EOSSDK-Win32-Shipping.dll",
+        false => "EOSSDK-Win64-Shipping.dll",
+    };
+    let config_dir = Path::new(&h.path_nemirtingas).parent().unwrap_or(Path::new(""));
+    let dll_dir = path_sym.join(config_dir.parent().unwrap_or(config_dir));
+    let dll = dll_dir.join(dll_name);
+    if dll.symlink_metadata().is_ok() {
+        std::fs::remove_file(&dll)?;
+    }
+    std::fs::create_dir_all(&dll_dir)?;
+    std::fs::copy(build.join(dll_name), &dll)?;
+    Ok(())
+}
+
+/// Runs the handler's bundled scripts on the freshly built symlink folder.
+/// Each script must match its declared SHA1 and runs sandboxed: it can only
+/// write inside the symlink folder, and the game files it links to stay
+/// read-only.
+fn run_handler_scripts(
+    h: &Handler,
+    path_root: &PathBuf,
+    path_sym: &PathBuf,
+) -> Result<(), Box<dyn Error>> {
+    if h.scripts.is_empty() {
+        return Ok(());
+    }
+    if !sandbox_available() {
+        return Err("bwrap is required to run scripts bundled with handlers".into());
+    }
+    for script in &h.scripts {
+        let exe = h.verified_asset(&script.path)?;
+        log_info(format!("Running handler script {}", script.path));
+        let status = run_sandboxed(&exe, &script.args, path_sym, &[path_root, &h.path_handler])?;
+        if !status.success() {
+            return Err(format!("Handler script {} failed ({status})", script.path).into());
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,334 @@
+use std::collections::HashMap;
+
+use crate::config::PadFilterType;
+use crate::logging::log_warn;
+use crate::util::{AxisRange, DeviceCalibration, load_calibrations};
+
+use evdev::*;
+
+mod nav;
+mod poller;
+mod r#virtual;
+pub use nav::NavState;
+use nav::calibrated_range;
+use poller::{EventFeed, watch_device};
+pub use r#virtual::{
+    DEFAULT_SPLIT_KEYS, KeyboardSplitter, is_split_keyboard, parse_key_ranges,
+    spawn_keyboard_splitter,
+};
+
+/// Fallback stick range for devices that don't report absinfo, matching the
+/// signed 16-bit range most XInput-style pads use.
+const DEFAULT_AXIS_RANGE: AxisRange = AxisRange {
+    min: -32_768,
+    center: 0,
+    max: 32_767,
+};
+
+/// Rear buttons of the Steam Deck as hid-steam reports them (`BTN_GRIPL`,
+/// `BTN_GRIPL2`, `BTN_GRIPR`, `BTN_GRIPR2`), which evdev has no names for.
+const DECK_L4: KeyCode = KeyCode(0x224);
+const DECK_L5: KeyCode = KeyCode(0x226);
+const DECK_R4: KeyCode = KeyCode(0x225);
+const DECK_R5: KeyCode = KeyCode(0x227);
+
+/// Rear button `key` stands for. Kernels before the grip codes reported L4,
+/// R4, L5 and R5 as `BTN_TRIGGER_HAPPY1`-`4`, which other pads use for their
+/// d-pad, so those only count with `legacy` set, i.e. on the Deck's own pad.
+fn rear_button(key: KeyCode, legacy: bool) -> Option<PadButton> {
+    match key {
+        DECK_L4 => Some(PadButton::L4Btn),
+        DECK_L5 => Some(PadButton::L5Btn),
+        DECK_R4 => Some(PadButton::R4Btn),
+        DECK_R5 => Some(PadButton::R5Btn),
+        KeyCode::BTN_TRIGGER_HAPPY1 if legacy => Some(PadButton::L4Btn),
+        KeyCode::BTN_TRIGGER_HAPPY2 if legacy => Some(PadButton::R4Btn),
+        KeyCode::BTN_TRIGGER_HAPPY3 if legacy => Some(PadButton::L5Btn),
+        KeyCode::BTN_TRIGGER_HAPPY4 if legacy => Some(PadButton::R5Btn),
+        _ => None,
+    }
+}
+
+/// Whether `dev` is the Steam Deck's built-in controller under hid-steam.
+fn is_steam_deck_pad(dev: &Device) -> bool {
+    let id = dev.input_id();
+    id.vendor() == 0x28de && id.product() == 0x1205
+}
+
+#[derive(Clone, PartialEq, Copy)]
+pub enum DeviceType {
+    Gamepad,
+    Keyboard,
+    Mouse,
+    Other,
+}
+
+pub enum PadButton {
+    Left,
+    Right,
+    Up,
+    Down,
+    ABtn,
+    BBtn,
+    XBtn,
+    YBtn,
+    StartBtn,
+    SelectBtn,
+    /// Steam Deck rear buttons, see [`InputDevice::has_rear_buttons`].
+    L4Btn,
+    L5Btn,
+    R4Btn,
+    R5Btn,
+
+    AKey,
+    RKey,
+    XKey,
+    ZKey,
+
+    RightClick,
+}
+
+#[derive(Clone)]
+pub struct DeviceInfo {
+    pub path: String,
+    pub enabled: bool,
+    pub device_type: DeviceType,
+    /// Stable identity used to look up per-device calibration at launch.
+    pub identity: String,
+}
+
+pub struct InputDevice {
+    path: String,
+    /// Handle for names, ids and rumble; events are read on the polling thread.
+    dev: Device,
+    enabled: bool,
+    device_type: DeviceType,
+    /// Events the polling thread read from this device.
+    events: EventFeed,
+    nav: NavState,
+    /// Stable identity (vendor, product and unique id or physical path) so
+    /// calibration follows the pad across reconnects.
+    identity: String,
+    /// Stored calibration, or defaults that mirror the old fixed deadzone.
+    calibration: DeviceCalibration,
+    /// Axis ranges reported by the kernel, used when no calibration exists.
+    axis_ranges: HashMap<u16, AxisRange>,
+    /// Force-feedback effect uploaded by the first menu rumble, reused after.
+    rumble: Option<FFEffect>,
+}
+impl InputDevice {
+    pub fn name(&self) -> &str {
+        self.dev.name().unwrap_or_else(|| "")
+    }
+    pub fn emoji(&self) -> &str {
+        match self.device_type() {
+            DeviceType::Gamepad => "🎮",
+            DeviceType::Keyboard => "🖮",
+            DeviceType::Mouse => "🖱",
+            DeviceType::Other => "",
+        }
+    }
+    pub fn fancyname(&self) -> &str {
+        match self.dev.input_id().vendor() {
+            0x045e => "Xbox Controller",
+            0x054c => "PS Controller",
+            0x057e => "NT Pro Controller",
+            0x28de => "Steam Input",
+            _ => self.name(),
+        }
+    }
+    pub fn path(&self) -> &str {
+        &self.path
+    }
+    pub fn enabled(&self) -> bool {
+        self.enabled
+    }
+    pub fn device_type(&self) -> DeviceType {
+        self.device_type
+    }
+    /// Whether the pad has the Steam Deck's rear buttons, which only the
+    /// hid-steam driver exposes; Steam Input's virtual pads hide them.
+    pub fn has_rear_buttons(&self) -> bool {
+        let legacy = is_steam_deck_pad(&self.dev);
+        self.dev
+            .supported_keys()
+            .is_some_and(|keys| keys.iter().any(|key| rear_button(key, legacy).is_some()))
+    }
+    pub fn has_button_held(&self) -> bool {
+        self.nav.has_button_held()
+    }
+    pub fn info(&self) -> DeviceInfo {
+        DeviceInfo {
+            path: self.path().to_string(),
+            enabled: self.enabled(),
+            device_type: self.device_type(),
+            identity: self.identity.clone(),
+        }
+    }
+    pub fn identity(&self) -> &str {
+        &self.identity
+    }
+    pub fn calibration(&self) -> &DeviceCalibration {
+        &self.calibration
+    }
+    pub fn set_calibration(&mut self, calibration: DeviceCalibration) {
+        self.calibration = calibration;
+    }
+    /// Returns the last raw value seen for an absolute axis.
+    pub fn raw_axis(&self, axis: AbsoluteAxisCode) -> Option<i32> {
+        self.nav.raw_axis(axis)
+    }
+    /// Returns the kernel-reported range for an axis, falling back to the
+    /// signed 16-bit default.
+    pub fn reported_axis_range(&self, axis: AbsoluteAxisCode) -> AxisRange {
+        self.axis_ranges
+            .get(&axis.0)
+            .copied()
+            .unwrap_or(DEFAULT_AXIS_RANGE)
+    }
+    /// Normalizes a raw axis value through the calibration, falling back to
+    /// the reported range when the axis was never calibrated.
+    pub fn normalized_axis(&self, axis: AbsoluteAxisCode, value: i32) -> f32 {
+        calibrated_range(&self.calibration, &self.axis_ranges, axis).normalize(value)
+    }
+    /// Plays a short rumble pulse as menu feedback. Pads without rumble, or
+    /// that can't be written to, stay still.
+    pub fn rumble_pulse(&mut self, strong: u16, weak: u16, length_ms: u16) {
+        let can_rumble = self
+            .dev
+            .supported_ff()
+            .is_some_and(|effects| effects.contains(FFEffectCode::FF_RUMBLE));
+        if self.device_type != DeviceType::Gamepad || !can_rumble {
+            return;
+        }
+        let data = FFEffectData {
+            direction: 0,
+            trigger: FFTrigger {
+                button: 0,
+                interval: 0,
+            },
+            replay: FFReplay {
+                length: length_ms,
+                delay: 0,
+            },
+            kind: FFEffectKind::Rumble {
+                strong_magnitude: strong,
+                weak_magnitude: weak,
+            },
+        };
+        // The effect stays uploaded; dropping it would cut the pulse short.
+        let _ = match &mut self.rumble {
+            Some(effect) => effect.update(data).and_then(|_| effect.play(1)),
+            None => self.dev.upload_ff_effect(data).and_then(|mut effect| {
+                effect.play(1)?;
+                self.rumble = Some(effect);
+                Ok(())
+            }),
+        };
+    }
+
+    /// Applies the events that arrived since the last call and returns the
+    /// last button they pressed. Never blocks; reading happens on the polling
+    /// thread.
+    pub fn poll(&mut self) -> Option<PadButton> {
+        let mut btn: Option<PadButton> = None;
+        for summary in self.events.drain() {
+            btn = self
+                .nav
+                .feed(summary, &self.calibration, &self.axis_ranges)
+                .or(btn);
+        }
+        btn
+    }
+}
+
+/// Builds a stable identity for a device from its vendor/product ids and the
+/// unique id (Bluetooth MAC, serial) or, failing that, its physical port.
+pub fn device_identity(dev: &Device) -> String {
+    let id = dev.input_id();
+    let location = dev
+        .unique_name()
+        .filter(|uniq| !uniq.is_empty())
+        .or_else(|| dev.physical_path())
+        .unwrap_or_default();
+    format!("{:04x}:{:04x}:{location}", id.vendor(), id.product())
+}
+
+pub fn scan_input_devices(filter: &PadFilterType) -> Vec<InputDevice> {
+    let mut pads: Vec<InputDevice> = Vec::new();
+    let calibrations = load_calibrations();
+    for dev in evdev::enumerate() {
+        let enabled = match filter {
+            PadFilterType::All => true,
+            PadFilterType::NoSteamInput => dev.1.input_id().vendor() != 0x28de,
+            PadFilterType::OnlySteamInput => dev.1.input_id().vendor() == 0x28de,
+        };
+
+        let device_type = if dev
+            .1
+            .supported_keys()
+            .map_or(false, |keys| keys.contains(KeyCode::BTN_SOUTH))
+        {
+            DeviceType::Gamepad
+        } else if dev
+            .1
+            .supported_keys()
+            .map_or(false, |keys| keys.contains(KeyCode::BTN_LEFT))
+        {
+            DeviceType::Mouse
+        } else if dev
+            .1
+            .supported_keys()
+            .map_or(false, |keys| keys.contains(KeyCode::KEY_SPACE))
+        {
+            DeviceType::Keyboard
+        } else {
+            DeviceType::Other
+        };
+
+        // A split keyboard only delivers events to its splitter; its two
+        // virtual halves are listed instead.
+        if is_split_keyboard(&dev.0.to_string_lossy()) {
+            continue;
+        }
+
+        if device_type != DeviceType::Other {
+            // A second handle feeds the polling thread so this one stays free
+            // for metadata and rumble.
+            let Ok(reader) = Device::open(&dev.0) else {
+                log_warn(format!("Failed to open {} for polling", dev.0.display()));
+                continue;
+            };
+            if reader.set_nonblocking(true).is_err() {
+                log_warn(format!("Failed to set non-blocking mode for {}", dev.0.display()));
+                continue;
+            }
+            let identity = device_identity(&dev.1);
+            let axis_ranges: HashMap<u16, AxisRange> = dev
+                .1
+                .get_absinfo()
+                .map(|infos| {
+                    infos
+                        .map(|(axis, info)| {
+                            (axis.0, AxisRange::from_bounds(info.minimum(), info.maximum()))
+                        })
+                        .collect()
+                })
+                .unwrap_or_default();
+            pads.push(InputDevice {
+                path: dev.0.to_str().unwrap().to_string(),
+                calibration: calibrations.get(&identity).cloned().unwrap_or_default(),
+                identity,
+                axis_ranges,
+                dev: dev.1,
+                enabled,
+                device_type,
+                events: watch_device(reader),
+                nav: NavState::with_legacy_rear_buttons(is_steam_deck_pad(&dev.1)),
+                rumble: None,
+            });
+        }
+    }
+    pads.sort_by_key(|pad| pad.path().to_string());
+    pads
+}
@@ -0,0 +1,164 @@
+use std::collections::HashMap;
+
+use evdev::{AbsoluteAxisCode, EventSummary, KeyCode};
+
+use crate::util::{AxisRange, DeviceCalibration};
+
+use super::{DEFAULT_AXIS_RANGE, PadButton, rear_button};
+
+/// Range an axis is normalized against: the stored calibration, else the
+/// range the kernel reported, else the signed 16-bit default.
+pub(super) fn calibrated_range(
+    calibration: &DeviceCalibration,
+    axis_ranges: &HashMap<u16, AxisRange>,
+    axis: AbsoluteAxisCode,
+) -> AxisRange {
+    calibration
+        .axes
+        .get(&axis.0)
+        .or_else(|| axis_ranges.get(&axis.0))
+        .copied()
+        .unwrap_or(DEFAULT_AXIS_RANGE)
+}
+
+/// Menu navigation state of one device, turning its raw evdev events into
+/// [`PadButton`] presses. It holds no device handle, so it can be fed
+/// recorded or synthetic events just as well as live ones.
+#[derive(Default)]
+pub struct NavState {
+    has_button_held: bool,
+    /// Remembers the last normalized horizontal stick direction so we only
+    /// emit navigation events when the player actually changes direction.
+    last_axis_x: i32,
+    /// Remembers the last normalized vertical stick direction for the same
+    /// reason as `last_axis_x` and avoids repeated events while the stick stays
+    /// held in one direction.
+    last_axis_y: i32,
+    /// Latest raw value per absolute axis so the calibration wizard can sample
+    /// centers and extremes.
+    raw_axes: HashMap<u16, i32>,
+    /// Whether the Steam Deck rear buttons' older key codes count, see
+    /// [`rear_button`].
+    legacy_rear_buttons: bool,
+}
+
+impl NavState {
+    /// State for a device that may report the Steam Deck rear buttons with
+    /// their older key codes.
+    pub fn with_legacy_rear_buttons(legacy: bool) -> NavState {
+        NavState {
+            legacy_rear_buttons: legacy,
+            ..NavState::default()
+        }
+    }
+
+    pub fn has_button_held(&self) -> bool {
+        self.has_button_held
+    }
+
+    /// Returns the last raw value seen for an absolute axis.
+    pub fn raw_axis(&self, axis: AbsoluteAxisCode) -> Option<i32> {
+        self.raw_axes.get(&axis.0).copied()
+    }
+
+    /// Applies one event and returns the button it pressed, if any. Sticks
+    /// are read through `calibration`, falling back to `axis_ranges`.
+    pub fn feed(
+        &mut self,
+        summary: EventSummary,
+        calibration: &DeviceCalibration,
+        axis_ranges: &HashMap<u16, AxisRange>,
+    ) -> Option<PadButton> {
+        if let EventSummary::AbsoluteAxis(_, axis, value) = summary {
+            self.raw_axes.insert(axis.0, value);
+        }
+        match summary {
+            EventSummary::Key(_, _, 1) => {
+                self.has_button_held = true;
+            }
+            EventSummary::Key(_, _, 0) => {
+                self.has_button_held = false;
+            }
+            _ => {}
+        }
+
+        if let EventSummary::Key(_, key, 1) = summary {
+            if let Some(button) = rear_button(key, self.legacy_rear_buttons) {
+                return Some(button);
+            }
+        }
+        match summary {
+            EventSummary::Key(_, KeyCode::BTN_SOUTH, 1) => Some(PadButton::ABtn),
+            EventSummary::Key(_, KeyCode::BTN_EAST, 1) => Some(PadButton::BBtn),
+            EventSummary::Key(_, KeyCode::BTN_NORTH, 1) => Some(PadButton::XBtn),
+            EventSummary::Key(_, KeyCode::BTN_WEST, 1) => Some(PadButton::YBtn),
+            EventSummary::Key(_, KeyCode::BTN_START, 1) => Some(PadButton::StartBtn),
+            EventSummary::Key(_, KeyCode::BTN_SELECT, 1) => Some(PadButton::SelectBtn),
+            EventSummary::AbsoluteAxis(_, AbsoluteAxisCode::ABS_HAT0X, -1) => {
+                Some(PadButton::Left)
+            }
+            EventSummary::AbsoluteAxis(_, AbsoluteAxisCode::ABS_HAT0X, 1) => {
+                Some(PadButton::Right)
+            }
+            EventSummary::AbsoluteAxis(_, AbsoluteAxisCode::ABS_HAT0Y, -1) => Some(PadButton::Up),
+            EventSummary::AbsoluteAxis(_, AbsoluteAxisCode::ABS_HAT0Y, 1) => {
+                Some(PadButton::Down)
+            }
+            EventSummary::AbsoluteAxis(_, AbsoluteAxisCode::ABS_X, value) => {
+                let direction =
+                    stick_direction(calibration, axis_ranges, AbsoluteAxisCode::ABS_X, value);
+                step_direction(&mut self.last_axis_x, direction, PadButton::Left, PadButton::Right)
+            }
+            EventSummary::AbsoluteAxis(_, AbsoluteAxisCode::ABS_Y, value) => {
+                let direction =
+                    stick_direction(calibration, axis_ranges, AbsoluteAxisCode::ABS_Y, value);
+                step_direction(&mut self.last_axis_y, direction, PadButton::Up, PadButton::Down)
+            }
+            //keyboard
+            EventSummary::Key(_, KeyCode::KEY_A, 1) => Some(PadButton::AKey),
+            EventSummary::Key(_, KeyCode::KEY_R, 1) => Some(PadButton::RKey),
+            EventSummary::Key(_, KeyCode::KEY_X, 1) => Some(PadButton::XKey),
+            EventSummary::Key(_, KeyCode::KEY_Z, 1) => Some(PadButton::ZKey),
+            //mouse
+            EventSummary::Key(_, KeyCode::BTN_RIGHT, 1) => Some(PadButton::RightClick),
+            _ => None,
+        }
+    }
+}
+
+/// Normalizes raw analog stick values into -1, 0, 1 so we can reason about
+/// direction while respecting the device's calibrated navigation threshold.
+fn stick_direction(
+    calibration: &DeviceCalibration,
+    axis_ranges: &HashMap<u16, AxisRange>,
+    axis: AbsoluteAxisCode,
+    value: i32,
+) -> i32 {
+    let normalized = calibrated_range(calibration, axis_ranges, axis).normalize(value);
+    if normalized <= -calibration.nav_threshold {
+        -1
+    } else if normalized >= calibration.nav_threshold {
+        1
+    } else {
+        0
+    }
+}
+
+/// Converts stick motion into one-shot navigation events so the UI can treat
+/// the analog stick just like the D-pad: only a change of direction presses.
+fn step_direction(
+    last: &mut i32,
+    direction: i32,
+    negative: PadButton,
+    positive: PadButton,
+) -> Option<PadButton> {
+    if direction == *last {
+        return None;
+    }
+    *last = direction;
+    match direction {
+        -1 => Some(negative),
+        1 => Some(positive),
+        _ => None,
+    }
+}
@@ -0,0 +1,90 @@
+use std::io::ErrorKind;
+use std::sync::mpsc::{self, Receiver, Sender, SyncSender, TryIter, TrySendError};
+use std::sync::{Arc, LazyLock, Weak};
+use std::thread;
+use std::time::Duration;
+
+use evdev::{Device, EventSummary};
+
+/// Pause between passes over the watched devices; short enough that menu
+/// navigation feels immediate.
+const POLL_INTERVAL: Duration = Duration::from_millis(4);
+/// Events kept per device while the UI doesn't drain them, e.g. while the
+/// window is hidden behind a running game. Newer events are dropped after.
+const FEED_CAPACITY: usize = 256;
+
+/// A device read by the polling thread, until its [`EventFeed`] is dropped.
+struct Watched {
+    device: Device,
+    events: SyncSender<EventSummary>,
+    owner: Weak<()>,
+}
+
+/// Events the polling thread read from one device, waiting for the UI.
+pub(super) struct EventFeed {
+    events: Receiver<EventSummary>,
+    /// Keeps the device watched; the thread lets go once this is dropped.
+    _owner: Arc<()>,
+}
+
+impl EventFeed {
+    /// Events that arrived since the last call, without blocking.
+    pub(super) fn drain(&self) -> TryIter<'_, EventSummary> {
+        self.events.try_iter()
+    }
+}
+
+/// Hands devices to the polling thread, which starts on first use.
+static POLLER: LazyLock<Sender<Watched>> = LazyLock::new(|| {
+    let (tx, rx) = mpsc::channel();
+    thread::spawn(move || poll_devices(rx));
+    tx
+});
+
+/// Reads `device` on the polling thread from now on, so fetching events
+/// never stalls a frame. `device` must be non-blocking.
+pub(super) fn watch_device(device: Device) -> EventFeed {
+    let (tx, rx) = mpsc::sync_channel(FEED_CAPACITY);
+    let owner = Arc::new(());
+    let _ = POLLER.send(Watched {
+        device,
+        events: tx,
+        owner: Arc::downgrade(&owner),
+    });
+    EventFeed {
+        events: rx,
+        _owner: owner,
+    }
+}
+
+fn poll_devices(new_devices: Receiver<Watched>) {
+    let mut watched: Vec<Watched> = Vec::new();
+    loop {
+        // Sleep on the channel while nothing is watched instead of spinning.
+        if watched.is_empty() {
+            match new_devices.recv() {
+                Ok(device) => watched.push(device),
+                Err(_) => return,
+            }
+        }
+        watched.extend(new_devices.try_iter());
+        watched.retain_mut(forward_events);
+        thread::sleep(POLL_INTERVAL);
+    }
+}
+
+/// Passes pending events of one device on; `false` once it was unplugged or
+/// nobody listens anymore. Events that don't fit into a full feed are
+/// dropped, so an undrained feed can't grow without bound.
+fn forward_events(watched: &mut Watched) -> bool {
+    if watched.owner.strong_count() == 0 {
+        return false;
+    }
+    let summaries: Vec<EventSummary> = match watched.device.fetch_events() {
+        Ok(events) => events.map(|event| event.destructure()).collect(),
+        Err(err) => return err.kind() == ErrorKind::WouldBlock,
+    };
+    summaries.into_iter().all(|summary| {
+        !matches!(watched.events.try_send(summary), Err(TrySendError::Disconnected(_)))
+    })
+}
@@ -0,0 +1,182 @@
+use std::collections::HashSet;
+use std::error::Error;
+use std::str::FromStr;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread::JoinHandle;
+use std::time::Duration;
+
+use evdev::uinput::VirtualDevice;
+use evdev::{AttributeSet, BusType, Device, EventSummary, InputEvent, InputId, KeyCode};
+
+use crate::logging::{log_info, log_warn};
+
+/// Keys the first player gets by default: the left side of the main block
+/// (Esc to 5, Q to T, A to G, Z to B), F1-F6, the left modifiers and space.
+/// Every other key, including the arrows and the numpad, goes to the second
+/// player.
+pub const DEFAULT_SPLIT_KEYS: &str = "KEY_ESC-KEY_5 KEY_TAB-KEY_T KEY_LEFTCTRL-KEY_G KEY_GRAVE KEY_LEFTSHIFT KEY_Z-KEY_B KEY_LEFTALT KEY_SPACE KEY_CAPSLOCK KEY_F1-KEY_F6";
+
+/// Vendor id of the synthetic keyboards ("SH"), so they never match a real
+/// device's identity.
+const SPLIT_VENDOR: u16 = 0x5348;
+
+/// How long the splitter thread sleeps when the keyboard has no events.
+const SPLIT_IDLE_SLEEP: Duration = Duration::from_millis(1);
+
+/// Physical keyboards currently grabbed by a splitter. Scans hide them, since
+/// the grab keeps their events from reaching anything but the splitter.
+static SPLIT_KEYBOARDS: Mutex<Vec<String>> = Mutex::new(Vec::new());
+
+/// Whether the keyboard at `path` is grabbed by a [`KeyboardSplitter`].
+pub fn is_split_keyboard(path: &str) -> bool {
+    SPLIT_KEYBOARDS
+        .lock()
+        .map(|paths| paths.iter().any(|p| p == path))
+        .unwrap_or(false)
+}
+
+fn parse_key(name: &str) -> Result<KeyCode, String> {
+    KeyCode::from_str(name).map_err(|_| format!("Unknown key {name}"))
+}
+
+/// Parses a key list such as `KEY_ESC-KEY_5 KEY_SPACE` into key codes.
+/// Entries are separated by spaces or commas; `FIRST-LAST` is an inclusive
+/// range in kernel key numbering, which follows the rows of a US keyboard.
+pub fn parse_key_ranges(spec: &str) -> Result<HashSet<u16>, String> {
+    let mut keys = HashSet::new();
+    for entry in spec.split([' ', ',']).filter(|entry| !entry.is_empty()) {
+        match entry.split_once('-') {
+            Some((first, last)) => {
+                let (first, last) = (parse_key(first)?.0, parse_key(last)?.0);
+                if first > last {
+                    return Err(format!("Range {entry} runs backwards"));
+                }
+                keys.extend(first..=last);
+            }
+            None => {
+                keys.insert(parse_key(entry)?.0);
+            }
+        }
+    }
+    if keys.is_empty() {
+        return Err("No keys are assigned to the first player".to_string());
+    }
+    Ok(keys)
+}
+
+/// One physical keyboard grabbed and re-exposed as two uinput keyboards, one
+/// per player. The keyboard is released once the splitter is dropped.
+pub struct KeyboardSplitter {
+    pub physical_path: String,
+    /// Event nodes of the first and second player's keyboard.
+    pub virtual_paths: [String; 2],
+    stop: Arc<AtomicBool>,
+    handle: Option<JoinHandle<()>>,
+}
+
+impl Drop for KeyboardSplitter {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::Relaxed);
+        if let Some(handle) = self.handle.take() {
+            let _ = handle.join();
+        }
+        if let Ok(mut paths) = SPLIT_KEYBOARDS.lock() {
+            paths.retain(|path| *path != self.physical_path);
+        }
+    }
+}
+
+/// Grabs the keyboard at `path` and routes `first_keys` to one virtual
+/// keyboard and every other key to a second one. Both declare the full key
+/// set of the physical keyboard, so games treat them as ordinary keyboards.
+pub fn spawn_keyboard_splitter(
+    path: &str,
+    first_keys: HashSet<u16>,
+) -> Result<KeyboardSplitter, Box<dyn Error>> {
+    let mut physical = Device::open(path)?;
+    let mut key_set = AttributeSet::<KeyCode>::new();
+    if let Some(keys) = physical.supported_keys() {
+        for key in keys.iter() {
+            key_set.insert(key);
+        }
+    }
+    if !key_set.contains(KeyCode::KEY_SPACE) {
+        return Err(format!("{path} is not a keyboard").into());
+    }
+
+    let mut halves = Vec::new();
+    let mut virtual_paths = Vec::new();
+    for half in 1..=2u16 {
+        let mut device = VirtualDevice::builder()?
+            .name(&format!("Split Happens Keyboard {half}"))
+            .input_id(InputId::new(BusType::BUS_VIRTUAL, SPLIT_VENDOR, half, 1))
+            .with_keys(&key_set)?
+            .build()?;
+        let node = device
+            .enumerate_dev_nodes_blocking()?
+            .flatten()
+            .next()
+            .ok_or("uinput did not report a device node for the split keyboard")?
+            .to_string_lossy()
+            .to_string();
+        virtual_paths.push(node);
+        halves.push(device);
+    }
+
+    // Grab last so a failed setup never leaves the desktop without a keyboard.
+    physical.grab()?;
+    physical.set_nonblocking(true)?;
+    if let Ok(mut paths) = SPLIT_KEYBOARDS.lock() {
+        paths.push(path.to_string());
+    }
+
+    let stop = Arc::new(AtomicBool::new(false));
+    let thread_stop = stop.clone();
+    let thread_path = path.to_string();
+    let handle = std::thread::spawn(move || {
+        while !thread_stop.load(Ordering::Relaxed) {
+            let mut batches: [Vec<InputEvent>; 2] = [Vec::new(), Vec::new()];
+            match physical.fetch_events() {
+                Ok(events) => {
+                    for event in events {
+                        // Scan codes and sync reports aren't forwarded; emit()
+                        // closes each batch with its own SYN_REPORT.
+                        if let EventSummary::Key(_, key, _) = event.destructure() {
+                            let half = if first_keys.contains(&key.0) { 0 } else { 1 };
+                            batches[half].push(event);
+                        }
+                    }
+                }
+                Err(err) if err.kind() == std::io::ErrorKind::WouldBlock => {
+                    std::thread::sleep(SPLIT_IDLE_SLEEP);
+                    continue;
+                }
+                Err(err) => {
+                    log_warn(format!("Keyboard splitter for {thread_path} stopped: {err}"));
+                    break;
+                }
+            }
+            for (device, batch) in halves.iter_mut().zip(&batches) {
+                if batch.is_empty() {
+                    continue;
+                }
+                if let Err(err) = device.emit(batch) {
+                    log_warn(format!("Keyboard splitter for {thread_path} failed to emit: {err}"));
+                }
+            }
+        }
+        let _ = physical.ungrab();
+    });
+
+    log_info(format!(
+        "Splitting keyboard {path} into {} and {}",
+        virtual_paths[0], virtual_paths[1]
+    ));
+    Ok(KeyboardSplitter {
+        physical_path: path.to_string(),
+        virtual_paths: [virtual_paths[0].clone(), virtual_paths[1].clone()],
+        stop,
+        handle: Some(handle),
+    })
+}
@@ -0,0 +1,228 @@
+use crate::config::{AutoAssignOrder, PartyConfig};
+use crate::input::{DeviceType, InputDevice};
+use crate::layout::{TwoPlayerSplit, pinned_rects, player_rects, session_screen};
+use crate::logging::log_info;
+use crate::util::{ProfileLock, host_profile};
+
+use std::os::unix::fs::MetadataExt;
+
+use serde::{Deserialize, Serialize};
+
+/// Build of the game an instance runs, for handlers that ship both a Linux
+/// and a Windows executable (`game.alt_exec`).
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Default)]
+pub enum InstancePlatform {
+    /// Whatever the handler declares with `game.win`.
+    #[default]
+    Handler,
+    Native,
+    Proton,
+}
+
+impl InstancePlatform {
+    pub const ALL: [InstancePlatform; 3] =
+        [InstancePlatform::Handler, InstancePlatform::Native, InstancePlatform::Proton];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            InstancePlatform::Handler => "Handler default",
+            InstancePlatform::Native => "Native",
+            InstancePlatform::Proton => "Proton",
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct Instance {
+    pub devices: Vec<usize>,
+    pub profname: String,
+    pub profselection: usize,
+    pub width: u32,
+    pub height: u32,
+    /// Display the instance is pinned to, by connector name. `None` shares
+    /// the primary display, or the whole screen when nobody is pinned.
+    pub monitor: Option<String>,
+    /// Whether the motion sensor and touchpad nodes of the instance's pads
+    /// (DualShock 4, DualSense and the like) are forwarded to it. Other
+    /// instances never see them.
+    pub motion_passthrough: bool,
+    /// Build this instance runs; only handlers with `game.alt_exec` honor
+    /// anything but [`InstancePlatform::Handler`].
+    pub platform: InstancePlatform,
+}
+
+pub fn set_instance_resolutions(instances: &mut Vec<Instance>, cfg: &PartyConfig) {
+    let playercount = instances.len();
+    // Size every instance to its tile so the layout never has to stretch it.
+    let monitors: Vec<Option<String>> = instances.iter().map(|i| i.monitor.clone()).collect();
+    let rects = pinned_rects(&monitors, cfg).unwrap_or_else(|| {
+        let screen = session_screen();
+        if playercount == 2 && cfg.two_player_split == TwoPlayerSplit::Auto {
+            log_info(format!(
+                "Splitting the {}x{} screen {} for two players",
+                screen.0,
+                screen.1,
+                match TwoPlayerSplit::Auto.resolve(screen) {
+                    TwoPlayerSplit::Vertical => "side by side",
+                    _ => "top and bottom",
+                }
+            ));
+        }
+        player_rects(playercount, screen, cfg)
+    });
+
+    let mut i = 0;
+    for instance in instances {
+        let (mut w, mut h) = (rects[i].width, rects[i].height);
+        // Round the calculated viewport down to even dimensions so Gamescope avoids
+        // fractional scaling that can introduce subtle frame pacing hitches.
+        if w % 2 == 1 && w > 1 {
+            w -= 1;
+        }
+        if h % 2 == 1 && h > 1 {
+            h -= 1;
+        }
+        if h < 600 && cfg.gamescope_fix_lowres {
+            let ratio = w as f32 / h as f32;
+            h = 600;
+            w = (h as f32 * ratio) as u32;
+            if w % 2 == 1 && w > 1 {
+                w -= 1;
+            }
+        }
+        log_info(format!("Resolution for instance {}/{playercount}: {w}x{h}", i + 1));
+        instance.width = w;
+        instance.height = h;
+        i += 1;
+    }
+}
+
+pub fn set_instance_names(instances: &mut Vec<Instance>, profiles: &[String]) {
+    // Track how many guest slots have been assigned so we can number them sequentially even
+    // when non-guest profiles appear between guest instances.
+    let mut next_guest_index = 1usize;
+
+    for instance in instances.iter_mut() {
+        // Guests of another running game keep their number, since each guest
+        // name has its own working tree under run/.
+        while ProfileLock::is_held(&format!("Guest{next_guest_index}")) {
+            next_guest_index += 1;
+        }
+        // Resolve the selected profile name and gracefully handle stale indices by
+        // falling back to a fresh guest slot. This keeps long-standing profiles from being
+        // misidentified as guests when the profile picker omits the synthetic "Guest" entry.
+        match profiles.get(instance.profselection) {
+            Some(selected) if selected == "Guest" => {
+                instance.profname = format!("Guest{next_guest_index}");
+                next_guest_index += 1;
+            }
+            Some(selected) => {
+                instance.profname = selected.to_owned();
+            }
+            None => {
+                instance.profname = format!("Guest{next_guest_index}");
+                next_guest_index += 1;
+            }
+        }
+    }
+}
+
+/// Slot of the profile marked to always play in slot 1, when it is one of
+/// `instances` but not in slot 1 yet. `profiles` is what `profselection`
+/// indexes.
+pub fn misplaced_host_slot(instances: &[Instance], profiles: &[String]) -> Option<usize> {
+    let host = host_profile()?;
+    instances
+        .iter()
+        .position(|instance| profiles.get(instance.profselection) == Some(&host))
+        .filter(|&slot| slot > 0)
+}
+
+/// Puts the profile marked to always play in slot 1 there when slots are
+/// filled automatically. If it plays in a later slot, that slot moves to the
+/// front with its devices and the slot it came from is returned; if no slot
+/// has it yet, slot 1 is given to it.
+pub fn place_host_in_first_slot(
+    instances: &mut [Instance],
+    profiles: &[String],
+) -> Option<usize> {
+    if let Some(slot) = misplaced_host_slot(instances, profiles) {
+        instances[..=slot].rotate_right(1);
+        return Some(slot);
+    }
+    let host = host_profile()?;
+    let index = profiles.iter().position(|profile| *profile == host)?;
+    if !instances.iter().any(|instance| instance.profselection == index) {
+        instances.first_mut()?.profselection = index;
+    }
+    None
+}
+
+/// Indices of the enabled gamepads in `devices`, in the order "Auto Assign"
+/// gives them to player slots.
+pub fn auto_assign_devices(devices: &[InputDevice], order: AutoAssignOrder) -> Vec<usize> {
+    let mut pads: Vec<usize> = (0..devices.len())
+        .filter(|&i| devices[i].enabled() && devices[i].device_type() == DeviceType::Gamepad)
+        .collect();
+    match order {
+        AutoAssignOrder::ConnectionTime => {
+            pads.sort_by_key(|&i| (connected_at(devices[i].path()), devices[i].path().to_string()))
+        }
+        AutoAssignOrder::Vendor => pads.sort_by_key(|&i| devices[i].identity().to_string()),
+    }
+    pads
+}
+
+/// When the device node appeared. Nodes under /dev/input are created when a
+/// device connects, so their change time orders pads by connection.
+fn connected_at(path: &str) -> (i64, i64) {
+    std::fs::metadata(path)
+        .map(|meta| (meta.ctime(), meta.ctime_nsec()))
+        .unwrap_or((i64::MAX, 0))
+}
+
+/// Identities of each instance's devices, the form device assignments are
+/// saved in and [`restore_device_slots`] reads back.
+pub fn device_slot_identities(instances: &[Instance], devices: &[InputDevice]) -> Vec<Vec<String>> {
+    instances
+        .iter()
+        .map(|instance| {
+            instance
+                .devices
+                .iter()
+                .filter_map(|&dev| devices.get(dev))
+                .map(|dev| dev.identity().to_string())
+                .collect()
+        })
+        .collect()
+}
+
+/// Device indices per slot for saved device identities. Devices that aren't
+/// connected are skipped, and slots left without one are dropped.
+pub fn restore_device_slots(devices: &[InputDevice], saved: &[Vec<String>]) -> Vec<Vec<usize>> {
+    match_device_slots(devices, saved)
+        .into_iter()
+        .filter(|slot| !slot.is_empty())
+        .collect()
+}
+
+/// Like [`restore_device_slots`], but keeps one entry per saved slot, empty
+/// when none of its devices are connected.
+pub fn match_device_slots(devices: &[InputDevice], saved: &[Vec<String>]) -> Vec<Vec<usize>> {
+    let mut used = vec![false; devices.len()];
+    saved
+        .iter()
+        .map(|identities| {
+            identities
+                .iter()
+                .filter_map(|identity| {
+                    let index = (0..devices.len()).find(|&i| {
+                        !used[i] && devices[i].enabled() && devices[i].identity() == identity
+                    })?;
+                    used[index] = true;
+                    Some(index)
+                })
+                .collect::<Vec<usize>>()
+        })
+        .collect()
+}
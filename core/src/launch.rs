@@ -0,0 +1,3699 @@
+use std::collections::{BTreeMap, HashMap, HashSet, VecDeque};
+use std::fs::{self, File, OpenOptions};
+use std::io::ErrorKind;
+use std::io::{BufRead, BufReader, Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+use crate::config::PartyConfig;
+use crate::game::Game;
+use crate::game::Game::{ExecRef, HandlerRef};
+use crate::handler::*;
+use crate::input::*;
+use crate::instance::*;
+use crate::layout::{NativeLayout, Rect, pinned_rects, write_kwin_script};
+use crate::logging::{self, SessionEventKind, log_info, log_instance, log_warn};
+use crate::paths::*;
+use crate::session::{BootStage, SessionCommand, SessionControl, SessionManager, SessionSlot};
+use crate::util::*;
+
+use nix::libc;
+use nix::sched::{CpuSet, sched_setaffinity};
+use nix::sys::signal::{Signal, kill};
+use nix::unistd::Pid;
+use std::process::ExitStatus;
+use std::process::{Child, Command, Stdio};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use evdev::{Device as EvDevice, EventSummary, KeyCode};
+
+fn prepare_working_tree(
+    profname: &str,
+    gamedir: &str,
+    nemirtingas_rel: &str,
+    src: &Path,
+    session: &SessionControl,
+) -> Result<PathBuf, Box<dyn std::error::Error>> {
+    let run_fs = PATH_APP.join(format!("run/{profname}/fs"));
+    if run_fs.exists() {
+        std::fs::remove_dir_all(&run_fs)?;
+    }
+    std::fs::create_dir_all(&run_fs)?;
+    // Large games have tens of thousands of files, so the loading screen
+    // shows how far linking got.
+    let linked = build_symlink_tree(Path::new(gamedir), &run_fs, |done, total| {
+        session.set_progress(Some(format!("Linking game files for {profname}: {done}/{total}")));
+        !session.is_cancelled()
+    });
+    session.set_progress(None);
+    linked?;
+    if !nemirtingas_rel.is_empty() {
+        let dest_dir = run_fs.join(Path::new(nemirtingas_rel).parent().unwrap());
+        if dest_dir.exists() {
+            if dest_dir.is_file() || dest_dir.is_symlink() {
+                std::fs::remove_file(&dest_dir)?;
+            } else {
+                std::fs::remove_dir_all(&dest_dir)?;
+            }
+        }
+        if let Some(parent) = dest_dir.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::os::unix::fs::symlink(src, &dest_dir)?;
+    }
+    Ok(run_fs)
+}
+
+/// Tracks Nemirtingas logging metadata for an instance so we can surface the
+/// persisted emulator output once the Proton processes terminate.
+#[derive(Clone)]
+struct NemirtingasLogContext {
+    profile_log: PathBuf,
+    appdata_root: Option<PathBuf>,
+}
+
+/// Nemirtingas log files the emulator wrote under the Proton AppData roots
+/// of `context`, sorted by path. Unreadable folders are only reported when
+/// `report_errors` is set, so repeated scans don't flood the log.
+fn nemirtingas_log_sources(context: &NemirtingasLogContext, report_errors: bool) -> Vec<PathBuf> {
+    let mut sources: Vec<PathBuf> = Vec::new();
+
+    if let Some(appdata_root) = &context.appdata_root {
+        let mut search_roots = vec![appdata_root.clone()];
+        if let Some(local_root) = appdata_root
+            .parent()
+            .and_then(|roaming| roaming.parent())
+            .map(|appdata| appdata.join("Local").join("NemirtingasEpicEmu"))
+        {
+            search_roots.push(local_root);
+        }
+
+        let mut stack = search_roots;
+        while let Some(path) = stack.pop() {
+            if !path.exists() {
+                continue;
+            }
+            if path.is_dir() {
+                match fs::read_dir(&path) {
+                    Ok(entries) => {
+                        for entry in entries.flatten() {
+                            let child = entry.path();
+                            if child.is_dir() {
+                                stack.push(child);
+                                continue;
+                            }
+                            if let Some(name) = child.file_name().and_then(|n| n.to_str()) {
+                                let lower = name.to_ascii_lowercase();
+                                let is_log = lower.ends_with(".log") || lower.ends_with(".txt");
+                                let matches_prefix =
+                                    lower.contains("nemirtingas") || lower.contains("applog");
+                                if is_log && matches_prefix {
+                                    sources.push(child);
+                                }
+                            }
+                        }
+                    }
+                    Err(err) if report_errors => {
+                        log_warn(format!(
+                            "Failed to enumerate Nemirtingas logs under {}: {}",
+                            path.display(),
+                            err
+                        ));
+                    }
+                    Err(_) => {}
+                }
+            }
+        }
+    }
+
+    sources.sort();
+    sources.dedup();
+    sources
+}
+
+/// Scans the Proton AppData roots for Nemirtingas log files and copies their
+/// contents into the Split Happens profile log so the advertised path always
+/// contains the most recent emulator errors for the user.
+fn collect_nemirtingas_logs(contexts: &[NemirtingasLogContext]) {
+    for context in contexts {
+        let sources = nemirtingas_log_sources(context, true);
+
+        let mut aggregated: Vec<u8> = Vec::new();
+        for source in sources {
+            match fs::read(&source) {
+                Ok(data) => {
+                    let header = format!("===== {} =====\n", source.display());
+                    aggregated.extend_from_slice(header.as_bytes());
+                    aggregated.extend_from_slice(&data);
+                    if !data.ends_with(b"\n") {
+                        aggregated.push(b'\n');
+                    }
+                    aggregated.push(b'\n');
+                }
+                Err(err) => {
+                    log_warn(format!(
+                        "Failed to read Nemirtingas log {}: {}",
+                        source.display(),
+                        err
+                    ));
+                }
+            }
+        }
+
+        if aggregated.is_empty() {
+            continue;
+        }
+
+        if let Some(parent) = context.profile_log.parent() {
+            if let Err(err) = fs::create_dir_all(parent) {
+                log_warn(format!(
+                    "Failed to prepare Nemirtingas log directory {}: {}",
+                    parent.display(),
+                    err
+                ));
+                continue;
+            }
+        }
+
+        match OpenOptions::new()
+            .create(true)
+            .write(true)
+            .truncate(true)
+            .open(&context.profile_log)
+        {
+            Ok(mut dest) => {
+                if let Err(err) = dest.write_all(&aggregated) {
+                    log_warn(format!(
+                        "Failed to persist Nemirtingas log {}: {}",
+                        context.profile_log.display(),
+                        err
+                    ));
+                }
+            }
+            Err(err) => {
+                log_warn(format!(
+                    "Failed to open Nemirtingas log {}: {}",
+                    context.profile_log.display(),
+                    err
+                ));
+            }
+        }
+    }
+}
+
+/// How often the session loop looks for new Nemirtingas log output.
+const NEMIRTINGAS_TAIL_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Follows the Nemirtingas log files of one instance while it runs, so
+/// discovery problems show up in the instance log without waiting for the
+/// post-exit collection.
+struct NemirtingasTail {
+    context: NemirtingasLogContext,
+    /// Bytes already read from each followed file.
+    offsets: HashMap<PathBuf, u64>,
+}
+
+impl NemirtingasTail {
+    /// Starts at the current end of existing files, so output left behind by
+    /// earlier sessions isn't replayed.
+    fn new(context: &NemirtingasLogContext) -> Self {
+        let mut tail = NemirtingasTail {
+            context: context.clone(),
+            offsets: HashMap::new(),
+        };
+        for path in tail.followed_files(true) {
+            let len = fs::metadata(&path).map_or(0, |meta| meta.len());
+            tail.offsets.insert(path, len);
+        }
+        tail
+    }
+
+    /// Proton builds write under AppData; native builds write straight to
+    /// the profile log.
+    fn followed_files(&self, report_errors: bool) -> Vec<PathBuf> {
+        match self.context.appdata_root {
+            Some(_) => nemirtingas_log_sources(&self.context, report_errors),
+            None if self.context.profile_log.is_file() => vec![self.context.profile_log.clone()],
+            None => Vec::new(),
+        }
+    }
+
+    /// Complete lines appended since the last call. Files that shrank were
+    /// recreated by the emulator and are read again from the start.
+    fn read_new_lines(&mut self) -> Vec<String> {
+        let mut lines = Vec::new();
+        for path in self.followed_files(false) {
+            let offset = self.offsets.entry(path.clone()).or_insert(0);
+            let Ok(mut file) = File::open(&path) else {
+                continue;
+            };
+            let len = file.metadata().map_or(0, |meta| meta.len());
+            if len < *offset {
+                *offset = 0;
+            }
+            if len == *offset || file.seek(SeekFrom::Start(*offset)).is_err() {
+                continue;
+            }
+            let mut data = Vec::new();
+            if file.read_to_end(&mut data).is_err() {
+                continue;
+            }
+            // Leave a partially written last line for the next call.
+            let Some(end) = data.iter().rposition(|byte| *byte == b'\n') else {
+                continue;
+            };
+            *offset += end as u64 + 1;
+            lines.extend(
+                String::from_utf8_lossy(&data[..end])
+                    .lines()
+                    .map(|line| line.trim_end().to_string())
+                    .filter(|line| !line.is_empty()),
+            );
+        }
+        lines
+    }
+}
+
+/// Streams new Nemirtingas log lines of every running instance into its
+/// instance log.
+fn tail_nemirtingas_logs(runtime_instances: &mut [RuntimeInstance]) {
+    for state in runtime_instances.iter_mut().filter(|state| state.child.is_some()) {
+        for line in state.nemirtingas_tail.read_new_lines() {
+            log_instance(
+                state.index,
+                &state.profile_name,
+                &format!("[Nemirtingas] {line}"),
+                false,
+            );
+        }
+    }
+}
+
+/// Captures the reusable artifacts from launching a single instance so crashes can be
+/// recovered without rebuilding the entire session state.
+struct SpawnOutcome {
+    child: Child,
+    /// The game's own process when it runs as another account, see
+    /// [`find_seat_process`].
+    seat_pid: Option<u32>,
+    log_context: NemirtingasLogContext,
+    proton_prefix: Option<String>,
+    /// The exact command line used, for the launch history.
+    command_line: String,
+}
+
+/// Patches the handler's `game.resolution_files` with the instance's
+/// resolution. Game files become a real copy in the instance's working tree,
+/// or under bubblewrap a copy in `run/<profile>/patched` that is returned to
+/// be bound over the shared game folder. Save files are edited in place.
+fn apply_resolution_files(
+    h: &Handler,
+    instance: &Instance,
+    instance_gamedir: &str,
+    party: &str,
+    use_bwrap: bool,
+) -> Result<Vec<(PathBuf, PathBuf)>, Box<dyn std::error::Error>> {
+    let path_save = PathBuf::from(format!(
+        "{party}/profiles/{}/saves/{}",
+        instance.profname, h.uid
+    ));
+    let path_patched = PATH_APP.join(format!("run/{}/patched", instance.profname));
+    let mut binds = Vec::new();
+    for file in &h.resolution_files {
+        let in_game = PathBuf::from(instance_gamedir).join(&file.path);
+        // Folders in profiles.game_paths are bound from the save folder, so
+        // the file is patched where the instance will actually read it.
+        let unique = h
+            .game_unique_paths
+            .iter()
+            .any(|subdir| Path::new(&file.path).starts_with(subdir));
+        let (source, target) = match file.root {
+            ResolutionFileRoot::Save => (path_save.join(&file.path), path_save.join(&file.path)),
+            ResolutionFileRoot::Game if use_bwrap && unique => {
+                (path_save.join(&file.path), path_save.join(&file.path))
+            }
+            ResolutionFileRoot::Game if use_bwrap => {
+                (in_game.clone(), path_patched.join(&file.path))
+            }
+            ResolutionFileRoot::Game => (in_game.clone(), in_game.clone()),
+        };
+        let contents = match fs::read_to_string(&source) {
+            Ok(contents) => contents,
+            Err(err) => {
+                log_warn(format!(
+                    "Instance {}: resolution file {} not patched: {err}",
+                    instance.profname,
+                    source.display()
+                ));
+                continue;
+            }
+        };
+        let (patched, changes) = match file.apply(&contents, instance.width, instance.height) {
+            Ok(result) => result,
+            Err(err) => {
+                log_warn(format!(
+                    "Instance {}: invalid regex for resolution file {}: {err}",
+                    instance.profname, file.path
+                ));
+                continue;
+            }
+        };
+        if changes == 0 {
+            log_warn(format!(
+                "Instance {}: nothing to patch in resolution file {}",
+                instance.profname,
+                source.display()
+            ));
+            continue;
+        }
+        if let Some(parent) = target.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        // Working trees link back to the shared game files, which must stay untouched.
+        if target.is_symlink() {
+            fs::remove_file(&target)?;
+        }
+        fs::write(&target, patched)?;
+        log_info(format!(
+            "Instance {}: patched {} ({changes} change(s)) for {}x{}",
+            instance.profname,
+            target.display(),
+            instance.width,
+            instance.height
+        ));
+        if target != source {
+            binds.push((target, in_game));
+        }
+    }
+    Ok(binds)
+}
+
+/// Renders a command as a shell-like line, quoting arguments with spaces.
+fn describe_command(cmd: &Command) -> String {
+    std::iter::once(cmd.get_program())
+        .chain(cmd.get_args())
+        .map(|part| {
+            let part = part.to_string_lossy();
+            if part.contains(' ') {
+                format!("'{part}'")
+            } else {
+                part.into_owned()
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Spawns a single Gamescope instance for the provided player slot while preparing all
+/// emulator mounts and controller bindings required by the handler. The returned
+/// [`SpawnOutcome`] keeps enough context for the caller to re-launch the same slot later
+/// when a crash occurs.
+fn spawn_instance_child(
+    index: usize,
+    instance: &Instance,
+    game: &Game,
+    game_id: &str,
+    gamedir: &str,
+    platform: &InstanceRuntime,
+    use_bwrap: bool,
+    cfg: &PartyConfig,
+    input_devices: &[DeviceInfo],
+    nemirtingas_ports: &HashMap<String, u16>,
+    drained_prefixes: &mut HashSet<String>,
+    purged_nemirtingas_prefixes: &mut HashSet<String>,
+    pad_proxies: &[(usize, PadProxy)],
+    overrides: &LaunchOverrides,
+    party: &str,
+    steam: &str,
+    home: &str,
+    localshare: &str,
+    session: &SessionControl,
+) -> Result<SpawnOutcome, Box<dyn std::error::Error>> {
+    let win = platform.win;
+    let proton_env = platform.proton_env.as_ref();
+    let profile_port = nemirtingas_ports.get(&instance.profname).copied();
+
+    let profile = Profile::load(&instance.profname);
+    let nemirtingas_template = match game {
+        HandlerRef(h) => h.nemirtingas_template()?,
+        ExecRef(_) => None,
+    };
+    let (nepice_dir, json_path, log_path, sha1_nemirtingas) = ensure_nemirtingas_config(
+        &instance.profname,
+        game_id,
+        profile_port,
+        &profile.eos_language(),
+        &profile.eos_country(),
+        nemirtingas_template.as_ref(),
+    )?;
+    let json_real = json_path.canonicalize()?;
+    let mut log_context = NemirtingasLogContext {
+        profile_log: log_path.clone(),
+        appdata_root: None,
+    };
+
+    reset_nemirtingas_session_state(&nepice_dir);
+
+    let instance_gamedir = if use_bwrap {
+        gamedir.to_string()
+    } else if let HandlerRef(h) = game {
+        let tree = prepare_working_tree(
+            instance.profname.as_str(),
+            gamedir,
+            h.path_nemirtingas.as_str(),
+            &nepice_dir,
+            session,
+        )?;
+        record_working_tree(&instance.profname, game.name());
+        tree.to_string_lossy().to_string()
+    } else {
+        gamedir.to_string()
+    };
+
+    let mut nemirtingas_binds: Vec<(PathBuf, PathBuf)> = Vec::new();
+    if let HandlerRef(h) = game {
+        if !h.path_nemirtingas.is_empty() {
+            let nemirtingas_rel = Path::new(&h.path_nemirtingas);
+            let Some(parent_rel) = nemirtingas_rel.parent() else {
+                return Err(format!(
+                    "Nemirtingas path {} has no parent directory; update the handler configuration.",
+                    h.path_nemirtingas
+                )
+                .into());
+            };
+
+            let dest_dir = PathBuf::from(&instance_gamedir).join(parent_rel);
+            if dest_dir.exists() && !dest_dir.is_dir() {
+                fs::remove_file(&dest_dir)?;
+            }
+            fs::create_dir_all(&dest_dir)?;
+
+            let dest_config = dest_dir.join(
+                nemirtingas_rel
+                    .file_name()
+                    .unwrap_or_else(|| std::ffi::OsStr::new("NemirtingasEpicEmu.json")),
+            );
+
+            log_info(format!(
+                "Instance {}: Nemirtingas config {} (SHA1 {}) -> {} (user {} appid {})",
+                instance.profname,
+                json_real.display(),
+                sha1_nemirtingas,
+                dest_config.display(),
+                instance.profname,
+                game_id
+            ));
+
+            if use_bwrap {
+                nemirtingas_binds.push((nepice_dir.clone(), dest_dir.clone()));
+            }
+        }
+    }
+
+    let resolution_binds = match game {
+        HandlerRef(h) => apply_resolution_files(h, instance, &instance_gamedir, party, use_bwrap)?,
+        ExecRef(_) => Vec::new(),
+    };
+
+    let mut cmd = Command::new(match cfg.kbm_support {
+        true => BIN_GSC_KBM.to_string_lossy().to_string(),
+        false => "gamescope".to_string(),
+    });
+
+    cmd.current_dir(&instance_gamedir);
+    // Tag the instance so the LAN diagnostics can attribute its sockets.
+    cmd.env(INSTANCE_ENV_TAG, &instance.profname);
+    cmd.env("SDL_JOYSTICK_HIDAPI", "0");
+    cmd.env("ENABLE_GAMESCOPE_WSI", "0");
+    cmd.env("PROTON_DISABLE_HIDRAW", "1");
+    if cfg.force_sdl && !win {
+        let mut path_sdl = "ubuntu12_32/steam-runtime/usr/lib/x86_64-linux-gnu/libSDL2-2.0.so.0";
+        if let HandlerRef(h) = game {
+            if h.is32bit {
+                path_sdl = "ubuntu12_32/steam-runtime/usr/lib/i386-linux-gnu/libSDL2-2.0.so.0";
+            }
+        }
+        cmd.env("SDL_DYNAMIC_API", format!("{steam}/{path_sdl}"));
+    }
+    if let Some(port) = profile_port {
+        cmd.env("EOS_OVERRIDE_LAN_PORT", port.to_string());
+    }
+    if win {
+        if let Some(env) = proton_env {
+            cmd.env("PROTON_VERB", "run");
+            cmd.env("PROTONPATH", env.env_value.clone());
+        }
+        if cfg.performance_enable_proton_fsr {
+            // Enable Proton's built-in FSR scaling so Windows games can render below native resolution without severe blur.
+            cmd.env("WINE_FULLSCREEN_FSR", "1");
+            cmd.env("WINE_FULLSCREEN_FSR_MODE", "1");
+            cmd.env("WINE_FULLSCREEN_FSR_STRENGTH", "2");
+        }
+        if let HandlerRef(h) = game {
+            let dlls: Vec<&String> =
+                h.dll_overrides.iter().chain(&overrides.dll_overrides).collect();
+            if !dlls.is_empty() {
+                let mut dll_env = String::new();
+                for dll in dlls {
+                    dll_env.push_str(&format!("{dll},"));
+                }
+                dll_env.push_str("=n,b");
+                cmd.env("WINEDLLOVERRIDES", dll_env);
+            }
+            if h.coldclient {
+                cmd.env("PROTON_DISABLE_LSTEAMCLIENT", "1");
+            }
+            match h.graphics_api {
+                Some(GraphicsApi::Wined3d) => {
+                    cmd.env("PROTON_USE_WINED3D", "1");
+                }
+                Some(GraphicsApi::Dxvk) => {
+                    cmd.env("PROTON_USE_WINED3D", "0");
+                }
+                None => {}
+            }
+            if !h.vkd3d_config.is_empty() {
+                cmd.env("VKD3D_CONFIG", h.vkd3d_config.join(","));
+            }
+            // DXVK reads a dxvk.conf next to the executable only without
+            // DXVK_CONFIG_FILE, so the game's own one is merged in.
+            let game_conf = Path::new(&instance_gamedir)
+                .join(&platform.exec)
+                .parent()
+                .and_then(|dir| fs::read_to_string(dir.join("dxvk.conf")).ok());
+            let dxvk_conf = render_dxvk_conf(
+                &h.dxvk_conf,
+                h.dxvk_fps_limit_for(index),
+                index + 1,
+                instance.width,
+                instance.height,
+                game_conf.as_deref(),
+            );
+            if let Some(conf) = dxvk_conf {
+                let pfx = instance_prefix(party, &instance.profname, index, cfg);
+                std::fs::create_dir_all(&pfx)?;
+                match write_dxvk_conf(Path::new(&pfx), &instance.profname, &conf) {
+                    Ok(path) => {
+                        cmd.env("DXVK_CONFIG_FILE", &path);
+                    }
+                    Err(err) => log_warn(format!(
+                        "Instance {}: couldn't write dxvk.conf: {err}",
+                        instance.profname
+                    )),
+                }
+            }
+        }
+    }
+    // Author mode turns Proton and DXVK logging all the way up, into the
+    // session's log folder.
+    if win && logging::author_mode() {
+        if let Some(dir) = logging::current_session_dir() {
+            cmd.env("PROTON_LOG", "1");
+            cmd.env("PROTON_LOG_DIR", &dir);
+            cmd.env("DXVK_LOG_LEVEL", "debug");
+            cmd.env("DXVK_LOG_PATH", &dir);
+            cmd.env("VKD3D_DEBUG", "trace");
+        }
+    }
+    // User overrides from the game page win over the defaults above.
+    for (key, value) in &overrides.env {
+        cmd.env(key, value);
+    }
+    // Variables set for just this instance on the Instances page come last.
+    if let Some(env) = cfg.instance_env(&game.persistent_id(), index) {
+        for (key, value) in env {
+            cmd.env(key, value);
+        }
+    }
+
+    let mut proton_prefix: Option<String> = None;
+    if win {
+        let pfx = instance_prefix(party, &instance.profname, index, cfg);
+        std::fs::create_dir_all(&pfx)?;
+        cmd.env("WINEPREFIX", &pfx);
+        cmd.env("STEAM_COMPAT_DATA_PATH", &pfx);
+        if let Some(env) = proton_env {
+            if env.root_path.is_some() && drained_prefixes.insert(pfx.clone()) {
+                drain_stale_proton_session(&pfx, env);
+            }
+        }
+        let proton_appdata = PathBuf::from(&pfx)
+            .join("drive_c")
+            .join("users")
+            .join("steamuser")
+            .join("AppData");
+        if purged_nemirtingas_prefixes.insert(pfx.clone()) {
+            // Only purge Nemirtingas Proton caches once per shared prefix so
+            // sibling instances cannot delete each other's command queues.
+            reset_proton_nemirtingas_session_state(&proton_appdata);
+        }
+        log_context.appdata_root = Some(proton_appdata.join("Roaming").join("NemirtingasEpicEmu"));
+        proton_prefix = Some(pfx);
+    }
+
+    cmd.arg("-W").arg(instance.width.to_string());
+    cmd.arg("-H").arg(instance.height.to_string());
+    if let Some(monitor) = &instance.monitor {
+        // Only the DRM backend honours this; nested sessions are moved to the
+        // display by the KWin script or the native layout.
+        cmd.arg("--prefer-output").arg(monitor);
+    }
+    if cfg.gamescope_sdl_backend {
+        cmd.arg("--backend=sdl");
+    }
+
+    if cfg.performance_gamescope_rt {
+        // Promote gamescope to its real-time scheduling mode to smooth frame pacing on the Deck.
+        cmd.arg("--rt");
+    }
+    if cfg.performance_limit_40fps {
+        // Clamp both active and unfocused windows to 40 FPS to keep dual sessions within the Deck's power budget.
+        cmd.arg(format!("--fps-limit={SESSION_FPS_LIMIT}"));
+        cmd.arg(format!("--secondary-no-focus-fps-limit={SESSION_FPS_LIMIT}"));
+    }
+    if logging::author_mode() {
+        // Logging only; neither changes how gamescope composites.
+        cmd.args(["--debug-layers", "--debug-focus"]);
+    }
+    if let HandlerRef(h) = game {
+        if let Some(upscaler) = h.gamescope_upscaler {
+            cmd.args(upscaler.args());
+        }
+        cmd.args(&h.gamescope_args);
+    }
+
+    if cfg.kbm_support {
+        let mut has_keyboard = false;
+        let mut has_mouse = false;
+        let mut kbms: Vec<String> = Vec::new();
+        for d in &instance.devices {
+            match input_devices[*d].device_type {
+                DeviceType::Keyboard => {
+                    has_keyboard = true;
+                    kbms.push(input_devices[*d].path.clone());
+                }
+                DeviceType::Mouse => {
+                    has_mouse = true;
+                    kbms.push(input_devices[*d].path.clone());
+                }
+                _ => {}
+            }
+        }
+        if has_keyboard {
+            cmd.arg("--backend-disable-keyboard");
+        }
+        if has_mouse {
+            cmd.arg("--backend-disable-mouse");
+        }
+        if !kbms.is_empty() {
+            cmd.arg("--libinput-hold-dev");
+            cmd.arg(kbms.join(","));
+        }
+    }
+
+    cmd.arg("--");
+    if use_bwrap {
+        cmd.arg("bwrap");
+        cmd.arg("--die-with-parent");
+        cmd.arg("--dev-bind").arg("/").arg("/");
+        cmd.arg("--bind").arg("/tmp").arg("/tmp");
+        if let Ok(runtime_dir) = std::env::var("XDG_RUNTIME_DIR") {
+            cmd.arg("--bind").arg(&runtime_dir).arg(&runtime_dir);
+        }
+
+        // Pads unplugged since launch have no node left to cover, and bwrap
+        // refuses to bind over a missing one when an instance restarts.
+        let mut hide = |path: &str| {
+            if Path::new(path).exists() {
+                cmd.args(["--bind", "/dev/null", path]);
+            }
+        };
+        for (d, dev) in input_devices.iter().enumerate() {
+            if !dev.enabled
+                || (!instance.devices.contains(&d) && dev.device_type == DeviceType::Gamepad)
+            {
+                hide(&dev.path);
+            }
+        }
+        // Motion sensors and touchpads only go to the instance that owns their
+        // pad, and only when it forwards them; otherwise every instance would
+        // feel every player's gyro.
+        for (d, dev) in input_devices.iter().enumerate() {
+            if dev.device_type != DeviceType::Gamepad {
+                continue;
+            }
+            let forward =
+                dev.enabled && instance.motion_passthrough && instance.devices.contains(&d);
+            for companion in find_pad_companions(&dev.path) {
+                if forward {
+                    log_info(format!("Instance {}: forwarding {companion}", instance.profname));
+                } else {
+                    hide(&companion);
+                }
+            }
+        }
+        // Virtual pads created by the proxy belong to exactly one instance, and
+        // the grabbed physical pads behind them are hidden from every instance.
+        for (owner, proxy) in pad_proxies {
+            if *owner != index {
+                hide(&proxy.virtual_path);
+            }
+            for physical in std::iter::once(&proxy.physical_path).chain(&proxy.copilot_paths) {
+                hide(physical);
+            }
+        }
+
+        if let HandlerRef(h) = game {
+            let path_prof = format!("{party}/profiles/{}", instance.profname);
+            let path_save = format!("{path_prof}/saves/{}", h.uid);
+            if !h.path_goldberg.is_empty() {
+                let src = format!("{path_prof}/steam");
+                let dst = format!("{instance_gamedir}/{}/goldbergsave", h.path_goldberg);
+                cmd.args(["--bind", src.as_str(), dst.as_str()]);
+            }
+            for (src, dest) in &nemirtingas_binds {
+                cmd.arg("--bind").arg(src).arg(dest);
+            }
+            if win {
+                let Some(prefix_value) = &proton_prefix else {
+                    return Err("Missing Proton prefix for Windows handler".into());
+                };
+                let path_windata = format!("{prefix_value}/drive_c/users/steamuser");
+                if h.win_unique_appdata {
+                    let src = format!("{path_save}/_AppData");
+                    let dst = format!("{path_windata}/AppData");
+                    cmd.args(["--bind", src.as_str(), dst.as_str()]);
+                }
+                if h.win_unique_documents {
+                    let src = format!("{path_save}/_Documents");
+                    let dst = format!("{path_windata}/Documents");
+                    cmd.args(["--bind", src.as_str(), dst.as_str()]);
+                }
+            } else {
+                if h.linux_unique_localshare {
+                    let src = format!("{path_save}/_share");
+                    cmd.args(["--bind", src.as_str(), localshare]);
+                }
+                if h.linux_unique_config {
+                    let src = format!("{path_save}/_config");
+                    let dst = format!("{home}/.config");
+                    cmd.args(["--bind", src.as_str(), dst.as_str()]);
+                }
+            }
+            for subdir in &h.game_unique_paths {
+                let src = format!("{path_save}/{subdir}");
+                let dst = format!("{instance_gamedir}/{subdir}");
+                cmd.args(["--bind", src.as_str(), dst.as_str()]);
+            }
+            for (src, dest) in &resolution_binds {
+                cmd.arg("--bind").arg(src).arg(dest);
+            }
+        }
+    }
+
+    if !platform.runtime.is_empty() {
+        cmd.arg(&platform.runtime);
+    }
+
+    let exec_path = PathBuf::from(&instance_gamedir).join(&platform.exec);
+    let exec_arg = if win {
+        exec_path
+            .canonicalize()
+            .unwrap_or_else(|_| exec_path.clone())
+    } else {
+        exec_path.clone()
+    };
+    cmd.arg(exec_arg.to_string_lossy().to_string());
+
+    let expand = |arg: &str| match arg {
+        "$GAMEDIR" => instance_gamedir.clone(),
+        "$PROFILE" => instance.profname.clone(),
+        "$WIDTH" => instance.width.to_string(),
+        "$HEIGHT" => instance.height.to_string(),
+        "$WIDTHXHEIGHT" => format!("{}x{}", instance.width, instance.height),
+        _ => arg.to_string(),
+    };
+    let mut args: Vec<String> = match game {
+        HandlerRef(_) if overrides.replace_args => Vec::new(),
+        HandlerRef(h) => h.args.iter().map(|arg| expand(arg)).collect(),
+        ExecRef(e) => e.args.split_whitespace().map(|s| s.to_string()).collect(),
+    };
+    args.extend(overrides.args.split_whitespace().map(expand));
+    for a in args {
+        cmd.arg(a);
+    }
+
+    // Handlers that opt into multi-seat mode run each slot under its assigned
+    // local account; slots without an account stay on the current user.
+    let seat_user = match game {
+        HandlerRef(h) if h.separate_users => seat_user_for_slot(&cfg.multiseat_users, index),
+        _ => None,
+    };
+    let mut seat_command = None;
+    if let Some(user) = &seat_user {
+        log_info(format!("Instance {}: running as local user {user}", instance.profname));
+        share_session_sockets(user);
+        let mut shared = vec![PathBuf::from(gamedir)];
+        if let HandlerRef(h) = game {
+            if let Some(Ok(root)) = known_rootpath_handler(h) {
+                shared.push(PathBuf::from(root));
+            }
+        }
+        share_instance_paths(&cmd, user, &shared)?;
+        let wrapped = wrap_command_for_user(&cmd, user)?;
+        seat_command = Some(std::mem::replace(&mut cmd, wrapped));
+    }
+
+    cmd.stdout(Stdio::piped());
+    cmd.stderr(Stdio::piped());
+
+    let command_line = describe_command(&cmd);
+    if logging::author_mode() {
+        logging::log_debug(format!("Instance {}: {command_line}", instance.profname));
+        // Only the variables set above; the inherited environment can hold
+        // tokens and has no place in a log that gets shared.
+        let env: BTreeMap<String, String> = cmd
+            .get_envs()
+            .map(|(key, value)| {
+                let value = value.map(|value| value.to_string_lossy().to_string());
+                (key.to_string_lossy().to_string(), value.unwrap_or_default())
+            })
+            .collect();
+        logging::log_instance_env(
+            &instance.profname,
+            env.iter().map(|(key, value)| (key.as_str(), value.as_str())),
+        );
+    }
+    let mut child = cmd.spawn()?;
+    // The wrapper only relays; priorities, pausing and windows belong to the game.
+    let seat_pid = match (&seat_user, &seat_command) {
+        (Some(user), Some(inner)) => {
+            let pid = find_seat_process(inner, user, &mut child, SEAT_PROCESS_TIMEOUT);
+            if pid.is_none() {
+                log_warn(format!(
+                    "Couldn't find the game process of {} running as {user}; CPU pinning, \
+                     pausing and window tracking act on run0/machinectl instead.",
+                    instance.profname
+                ));
+            }
+            pid
+        }
+        _ => None,
+    };
+
+    Ok(SpawnOutcome {
+        child,
+        seat_pid,
+        log_context,
+        proton_prefix,
+        command_line,
+    })
+}
+
+/// Tracks the runtime state of a launched instance so crashes can trigger targeted
+/// restarts without disturbing other players.
+struct RuntimeInstance {
+    index: usize,
+    profile_name: String,
+    instance: Instance,
+    child: Option<Child>,
+    /// The spawned process, which stops are sent to.
+    last_pid: Option<u32>,
+    /// The game's own process when `last_pid` is the run0/machinectl wrapper
+    /// of an instance running as another account.
+    seat_pid: Option<u32>,
+    log_context: NemirtingasLogContext,
+    proton_prefix: Option<String>,
+    finished: bool,
+    /// Profile (and its lock) waiting to take over the slot once it stops.
+    pending_swap: Option<(String, ProfileLock)>,
+    /// When a graceful stop escalates from SIGTERM to SIGKILL.
+    stop_deadline: Option<Instant>,
+    /// Set when the instance crashed and wasn't restarted.
+    crashed: bool,
+    /// Respawn the slot on the next poll; set by the restart hotkey.
+    restart_pending: bool,
+    /// Recent output, read to explain a crash in the restart prompt.
+    output_tail: OutputTail,
+    /// Threads filling `output_tail` from the current process's pipes.
+    output_readers: Vec<thread::JoinHandle<()>>,
+    /// When the current process was started.
+    started_at: Instant,
+    /// When its window first showed up, see [`track_instance_windows`].
+    window_at: Option<Instant>,
+    /// Live view of the emulator logs, see [`tail_nemirtingas_logs`].
+    nemirtingas_tail: NemirtingasTail,
+}
+
+impl RuntimeInstance {
+    /// Process the game runs in, for priorities, pausing and window lookups.
+    fn game_pid(&self) -> Option<u32> {
+        self.seat_pid.or(self.last_pid)
+    }
+}
+
+/// How long an instance run as another account has to show up, including
+/// the time the user takes to authenticate.
+const SEAT_PROCESS_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// How long an instance has to keep running after its window appears to be
+/// shown as up rather than loading.
+const BOOT_STABLE_AFTER: Duration = Duration::from_secs(15);
+
+/// Notes when the window of each starting instance appears. Without a
+/// display to ask, instances count as shown as soon as they run; when asking
+/// fails, nothing is marked and the next tick tries again.
+fn track_instance_windows(runtime_instances: &mut [RuntimeInstance], probe: Option<&WindowProbe>) {
+    let waiting = |state: &RuntimeInstance| state.child.is_some() && state.window_at.is_none();
+    if !runtime_instances.iter().any(waiting) {
+        return;
+    }
+    let window_pids = match probe.map(|probe| probe.window_pids()) {
+        Some(Ok(pids)) => Some(pids),
+        Some(Err(_)) => return,
+        None => None,
+    };
+    for state in runtime_instances.iter_mut().filter(|state| waiting(state)) {
+        let shown = match (probe, &window_pids, state.game_pid()) {
+            (Some(probe), Some(pids), Some(pid)) => {
+                probe.game_window_shown(&process_tree(pid), pids).unwrap_or(false)
+            }
+            _ => true,
+        };
+        if shown {
+            state.window_at = Some(Instant::now());
+        }
+    }
+}
+
+/// Last lines an instance printed, shared with its output readers.
+type OutputTail = Arc<Mutex<VecDeque<String>>>;
+
+/// Lines kept per instance; Wine reports missing DLLs shortly before exiting.
+const OUTPUT_TAIL_LINES: usize = 200;
+
+/// How long a crash prompt waits for the output readers to reach the end of
+/// the pipes. Processes the instance left behind can hold them open.
+const OUTPUT_DRAIN_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// Waits for the output readers of an exited instance, so its last lines are
+/// in the tail. Readers still blocked after [`OUTPUT_DRAIN_TIMEOUT`] are left
+/// running.
+fn drain_output_readers(readers: &mut Vec<thread::JoinHandle<()>>) {
+    let deadline = Instant::now() + OUTPUT_DRAIN_TIMEOUT;
+    while readers.iter().any(|reader| !reader.is_finished()) && Instant::now() < deadline {
+        thread::sleep(Duration::from_millis(20));
+    }
+    for reader in readers.drain(..).filter(|reader| reader.is_finished()) {
+        let _ = reader.join();
+    }
+}
+
+/// Restart prompt for a crashed instance, with the likely cause and fix when
+/// its output matches a known failure.
+fn crash_prompt(profile: &str, status: ExitStatus, tail: &OutputTail) -> String {
+    let exit = match status.code() {
+        Some(code) => format!("exit code {code}"),
+        None => status.to_string(),
+    };
+    let tail = tail.lock().unwrap();
+    let hint = classify_failure(tail.iter().map(String::as_str));
+    match hint {
+        Some(hint) => format!(
+            "Profile {profile} closed unexpectedly ({exit}).\n\n{}\nSuggested fix: {}\n\nRestart it in the reserved slot?",
+            hint.cause, hint.fix
+        ),
+        None => format!(
+            "Profile {profile} closed unexpectedly ({exit}). Restart it in the reserved slot?"
+        ),
+    }
+}
+
+/// Nice value instances are promoted to at launch.
+const INSTANCE_NICE: i32 = -5;
+
+/// Frame limit of the 40 FPS performance option and the FPS hotkey.
+const SESSION_FPS_LIMIT: u32 = 40;
+
+/// How long every instance has to stay up for a launch to count as good.
+const LAUNCH_CONFIRM_AFTER: Duration = Duration::from_secs(60);
+
+/// Appends this launch to the game's history so a failing settings change can
+/// be rolled back from the game page.
+fn record_launch_outcome(
+    game_id: &str,
+    succeeded: bool,
+    cfg: &PartyConfig,
+    overrides: &LaunchOverrides,
+    commands: &[String],
+) {
+    let settings = launch_settings_snapshot(&serde_json::to_value(cfg).unwrap_or_default());
+    // Links the entry to the session's logs for the history page.
+    let log_session = logging::current_session_dir()
+        .and_then(|dir| Some(dir.file_name()?.to_string_lossy().to_string()))
+        .unwrap_or_default();
+    let record = LaunchRecord::new(
+        succeeded,
+        settings,
+        overrides.clone(),
+        commands.to_vec(),
+        log_session,
+    );
+    if let Err(err) = record_launch(game_id, record) {
+        log_warn(format!("Couldn't record launch history: {err}"));
+    }
+}
+
+/// How long an instance gets to exit after SIGTERM before it is killed.
+const INSTANCE_STOP_GRACE: Duration = Duration::from_secs(10);
+
+/// Publishes the current slot states to the session panel.
+fn publish_session(
+    session: &SessionControl,
+    runtime_instances: &[RuntimeInstance],
+    devices: &[DeviceInfo],
+    disconnected: &[String],
+) {
+    session.publish(
+        runtime_instances
+            .iter()
+            .map(|state| {
+                let devices: Vec<String> = state
+                    .instance
+                    .devices
+                    .iter()
+                    .filter_map(|d| devices.get(*d).map(|dev| dev.path.clone()))
+                    .collect();
+                SessionSlot {
+                    slot: state.index,
+                    profile: state.profile_name.clone(),
+                    pid: state.game_pid(),
+                    running: state.child.is_some(),
+                    swapping: state.pending_swap.is_some(),
+                    disconnected: devices
+                        .iter()
+                        .filter(|path| disconnected.contains(path))
+                        .cloned()
+                        .collect(),
+                    devices,
+                    cores: state.game_pid().map(process_affinity).unwrap_or_default(),
+                    nice: state.game_pid().and_then(process_niceness),
+                    boot: match state.window_at {
+                        Some(shown) if shown.elapsed() >= BOOT_STABLE_AFTER => BootStage::Stable,
+                        Some(_) => BootStage::WindowShown,
+                        None => BootStage::Spawned,
+                    },
+                    uptime_secs: state.started_at.elapsed().as_secs(),
+                }
+            })
+            .collect(),
+    );
+}
+
+/// Reacts to a pad of the session leaving or coming back: proxied pads are
+/// rebound to their new node, and players hear about it either way.
+fn handle_hotplug_event(
+    event: HotplugEvent,
+    runtime_instances: &[RuntimeInstance],
+    session_devices: &mut [DeviceInfo],
+    pad_proxies: &mut [(usize, PadProxy)],
+) {
+    let profile_of = |slot: usize| {
+        runtime_instances
+            .iter()
+            .find(|state| state.index == slot)
+            .map(|state| state.profile_name.clone())
+            .unwrap_or_default()
+    };
+    match event {
+        HotplugEvent::Disconnected { slot, path } => {
+            let profile = profile_of(slot);
+            log_warn(format!("Controller {path} of player {} disconnected.", slot + 1));
+            logging::log_event(SessionEventKind::Disconnect, Some(slot), &profile, &path);
+            notify_desktop(
+                "Controller disconnected",
+                &format!(
+                    "Player {} ({profile}) lost a controller. Reconnect it to keep playing.",
+                    slot + 1
+                ),
+            );
+        }
+        HotplugEvent::Reconnected {
+            slot,
+            old_path,
+            new_path,
+        } => {
+            let profile = profile_of(slot);
+            for dev in session_devices.iter_mut().filter(|dev| dev.path == old_path) {
+                dev.path = new_path.clone();
+            }
+            let proxy = pad_proxies
+                .iter_mut()
+                .find(|(owner, proxy)| *owner == slot && proxy.reads(&old_path));
+            let message = match proxy {
+                Some((_, proxy)) => {
+                    proxy.rebind(&old_path, &new_path);
+                    log_info(format!(
+                        "Controller of player {} is back as {new_path}; rebound.",
+                        slot + 1
+                    ));
+                    format!("Player {} ({profile}) is back in control.", slot + 1)
+                }
+                None => {
+                    // Without a proxy the game has to find the new node itself.
+                    log_warn(format!(
+                        "Controller of player {} is back as {new_path} but isn't proxied.",
+                        slot + 1
+                    ));
+                    format!(
+                        "Player {} ({profile}) reconnected, but the game may not see the controller until it restarts.",
+                        slot + 1
+                    )
+                }
+            };
+            logging::log_event(SessionEventKind::Reconnect, Some(slot), &profile, &new_path);
+            notify_desktop("Controller reconnected", &message);
+        }
+    }
+}
+
+/// Spawns the uinput proxies one instance's gamepads need. With co-pilot
+/// merging, every extra pad of the instance is folded into its first one.
+/// Pads without calibration, gyro, assists, remaps or co-pilots are left alone,
+/// unless hotplug rebinding wants every pad behind a proxy. Safe mode hands
+/// every pad to the game directly.
+fn spawn_instance_pad_proxies(
+    index: usize,
+    instance: &Instance,
+    input_devices: &[DeviceInfo],
+    cfg: &PartyConfig,
+    activity: &InputActivity,
+) -> Vec<(usize, PadProxy)> {
+    if cfg.safe_mode {
+        return Vec::new();
+    }
+    let mut proxies = Vec::new();
+    let profile_input = load_profile_input(&instance.profname);
+    let pads: Vec<&DeviceInfo> = instance
+        .devices
+        .iter()
+        .map(|d| &input_devices[*d])
+        .filter(|dev| dev.device_type == DeviceType::Gamepad)
+        .collect();
+    let merge_copilots = cfg.copilot_merge && pads.len() > 1;
+    let groups: Vec<(&DeviceInfo, &[&DeviceInfo])> = if merge_copilots {
+        vec![(pads[0], &pads[1..])]
+    } else {
+        pads.iter().map(|pad| (*pad, &[][..])).collect()
+    };
+
+    for (dev, copilots) in groups {
+        let calibration = load_calibration(&dev.identity);
+        let gyro = if profile_input.gyro.enabled {
+            match find_motion_sensor(&dev.path) {
+                Some(motion_path) => Some((profile_input.gyro.clone(), motion_path)),
+                None => {
+                    log_warn(format!(
+                        "{} has gyro aiming enabled but {} exposes no motion sensor",
+                        instance.profname, dev.path
+                    ));
+                    None
+                }
+            }
+        } else {
+            None
+        };
+        let assists = profile_input
+            .assists
+            .is_active()
+            .then(|| profile_input.assists.clone());
+        let remap = profile_input
+            .remap
+            .is_active()
+            .then(|| profile_input.remap.clone());
+        // A proxied pad can be rebound after a reconnect without the game noticing.
+        let needs_proxy = cfg.hotplug_rebind
+            || calibration.is_some()
+            || gyro.is_some()
+            || assists.is_some()
+            || remap.is_some()
+            || !copilots.is_empty();
+        if !needs_proxy {
+            continue;
+        }
+        let options = PadProxyOptions {
+            calibration,
+            gyro,
+            assists,
+            remap,
+            copilots: copilots
+                .iter()
+                .map(|copilot| (copilot.path.clone(), load_calibration(&copilot.identity)))
+                .collect(),
+            activity: Some(activity.clone()),
+        };
+        match spawn_pad_proxy(&dev.path, options) {
+            Ok(proxy) => proxies.push((index, proxy)),
+            Err(err) => log_warn(format!(
+                "Couldn't proxy pad {}; using it unmodified: {err}",
+                dev.path
+            )),
+        }
+    }
+    proxies
+}
+
+/// Watches the session's devices that no pad proxy grabbed for idle
+/// detection. Returns `None` while idle detection is off.
+fn watch_session_idle(
+    cfg: &PartyConfig,
+    runtime_instances: &[RuntimeInstance],
+    session_devices: &[DeviceInfo],
+    pad_proxies: &[(usize, PadProxy)],
+    activity: &InputActivity,
+) -> Option<IdleWatcher> {
+    if cfg.idle_timeout_minutes == 0 {
+        return None;
+    }
+    // Grabbed pads can't be read from here, but their proxies report input.
+    let proxied: Vec<&String> = pad_proxies
+        .iter()
+        .flat_map(|(_, proxy)| std::iter::once(&proxy.physical_path).chain(&proxy.copilot_paths))
+        .collect();
+    let mut paths: Vec<String> = runtime_instances
+        .iter()
+        .flat_map(|state| &state.instance.devices)
+        .filter_map(|&dev| session_devices.get(dev))
+        .map(|dev| dev.path.clone())
+        .filter(|path| !proxied.contains(&path))
+        .collect();
+    paths.sort();
+    paths.dedup();
+    Some(IdleWatcher::start(&paths, activity.clone()))
+}
+
+/// Watches each player's pads for the screenshot chord. Proxied pads are read
+/// through their virtual twin, since the proxy grabbed the physical one.
+/// Returns `None` while the chord is off or can't be parsed.
+fn watch_screenshot_chord(
+    cfg: &PartyConfig,
+    runtime_instances: &[RuntimeInstance],
+    session_devices: &[DeviceInfo],
+    pad_proxies: &[(usize, PadProxy)],
+    session: &SessionControl,
+) -> Option<ScreenshotChordListener> {
+    let chord = match parse_screenshot_chord(&cfg.screenshot_chord) {
+        Ok(chord) if chord.is_empty() => return None,
+        Ok(chord) => chord,
+        Err(err) => {
+            log_warn(format!("Screenshot chord is off: {err}"));
+            return None;
+        }
+    };
+    let slots: Vec<(usize, Vec<String>)> = runtime_instances
+        .iter()
+        .map(|state| {
+            let mut paths: Vec<String> = state
+                .instance
+                .devices
+                .iter()
+                .filter_map(|&dev| session_devices.get(dev))
+                .filter(|dev| dev.device_type == DeviceType::Gamepad)
+                .map(|dev| {
+                    let proxy = pad_proxies.iter().map(|(_, proxy)| proxy).find(|proxy| {
+                        proxy.physical_path == dev.path || proxy.copilot_paths.contains(&dev.path)
+                    });
+                    proxy.map_or_else(|| dev.path.clone(), |proxy| proxy.virtual_path.clone())
+                })
+                .collect();
+            paths.sort();
+            paths.dedup();
+            (state.index, paths)
+        })
+        .collect();
+    Some(ScreenshotChordListener::start(chord, &slots, session.clone()))
+}
+
+/// Pauses (SIGSTOP) or resumes (SIGCONT) every running instance of an idle
+/// session and dims or restores the screen, as configured.
+fn set_session_suspended(runtime_instances: &[RuntimeInstance], suspend: bool, cfg: &PartyConfig) {
+    if cfg.idle_pause_instances {
+        let signal = if suspend { Signal::SIGSTOP } else { Signal::SIGCONT };
+        for state in runtime_instances {
+            if let Some(pid) = state.game_pid().filter(|_| state.child.is_some()) {
+                let _ = kill(Pid::from_raw(-(pid as i32)), signal);
+            }
+        }
+    }
+    if cfg.idle_dim_display {
+        if let Err(err) = dim_display(suspend) {
+            log_warn(format!("Couldn't change the screen brightness: {err}"));
+        }
+    }
+}
+
+/// KWin scripts loaded for one session by [`start_kwin_layout`].
+struct KwinLayout {
+    script: KwinScript,
+    /// Name of the player border overlay, when it loaded.
+    borders: Option<String>,
+}
+
+/// Loads the KWin tiling script generated for the session's layout, plus the
+/// player border overlay when enabled. Slot colours follow `profile_names`;
+/// `pinned` holds the player rectangles when instances are pinned to displays.
+/// Both scripts are named after the session and only handle the windows of
+/// `owned_pids`, the session's instance PIDs.
+fn start_kwin_layout(
+    profile_names: &[String],
+    cfg: &PartyConfig,
+    pinned: Option<&[Rect]>,
+    session_id: u64,
+    owned_pids: &[u32],
+) -> Result<KwinLayout, Box<dyn std::error::Error>> {
+    let name = format!("splitscreen_{session_id}");
+    let script_path = write_kwin_script(profile_names.len(), cfg, pinned, &name, owned_pids)?;
+    let script = kwin_dbus_start_script(script_path, &name)?;
+
+    let mut borders = None;
+    if cfg.kwin_player_borders {
+        // Borders are cosmetic, so a compositor that rejects the overlay
+        // only produces a warning instead of aborting the launch.
+        let colors: Vec<String> = profile_names
+            .iter()
+            .enumerate()
+            .map(|(slot, profile)| Profile::load(profile).color(slot))
+            .collect();
+        let border_name = format!("splitscreen_borders_{session_id}");
+        match kwin_dbus_start_border_script(&colors, 4, &border_name, owned_pids) {
+            Ok(()) => borders = Some(border_name),
+            Err(err) => log_warn(format!("Failed to start KWin player borders: {err}")),
+        }
+    }
+    Ok(KwinLayout { script, borders })
+}
+
+/// Swaps the session's KWin scripts for fresh ones that know every instance
+/// PID in `child_pids`. Instances are spawned before their windows appear, so
+/// reloading after each spawn tiles every window of the session.
+fn reload_kwin_layout(
+    layout: &mut Option<KwinLayout>,
+    profile_names: &[String],
+    cfg: &PartyConfig,
+    pinned: Option<&[Rect]>,
+    session_id: u64,
+    child_pids: &Mutex<Vec<u32>>,
+) {
+    if let Some(old) = layout.take() {
+        if let Err(err) = stop_kwin_layout(old) {
+            log_warn(format!("Failed to unload KWin layout: {err}"));
+        }
+    }
+    let owned_pids = child_pids.lock().map(|pids| pids.clone()).unwrap_or_default();
+    match start_kwin_layout(profile_names, cfg, pinned, session_id, &owned_pids) {
+        Ok(started) => *layout = Some(started),
+        Err(err) => log_warn(format!("Failed to load KWin layout: {err}")),
+    }
+}
+
+/// Unloads everything [`start_kwin_layout`] loaded.
+fn stop_kwin_layout(layout: KwinLayout) -> Result<(), Box<dyn std::error::Error>> {
+    if let Some(borders) = &layout.borders {
+        if let Err(err) = kwin_dbus_unload_border_script(borders) {
+            log_warn(format!("Failed to unload KWin player borders: {err}"));
+        }
+    }
+    kwin_dbus_unload_script(layout.script)
+}
+
+/// PID of the instance running in `slot`, if it is running.
+fn running_slot_pid(runtime_instances: &[RuntimeInstance], slot: usize) -> Option<u32> {
+    runtime_instances
+        .iter()
+        .find(|state| state.index == slot && state.child.is_some())
+        .and_then(|state| state.game_pid())
+}
+
+/// Profiles playing in the session, including ones waiting to swap in.
+fn session_profiles(runtime_instances: &[RuntimeInstance]) -> Vec<String> {
+    runtime_instances
+        .iter()
+        .flat_map(|state| {
+            std::iter::once(state.profile_name.clone())
+                .chain(state.pending_swap.as_ref().map(|(name, _)| name.clone()))
+        })
+        .collect()
+}
+
+/// Prepares `profile` to take over a running or newly added slot: resolves a
+/// free guest name, locks the profile and creates its save data. `taken`
+/// lists the profiles already playing in the session.
+fn prepare_swap_profile(
+    profile: &str,
+    taken: &[String],
+    game: &Game,
+    game_id: &str,
+    goldberg_port: Option<u16>,
+) -> Result<(String, ProfileLock), Box<dyn std::error::Error>> {
+    let name = if profile == "Guest" {
+        (1..)
+            .map(|n| format!("Guest{n}"))
+            .find(|name| !taken.contains(name) && !ProfileLock::is_held(name))
+            .unwrap_or_default()
+    } else {
+        profile.to_string()
+    };
+    if taken.contains(&name) {
+        return Err(format!("{name} is already playing in this session").into());
+    }
+    if ProfileLock::is_held(&name) {
+        return Err(format!("{name} is already playing in another game").into());
+    }
+
+    let lock = ProfileLock::acquire(game_id, &name)?;
+    if let HandlerRef(h) = game {
+        create_profile(&name)?;
+        create_gamesave(&name, h)?;
+        if !h.path_goldberg.is_empty() {
+            synchronize_goldberg_profiles(&[name.clone()], game_id, goldberg_port)?;
+        }
+    }
+    Ok((name, lock))
+}
+
+/// Wraps an evdev device so the restart prompt can watch for the specific
+/// confirmation and cancellation buttons without reimplementing the full
+/// controller stack used by the main UI.
+struct ControllerPromptDevice {
+    path: String,
+    device: EvDevice,
+}
+
+impl ControllerPromptDevice {
+    /// Opens the referenced event node in non-blocking mode so we can poll for
+    /// X/Circle presses while the desktop dialog remains visible.
+    fn open(path: &str) -> Option<Self> {
+        match EvDevice::open(path) {
+            Ok(device) => {
+                if let Err(err) = device.set_nonblocking(true) {
+                    log_warn(format!(
+                        "Failed to enable non-blocking mode for controller {}: {}",
+                        path, err
+                    ));
+                }
+                Some(Self {
+                    path: path.to_string(),
+                    device,
+                })
+            }
+            Err(err) => {
+                log_warn(format!("Unable to open controller {} for restart prompt: {}", path, err));
+                None
+            }
+        }
+    }
+
+    /// Polls pending input events and translates X/Circle presses into restart
+    /// or exit decisions while ignoring unrelated traffic.
+    fn poll_choice(&mut self) -> Result<Option<bool>, std::io::Error> {
+        match self.device.fetch_events() {
+            Ok(events) => {
+                for event in events {
+                    match event.destructure() {
+                        EventSummary::Key(_, KeyCode::BTN_SOUTH, 1) => {
+                            return Ok(Some(true));
+                        }
+                        EventSummary::Key(_, KeyCode::BTN_EAST, 1) => {
+                            return Ok(Some(false));
+                        }
+                        _ => {}
+                    }
+                }
+                Ok(None)
+            }
+            Err(err) if err.kind() == ErrorKind::WouldBlock => Ok(None),
+            Err(err) => Err(err),
+        }
+    }
+}
+
+/// Represents the desktop dialog used to surface the restart question so it
+/// can be cancelled immediately when the assigned controller makes a choice.
+enum DesktopPrompt {
+    KDialog(Child),
+    Zenity(Child),
+}
+
+impl DesktopPrompt {
+    /// Attempts to spawn a lightweight desktop dialog using the available
+    /// system helper (preferring KDE's kdialog and falling back to Zenity).
+    fn spawn(title: &str, contents: &str) -> Option<Self> {
+        if let Ok(child) = Command::new("kdialog")
+            .arg("--title")
+            .arg(title)
+            .arg("--yesno")
+            .arg(contents)
+            .stdin(Stdio::null())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .spawn()
+        {
+            return Some(Self::KDialog(child));
+        }
+
+        if let Ok(child) = Command::new("zenity")
+            .arg("--question")
+            .arg("--title")
+            .arg(title)
+            .arg("--text")
+            .arg(contents)
+            .stdin(Stdio::null())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .spawn()
+        {
+            return Some(Self::Zenity(child));
+        }
+
+        None
+    }
+
+    /// Checks whether the dialog already produced a response so the caller can
+    /// mirror mouse or keyboard selections immediately.
+    fn try_poll(&mut self) -> Option<bool> {
+        match self {
+            DesktopPrompt::KDialog(child) | DesktopPrompt::Zenity(child) => {
+                match child.try_wait() {
+                    Ok(Some(status)) => Some(Self::interpret_status(status)),
+                    Ok(None) => None,
+                    Err(err) => {
+                        log_warn(format!("Restart dialog closed unexpectedly: {}", err));
+                        Some(false)
+                    }
+                }
+            }
+        }
+    }
+
+    /// Terminates the helper process if the controller already answered the prompt.
+    fn close(&mut self) {
+        match self {
+            DesktopPrompt::KDialog(child) | DesktopPrompt::Zenity(child) => {
+                if let Err(err) = child.kill() {
+                    if err.kind() != ErrorKind::InvalidInput {
+                        log_warn(format!("Failed to close restart dialog helper: {}", err));
+                    }
+                }
+                let _ = child.wait();
+            }
+        }
+    }
+
+    /// Normalizes exit codes across the supported helpers into a simple boolean
+    /// choice where `true` indicates a restart request.
+    fn interpret_status(status: ExitStatus) -> bool {
+        status.success()
+    }
+}
+
+/// Presents the restart prompt to the player assigned to the crashed instance
+/// and watches the relevant controller for Cross/Circle decisions while keeping
+/// the desktop modal available for keyboard or mouse input.
+fn prompt_instance_restart(
+    runtime_state: &RuntimeInstance,
+    input_devices: &[DeviceInfo],
+    title: &str,
+    message: &str,
+) -> bool {
+    let mut seen: HashSet<String> = HashSet::new();
+    let mut controllers: Vec<ControllerPromptDevice> = Vec::new();
+    for device_index in &runtime_state.instance.devices {
+        if let Some(info) = input_devices.get(*device_index) {
+            if seen.insert(info.path.clone()) {
+                if let Some(device) = ControllerPromptDevice::open(&info.path) {
+                    controllers.push(device);
+                }
+            }
+        }
+    }
+
+    if controllers.is_empty() {
+        return yesno(title, message);
+    }
+
+    // Frontends without dialogs, like the terminal UI, only take the controller's answer.
+    let mut dialog = prompts_available().then(|| DesktopPrompt::spawn(title, message)).flatten();
+    if dialog.is_none() {
+        log_warn("Unable to present desktop restart dialog; waiting for controller input.");
+    }
+
+    let mut removals: Vec<usize> = Vec::new();
+
+    loop {
+        removals.clear();
+        for (index, device) in controllers.iter_mut().enumerate() {
+            match device.poll_choice() {
+                Ok(Some(choice)) => {
+                    if let Some(mut prompt) = dialog.take() {
+                        prompt.close();
+                    }
+                    return choice;
+                }
+                Ok(None) => {}
+                Err(err) => {
+                    log_warn(format!(
+                        "Lost controller {} during restart prompt: {}",
+                        device.path, err
+                    ));
+                    removals.push(index);
+                }
+            }
+        }
+
+        for index in removals.iter().rev() {
+            controllers.remove(*index);
+        }
+
+        if let Some(prompt) = dialog.as_mut() {
+            if let Some(choice) = prompt.try_poll() {
+                return choice;
+            }
+        } else if controllers.is_empty() {
+            break;
+        }
+
+        thread::sleep(Duration::from_millis(50));
+    }
+
+    yesno(title, message)
+}
+
+/// Removes a PID from the shared cleanup list once the corresponding process exits so the
+/// Ctrl+C handler stops signalling stale process groups.
+fn unregister_child_pid(child_pids: &Arc<Mutex<Vec<u32>>>, pid: u32) {
+    if let Ok(mut pids) = child_pids.lock() {
+        if let Some(pos) = pids.iter().position(|existing| *existing == pid) {
+            pids.swap_remove(pos);
+        }
+    }
+}
+
+/// Raises the niceness of a spawned instance slightly so CPU scheduling stays balanced when
+/// multiple Gamescope sessions render simultaneously.
+fn promote_instance_priority(pid: u32, index: usize, total_instances: usize) {
+    let result =
+        unsafe { libc::setpriority(libc::PRIO_PROCESS, pid as libc::id_t, INSTANCE_NICE) };
+    if result == 0 {
+        log_info(format!(
+            "Elevated scheduling priority for instance {}/{} (PID {}).",
+            index + 1,
+            total_instances,
+            pid
+        ));
+    } else {
+        let err = std::io::Error::last_os_error();
+        log_warn(format!(
+            "Unable to boost priority for instance {} (PID {}): {}",
+            index + 1,
+            pid,
+            err
+        ));
+    }
+}
+
+/// Recursively removes stale Nemirtingas emulator artifacts within the provided
+/// cache root while optionally preserving the `Logs` directory so previous
+/// sessions remain debuggable.
+fn purge_nemirtingas_appdata(root: &Path, preserve_logs: bool) {
+    if !root.exists() {
+        return;
+    }
+
+    let logs_dir = root.join("Logs");
+    match fs::read_dir(root) {
+        Ok(entries) => {
+            for entry in entries.flatten() {
+                let path = entry.path();
+                if preserve_logs && path == logs_dir {
+                    continue;
+                }
+
+                let result = if entry.file_type().map(|ft| ft.is_dir()).unwrap_or(false) {
+                    fs::remove_dir_all(&path)
+                } else {
+                    fs::remove_file(&path)
+                };
+
+                if let Err(err) = result {
+                    log_warn(format!(
+                        "Failed to remove stale Nemirtingas appdata {}: {}",
+                        path.display(),
+                        err
+                    ));
+                }
+            }
+        }
+        Err(err) => log_warn(format!(
+            "Failed to enumerate Nemirtingas appdata {}: {}",
+            root.display(),
+            err
+        )),
+    }
+}
+
+/// Ensures the Nemirtingas command queue folder exists after cache purges so
+/// the emulator can submit fresh EOS commands without tripping startup
+/// assertions.
+fn ensure_nemirtingas_command_cache(root: &Path) {
+    let commands_dir = root.join("Commands");
+    if let Err(err) = fs::create_dir_all(&commands_dir) {
+        log_warn(format!(
+            "Failed to recreate Nemirtingas command directory {}: {}",
+            commands_dir.display(),
+            err
+        ));
+    }
+}
+
+/// Purges the per-profile Nemirtingas cache tree in `nepice_settings` before a
+/// launch so Split Happens never replays partially submitted command buffers.
+fn reset_nemirtingas_session_state(nepice_dir: &Path) {
+    let appdata = nepice_dir.join("appdata");
+    purge_nemirtingas_appdata(&appdata, true);
+    ensure_nemirtingas_command_cache(&appdata);
+}
+
+/// Clears Nemirtingas caches inside the Proton prefix (`AppData/Local` and
+/// `AppData/Roaming`) to eliminate stale Windows-side EOS commands that would
+/// otherwise recreate the COMMAND_STATE_SUBMITTED assertion for players.
+fn reset_proton_nemirtingas_session_state(appdata_root: &Path) {
+    let roaming = appdata_root.join("Roaming").join("NemirtingasEpicEmu");
+    let local = appdata_root.join("Local").join("NemirtingasEpicEmu");
+    let local_low = appdata_root.join("LocalLow").join("NemirtingasEpicEmu");
+
+    purge_nemirtingas_appdata(&roaming, true);
+    purge_nemirtingas_appdata(&local, false);
+    purge_nemirtingas_appdata(&local_low, false);
+
+    ensure_nemirtingas_command_cache(&local);
+}
+
+/// Build, runtime wrapper and Proton build one instance runs with.
+struct InstanceRuntime {
+    win: bool,
+    exec: String,
+    /// umu-run for Proton, the Steam Linux Runtime entry point for native
+    /// handlers that ask for one, else empty.
+    runtime: String,
+    proton_env: Option<ProtonEnvironment>,
+}
+
+/// Picks the [`InstanceRuntime`] of each instance of a session: the
+/// handler's own build unless the instance switched to the other one.
+struct RuntimeResolver {
+    default_win: bool,
+    exec: String,
+    /// Executable of the other build; empty when the game has only one.
+    alt_exec: String,
+    native_runtime: String,
+    proton_env: Option<ProtonEnvironment>,
+}
+
+impl RuntimeResolver {
+    /// Whether `instance` runs the Windows build.
+    fn win_for(&self, instance: &Instance) -> bool {
+        let requested = match instance.platform {
+            InstancePlatform::Handler => return self.default_win,
+            InstancePlatform::Native => false,
+            InstancePlatform::Proton => true,
+        };
+        // Switching builds needs the handler to name the other executable.
+        match requested != self.default_win && self.alt_exec.is_empty() {
+            true => self.default_win,
+            false => requested,
+        }
+    }
+
+    fn for_instance(&self, instance: &Instance) -> InstanceRuntime {
+        let win = self.win_for(instance);
+        let exec = match win == self.default_win {
+            true => self.exec.clone(),
+            false => self.alt_exec.clone(),
+        };
+        InstanceRuntime {
+            win,
+            exec,
+            runtime: match win {
+                true => BIN_UMU_RUN.to_string_lossy().to_string(),
+                false => self.native_runtime.clone(),
+            },
+            proton_env: self.proton_env.clone().filter(|_| win),
+        }
+    }
+}
+
+/// Proton prefix of the instance in slot `index`, named by the profile's id
+/// and shared by every slot of the profile unless separate prefixes are on.
+pub fn instance_prefix(party: &str, profname: &str, index: usize, cfg: &PartyConfig) -> String {
+    let pfx = format!("{party}/pfx/{}", profile_prefix_key(profname));
+    match cfg.proton_separate_pfxs {
+        true => format!("{pfx}_{}", index + 1),
+        false => pfx,
+    }
+}
+
+/// Installs the handler's winetricks verbs into every instance prefix that
+/// lacks them, reporting each step as launch progress. A failed verb is only
+/// logged; it is retried on the next launch and the game may run without it.
+fn bootstrap_winetricks(
+    h: &Handler,
+    instances: &[Instance],
+    runtimes: &[InstanceRuntime],
+    party: &str,
+    cfg: &PartyConfig,
+    session: &SessionControl,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let mut prefixes: Vec<(String, String, &ProtonEnvironment)> = Vec::new();
+    for (index, instance) in instances.iter().enumerate() {
+        // Native instances have no prefix to prepare.
+        let Some(proton_env) = runtimes[index].proton_env.as_ref() else {
+            continue;
+        };
+        let pfx = instance_prefix(party, &instance.profname, index, cfg);
+        if !prefixes.iter().any(|(known, _, _)| *known == pfx) {
+            prefixes.push((pfx, instance.profname.clone(), proton_env));
+        }
+    }
+
+    for (pfx, profname, proton_env) in prefixes {
+        let missing = missing_winetricks_verbs(Path::new(&pfx), &h.winetricks);
+        for (done, verb) in missing.iter().enumerate() {
+            check_cancelled(session)?;
+            session.set_progress(Some(format!(
+                "Installing {verb} for {profname} ({}/{})",
+                done + 1,
+                missing.len()
+            )));
+            log_info(format!("Installing winetricks verb {verb} into {pfx}"));
+            // A long install keeps the launch from timing out while it runs.
+            let keep_going = || {
+                session.beat();
+                !session.is_cancelled()
+            };
+            if let Err(err) = install_winetricks_verb(Path::new(&pfx), proton_env, verb, keep_going)
+            {
+                if session.is_cancelled() {
+                    // wineserver runs detached and would keep the prefix busy.
+                    drain_stale_proton_session(&pfx, proton_env);
+                    return Err(err);
+                }
+                log_warn(format!("Couldn't install {verb} into {pfx}: {err}"));
+            }
+        }
+    }
+    session.set_progress(None);
+    Ok(())
+}
+
+/// Ensures the targeted Proton prefix is not held by lingering Wine processes
+/// by issuing a graceful shutdown and waiting for cleanup.
+fn drain_stale_proton_session(prefix: &str, proton_env: &ProtonEnvironment) {
+    let prefix_path = Path::new(prefix);
+    if !prefix_path.exists() {
+        return;
+    }
+
+    let actions = [("-k", "terminate"), ("-w", "wait for cleanup")];
+    for (flag, description) in actions {
+        let mut helper = Command::new(&*BIN_UMU_RUN);
+        helper.env("PROTON_VERB", "run");
+        helper.env("PROTONPATH", proton_env.env_value.clone());
+        helper.env("WINEPREFIX", prefix);
+        helper.env("STEAM_COMPAT_DATA_PATH", prefix);
+        helper.env("SDL_JOYSTICK_HIDAPI", "0");
+        helper.env("ENABLE_GAMESCOPE_WSI", "0");
+        helper.env("PROTON_DISABLE_HIDRAW", "1");
+        helper.arg("--");
+        helper.arg("wineserver");
+        helper.arg(flag);
+
+        match helper.status() {
+            Ok(status) => {
+                if !status.success() {
+                    log_warn(format!(
+                        "wineserver {flag} failed to {description} prefix {} (status: {status})",
+                        prefix_path.display(),
+                    ));
+                }
+            }
+            Err(err) => {
+                log_warn(format!(
+                    "Failed to run wineserver {flag} while preparing prefix {}: {}",
+                    prefix_path.display(),
+                    err
+                ));
+                break;
+            }
+        }
+    }
+}
+
+/// Distributes CPU cores across running instances while keeping the affinity sets
+/// as balanced as possible. The first few players (host included) receive a single
+/// extra logical core whenever the CPU count is not perfectly divisible so hosting
+/// retains a light advantage without starving other instances.
+fn balanced_instance_cores(instance_index: usize, total_instances: usize) -> Option<Vec<usize>> {
+    if total_instances <= 1 {
+        return None;
+    }
+
+    let Ok(cpu_count) = std::thread::available_parallelism() else {
+        log_warn(format!(
+            "Unable to query CPU core count for affinity; leaving instance {} unpinned.",
+            instance_index + 1
+        ));
+        return None;
+    };
+    let cpu_count = cpu_count.get();
+
+    if cpu_count == 0 {
+        log_warn(format!(
+            "Reported CPU core count was zero; skipping affinity for instance {}.",
+            instance_index + 1
+        ));
+        return None;
+    }
+
+    if cpu_count < total_instances {
+        log_warn(format!(
+            "Only {} CPU cores available for {} instances; skipping affinity to avoid starving players.",
+            cpu_count, total_instances
+        ));
+        return None;
+    }
+
+    let base = cpu_count / total_instances;
+    if base == 0 {
+        return None;
+    }
+    let remainder = cpu_count % total_instances;
+    let extra = if instance_index < remainder { 1 } else { 0 };
+    let target_width = base + extra;
+
+    if target_width == 0 {
+        log_warn(format!(
+            "Calculated empty CPU set for instance {}; affinity skipped.",
+            instance_index + 1
+        ));
+        return None;
+    }
+
+    // Assign logical cores in a round-robin pattern so each instance stays close in size
+    // while the first few players receive the leftover cores.
+    let mut assigned: Vec<usize> = Vec::with_capacity(target_width);
+    for core in (instance_index..cpu_count).step_by(total_instances) {
+        assigned.push(core);
+        if assigned.len() == target_width {
+            break;
+        }
+    }
+
+    if assigned.is_empty() {
+        log_warn(format!(
+            "No CPU cores mapped to instance {}; affinity skipped.",
+            instance_index + 1
+        ));
+        return None;
+    }
+
+    Some(assigned)
+}
+
+/// Restores the launch-time core split and priority on every running slot,
+/// including threads the games started since. Used after the player count
+/// changes and when the session panel asks for a rebalance.
+fn rebalance_session_cpus(runtime_instances: &[RuntimeInstance]) {
+    let total = runtime_instances.len();
+    let cpu_count = std::thread::available_parallelism().map_or(1, |n| n.get());
+    let all_cores: Vec<usize> = (0..cpu_count).collect();
+    for state in runtime_instances {
+        let Some(pid) = state.game_pid().filter(|_| state.child.is_some()) else {
+            continue;
+        };
+        let cores =
+            balanced_instance_cores(state.index, total).unwrap_or_else(|| all_cores.clone());
+        if let Err(err) = set_tree_affinity(pid, &cores) {
+            log_warn(format!("Couldn't rebalance slot {}: {err}", state.index + 1));
+        }
+        if let Err(err) = set_tree_niceness(pid, INSTANCE_NICE) {
+            log_warn(format!("Couldn't restore priority of slot {}: {err}", state.index + 1));
+        }
+    }
+    log_info(format!("Rebalanced CPU cores across {total} instances."));
+}
+
+/// Pins a freshly spawned instance to its share from [`balanced_instance_cores`].
+fn apply_instance_cpu_affinity(pid: u32, instance_index: usize, total_instances: usize) {
+    let Some(assigned) = balanced_instance_cores(instance_index, total_instances) else {
+        return;
+    };
+
+    // `CpuSet::new` zero-initializes an affinity mask for us on glibc-based
+    // targets, so there is no failure path to handle here while targeting the
+    // Steam Deck runtime.
+    let mut cpuset = CpuSet::new();
+
+    for &core in &assigned {
+        if let Err(err) = cpuset.set(core) {
+            log_warn(format!(
+                "Unable to add core {} to affinity set for instance {}: {}",
+                core,
+                instance_index + 1,
+                err
+            ));
+            return;
+        }
+    }
+
+    let target_pid = Pid::from_raw(pid as i32);
+    match sched_setaffinity(target_pid, &cpuset) {
+        Ok(_) => {
+            let core_list = assigned
+                .iter()
+                .map(|core| core.to_string())
+                .collect::<Vec<_>>()
+                .join(", ");
+            log_info(format!(
+                "Bound instance {}/{} (PID {}) to CPU cores [{}]",
+                instance_index + 1,
+                total_instances,
+                pid,
+                core_list
+            ));
+        }
+        Err(err) => {
+            log_warn(format!(
+                "Failed to set CPU affinity for instance {}: {}",
+                instance_index + 1,
+                err
+            ));
+        }
+    }
+}
+
+/// Streams child output on a background thread into the instance's session log,
+/// dropping or marking lines as the user's output rules say. The latest kept lines
+/// also go to `tail`. The returned thread ends once the pipe closes.
+fn forward_child_output<R>(
+    reader: R,
+    index: usize,
+    profile: &str,
+    tail: &OutputTail,
+    filter: &Arc<OutputFilter>,
+) -> thread::JoinHandle<()>
+where
+    R: Read + Send + 'static,
+{
+    let profile = profile.to_string();
+    let log_session = logging::current_session();
+    let tail = Arc::clone(tail);
+    let filter = Arc::clone(filter);
+    thread::spawn(move || {
+        logging::attach_session(log_session);
+        let reader = BufReader::new(reader);
+        for line in reader.lines() {
+            match line {
+                Ok(line) => {
+                    let action = filter.action(&line);
+                    if action == Some(OutputAction::Hide) {
+                        continue;
+                    }
+                    let highlight = action == Some(OutputAction::Highlight);
+                    log_instance(index, &profile, &line, highlight);
+                    let mut tail = tail.lock().unwrap();
+                    if tail.len() == OUTPUT_TAIL_LINES {
+                        tail.pop_front();
+                    }
+                    tail.push_back(line);
+                }
+                Err(err) => {
+                    log_warn(format!("Failed to read output of {profile}: {err}"));
+                    break;
+                }
+            }
+        }
+    })
+}
+
+/// Logs diagnostic information for handlers so users can verify their assets before launch.
+fn log_handler_resource_state(handler: &Handler, gamedir: &str) {
+    // Report the resolved executable path so the user can confirm the handler layout.
+    let exec_path = PathBuf::from(gamedir).join(&handler.exec);
+    log_info(format!("Handler {} uses executable {}", handler.uid, exec_path.display()));
+
+    if !handler.path_nemirtingas.is_empty() {
+        // Expose the resolved Nemirtingas config target to make missing path issues obvious.
+        let nemirtingas_target = PathBuf::from(gamedir).join(&handler.path_nemirtingas);
+        log_info(format!(
+            "Handler {} expects Nemirtingas config at {}",
+            handler.uid,
+            nemirtingas_target.display()
+        ));
+
+        let parent_rel = Path::new(&handler.path_nemirtingas).parent();
+        let Some(parent_rel) = parent_rel else {
+            log_warn(format!(
+                "Nemirtingas path for handler {} has no parent directory; check handler JSON.",
+                handler.uid
+            ));
+
+            return;
+        };
+
+        // Validate the directory next to the Nemirtingas config contains patched EOSSDK files.
+        let parent_path = PathBuf::from(gamedir).join(parent_rel);
+        if !parent_path.exists() {
+            log_warn(format!(
+                "Nemirtingas directory {} is missing. Ensure the handler copied patched EOSSDK files there.",
+                parent_path.display()
+            ));
+            return;
+        }
+
+        // Walk upward from the Nemirtingas config directory so we also catch EOSSDK files
+        // that sit next to the executable instead of inside the nepice_settings folder.
+        let gamedir_path = PathBuf::from(gamedir);
+        let mut eos_paths = Vec::new();
+        let mut scanned_dirs = Vec::new();
+        let mut search_dir = parent_path.clone();
+        while search_dir.starts_with(&gamedir_path) {
+            scanned_dirs.push(search_dir.clone());
+
+            match fs::read_dir(&search_dir) {
+                Ok(entries) => {
+                    for entry in entries.flatten() {
+                        if entry
+                            .file_type()
+                            .map(|file_type| file_type.is_file())
+                            .unwrap_or(false)
+                        {
+                            let name_lower = entry.file_name().to_string_lossy().to_lowercase();
+                            if name_lower.contains("eossdk") {
+                                eos_paths.push(entry.path());
+                            }
+                        }
+                    }
+                }
+                Err(err) => {
+                    log_warn(format!(
+                        "Failed to scan {} for EOSSDK files: {}. Verify directory permissions.",
+                        search_dir.display(),
+                        err
+                    ));
+                    return;
+                }
+            }
+
+            if !eos_paths.is_empty() {
+                break;
+            }
+
+            let Some(parent) = search_dir.parent() else {
+                break;
+            };
+            if parent == search_dir || !parent.starts_with(&gamedir_path) {
+                break;
+            }
+            search_dir = parent.to_path_buf();
+        }
+
+        if eos_paths.is_empty() {
+            let scanned_display = if scanned_dirs.is_empty() {
+                String::from("<none>")
+            } else {
+                scanned_dirs
+                    .iter()
+                    .map(|dir| dir.display().to_string())
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            };
+            log_warn(format!(
+                "No EOSSDK files were found near {} (searched: {}). Nemirtingas may fail to initialize.",
+                nemirtingas_target.display(),
+                scanned_display
+            ));
+        } else {
+            // List the discovered EOSSDK assets to help verify the patched binaries are available.
+            for path in eos_paths {
+                log_info(format!("Found EOS-related file for Nemirtingas: {}", path.display()));
+            }
+        }
+    }
+
+    if handler.path_goldberg.is_empty() {
+        return;
+    }
+
+    // Surface the resolved Goldberg override directory so the user can spot missing assets.
+    let goldberg_dir = PathBuf::from(gamedir).join(&handler.path_goldberg);
+    log_info(format!(
+        "Handler {} expects Goldberg assets at {}",
+        handler.uid,
+        goldberg_dir.display()
+    ));
+
+    if !goldberg_dir.exists() {
+        log_warn(format!(
+            "Goldberg directory {} is missing. Ensure the handler copied Goldberg files there.",
+            goldberg_dir.display()
+        ));
+        return;
+    }
+
+    // Validate the presence of the per-game steam_settings folder and critical config files.
+    let steam_settings = goldberg_dir.join("steam_settings");
+    if !steam_settings.exists() {
+        log_warn(format!(
+            "Goldberg path {} lacks a steam_settings directory. Multiplayer emulation will likely fail.",
+            goldberg_dir.display()
+        ));
+        return;
+    }
+
+    for (filename, description) in [
+        ("steam_appid.txt", "Steam App ID"),
+        ("configs.user.ini", "user configuration"),
+        ("steam_interfaces.txt", "interface list"),
+    ] {
+        let file_path = steam_settings.join(filename);
+        if !file_path.exists() {
+            log_warn(format!(
+                "steam_settings at {} is missing {} ({}).",
+                steam_settings.display(),
+                filename,
+                description
+            ));
+            continue;
+        }
+
+        // Emit light diagnostics so we can inspect mismatched App IDs at a glance.
+        if filename == "steam_appid.txt" {
+            match fs::read_to_string(&file_path) {
+                Ok(contents) => {
+                    let trimmed = contents.trim();
+                    if let Some(expected_appid) = &handler.steam_appid {
+                        if trimmed != expected_appid {
+                            log_warn(format!(
+                                "steam_appid.txt at {} contains {} but handler expects {}.",
+                                file_path.display(),
+                                trimmed,
+                                expected_appid
+                            ));
+                        }
+                    }
+                    log_info(format!(
+                        "Detected steam_appid.txt at {} with value {}",
+                        file_path.display(),
+                        trimmed
+                    ));
+                }
+                Err(err) => {
+                    log_warn(format!("Failed to read {}: {}", file_path.display(), err));
+                }
+            }
+        } else {
+            log_info(format!("Found Goldberg config file: {}", file_path.display()));
+        }
+    }
+}
+
+/// Applies `update` to the profile.json of every launched profile. Stats are
+/// informational, so write failures are only logged.
+fn update_profile_stats(profile_names: &[String], update: impl Fn(&mut Profile)) {
+    for name in profile_names {
+        let mut profile = Profile::load(name);
+        update(&mut profile);
+        if let Err(err) = profile.save() {
+            log_warn(format!("Failed to update stats of profile {name}: {err}"));
+        }
+    }
+}
+
+/// Stops launch preparation once the UI cancelled the launch.
+fn check_cancelled(session: &SessionControl) -> Result<(), Box<dyn std::error::Error>> {
+    if session.is_cancelled() {
+        return Err("Launch cancelled".into());
+    }
+    Ok(())
+}
+
+/// Undoes the session-wide setup of a launch cancelled while its instances
+/// were starting. Started instances are stopped first, so their profile
+/// locks aren't released while they still run.
+fn abandon_cancelled_launch(
+    runtime_instances: &mut [RuntimeInstance],
+    total: usize,
+    session: &SessionControl,
+    pad_proxies: Vec<(usize, PadProxy)>,
+    native_layout: Option<NativeLayout>,
+    kwin_layout: Option<KwinLayout>,
+) -> Box<dyn std::error::Error> {
+    let started = runtime_instances.len();
+    log_warn(format!("Launch cancelled after {started} of {total} instances started."));
+    stop_started_instances(runtime_instances);
+    drop(pad_proxies);
+    let manager = SessionManager::global();
+    manager.unregister(session.id());
+    drop(native_layout);
+    if let Some(layout) = kwin_layout {
+        if let Err(err) = stop_kwin_layout(layout) {
+            log_warn(format!("Failed to stop the KWin layout: {err}"));
+        }
+    }
+    // Guest folders are shared by name, see the end of `launch_game`.
+    if session.is_stop_requested() && !manager.any_running() {
+        if let Err(err) = remove_guest_profiles() {
+            log_warn(format!("Failed to remove guest profiles: {err}"));
+        }
+    }
+    "Launch cancelled".into()
+}
+
+/// Stops the instances of a launch cancelled while it was starting:
+/// SIGTERM to every process group, then SIGKILL for those still running after
+/// [`INSTANCE_STOP_GRACE`].
+fn stop_started_instances(runtime_instances: &mut [RuntimeInstance]) {
+    for state in runtime_instances.iter() {
+        if let (Some(_), Some(pid)) = (&state.child, state.last_pid) {
+            let _ = kill(Pid::from_raw(-(pid as i32)), Signal::SIGTERM);
+        }
+    }
+    let deadline = Instant::now() + INSTANCE_STOP_GRACE;
+    for state in runtime_instances.iter_mut() {
+        let Some(mut child) = state.child.take() else {
+            continue;
+        };
+        while matches!(child.try_wait(), Ok(None)) {
+            if Instant::now() >= deadline {
+                if let Some(pid) = state.last_pid {
+                    let _ = kill(Pid::from_raw(-(pid as i32)), Signal::SIGKILL);
+                }
+                let _ = child.wait();
+                break;
+            }
+            thread::sleep(Duration::from_millis(100));
+        }
+    }
+    log_info(format!("Stopped {} started instances.", runtime_instances.len()));
+}
+
+/// Plain-text summary of what launching `game` with `instances` would do,
+/// for handler authors to paste into bug reports: the handler, the settings
+/// and overrides that shape the command line, every player's profile,
+/// resolution and devices, and the command lines of the last launch.
+pub fn describe_launch_plan(
+    game: Option<&Game>,
+    instances: &[Instance],
+    input_devices: &[DeviceInfo],
+    cfg: &PartyConfig,
+) -> String {
+    let mut plan = format!("Split Happens {} launch plan\n", env!("CARGO_PKG_VERSION"));
+    let Some(game) = game else {
+        plan.push_str("No game selected.\n");
+        return plan;
+    };
+    let game_id = game.persistent_id();
+    plan.push_str(&format!("Game: {} ({game_id})\n", game.name()));
+    match game {
+        HandlerRef(h) => {
+            plan.push_str(&format!(
+                "Handler: {} {} by {}, {}\n",
+                h.uid,
+                h.version,
+                h.author,
+                if h.win { "Windows (Proton)" } else { "native" }
+            ));
+            plan.push_str(&format!("Executable: {} {}\n", h.exec, h.args.join(" ")));
+            if !h.alt_exec.is_empty() {
+                plan.push_str(&format!("Other build: {}\n", h.alt_exec));
+            }
+            if !h.runtime.is_empty() {
+                plan.push_str(&format!("Runtime: {}\n", h.runtime));
+            }
+            if !h.path_goldberg.is_empty() {
+                plan.push_str(&format!("Goldberg: {}\n", h.path_goldberg));
+            }
+            if !h.path_nemirtingas.is_empty() {
+                plan.push_str(&format!("Nemirtingas: {}\n", h.path_nemirtingas));
+            }
+            if !h.gamescope_args.is_empty() {
+                plan.push_str(&format!("Gamescope args: {}\n", h.gamescope_args.join(" ")));
+            }
+            let overrides = load_launch_overrides(&h.uid);
+            if !overrides.is_empty() {
+                let overrides = serde_json::to_string(&overrides).unwrap_or_default();
+                plan.push_str(&format!("Overrides: {overrides}\n"));
+            }
+        }
+        ExecRef(e) => {
+            plan.push_str(&format!("Executable: {} {}\n", e.path().display(), e.args));
+        }
+    }
+    let settings = launch_settings_snapshot(&serde_json::to_value(cfg).unwrap_or_default());
+    plan.push_str(&format!("Settings: {}\n", serde_json::Value::Object(settings)));
+
+    plan.push_str(&format!("Players: {}\n", instances.len()));
+    for (i, instance) in instances.iter().enumerate() {
+        let monitor = instance.monitor.as_deref().unwrap_or("shared");
+        let platform = match instance.platform {
+            InstancePlatform::Handler => String::new(),
+            other => format!(", {}", other.label()),
+        };
+        plan.push_str(&format!(
+            "  P{} {}: {}x{} on {monitor}{platform}\n",
+            i + 1,
+            instance.profname,
+            instance.width,
+            instance.height
+        ));
+        if let Some(env) = cfg.instance_env(&game_id, i).filter(|env| !env.is_empty()) {
+            let vars: Vec<String> =
+                env.iter().map(|(key, value)| format!("{key}={value}")).collect();
+            plan.push_str(&format!("    env {}\n", vars.join(" ")));
+        }
+        for dev in instance.devices.iter().filter_map(|&d| input_devices.get(d)) {
+            let kind = match dev.device_type {
+                DeviceType::Gamepad => "pad",
+                DeviceType::Keyboard => "keyboard",
+                DeviceType::Mouse => "mouse",
+                DeviceType::Other => "other",
+            };
+            plan.push_str(&format!("    {kind} {} [{}]\n", dev.path, dev.identity));
+        }
+    }
+
+    if let Some(last) = load_launch_history(&game_id).last() {
+        let outcome = if last.succeeded { "succeeded" } else { "failed" };
+        plan.push_str(&format!("Last launch ({outcome}):\n"));
+        for command in &last.commands {
+            plan.push_str(&format!("  {command}\n"));
+        }
+    }
+    plan
+}
+
+pub fn launch_game(
+    game: &Game,
+    input_devices: &[DeviceInfo],
+    instances: &Vec<Instance>,
+    cfg: &PartyConfig,
+    session: &SessionControl,
+) -> Result<(), Box<dyn std::error::Error>> {
+    // Working trees are rebuilt below; don't race the startup cleanup.
+    wait_for_tree_cleanup();
+    // A game folder picked during this launch is checked for anti-cheat.
+    let folder_known = match game {
+        HandlerRef(h) => known_rootpath_handler(h).is_some(),
+        ExecRef(_) => true,
+    };
+    if let HandlerRef(h) = game {
+        for instance in instances {
+            create_profile(instance.profname.as_str())?;
+            create_gamesave(instance.profname.as_str(), &h)?;
+        }
+        if h.symlink_dir {
+            create_symlink_folder(&h, cfg)?;
+            // Refreshed every launch so DLCs released since show up too. A
+            // missing list only costs the DLCs, so it doesn't stop the launch.
+            if cfg.goldberg_dlc_list && !h.path_goldberg.is_empty() {
+                if let Err(err) = generate_goldberg_dlc_list(&h) {
+                    log_warn(format!("Couldn't generate the Goldberg DLC list: {err}"));
+                }
+            }
+        }
+    }
+    check_cancelled(session)?;
+
+    let game_id = match game {
+        ExecRef(e) => e.filename().to_string(),
+        HandlerRef(h) => h.uid.clone(),
+    };
+    // Every frontend launches through here, so author mode follows the
+    // settings even where no UI set it.
+    logging::set_author_mode(cfg.author_mode);
+    let _session_log = logging::start_session(&game_id);
+    for line in system_info_lines() {
+        log_info(line);
+    }
+
+    // Compiled once and shared by every output reader of the session.
+    let (output_filter, rule_errors) = OutputFilter::new(&cfg.output_rules);
+    for err in rule_errors {
+        log_warn(err);
+    }
+    let output_filter = Arc::new(output_filter);
+
+    let mut profile_names: Vec<String> = instances
+        .iter()
+        .map(|instance| instance.profname.clone())
+        .collect();
+
+    let mut synchronized_goldberg_port: Option<u16> = None;
+    if let HandlerRef(h) = game {
+        if !h.path_goldberg.is_empty() && !profile_names.is_empty() {
+            // Normalize Goldberg LAN metadata so every running instance advertises the
+            // same listen port and exposes required identity files for lobby discovery.
+            synchronized_goldberg_port =
+                synchronize_goldberg_profiles(&profile_names, &game_id, None)?;
+        }
+    }
+
+    if let Some(port) = synchronized_goldberg_port {
+        log_info(format!("Goldberg listen_port for {} synchronized to {}", game_id, port));
+    }
+    let mut nemirtingas_ports: HashMap<String, u16> = HashMap::new();
+    if let HandlerRef(h) = game {
+        if !h.path_nemirtingas.is_empty() && !profile_names.is_empty() {
+            // Resolve deterministic Nemirtingas LAN ports per profile so each instance binds a
+            // unique UDP socket without fighting for the same override on the same machine.
+            nemirtingas_ports =
+                resolve_nemirtingas_ports(&profile_names, &game_id, synchronized_goldberg_port);
+
+            for profile in &profile_names {
+                if let Some(port) = nemirtingas_ports.get(profile) {
+                    log_info(format!(
+                        "Nemirtingas LAN port for profile {} on {} resolved to {}",
+                        profile, game_id, port
+                    ));
+                }
+            }
+        }
+    }
+    check_cancelled(session)?;
+    let mut locks_vec = Vec::new();
+    for instance in instances {
+        // The profile's working tree under run/ is shared by every game, so a
+        // profile plays in one game at a time.
+        if ProfileLock::is_held(&instance.profname) {
+            return Err(format!(
+                "{} is already playing in another game. Stop that game or pick another profile.",
+                instance.profname
+            )
+            .into());
+        }
+        let lock = ProfileLock::acquire(&game_id, &instance.profname)?;
+        locks_vec.push(lock);
+    }
+    let locks = Arc::new(Mutex::new(locks_vec));
+    let child_pids: Arc<Mutex<Vec<u32>>> = Arc::new(Mutex::new(Vec::new()));
+    let manager = SessionManager::global();
+    manager.register(
+        session.id(),
+        Arc::clone(&child_pids),
+        Arc::clone(&locks),
+        logging::current_session(),
+    );
+    manager.install_ctrlc_handler()?;
+
+    let home = PATH_HOME.to_string_lossy().to_string();
+    let localshare = PATH_LOCAL_SHARE.to_string_lossy().to_string();
+    let party = PATH_APP.to_string_lossy().to_string();
+    let steam = PATH_STEAM.to_string_lossy().to_string();
+
+    let gamedir = match game {
+        ExecRef(e) => e
+            .path()
+            .parent()
+            .ok_or_else(|| "Invalid path")?
+            .to_string_lossy()
+            .to_string(),
+        HandlerRef(h) => match h.symlink_dir {
+            true => format!("{party}/gamesyms/{}", h.uid),
+            false => get_rootpath_handler(&h)?,
+        },
+    };
+    if let (HandlerRef(h), false) = (game, folder_known) {
+        if let Some(Ok(root)) = known_rootpath_handler(h) {
+            if let Some(warning) = anticheat_warning(h.display(), Path::new(&root)) {
+                session.notify(ANTICHEAT_TITLE, &warning);
+            }
+        }
+    }
+
+    let win = match game {
+        ExecRef(e) => e.path().extension().unwrap_or_default() == "exe",
+        HandlerRef(h) => h.win,
+    };
+
+    let (exec, alt_exec) = match game {
+        ExecRef(e) => (e.filename().to_string(), String::new()),
+        HandlerRef(h) => (h.exec.clone(), h.alt_exec.clone()),
+    };
+
+    let native_runtime = match game {
+        HandlerRef(h) => match h.runtime.as_str() {
+            "scout" => format!("{steam}/ubuntu12_32/steam-runtime/run.sh"),
+            "soldier" => {
+                format!("{steam}/steamapps/common/SteamLinuxRuntime_soldier/_v2-entry-point")
+            }
+            _ => String::new(),
+        },
+        ExecRef(_) => String::new(),
+    };
+    let mut resolver = RuntimeResolver {
+        default_win: win,
+        exec,
+        alt_exec,
+        native_runtime,
+        proton_env: None,
+    };
+
+    // Proton is looked up when the handler's own build needs it, which also
+    // covers players joining later, or when an instance switched to it.
+    let needs_proton = win || instances.iter().any(|instance| resolver.win_for(instance));
+    resolver.proton_env = if needs_proton {
+        let resolved = resolve_proton_environment(cfg.proton_version.as_str());
+        if resolved.root_path.is_none() {
+            log_warn(format!(
+                "Unable to verify Proton build '{}' on disk; continuing with the provided hint.",
+                resolved.display_name
+            ));
+        } else if let Some(path) = &resolved.root_path {
+            let build = proton_build(path).unwrap_or_else(|| "unknown".to_string());
+            log_info(format!(
+                "Using Proton build {} ({build}) at {}",
+                resolved.display_name,
+                path.display()
+            ));
+        }
+        Some(resolved)
+    } else {
+        None
+    };
+
+    let runtimes: Vec<InstanceRuntime> =
+        instances.iter().map(|instance| resolver.for_instance(instance)).collect();
+    for (i, platform) in runtimes.iter().enumerate() {
+        if platform.win != resolver.default_win {
+            log_info(format!(
+                "Instance {}: running the {} build",
+                instances[i].profname,
+                if platform.win { "Windows" } else { "native" }
+            ));
+        }
+        if !PathBuf::from(&gamedir).join(&platform.exec).exists() {
+            return Err(format!("Executable not found: {gamedir}/{}", platform.exec).into());
+        }
+    }
+
+    if let HandlerRef(h) = game {
+        // Only native instances go through the Steam Linux Runtime.
+        let any_native = runtimes.iter().any(|platform| !platform.win);
+        if any_native
+            && h.runtime == "scout"
+            && !PATH_STEAM.join("ubuntu12_32/steam-runtime/run.sh").exists()
+        {
+            return Err("Steam Scout Runtime not found".into());
+        } else if any_native
+            && h.runtime == "soldier"
+            && !PATH_STEAM
+                .join("steamapps/common/SteamLinuxRuntime_soldier")
+                .exists()
+        {
+            return Err("Steam Soldier Runtime not found".into());
+        }
+
+        // Surface handler-specific resource information so users can debug launch issues quickly.
+        log_handler_resource_state(h, &gamedir);
+    }
+
+    // Persist a memory pressure warning so thrashing sessions are easy to diagnose afterwards.
+    let memory_mb = match game {
+        ExecRef(_) => None,
+        HandlerRef(h) => h.memory_mb,
+    };
+    if let Some(warning) = memory_pressure_warning(instances.len(), memory_mb) {
+        log_warn(&warning);
+    }
+
+    // Runtimes go in before any instance starts so the game finds them on
+    // its first launch in a fresh prefix.
+    if let HandlerRef(h) = game {
+        if !h.winetricks.is_empty() {
+            bootstrap_winetricks(h, instances, &runtimes, &party, cfg, session)?;
+        }
+    }
+
+    let use_bwrap = Command::new("bwrap").arg("--version").status().is_ok();
+
+    let launch_overrides = match game {
+        HandlerRef(h) => load_launch_overrides(&h.uid),
+        ExecRef(_) => LaunchOverrides::default(),
+    };
+    if !launch_overrides.is_empty() {
+        log_info(format!(
+            "Applying launch overrides for {}: args [{}]{}, {} env vars, {} DLL overrides",
+            game_id,
+            launch_overrides.args,
+            if launch_overrides.replace_args {
+                " (replacing handler args)"
+            } else {
+                ""
+            },
+            launch_overrides.env.len(),
+            launch_overrides.dll_overrides.len()
+        ));
+    }
+
+    // A gamescope-kbm built against older libraries dies before showing a
+    // window, which would leave every slot blank. Fall back to stock gamescope
+    // for this session and tell the user how to fix it.
+    let kbm_fallback;
+    let cfg = match cfg.kbm_support {
+        true => match gamescope_start_failure(&BIN_GSC_KBM) {
+            Some(reason) => {
+                log_warn(format!(
+                    "{} won't start ({reason}); using stock gamescope without keyboard and mouse support for this session.",
+                    BIN_GSC_KBM.display()
+                ));
+                notify_desktop(
+                    "gamescope-kbm won't start",
+                    "Launching with stock gamescope, so keyboard and mouse players aren't separated this time. After a system update, reinstall or rebuild gamescope-kbm for the new libraries, or turn off keyboard and mouse support in Settings.",
+                );
+                kbm_fallback = PartyConfig {
+                    kbm_support: false,
+                    ..cfg.clone()
+                };
+                &kbm_fallback
+            }
+            None => cfg,
+        },
+        false => cfg,
+    };
+    log_info(gamescope_info_line(match cfg.kbm_support {
+        true => BIN_GSC_KBM.as_path(),
+        false => Path::new("gamescope"),
+    }));
+
+    // The native layout tiles windows itself as they appear; it stops when dropped.
+    if cfg.safe_mode {
+        log_info(
+            "Safe mode: layout scripts, gamescope-kbm, pad proxies, performance tweaks and CPU pinning are off.",
+        );
+    }
+    let monitors: Vec<Option<String>> = instances.iter().map(|i| i.monitor.clone()).collect();
+    let pinned = pinned_rects(&monitors, cfg);
+    let mut native_layout: Option<NativeLayout> = None;
+    let mut kwin_layout: Option<KwinLayout> = None;
+    // The KWin scripts are loaded as each instance spawns, once its PID is known.
+    let kwin_tiling = cfg.enable_kwin_script && !cfg.native_layout;
+    if cfg.enable_kwin_script && cfg.native_layout {
+        let owned_pids = Arc::clone(&child_pids);
+        native_layout = Some(NativeLayout::start(cfg, pinned.clone(), owned_pids)?);
+    }
+
+    // Route calibrated gamepads and players with gyro or input assists through
+    // the uinput proxy so their adjustments also apply in-game. Proxies live
+    // until the session ends.
+    let mut pad_proxies: Vec<(usize, PadProxy)> = Vec::new();
+    // Proxied pads report input for idle detection themselves.
+    let input_activity = InputActivity::default();
+    if use_bwrap {
+        for (i, instance) in instances.iter().enumerate() {
+            pad_proxies.extend(spawn_instance_pad_proxies(
+                i,
+                instance,
+                input_devices,
+                cfg,
+                &input_activity,
+            ));
+        }
+    }
+
+    let mut drained_prefixes: HashSet<String> = HashSet::new();
+    // Track which Proton prefixes already had their Nemirtingas caches scrubbed
+    // so shared prefixes are only purged once before any instances launch.
+    let mut purged_nemirtingas_prefixes: HashSet<String> = HashSet::new();
+    let mut runtime_instances: Vec<RuntimeInstance> = Vec::new();
+    let mut launch_commands: Vec<String> = Vec::new();
+    for (i, instance) in instances.iter().enumerate() {
+        if session.is_cancelled() {
+            return Err(abandon_cancelled_launch(
+                &mut runtime_instances,
+                instances.len(),
+                session,
+                pad_proxies,
+                native_layout,
+                kwin_layout,
+            ));
+        }
+        let spawned = spawn_instance_child(
+            i,
+            instance,
+            game,
+            &game_id,
+            &gamedir,
+            &runtimes[i],
+            use_bwrap,
+            cfg,
+            input_devices,
+            &nemirtingas_ports,
+            &mut drained_prefixes,
+            &mut purged_nemirtingas_prefixes,
+            &pad_proxies,
+            &launch_overrides,
+            &party,
+            &steam,
+            &home,
+            &localshare,
+            session,
+        );
+        let outcome = match spawned {
+            Ok(outcome) => outcome,
+            // Preparing the instance noticed the cancel; undo the session setup.
+            Err(_) if session.is_cancelled() => {
+                return Err(abandon_cancelled_launch(
+                    &mut runtime_instances,
+                    instances.len(),
+                    session,
+                    pad_proxies,
+                    native_layout,
+                    kwin_layout,
+                ));
+            }
+            Err(err) => return Err(err),
+        };
+
+        launch_commands.push(outcome.command_line);
+        let mut child = outcome.child;
+        let raw_pid = child.id();
+        let seat_pid = outcome.seat_pid;
+        // The game's own PID too, so layouts and hotkeys recognise its window.
+        child_pids.lock().unwrap().extend(std::iter::once(raw_pid).chain(seat_pid));
+        if kwin_tiling {
+            reload_kwin_layout(
+                &mut kwin_layout,
+                &profile_names,
+                cfg,
+                pinned.as_deref(),
+                session.id(),
+                &child_pids,
+            );
+        }
+        session.beat();
+        // Safe mode leaves scheduling to the kernel.
+        if !cfg.safe_mode {
+            let game_pid = seat_pid.unwrap_or(raw_pid);
+            apply_instance_cpu_affinity(game_pid, i, instances.len());
+            promote_instance_priority(game_pid, i, instances.len());
+        }
+
+        let output_tail = OutputTail::default();
+        let mut output_readers = Vec::new();
+        if let Some(stdout) = child.stdout.take() {
+            let name = &instance.profname;
+            let reader = forward_child_output(stdout, i, name, &output_tail, &output_filter);
+            output_readers.push(reader);
+        }
+        if let Some(stderr) = child.stderr.take() {
+            let name = &instance.profname;
+            let reader = forward_child_output(stderr, i, name, &output_tail, &output_filter);
+            output_readers.push(reader);
+        }
+
+        runtime_instances.push(RuntimeInstance {
+            index: i,
+            profile_name: instance.profname.clone(),
+            instance: instance.clone(),
+            child: Some(child),
+            last_pid: Some(raw_pid),
+            seat_pid,
+            nemirtingas_tail: NemirtingasTail::new(&outcome.log_context),
+            log_context: outcome.log_context,
+            proton_prefix: outcome.proton_prefix,
+            finished: false,
+            pending_swap: None,
+            stop_deadline: None,
+            crashed: false,
+            restart_pending: false,
+            output_tail,
+            output_readers,
+            started_at: Instant::now(),
+            window_at: None,
+        });
+
+        if i < instances.len() - 1 {
+            // A cancel during the wait is handled at the top of the loop.
+            session.wait_unless_cancelled(cfg.timings.instance_stagger());
+        }
+    }
+    if session.is_cancelled() {
+        return Err(abandon_cancelled_launch(
+            &mut runtime_instances,
+            instances.len(),
+            session,
+            pad_proxies,
+            native_layout,
+            kwin_layout,
+        ));
+    }
+
+    fire_session_webhook(
+        &cfg.webhook_url,
+        "session_start",
+        game.name(),
+        &profile_names,
+        serde_json::Value::Null,
+    );
+    let session_started = Instant::now();
+    logging::log_event(
+        SessionEventKind::Launch,
+        None,
+        "",
+        &format!("{} players: {}", profile_names.len(), profile_names.join(", ")),
+    );
+    update_profile_stats(&profile_names, |profile| profile.record_session());
+
+    // The launch is recorded as good once every instance survives the first
+    // minute, or as failed when one crashes before that.
+    let launch_started = Instant::now();
+    let mut launch_recorded = false;
+
+    // Players added mid-session may bring pads that weren't around at launch.
+    let mut session_devices: Vec<DeviceInfo> = input_devices.to_vec();
+
+    // Follow the assigned pads so one that drops out can be rebound when it returns.
+    let mut device_monitor = DeviceMonitor::default();
+    for state in &runtime_instances {
+        for dev in state.instance.devices.iter().filter_map(|&d| session_devices.get(d)) {
+            device_monitor.track(state.index, dev);
+        }
+    }
+    // Followed until every instance's window is up; see the session panel.
+    let window_probe = WindowProbe::connect();
+    track_instance_windows(&mut runtime_instances, window_probe.as_ref());
+    let mut nemirtingas_tailed_at = Instant::now();
+    publish_session(
+        session,
+        &runtime_instances,
+        &session_devices,
+        &device_monitor.disconnected(),
+    );
+
+    // Set once every instance was asked to quit, so exits aren't treated as crashes.
+    let mut stopping = false;
+    let mut fps_limited = cfg.performance_limit_40fps;
+    let _hotkeys = if cfg.session_hotkeys {
+        Some(HotkeyListener::start(session.clone()))
+    } else {
+        None
+    };
+    let mut screenshot_chord = watch_screenshot_chord(
+        cfg,
+        &runtime_instances,
+        &session_devices,
+        &pad_proxies,
+        session,
+    );
+
+    // Suspend the session while nobody touches a device.
+    let idle_timeout = Duration::from_secs(cfg.idle_timeout_minutes as u64 * 60);
+    let mut idle_watcher = watch_session_idle(
+        cfg,
+        &runtime_instances,
+        &session_devices,
+        &pad_proxies,
+        &input_activity,
+    );
+    let mut suspended = false;
+
+    while runtime_instances.iter().any(|state| !state.finished) {
+        let mut made_progress = false;
+        let commands = session.take_commands();
+
+        // Any input wakes a suspended session, and so does any command so a
+        // stop or swap isn't held up by paused instances.
+        if idle_watcher.is_some() {
+            let idle = input_activity.idle_for() >= idle_timeout;
+            if !suspended && idle && !stopping {
+                log_info(format!(
+                    "No input for {} minutes; suspending the session.",
+                    cfg.idle_timeout_minutes
+                ));
+                set_session_suspended(&runtime_instances, true, cfg);
+                session.set_idle(true);
+                suspended = true;
+            } else if suspended && (!idle || !commands.is_empty()) {
+                log_info("Resuming the idle session.");
+                set_session_suspended(&runtime_instances, false, cfg);
+                session.set_idle(false);
+                suspended = false;
+                // Restart the idle clock so a command doesn't suspend it again right away.
+                input_activity.touch();
+            }
+        }
+
+        // Apply requests from the session panel between child polls.
+        for command in commands {
+            match command {
+                SessionCommand::SwapProfile { slot, profile } => {
+                    let taken = session_profiles(&runtime_instances);
+                    let Some(state) = runtime_instances
+                        .iter_mut()
+                        .find(|state| state.index == slot && state.pending_swap.is_none())
+                    else {
+                        continue;
+                    };
+                    let Some(pid) = state.last_pid.filter(|_| state.child.is_some()) else {
+                        log_warn(format!(
+                            "Slot {} is not running; can't swap its profile.",
+                            slot + 1
+                        ));
+                        continue;
+                    };
+                    match prepare_swap_profile(
+                        &profile,
+                        &taken,
+                        game,
+                        &game_id,
+                        synchronized_goldberg_port,
+                    ) {
+                        Ok((name, lock)) => {
+                            // Every profile shares the session's Nemirtingas port.
+                            if let Some(port) = nemirtingas_ports.values().next().copied() {
+                                nemirtingas_ports.entry(name.clone()).or_insert(port);
+                            }
+                            log_info(format!(
+                                "Swapping slot {} from {} to {}.",
+                                slot + 1,
+                                state.profile_name,
+                                name
+                            ));
+                            logging::log_event(
+                                SessionEventKind::Swap,
+                                Some(slot),
+                                &state.profile_name,
+                                &format!("{} → {name}", state.profile_name),
+                            );
+                            let _ = kill(Pid::from_raw(-(pid as i32)), Signal::SIGTERM);
+                            state.pending_swap = Some((name, lock));
+                            state.stop_deadline = Some(Instant::now() + INSTANCE_STOP_GRACE);
+                        }
+                        Err(err) => log_warn(format!(
+                            "Couldn't swap slot {} to {profile}: {err}",
+                            slot + 1
+                        )),
+                    }
+                }
+                SessionCommand::AddInstance { profile, device } => {
+                    let taken = session_profiles(&runtime_instances);
+                    let (name, lock) = match prepare_swap_profile(
+                        &profile,
+                        &taken,
+                        game,
+                        &game_id,
+                        synchronized_goldberg_port,
+                    ) {
+                        Ok(prepared) => prepared,
+                        Err(err) => {
+                            log_warn(format!("Couldn't add {profile}: {err}"));
+                            continue;
+                        }
+                    };
+                    if let Some(port) = nemirtingas_ports.values().next().copied() {
+                        nemirtingas_ports.entry(name.clone()).or_insert(port);
+                    }
+
+                    let known = session_devices
+                        .iter()
+                        .position(|dev| dev.path == device.path);
+                    let device_index = match known {
+                        Some(index) => index,
+                        None => {
+                            session_devices.push(device);
+                            session_devices.len() - 1
+                        }
+                    };
+                    let index = runtime_instances.len();
+                    device_monitor.track(index, &session_devices[device_index]);
+                    let total = index + 1;
+
+                    // Size the newcomer for the new player count. Running
+                    // instances keep their render size and are re-tiled by KWin.
+                    let mut layout: Vec<Instance> = runtime_instances
+                        .iter()
+                        .map(|state| state.instance.clone())
+                        .collect();
+                    layout.push(Instance {
+                        devices: vec![device_index],
+                        profname: name.clone(),
+                        profselection: 0,
+                        width: 0,
+                        height: 0,
+                        monitor: None,
+                        motion_passthrough: true,
+                        platform: InstancePlatform::Handler,
+                    });
+                    set_instance_resolutions(&mut layout, cfg);
+                    let instance = layout.pop().unwrap();
+
+                    if use_bwrap {
+                        pad_proxies.extend(spawn_instance_pad_proxies(
+                            index,
+                            &instance,
+                            &session_devices,
+                            cfg,
+                            &input_activity,
+                        ));
+                    }
+
+                    let outcome = match spawn_instance_child(
+                        index,
+                        &instance,
+                        game,
+                        &game_id,
+                        &gamedir,
+                        &resolver.for_instance(&instance),
+                        use_bwrap,
+                        cfg,
+                        &session_devices,
+                        &nemirtingas_ports,
+                        &mut drained_prefixes,
+                        &mut purged_nemirtingas_prefixes,
+                        &pad_proxies,
+                        &launch_overrides,
+                        &party,
+                        &steam,
+                        &home,
+                        &localshare,
+                        session,
+                    ) {
+                        Ok(outcome) => outcome,
+                        Err(err) => {
+                            pad_proxies.retain(|(slot, _)| *slot != index);
+                            log_warn(format!("Couldn't add {name}: {err}"));
+                            continue;
+                        }
+                    };
+
+                    let mut child = outcome.child;
+                    let raw_pid = child.id();
+                    let seat_pid = outcome.seat_pid;
+                    child_pids.lock().unwrap().extend(std::iter::once(raw_pid).chain(seat_pid));
+                    let output_tail = OutputTail::default();
+                    let mut output_readers = Vec::new();
+                    if let Some(stdout) = child.stdout.take() {
+                        output_readers.push(forward_child_output(
+                            stdout,
+                            index,
+                            &name,
+                            &output_tail,
+                            &output_filter,
+                        ));
+                    }
+                    if let Some(stderr) = child.stderr.take() {
+                        output_readers.push(forward_child_output(
+                            stderr,
+                            index,
+                            &name,
+                            &output_tail,
+                            &output_filter,
+                        ));
+                    }
+
+                    locks.lock().unwrap().push(lock);
+                    profile_names.push(name.clone());
+                    runtime_instances.push(RuntimeInstance {
+                        index,
+                        profile_name: name.clone(),
+                        instance,
+                        child: Some(child),
+                        last_pid: Some(raw_pid),
+                        seat_pid,
+                        nemirtingas_tail: NemirtingasTail::new(&outcome.log_context),
+                        log_context: outcome.log_context,
+                        proton_prefix: outcome.proton_prefix,
+                        finished: false,
+                        pending_swap: None,
+                        stop_deadline: None,
+                        crashed: false,
+                        restart_pending: false,
+                        output_tail,
+                        output_readers,
+                        started_at: Instant::now(),
+                        window_at: None,
+                    });
+
+                    // Rebalance CPU cores and priorities across the new player count.
+                    if !cfg.safe_mode {
+                        rebalance_session_cpus(&runtime_instances);
+                    }
+
+                    // Re-run the tiling script so it lays out the extra window. The
+                    // native layout picks new windows up on its own.
+                    if kwin_tiling {
+                        let monitors: Vec<Option<String>> = runtime_instances
+                            .iter()
+                            .map(|state| state.instance.monitor.clone())
+                            .collect();
+                        let pinned = pinned_rects(&monitors, cfg);
+                        reload_kwin_layout(
+                            &mut kwin_layout,
+                            &profile_names,
+                            cfg,
+                            pinned.as_deref(),
+                            session.id(),
+                            &child_pids,
+                        );
+                    }
+
+                    // Watch the newcomer's devices for idle detection too.
+                    idle_watcher = watch_session_idle(
+                        cfg,
+                        &runtime_instances,
+                        &session_devices,
+                        &pad_proxies,
+                        &input_activity,
+                    );
+                    screenshot_chord = watch_screenshot_chord(
+                        cfg,
+                        &runtime_instances,
+                        &session_devices,
+                        &pad_proxies,
+                        session,
+                    );
+
+                    log_info(format!("Added {} to the session in slot {}.", name, total));
+                    logging::log_event(SessionEventKind::Join, Some(index), &name, "");
+                    made_progress = true;
+                }
+                SessionCommand::SetAffinity { slot, cores } => {
+                    let Some(pid) = running_slot_pid(&runtime_instances, slot) else {
+                        continue;
+                    };
+                    match set_tree_affinity(pid, &cores) {
+                        Ok(threads) => log_info(format!(
+                            "Pinned slot {} ({threads} threads) to CPU cores {:?}.",
+                            slot + 1,
+                            cores
+                        )),
+                        Err(err) => log_warn(format!(
+                            "Couldn't change CPU cores of slot {}: {err}",
+                            slot + 1
+                        )),
+                    }
+                }
+                SessionCommand::SetNiceness { slot, nice } => {
+                    let Some(pid) = running_slot_pid(&runtime_instances, slot) else {
+                        continue;
+                    };
+                    match set_tree_niceness(pid, nice) {
+                        Ok(threads) => log_info(format!(
+                            "Set nice {nice} on slot {} ({threads} threads).",
+                            slot + 1
+                        )),
+                        Err(err) => log_warn(format!(
+                            "Couldn't change priority of slot {}: {err}",
+                            slot + 1
+                        )),
+                    }
+                }
+                SessionCommand::RebalanceCpu => rebalance_session_cpus(&runtime_instances),
+                SessionCommand::StopAll => {
+                    log_info("Stopping every instance.");
+                    stopping = true;
+                    for state in runtime_instances.iter_mut() {
+                        state.pending_swap = None;
+                        state.restart_pending = false;
+                        if let Some(pid) = state.last_pid.filter(|_| state.child.is_some()) {
+                            let _ = kill(Pid::from_raw(-(pid as i32)), Signal::SIGTERM);
+                            state.stop_deadline = Some(Instant::now() + INSTANCE_STOP_GRACE);
+                        }
+                    }
+                }
+                SessionCommand::RestartCrashed => {
+                    for state in runtime_instances.iter_mut() {
+                        if state.crashed && state.child.is_none() && !stopping {
+                            state.restart_pending = true;
+                            state.finished = false;
+                        }
+                    }
+                }
+                SessionCommand::ToggleFpsLimit => {
+                    fps_limited = !fps_limited;
+                    let limit = if fps_limited { SESSION_FPS_LIMIT } else { 0 };
+                    for state in &runtime_instances {
+                        let Some(pid) = state.game_pid().filter(|_| state.child.is_some()) else {
+                            continue;
+                        };
+                        match set_gamescope_fps_limit(pid, limit) {
+                            Ok(()) if fps_limited => log_info(format!(
+                                "Limited slot {} to {SESSION_FPS_LIMIT} FPS.",
+                                state.index + 1
+                            )),
+                            Ok(()) => log_info(format!(
+                                "Removed the FPS limit of slot {}.",
+                                state.index + 1
+                            )),
+                            Err(err) => log_warn(format!(
+                                "Couldn't change the FPS limit of slot {}: {err}",
+                                state.index + 1
+                            )),
+                        }
+                    }
+                }
+                SessionCommand::Screenshot { slot } => {
+                    let Some(pid) = running_slot_pid(&runtime_instances, slot) else {
+                        continue;
+                    };
+                    let Some(state) = runtime_instances.iter().find(|state| state.index == slot)
+                    else {
+                        continue;
+                    };
+                    let Some(target) = logging::session_screenshot_path(&state.profile_name)
+                    else {
+                        continue;
+                    };
+                    // Gamescope takes a moment to write the file, which would
+                    // stall the other instances' polling.
+                    let log_session = logging::current_session();
+                    thread::spawn(move || {
+                        logging::attach_session(log_session);
+                        if let Err(err) = save_instance_screenshot(pid, &target) {
+                            log_warn(format!(
+                                "Couldn't take a screenshot of slot {}: {err}",
+                                slot + 1
+                            ));
+                        }
+                    });
+                }
+            }
+        }
+
+        let total_instances = runtime_instances.len();
+        let mut respawned = false;
+        for state in runtime_instances.iter_mut() {
+            let mut restart_requested = std::mem::take(&mut state.restart_pending);
+            if let Some(child) = state.child.as_mut() {
+                // Escalate graceful stops that outlived their grace period.
+                if let (Some(deadline), Some(pid)) = (state.stop_deadline, state.last_pid) {
+                    if Instant::now() >= deadline {
+                        let _ = kill(Pid::from_raw(-(pid as i32)), Signal::SIGKILL);
+                        state.stop_deadline = None;
+                    }
+                }
+
+                match child.try_wait() {
+                    Ok(Some(status)) => {
+                        let pids = [state.last_pid.take(), state.seat_pid.take()];
+                        for pid in pids.into_iter().flatten() {
+                            unregister_child_pid(&child_pids, pid);
+                        }
+                        state.child = None;
+                        state.stop_deadline = None;
+                        // Every exit ends a run, whether it is restarted or not.
+                        let played = state.started_at.elapsed();
+                        record_playtime(&game_id, &state.profile_name, played);
+
+                        if stopping {
+                            // Every instance was asked to quit; nothing to restart.
+                        } else if let Some((name, lock)) = state.pending_swap.take() {
+                            // A requested swap: hand the slot to the new profile and
+                            // respawn through the restart path instead of prompting.
+                            if let Some(slot_lock) = locks.lock().unwrap().get_mut(state.index) {
+                                *slot_lock = lock;
+                            }
+                            if let Some(slot_name) = profile_names.get_mut(state.index) {
+                                *slot_name = name.clone();
+                            }
+                            state.profile_name = name.clone();
+                            state.instance.profname = name;
+                            restart_requested = true;
+                        } else if !status.success() {
+                            log_warn(format!(
+                                "Instance {} exited unexpectedly (status: {:?}).",
+                                state.profile_name, status
+                            ));
+                            logging::log_event(
+                                SessionEventKind::Crash,
+                                Some(state.index),
+                                &state.profile_name,
+                                &status.to_string(),
+                            );
+                            if !launch_recorded && launch_started.elapsed() < LAUNCH_CONFIRM_AFTER
+                            {
+                                launch_recorded = true;
+                                record_launch_outcome(
+                                    &game_id,
+                                    false,
+                                    cfg,
+                                    &launch_overrides,
+                                    &launch_commands,
+                                );
+                            }
+                            fire_session_webhook(
+                                &cfg.webhook_url,
+                                "instance_crash",
+                                game.name(),
+                                &profile_names,
+                                serde_json::json!({
+                                    "profile": state.profile_name,
+                                    "slot": state.index + 1,
+                                    "exit_code": status.code(),
+                                }),
+                            );
+                            drain_output_readers(&mut state.output_readers);
+                            let prompt =
+                                crash_prompt(&state.profile_name, status, &state.output_tail);
+                            restart_requested = prompt_instance_restart(
+                                state,
+                                &session_devices,
+                                "Restart crashed instance?",
+                                &prompt,
+                            );
+                            // Declined restarts can still be picked up with the hotkey.
+                            state.crashed = !restart_requested;
+                        } else {
+                            logging::log_event(
+                                SessionEventKind::Leave,
+                                Some(state.index),
+                                &state.profile_name,
+                                "",
+                            );
+                        }
+
+                        if !restart_requested {
+                            state.finished = true;
+                        }
+                        made_progress = true;
+                    }
+                    Ok(None) => {}
+                    Err(err) => {
+                        log_warn(format!(
+                            "Failed to poll instance {}: {}",
+                            state.profile_name, err
+                        ));
+                    }
+                }
+            }
+
+            if restart_requested {
+                if let Some(prefix) = state.proton_prefix.clone() {
+                    drained_prefixes.remove(&prefix);
+                    purged_nemirtingas_prefixes.remove(&prefix);
+                }
+                std::thread::sleep(cfg.timings.restart_delay());
+                match spawn_instance_child(
+                    state.index,
+                    &state.instance,
+                    game,
+                    &game_id,
+                    &gamedir,
+                    &resolver.for_instance(&state.instance),
+                    use_bwrap,
+                    cfg,
+                    &session_devices,
+                    &nemirtingas_ports,
+                    &mut drained_prefixes,
+                    &mut purged_nemirtingas_prefixes,
+                    &pad_proxies,
+                    &launch_overrides,
+                    &party,
+                    &steam,
+                    &home,
+                    &localshare,
+                    session,
+                ) {
+                    Ok(mut respawn) => {
+                        let new_pid = respawn.child.id();
+                        let pids = std::iter::once(new_pid).chain(respawn.seat_pid);
+                        child_pids.lock().unwrap().extend(pids);
+                        respawned = true;
+                        if !cfg.safe_mode {
+                            let game_pid = respawn.seat_pid.unwrap_or(new_pid);
+                            apply_instance_cpu_affinity(game_pid, state.index, total_instances);
+                            promote_instance_priority(game_pid, state.index, total_instances);
+                        }
+
+                        // A fresh tail, so the old run's errors can't explain a new crash.
+                        state.output_tail = OutputTail::default();
+                        state.output_readers.clear();
+                        let name = &state.instance.profname;
+                        if let Some(stdout) = respawn.child.stdout.take() {
+                            state.output_readers.push(forward_child_output(
+                                stdout,
+                                state.index,
+                                name,
+                                &state.output_tail,
+                                &output_filter,
+                            ));
+                        }
+                        if let Some(stderr) = respawn.child.stderr.take() {
+                            state.output_readers.push(forward_child_output(
+                                stderr,
+                                state.index,
+                                name,
+                                &state.output_tail,
+                                &output_filter,
+                            ));
+                        }
+
+                        state.child = Some(respawn.child);
+                        state.last_pid = Some(new_pid);
+                        state.seat_pid = respawn.seat_pid;
+                        state.nemirtingas_tail = NemirtingasTail::new(&respawn.log_context);
+                        state.log_context = respawn.log_context;
+                        state.proton_prefix = respawn.proton_prefix;
+                        state.finished = false;
+                        state.crashed = false;
+                        state.started_at = Instant::now();
+                        state.window_at = None;
+                        log_info(format!(
+                            "Restarted profile {} in slot {}.",
+                            state.profile_name,
+                            state.index + 1
+                        ));
+                        logging::log_event(
+                            SessionEventKind::Restart,
+                            Some(state.index),
+                            &state.profile_name,
+                            "",
+                        );
+                    }
+                    Err(err) => {
+                        log_warn(format!(
+                            "Failed to restart instance {}: {}",
+                            state.profile_name, err
+                        ));
+                        state.finished = true;
+                        state.crashed = true;
+                    }
+                }
+                made_progress = true;
+            }
+        }
+        // The layout only tiles PIDs it was loaded with.
+        if respawned && kwin_tiling {
+            let monitors: Vec<Option<String>> = runtime_instances
+                .iter()
+                .map(|state| state.instance.monitor.clone())
+                .collect();
+            let pinned = pinned_rects(&monitors, cfg);
+            reload_kwin_layout(
+                &mut kwin_layout,
+                &profile_names,
+                cfg,
+                pinned.as_deref(),
+                session.id(),
+                &child_pids,
+            );
+        }
+
+        // Rebind pads that came back and tell the players about the ones that left.
+        let virtual_pads: Vec<String> = pad_proxies
+            .iter()
+            .map(|(_, proxy)| proxy.virtual_path.clone())
+            .collect();
+        for event in device_monitor.poll(&virtual_pads) {
+            handle_hotplug_event(
+                event,
+                &runtime_instances,
+                &mut session_devices,
+                &mut pad_proxies,
+            );
+        }
+
+        track_instance_windows(&mut runtime_instances, window_probe.as_ref());
+        if nemirtingas_tailed_at.elapsed() >= NEMIRTINGAS_TAIL_INTERVAL {
+            tail_nemirtingas_logs(&mut runtime_instances);
+            nemirtingas_tailed_at = Instant::now();
+        }
+        publish_session(
+            session,
+            &runtime_instances,
+            &session_devices,
+            &device_monitor.disconnected(),
+        );
+
+        if !launch_recorded && launch_started.elapsed() >= LAUNCH_CONFIRM_AFTER {
+            launch_recorded = true;
+            record_launch_outcome(&game_id, true, cfg, &launch_overrides, &launch_commands);
+        }
+
+        if !made_progress {
+            std::thread::sleep(cfg.timings.session_poll());
+        }
+    }
+    session.publish(Vec::new());
+    drop(idle_watcher);
+    drop(screenshot_chord);
+    if suspended {
+        // The instances are gone; only the screen needs restoring.
+        session.set_idle(false);
+        if cfg.idle_dim_display {
+            let _ = dim_display(false);
+        }
+    }
+
+    let nemirtingas_logs: Vec<NemirtingasLogContext> = runtime_instances
+        .iter()
+        .map(|state| state.log_context.clone())
+        .collect();
+
+    collect_nemirtingas_logs(&nemirtingas_logs);
+
+    let played = session_started.elapsed().as_secs();
+    logging::log_event(
+        SessionEventKind::End,
+        None,
+        "",
+        &format!("after {} min", played / 60),
+    );
+    update_profile_stats(&profile_names, |profile| profile.stats.playtime_secs += played);
+
+    fire_session_webhook(
+        &cfg.webhook_url,
+        "session_end",
+        game.name(),
+        &profile_names,
+        serde_json::Value::Null,
+    );
+
+    manager.finish(session.id());
+    // Release grabbed pads and remove their virtual twins.
+    drop(pad_proxies);
+
+    drop(native_layout);
+    if let Some(layout) = kwin_layout {
+        stop_kwin_layout(layout)?;
+    }
+
+    // Guest folders are shared by name, so they stay while another session
+    // may still be using them.
+    if !manager.any_running() {
+        remove_guest_profiles()?;
+    }
+
+    Ok(())
+}
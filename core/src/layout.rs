@@ -0,0 +1,511 @@
+use crate::config::PartyConfig;
+use crate::logging::{log_info, log_warn};
+use crate::paths::{PATH_APP, PATH_RES};
+use crate::util::{
+    Display, client_windows, enumerate_displays, get_screen_resolution, window_pid,
+};
+
+use std::error::Error;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::thread::JoinHandle;
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use x11rb::connection::Connection;
+use x11rb::protocol::xproto::{
+    AtomEnum, ConfigureWindowAux, ConnectionExt, MapState, PropMode, Window,
+};
+use x11rb::rust_connection::RustConnection;
+use x11rb::wrapper::ConnectionExt as _;
+
+/// Screens at least this many times wider than tall count as ultrawide.
+const ULTRAWIDE_ASPECT: f32 = 2.0;
+
+/// How two players share the screen.
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Default)]
+pub enum TwoPlayerSplit {
+    /// Side by side on ultrawide screens, stacked on everything else.
+    #[default]
+    Auto,
+    /// Stacked, one player above the other.
+    Horizontal,
+    /// Side by side.
+    Vertical,
+}
+
+impl TwoPlayerSplit {
+    pub const ALL: [TwoPlayerSplit; 3] = [
+        TwoPlayerSplit::Auto,
+        TwoPlayerSplit::Horizontal,
+        TwoPlayerSplit::Vertical,
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            TwoPlayerSplit::Auto => "Auto",
+            TwoPlayerSplit::Horizontal => "Horizontal (stacked)",
+            TwoPlayerSplit::Vertical => "Vertical (side by side)",
+        }
+    }
+
+    /// The split used on a `width`x`height` screen. A 21:9 or 32:9 screen
+    /// leaves each player a normal 16:9-ish pane side by side, while 16:9 and
+    /// 16:10 ones keep the panes wide by stacking them.
+    pub fn resolve(self, (width, height): (u32, u32)) -> TwoPlayerSplit {
+        match self {
+            TwoPlayerSplit::Auto if width as f32 >= height as f32 * ULTRAWIDE_ASPECT => {
+                TwoPlayerSplit::Vertical
+            }
+            TwoPlayerSplit::Auto => TwoPlayerSplit::Horizontal,
+            split => split,
+        }
+    }
+}
+
+/// How three players share the screen.
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Default)]
+pub enum ThreePlayerLayout {
+    /// Player 1 spans the top half, players 2 and 3 split the bottom.
+    #[default]
+    TopWide,
+    /// Players 1 and 2 split the top half, player 3 spans the bottom.
+    BottomWide,
+    /// Three side-by-side columns.
+    Columns,
+}
+
+impl ThreePlayerLayout {
+    pub const ALL: [ThreePlayerLayout; 3] = [
+        ThreePlayerLayout::TopWide,
+        ThreePlayerLayout::BottomWide,
+        ThreePlayerLayout::Columns,
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            ThreePlayerLayout::TopWide => "One on top, two below",
+            ThreePlayerLayout::BottomWide => "Two on top, one below",
+            ThreePlayerLayout::Columns => "Three columns",
+        }
+    }
+}
+
+/// How four players share the screen.
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Default)]
+pub enum FourPlayerLayout {
+    /// 2x2 grid.
+    #[default]
+    Grid,
+    /// Four side-by-side columns.
+    Columns,
+    /// Four stacked rows.
+    Rows,
+}
+
+impl FourPlayerLayout {
+    pub const ALL: [FourPlayerLayout; 3] = [
+        FourPlayerLayout::Grid,
+        FourPlayerLayout::Columns,
+        FourPlayerLayout::Rows,
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            FourPlayerLayout::Grid => "2x2 grid",
+            FourPlayerLayout::Columns => "Four columns",
+            FourPlayerLayout::Rows => "Four rows",
+        }
+    }
+}
+
+/// Screen area assigned to one player.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct Rect {
+    pub x: i32,
+    pub y: i32,
+    pub width: u32,
+    pub height: u32,
+}
+
+/// Player region as fractions of the screen, so layouts survive resolution
+/// changes. Custom layouts are stored in this form.
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Debug)]
+pub struct LayoutRegion {
+    pub x: f32,
+    pub y: f32,
+    pub width: f32,
+    pub height: f32,
+}
+
+impl LayoutRegion {
+    pub const fn new(x: f32, y: f32, width: f32, height: f32) -> LayoutRegion {
+        LayoutRegion {
+            x,
+            y,
+            width,
+            height,
+        }
+    }
+}
+
+/// Regions for `count` players from the built-in presets on a screen of size
+/// `screen`. Counts above four use the smallest near-square grid that fits
+/// everyone.
+pub fn preset_regions(count: usize, cfg: &PartyConfig, screen: (u32, u32)) -> Vec<LayoutRegion> {
+    let cell = LayoutRegion::new;
+    let columns = |n: usize| -> Vec<LayoutRegion> {
+        let w = 1.0 / n as f32;
+        (0..n).map(|i| cell(i as f32 * w, 0.0, w, 1.0)).collect()
+    };
+    let rows = |n: usize| -> Vec<LayoutRegion> {
+        let h = 1.0 / n as f32;
+        (0..n).map(|i| cell(0.0, i as f32 * h, 1.0, h)).collect()
+    };
+
+    match count {
+        0 => Vec::new(),
+        1 => vec![cell(0.0, 0.0, 1.0, 1.0)],
+        2 => match cfg.two_player_split.resolve(screen) {
+            TwoPlayerSplit::Vertical => columns(2),
+            _ => rows(2),
+        },
+        3 => match cfg.three_player_layout {
+            ThreePlayerLayout::TopWide => vec![
+                cell(0.0, 0.0, 1.0, 0.5),
+                cell(0.0, 0.5, 0.5, 0.5),
+                cell(0.5, 0.5, 0.5, 0.5),
+            ],
+            ThreePlayerLayout::BottomWide => vec![
+                cell(0.0, 0.0, 0.5, 0.5),
+                cell(0.5, 0.0, 0.5, 0.5),
+                cell(0.0, 0.5, 1.0, 0.5),
+            ],
+            ThreePlayerLayout::Columns => columns(3),
+        },
+        4 => match cfg.four_player_layout {
+            FourPlayerLayout::Grid => vec![
+                cell(0.0, 0.0, 0.5, 0.5),
+                cell(0.5, 0.0, 0.5, 0.5),
+                cell(0.0, 0.5, 0.5, 0.5),
+                cell(0.5, 0.5, 0.5, 0.5),
+            ],
+            FourPlayerLayout::Columns => columns(4),
+            FourPlayerLayout::Rows => rows(4),
+        },
+        _ => {
+            let cols = (count as f32).sqrt().ceil() as usize;
+            let grid_rows = count.div_ceil(cols);
+            let (w, h) = (1.0 / cols as f32, 1.0 / grid_rows as f32);
+            (0..count)
+                .map(|i| cell((i % cols) as f32 * w, (i / cols) as f32 * h, w, h))
+                .collect()
+        }
+    }
+}
+
+/// Regions for `count` players: the saved custom layout for that count when
+/// one exists, the presets for `screen` otherwise.
+pub fn player_regions(count: usize, cfg: &PartyConfig, screen: (u32, u32)) -> Vec<LayoutRegion> {
+    match cfg.custom_layouts.get(&count.to_string()) {
+        Some(custom) if custom.len() == count => custom.clone(),
+        _ => preset_regions(count, cfg, screen),
+    }
+}
+
+/// Splits a `width`x`height` screen into `count` player rectangles.
+pub fn player_rects(count: usize, (width, height): (u32, u32), cfg: &PartyConfig) -> Vec<Rect> {
+    let (w, h) = (width as f32, height as f32);
+    player_regions(count, cfg, (width, height))
+        .into_iter()
+        .map(|region| Rect {
+            x: (region.x * w).round() as i32,
+            y: (region.y * h).round() as i32,
+            width: (region.width * w).round() as u32,
+            height: (region.height * h).round() as u32,
+        })
+        .collect()
+}
+
+/// Splits each display among the players pinned to it. Unpinned players, and
+/// players pinned to a display that is gone, share the primary display.
+/// Rectangles are in global desktop coordinates.
+pub fn split_displays(
+    monitors: &[Option<String>],
+    displays: &[Display],
+    cfg: &PartyConfig,
+) -> Vec<Rect> {
+    let primary = displays.iter().position(|d| d.primary).unwrap_or(0);
+    let placement: Vec<usize> = monitors
+        .iter()
+        .map(|monitor| {
+            monitor
+                .as_ref()
+                .and_then(|name| displays.iter().position(|d| &d.name == name))
+                .unwrap_or(primary)
+        })
+        .collect();
+
+    let mut rects = vec![
+        Rect {
+            x: 0,
+            y: 0,
+            width: 0,
+            height: 0,
+        };
+        monitors.len()
+    ];
+    for (index, display) in displays.iter().enumerate() {
+        let players: Vec<usize> = (0..monitors.len()).filter(|&p| placement[p] == index).collect();
+        let tiles = player_rects(players.len(), (display.width, display.height), cfg);
+        for (player, tile) in players.into_iter().zip(tiles) {
+            rects[player] = Rect {
+                x: display.x + tile.x,
+                y: display.y + tile.y,
+                ..tile
+            };
+        }
+    }
+    rects
+}
+
+/// Size of the screen a session without pinned players plays on: the primary
+/// display, which KWin tiles as the active screen, so a second monitor doesn't
+/// skew the two-player split. Falls back to the whole X screen when no display
+/// can be listed.
+pub fn session_screen() -> (u32, u32) {
+    let displays = enumerate_displays();
+    match displays.iter().find(|d| d.primary).or(displays.first()) {
+        Some(display) => (display.width, display.height),
+        None => get_screen_resolution(),
+    }
+}
+
+/// Player rectangles when at least one player (one entry of `monitors` per
+/// player) is pinned to a display. `None` when nobody is pinned or no
+/// display could be listed, so the whole screen is shared as before.
+pub fn pinned_rects(monitors: &[Option<String>], cfg: &PartyConfig) -> Option<Vec<Rect>> {
+    if monitors.iter().all(Option::is_none) {
+        return None;
+    }
+    let displays = enumerate_displays();
+    if displays.is_empty() {
+        log_warn("Players are pinned to displays, but none could be listed; sharing one screen.");
+        return None;
+    }
+    Some(split_displays(monitors, &displays, cfg))
+}
+
+/// Writes the KWin tiling script `name` with the regions for every player
+/// count up to `max_players`, since windows are tiled as they appear one by
+/// one. With `pinned`, windows are instead moved to those absolute rectangles
+/// in launch order, which can span several screens. Only windows of
+/// `owned_pids`, the session's instance PIDs, are tiled.
+pub fn write_kwin_script(
+    max_players: usize,
+    cfg: &PartyConfig,
+    pinned: Option<&[Rect]>,
+    name: &str,
+    owned_pids: &[u32],
+) -> Result<PathBuf, Box<dyn Error>> {
+    let template = std::fs::read_to_string(PATH_RES.join("splitscreen_kwin.js"))?;
+    let screen = session_screen();
+    let layouts: Vec<Vec<[f32; 4]>> = (0..=max_players)
+        .map(|count| {
+            player_regions(count, cfg, screen)
+                .iter()
+                .map(|r| [r.x, r.y, r.width, r.height])
+                .collect()
+        })
+        .collect();
+    let pinned: Option<Vec<[i64; 4]>> = pinned.map(|rects| {
+        rects
+            .iter()
+            .map(|r| [r.x as i64, r.y as i64, r.width as i64, r.height as i64])
+            .collect()
+    });
+    let script = template
+        .replace("__PLAYER_LAYOUTS__", &serde_json::to_string(&layouts)?)
+        .replace("__PLAYER_RECTS__", &serde_json::to_string(&pinned)?)
+        .replace("__OWN_PIDS__", &serde_json::to_string(owned_pids)?);
+
+    let script_dir = PATH_APP.join("kwin");
+    std::fs::create_dir_all(&script_dir)?;
+    let script_path = script_dir.join(format!("{name}.js"));
+    std::fs::write(&script_path, script)?;
+    Ok(script_path)
+}
+
+/// Tiles gamescope windows over X11 without relying on a specific window
+/// manager. A background thread watches for new gamescope windows and lays
+/// every known one out again whenever one appears, mirroring the KWin script.
+pub struct NativeLayout {
+    stop: Arc<AtomicBool>,
+    handle: Option<JoinHandle<()>>,
+}
+
+impl NativeLayout {
+    /// Connects to the X server and starts watching for gamescope windows.
+    /// With `pinned`, windows go to those rectangles in the order they appear.
+    /// Windows whose `_NET_WM_PID` isn't in `owned_pids`, the session's
+    /// instance PIDs, belong to another session and are left alone.
+    pub fn start(
+        cfg: &PartyConfig,
+        pinned: Option<Vec<Rect>>,
+        owned_pids: Arc<Mutex<Vec<u32>>>,
+    ) -> Result<NativeLayout, Box<dyn Error>> {
+        let (conn, screen_num) = x11rb::connect(None)?;
+        let cfg = cfg.clone();
+        let stop = Arc::new(AtomicBool::new(false));
+        let thread_stop = Arc::clone(&stop);
+        let handle = std::thread::spawn(move || {
+            let mut placer = WindowPlacer::new(conn, screen_num, pinned, owned_pids);
+            while !thread_stop.load(Ordering::Relaxed) {
+                if let Err(err) = placer.update(&cfg) {
+                    log_warn(format!("Native layout update failed: {err}"));
+                }
+                std::thread::sleep(Duration::from_millis(500));
+            }
+        });
+        log_info("Native X11 window layout started.");
+        Ok(NativeLayout {
+            stop,
+            handle: Some(handle),
+        })
+    }
+}
+
+impl Drop for NativeLayout {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::Relaxed);
+        if let Some(handle) = self.handle.take() {
+            let _ = handle.join();
+        }
+    }
+}
+
+struct WindowPlacer {
+    conn: RustConnection,
+    root: Window,
+    screen: (u32, u32),
+    /// Gamescope windows in the order they first appeared; the order decides slots.
+    known: Vec<Window>,
+    /// Fixed rectangles per slot when players are pinned to displays.
+    pinned: Option<Vec<Rect>>,
+    /// Instance PIDs of the session this placer tiles.
+    owned_pids: Arc<Mutex<Vec<u32>>>,
+}
+
+impl WindowPlacer {
+    fn new(
+        conn: RustConnection,
+        screen_num: usize,
+        pinned: Option<Vec<Rect>>,
+        owned_pids: Arc<Mutex<Vec<u32>>>,
+    ) -> WindowPlacer {
+        let screen = &conn.setup().roots[screen_num];
+        let root = screen.root;
+        let size = (screen.width_in_pixels as u32, screen.height_in_pixels as u32);
+        WindowPlacer {
+            conn,
+            root,
+            screen: size,
+            known: Vec::new(),
+            pinned,
+            owned_pids,
+        }
+    }
+
+    /// Re-tiles when a new gamescope window shows up. Closed windows are only
+    /// pruned at that point so survivors keep their slot after a peer crashes.
+    fn update(&mut self, cfg: &PartyConfig) -> Result<(), Box<dyn Error>> {
+        let windows = self.gamescope_windows()?;
+        if windows.iter().all(|window| self.known.contains(window)) {
+            return Ok(());
+        }
+        self.known.retain(|window| windows.contains(window));
+        for window in windows {
+            if !self.known.contains(&window) {
+                self.known.push(window);
+            }
+        }
+
+        // Pinned slots only fit the players of the launch; extra windows fall
+        // back to sharing the whole screen.
+        let rects = match &self.pinned {
+            Some(pinned) if self.known.len() <= pinned.len() => pinned.clone(),
+            _ => player_rects(self.known.len(), self.screen, cfg),
+        };
+        let motif_hints = self.atom(b"_MOTIF_WM_HINTS")?;
+        for (window, rect) in self.known.iter().zip(rects) {
+            // Ask the window manager to drop decorations (flags: decorations, value: none).
+            self.conn.change_property32(
+                PropMode::REPLACE,
+                *window,
+                motif_hints,
+                motif_hints,
+                &[2, 0, 0, 0, 0],
+            )?;
+            self.conn.configure_window(
+                *window,
+                &ConfigureWindowAux::new()
+                    .x(rect.x)
+                    .y(rect.y)
+                    .width(rect.width)
+                    .height(rect.height),
+            )?;
+        }
+        self.conn.flush()?;
+        Ok(())
+    }
+
+    /// Mapped gamescope windows, taken from the window manager's client list
+    /// when it publishes one and from the root's children otherwise.
+    fn gamescope_windows(&self) -> Result<Vec<Window>, Box<dyn Error>> {
+        let candidates = client_windows(&self.conn, self.root)?;
+
+        let mut windows = Vec::new();
+        for window in candidates {
+            let Ok(attributes) = self.conn.get_window_attributes(window)?.reply() else {
+                continue;
+            };
+            if attributes.map_state == MapState::VIEWABLE
+                && self.is_gamescope(window)?
+                && self.is_owned(window)?
+            {
+                windows.push(window);
+            }
+        }
+        Ok(windows)
+    }
+
+    fn is_gamescope(&self, window: Window) -> Result<bool, Box<dyn Error>> {
+        let reply = self
+            .conn
+            .get_property(false, window, AtomEnum::WM_CLASS, AtomEnum::STRING, 0, 256)?
+            .reply()?;
+        // WM_CLASS holds "instance\0class\0".
+        Ok(reply
+            .value
+            .split(|byte| *byte == 0)
+            .any(|part| part == b"gamescope" || part == b"gamescope-kbm"))
+    }
+
+    /// Whether `window` belongs to this session. Windows that don't publish
+    /// `_NET_WM_PID` can't be told apart and are treated as owned.
+    fn is_owned(&self, window: Window) -> Result<bool, Box<dyn Error>> {
+        let Some(pid) = window_pid(&self.conn, window)? else {
+            return Ok(true);
+        };
+        Ok(self
+            .owned_pids
+            .lock()
+            .map(|pids| pids.contains(&pid))
+            .unwrap_or(true))
+    }
+
+    fn atom(&self, name: &[u8]) -> Result<u32, Box<dyn Error>> {
+        Ok(self.conn.intern_atom(false, name)?.reply()?.atom)
+    }
+}
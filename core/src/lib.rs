@@ -0,0 +1,40 @@
+//! Everything Split Happens does besides drawing its window: handlers and the
+//! game library, profiles, input devices, launch planning, emulator config and
+//! the sessions of running instances. The GUI in the `split-happens` binary is
+//! one frontend on top of it; others only need this crate.
+//!
+//! The main entry points:
+//!
+//! - [`config::load_cfg`] reads the launcher settings every launch depends on.
+//! - [`game::scan_all_games`] lists installed handlers and added executables.
+//! - [`input::scan_input_devices`] finds the controllers, keyboards and mice
+//!   that can be handed to players.
+//! - [`instance`] turns players and their devices into [`instance::Instance`]s
+//!   sized for the screen.
+//! - [`launch::launch_game`] starts one gamescope instance per player and runs
+//!   the session until every instance exits, driven through a
+//!   [`session::SessionControl`].
+//! - [`util::set_prompter`] installs the frontend's [`util::Prompter`] for
+//!   the messages, questions and folder pickers the core needs. Without one,
+//!   messages only reach the log and every question is answered no.
+//!
+//! ```no_run
+//! use split_happens_core::game::scan_all_games;
+//!
+//! for game in scan_all_games() {
+//!     println!("{} ({})", game.name(), game.persistent_id());
+//! }
+//! ```
+
+pub mod config;
+pub mod game;
+pub mod handler;
+pub mod input;
+pub mod instance;
+pub mod launch;
+pub mod layout;
+pub mod logging;
+pub mod monitor;
+pub mod paths;
+pub mod session;
+pub mod util;
@@ -0,0 +1,535 @@
+use std::cell::Cell;
+use std::collections::HashMap;
+use std::fs::{self, File, OpenOptions};
+use std::io::{IsTerminal, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
+
+use crate::paths::*;
+
+/// Sessions kept under `logs/`; older session folders are removed when a new
+/// one starts.
+const MAX_LOG_SESSIONS: usize = 20;
+
+/// File inside a session folder that collects the launcher's own messages.
+pub const LAUNCHER_LOG: &str = "launcher.log";
+
+/// File inside a session folder with the session's timeline, one JSON
+/// [`SessionEvent`] per line.
+pub const EVENTS_LOG: &str = "events.jsonl";
+
+/// Folder inside a session folder that holds the screenshots players took.
+pub const SCREENSHOTS_DIR: &str = "screenshots";
+
+/// Follows the slot tag of instance output lines an output rule highlighted.
+pub const HIGHLIGHT_MARK: &str = "[!]";
+
+/// What happened at a point of a session's timeline.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+pub enum SessionEventKind {
+    /// Every instance was started.
+    Launch,
+    /// A player was added mid-session.
+    Join,
+    /// An instance exited on its own without an error.
+    Leave,
+    /// An instance exited with an error.
+    Crash,
+    /// A crashed or swapped instance was started again.
+    Restart,
+    /// A slot was handed to another profile.
+    Swap,
+    /// A controller of the slot disconnected.
+    Disconnect,
+    /// A disconnected controller came back and was rebound.
+    Reconnect,
+    /// The last instance exited.
+    End,
+}
+
+impl SessionEventKind {
+    pub fn label(&self) -> &'static str {
+        match self {
+            SessionEventKind::Launch => "Launch",
+            SessionEventKind::Join => "Join",
+            SessionEventKind::Leave => "Leave",
+            SessionEventKind::Crash => "Crash",
+            SessionEventKind::Restart => "Restart",
+            SessionEventKind::Swap => "Swap",
+            SessionEventKind::Disconnect => "Disconnect",
+            SessionEventKind::Reconnect => "Reconnect",
+            SessionEventKind::End => "End",
+        }
+    }
+}
+
+/// One entry of a session's timeline.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct SessionEvent {
+    /// Milliseconds since the Unix epoch.
+    pub timestamp_ms: u64,
+    pub kind: SessionEventKind,
+    /// Player slot, for events about a single instance.
+    #[serde(default)]
+    pub slot: Option<usize>,
+    /// Profile of that instance, whose log covers the event.
+    #[serde(default)]
+    pub profile: String,
+    #[serde(default)]
+    pub detail: String,
+}
+
+/// Size a `.log` file of a session may reach before it is rotated, so a game
+/// spamming its output can't fill the disk.
+const MAX_LOG_FILE_BYTES: u64 = 16 * 1024 * 1024;
+
+/// Rotated parts kept per log file, as `<name>~1.log` (newest) up to
+/// `<name>~3.log`; older output is dropped.
+const LOG_FILE_ROTATIONS: usize = 3;
+
+/// Goes between a log's name and its part number. [`file_stem`] never puts
+/// it in a name, so no profile's log can be taken for another's part.
+const LOG_PART_SEPARATOR: char = '~';
+
+/// A log file open in a session folder and how much it holds.
+struct OpenLog {
+    file: File,
+    len: u64,
+}
+
+/// Log folder of a running session and the files opened in it so far.
+struct SessionLog {
+    id: u64,
+    dir: PathBuf,
+    files: HashMap<String, OpenLog>,
+}
+
+impl SessionLog {
+    fn write(&mut self, file_name: &str, line: &str) {
+        if !self.files.contains_key(file_name) {
+            let path = self.dir.join(file_name);
+            match OpenOptions::new().create(true).append(true).open(&path) {
+                Ok(file) => {
+                    let len = file.metadata().map_or(0, |meta| meta.len());
+                    self.files.insert(file_name.to_string(), OpenLog { file, len });
+                }
+                Err(err) => {
+                    if console_output() {
+                        println!("[SPLIT HAPPENS][WARN] Can't open log {}: {err}", path.display());
+                    }
+                    return;
+                }
+            }
+        }
+        let Some(log) = self.files.get_mut(file_name) else {
+            return;
+        };
+        if writeln!(log.file, "{line}").is_ok() {
+            log.len += line.len() as u64 + 1;
+        }
+        // The timeline is read back whole, so only plain logs rotate.
+        if log.len >= MAX_LOG_FILE_BYTES && file_name.ends_with(".log") {
+            self.files.remove(file_name);
+            rotate_log(&self.dir, file_name);
+        }
+    }
+}
+
+/// Shifts `file_name` in `dir` and its rotated parts up by one, dropping the
+/// oldest. The next write starts a fresh file.
+fn rotate_log(dir: &Path, file_name: &str) {
+    let stem = file_name.trim_end_matches(".log");
+    let part = |n: usize| dir.join(format!("{stem}{LOG_PART_SEPARATOR}{n}.log"));
+    let _ = fs::remove_file(part(LOG_FILE_ROTATIONS));
+    for n in (1..LOG_FILE_ROTATIONS).rev() {
+        let _ = fs::rename(part(n), part(n + 1));
+    }
+    if let Err(err) = fs::rename(dir.join(file_name), part(1)) {
+        if console_output() {
+            println!("[SPLIT HAPPENS][WARN] Can't rotate log {file_name}: {err}");
+        }
+    }
+}
+
+/// Logs of the running sessions. Several games can run at once, each with
+/// its own folder.
+static SESSIONS: Mutex<Vec<SessionLog>> = Mutex::new(Vec::new());
+static NEXT_SESSION_ID: AtomicU64 = AtomicU64::new(1);
+/// Whether messages are echoed to stdout as well as written to the logs.
+static CONSOLE_OUTPUT: AtomicBool = AtomicBool::new(true);
+/// Handler author mode: debug messages are kept and logs go to `logs-dev/`.
+static AUTHOR_MODE: AtomicBool = AtomicBool::new(false);
+
+thread_local! {
+    /// Session log the current thread writes to.
+    static CURRENT: Cell<Option<u64>> = const { Cell::new(None) };
+}
+
+/// Runs `write` on the log of the session this thread belongs to. Threads
+/// that never joined a session fall back to the only running one, if exactly
+/// one runs. Returns whether a session log was found.
+fn with_session(write: impl FnOnce(&mut SessionLog)) -> bool {
+    let mut sessions = SESSIONS.lock().unwrap();
+    let session = match CURRENT.get() {
+        Some(id) => sessions.iter_mut().find(|session| session.id == id),
+        None if sessions.len() == 1 => sessions.first_mut(),
+        None => None,
+    };
+    match session {
+        Some(session) => {
+            write(session);
+            true
+        }
+        None => false,
+    }
+}
+
+/// `logs/`, or `logs-dev/` in author mode so debugging runs don't push
+/// regular sessions out of the history.
+fn logs_dir() -> PathBuf {
+    match author_mode() {
+        true => PATH_APP.join("logs-dev"),
+        false => PATH_APP.join("logs"),
+    }
+}
+
+/// `name` with everything but letters, digits, `-` and `_` replaced, so it is
+/// safe as a single path component.
+fn file_stem(name: &str) -> String {
+    name.chars()
+        .map(|c| if c.is_alphanumeric() || c == '-' || c == '_' { c } else { '_' })
+        .collect()
+}
+
+/// `YYYY-MM-DD HH:MM:SS` in UTC for `secs` since the Unix epoch.
+fn format_utc(secs: u64) -> String {
+    // Civil-from-days, see http://howardhinnant.github.io/date_algorithms.html
+    let days = (secs / 86400) as i64 + 719468;
+    let era = days.div_euclid(146097);
+    let doe = days.rem_euclid(146097);
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + i64::from(month <= 2);
+    let time = secs % 86400;
+    format!(
+        "{year:04}-{month:02}-{day:02} {:02}:{:02}:{:02}",
+        time / 3600,
+        time / 60 % 60,
+        time % 60
+    )
+}
+
+fn now_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |elapsed| elapsed.as_secs())
+}
+
+/// Keeps the session log open; dropping it closes the session's files.
+pub struct SessionLogGuard {
+    id: Option<u64>,
+}
+
+impl Drop for SessionLogGuard {
+    fn drop(&mut self) {
+        let Some(id) = self.id else {
+            return;
+        };
+        SESSIONS.lock().unwrap().retain(|session| session.id != id);
+        if CURRENT.get() == Some(id) {
+            CURRENT.set(None);
+        }
+    }
+}
+
+/// Opens a new log folder `logs/<date>_<time>_<game>` that collects every
+/// message of the calling thread, and of threads attached to it with
+/// [`attach_session`], until the returned guard is dropped.
+pub fn start_session(game: &str) -> SessionLogGuard {
+    let stamp = format_utc(now_secs()).replace([' ', ':'], "-");
+    let dir = logs_dir().join(format!("{stamp}_{}", file_stem(game)));
+    if let Err(err) = fs::create_dir_all(&dir) {
+        log_warn(format!("Can't create log folder {}: {err}", dir.display()));
+        return SessionLogGuard { id: None };
+    }
+    prune_sessions();
+    let id = NEXT_SESSION_ID.fetch_add(1, Ordering::Relaxed);
+    SESSIONS.lock().unwrap().push(SessionLog {
+        id,
+        dir: dir.clone(),
+        files: HashMap::new(),
+    });
+    CURRENT.set(Some(id));
+    log_info(format!("Logging session to {}", dir.display()));
+    SessionLogGuard { id: Some(id) }
+}
+
+/// Session log the calling thread writes to, to hand to threads it spawns.
+pub fn current_session() -> Option<u64> {
+    CURRENT.get()
+}
+
+/// Makes the calling thread write to the session log `id`, as returned by
+/// [`current_session`] on the thread that started the session.
+pub fn attach_session(id: Option<u64>) {
+    CURRENT.set(id);
+}
+
+fn write_launcher_line(level: &str, message: &str) {
+    let line = format!("[{}] {level} {message}", format_utc(now_secs()));
+    // Outside a session, messages still land in a shared file, which is
+    // rotated like session logs since no session cleanup ever removes it.
+    if !with_session(|session| session.write(LAUNCHER_LOG, &line)) {
+        let dir = logs_dir();
+        let _ = fs::create_dir_all(&dir);
+        if let Ok(mut file) = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(dir.join(LAUNCHER_LOG))
+        {
+            let _ = writeln!(file, "{line}");
+            if file.metadata().is_ok_and(|meta| meta.len() >= MAX_LOG_FILE_BYTES) {
+                rotate_log(&dir, LAUNCHER_LOG);
+            }
+        }
+    }
+}
+
+/// Stops or resumes echoing log messages to stdout, e.g. while a terminal UI
+/// owns the screen. The log files are written either way.
+pub fn set_console_output(enabled: bool) {
+    CONSOLE_OUTPUT.store(enabled, Ordering::Relaxed);
+}
+
+fn console_output() -> bool {
+    CONSOLE_OUTPUT.load(Ordering::Relaxed)
+}
+
+/// Turns handler author mode on or off, from the `author_mode` setting.
+pub fn set_author_mode(enabled: bool) {
+    AUTHOR_MODE.store(enabled, Ordering::Relaxed);
+}
+
+/// Whether handler author mode asks every subsystem for maximum verbosity.
+pub fn author_mode() -> bool {
+    AUTHOR_MODE.load(Ordering::Relaxed)
+}
+
+/// Prints an informational launcher message and records it in the session log.
+pub fn log_info(message: impl AsRef<str>) {
+    let message = message.as_ref();
+    if console_output() {
+        println!("[SPLIT HAPPENS] {message}");
+    }
+    write_launcher_line("INFO", message);
+}
+
+/// Prints a launcher warning and records it in the session log.
+pub fn log_warn(message: impl AsRef<str>) {
+    let message = message.as_ref();
+    if console_output() {
+        println!("[SPLIT HAPPENS][WARN] {message}");
+    }
+    write_launcher_line("WARN", message);
+}
+
+/// Prints and records a debug message, only in author mode.
+pub fn log_debug(message: impl AsRef<str>) {
+    if !author_mode() {
+        return;
+    }
+    let message = message.as_ref();
+    if console_output() {
+        println!("[SPLIT HAPPENS][DEBUG] {message}");
+    }
+    write_launcher_line("DEBUG", message);
+}
+
+/// Writes the variables the launcher set for an instance to
+/// `<profile>-env.log` in the running session. Removed ones are logged empty.
+pub fn log_instance_env<'a>(profile: &str, vars: impl Iterator<Item = (&'a str, &'a str)>) {
+    let file_name = format!("{}-env.log", file_stem(profile));
+    with_session(|session| {
+        for (key, value) in vars {
+            session.write(&file_name, &format!("{key}={value}"));
+        }
+    });
+}
+
+/// Folder of the session this thread belongs to, for tools that write their
+/// own logs, such as Proton.
+pub fn current_session_dir() -> Option<PathBuf> {
+    let mut dir = None;
+    with_session(|session| dir = Some(session.dir.clone()));
+    dir
+}
+
+/// Unused file in the running session's screenshot folder for a screenshot
+/// of `profile`, like `Alice_2025-06-01-20-31-07.png`. Creates the folder.
+pub fn session_screenshot_path(profile: &str) -> Option<PathBuf> {
+    let dir = current_session_dir()?.join(SCREENSHOTS_DIR);
+    if let Err(err) = fs::create_dir_all(&dir) {
+        log_warn(format!("Can't create screenshot folder {}: {err}", dir.display()));
+        return None;
+    }
+    let stem = format!(
+        "{}_{}",
+        file_stem(profile),
+        format_utc(now_secs()).replace([' ', ':'], "-")
+    );
+    // Two screenshots within a second get numbered.
+    (1..)
+        .map(|n| match n {
+            1 => dir.join(format!("{stem}.png")),
+            n => dir.join(format!("{stem}_{n}.png")),
+        })
+        .find(|path| !path.exists())
+}
+
+/// Echoes a line of instance output and appends it to that profile's log in
+/// the running session, tagged with the time and player slot. Highlighted
+/// lines are printed in bold red on a terminal and marked with
+/// [`HIGHLIGHT_MARK`] in the log.
+pub fn log_instance(index: usize, profile: &str, line: &str, highlight: bool) {
+    if console_output() {
+        if highlight && std::io::stdout().is_terminal() {
+            println!("\x1b[1;31m{line}\x1b[0m");
+        } else {
+            println!("{line}");
+        }
+    }
+    let mark = if highlight { HIGHLIGHT_MARK } else { "" };
+    let line = format!("[{}][P{}]{mark} {line}", format_utc(now_secs()), index + 1);
+    with_session(|session| session.write(&format!("{}.log", file_stem(profile)), &line));
+}
+
+/// Whether a line read back from an instance log was marked by
+/// [`log_instance`] as highlighted. Only the mark right after the player slot
+/// counts, since the game's own output can contain it too.
+pub fn is_highlighted_line(line: &str) -> bool {
+    let Some(rest) = line.strip_prefix('[') else {
+        return false;
+    };
+    let Some((_, rest)) = rest.split_once("][P") else {
+        return false;
+    };
+    let Some((slot, rest)) = rest.split_once(']') else {
+        return false;
+    };
+    !slot.is_empty()
+        && slot.chars().all(|c| c.is_ascii_digit())
+        && rest.starts_with(&format!("{HIGHLIGHT_MARK} "))
+}
+
+/// Adds an event to the timeline of the session this thread belongs to.
+pub fn log_event(kind: SessionEventKind, slot: Option<usize>, profile: &str, detail: &str) {
+    let event = SessionEvent {
+        timestamp_ms: SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |elapsed| elapsed.as_millis() as u64),
+        kind,
+        slot,
+        profile: profile.to_string(),
+        detail: detail.to_string(),
+    };
+    if let Ok(line) = serde_json::to_string(&event) {
+        with_session(|session| session.write(EVENTS_LOG, &line));
+    }
+}
+
+/// Timeline of the session folder `session`, oldest event first.
+pub fn load_session_events(session: &str) -> Vec<SessionEvent> {
+    let content = fs::read_to_string(logs_dir().join(session).join(EVENTS_LOG));
+    content
+        .unwrap_or_default()
+        .lines()
+        .filter_map(|line| serde_json::from_str(line).ok())
+        .collect()
+}
+
+/// Log file of `profile` in the session folder `session`, or the launcher's
+/// own log for an empty profile.
+pub fn session_log_file(session: &str, profile: &str) -> PathBuf {
+    let dir = logs_dir().join(session);
+    match profile {
+        "" => dir.join(LAUNCHER_LOG),
+        profile => dir.join(format!("{}.log", file_stem(profile))),
+    }
+}
+
+/// Time stamp log lines start with for `timestamp_ms`, to find the lines
+/// written around an event.
+pub fn log_line_stamp(timestamp_ms: u64) -> String {
+    format!("[{}]", format_utc(timestamp_ms / 1000))
+}
+
+/// One session folder under `logs/`.
+pub struct LogSession {
+    pub name: String,
+    pub files: Vec<PathBuf>,
+    /// Whether the session recorded a timeline.
+    pub has_events: bool,
+    /// Screenshots players took during the session, oldest first.
+    pub screenshots: Vec<PathBuf>,
+}
+
+/// Recorded sessions, newest first.
+pub fn list_log_sessions() -> Vec<LogSession> {
+    let mut sessions: Vec<LogSession> = fs::read_dir(logs_dir())
+        .into_iter()
+        .flatten()
+        .flatten()
+        .filter(|entry| entry.file_type().is_ok_and(|kind| kind.is_dir()))
+        .map(|entry| {
+            let mut files: Vec<PathBuf> = fs::read_dir(entry.path())
+                .into_iter()
+                .flatten()
+                .flatten()
+                .map(|file| file.path())
+                .filter(|path| path.extension().is_some_and(|ext| ext == "log"))
+                .collect();
+            // The launcher's own log first, then players alphabetically.
+            files.sort_by_key(|path| (!path.ends_with(LAUNCHER_LOG), path.clone()));
+            let mut screenshots: Vec<PathBuf> = fs::read_dir(entry.path().join(SCREENSHOTS_DIR))
+                .into_iter()
+                .flatten()
+                .flatten()
+                .map(|file| file.path())
+                .filter(|path| path.extension().is_some_and(|ext| ext == "png"))
+                .collect();
+            screenshots.sort_by_key(|path| path.metadata().and_then(|meta| meta.modified()).ok());
+            LogSession {
+                name: entry.file_name().to_string_lossy().to_string(),
+                files,
+                has_events: entry.path().join(EVENTS_LOG).is_file(),
+                screenshots,
+            }
+        })
+        .collect();
+    sessions.sort_by(|a, b| b.name.cmp(&a.name));
+    sessions
+}
+
+/// Newest session folder recorded for `game`, a handler uid or executable
+/// name as passed to [`start_session`].
+pub fn latest_game_session(game: &str) -> Option<LogSession> {
+    let stem = file_stem(game);
+    // Folder names are `<date>-<time>_<game>` and the stamp has no underscore.
+    list_log_sessions()
+        .into_iter()
+        .find(|session| session.name.split_once('_').is_some_and(|(_, name)| name == stem))
+}
+
+/// Drops the oldest session folders beyond [`MAX_LOG_SESSIONS`].
+fn prune_sessions() {
+    for session in list_log_sessions().into_iter().skip(MAX_LOG_SESSIONS) {
+        let _ = fs::remove_dir_all(logs_dir().join(session.name));
+    }
+}
@@ -0,0 +1,228 @@
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::time::{Duration, Instant};
+
+use nix::libc;
+
+use crate::session::SessionSlot;
+use crate::util::process_tree;
+
+/// How often the monitor re-reads /proc; the panel repaints more often.
+const SAMPLE_INTERVAL: Duration = Duration::from_secs(1);
+
+/// One process of an instance's tree.
+#[derive(Clone, Debug)]
+pub struct ProcessUsage {
+    pub pid: u32,
+    pub name: String,
+    /// Share of one core since the last sample, so busy games exceed 100.
+    pub cpu_percent: f32,
+    pub rss_bytes: u64,
+}
+
+/// Resource usage of one instance slot, summed over its process tree.
+#[derive(Clone, Debug)]
+pub struct InstanceUsage {
+    pub slot: usize,
+    pub processes: Vec<ProcessUsage>,
+    pub cpu_percent: f32,
+    pub rss_bytes: u64,
+    /// Busy share of the GPU engines the instance's DRM clients used, from
+    /// the fdinfo counters `gputop` reads. `None` when the driver has none.
+    pub gpu_percent: Option<f32>,
+}
+
+/// Samples CPU, memory and GPU use of running instances from /proc and
+/// /sys. Rates need two samples, so the first one reports zero usage.
+pub struct SessionMonitor {
+    clock_ticks: f32,
+    page_size: u64,
+    last_sample: Option<Instant>,
+    /// utime + stime per PID at the last sample.
+    cpu_ticks: HashMap<u32, u64>,
+    /// Engine busy nanoseconds per DRM client at the last sample.
+    gpu_ns: HashMap<String, u64>,
+    usage: Vec<InstanceUsage>,
+    /// Whole-GPU busy percentage per card from amdgpu's `gpu_busy_percent`.
+    card_busy: Vec<(String, u32)>,
+}
+
+impl SessionMonitor {
+    pub fn new() -> SessionMonitor {
+        let sysconf_or = |name: libc::c_int, fallback: libc::c_long| {
+            let value = unsafe { libc::sysconf(name) };
+            if value > 0 { value } else { fallback }
+        };
+        SessionMonitor {
+            clock_ticks: sysconf_or(libc::_SC_CLK_TCK, 100) as f32,
+            page_size: sysconf_or(libc::_SC_PAGESIZE, 4096) as u64,
+            last_sample: None,
+            cpu_ticks: HashMap::new(),
+            gpu_ns: HashMap::new(),
+            usage: Vec::new(),
+            card_busy: Vec::new(),
+        }
+    }
+
+    /// Latest per-instance usage, resampled when the last one is over
+    /// [`SAMPLE_INTERVAL`] old.
+    pub fn usage(&mut self, slots: &[SessionSlot]) -> &[InstanceUsage] {
+        if self.last_sample.is_none_or(|at| at.elapsed() >= SAMPLE_INTERVAL) {
+            self.sample(slots);
+        }
+        &self.usage
+    }
+
+    /// Whole-GPU load per card, for drivers that report it.
+    pub fn card_busy(&self) -> &[(String, u32)] {
+        &self.card_busy
+    }
+
+    fn sample(&mut self, slots: &[SessionSlot]) {
+        let now = Instant::now();
+        let elapsed = self
+            .last_sample
+            .map(|at| now.duration_since(at).as_secs_f32())
+            .filter(|secs| *secs > 0.0);
+        self.last_sample = Some(now);
+
+        let mut cpu_ticks = HashMap::new();
+        let mut gpu_ns = HashMap::new();
+        self.usage = slots
+            .iter()
+            .filter(|slot| slot.running)
+            .filter_map(|slot| Some((slot.slot, slot.pid?)))
+            .map(|(slot, root)| {
+                let mut processes = Vec::new();
+                let mut gpu_delta: Option<u64> = None;
+                for pid in process_tree(root) {
+                    let Some((name, ticks)) = read_cpu_ticks(pid) else {
+                        continue;
+                    };
+                    let cpu_percent = match (elapsed, self.cpu_ticks.get(&pid)) {
+                        (Some(secs), Some(before)) => {
+                            ticks.saturating_sub(*before) as f32 / self.clock_ticks / secs * 100.0
+                        }
+                        _ => 0.0,
+                    };
+                    cpu_ticks.insert(pid, ticks);
+
+                    for (client, ns) in read_drm_clients(pid) {
+                        // Several fds can share one DRM client; count it once.
+                        if gpu_ns.insert(client.clone(), ns).is_some() {
+                            continue;
+                        }
+                        let before = self.gpu_ns.get(&client).copied().unwrap_or(ns);
+                        *gpu_delta.get_or_insert(0) += ns.saturating_sub(before);
+                    }
+
+                    processes.push(ProcessUsage {
+                        pid,
+                        name,
+                        cpu_percent,
+                        rss_bytes: read_rss_pages(pid) * self.page_size,
+                    });
+                }
+                let gpu_percent = gpu_delta.and_then(|ns| {
+                    let secs = elapsed?;
+                    Some((ns as f32 / 1e9 / secs * 100.0).min(100.0))
+                });
+                InstanceUsage {
+                    slot,
+                    cpu_percent: processes.iter().map(|p| p.cpu_percent).sum(),
+                    rss_bytes: processes.iter().map(|p| p.rss_bytes).sum(),
+                    processes,
+                    gpu_percent,
+                }
+            })
+            .collect();
+        self.cpu_ticks = cpu_ticks;
+        self.gpu_ns = gpu_ns;
+        self.card_busy = read_card_busy();
+    }
+}
+
+/// Command name and utime + stime of `pid`.
+fn read_cpu_ticks(pid: u32) -> Option<(String, u64)> {
+    let stat = fs::read_to_string(format!("/proc/{pid}/stat")).ok()?;
+    let (head, rest) = stat.rsplit_once(')')?;
+    let name = head.split_once('(')?.1.to_string();
+    // Fields after the command name start at the state, so utime and stime
+    // (fields 14 and 15 of stat) land at 11 and 12.
+    let fields: Vec<&str> = rest.split_whitespace().collect();
+    let utime: u64 = fields.get(11)?.parse().ok()?;
+    let stime: u64 = fields.get(12)?.parse().ok()?;
+    Some((name, utime + stime))
+}
+
+fn read_rss_pages(pid: u32) -> u64 {
+    fs::read_to_string(format!("/proc/{pid}/statm"))
+        .ok()
+        .and_then(|statm| statm.split_whitespace().nth(1)?.parse().ok())
+        .unwrap_or(0)
+}
+
+/// Busy nanoseconds of every engine, per DRM client the process has open,
+/// from the `drm-engine-*` keys of `/proc/<pid>/fdinfo`.
+fn read_drm_clients(pid: u32) -> Vec<(String, u64)> {
+    let Ok(entries) = fs::read_dir(format!("/proc/{pid}/fdinfo")) else {
+        return Vec::new();
+    };
+    let mut seen = HashSet::new();
+    let mut clients = Vec::new();
+    for entry in entries.flatten() {
+        let Ok(info) = fs::read_to_string(entry.path()) else {
+            continue;
+        };
+        let mut client = None;
+        let mut driver = "";
+        let mut busy_ns = 0u64;
+        for line in info.lines() {
+            let Some((key, value)) = line.split_once(':') else {
+                continue;
+            };
+            let value = value.trim();
+            match key {
+                "drm-client-id" => client = Some(value.to_string()),
+                "drm-driver" => driver = value,
+                key if key.starts_with("drm-engine-")
+                    && !key.starts_with("drm-engine-capacity") =>
+                {
+                    busy_ns += value
+                        .split_whitespace()
+                        .next()
+                        .and_then(|ns| ns.parse::<u64>().ok())
+                        .unwrap_or(0);
+                }
+                _ => {}
+            }
+        }
+        // Client ids are only unique per driver.
+        if let Some(client) = client.map(|id| format!("{driver}:{id}")) {
+            if seen.insert(client.clone()) {
+                clients.push((client, busy_ns));
+            }
+        }
+    }
+    clients
+}
+
+/// `gpu_busy_percent` of every card that exposes it.
+fn read_card_busy() -> Vec<(String, u32)> {
+    let Ok(entries) = fs::read_dir("/sys/class/drm") else {
+        return Vec::new();
+    };
+    let mut cards: Vec<(String, u32)> = entries
+        .flatten()
+        .filter_map(|entry| {
+            let name = entry.file_name().to_str()?.to_string();
+            if !name.starts_with("card") || name.contains('-') {
+                return None;
+            }
+            let busy = fs::read_to_string(entry.path().join("device/gpu_busy_percent")).ok()?;
+            Some((name, busy.trim().parse().ok()?))
+        })
+        .collect();
+    cards.sort();
+    cards
+}
@@ -23,6 +23,15 @@ pub static PATH_APP: LazyLock<PathBuf> = LazyLock::new(|| {
     PATH_LOCAL_SHARE.join("split-happens")
 });
 
+/// System-wide handler directory shipped by distro packages or admins. It is
+/// scanned read-only and any user handler with the same uid takes precedence.
+pub static PATH_SYSTEM_HANDLERS: LazyLock<PathBuf> = LazyLock::new(|| {
+    if let Ok(dir) = env::var("SPLIT_HAPPENS_SYSTEM_HANDLERS") {
+        return PathBuf::from(dir);
+    }
+    PathBuf::from("/usr/share/split-happens/handlers")
+});
+
 pub static PATH_STEAM: LazyLock<PathBuf> = LazyLock::new(|| {
     if let Ok(steamdir) = steamlocate::SteamDir::locate() {
         let steam_path = steamdir.path().to_path_buf();
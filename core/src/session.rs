@@ -0,0 +1,414 @@
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::{Duration, Instant};
+
+use nix::sys::signal::{Signal, kill};
+use nix::unistd::Pid;
+
+use crate::input::DeviceInfo;
+use crate::logging::{self, log_warn};
+use crate::util::ProfileLock;
+
+/// How often a cancellable wait checks the cancel flag.
+const CANCEL_CHECK_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Source of [`SessionControl::id`], unique for the lifetime of the process.
+static NEXT_SESSION_ID: AtomicU64 = AtomicU64::new(1);
+
+/// Sessions launched from this process, see [`SessionManager::global`].
+static SESSION_MANAGER: SessionManager = SessionManager::new();
+
+/// Requests the UI can send to a running session. The launch thread drains
+/// them from its monitor loop, so they are applied between child polls.
+#[derive(Clone)]
+pub enum SessionCommand {
+    /// Gracefully stop the instance in `slot` and respawn it for `profile`.
+    /// `"Guest"` picks the next free guest name.
+    SwapProfile { slot: usize, profile: String },
+    /// Spawn one more instance for `profile` driven by `device`, then
+    /// re-split the screen and CPU cores across every slot.
+    AddInstance { profile: String, device: DeviceInfo },
+    /// Pin every thread of the instance in `slot` to `cores`.
+    SetAffinity { slot: usize, cores: Vec<usize> },
+    /// Apply `nice` to every thread of the instance in `slot`.
+    SetNiceness { slot: usize, nice: i32 },
+    /// Restore the launch-time core split and priority on every slot.
+    RebalanceCpu,
+    /// Gracefully stop every instance and end the session.
+    StopAll,
+    /// Respawn every slot whose instance crashed and wasn't restarted.
+    RestartCrashed,
+    /// Switch the gamescope frame limit of every instance on or off.
+    ToggleFpsLimit,
+    /// Save a screenshot of the instance in `slot` to the session's gallery.
+    Screenshot { slot: usize },
+}
+
+/// How far an instance has come since it was started.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum BootStage {
+    /// The process runs but has no window yet.
+    Spawned,
+    /// Its window is up; the game may still be loading.
+    WindowShown,
+    /// It kept running for a while after its window appeared.
+    Stable,
+}
+
+impl BootStage {
+    /// Short status shown next to the slot while it runs.
+    pub fn label(self) -> &'static str {
+        match self {
+            BootStage::Spawned => "starting, no window yet",
+            BootStage::WindowShown => "window open, loading",
+            BootStage::Stable => "running",
+        }
+    }
+}
+
+/// Snapshot of one instance slot, published by the launch thread.
+#[derive(Clone, Debug)]
+pub struct SessionSlot {
+    pub slot: usize,
+    pub profile: String,
+    pub pid: Option<u32>,
+    pub running: bool,
+    /// Set while the slot is being stopped for a profile swap.
+    pub swapping: bool,
+    /// Paths of the input devices assigned to the slot.
+    pub devices: Vec<String>,
+    /// Those of `devices` that disconnected and haven't come back yet.
+    pub disconnected: Vec<String>,
+    /// CPU cores the instance may currently run on.
+    pub cores: Vec<usize>,
+    /// Current nice value of the instance.
+    pub nice: Option<i32>,
+    /// Startup progress, for telling a slow start from a stuck one.
+    pub boot: BootStage,
+    /// Seconds since the instance was last started.
+    pub uptime_secs: u64,
+}
+
+impl SessionSlot {
+    /// Startup status of a running slot, with the time spent so far while it
+    /// is still coming up.
+    pub fn boot_status(&self) -> String {
+        match self.boot {
+            BootStage::Stable => self.boot.label().to_string(),
+            stage => format!("{} ({} s)", stage.label(), self.uptime_secs),
+        }
+    }
+}
+
+/// Shared handle between the UI and the launch thread of one session.
+#[derive(Clone)]
+pub struct SessionControl {
+    /// Tells concurrent sessions apart, e.g. in KWin script names.
+    id: u64,
+    commands: Arc<Mutex<Vec<SessionCommand>>>,
+    slots: Arc<Mutex<Vec<SessionSlot>>>,
+    /// Set by the UI when the launch timed out; preparation stops at its
+    /// next checkpoint.
+    cancelled: Arc<AtomicBool>,
+    /// Set by the Stop Session button, as opposed to a launch that timed out.
+    stop_requested: Arc<AtomicBool>,
+    /// What a long preparation step is doing right now, for the loading screen.
+    progress: Arc<Mutex<Option<String>>>,
+    /// When the launch thread last showed it is getting somewhere; the UI
+    /// times a launch out by this rather than by its total duration.
+    heartbeat: Arc<Mutex<Instant>>,
+    /// Set while the session is suspended for lack of input.
+    idle: Arc<AtomicBool>,
+    /// Titles and texts of warnings for the UI to show, so the launch thread
+    /// doesn't block on a dialog.
+    notices: Arc<Mutex<Vec<(String, String)>>>,
+}
+
+impl Default for SessionControl {
+    fn default() -> Self {
+        SessionControl {
+            id: NEXT_SESSION_ID.fetch_add(1, Ordering::Relaxed),
+            commands: Arc::default(),
+            slots: Arc::default(),
+            cancelled: Arc::default(),
+            stop_requested: Arc::default(),
+            progress: Arc::default(),
+            heartbeat: Arc::new(Mutex::new(Instant::now())),
+            idle: Arc::default(),
+            notices: Arc::default(),
+        }
+    }
+}
+
+impl SessionControl {
+    pub fn id(&self) -> u64 {
+        self.id
+    }
+
+    /// Queues a command for the launch thread.
+    pub fn send(&self, command: SessionCommand) {
+        if let Ok(mut commands) = self.commands.lock() {
+            commands.push(command);
+        }
+    }
+
+    /// Takes every pending command; called by the launch thread.
+    pub fn take_commands(&self) -> Vec<SessionCommand> {
+        self.commands
+            .lock()
+            .map(|mut commands| std::mem::take(&mut *commands))
+            .unwrap_or_default()
+    }
+
+    /// Replaces the published slot list; called by the launch thread.
+    pub fn publish(&self, slots: Vec<SessionSlot>) {
+        if let Ok(mut published) = self.slots.lock() {
+            *published = slots;
+        }
+    }
+
+    /// Latest slot snapshot. Empty until every instance has been spawned.
+    pub fn slots(&self) -> Vec<SessionSlot> {
+        self.slots
+            .lock()
+            .map(|slots| slots.clone())
+            .unwrap_or_default()
+    }
+
+    /// Describes the current preparation step, or clears it with `None`;
+    /// called by the launch thread.
+    pub fn set_progress(&self, progress: Option<String>) {
+        if let Ok(mut current) = self.progress.lock() {
+            *current = progress;
+        }
+        self.beat();
+    }
+
+    /// Tells the UI the launch is still making progress; called by the
+    /// launch thread between and during long steps.
+    pub fn beat(&self) {
+        if let Ok(mut heartbeat) = self.heartbeat.lock() {
+            *heartbeat = Instant::now();
+        }
+    }
+
+    /// Time since the launch thread last reported progress.
+    pub fn since_heartbeat(&self) -> Duration {
+        self.heartbeat
+            .lock()
+            .map(|heartbeat| heartbeat.elapsed())
+            .unwrap_or_default()
+    }
+
+    /// Latest preparation step, while one is reported.
+    pub fn progress(&self) -> Option<String> {
+        self.progress.lock().ok().and_then(|progress| progress.clone())
+    }
+
+    /// Marks the session suspended for lack of input, or awake again; called
+    /// by the launch thread.
+    pub fn set_idle(&self, idle: bool) {
+        self.idle.store(idle, Ordering::Relaxed);
+    }
+
+    pub fn is_idle(&self) -> bool {
+        self.idle.load(Ordering::Relaxed)
+    }
+
+    /// Queues a warning for the UI; called by the launch thread.
+    pub fn notify(&self, title: &str, text: &str) {
+        if let Ok(mut notices) = self.notices.lock() {
+            notices.push((title.to_string(), text.to_string()));
+        }
+    }
+
+    /// Takes every queued warning as title and text.
+    pub fn take_notices(&self) -> Vec<(String, String)> {
+        self.notices
+            .lock()
+            .map(|mut notices| std::mem::take(&mut *notices))
+            .unwrap_or_default()
+    }
+
+    /// Asks the launch thread to stop preparing the session.
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::Relaxed);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::Relaxed)
+    }
+
+    /// Ends the session however far it got: a launch still starting
+    /// instances is cancelled and stops the ones it started, a running
+    /// session closes every instance.
+    pub fn request_stop(&self) {
+        self.stop_requested.store(true, Ordering::Relaxed);
+        self.cancel();
+        self.send(SessionCommand::StopAll);
+    }
+
+    pub fn is_stop_requested(&self) -> bool {
+        self.stop_requested.load(Ordering::Relaxed)
+    }
+
+    /// Sleeps for `duration` unless the launch is cancelled first. Returns
+    /// whether it was cancelled. A deliberate wait counts as progress.
+    pub fn wait_unless_cancelled(&self, duration: Duration) -> bool {
+        let deadline = Instant::now() + duration;
+        while !self.is_cancelled() {
+            self.beat();
+            let now = Instant::now();
+            if now >= deadline {
+                return false;
+            }
+            std::thread::sleep(CANCEL_CHECK_INTERVAL.min(deadline - now));
+        }
+        true
+    }
+}
+
+/// What has to be torn down when a session ends or the process is
+/// interrupted.
+struct ManagedSession {
+    id: u64,
+    /// Instance PIDs; each leads its own process group.
+    child_pids: Arc<Mutex<Vec<u32>>>,
+    /// Profile locks held for the session's instances.
+    locks: Arc<Mutex<Vec<ProfileLock>>>,
+    /// Log session the instances write to, see `logging::current_session`.
+    log_session: Option<u64>,
+}
+
+impl ManagedSession {
+    /// Sends SIGTERM to every instance process group and releases the locks.
+    fn cleanup(&self) {
+        if let Ok(pids) = self.child_pids.lock() {
+            for pid in pids.iter() {
+                let _ = kill(Pid::from_raw(-(*pid as i32)), Signal::SIGTERM);
+                // Instances paused by idle detection only handle it once resumed.
+                let _ = kill(Pid::from_raw(-(*pid as i32)), Signal::SIGCONT);
+            }
+        }
+        if let Ok(mut locks) = self.locks.lock() {
+            for lock in locks.iter() {
+                lock.cleanup();
+            }
+            locks.clear();
+        }
+    }
+}
+
+/// Owns the child PIDs, profile locks and log sessions of every running
+/// session, so they can be cleaned up one session at a time or all at once
+/// on Ctrl+C.
+pub struct SessionManager {
+    sessions: Mutex<Vec<ManagedSession>>,
+    ctrlc_installed: OnceLock<()>,
+}
+
+impl Default for SessionManager {
+    fn default() -> Self {
+        SessionManager::new()
+    }
+}
+
+impl SessionManager {
+    pub const fn new() -> Self {
+        SessionManager {
+            sessions: Mutex::new(Vec::new()),
+            ctrlc_installed: OnceLock::new(),
+        }
+    }
+
+    /// The manager shared by every launch in this process.
+    pub fn global() -> &'static SessionManager {
+        &SESSION_MANAGER
+    }
+
+    /// Starts tracking a session, replacing an earlier entry with the same id.
+    pub fn register(
+        &self,
+        id: u64,
+        child_pids: Arc<Mutex<Vec<u32>>>,
+        locks: Arc<Mutex<Vec<ProfileLock>>>,
+        log_session: Option<u64>,
+    ) {
+        if let Ok(mut sessions) = self.sessions.lock() {
+            sessions.retain(|session| session.id != id);
+            sessions.push(ManagedSession {
+                id,
+                child_pids,
+                locks,
+                log_session,
+            });
+        }
+    }
+
+    /// Stops tracking a session without touching its processes, e.g. when a
+    /// cancelled launch leaves started instances for the user to stop.
+    pub fn unregister(&self, id: u64) {
+        if let Ok(mut sessions) = self.sessions.lock() {
+            sessions.retain(|session| session.id != id);
+        }
+    }
+
+    /// Stops a session's instances, releases its locks and stops tracking it.
+    pub fn finish(&self, id: u64) {
+        let finished = self.sessions.lock().ok().and_then(|mut sessions| {
+            let index = sessions.iter().position(|session| session.id == id)?;
+            Some(sessions.remove(index))
+        });
+        if let Some(session) = finished {
+            session.cleanup();
+        }
+    }
+
+    /// Cleans up every tracked session; what the Ctrl+C handler runs.
+    pub fn cleanup_all(&self) {
+        if let Ok(sessions) = self.sessions.lock() {
+            for session in sessions.iter() {
+                // Note the interruption in the session's own log.
+                logging::attach_session(session.log_session);
+                log_warn("Interrupted, stopping the session's instances.");
+                session.cleanup();
+            }
+        }
+    }
+
+    /// Whether any session is still tracked, e.g. another game launched
+    /// alongside the one that just ended.
+    pub fn any_running(&self) -> bool {
+        self.sessions
+            .lock()
+            .map(|sessions| !sessions.is_empty())
+            .unwrap_or(false)
+    }
+
+    /// Number of tracked sessions.
+    pub fn running_count(&self) -> usize {
+        self.sessions.lock().map(|sessions| sessions.len()).unwrap_or(0)
+    }
+
+    /// Id of the session that started the instance `pid`, if any.
+    pub fn session_of(&self, pid: u32) -> Option<u64> {
+        let sessions = self.sessions.lock().ok()?;
+        sessions
+            .iter()
+            .find(|session| {
+                let pids = session.child_pids.lock();
+                pids.is_ok_and(|pids| pids.contains(&pid))
+            })
+            .map(|session| session.id)
+    }
+
+    /// Installs the process-wide Ctrl+C handler on first use. The ctrlc crate
+    /// only allows one handler per process, so every session shares it.
+    pub fn install_ctrlc_handler(&'static self) -> Result<(), ctrlc::Error> {
+        if self.ctrlc_installed.get().is_none() {
+            ctrlc::set_handler(move || self.cleanup_all())?;
+            let _ = self.ctrlc_installed.set(());
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,72 @@
+use std::path::Path;
+
+use crate::logging::log_warn;
+
+/// Title of the dialog showing an [`anticheat_warning`].
+pub const ANTICHEAT_TITLE: &str = "Anti-cheat detected";
+
+/// Anti-cheat that checks the game against its vendor's servers and can't
+/// be satisfied by the Steam and Epic emulators splitscreen play relies on.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum AntiCheat {
+    EasyAntiCheat,
+    BattlEye,
+}
+
+impl AntiCheat {
+    pub fn label(self) -> &'static str {
+        match self {
+            AntiCheat::EasyAntiCheat => "Easy Anti-Cheat",
+            AntiCheat::BattlEye => "BattlEye",
+        }
+    }
+}
+
+/// Lowercase file and folder names only these anti-cheats ship.
+const ANTICHEAT_MARKERS: &[(&str, AntiCheat)] = &[
+    ("easyanticheat", AntiCheat::EasyAntiCheat),
+    ("easyanticheat_x64.dll", AntiCheat::EasyAntiCheat),
+    ("easyanticheat_x86.dll", AntiCheat::EasyAntiCheat),
+    ("easyanticheat_x64.so", AntiCheat::EasyAntiCheat),
+    ("easyanticheat_setup.exe", AntiCheat::EasyAntiCheat),
+    ("easyanticheat_eos_setup.exe", AntiCheat::EasyAntiCheat),
+    ("start_protected_game.exe", AntiCheat::EasyAntiCheat),
+    ("battleye", AntiCheat::BattlEye),
+    ("beservice.exe", AntiCheat::BattlEye),
+    ("beservice_x64.exe", AntiCheat::BattlEye),
+    ("beclient.dll", AntiCheat::BattlEye),
+    ("beclient_x64.dll", AntiCheat::BattlEye),
+];
+
+/// Anti-cheats installed in the game folder `dir`. Only the first levels are
+/// searched, where games keep them, so large installs are scanned quickly.
+pub fn detect_anticheat(dir: &Path) -> Vec<AntiCheat> {
+    let mut found = Vec::new();
+    for entry in walkdir::WalkDir::new(dir).max_depth(4).into_iter().flatten() {
+        let name = entry.file_name().to_string_lossy().to_lowercase();
+        let Some((_, anticheat)) = ANTICHEAT_MARKERS.iter().find(|(marker, _)| *marker == name)
+        else {
+            continue;
+        };
+        if !found.contains(anticheat) {
+            found.push(*anticheat);
+        }
+    }
+    found
+}
+
+/// Scans the folder of `game` and, if it ships an anti-cheat, logs it and
+/// returns why splitscreen play won't work, for the frontend to show before
+/// anyone spends an evening on it.
+pub fn anticheat_warning(game: &str, dir: &Path) -> Option<String> {
+    let found = detect_anticheat(dir);
+    if found.is_empty() {
+        return None;
+    }
+    let names: Vec<&str> = found.iter().map(|anticheat| anticheat.label()).collect();
+    let names = names.join(" and ");
+    log_warn(format!("{game} ships {names} in {}", dir.display()));
+    Some(format!(
+        "{game} ships {names}. Its anti-cheat checks the game against online services that the Steam and Epic emulators Split Happens uses can't answer, so the game will most likely refuse to start or to connect the players. Some games have an offline or anti-cheat-free mode, or a community launcher that skips it; the handler's notes usually say so."
+    ))
+}
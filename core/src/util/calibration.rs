@@ -0,0 +1,129 @@
+use std::collections::HashMap;
+use std::error::Error;
+use std::fs::File;
+use std::io::BufReader;
+
+use serde::{Deserialize, Serialize};
+
+use super::filesystem::write_atomic;
+use crate::paths::*;
+
+/// Navigation threshold that matches the historical hardcoded 12000/32767
+/// stick deadzone, so uncalibrated pads navigate the UI exactly as before.
+pub const DEFAULT_NAV_THRESHOLD: f32 = 12_000.0 / 32_767.0;
+
+/// Default in-game dead zone applied by the pad proxy for calibrated devices.
+pub const DEFAULT_STICK_DEADZONE: f32 = 0.05;
+
+/// Raw range of a single analog axis as observed during calibration.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+pub struct AxisRange {
+    pub min: i32,
+    pub center: i32,
+    pub max: i32,
+}
+
+impl AxisRange {
+    /// Builds a symmetric range around the midpoint, used for devices that
+    /// were never calibrated.
+    pub fn from_bounds(min: i32, max: i32) -> Self {
+        Self {
+            min,
+            center: min + (max - min) / 2,
+            max,
+        }
+    }
+
+    /// Maps a raw value to `-1.0..=1.0` relative to the calibrated center so
+    /// sticks with an off-center rest position still read as neutral.
+    pub fn normalize(&self, value: i32) -> f32 {
+        let value = value.clamp(self.min, self.max);
+        if value >= self.center {
+            let span = (self.max - self.center).max(1) as f32;
+            (value - self.center) as f32 / span
+        } else {
+            let span = (self.center - self.min).max(1) as f32;
+            (value - self.center) as f32 / span
+        }
+    }
+
+    /// Inverse of [`AxisRange::normalize`], producing a raw value within
+    /// `target`'s bounds for re-emitting through a virtual device.
+    pub fn denormalize(target: &AxisRange, normalized: f32) -> i32 {
+        let normalized = normalized.clamp(-1.0, 1.0);
+        if normalized >= 0.0 {
+            target.center + ((target.max - target.center) as f32 * normalized).round() as i32
+        } else {
+            target.center + ((target.center - target.min) as f32 * normalized).round() as i32
+        }
+    }
+}
+
+/// Per-device calibration, keyed by a stable identity so it follows the pad
+/// across reconnects and changing `/dev/input/event*` numbers.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct DeviceCalibration {
+    /// Dead zone applied to stick output forwarded to games (fraction of travel).
+    pub deadzone: f32,
+    /// How far a stick must travel before it counts as a menu navigation press.
+    pub nav_threshold: f32,
+    /// Observed ranges per absolute axis code.
+    #[serde(default)]
+    pub axes: HashMap<u16, AxisRange>,
+}
+
+impl Default for DeviceCalibration {
+    fn default() -> Self {
+        Self {
+            deadzone: DEFAULT_STICK_DEADZONE,
+            nav_threshold: DEFAULT_NAV_THRESHOLD,
+            axes: HashMap::new(),
+        }
+    }
+}
+
+impl DeviceCalibration {
+    /// Rescales a normalized value so the dead zone maps to zero and the
+    /// remaining travel still reaches full deflection.
+    pub fn apply_deadzone(&self, normalized: f32) -> f32 {
+        let deadzone = self.deadzone.clamp(0.0, 0.9);
+        let magnitude = normalized.abs();
+        if magnitude <= deadzone {
+            return 0.0;
+        }
+        normalized.signum() * ((magnitude - deadzone) / (1.0 - deadzone)).min(1.0)
+    }
+}
+
+/// Loads every stored calibration from `PATH_APP/calibration.json`.
+pub fn load_calibrations() -> HashMap<String, DeviceCalibration> {
+    File::open(PATH_APP.join("calibration.json"))
+        .ok()
+        .and_then(|file| serde_json::from_reader(BufReader::new(file)).ok())
+        .unwrap_or_default()
+}
+
+/// Returns the stored calibration for a device identity, if any.
+pub fn load_calibration(identity: &str) -> Option<DeviceCalibration> {
+    load_calibrations().remove(identity)
+}
+
+/// Persists the calibration for a device identity, replacing any earlier one.
+pub fn save_calibration(
+    identity: &str,
+    calibration: &DeviceCalibration,
+) -> Result<(), Box<dyn Error>> {
+    let mut all = load_calibrations();
+    all.insert(identity.to_string(), calibration.clone());
+    write_atomic(&PATH_APP.join("calibration.json"), serde_json::to_vec_pretty(&all)?)?;
+    Ok(())
+}
+
+/// Drops the stored calibration so the device falls back to defaults.
+pub fn remove_calibration(identity: &str) -> Result<(), Box<dyn Error>> {
+    let mut all = load_calibrations();
+    if all.remove(identity).is_some() {
+        write_atomic(&PATH_APP.join("calibration.json"), serde_json::to_vec_pretty(&all)?)?;
+    }
+    Ok(())
+}
@@ -0,0 +1,108 @@
+use std::error::Error;
+use std::io::{BufRead, BufReader};
+use std::process::{Child, ChildStdin, Command, Stdio};
+use std::sync::{Arc, Mutex};
+
+use crate::input::PadButton;
+use crate::logging::log_warn;
+
+/// CEC opcode a TV sends when a remote key goes down.
+const USER_CONTROL_PRESSED: u8 = 0x44;
+
+/// A TV remote key the launcher reacts to.
+pub enum CecKey {
+    /// Handled exactly like the pad button.
+    Nav(PadButton),
+    /// The colored keys start the first four presets, in the order the home
+    /// page lists them.
+    Preset(usize),
+}
+
+/// TV remote input over HDMI-CEC. Runs libcec's `cec-client` as the playback
+/// device of the TV and queues the keys it reports; the UI takes them on its
+/// next frame. The client is stopped when dropped.
+pub struct CecRemote {
+    child: Child,
+    // `cec-client` quits once its command input closes, so it is held open.
+    _stdin: Option<ChildStdin>,
+    keys: Arc<Mutex<Vec<CecKey>>>,
+}
+
+impl CecRemote {
+    /// Starts `cec-client` on the first CEC adapter it finds.
+    pub fn start() -> Result<Self, Box<dyn Error>> {
+        let mut child = Command::new("cec-client")
+            .args(["-t", "p", "-o", "Split Happens", "-d", "8"])
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::null())
+            .spawn()
+            .map_err(|err| format!("Couldn't start cec-client (is libcec installed?): {err}"))?;
+        let stdout = child.stdout.take().ok_or("cec-client has no output")?;
+        let keys = Arc::new(Mutex::new(Vec::new()));
+
+        let queue = keys.clone();
+        std::thread::spawn(move || {
+            for line in BufReader::new(stdout).lines().map_while(Result::ok) {
+                // Missing adapters are reported as "autodetect FAILED".
+                if line.starts_with("ERROR") || line.contains("FAILED") {
+                    log_warn(format!("HDMI-CEC: {}", line.trim()));
+                }
+                if let Some(key) = key_pressed(&line).and_then(cec_key) {
+                    queue.lock().unwrap().push(key);
+                }
+            }
+        });
+
+        Ok(CecRemote {
+            _stdin: child.stdin.take(),
+            child,
+            keys,
+        })
+    }
+
+    /// Returns and clears the keys pressed since the last call.
+    pub fn take_keys(&self) -> Vec<CecKey> {
+        std::mem::take(&mut *self.keys.lock().unwrap())
+    }
+}
+
+impl Drop for CecRemote {
+    fn drop(&mut self) {
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+    }
+}
+
+/// Key code of a "user control pressed" message in a `cec-client` traffic
+/// line, like `TRAFFIC: [  4213]	>> 04:44:01`.
+fn key_pressed(line: &str) -> Option<u8> {
+    let (_, frame) = line.split_once(">> ")?;
+    let mut bytes = frame.trim().split(':').map(|byte| u8::from_str_radix(byte, 16));
+    let _header = bytes.next()?.ok()?;
+    if bytes.next()?.ok()? != USER_CONTROL_PRESSED {
+        return None;
+    }
+    bytes.next()?.ok()
+}
+
+/// What a CEC user control code does in the launcher.
+fn cec_key(code: u8) -> Option<CecKey> {
+    let button = match code {
+        0x00 => PadButton::ABtn,
+        0x01 => PadButton::Up,
+        0x02 => PadButton::Down,
+        0x03 => PadButton::Left,
+        0x04 => PadButton::Right,
+        // Exit and Back.
+        0x0D | 0x91 => PadButton::BBtn,
+        // Root menu and Setup menu.
+        0x09 | 0x0A => PadButton::YBtn,
+        // Play starts the instances of the open game.
+        0x44 => PadButton::StartBtn,
+        // Blue, red, green and yellow.
+        0x71..=0x74 => return Some(CecKey::Preset((code - 0x71) as usize)),
+        _ => return None,
+    };
+    Some(CecKey::Nav(button))
+}
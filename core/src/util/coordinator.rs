@@ -0,0 +1,253 @@
+use std::collections::HashMap;
+use std::error::Error;
+use std::net::{Ipv4Addr, SocketAddrV4, UdpSocket};
+use std::os::fd::AsRawFd;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use nix::sys::socket::{
+    AddressFamily, SockFlag, SockType, SockaddrIn, bind, setsockopt, socket, sockopt,
+};
+use serde_json::{Value, json};
+
+use crate::logging::log_warn;
+
+/// UDP port every Split Happens coordinator listens and broadcasts on.
+const COORDINATOR_PORT: u16 = 47360;
+/// How often each machine re-announces what it is hosting.
+const ANNOUNCE_INTERVAL: Duration = Duration::from_secs(2);
+/// Peers that stay silent longer than this are dropped from the list.
+const PEER_TIMEOUT: Duration = Duration::from_secs(7);
+
+/// Another machine running Split Happens on the same LAN.
+#[derive(Clone, Debug)]
+pub struct CoordinatorPeer {
+    pub host: String,
+    pub game: String,
+    pub game_uid: String,
+    pub players: usize,
+    pub last_seen: Instant,
+}
+
+/// A synchronized launch announced by a peer (or by this machine).
+#[derive(Clone, Debug)]
+pub struct CoordinatorCountdown {
+    pub host: String,
+    pub game_uid: String,
+    pub launch_at: Instant,
+    /// Whether this machine takes part. Countdowns from peers only launch
+    /// once the host joins them, so nobody on the LAN can start a game here.
+    pub accepted: bool,
+}
+
+/// What this machine currently advertises to its peers.
+#[derive(Clone, Default)]
+struct LocalStatus {
+    game: String,
+    game_uid: String,
+    players: usize,
+}
+
+/// Discovers other Split Happens machines via UDP broadcast and relays
+/// countdown-to-launch signals so LAN titles start simultaneously. The
+/// background thread stops when the coordinator is dropped.
+pub struct Coordinator {
+    socket: UdpSocket,
+    node_id: String,
+    hostname: String,
+    status: Arc<Mutex<Option<LocalStatus>>>,
+    peers: Arc<Mutex<HashMap<String, CoordinatorPeer>>>,
+    countdown: Arc<Mutex<Option<CoordinatorCountdown>>>,
+    stop: Arc<AtomicBool>,
+}
+
+/// Reads the machine hostname so peers see a recognizable name.
+fn local_hostname() -> String {
+    std::fs::read_to_string("/proc/sys/kernel/hostname")
+        .map(|name| name.trim().to_string())
+        .ok()
+        .filter(|name| !name.is_empty())
+        .unwrap_or_else(|| "split-happens".to_string())
+}
+
+/// Sends a JSON message to the LAN broadcast address.
+fn broadcast(socket: &UdpSocket, message: &Value) {
+    let target = SocketAddrV4::new(Ipv4Addr::BROADCAST, COORDINATOR_PORT);
+    if let Err(err) = socket.send_to(message.to_string().as_bytes(), target) {
+        log_warn(format!("Coordinator broadcast failed: {err}"));
+    }
+}
+
+/// Binds the coordinator port with SO_REUSEADDR, so a restarted coordinator
+/// or a second Split Happens on the same machine can share it.
+fn bind_coordinator_socket() -> Result<UdpSocket, Box<dyn Error>> {
+    let fd = socket(AddressFamily::Inet, SockType::Datagram, SockFlag::SOCK_CLOEXEC, None)?;
+    setsockopt(&fd, sockopt::ReuseAddr, &true)?;
+    let addr = SocketAddrV4::new(Ipv4Addr::UNSPECIFIED, COORDINATOR_PORT);
+    bind(fd.as_raw_fd(), &SockaddrIn::from(addr))?;
+    Ok(UdpSocket::from(fd))
+}
+
+impl Coordinator {
+    /// Binds the coordinator port and starts the announce/listen thread.
+    pub fn start() -> Result<Self, Box<dyn Error>> {
+        let socket = bind_coordinator_socket()
+            .map_err(|err| format!("Couldn't bind coordinator port {COORDINATOR_PORT}: {err}"))?;
+        socket.set_broadcast(true)?;
+        socket.set_read_timeout(Some(Duration::from_millis(500)))?;
+
+        let coordinator = Self {
+            socket: socket.try_clone()?,
+            node_id: format!("{}-{}", local_hostname(), std::process::id()),
+            hostname: local_hostname(),
+            status: Arc::new(Mutex::new(None)),
+            peers: Arc::new(Mutex::new(HashMap::new())),
+            countdown: Arc::new(Mutex::new(None)),
+            stop: Arc::new(AtomicBool::new(false)),
+        };
+
+        let node_id = coordinator.node_id.clone();
+        let hostname = coordinator.hostname.clone();
+        let status = coordinator.status.clone();
+        let peers = coordinator.peers.clone();
+        let countdown = coordinator.countdown.clone();
+        let stop = coordinator.stop.clone();
+
+        std::thread::spawn(move || {
+            let mut last_announce: Option<Instant> = None;
+            let mut buf = [0u8; 2048];
+            while !stop.load(Ordering::Relaxed) {
+                // Re-announce on a fixed cadence so peers can expire stale hosts.
+                if last_announce.is_none_or(|at| at.elapsed() >= ANNOUNCE_INTERVAL) {
+                    let current = status.lock().unwrap().clone().unwrap_or_default();
+                    broadcast(
+                        &socket,
+                        &json!({
+                            "type": "announce",
+                            "node": node_id,
+                            "host": hostname,
+                            "game": current.game,
+                            "game_uid": current.game_uid,
+                            "players": current.players,
+                        }),
+                    );
+                    last_announce = Some(Instant::now());
+                }
+
+                let Ok((len, _)) = socket.recv_from(&mut buf) else {
+                    continue;
+                };
+                let Ok(message) = serde_json::from_slice::<Value>(&buf[..len]) else {
+                    continue;
+                };
+                let node = message["node"].as_str().unwrap_or_default();
+                if node.is_empty() || node == node_id {
+                    continue;
+                }
+                let host = message["host"].as_str().unwrap_or(node).to_string();
+
+                match message["type"].as_str() {
+                    Some("announce") => {
+                        peers.lock().unwrap().insert(
+                            node.to_string(),
+                            CoordinatorPeer {
+                                host,
+                                game: message["game"].as_str().unwrap_or_default().to_string(),
+                                game_uid: message["game_uid"]
+                                    .as_str()
+                                    .unwrap_or_default()
+                                    .to_string(),
+                                players: message["players"].as_u64().unwrap_or(0) as usize,
+                                last_seen: Instant::now(),
+                            },
+                        );
+                    }
+                    Some("countdown") => {
+                        let mut countdown = countdown.lock().unwrap();
+                        // A peer can't replace a countdown this machine already joined.
+                        if countdown.as_ref().is_some_and(|current| current.accepted) {
+                            continue;
+                        }
+                        // Delays are relative so machines don't need synced clocks.
+                        let delay_ms = message["delay_ms"].as_u64().unwrap_or(0);
+                        *countdown = Some(CoordinatorCountdown {
+                            host,
+                            game_uid: message["game_uid"]
+                                .as_str()
+                                .unwrap_or_default()
+                                .to_string(),
+                            launch_at: Instant::now() + Duration::from_millis(delay_ms),
+                            accepted: false,
+                        });
+                    }
+                    _ => {}
+                }
+            }
+        });
+
+        Ok(coordinator)
+    }
+
+    /// Updates what this machine advertises. `None` announces an idle host.
+    pub fn set_hosting(&self, game: Option<(&str, &str, usize)>) {
+        *self.status.lock().unwrap() = game.map(|(game, game_uid, players)| LocalStatus {
+            game: game.to_string(),
+            game_uid: game_uid.to_string(),
+            players,
+        });
+    }
+
+    /// Returns the peers heard from recently, sorted by hostname.
+    pub fn peers(&self) -> Vec<CoordinatorPeer> {
+        let mut peers = self.peers.lock().unwrap();
+        peers.retain(|_, peer| peer.last_seen.elapsed() < PEER_TIMEOUT);
+        let mut list: Vec<CoordinatorPeer> = peers.values().cloned().collect();
+        list.sort_by(|a, b| a.host.cmp(&b.host));
+        list
+    }
+
+    /// Broadcasts a countdown for `game_uid` and schedules it locally as well,
+    /// so the initiating machine launches at the same moment as its peers.
+    pub fn start_countdown(&self, game_uid: &str, delay: Duration) {
+        broadcast(
+            &self.socket,
+            &json!({
+                "type": "countdown",
+                "node": self.node_id,
+                "host": self.hostname,
+                "game_uid": game_uid,
+                "delay_ms": delay.as_millis() as u64,
+            }),
+        );
+        *self.countdown.lock().unwrap() = Some(CoordinatorCountdown {
+            host: self.hostname.clone(),
+            game_uid: game_uid.to_string(),
+            launch_at: Instant::now() + delay,
+            accepted: true,
+        });
+    }
+
+    /// Joins the countdown a peer started, so this machine launches with it.
+    pub fn accept_countdown(&self) {
+        if let Some(countdown) = self.countdown.lock().unwrap().as_mut() {
+            countdown.accepted = true;
+        }
+    }
+
+    /// Returns the active countdown, if any, without consuming it.
+    pub fn countdown(&self) -> Option<CoordinatorCountdown> {
+        self.countdown.lock().unwrap().clone()
+    }
+
+    /// Clears the active countdown once it has fired or been dismissed.
+    pub fn clear_countdown(&self) {
+        *self.countdown.lock().unwrap() = None;
+    }
+}
+
+impl Drop for Coordinator {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::Relaxed);
+    }
+}
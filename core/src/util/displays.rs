@@ -0,0 +1,135 @@
+use std::error::Error;
+use std::process::Command;
+
+use serde_json::Value;
+use x11rb::connection::Connection;
+use x11rb::protocol::randr::ConnectionExt as _;
+use x11rb::protocol::xproto::ConnectionExt as _;
+
+use crate::logging::log_warn;
+
+/// One connected display, in the desktop's global (logical) coordinates.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Display {
+    /// Connector name such as `DP-1` or `eDP-1`, as gamescope's
+    /// `--prefer-output` expects it.
+    pub name: String,
+    pub x: i32,
+    pub y: i32,
+    pub width: u32,
+    pub height: u32,
+    pub primary: bool,
+}
+
+impl Display {
+    pub fn label(&self) -> String {
+        format!("{} ({}x{})", self.name, self.width, self.height)
+    }
+}
+
+fn json_i32(value: &Value, key: &str) -> Option<i32> {
+    value.get(key)?.as_i64().map(|n| n as i32)
+}
+
+/// Outputs reported by `kscreen-doctor -j` on Plasma. Sizes are divided by
+/// the output scale, since KWin places windows in logical pixels.
+fn kscreen_displays() -> Option<Vec<Display>> {
+    let output = Command::new("kscreen-doctor").arg("-j").output().ok()?;
+    let json: Value = serde_json::from_slice(&output.stdout).ok()?;
+    let displays = json
+        .get("outputs")?
+        .as_array()?
+        .iter()
+        .filter(|out| out["enabled"].as_bool() == Some(true))
+        .filter_map(|out| {
+            let scale = out["scale"].as_f64().unwrap_or(1.0).max(0.1);
+            Some(Display {
+                name: out["name"].as_str()?.to_string(),
+                x: json_i32(&out["pos"], "x")?,
+                y: json_i32(&out["pos"], "y")?,
+                width: (out["size"]["width"].as_f64()? / scale).round() as u32,
+                height: (out["size"]["height"].as_f64()? / scale).round() as u32,
+                // Plasma 5.27+ reports priority 1 for the primary output.
+                primary: out["priority"].as_u64() == Some(1)
+                    || out["primary"].as_bool() == Some(true),
+            })
+        })
+        .collect();
+    Some(displays)
+}
+
+/// Outputs reported by `wlr-randr --json` on wlroots compositors.
+fn wlr_randr_displays() -> Option<Vec<Display>> {
+    let output = Command::new("wlr-randr").arg("--json").output().ok()?;
+    let json: Value = serde_json::from_slice(&output.stdout).ok()?;
+    let displays = json
+        .as_array()?
+        .iter()
+        .filter(|out| out["enabled"].as_bool() == Some(true))
+        .filter_map(|out| {
+            let scale = out["scale"].as_f64().unwrap_or(1.0).max(0.1);
+            let mode = out["modes"]
+                .as_array()?
+                .iter()
+                .find(|mode| mode["current"].as_bool() == Some(true))?;
+            Some(Display {
+                name: out["name"].as_str()?.to_string(),
+                x: json_i32(&out["position"], "x")?,
+                y: json_i32(&out["position"], "y")?,
+                width: (mode["width"].as_f64()? / scale).round() as u32,
+                height: (mode["height"].as_f64()? / scale).round() as u32,
+                primary: false,
+            })
+        })
+        .collect();
+    Some(displays)
+}
+
+/// Monitors the X server (or Xwayland) reports through RandR 1.5.
+fn randr_displays() -> Result<Vec<Display>, Box<dyn Error>> {
+    let (conn, screen_num) = x11rb::connect(None)?;
+    let root = conn.setup().roots[screen_num].root;
+    let monitors = conn.randr_get_monitors(root, true)?.reply()?.monitors;
+    let mut displays = Vec::new();
+    for monitor in monitors {
+        let name = conn.get_atom_name(monitor.name)?.reply()?.name;
+        displays.push(Display {
+            name: String::from_utf8_lossy(&name).to_string(),
+            x: monitor.x as i32,
+            y: monitor.y as i32,
+            width: monitor.width as u32,
+            height: monitor.height as u32,
+            primary: monitor.primary,
+        });
+    }
+    Ok(displays)
+}
+
+/// Connected displays, left to right. Wayland sessions ask the compositor's
+/// output tool first, since Xwayland may not know the real connector names;
+/// X11 and any fallback use RandR. Exactly one display is marked primary.
+pub fn enumerate_displays() -> Vec<Display> {
+    let wayland = std::env::var_os("WAYLAND_DISPLAY").is_some();
+    let mut displays = if wayland {
+        kscreen_displays()
+            .filter(|displays| !displays.is_empty())
+            .or_else(wlr_randr_displays)
+            .unwrap_or_default()
+    } else {
+        Vec::new()
+    };
+    if displays.is_empty() {
+        displays = randr_displays().unwrap_or_else(|err| {
+            log_warn(format!("Couldn't list displays through RandR: {err}"));
+            Vec::new()
+        });
+    }
+
+    displays.sort_by_key(|display| (display.x, display.y));
+    if !displays.iter().any(|display| display.primary) {
+        if let Some(first) = displays.first_mut() {
+            first.primary = true;
+        }
+    }
+    displays
+}
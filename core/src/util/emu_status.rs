@@ -0,0 +1,288 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+use serde_json::Value;
+
+use super::netmon::udp_port_taken;
+use super::profiles::{deterministic_goldberg_port, read_config_value, resolve_nemirtingas_ports};
+use crate::handler::Handler;
+use crate::paths::*;
+
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug)]
+pub enum EmuSeverity {
+    /// Stale values the next launch rewrites anyway.
+    Info,
+    Warning,
+    /// Keeps instances from finding each other's lobbies.
+    Conflict,
+}
+
+#[derive(Clone, Debug)]
+pub struct EmuIssue {
+    pub severity: EmuSeverity,
+    pub message: String,
+}
+
+/// Goldberg identity files of one profile as they are on disk.
+#[derive(Clone, Debug, Default)]
+pub struct GoldbergIdentity {
+    pub account_name: Option<String>,
+    pub steam_id: Option<String>,
+    pub listen_port: Option<u16>,
+}
+
+/// Identity and network section of one profile's `NemirtingasEpicEmu.json`.
+#[derive(Clone, Debug, Default)]
+pub struct NemirtingasIdentity {
+    pub username: Option<String>,
+    pub epic_id: Option<String>,
+    pub product_user_id: Option<String>,
+    pub account_id: Option<String>,
+    pub app_id: Option<String>,
+    pub lan_port: Option<u16>,
+}
+
+/// Emulator configs of one player. `None` means the file hasn't been
+/// generated yet; the first launch creates it.
+#[derive(Clone, Debug)]
+pub struct ProfileEmuStatus {
+    pub profile: String,
+    pub goldberg: Option<GoldbergIdentity>,
+    pub nemirtingas: Option<NemirtingasIdentity>,
+}
+
+/// Emulator configs of the profiles about to play a handler, checked
+/// against each other and against what the launcher will write.
+#[derive(Clone, Debug)]
+pub struct EmulatorStatus {
+    pub profiles: Vec<ProfileEmuStatus>,
+    /// Ports the next launch synchronizes every profile to.
+    pub goldberg_port: Option<u16>,
+    pub nemirtingas_port: Option<u16>,
+    /// Worst first.
+    pub issues: Vec<EmuIssue>,
+}
+
+impl EmulatorStatus {
+    pub fn worst(&self) -> Option<EmuSeverity> {
+        self.issues.first().map(|issue| issue.severity)
+    }
+}
+
+fn read_trimmed(path: &Path) -> Option<String> {
+    fs::read_to_string(path)
+        .ok()
+        .map(|s| s.trim().to_string())
+        .filter(|s| !s.is_empty())
+}
+
+/// Reads the same sources `synchronize_goldberg_profiles` prefers: the
+/// helper text files first, then `configs.user.ini`.
+fn read_goldberg_identity(profile: &str) -> Option<GoldbergIdentity> {
+    let steam_settings = PATH_APP.join(format!("profiles/{profile}/steam/settings"));
+    let user_ini = steam_settings.join("configs.user.ini");
+    let identity = GoldbergIdentity {
+        account_name: read_trimmed(&steam_settings.join("account_name.txt"))
+            .or_else(|| read_config_value(&user_ini, "account_name")),
+        steam_id: read_trimmed(&steam_settings.join("user_steam_id.txt"))
+            .or_else(|| read_config_value(&user_ini, "account_steamid")),
+        listen_port: read_trimmed(&steam_settings.join("listen_port.txt"))
+            .or_else(|| read_config_value(&user_ini, "listen_port"))
+            .and_then(|port| port.parse().ok()),
+    };
+    if identity.account_name.is_none() && identity.steam_id.is_none() {
+        return None;
+    }
+    Some(identity)
+}
+
+/// Reads both the nested `EOSEmu` layout and the legacy flat keys, like
+/// `ensure_nemirtingas_config`.
+fn read_nemirtingas_identity(profile: &str) -> Option<NemirtingasIdentity> {
+    let path =
+        PATH_APP.join(format!("profiles/{profile}/nepice_settings/NemirtingasEpicEmu.json"));
+    let json: Value = serde_json::from_str(&fs::read_to_string(path).ok()?).ok()?;
+    let string = |pointer: &str, flat: &str| {
+        json.pointer(pointer)
+            .or_else(|| json.get(flat))
+            .and_then(Value::as_str)
+            .map(str::to_string)
+            .filter(|s| !s.is_empty())
+    };
+    Some(NemirtingasIdentity {
+        username: string("/EOSEmu/User/UserName", "username"),
+        epic_id: string("/EOSEmu/User/EpicId", "epicid"),
+        product_user_id: string("/EOSEmu/User/ProductUserId", "productuserid"),
+        account_id: string("/EOSEmu/User/AccountId", "accountid"),
+        app_id: string("/EOSEmu/Application/AppId", "appid"),
+        lan_port: json
+            .pointer("/Network/Lan/OverridePort")
+            .or_else(|| json.get("lan_port"))
+            .and_then(Value::as_u64)
+            .and_then(|port| u16::try_from(port).ok()),
+    })
+}
+
+/// Adds an issue for every value that more than one profile has.
+fn flag_duplicates<'a>(
+    issues: &mut Vec<EmuIssue>,
+    severity: EmuSeverity,
+    what: &str,
+    values: impl Iterator<Item = (&'a str, Option<&'a String>)>,
+) {
+    let mut owners: HashMap<&str, Vec<&str>> = HashMap::new();
+    for (profile, value) in values {
+        let Some(value) = value else {
+            continue;
+        };
+        let sharing = owners.entry(value.as_str()).or_default();
+        if !sharing.contains(&profile) {
+            sharing.push(profile);
+        }
+    }
+    let mut shared: Vec<(&str, Vec<&str>)> =
+        owners.into_iter().filter(|(_, profiles)| profiles.len() > 1).collect();
+    shared.sort();
+    for (value, profiles) in shared {
+        issues.push(EmuIssue {
+            severity,
+            message: format!("{} share {what} {value}.", profiles.join(", ")),
+        });
+    }
+}
+
+/// Checks the emulator configs of `profiles` (resolved profile names, one
+/// per instance) for a launch of `handler`. Only reads files; identities are
+/// kept by the launcher, so duplicates found here persist until fixed, while
+/// ports and app IDs are rewritten at launch and only reported as info.
+pub fn emulator_status(handler: &Handler, profiles: &[String]) -> EmulatorStatus {
+    let uses_goldberg = !handler.path_goldberg.is_empty();
+    let uses_nemirtingas = !handler.path_nemirtingas.is_empty();
+    let goldberg_port = uses_goldberg.then(|| deterministic_goldberg_port(&handler.uid));
+    let nemirtingas_port = uses_nemirtingas
+        .then(|| {
+            resolve_nemirtingas_ports(profiles, &handler.uid, goldberg_port)
+                .into_values()
+                .next()
+        })
+        .flatten();
+
+    let statuses: Vec<ProfileEmuStatus> = profiles
+        .iter()
+        .map(|profile| ProfileEmuStatus {
+            profile: profile.clone(),
+            goldberg: uses_goldberg.then(|| read_goldberg_identity(profile)).flatten(),
+            nemirtingas: uses_nemirtingas
+                .then(|| read_nemirtingas_identity(profile))
+                .flatten(),
+        })
+        .collect();
+
+    let mut issues = Vec::new();
+    let mut picked: Vec<&String> = Vec::new();
+    for profile in profiles {
+        if picked.contains(&profile) {
+            issues.push(EmuIssue {
+                severity: EmuSeverity::Conflict,
+                message: format!("{profile} is picked for more than one instance."),
+            });
+        }
+        picked.push(profile);
+    }
+
+    if uses_goldberg {
+        let identities: Vec<(&str, GoldbergIdentity)> = statuses
+            .iter()
+            .map(|s| (s.profile.as_str(), s.goldberg.clone().unwrap_or_default()))
+            .collect();
+        flag_duplicates(
+            &mut issues,
+            EmuSeverity::Conflict,
+            "SteamID",
+            identities.iter().map(|(p, id)| (*p, id.steam_id.as_ref())),
+        );
+        flag_duplicates(
+            &mut issues,
+            EmuSeverity::Warning,
+            "Goldberg account name",
+            identities.iter().map(|(p, id)| (*p, id.account_name.as_ref())),
+        );
+        for (profile, identity) in &identities {
+            if identity.listen_port.is_some_and(|port| Some(port) != goldberg_port) {
+                issues.push(EmuIssue {
+                    severity: EmuSeverity::Info,
+                    message: format!(
+                        "{profile} listens on port {} from an earlier game; launch resets it.",
+                        identity.listen_port.unwrap_or_default()
+                    ),
+                });
+            }
+        }
+        if handler.steam_appid.is_none() {
+            issues.push(EmuIssue {
+                severity: EmuSeverity::Warning,
+                message: "The handler sets no steam.appid, so Goldberg relies on a steam_appid.txt shipped with the game.".to_string(),
+            });
+        }
+    }
+
+    if uses_nemirtingas {
+        let identities: Vec<(&str, NemirtingasIdentity)> = statuses
+            .iter()
+            .map(|s| (s.profile.as_str(), s.nemirtingas.clone().unwrap_or_default()))
+            .collect();
+        let fields: [(&str, fn(&NemirtingasIdentity) -> Option<&String>); 3] = [
+            ("EpicId", |id| id.epic_id.as_ref()),
+            ("ProductUserId", |id| id.product_user_id.as_ref()),
+            ("AccountId", |id| id.account_id.as_ref()),
+        ];
+        for (what, field) in fields {
+            flag_duplicates(
+                &mut issues,
+                EmuSeverity::Conflict,
+                what,
+                identities.iter().map(|(p, id)| (*p, field(id))),
+            );
+        }
+        for (profile, identity) in &identities {
+            if identity.app_id.as_ref().is_some_and(|id| *id != handler.uid) {
+                issues.push(EmuIssue {
+                    severity: EmuSeverity::Info,
+                    message: format!(
+                        "{profile}'s Nemirtingas AppId is from an earlier game; launch resets it."
+                    ),
+                });
+            }
+            if identity.lan_port.is_some() && identity.lan_port != nemirtingas_port {
+                issues.push(EmuIssue {
+                    severity: EmuSeverity::Info,
+                    message: format!(
+                        "{profile}'s Nemirtingas LAN port is from an earlier game; launch resets it."
+                    ),
+                });
+            }
+        }
+    }
+
+    let mut ports: Vec<u16> = goldberg_port.into_iter().chain(nemirtingas_port).collect();
+    ports.dedup();
+    for port in ports {
+        if udp_port_taken(port) {
+            issues.push(EmuIssue {
+                severity: EmuSeverity::Conflict,
+                message: format!(
+                    "UDP port {port} is already open in another program, so instances can't listen for lobbies on it."
+                ),
+            });
+        }
+    }
+
+    issues.sort_by(|a, b| b.severity.cmp(&a.severity));
+    EmulatorStatus {
+        profiles: statuses,
+        goldberg_port,
+        nemirtingas_port,
+        issues,
+    }
+}
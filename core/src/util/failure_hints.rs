@@ -0,0 +1,105 @@
+/// Likely cause of an instance exiting with an error, with what usually
+/// fixes it.
+pub struct FailureHint {
+    pub cause: &'static str,
+    pub fix: &'static str,
+}
+
+/// Output fragments, matched case-insensitively, and the failure they point
+/// at. More specific signatures come first, since the first match wins.
+const FAILURE_SIGNATURES: &[(&[&str], FailureHint)] = &[
+    (
+        &[
+            "library d3dx9_",
+            "library d3dx10_",
+            "library d3dx11_",
+            "library d3dcompiler_",
+        ],
+        FailureHint {
+            cause: "A DirectX runtime library the game needs is missing from the Proton prefix.",
+            fix: "Add the matching winetricks verb (e.g. d3dx9 or d3dcompiler_47) to the handler's proton.winetricks.",
+        },
+    ),
+    (
+        &[
+            "library msvcp1",
+            "library msvcr1",
+            "library vcruntime140",
+            "library vcomp1",
+        ],
+        FailureHint {
+            cause: "The Visual C++ runtime the game was built with is missing from the Proton prefix.",
+            fix: "Add vcrun2022 (or the year the game needs, e.g. vcrun2010) to the handler's proton.winetricks.",
+        },
+    ),
+    (
+        &["library mscoree.dll", "cannot find .net", "mono is not installed"],
+        FailureHint {
+            cause: "The game needs the .NET Framework, which the Proton prefix doesn't have.",
+            fix: "Add the dotnet verb the game needs (e.g. dotnet48) to the handler's proton.winetricks.",
+        },
+    ),
+    (
+        &[
+            "eos_initialize failed",
+            "eos_platform_create returned null",
+            "library eossdk-win64-shipping.dll",
+            "library eossdk-win32-shipping.dll",
+        ],
+        FailureHint {
+            cause: "The Epic Online Services emulator failed to start.",
+            fix: "Check that the handler's eos.config_path points at the folder of the game's EOSSDK DLL and that the picked Nemirtingas build matches the game's architecture.",
+        },
+    ),
+    (
+        &[
+            "steamapi_init failed",
+            "steamapi_init() failed",
+            "library steam_api64.dll",
+            "library steam_api.dll",
+            "libsteam_api.so: cannot open",
+        ],
+        FailureHint {
+            cause: "The game couldn't load the Steam API the Goldberg emulator provides.",
+            fix: "Check that the handler's steam.api_path is the folder that holds the game's steam_api DLL, then use Settings → Erase Symlink Data so the game folder is rebuilt.",
+        },
+    ),
+    (
+        &[
+            "vk_error_incompatible_driver",
+            "vk_error_initialization_failed",
+            "vkcreateinstance failed",
+            "dxvk: no adapters",
+        ],
+        FailureHint {
+            cause: "DXVK found no Vulkan driver to render with.",
+            fix: "Install your GPU's Vulkan driver including its 32-bit package, or set PROTON_USE_WINED3D=1 in the game's Advanced Options.",
+        },
+    ),
+    (
+        &[
+            "wine: could not load kernel32.dll",
+            "wineserver: version mismatch",
+            "could not find wineserver",
+        ],
+        FailureHint {
+            cause: "The Proton prefix doesn't fit the Proton build in use, usually after switching versions.",
+            fix: "Go back to the Proton build the prefix was made with, or use Settings → Erase Proton Prefix so the next launch creates a fresh one.",
+        },
+    ),
+];
+
+/// First known failure the output `lines` of a crashed instance point at.
+pub fn classify_failure<'a>(
+    lines: impl IntoIterator<Item = &'a str>,
+) -> Option<&'static FailureHint> {
+    let output: Vec<String> = lines.into_iter().map(str::to_lowercase).collect();
+    FAILURE_SIGNATURES
+        .iter()
+        .find(|(patterns, _)| {
+            patterns
+                .iter()
+                .any(|pattern| output.iter().any(|line| line.contains(pattern)))
+        })
+        .map(|(_, hint)| hint)
+}
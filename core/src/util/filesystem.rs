@@ -0,0 +1,330 @@
+use crate::handler::Handler;
+use crate::logging::log_info;
+use crate::paths::*;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::error::Error;
+use std::fs::File;
+use std::io::BufReader;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::time::Duration;
+
+use super::prompts::pick_folder;
+
+/// Writes `contents` to a temporary file next to `path` and renames it over
+/// `path`, so a crash mid-write can't leave a half-written file behind.
+pub fn write_atomic(path: &Path, contents: impl AsRef<[u8]>) -> std::io::Result<()> {
+    let mut tmp_name = path.file_name().unwrap_or_default().to_os_string();
+    tmp_name.push(".tmp");
+    let tmp = path.with_file_name(tmp_name);
+    let mut file = File::create(&tmp)?;
+    std::io::Write::write_all(&mut file, contents.as_ref())?;
+    file.sync_all()?;
+    drop(file);
+    std::fs::rename(&tmp, path)
+}
+
+pub fn copy_dir_recursive(
+    src: &PathBuf,
+    dest: &PathBuf,
+    symlink_instead: bool,
+    overwrite_dest: bool,
+    never_symlink: Option<&Vec<PathBuf>>,
+) -> Result<(), Box<dyn Error>> {
+    log_info(format!(
+        "copy_dir_recursive - src: {}, dest: {}",
+        src.display(),
+        dest.display()
+    ));
+
+    let walk_path = walkdir::WalkDir::new(src).min_depth(1).follow_links(false);
+
+    for entry in walk_path {
+        let entry = entry?;
+        let rel_path = entry.path().strip_prefix(src)?;
+        let new_path = dest.join(rel_path);
+        // println!(
+        //     "entry: {}\n rel_path: {}\n new_path: {}\n",
+        //     entry.path().display(),
+        //     rel_path.display(),
+        //     new_path.display()
+        // );
+
+        if entry.file_type().is_dir() {
+            std::fs::create_dir_all(&new_path)?;
+        } else if entry.file_type().is_symlink() {
+            let symlink_src = std::fs::read_link(entry.path())?;
+            std::os::unix::fs::symlink(symlink_src, new_path)?;
+        } else {
+            if let Some(parent) = new_path.parent() {
+                std::fs::create_dir_all(parent)?;
+            }
+            if new_path.exists() && overwrite_dest {
+                std::fs::remove_file(&new_path)?;
+            }
+            let is_excluded = never_symlink
+                .map(|paths| paths.iter().any(|p| p == &new_path))
+                .unwrap_or(false);
+            if symlink_instead && !is_excluded {
+                std::os::unix::fs::symlink(entry.path(), new_path)?;
+            } else if symlink_instead && is_excluded {
+                if new_path.is_symlink() {
+                    std::fs::remove_file(&new_path)?;
+                }
+                std::fs::File::create(&new_path)?;
+            } else {
+                std::fs::copy(entry.path(), new_path)?;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Mirrors `src` into `dest` as real directories with a symlink to every
+/// file, like `cp -r -s`. Symlinks inside `src` are recreated as they are.
+/// Linking runs on several threads; `progress` is called from the calling
+/// thread with the number of entries linked so far and the total, and stops
+/// the linking when it returns `false`.
+pub fn build_symlink_tree(
+    src: &Path,
+    dest: &Path,
+    progress: impl Fn(usize, usize) -> bool,
+) -> Result<(), Box<dyn Error>> {
+    // Links must point at absolute paths to resolve from inside `dest`.
+    let src = std::path::absolute(src)?;
+
+    // Directories are created up front, in walk order, so every link has a
+    // parent; only the files are handed to the worker threads.
+    let mut files: Vec<PathBuf> = Vec::new();
+    for entry in walkdir::WalkDir::new(&src).min_depth(1).follow_links(false) {
+        let entry = entry.map_err(|err| format!("Couldn't read {}: {err}", src.display()))?;
+        if entry.file_type().is_dir() {
+            let rel_path = entry.path().strip_prefix(&src)?;
+            let new_dir = dest.join(rel_path);
+            std::fs::create_dir_all(&new_dir)
+                .map_err(|err| format!("Couldn't create {}: {err}", new_dir.display()))?;
+        } else {
+            files.push(entry.into_path());
+        }
+    }
+
+    let total = files.len();
+    let done = AtomicUsize::new(0);
+    let aborted = AtomicBool::new(false);
+    let workers = std::thread::available_parallelism().map_or(4, |n| n.get()).min(8);
+    let chunk_size = total.div_ceil(workers).max(1);
+
+    let results: Vec<Result<(), String>> = std::thread::scope(|scope| {
+        let handles: Vec<_> = files
+            .chunks(chunk_size)
+            .map(|chunk| {
+                let src = &src;
+                let done = &done;
+                let aborted = &aborted;
+                scope.spawn(move || {
+                    for path in chunk {
+                        if aborted.load(Ordering::Relaxed) {
+                            break;
+                        }
+                        link_into_tree(src, dest, path)?;
+                        done.fetch_add(1, Ordering::Relaxed);
+                    }
+                    Ok(())
+                })
+            })
+            .collect();
+
+        while !handles.iter().all(|handle| handle.is_finished()) {
+            if !progress(done.load(Ordering::Relaxed), total) {
+                aborted.store(true, Ordering::Relaxed);
+            }
+            std::thread::sleep(Duration::from_millis(100));
+        }
+        handles
+            .into_iter()
+            .map(|handle| handle.join().unwrap_or_else(|_| Err("Linking thread panicked".into())))
+            .collect()
+    });
+    if aborted.load(Ordering::Relaxed) {
+        return Err("Linking cancelled".into());
+    }
+    progress(done.load(Ordering::Relaxed), total);
+
+    for result in results {
+        result?;
+    }
+    Ok(())
+}
+
+/// Links one file of `src` into the same place below `dest`, or copies the
+/// symlink when the entry is one.
+fn link_into_tree(src: &Path, dest: &Path, path: &Path) -> Result<(), String> {
+    let rel_path = path.strip_prefix(src).map_err(|err| err.to_string())?;
+    let new_path = dest.join(rel_path);
+    let target = if path.is_symlink() {
+        std::fs::read_link(path)
+            .map_err(|err| format!("Couldn't read link {}: {err}", path.display()))?
+    } else {
+        path.to_path_buf()
+    };
+    std::os::unix::fs::symlink(&target, &new_path)
+        .map_err(|err| format!("Couldn't link {}: {err}", new_path.display()))
+}
+
+/// Game folder of `handler` when it is already known: saved earlier or
+/// found in a Steam library through `steam.appid`. Never asks the user.
+pub fn known_rootpath_handler(handler: &Handler) -> Option<Result<String, Box<dyn Error>>> {
+    if let Some(value) = find_saved_path(&handler.uid) {
+        return Some(value);
+    }
+
+    let appid_number = str::parse::<u32>(handler.steam_appid.as_deref()?).ok()?;
+    let steam = match steamlocate::SteamDir::locate() {
+        Ok(steam) => steam,
+        Err(err) => return Some(Err(err.into())),
+    };
+    let (app, library) = steam.find_app(appid_number).ok().flatten()?;
+    let path = library.resolve_app_dir(&app);
+    if !path.exists() {
+        return None;
+    }
+    let pathstr = path.to_string_lossy().to_string();
+    Some(add_path(&handler.uid, &pathstr).map(|()| pathstr))
+}
+
+pub fn get_rootpath_handler(handler: &Handler) -> Result<String, Box<dyn Error>> {
+    if let Some(value) = known_rootpath_handler(handler) {
+        return value;
+    }
+
+    // If we didn't get a path from the file, ask user for folder
+    let path = pick_folder(&format!("Locate folder for {}", handler.uid))
+        .ok_or_else(|| "No folder selected")?;
+    let result = path.to_string_lossy().to_string();
+
+    // Create/update the json file
+    add_path(&handler.uid, &result)?;
+
+    Ok(result)
+}
+
+pub fn get_rootpath(uid: &str) -> Result<String, Box<dyn Error>> {
+    if let Some(value) = find_saved_path(uid) {
+        return value;
+    }
+
+    // If we didn't get a path from the file, ask user for folder
+    let path = pick_folder(&format!("Locate folder for {uid}"))
+        .ok_or_else(|| "No folder selected")?;
+    let result = path.to_string_lossy().to_string();
+
+    // Create/update the json file
+    add_path(uid, &result)?;
+
+    Ok(result)
+}
+
+fn add_path(uid: &str, path: &String) -> Result<(), Box<dyn Error>> {
+    log_info(format!("Updating paths.json with {uid}: {path}"));
+    let mut paths = if let Ok(file) = File::open(PATH_APP.join("paths.json")) {
+        serde_json::from_reader(BufReader::new(file))
+            .unwrap_or(Value::Object(serde_json::Map::new()))
+    } else {
+        Value::Object(serde_json::Map::new())
+    };
+
+    if let Value::Object(ref mut map) = paths {
+        map.insert(uid.to_string(), Value::String(path.clone()));
+        std::fs::write(
+            PATH_APP.join("paths.json"),
+            serde_json::to_string_pretty(&paths)?,
+        )?;
+    }
+    Ok(())
+}
+
+/// Game folder already recorded for `uid`, without asking the user or
+/// searching the Steam libraries.
+pub fn saved_rootpath(uid: &str) -> Option<PathBuf> {
+    find_saved_path(uid)?.ok().map(PathBuf::from)
+}
+
+/// Every game folder recorded in paths.json, by handler uid, read at once
+/// for callers that look up many handlers.
+pub fn saved_rootpaths() -> HashMap<String, PathBuf> {
+    let Ok(file) = File::open(PATH_APP.join("paths.json")) else {
+        return HashMap::new();
+    };
+    let json: Value = serde_json::from_reader(BufReader::new(file)).unwrap_or_default();
+    json.as_object()
+        .into_iter()
+        .flatten()
+        .filter_map(|(uid, path)| Some((uid.clone(), PathBuf::from(path.as_str()?))))
+        .collect()
+}
+
+fn find_saved_path(uid: &str) -> Option<Result<String, Box<dyn Error>>> {
+    log_info(format!("Reading paths.json for root path of {uid}"));
+    if let Ok(file) = File::open(PATH_APP.join("paths.json")) {
+        let reader = BufReader::new(file);
+        if let Ok(json) = serde_json::from_reader::<_, Value>(reader) {
+            if let Some(path) = json.get(uid) {
+                if let Some(path_str) = path.as_str() {
+                    log_info(format!("Found root path for {uid}: {path_str}"));
+                    return Some(Ok(path_str.to_string()));
+                }
+            }
+        }
+    }
+    None
+}
+
+pub trait SanitizePath {
+    fn sanitize_path(&self) -> String;
+}
+
+impl SanitizePath for String {
+    fn sanitize_path(&self) -> String {
+        if self.is_empty() {
+            return String::new();
+        }
+
+        let mut sanitized = self.clone();
+
+        // Remove potentially dangerous characters
+        // Allow single quotes in paths since they are quoted when launching
+        // commands. Double quotes would break the quoting though, so we still
+        // strip those along with other potentially dangerous characters.
+        let chars_to_sanitize = [';', '&', '|', '$', '`', '(', ')', '<', '>', '"', '\\', '/'];
+
+        if chars_to_sanitize.iter().any(|&c| sanitized.contains(c)) {
+            sanitized = sanitized
+                .replace(";", "")
+                .replace("&", "")
+                .replace("|", "")
+                .replace("$", "")
+                .replace("`", "")
+                .replace("(", "")
+                .replace(")", "")
+                .replace("<", "")
+                .replace(">", "")
+                .replace("\"", "")
+                .replace("\\", "/") // Convert Windows backslashes to forward slashes
+                .replace("//", "/"); // Remove any doubled slashes
+        }
+
+        // Prevent path traversal attacks
+        while sanitized.contains("../") || sanitized.contains("./") {
+            sanitized = sanitized.replace("../", "").replace("./", "");
+        }
+
+        // Remove leading slash to allow joining with other paths
+        if sanitized.starts_with('/') {
+            sanitized = sanitized[1..].to_string();
+        }
+
+        sanitized
+    }
+}
@@ -0,0 +1,303 @@
+use std::error::Error;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use serde::{Deserialize, Serialize};
+
+use super::filesystem::write_atomic;
+use crate::logging::{log_info, log_warn};
+use crate::paths::PATH_APP;
+
+/// Numbers the markers of the sessions this process opens ports for.
+static NEXT_MARKER: AtomicU64 = AtomicU64::new(1);
+
+/// Host firewall front-ends Split Happens knows how to drive for the duration
+/// of a session.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub enum FirewallBackend {
+    Firewalld,
+    Ufw,
+}
+
+impl FirewallBackend {
+    pub fn name(&self) -> &'static str {
+        match self {
+            FirewallBackend::Firewalld => "firewalld",
+            FirewallBackend::Ufw => "ufw",
+        }
+    }
+
+    /// Converts a normalized `start[-end]/proto` spec into the syntax expected
+    /// by the backend; ufw spells ranges with a colon.
+    fn port_arg(&self, spec: &str) -> String {
+        match self {
+            FirewallBackend::Firewalld => spec.to_string(),
+            FirewallBackend::Ufw => spec.replace('-', ":"),
+        }
+    }
+
+    /// Shell test that succeeds when `spec` is already open.
+    fn query_command(&self, spec: &str) -> String {
+        let port = self.port_arg(spec);
+        match self {
+            FirewallBackend::Firewalld => format!("firewall-cmd --query-port={port} >/dev/null"),
+            FirewallBackend::Ufw => format!("ufw show added | grep -qxF 'ufw allow {port}'"),
+        }
+    }
+
+    fn add_command(&self, spec: &str) -> String {
+        let port = self.port_arg(spec);
+        match self {
+            FirewallBackend::Firewalld => format!("firewall-cmd --add-port={port} >/dev/null"),
+            FirewallBackend::Ufw => format!("ufw allow {port} >/dev/null"),
+        }
+    }
+
+    fn remove_command(&self, spec: &str) -> String {
+        let port = self.port_arg(spec);
+        match self {
+            FirewallBackend::Firewalld => format!("firewall-cmd --remove-port={port} >/dev/null"),
+            FirewallBackend::Ufw => format!("ufw delete allow {port} >/dev/null"),
+        }
+    }
+}
+
+/// Normalizes a handler port entry such as `"7777/udp"` or `"27015-27030/TCP"`.
+/// Returns `None` for malformed specs so they are never passed to a root shell.
+pub fn parse_port_spec(spec: &str) -> Option<String> {
+    let (ports, proto) = spec.trim().split_once('/')?;
+    let proto = proto.to_ascii_lowercase();
+    if proto != "udp" && proto != "tcp" {
+        return None;
+    }
+
+    let (start, end) = match ports.split_once('-') {
+        Some((start, end)) => (start.parse::<u16>().ok()?, end.parse::<u16>().ok()?),
+        None => {
+            let port = ports.parse::<u16>().ok()?;
+            (port, port)
+        }
+    };
+    if start == 0 || end < start {
+        return None;
+    }
+
+    if start == end {
+        Some(format!("{start}/{proto}"))
+    } else {
+        Some(format!("{start}-{end}/{proto}"))
+    }
+}
+
+/// Detects which firewall is currently filtering traffic. Returns `None` when
+/// neither firewalld nor ufw is active, in which case nothing needs opening.
+pub fn detect_firewall() -> Option<FirewallBackend> {
+    let firewalld_running = Command::new("firewall-cmd")
+        .arg("--state")
+        .output()
+        .map(|out| out.status.success())
+        .unwrap_or(false);
+    if firewalld_running {
+        return Some(FirewallBackend::Firewalld);
+    }
+
+    // `ufw status` needs root, so read the persisted enable flag instead.
+    let ufw_enabled = fs::read_to_string("/etc/ufw/ufw.conf")
+        .map(|conf| {
+            conf.lines()
+                .any(|line| line.trim().eq_ignore_ascii_case("ENABLED=yes"))
+        })
+        .unwrap_or(false);
+    if ufw_enabled {
+        return Some(FirewallBackend::Ufw);
+    }
+
+    None
+}
+
+/// Ports a session opened, written to `run/firewall/` until they are closed
+/// so the next start can close the ones a crashed session left open.
+#[derive(Serialize, Deserialize)]
+struct PortMarker {
+    backend: FirewallBackend,
+    ports: Vec<String>,
+}
+
+fn markers_dir() -> PathBuf {
+    PATH_APP.join("run/firewall")
+}
+
+fn write_marker(path: &Path, record: &PortMarker) -> Result<(), Box<dyn Error>> {
+    fs::create_dir_all(markers_dir())?;
+    write_atomic(path, serde_json::to_string(record)?)?;
+    Ok(())
+}
+
+/// Ports temporarily opened for a running session. Ports that were already
+/// open before the session are left out, so closing it never removes a rule
+/// the user had.
+pub struct FirewallSession {
+    backend: FirewallBackend,
+    /// Ports this session added.
+    ports: Vec<String>,
+    marker: PathBuf,
+}
+
+impl FirewallSession {
+    /// Opens the given normalized port specs through `pkexec` so the user gets
+    /// a single polkit prompt for the whole session. `None` when nothing was
+    /// opened.
+    pub fn open(backend: FirewallBackend, ports: &[String]) -> Option<Self> {
+        if ports.is_empty() {
+            return None;
+        }
+
+        // Each port added is echoed back, so only those are reverted later.
+        let steps: Vec<String> = ports
+            .iter()
+            .map(|port| {
+                format!(
+                    "if ! {}; then {} && echo {port} || failed=1; fi",
+                    backend.query_command(port),
+                    backend.add_command(port)
+                )
+            })
+            .collect();
+        let output = Command::new("pkexec")
+            .arg("sh")
+            .arg("-c")
+            .arg(format!("failed=0; {}; exit $failed", steps.join("; ")))
+            .output();
+        let Ok(output) = output else {
+            log_warn(format!("Failed to run pkexec to open firewall ports via {}", backend.name()));
+            return None;
+        };
+        let added: Vec<String> = String::from_utf8_lossy(&output.stdout)
+            .lines()
+            .map(str::trim)
+            .filter(|line| ports.iter().any(|port| port == line))
+            .map(str::to_string)
+            .collect();
+        if !output.status.success() {
+            log_warn(format!(
+                "Failed to open some of the firewall ports {} via {}",
+                ports.join(", "),
+                backend.name()
+            ));
+        }
+        let already_open: Vec<&str> = ports
+            .iter()
+            .filter(|port| !added.contains(port))
+            .map(String::as_str)
+            .collect();
+        if output.status.success() && !already_open.is_empty() {
+            log_info(format!(
+                "{} already open in {}; leaving those rules alone",
+                already_open.join(", "),
+                backend.name()
+            ));
+        }
+        if added.is_empty() {
+            return None;
+        }
+        log_info(format!("Opened {} via {} for this session", added.join(", "), backend.name()));
+
+        let marker = markers_dir().join(format!(
+            "{}-{}.json",
+            std::process::id(),
+            NEXT_MARKER.fetch_add(1, Ordering::Relaxed)
+        ));
+        let record = PortMarker {
+            backend,
+            ports: added.clone(),
+        };
+        if let Err(err) = write_marker(&marker, &record) {
+            log_warn(format!("Couldn't record the opened firewall ports: {err}"));
+        }
+        Some(Self {
+            backend,
+            ports: added,
+            marker,
+        })
+    }
+
+    /// Reverts the rules added by [`FirewallSession::open`]. When that fails,
+    /// the marker stays so the next start tries again.
+    pub fn close(self) {
+        if close_ports(self.backend, &self.ports) {
+            let _ = fs::remove_file(&self.marker);
+        }
+    }
+}
+
+/// Removes `ports` from `backend`, warning when that fails.
+fn close_ports(backend: FirewallBackend, ports: &[String]) -> bool {
+    let steps: Vec<String> = ports.iter().map(|port| backend.remove_command(port)).collect();
+    let status = Command::new("pkexec")
+        .arg("sh")
+        .arg("-c")
+        .arg(steps.join(" ; "))
+        .status();
+    let closed = matches!(status, Ok(status) if status.success());
+    if !closed {
+        log_warn(format!(
+            "Failed to close firewall ports {} via {}; remove them manually",
+            ports.join(", "),
+            backend.name()
+        ));
+    }
+    closed
+}
+
+/// Closes the ports recorded by sessions whose launcher process is gone, e.g.
+/// after a crash, on a background thread. ufw rules persist across reboots,
+/// so they would otherwise stay open for good.
+pub fn spawn_leftover_port_cleanup() {
+    let leftovers: Vec<(PathBuf, PortMarker)> = fs::read_dir(markers_dir())
+        .into_iter()
+        .flatten()
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| !owner_running(path))
+        .filter_map(|path| {
+            let record = fs::read_to_string(&path)
+                .ok()
+                .and_then(|json| serde_json::from_str::<PortMarker>(&json).ok());
+            match record {
+                Some(record) => Some((path, record)),
+                None => {
+                    let _ = fs::remove_file(&path);
+                    None
+                }
+            }
+        })
+        .collect();
+    if leftovers.is_empty() {
+        return;
+    }
+    std::thread::spawn(move || {
+        for (path, record) in leftovers {
+            log_info(format!(
+                "Closing firewall ports {} left open by an earlier session",
+                record.ports.join(", ")
+            ));
+            if close_ports(record.backend, &record.ports) {
+                let _ = fs::remove_file(&path);
+            }
+        }
+    });
+}
+
+/// Whether the launcher process that wrote the marker at `path` still runs.
+fn owner_running(path: &Path) -> bool {
+    let pid = path
+        .file_stem()
+        .and_then(|stem| stem.to_str())
+        .and_then(|stem| stem.split('-').next())
+        .and_then(|pid| pid.parse::<u32>().ok());
+    pid.is_some_and(|pid| {
+        pid != std::process::id() && Path::new(&format!("/proc/{pid}")).exists()
+    })
+}
@@ -0,0 +1,121 @@
+use std::fs;
+use std::io::Read;
+use std::os::unix::process::ExitStatusExt;
+use std::path::Path;
+use std::process::{Command, Stdio};
+use std::time::{Duration, Instant};
+
+use x11rb::connection::Connection;
+use x11rb::protocol::xproto::{AtomEnum, ConnectionExt, PropMode};
+use x11rb::wrapper::ConnectionExt as _;
+
+use super::process_tree::process_tree;
+
+/// How long a probed gamescope binary may run before it counts as starting.
+const GAMESCOPE_PROBE_TIMEOUT: Duration = Duration::from_secs(3);
+
+/// What the dynamic loader prints when a binary was built against libraries
+/// the system no longer has.
+const LOADER_ERRORS: &[&str] = &[
+    "error while loading shared libraries",
+    "symbol lookup error",
+    "undefined symbol",
+    "version `",
+];
+
+/// Why the gamescope binary at `bin` dies right away, or `None` when it
+/// starts. Runs it with `--help`, which exits on its own once the loader and
+/// static initialization are through; an ABI mismatch fails before that.
+pub fn gamescope_start_failure(bin: &Path) -> Option<String> {
+    let mut child = match Command::new(bin)
+        .arg("--help")
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped())
+        .spawn()
+    {
+        Ok(child) => child,
+        Err(err) => return Some(err.to_string()),
+    };
+
+    let started = Instant::now();
+    let status = loop {
+        match child.try_wait() {
+            Ok(Some(status)) => break status,
+            Ok(None) if started.elapsed() < GAMESCOPE_PROBE_TIMEOUT => {
+                std::thread::sleep(Duration::from_millis(50));
+            }
+            // Still running: whatever it is doing, it got past the loader.
+            _ => {
+                let _ = child.kill();
+                let _ = child.wait();
+                return None;
+            }
+        }
+    };
+
+    let mut stderr = String::new();
+    if let Some(mut pipe) = child.stderr.take() {
+        let _ = pipe.read_to_string(&mut stderr);
+    }
+    let loader_error = stderr
+        .lines()
+        .find(|line| LOADER_ERRORS.iter().any(|marker| line.contains(marker)));
+    if let Some(line) = loader_error {
+        return Some(line.trim().to_string());
+    }
+    if let Some(signal) = status.signal() {
+        return Some(format!("it crashed with signal {signal}"));
+    }
+    // 126 and 127 are the shell's "can't execute" and "not found" codes, which
+    // wrapper scripts pass on.
+    match status.code() {
+        Some(code @ (126 | 127)) => Some(format!("it exited with code {code}")),
+        _ => None,
+    }
+}
+
+/// X display of the nested Xwayland server gamescope started under `pid`,
+/// read from the environment of its children.
+fn gamescope_display(pid: u32) -> Option<String> {
+    let outer = std::env::var("DISPLAY").unwrap_or_default();
+    process_tree(pid).into_iter().skip(1).find_map(|child| {
+        let environ = fs::read(format!("/proc/{child}/environ")).ok()?;
+        environ
+            .split(|byte| *byte == 0)
+            .filter_map(|entry| std::str::from_utf8(entry).ok())
+            .find_map(|entry| entry.strip_prefix("DISPLAY="))
+            .filter(|display| *display != outer)
+            .map(str::to_string)
+    })
+}
+
+/// Sets the `CARDINAL` property `name` on the root window of the gamescope
+/// instance under `pid`, which gamescope watches for control requests.
+fn set_gamescope_property(pid: u32, name: &[u8], value: u32) -> Result<(), String> {
+    let display = gamescope_display(pid).ok_or("gamescope display not found")?;
+    let (conn, screen) = x11rb::connect(Some(&display)).map_err(|err| err.to_string())?;
+    let root = conn.setup().roots[screen].root;
+    let atom = conn
+        .intern_atom(false, name)
+        .map_err(|err| err.to_string())?
+        .reply()
+        .map_err(|err| err.to_string())?
+        .atom;
+    conn.change_property32(PropMode::REPLACE, root, atom, AtomEnum::CARDINAL, &[value])
+        .map_err(|err| err.to_string())?;
+    conn.flush().map_err(|err| err.to_string())?;
+    Ok(())
+}
+
+/// Changes the frame limit of the gamescope instance under `pid` while it
+/// runs; `0` removes the limit.
+pub fn set_gamescope_fps_limit(pid: u32, limit: u32) -> Result<(), String> {
+    set_gamescope_property(pid, b"GAMESCOPE_FPS_LIMIT", limit)
+}
+
+/// Asks the gamescope instance under `pid` to save a screenshot of its own
+/// output. Gamescope writes it to `/tmp/gamescope.png` on its own time.
+pub fn request_gamescope_screenshot(pid: u32) -> Result<(), String> {
+    set_gamescope_property(pid, b"GAMESCOPECTRL_REQUEST_SCREENSHOT", 1)
+}
@@ -0,0 +1,80 @@
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// Options that set a frame limit in dxvk.conf, for D3D11/D3D10 and D3D9.
+const DXVK_FPS_OPTIONS: [&str; 2] = ["dxgi.maxFrameRate", "d3d9.maxFrameRate"];
+
+/// Whether `flag` looks like a VKD3D_CONFIG flag (`dxr`, `no_upload_hvv`)
+/// rather than a list or something shell-like.
+pub fn is_vkd3d_flag(flag: &str) -> bool {
+    !flag.is_empty() && flag.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+/// Option a dxvk.conf line sets, if it sets one.
+fn conf_key(line: &str) -> Option<&str> {
+    let key = line.split_once('=')?.0.trim();
+    Some(key).filter(|key| !key.is_empty() && !key.starts_with('#'))
+}
+
+/// Fills in a handler's dxvk.conf template for one instance: `$SLOT` is the
+/// player number, `$WIDTH` and `$HEIGHT` its size and `$FPS` its frame limit,
+/// `0` without one. The limit is also added for D3D9 through D3D11 unless the
+/// template sets those itself. `game_conf` is the dxvk.conf the game ships,
+/// which DXVK no longer reads once the rendered file replaces it, so its lines
+/// are carried over unless the handler sets the same option. `None` when there
+/// is nothing to write.
+pub fn render_dxvk_conf(
+    template: &[String],
+    fps_limit: Option<u32>,
+    slot: usize,
+    width: u32,
+    height: u32,
+    game_conf: Option<&str>,
+) -> Option<String> {
+    if template.is_empty() && fps_limit.is_none() {
+        return None;
+    }
+    let fps = fps_limit.unwrap_or(0).to_string();
+    let mut lines: Vec<String> = template
+        .iter()
+        .map(|line| {
+            line.replace("$SLOT", &slot.to_string())
+                .replace("$WIDTH", &width.to_string())
+                .replace("$HEIGHT", &height.to_string())
+                .replace("$FPS", &fps)
+        })
+        .collect();
+    if fps_limit.is_some() {
+        for option in DXVK_FPS_OPTIONS {
+            let set = lines
+                .iter()
+                .any(|line| line.split('=').next().is_some_and(|key| key.trim() == option));
+            if !set {
+                lines.push(format!("{option} = {fps}"));
+            }
+        }
+    }
+    if let Some(game_conf) = game_conf {
+        let set: Vec<String> =
+            lines.iter().filter_map(|line| conf_key(line)).map(str::to_string).collect();
+        lines.push("# From the game's own dxvk.conf".to_string());
+        lines.extend(
+            game_conf
+                .lines()
+                .filter(|line| conf_key(line).is_none_or(|key| !set.iter().any(|s| s == key)))
+                .map(str::to_string),
+        );
+    }
+    let mut conf = lines.join("\n");
+    conf.push('\n');
+    Some(conf)
+}
+
+/// Writes the dxvk.conf of `profname`'s instance into its Proton prefix and
+/// returns its path. Named per profile, since instances can share a prefix.
+pub fn write_dxvk_conf(prefix: &Path, profname: &str, conf: &str) -> io::Result<PathBuf> {
+    let path = prefix.join(format!("dxvk-{profname}.conf"));
+    fs::write(&path, conf)?;
+    Ok(path)
+}
@@ -0,0 +1,94 @@
+use std::error::Error;
+use std::fs::{self, File};
+use std::io::{BufReader, Cursor};
+use std::path::Path;
+
+use walkdir::WalkDir;
+use zip::write::SimpleFileOptions;
+use zip::{ZipArchive, ZipWriter};
+
+use crate::paths::PATH_APP;
+
+/// Most a bundle may hold once unpacked. Bundles arrive over the LAN, and a
+/// small zip can expand far enough to fill the disk.
+const MAX_BUNDLE_BYTES: u64 = 2 * 1024 * 1024 * 1024;
+
+/// Most files and folders a bundle may hold.
+const MAX_BUNDLE_ENTRIES: usize = 50_000;
+
+/// Whether `name` is one of the numbered guest profiles a launch creates,
+/// e.g. `Guest2`.
+pub fn is_guest_profile(name: &str) -> bool {
+    name.strip_prefix("Guest")
+        .is_some_and(|number| !number.is_empty() && number.chars().all(|c| c.is_ascii_digit()))
+}
+
+/// Zips the folder of guest profile `name`, saves included, so the guest can
+/// take their progress home. Symlinks are left out.
+pub fn export_guest_bundle(name: &str) -> Result<Vec<u8>, Box<dyn Error>> {
+    if !is_guest_profile(name) {
+        return Err(format!("{name} isn't a guest profile").into());
+    }
+    let dir = PATH_APP.join("profiles").join(name);
+    if !dir.is_dir() {
+        return Err(format!("{name} has no progress yet").into());
+    }
+
+    let mut zip = ZipWriter::new(Cursor::new(Vec::new()));
+    let options = SimpleFileOptions::default();
+    for entry in WalkDir::new(&dir).min_depth(1) {
+        let entry = entry?;
+        let relative = entry.path().strip_prefix(&dir)?.to_string_lossy().to_string();
+        if entry.file_type().is_dir() {
+            zip.add_directory(relative, options)?;
+        } else if entry.file_type().is_file() {
+            zip.start_file(relative, options)?;
+            std::io::copy(&mut File::open(entry.path())?, &mut zip)?;
+        }
+    }
+    Ok(zip.finish()?.into_inner())
+}
+
+/// Restores the bundle file made by [`export_guest_bundle`] into guest
+/// profile `name`, replacing what that guest slot had before.
+pub fn import_guest_bundle(name: &str, bundle: &Path) -> Result<(), Box<dyn Error>> {
+    if !is_guest_profile(name) {
+        return Err(format!("{name} isn't a guest profile").into());
+    }
+    let mut archive = ZipArchive::new(BufReader::new(File::open(bundle)?))
+        .map_err(|err| format!("Not a Split Happens save bundle: {err}"))?;
+    if archive.len() > MAX_BUNDLE_ENTRIES {
+        return Err(format!("The bundle holds more than {MAX_BUNDLE_ENTRIES} files").into());
+    }
+    // Extraction stops each entry at the size it declares, so the sum bounds
+    // what lands on disk.
+    let mut unpacked: u64 = 0;
+    for index in 0..archive.len() {
+        unpacked = unpacked.saturating_add(archive.by_index_raw(index)?.size());
+    }
+    if unpacked > MAX_BUNDLE_BYTES {
+        return Err(format!(
+            "The bundle unpacks to {} MiB, more than the {} MiB a guest's saves may take",
+            unpacked / (1024 * 1024),
+            MAX_BUNDLE_BYTES / (1024 * 1024)
+        )
+        .into());
+    }
+
+    // Extract next to the profile first so a broken bundle leaves it alone.
+    // Dot folders are swept by `remove_guest_profiles` if this fails midway.
+    let profiles = PATH_APP.join("profiles");
+    let staging = profiles.join(format!(".import-{name}"));
+    if staging.exists() {
+        fs::remove_dir_all(&staging)?;
+    }
+    // Entries that would land outside the folder are rejected here.
+    archive.extract(&staging)?;
+
+    let target = profiles.join(name);
+    if target.exists() {
+        fs::remove_dir_all(&target)?;
+    }
+    fs::rename(&staging, &target)?;
+    Ok(())
+}
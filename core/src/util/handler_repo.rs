@@ -0,0 +1,167 @@
+use std::error::Error;
+use std::fs;
+use std::path::PathBuf;
+
+use serde::Deserialize;
+use serde_json::Value;
+
+use crate::logging::log_warn;
+use crate::paths::*;
+use crate::util::sha1_file;
+
+use super::net::curl_command;
+
+/// Upper bound for index and artwork requests; archives get a longer budget.
+const REPO_INDEX_TIMEOUT_SECS: &str = "15";
+const REPO_DOWNLOAD_TIMEOUT_SECS: &str = "300";
+
+/// A handler advertised by a repository index.
+#[derive(Deserialize, Clone, Debug)]
+pub struct RepoHandler {
+    pub uid: String,
+    pub name: String,
+    #[serde(default)]
+    pub version: String,
+    #[serde(default)]
+    pub author: String,
+    #[serde(default)]
+    pub info: String,
+    /// Optional cover or icon shown in the browser.
+    #[serde(default)]
+    pub artwork: String,
+    /// Location of the `.pdh` archive, absolute or relative to the index.
+    pub download: String,
+    /// Optional SHA-1 of the archive, verified before installing.
+    #[serde(default)]
+    pub sha1: String,
+}
+
+/// Resolves a possibly relative index entry against the index URL.
+fn resolve_url(index_url: &str, target: &str) -> String {
+    if target.contains("://") {
+        return target.to_string();
+    }
+    let base = index_url.rsplit_once('/').map_or(index_url, |(base, _)| base);
+    format!("{base}/{}", target.trim_start_matches('/'))
+}
+
+/// Downloads `url` to `dest` with the system curl binary, matching the update
+/// checker so no TLS crate is needed.
+pub fn curl_download(url: &str, dest: &PathBuf, timeout: &str) -> Result<(), Box<dyn Error>> {
+    let output = curl_command()?
+        .args(["-sSfL", "--max-time", timeout, "-H", "User-Agent: split-happens", "-o"])
+        .arg(dest)
+        .arg(url)
+        .output()?;
+    if !output.status.success() {
+        let _ = fs::remove_file(dest);
+        return Err(format!(
+            "Download of {url} failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        )
+        .into());
+    }
+    Ok(())
+}
+
+/// Fetches and parses a repository index. Both a bare array and an object
+/// with a `handlers` array are accepted; relative URLs are made absolute.
+pub fn fetch_handler_index(url: &str) -> Result<Vec<RepoHandler>, Box<dyn Error>> {
+    let url = url.trim();
+    if url.is_empty() {
+        return Err("No handler repository URL configured".into());
+    }
+    let output = curl_command()?
+        .args([
+            "-sSfL",
+            "--max-time",
+            REPO_INDEX_TIMEOUT_SECS,
+            "-H",
+            "User-Agent: split-happens",
+            url,
+        ])
+        .output()?;
+    if !output.status.success() {
+        return Err(format!(
+            "Couldn't fetch handler index: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        )
+        .into());
+    }
+
+    let json: Value = serde_json::from_slice(&output.stdout)?;
+    let list = match json {
+        Value::Object(mut map) => map.remove("handlers").unwrap_or(Value::Null),
+        other => other,
+    };
+    let mut handlers: Vec<RepoHandler> = serde_json::from_value(list)?;
+    for handler in &mut handlers {
+        handler.download = resolve_url(url, &handler.download);
+        if !handler.artwork.is_empty() {
+            handler.artwork = resolve_url(url, &handler.artwork);
+        }
+    }
+    handlers.retain(|handler| handler.uid.chars().all(char::is_alphanumeric));
+    Ok(handlers)
+}
+
+/// Caches a handler's artwork under `PATH_APP/cache/repository` and returns
+/// the local file, reusing an earlier download when present.
+pub fn cache_handler_artwork(handler: &RepoHandler) -> Option<PathBuf> {
+    if handler.artwork.is_empty() {
+        return None;
+    }
+    let cache_dir = PATH_APP.join("cache/repository");
+    fs::create_dir_all(&cache_dir).ok()?;
+    // Keep the original extension so the egui image loaders pick the right decoder.
+    let extension = handler
+        .artwork
+        .rsplit_once('.')
+        .map(|(_, ext)| ext.to_ascii_lowercase())
+        .filter(|ext| ["png", "jpg", "jpeg", "svg"].contains(&ext.as_str()))
+        .unwrap_or_else(|| "png".to_string());
+    // The version comes from the remote index; keep it from naming a path.
+    let version: String = handler
+        .version
+        .chars()
+        .filter(|c| c.is_ascii_alphanumeric() || matches!(c, '.' | '-'))
+        .collect();
+    let name = format!("{}-{version}.{extension}", handler.uid);
+    if name.contains(['/', '\\']) || !handler.uid.chars().all(char::is_alphanumeric) {
+        log_warn(format!("Skipping artwork of repository handler {}", handler.uid));
+        return None;
+    }
+    let dest = cache_dir.join(name);
+    if dest.exists() {
+        return Some(dest);
+    }
+    match curl_download(&handler.artwork, &dest, REPO_INDEX_TIMEOUT_SECS) {
+        Ok(()) => Some(dest),
+        Err(err) => {
+            log_warn(err.to_string());
+            None
+        }
+    }
+}
+
+/// Downloads a handler archive to `PATH_APP/downloads` and verifies its
+/// checksum when the index provides one. Returns the path of the `.pdh`.
+pub fn download_handler_archive(handler: &RepoHandler) -> Result<PathBuf, Box<dyn Error>> {
+    let download_dir = PATH_APP.join("downloads");
+    fs::create_dir_all(&download_dir)?;
+    let dest = download_dir.join(format!("{}.pdh", handler.uid));
+    curl_download(&handler.download, &dest, REPO_DOWNLOAD_TIMEOUT_SECS)?;
+
+    if !handler.sha1.is_empty() {
+        let actual = sha1_file(&dest)?;
+        if !actual.eq_ignore_ascii_case(handler.sha1.trim()) {
+            let _ = fs::remove_file(&dest);
+            return Err(format!(
+                "Checksum mismatch for {}: expected {}, got {actual}",
+                handler.name, handler.sha1
+            )
+            .into());
+        }
+    }
+    Ok(dest)
+}
@@ -0,0 +1,251 @@
+use std::fmt;
+
+use serde_json::Value;
+
+/// Shape a handler.json value must have for `Handler::new` to read it.
+#[derive(Clone, Copy)]
+enum FieldKind {
+    Text,
+    Flag,
+    Number,
+    /// A whole number, or a list of them, e.g. one per player.
+    NumberList,
+    TextList,
+    /// Object whose values are all strings, e.g. `handler.sha1`.
+    TextMap,
+    Object,
+    /// `handler.scripts`: paths or `{ "path", "args" }` objects.
+    Scripts,
+    /// `game.resolution_files`: `{ "path", "key" or "regex", "value" }` objects.
+    ResolutionFiles,
+}
+
+impl FieldKind {
+    fn describe(self) -> &'static str {
+        match self {
+            FieldKind::Text => "a string",
+            FieldKind::Flag => "true or false",
+            FieldKind::Number => "a whole number",
+            FieldKind::NumberList => "a whole number or a list of them",
+            FieldKind::TextList => "a list of strings",
+            FieldKind::TextMap => "an object of strings",
+            FieldKind::Object => "an object",
+            FieldKind::Scripts => "a list of script paths or { \"path\", \"args\" } objects",
+            FieldKind::ResolutionFiles => {
+                "a list of { \"path\", \"key\" or \"regex\", \"value\" } objects"
+            }
+        }
+    }
+
+    fn matches(self, value: &Value) -> bool {
+        match self {
+            FieldKind::Text => value.is_string(),
+            FieldKind::Flag => value.is_boolean(),
+            FieldKind::Number => value.is_u64(),
+            FieldKind::NumberList => {
+                value.is_u64() || value.as_array().is_some_and(|arr| arr.iter().all(Value::is_u64))
+            }
+            FieldKind::TextList => value
+                .as_array()
+                .is_some_and(|arr| arr.iter().all(Value::is_string)),
+            FieldKind::TextMap => value
+                .as_object()
+                .is_some_and(|map| map.values().all(Value::is_string)),
+            FieldKind::Object => value.is_object(),
+            FieldKind::Scripts => value.as_array().is_some_and(|arr| {
+                arr.iter()
+                    .all(|script| script.is_string() || script["path"].is_string())
+            }),
+            FieldKind::ResolutionFiles => value.as_array().is_some_and(|arr| {
+                arr.iter().all(|file| {
+                    file["path"].is_string()
+                        && file["value"].is_string()
+                        && (file["key"].is_string() || file["regex"].is_string())
+                })
+            }),
+        }
+    }
+}
+
+/// Every key `Handler::new` reads, with the shape it expects.
+const HANDLER_FIELDS: &[(&str, FieldKind)] = &[
+    ("handler.uid", FieldKind::Text),
+    ("handler.name", FieldKind::Text),
+    ("handler.info", FieldKind::Text),
+    ("handler.author", FieldKind::Text),
+    ("handler.version", FieldKind::Text),
+    ("handler.sha1", FieldKind::TextMap),
+    ("handler.scripts", FieldKind::Scripts),
+    ("handler.former_uids", FieldKind::TextList),
+    ("handler.save_compatible_uids", FieldKind::TextList),
+    ("game.symlink_dir", FieldKind::Flag),
+    ("game.win", FieldKind::Flag),
+    ("game.32bit", FieldKind::Flag),
+    ("game.runtime", FieldKind::Text),
+    ("game.exec", FieldKind::Text),
+    ("game.alt_exec", FieldKind::Text),
+    ("game.args", FieldKind::TextList),
+    ("game.copy_instead_paths", FieldKind::TextList),
+    ("game.remove_paths", FieldKind::TextList),
+    ("game.dll_overrides", FieldKind::TextList),
+    ("game.memory_mb", FieldKind::Number),
+    ("game.separate_users", FieldKind::Flag),
+    ("game.firewall_ports", FieldKind::TextList),
+    ("game.never_symlink_paths", FieldKind::TextList),
+    ("game.resolution_files", FieldKind::ResolutionFiles),
+    ("proton.winetricks", FieldKind::TextList),
+    ("proton.graphics", FieldKind::Text),
+    ("proton.dxvk_conf", FieldKind::TextList),
+    ("proton.dxvk_fps_limit", FieldKind::NumberList),
+    ("proton.vkd3d_config", FieldKind::TextList),
+    ("launcher.settings", FieldKind::Object),
+    ("gamescope.args", FieldKind::TextList),
+    ("gamescope.upscaler", FieldKind::Text),
+    ("steam.api_path", FieldKind::Text),
+    ("steam.appid", FieldKind::Text),
+    ("steam.gb_coldclient", FieldKind::Flag),
+    ("steam.gb_version", FieldKind::Text),
+    ("eos.config_path", FieldKind::Text),
+    ("eos.per_instance", FieldKind::Flag),
+    ("profiles.unique_appdata", FieldKind::Flag),
+    ("profiles.unique_documents", FieldKind::Flag),
+    ("profiles.unique_localshare", FieldKind::Flag),
+    ("profiles.unique_config", FieldKind::Flag),
+    ("profiles.game_paths", FieldKind::TextList),
+];
+
+/// Keys a handler can't work without.
+const REQUIRED_FIELDS: &[&str] = &["handler.uid", "game.exec"];
+
+/// Whether a handler still installs with the problem.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum IssueSeverity {
+    /// The key is ignored, e.g. an unknown or misspelled key.
+    Warning,
+    /// The handler can't be read as written.
+    Error,
+}
+
+/// One problem found in a handler.json, pointing at the line it is on.
+#[derive(Clone, Debug)]
+pub struct HandlerIssue {
+    /// 1-based line in handler.json, when the problem sits on one.
+    pub line: Option<usize>,
+    pub severity: IssueSeverity,
+    pub message: String,
+}
+
+impl fmt::Display for HandlerIssue {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.line {
+            Some(line) => write!(f, "line {line}: {}", self.message),
+            None => write!(f, "{}", self.message),
+        }
+    }
+}
+
+/// Line the top-level `key` is declared on.
+fn key_line(text: &str, key: &str) -> Option<usize> {
+    let quoted = format!("\"{key}\"");
+    text.lines()
+        .position(|line| {
+            line.find(&quoted)
+                .is_some_and(|at| line[at + quoted.len()..].trim_start().starts_with(':'))
+        })
+        .map(|index| index + 1)
+}
+
+/// Number of single-character edits between `a` and `b`.
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut row: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut diagonal = row[0];
+        row[0] = i + 1;
+        for (j, &cb) in b.iter().enumerate() {
+            let above = row[j + 1];
+            row[j + 1] = (above + 1).min(row[j] + 1).min(diagonal + usize::from(ca != cb));
+            diagonal = above;
+        }
+    }
+    row[b.len()]
+}
+
+/// Known key closest to a misspelled `key`, if it is a likely typo.
+fn closest_key(key: &str) -> Option<&'static str> {
+    HANDLER_FIELDS
+        .iter()
+        .map(|(known, _)| (edit_distance(key, known), *known))
+        .filter(|(distance, _)| *distance <= 3)
+        .min_by_key(|(distance, _)| *distance)
+        .map(|(_, known)| known)
+}
+
+/// Checks the text of a handler.json against the keys `Handler::new` reads:
+/// invalid JSON, missing required keys, values of the wrong type and unknown
+/// keys, which are ignored at load time and usually typos.
+pub fn validate_handler_json(text: &str) -> Vec<HandlerIssue> {
+    let json: Value = match serde_json::from_str(text) {
+        Ok(json) => json,
+        Err(err) => {
+            return vec![HandlerIssue {
+                line: Some(err.line()),
+                severity: IssueSeverity::Error,
+                message: format!("invalid JSON: {err}"),
+            }];
+        }
+    };
+    let Some(fields) = json.as_object() else {
+        return vec![HandlerIssue {
+            line: Some(1),
+            severity: IssueSeverity::Error,
+            message: "handler.json must be a JSON object".to_string(),
+        }];
+    };
+
+    let mut issues = Vec::new();
+    for required in REQUIRED_FIELDS {
+        if fields.get(*required).is_none_or(|value| value.as_str() == Some("")) {
+            issues.push(HandlerIssue {
+                line: None,
+                severity: IssueSeverity::Error,
+                message: format!("{required} is required but missing or empty"),
+            });
+        }
+    }
+    for (key, value) in fields {
+        let line = key_line(text, key);
+        match HANDLER_FIELDS.iter().find(|(known, _)| *known == key.as_str()) {
+            // Null reads the same as an absent key.
+            Some((_, kind)) if !value.is_null() && !kind.matches(value) => {
+                issues.push(HandlerIssue {
+                    line,
+                    severity: IssueSeverity::Error,
+                    message: format!("{key} must be {}", kind.describe()),
+                });
+            }
+            Some(_) => {}
+            None => {
+                let hint = closest_key(key)
+                    .map(|known| format!(", did you mean {known}?"))
+                    .unwrap_or_default();
+                issues.push(HandlerIssue {
+                    line,
+                    severity: IssueSeverity::Warning,
+                    message: format!("unknown key {key} is ignored{hint}"),
+                });
+            }
+        }
+    }
+    issues.sort_by_key(|issue| issue.line.unwrap_or(0));
+    issues
+}
+
+/// Lists `issues` one per line, for dialogs and logs.
+pub fn format_handler_issues(issues: &[HandlerIssue]) -> String {
+    issues
+        .iter()
+        .map(|issue| format!("• {issue}"))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
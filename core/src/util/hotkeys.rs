@@ -0,0 +1,152 @@
+use std::collections::HashSet;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant};
+
+use evdev::{Device, EventSummary, KeyCode};
+use x11rb::connection::Connection;
+
+use super::window_probe::{active_window, window_pid};
+use crate::logging::log_info;
+use crate::session::{SessionCommand, SessionControl, SessionManager};
+
+/// How often the listener looks for keyboards plugged in mid-session.
+const KEYBOARD_RESCAN: Duration = Duration::from_secs(5);
+
+/// Ctrl+Shift combos and the session command each one sends.
+const HOTKEYS: [(KeyCode, SessionCommand, &str); 3] = [
+    (KeyCode::KEY_Q, SessionCommand::StopAll, "Ctrl+Shift+Q: stop all instances"),
+    (KeyCode::KEY_R, SessionCommand::RestartCrashed, "Ctrl+Shift+R: restart crashed instances"),
+    (KeyCode::KEY_F, SessionCommand::ToggleFpsLimit, "Ctrl+Shift+F: toggle the FPS limit"),
+];
+
+const CTRL_KEYS: [KeyCode; 2] = [KeyCode::KEY_LEFTCTRL, KeyCode::KEY_RIGHTCTRL];
+const SHIFT_KEYS: [KeyCode; 2] = [KeyCode::KEY_LEFTSHIFT, KeyCode::KEY_RIGHTSHIFT];
+
+/// Reads every keyboard during a session and turns the global hotkeys into
+/// [`SessionCommand`]s. Keyboards are only read, never grabbed, so games keep
+/// receiving the keys. Stops when dropped.
+pub struct HotkeyListener {
+    stop: Arc<AtomicBool>,
+    handle: Option<JoinHandle<()>>,
+}
+
+impl HotkeyListener {
+    pub fn start(session: SessionControl) -> HotkeyListener {
+        let stop = Arc::new(AtomicBool::new(false));
+        let thread_stop = Arc::clone(&stop);
+        let handle = std::thread::spawn(move || {
+            let mut keyboards: Vec<(PathBuf, Device)> = Vec::new();
+            let mut held: HashSet<KeyCode> = HashSet::new();
+            let mut last_scan: Option<Instant> = None;
+            while !thread_stop.load(Ordering::Relaxed) {
+                if last_scan.is_none_or(|at| at.elapsed() >= KEYBOARD_RESCAN) {
+                    open_new_keyboards(&mut keyboards);
+                    last_scan = Some(Instant::now());
+                }
+
+                let mut lost = Vec::new();
+                for (index, (_, device)) in keyboards.iter_mut().enumerate() {
+                    let summaries: Vec<EventSummary> = match device.fetch_events() {
+                        Ok(events) => events.map(|event| event.destructure()).collect(),
+                        Err(err) if err.kind() == std::io::ErrorKind::WouldBlock => continue,
+                        Err(_) => {
+                            lost.push(index);
+                            continue;
+                        }
+                    };
+                    for summary in summaries {
+                        let EventSummary::Key(_, key, value) = summary else {
+                            continue;
+                        };
+                        match value {
+                            0 => {
+                                held.remove(&key);
+                            }
+                            1 => {
+                                held.insert(key);
+                                if let Some(command) = hotkey_command(&held, key) {
+                                    if hotkey_targets(session.id()) {
+                                        session.send(command);
+                                    }
+                                }
+                            }
+                            _ => {}
+                        }
+                    }
+                }
+                for index in lost.into_iter().rev() {
+                    keyboards.remove(index);
+                }
+
+                std::thread::sleep(Duration::from_millis(20));
+            }
+        });
+        log_info(format!(
+            "Session hotkeys active ({}).",
+            HOTKEYS.map(|(_, _, help)| help).join(", ")
+        ));
+        HotkeyListener {
+            stop,
+            handle: Some(handle),
+        }
+    }
+}
+
+impl Drop for HotkeyListener {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::Relaxed);
+        if let Some(handle) = self.handle.take() {
+            let _ = handle.join();
+        }
+    }
+}
+
+/// Command bound to `key` if Ctrl and Shift are both held.
+fn hotkey_command(held: &HashSet<KeyCode>, key: KeyCode) -> Option<SessionCommand> {
+    let ctrl = CTRL_KEYS.iter().any(|code| held.contains(code));
+    let shift = SHIFT_KEYS.iter().any(|code| held.contains(code));
+    if !ctrl || !shift {
+        return None;
+    }
+    HOTKEYS
+        .iter()
+        .find(|(code, _, _)| *code == key)
+        .map(|(_, command, _)| command.clone())
+}
+
+/// Whether a hotkey is meant for the session `id`. Every session reads the
+/// same keyboards, so with several running only the one whose instance has
+/// focus acts on it.
+fn hotkey_targets(id: u64) -> bool {
+    let manager = SessionManager::global();
+    if manager.running_count() <= 1 {
+        return true;
+    }
+    focused_pid().and_then(|pid| manager.session_of(pid)) == Some(id)
+}
+
+/// PID of the focused window, asked from the X server or XWayland.
+fn focused_pid() -> Option<u32> {
+    let (conn, screen_num) = x11rb::connect(None).ok()?;
+    let root = conn.setup().roots.get(screen_num)?.root;
+    let window = active_window(&conn, root).ok()??;
+    window_pid(&conn, window).ok()?
+}
+
+/// Adds keyboards that aren't open yet to `keyboards`.
+fn open_new_keyboards(keyboards: &mut Vec<(PathBuf, Device)>) {
+    for (path, device) in evdev::enumerate() {
+        if keyboards.iter().any(|(open, _)| *open == path) {
+            continue;
+        }
+        let is_keyboard = device.supported_keys().is_some_and(|keys| {
+            keys.contains(KeyCode::KEY_Q) && keys.contains(KeyCode::KEY_LEFTCTRL)
+        });
+        if is_keyboard && device.set_nonblocking(true).is_ok() {
+            keyboards.push((path, device));
+        }
+    }
+}
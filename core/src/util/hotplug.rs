@@ -0,0 +1,126 @@
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+use evdev::KeyCode;
+
+use crate::input::{DeviceInfo, DeviceType, device_identity};
+
+/// How often the monitor looks for pads that left or came back.
+const HOTPLUG_POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Change in the pads assigned to a running session.
+#[derive(Clone, Debug)]
+pub enum HotplugEvent {
+    /// The pad at `path` went away; the slot has no input from it until it returns.
+    Disconnected { slot: usize, path: String },
+    /// The same pad showed up again, possibly under another event node.
+    Reconnected {
+        slot: usize,
+        old_path: String,
+        new_path: String,
+    },
+}
+
+/// One pad the monitor keeps an eye on.
+struct TrackedPad {
+    slot: usize,
+    /// Vendor, product and unique id or port, which survive a reconnect.
+    identity: String,
+    path: String,
+    connected: bool,
+}
+
+/// Notices when an assigned pad disconnects mid-session and finds the same
+/// pad again when it reconnects, matched by its stable identity.
+#[derive(Default)]
+pub struct DeviceMonitor {
+    pads: Vec<TrackedPad>,
+    last_poll: Option<Instant>,
+}
+
+impl DeviceMonitor {
+    /// Watches `device` as one of the pads of `slot`. Keyboards and mice are
+    /// held by gamescope, so only gamepads are followed.
+    pub fn track(&mut self, slot: usize, device: &DeviceInfo) {
+        if device.device_type != DeviceType::Gamepad || device.identity.is_empty() {
+            return;
+        }
+        self.pads.push(TrackedPad {
+            slot,
+            identity: device.identity.clone(),
+            path: device.path.clone(),
+            connected: Path::new(&device.path).exists(),
+        });
+    }
+
+    /// Paths of the tracked pads that are currently gone.
+    pub fn disconnected(&self) -> Vec<String> {
+        self.pads
+            .iter()
+            .filter(|pad| !pad.connected)
+            .map(|pad| pad.path.clone())
+            .collect()
+    }
+
+    /// Checks the tracked pads at most once per second. Nodes in `ignore`,
+    /// such as the session's own virtual pads, are never taken for a
+    /// returning pad.
+    pub fn poll(&mut self, ignore: &[String]) -> Vec<HotplugEvent> {
+        if self
+            .last_poll
+            .is_some_and(|last| last.elapsed() < HOTPLUG_POLL_INTERVAL)
+        {
+            return Vec::new();
+        }
+        self.last_poll = Some(Instant::now());
+
+        let mut events = Vec::new();
+        for pad in self.pads.iter_mut().filter(|pad| pad.connected) {
+            if !Path::new(&pad.path).exists() {
+                pad.connected = false;
+                events.push(HotplugEvent::Disconnected {
+                    slot: pad.slot,
+                    path: pad.path.clone(),
+                });
+            }
+        }
+        if self.pads.iter().all(|pad| pad.connected) {
+            return events;
+        }
+
+        // Only enumerate while something is missing; opening every node is slow.
+        let mut taken: Vec<String> = self
+            .pads
+            .iter()
+            .filter(|pad| pad.connected)
+            .map(|pad| pad.path.clone())
+            .collect();
+        taken.extend(ignore.iter().cloned());
+        for (path, device) in evdev::enumerate() {
+            let path = path.to_string_lossy().to_string();
+            // Motion sensors share the pad's identity but have no buttons.
+            let is_pad = device
+                .supported_keys()
+                .is_some_and(|keys| keys.contains(KeyCode::BTN_SOUTH));
+            if !is_pad || taken.contains(&path) {
+                continue;
+            }
+            let identity = device_identity(&device);
+            let Some(pad) = self
+                .pads
+                .iter_mut()
+                .find(|pad| !pad.connected && pad.identity == identity)
+            else {
+                continue;
+            };
+            events.push(HotplugEvent::Reconnected {
+                slot: pad.slot,
+                old_path: std::mem::replace(&mut pad.path, path.clone()),
+                new_path: path.clone(),
+            });
+            pad.connected = true;
+            taken.push(path);
+        }
+        events
+    }
+}
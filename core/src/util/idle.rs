@@ -0,0 +1,173 @@
+use std::collections::HashMap;
+use std::error::Error;
+use std::process::Command;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant};
+
+use evdev::{AbsoluteAxisCode, Device, EventSummary, InputEvent};
+
+use super::calibration::AxisRange;
+use super::pad_proxy::is_motion_sensor;
+use crate::logging::log_warn;
+
+/// How often the idle watcher reads the devices it watches.
+const IDLE_POLL_INTERVAL: Duration = Duration::from_millis(100);
+/// Share of its travel an axis has to move before it counts as input, so
+/// stick drift and sensor noise don't keep an idle session awake.
+const ACTIVITY_AXIS_THRESHOLD: f32 = 0.25;
+
+/// Whether `event` is a player doing something: a key or button press, a
+/// mouse move, a d-pad push, or a stick or trigger moved past
+/// [`ACTIVITY_AXIS_THRESHOLD`] of its range in `ranges`. Releases, sync
+/// events and axes without a known range don't count.
+pub fn is_player_activity(event: &InputEvent, ranges: &HashMap<u16, AxisRange>) -> bool {
+    match event.destructure() {
+        EventSummary::Key(_, _, value) => value == 1,
+        EventSummary::RelativeAxis(..) => true,
+        EventSummary::AbsoluteAxis(_, axis, value) => {
+            let Some(range) = ranges.get(&axis.0) else {
+                return false;
+            };
+            match axis {
+                AbsoluteAxisCode::ABS_HAT0X | AbsoluteAxisCode::ABS_HAT0Y => value != 0,
+                // Triggers rest at one end of their range rather than in the middle.
+                AbsoluteAxisCode::ABS_Z
+                | AbsoluteAxisCode::ABS_RZ
+                | AbsoluteAxisCode::ABS_GAS
+                | AbsoluteAxisCode::ABS_BRAKE => {
+                    let travel = (range.max - range.min).max(1) as f32;
+                    (value - range.min) as f32 / travel >= ACTIVITY_AXIS_THRESHOLD
+                }
+                _ => range.normalize(value).abs() >= ACTIVITY_AXIS_THRESHOLD,
+            }
+        }
+        _ => false,
+    }
+}
+
+/// When a player last touched any device of a session. Pad proxies and the
+/// [`IdleWatcher`] update it; the launch thread reads it.
+#[derive(Clone)]
+pub struct InputActivity {
+    epoch: Instant,
+    /// Milliseconds after `epoch` of the latest input.
+    last_ms: Arc<AtomicU64>,
+}
+
+impl Default for InputActivity {
+    fn default() -> Self {
+        InputActivity {
+            epoch: Instant::now(),
+            last_ms: Arc::default(),
+        }
+    }
+}
+
+impl InputActivity {
+    /// Records input happening now.
+    pub fn touch(&self) {
+        let now = self.epoch.elapsed().as_millis() as u64;
+        self.last_ms.fetch_max(now, Ordering::Relaxed);
+    }
+
+    /// Time since the latest input, or since the session started without any.
+    pub fn idle_for(&self) -> Duration {
+        let last = Duration::from_millis(self.last_ms.load(Ordering::Relaxed));
+        self.epoch.elapsed().saturating_sub(last)
+    }
+}
+
+/// Reads devices that no pad proxy grabbed, without grabbing them either, and
+/// records their input in an [`InputActivity`]. Stops when dropped.
+pub struct IdleWatcher {
+    stop: Arc<AtomicBool>,
+    handle: Option<JoinHandle<()>>,
+}
+
+impl IdleWatcher {
+    /// Watches the evdev nodes at `paths`. Nodes that can't be opened are
+    /// logged and skipped, so they never count as activity, and motion
+    /// sensors are skipped since a pad lying on a table still reports motion.
+    pub fn start(paths: &[String], activity: InputActivity) -> IdleWatcher {
+        let mut devices: Vec<(Device, HashMap<u16, AxisRange>)> = Vec::new();
+        for path in paths {
+            match Device::open(path).and_then(|device| {
+                device.set_nonblocking(true)?;
+                Ok(device)
+            }) {
+                Ok(device) if is_motion_sensor(&device) => {}
+                Ok(device) => {
+                    let ranges = device
+                        .get_absinfo()
+                        .map(|infos| {
+                            infos
+                                .map(|(axis, info)| {
+                                    (axis.0, AxisRange::from_bounds(info.minimum(), info.maximum()))
+                                })
+                                .collect()
+                        })
+                        .unwrap_or_default();
+                    devices.push((device, ranges));
+                }
+                Err(err) => log_warn(format!("Idle detection can't read {path}: {err}")),
+            }
+        }
+
+        let stop = Arc::new(AtomicBool::new(false));
+        let thread_stop = Arc::clone(&stop);
+        let handle = std::thread::spawn(move || {
+            while !thread_stop.load(Ordering::Relaxed) {
+                for (device, ranges) in devices.iter_mut() {
+                    let touched = device.fetch_events().is_ok_and(|events| {
+                        // Drain every event, so old noise isn't read on the next poll.
+                        events.fold(false, |touched, event| {
+                            touched | is_player_activity(&event, ranges)
+                        })
+                    });
+                    if touched {
+                        activity.touch();
+                    }
+                }
+                std::thread::sleep(IDLE_POLL_INTERVAL);
+            }
+        });
+
+        IdleWatcher {
+            stop,
+            handle: Some(handle),
+        }
+    }
+}
+
+impl Drop for IdleWatcher {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::Relaxed);
+        if let Some(handle) = self.handle.take() {
+            let _ = handle.join();
+        }
+    }
+}
+
+/// Dims the screen backlight through brightnessctl while the session is idle
+/// and restores the saved brightness afterwards. Monitors without a backlight
+/// brightnessctl can control are left alone.
+pub fn dim_display(dim: bool) -> Result<(), Box<dyn Error>> {
+    let mut cmd = Command::new("brightnessctl");
+    if dim {
+        // -s saves the current level for the restore below.
+        cmd.args(["-q", "-s", "set", "10%"]);
+    } else {
+        cmd.args(["-q", "-r"]);
+    }
+    let output = cmd.output()?;
+    if !output.status.success() {
+        return Err(format!(
+            "brightnessctl failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        )
+        .into());
+    }
+    Ok(())
+}
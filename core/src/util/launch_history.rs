@@ -0,0 +1,111 @@
+use std::error::Error;
+use std::fs::{self, File};
+use std::io::BufReader;
+use std::path::PathBuf;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+use crate::paths::*;
+use crate::util::LaunchOverrides;
+
+/// Settings keys that shape the gamescope and bwrap command line. Only these
+/// are recorded and restored, so rolling back leaves unrelated settings alone.
+const LAUNCH_SETTING_KEYS: [&str; 10] = [
+    "safe_mode",
+    "force_sdl",
+    "gamescope_fix_lowres",
+    "gamescope_sdl_backend",
+    "kbm_support",
+    "proton_version",
+    "proton_separate_pfxs",
+    "performance_limit_40fps",
+    "performance_gamescope_rt",
+    "performance_enable_proton_fsr",
+];
+
+/// Launches kept per game; older entries are dropped.
+const MAX_LAUNCH_HISTORY: usize = 20;
+
+/// One recorded launch of a game.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct LaunchRecord {
+    /// Seconds since the Unix epoch.
+    pub timestamp: u64,
+    /// Whether every instance survived the first minute.
+    pub succeeded: bool,
+    /// Values of [`LAUNCH_SETTING_KEYS`] at launch time.
+    pub settings: Map<String, Value>,
+    #[serde(default)]
+    pub overrides: LaunchOverrides,
+    /// Full gamescope/bwrap command line of each instance.
+    pub commands: Vec<String>,
+    /// Log folder of the launch under `logs/`, empty when it had none.
+    #[serde(default)]
+    pub log_session: String,
+}
+
+impl LaunchRecord {
+    pub fn new(
+        succeeded: bool,
+        settings: Map<String, Value>,
+        overrides: LaunchOverrides,
+        commands: Vec<String>,
+        log_session: String,
+    ) -> LaunchRecord {
+        LaunchRecord {
+            timestamp: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map_or(0, |elapsed| elapsed.as_secs()),
+            succeeded,
+            settings,
+            overrides,
+            commands,
+            log_session,
+        }
+    }
+
+    /// Whether both launches used the same settings and overrides.
+    pub fn same_configuration(&self, other: &LaunchRecord) -> bool {
+        self.settings == other.settings && self.overrides == other.overrides
+    }
+}
+
+/// Picks [`LAUNCH_SETTING_KEYS`] out of the serialized settings.
+pub fn launch_settings_snapshot(settings: &Value) -> Map<String, Value> {
+    LAUNCH_SETTING_KEYS
+        .iter()
+        .filter_map(|key| Some((key.to_string(), settings.get(*key)?.clone())))
+        .collect()
+}
+
+fn history_path(game_id: &str) -> PathBuf {
+    PATH_APP.join("launch_history").join(format!("{game_id}.json"))
+}
+
+/// Recorded launches of `game_id`, oldest first.
+pub fn load_launch_history(game_id: &str) -> Vec<LaunchRecord> {
+    File::open(history_path(game_id))
+        .ok()
+        .and_then(|file| serde_json::from_reader(BufReader::new(file)).ok())
+        .unwrap_or_default()
+}
+
+/// Appends `record` to the history of `game_id`.
+pub fn record_launch(game_id: &str, record: LaunchRecord) -> Result<(), Box<dyn Error>> {
+    let mut history = load_launch_history(game_id);
+    history.push(record);
+    if history.len() > MAX_LAUNCH_HISTORY {
+        history.drain(..history.len() - MAX_LAUNCH_HISTORY);
+    }
+    fs::create_dir_all(PATH_APP.join("launch_history"))?;
+    let file = File::create(history_path(game_id))?;
+    serde_json::to_writer_pretty(file, &history)?;
+    Ok(())
+}
+
+/// The most recent successful launch in `history`.
+pub fn last_known_good(history: &[LaunchRecord]) -> Option<&LaunchRecord> {
+    history.iter().rev().find(|record| record.succeeded)
+}
@@ -0,0 +1,59 @@
+use std::collections::BTreeMap;
+use std::error::Error;
+use std::fs::{self, File};
+use std::io::BufReader;
+use std::path::PathBuf;
+
+use serde::{Deserialize, Serialize};
+
+use crate::paths::*;
+
+/// Per-game launch tweaks kept outside handler.json, so handler updates and
+/// reinstalls leave them alone.
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq)]
+#[serde(default)]
+pub struct LaunchOverrides {
+    /// Extra arguments, split on whitespace. Handler placeholders such as
+    /// `$PROFILE` and `$WIDTHXHEIGHT` are expanded.
+    pub args: String,
+    /// Drop the handler's own arguments and only pass `args`.
+    pub replace_args: bool,
+    /// Environment variables set on every instance, after the launcher's own.
+    pub env: BTreeMap<String, String>,
+    /// DLLs loaded native-first in addition to the handler's list.
+    pub dll_overrides: Vec<String>,
+}
+
+impl LaunchOverrides {
+    pub fn is_empty(&self) -> bool {
+        *self == LaunchOverrides::default()
+    }
+}
+
+fn overrides_path(uid: &str) -> PathBuf {
+    PATH_APP.join("overrides").join(format!("{uid}.json"))
+}
+
+/// Loads the launch overrides for the handler `uid`, or none if unset.
+pub fn load_launch_overrides(uid: &str) -> LaunchOverrides {
+    File::open(overrides_path(uid))
+        .ok()
+        .and_then(|file| serde_json::from_reader(BufReader::new(file)).ok())
+        .unwrap_or_default()
+}
+
+/// Persists the launch overrides for the handler `uid`; empty overrides
+/// remove the file.
+pub fn save_launch_overrides(uid: &str, overrides: &LaunchOverrides) -> Result<(), Box<dyn Error>> {
+    let path = overrides_path(uid);
+    if overrides.is_empty() {
+        if path.exists() {
+            fs::remove_file(path)?;
+        }
+        return Ok(());
+    }
+    fs::create_dir_all(PATH_APP.join("overrides"))?;
+    let file = File::create(path)?;
+    serde_json::to_writer_pretty(file, overrides)?;
+    Ok(())
+}
@@ -5,6 +5,7 @@ use std::io::{ErrorKind, Write};
 use std::path::{Path, PathBuf};
 use std::time::{SystemTime, UNIX_EPOCH};
 
+use crate::logging::{log_info, log_warn};
 use crate::paths::PATH_APP;
 use crate::util::SanitizePath;
 
@@ -49,16 +50,16 @@ impl ProfileLock {
                 Err(e) if e.kind() == ErrorKind::WouldBlock => {
                     drop(file);
                     if Self::stale(&path, profile) {
-                        println!("Removing stale lock {}", path.display());
+                        log_info(format!("Removing stale lock {}", path.display()));
                         std::fs::remove_file(&path)?;
                         continue;
                     } else {
                         if let Ok(content) = std::fs::read_to_string(&path) {
                             if let Ok(info) = serde_json::from_str::<LockInfo>(&content) {
-                                println!(
+                                log_warn(format!(
                                     "Instance {} already running with PID {}",
                                     info.profile, info.pid
-                                );
+                                ));
                             }
                         }
                         return Err("Instance already running".into());
@@ -69,6 +70,21 @@ impl ProfileLock {
         }
     }
 
+    /// Whether a running session, in this or another launcher process,
+    /// holds a lock for `profile` in any game.
+    pub fn is_held(profile: &str) -> bool {
+        let Ok(entries) = std::fs::read_dir(PATH_APP.join("run/locks")) else {
+            return false;
+        };
+        entries.flatten().any(|entry| {
+            let info = std::fs::read_to_string(entry.path())
+                .ok()
+                .and_then(|content| serde_json::from_str::<LockInfo>(&content).ok());
+            info.is_some_and(|info| info.profile == profile)
+                && File::open(entry.path()).is_ok_and(|file| file.try_lock_shared().is_err())
+        })
+    }
+
     fn stale(path: &Path, profile: &str) -> bool {
         if let Ok(content) = std::fs::read_to_string(path) {
             if let Ok(info) = serde_json::from_str::<LockInfo>(&content) {
@@ -0,0 +1,84 @@
+use std::fs;
+
+/// Per-instance memory budget (in MiB) assumed when a handler does not declare
+/// `game.memory_mb`. Sized for a typical Proton title so Deck sessions get a
+/// realistic estimate instead of an optimistic one.
+const DEFAULT_INSTANCE_MEMORY_MB: u64 = 2048;
+
+/// Captures the host memory counters that matter when sizing a splitscreen
+/// session so callers can reason about RAM and swap headroom together.
+#[derive(Clone, Debug)]
+pub struct MemorySnapshot {
+    pub available_mb: u64,
+    pub swap_free_mb: u64,
+    /// True when the active swap lives on zram (SteamOS default), which is far
+    /// cheaper to page into than disk-backed swap.
+    pub zram_swap: bool,
+}
+
+impl MemorySnapshot {
+    /// Weighs swap headroom by its backing store: zram pages cost CPU but stay
+    /// usable, while disk swap thrashes almost immediately under game workloads.
+    pub fn effective_mb(&self) -> u64 {
+        let swap_weight = if self.zram_swap { 2 } else { 4 };
+        self.available_mb + self.swap_free_mb / swap_weight
+    }
+}
+
+/// Reads `/proc/meminfo` and `/proc/swaps` to build a [`MemorySnapshot`].
+/// Returns `None` when the kernel counters are unavailable.
+pub fn read_memory_snapshot() -> Option<MemorySnapshot> {
+    let meminfo = fs::read_to_string("/proc/meminfo").ok()?;
+
+    // Pull a single `Key:   1234 kB` entry and convert it to MiB.
+    let read_kb = |key: &str| -> Option<u64> {
+        meminfo
+            .lines()
+            .find_map(|line| line.strip_prefix(key))
+            .and_then(|rest| rest.trim_start_matches(':').split_whitespace().next())
+            .and_then(|value| value.parse::<u64>().ok())
+    };
+
+    let available_mb = read_kb("MemAvailable")? / 1024;
+    let swap_free_mb = read_kb("SwapFree").unwrap_or(0) / 1024;
+
+    let zram_swap = fs::read_to_string("/proc/swaps")
+        .map(|swaps| swaps.lines().skip(1).any(|line| line.contains("zram")))
+        .unwrap_or(false);
+
+    Some(MemorySnapshot {
+        available_mb,
+        swap_free_mb,
+        zram_swap,
+    })
+}
+
+/// Suggests how many instances fit into the current memory headroom given the
+/// handler's declared footprint. Always returns at least one.
+pub fn suggest_max_instances(snapshot: &MemorySnapshot, per_instance_mb: Option<u64>) -> usize {
+    let per_instance = per_instance_mb
+        .filter(|mb| *mb > 0)
+        .unwrap_or(DEFAULT_INSTANCE_MEMORY_MB);
+    ((snapshot.effective_mb() / per_instance) as usize).max(1)
+}
+
+/// Builds a user-facing warning when the requested instance count will likely
+/// push the system into heavy swapping. Returns `None` when the session fits or
+/// memory information could not be read.
+pub fn memory_pressure_warning(requested: usize, per_instance_mb: Option<u64>) -> Option<String> {
+    let snapshot = read_memory_snapshot()?;
+    let suggested = suggest_max_instances(&snapshot, per_instance_mb);
+    if requested <= suggested {
+        return None;
+    }
+
+    let swap_kind = if snapshot.zram_swap { "zram" } else { "disk" };
+    Some(format!(
+        "Launching {requested} instances will likely thrash memory: {} MiB RAM available plus {} MiB free {swap_kind} swap fits about {suggested} instance(s) at {} MiB each.",
+        snapshot.available_mb,
+        snapshot.swap_free_mb,
+        per_instance_mb
+            .filter(|mb| *mb > 0)
+            .unwrap_or(DEFAULT_INSTANCE_MEMORY_MB),
+    ))
+}
@@ -0,0 +1,297 @@
+// Re-export all utility functions from submodules
+mod anticheat;
+mod artwork;
+mod calibration;
+mod cec_remote;
+mod coordinator;
+mod displays;
+mod emu_status;
+mod failure_hints;
+mod filesystem;
+mod firewall;
+mod gamescope;
+mod graphics;
+mod guest_bundle;
+mod handler_schema;
+mod handler_repo;
+mod hash;
+mod hotkeys;
+mod hotplug;
+mod idle;
+mod launch_history;
+mod launch_overrides;
+mod lock;
+mod memory;
+mod multiseat;
+mod nav_feedback;
+mod net;
+mod netmon;
+mod output_rules;
+mod pad_proxy;
+mod pe;
+mod playtime;
+mod prefixes;
+mod presets;
+mod profile_input;
+mod profiles;
+mod process_tree;
+mod prompts;
+mod proton;
+mod resources;
+mod sandbox;
+mod screenshots;
+mod steam_cloud;
+mod steam_dlc;
+mod steam_shortcuts;
+mod steamdeck;
+mod sys;
+mod system_check;
+mod system_info;
+mod uid_migration;
+mod updates;
+mod watch;
+mod web_remote;
+mod webhooks;
+mod window_probe;
+mod winetricks;
+mod working_trees;
+
+// Re-export functions from profiles
+pub use profiles::{
+    CharacterLook, Profile, ProfileStats, create_gamesave, create_profile,
+    ensure_nemirtingas_config, host_profile, pick_profile_avatar, profile_color,
+    remove_guest_profiles, rename_profile, resolve_nemirtingas_ports, scan_profiles,
+    set_host_profile, set_profile_avatar, synchronize_goldberg_profiles,
+};
+
+// Goldberg INI edits, also used when a game's own Goldberg config is adopted.
+pub(crate) use profiles::{ensure_ini_setting, read_config_value};
+
+// Proton prefixes named by profile id, listed with their owners on the storage page.
+pub use prefixes::{ProtonPrefix, list_proton_prefixes, profile_prefix_key};
+
+// Re-export functions from filesystem
+pub use filesystem::{
+    SanitizePath, build_symlink_tree, copy_dir_recursive, get_rootpath, get_rootpath_handler,
+    known_rootpath_handler, saved_rootpath, saved_rootpaths, write_atomic,
+};
+
+// Kernel anti-cheats found in a game folder, which block emulator play.
+pub use anticheat::{ANTICHEAT_TITLE, AntiCheat, anticheat_warning, detect_anticheat};
+
+// SteamGridDB artwork cached per game for the home tiles.
+pub use artwork::{ArtworkRequest, GameArtwork, cached_artwork, fetch_game_artwork};
+
+// Per-device stick calibration shared by menu navigation and the pad proxy.
+pub use calibration::{
+    AxisRange, DeviceCalibration, load_calibration, load_calibrations, remove_calibration,
+    save_calibration,
+};
+
+// The LAN coordinator lets several machines discover each other and launch in sync.
+pub use coordinator::Coordinator;
+
+// Pre-launch checks of the Goldberg and Nemirtingas configs of the picked profiles.
+pub use emu_status::{EmuIssue, EmuSeverity, EmulatorStatus, emulator_status};
+
+// Known crash signatures in instance output, turned into suggested fixes.
+pub use failure_hints::{FailureHint, classify_failure};
+
+// Firewall helpers open handler-declared LAN ports for the length of a session.
+pub use firewall::{
+    FirewallBackend, FirewallSession, detect_firewall, parse_port_spec, spawn_leftover_port_cleanup,
+};
+
+// Schema checks that point handler authors at typos and mistyped values.
+pub use handler_schema::{
+    HandlerIssue, IssueSeverity, format_handler_issues, validate_handler_json,
+};
+
+// Handler repository access backs the in-app handler browser.
+pub use handler_repo::{
+    RepoHandler, cache_handler_artwork, download_handler_archive, fetch_handler_index,
+};
+
+// Guest saves packed for the web remote's QR handoff and restored from it.
+pub use guest_bundle::{export_guest_bundle, import_guest_bundle, is_guest_profile};
+
+// Live gamescope tweaks for running instances.
+pub use gamescope::{gamescope_start_failure, set_gamescope_fps_limit};
+
+// Per-handler Direct3D options: dxvk.conf templates and VKD3D flags.
+pub use graphics::{is_vkd3d_flag, render_dxvk_conf, write_dxvk_conf};
+
+pub use hash::sha1_file;
+
+// Global keyboard shortcuts that control a running session.
+pub use hotkeys::HotkeyListener;
+
+// Pads that drop out mid-session are followed and rebound when they return.
+pub use hotplug::{DeviceMonitor, HotplugEvent};
+
+// Idle detection that suspends a session nobody is playing.
+pub use idle::{IdleWatcher, InputActivity, dim_display};
+
+// Recorded command lines per game, used to roll back to a configuration that worked.
+pub use launch_history::{
+    LaunchRecord, last_known_good, launch_settings_snapshot, load_launch_history, record_launch,
+};
+
+// Per-game argument, environment and DLL overrides edited on the game page.
+pub use launch_overrides::{LaunchOverrides, load_launch_overrides, save_launch_overrides};
+
+pub use lock::ProfileLock;
+
+// Expose the memory headroom helpers so both the launcher and the UI can warn
+// before a session oversubscribes RAM and swap.
+pub use memory::memory_pressure_warning;
+
+// Multi-seat helpers let the launcher re-run an instance command under another
+// local account while sharing the current display and audio sockets.
+pub use multiseat::{
+    find_seat_process, seat_user_for_slot, share_instance_paths, share_session_sockets,
+    wrap_command_for_user,
+};
+
+// Clicks and rumble pulses that answer controller navigation in the menus.
+pub use nav_feedback::{NavFeedback, play_nav_sound};
+
+// Outbound requests honor the proxy environment and the offline mode toggle.
+pub use net::{curl_command, offline_mode, set_offline_mode};
+
+// Network polling backs the LAN diagnostics card and tags launched instances.
+pub use netmon::{
+    INSTANCE_ENV_TAG, NetworkSnapshot, capture_network_snapshot, udp_port_taken,
+};
+
+// The pad proxy re-exposes calibrated gamepads through uinput for games.
+pub use pad_proxy::{
+    PadProxy, PadProxyOptions, find_motion_sensor, find_pad_companions, spawn_pad_proxy,
+};
+
+// Regex rules that hide or highlight lines of instance output.
+pub use output_rules::{
+    OutputAction, OutputFilter, OutputRule, default_output_rules, validate_output_pattern,
+};
+
+// Architecture of game executables, used to pick 32- or 64-bit emulator builds.
+pub use pe::{PeArch, elf_is32bit, pe_arch};
+
+// Per-game, per-profile playtime recorded as instances exit.
+pub use playtime::{
+    PlayStats, Playtime, format_playtime, migrate_playtime_game, record_playtime,
+    rename_playtime_profile,
+};
+
+// Saved launch configurations relaunched from the home page.
+pub use presets::{PresetSlot, SessionPreset, list_presets, remove_preset, save_preset};
+
+// Per-profile input preferences such as gyro aiming, accessibility assists and
+// button remaps, applied through the pad proxy.
+pub use profile_input::{
+    ASSIST_BUTTONS, GyroMapping, InputAssists, PadRemap, ProfileInputSettings,
+    load_profile_input, save_profile_input,
+};
+
+// Messages, questions and file pickers, shown by whichever frontend installed a prompter.
+pub use prompts::{
+    Prompter, msg, pick_file, pick_folder, prompts_available, set_prompter, yesno,
+};
+
+// Live scheduling changes for running instances and their child processes.
+pub use process_tree::{
+    process_affinity, process_niceness, process_tree, process_tree_tasks, set_tree_affinity,
+    set_tree_niceness,
+};
+
+// Downloaded Goldberg/Nemirtingas builds, pinned by checksum and chosen per handler.
+pub use resources::{
+    ResourceBuild, ResourceKind, ResourceRelease, bundled_goldberg_dir, bundled_goldberg_versions,
+    fetch_resource_releases, install_resource, installed_resources, is_version_name,
+    remove_resource, resource_dir,
+};
+
+// Bubblewrap sandbox for executables that ship with handlers.
+pub use sandbox::{run_sandboxed, sandbox_available};
+
+// Per-player screenshots through gamescope, triggered by a pad chord.
+pub use screenshots::{
+    DEFAULT_SCREENSHOT_CHORD, ScreenshotChordListener, parse_screenshot_chord,
+    save_instance_screenshot,
+};
+
+// Connected displays for pinning instances to a monitor.
+pub use displays::{Display, enumerate_displays};
+
+// Re-export functions from launcher
+pub use sys::{
+    KwinScript, get_screen_resolution, kwin_dbus_start_border_script, kwin_dbus_start_script,
+    kwin_dbus_unload_border_script, kwin_dbus_unload_script, notify_desktop,
+};
+
+// Host setup checks for the first-run wizard, the settings page and --doctor.
+pub use system_check::{SystemCheck, SystemFix, run_setup_checks, run_system_checks};
+
+// Host details written at the top of every session log for bug reports.
+pub use system_info::{
+    gamescope_info_line, prefetch_system_info, proton_build, system_info_lines,
+};
+
+// Copies Goldberg cloud saves into a real Steam account's userdata folder.
+pub use steam_cloud::{
+    SaveExportAction, SaveExportEntry, SaveExportPlan, SteamAccount, apply_save_export,
+    plan_goldberg_save_export, steam_accounts,
+};
+
+// DLC lists fetched from the Steam store so Goldberg unlocks a game's DLCs.
+pub use steam_dlc::{SteamDlc, steam_dlc_list, write_goldberg_dlc_list};
+
+// Non-Steam game shortcuts that launch a handler from Steam's library.
+pub use steam_shortcuts::{ShortcutArtwork, SteamShortcut, add_steam_shortcut, steam_is_running};
+
+// Surface Steam Deck specific helpers to the rest of the application so UI and
+// renderer code can adjust behaviour without reimplementing the detection.
+pub use steamdeck::{is_steam_deck, recommended_repaint_interval, recommended_zoom_factor};
+
+// Moves saves and per-game data when a handler is republished under a new uid.
+pub use uid_migration::{
+    SaveTransfer, migrate_game_uid, orphaned_game_uids, profiles_with_saves,
+    transfer_profile_saves,
+};
+
+// Re-export functions from updates
+pub use updates::{
+    ReleaseUpdate, check_for_split_happens_update, find_release_update, install_release_update,
+    relaunch,
+};
+
+// Watches the handler and profile folders so outside edits show up in the library.
+pub use watch::{LibraryChange, LibraryWatcher};
+
+// TV remote navigation over HDMI-CEC while the Deck is docked.
+pub use cec_remote::{CecKey, CecRemote};
+
+// Phone join page for picking profiles and readying up from the instances page.
+pub use web_remote::{
+    DEFAULT_WEB_REMOTE_PORT, GuestUpload, JoinScreen, RemoteAction, RemotePlayer, WebRemote,
+};
+
+// Game working trees under `run/`, listed and cleaned up from the storage page.
+pub use working_trees::{
+    WorkingTree, leftover_working_trees, list_working_trees, record_working_tree,
+    remove_working_tree, spawn_leftover_tree_cleanup, wait_for_tree_cleanup,
+};
+
+// Window lookups that show how far each instance's startup has come.
+pub use window_probe::{WindowProbe, active_window, client_windows, window_pid};
+
+// Session webhooks are fired by the launcher and tested from the settings page.
+pub use webhooks::{fire_session_webhook, finish_session_webhooks, test_session_webhook};
+
+// Re-export Proton helpers so the UI and launcher can reference them directly.
+pub use proton::{
+    ProtonEnvironment, ProtonInstall, discover_proton_versions, resolve_proton_environment,
+};
+
+// Runtimes handlers ask for, installed into each Proton prefix before launch.
+pub use winetricks::{install_winetricks_verb, is_winetricks_verb, missing_winetricks_verbs};
@@ -0,0 +1,283 @@
+use std::error::Error;
+use std::ffi::OsStr;
+use std::fs;
+use std::os::unix::fs::MetadataExt;
+use std::path::{Path, PathBuf};
+use std::process::{Child, Command};
+use std::time::{Duration, Instant};
+
+use crate::logging::log_warn;
+use crate::paths::{PATH_APP, PATH_HOME};
+
+/// Session variables a child running under another account needs so it can
+/// still reach the invoking user's display server and audio daemon.
+const SHARED_SESSION_VARS: [&str; 3] = ["DISPLAY", "XAUTHORITY", "XDG_SESSION_TYPE"];
+
+/// Picks the local account configured for an instance slot from the
+/// comma-separated settings value. Empty entries leave the slot on the
+/// current user.
+pub fn seat_user_for_slot(users: &str, index: usize) -> Option<String> {
+    users
+        .split(',')
+        .map(str::trim)
+        .nth(index)
+        .filter(|user| !user.is_empty())
+        .map(str::to_string)
+}
+
+/// Resolves a bare program name through `PATH` so `machinectl shell`, which
+/// requires absolute paths, can execute it.
+fn resolve_program(program: &OsStr) -> PathBuf {
+    let path = Path::new(program);
+    if path.is_absolute() {
+        return path.to_path_buf();
+    }
+    std::env::var_os("PATH")
+        .and_then(|paths| {
+            std::env::split_paths(&paths)
+                .map(|dir| dir.join(path))
+                .find(|candidate| candidate.exists())
+        })
+        .unwrap_or_else(|| path.to_path_buf())
+}
+
+/// Checks whether a helper binary is reachable on `PATH`.
+fn has_program(name: &str) -> bool {
+    resolve_program(OsStr::new(name)).is_absolute()
+}
+
+/// Collects the display and audio socket locations of the current session as
+/// explicit environment assignments for the target user.
+fn shared_session_env() -> Vec<(String, String)> {
+    let mut vars: Vec<(String, String)> = SHARED_SESSION_VARS
+        .iter()
+        .filter_map(|key| std::env::var(key).ok().map(|value| (key.to_string(), value)))
+        .collect();
+
+    // Sockets under XDG_RUNTIME_DIR are addressed absolutely because the other
+    // account has its own runtime directory.
+    if let Ok(runtime_dir) = std::env::var("XDG_RUNTIME_DIR") {
+        if let Ok(wayland) = std::env::var("WAYLAND_DISPLAY") {
+            let socket = if wayland.starts_with('/') {
+                wayland
+            } else {
+                format!("{runtime_dir}/{wayland}")
+            };
+            vars.push(("WAYLAND_DISPLAY".to_string(), socket));
+        }
+        vars.push((
+            "PIPEWIRE_REMOTE".to_string(),
+            format!("{runtime_dir}/pipewire-0"),
+        ));
+        vars.push((
+            "PULSE_SERVER".to_string(),
+            format!("unix:{runtime_dir}/pulse/native"),
+        ));
+    }
+    vars
+}
+
+/// Grants `user` access to the invoking session's X server, Wayland socket and
+/// PipeWire/Pulse sockets. Failures are logged but not fatal so the launch can
+/// still proceed when the user already has access.
+pub fn share_session_sockets(user: &str) {
+    if std::env::var("DISPLAY").is_ok() {
+        let status = Command::new("xhost")
+            .arg(format!("+SI:localuser:{user}"))
+            .status();
+        if !matches!(status, Ok(status) if status.success()) {
+            log_warn(format!("Failed to grant X11 access to {user} via xhost"));
+        }
+    }
+
+    let Ok(runtime_dir) = std::env::var("XDG_RUNTIME_DIR") else {
+        return;
+    };
+    let mut acl_targets = vec![(runtime_dir.clone(), "x")];
+    if let Ok(wayland) = std::env::var("WAYLAND_DISPLAY") {
+        acl_targets.push((format!("{runtime_dir}/{wayland}"), "rw"));
+    }
+    acl_targets.push((format!("{runtime_dir}/pipewire-0"), "rw"));
+    acl_targets.push((format!("{runtime_dir}/pulse"), "x"));
+    acl_targets.push((format!("{runtime_dir}/pulse/native"), "rw"));
+
+    for (target, perms) in acl_targets {
+        if !Path::new(&target).exists() {
+            continue;
+        }
+        let status = Command::new("setfacl")
+            .arg("-m")
+            .arg(format!("u:{user}:{perms}"))
+            .arg(&target)
+            .status();
+        if !matches!(status, Ok(status) if status.success()) {
+            log_warn(format!("Failed to share {target} with {user} via setfacl"));
+        }
+    }
+}
+
+/// Rebuilds a fully prepared instance command so it runs as `user`, preferring
+/// `run0` and falling back to `machinectl shell`. Environment variables and the
+/// working directory are carried over explicitly because both helpers start
+/// the target from a clean login environment.
+pub fn wrap_command_for_user(cmd: &Command, user: &str) -> Result<Command, Box<dyn Error>> {
+    let mut env: Vec<(String, String)> = shared_session_env();
+    for (key, value) in cmd.get_envs() {
+        if let Some(value) = value {
+            env.retain(|(existing, _)| existing.as_str() != key.to_string_lossy());
+            env.push((
+                key.to_string_lossy().to_string(),
+                value.to_string_lossy().to_string(),
+            ));
+        }
+    }
+
+    let mut wrapped = if has_program("run0") {
+        let mut wrapped = Command::new("run0");
+        wrapped.arg(format!("--user={user}"));
+        if let Some(dir) = cmd.get_current_dir() {
+            wrapped.arg(format!("--chdir={}", dir.display()));
+        }
+        for (key, value) in &env {
+            wrapped.arg(format!("--setenv={key}={value}"));
+        }
+        wrapped.arg(cmd.get_program());
+        wrapped
+    } else if has_program("machinectl") {
+        let mut wrapped = Command::new("machinectl");
+        wrapped.arg("shell").arg(format!("--uid={user}"));
+        for (key, value) in &env {
+            wrapped.arg(format!("--setenv={key}={value}"));
+        }
+        // machinectl has no working directory flag, so hop through env -C.
+        wrapped.arg(".host").arg("/usr/bin/env");
+        if let Some(dir) = cmd.get_current_dir() {
+            wrapped.arg("-C").arg(dir);
+        }
+        wrapped.arg(resolve_program(cmd.get_program()));
+        wrapped
+    } else {
+        return Err("Running instances as separate users requires run0 or machinectl".into());
+    };
+
+    wrapped.args(cmd.get_args());
+    Ok(wrapped)
+}
+
+/// Gives `user` access to what an instance run as them reads under the
+/// invoking user's home, which other accounts usually can't enter: the Split
+/// Happens folder read-write, `extra` and the folders of absolute paths among
+/// `cmd`'s arguments read-only. Folders on the way only get search
+/// permission. Fails naming the path that couldn't be shared, since the
+/// instance can't start without it.
+pub fn share_instance_paths(
+    cmd: &Command,
+    user: &str,
+    extra: &[PathBuf],
+) -> Result<(), Box<dyn Error>> {
+    let home = PATH_HOME.canonicalize().unwrap_or_else(|_| PATH_HOME.clone());
+    let app = PATH_APP.canonicalize().unwrap_or_else(|_| PATH_APP.clone());
+    let mut paths: Vec<PathBuf> = extra.to_vec();
+    paths.extend(cmd.get_current_dir().map(Path::to_path_buf));
+    paths.extend(cmd.get_args().map(PathBuf::from).filter(|path| path.is_absolute()));
+
+    let mut targets: Vec<PathBuf> = Vec::new();
+    for path in paths {
+        let Ok(path) = path.canonicalize() else {
+            continue;
+        };
+        let target = if path.starts_with(&app) {
+            app.clone()
+        } else if path.is_dir() {
+            path
+        } else {
+            path.parent().map_or(path.clone(), Path::to_path_buf)
+        };
+        // Folders holding the Split Happens folder, like the home itself,
+        // would share far more than the instance needs.
+        if !target.starts_with(&home) || app.starts_with(&target) || targets.contains(&target) {
+            continue;
+        }
+        targets.push(target);
+    }
+    // A recursive grant on a folder already covers what lies inside it.
+    let nested: Vec<PathBuf> = targets
+        .iter()
+        .filter(|target| targets.iter().any(|other| other != *target && target.starts_with(other)))
+        .cloned()
+        .collect();
+    targets.retain(|target| !nested.contains(target));
+
+    for target in &targets {
+        for folder in target.ancestors().skip(1).take_while(|folder| folder.starts_with(&home)) {
+            grant_acl(user, "x", folder, false)?;
+        }
+        let perms = if target.starts_with(&app) { "rwX" } else { "rX" };
+        grant_acl(user, perms, target, true)?;
+    }
+    Ok(())
+}
+
+fn grant_acl(user: &str, perms: &str, path: &Path, recursive: bool) -> Result<(), Box<dyn Error>> {
+    let mut cmd = Command::new("setfacl");
+    if recursive {
+        cmd.arg("-R");
+    }
+    let status = cmd.arg("-m").arg(format!("u:{user}:{perms}")).arg(path).status();
+    if matches!(status, Ok(status) if status.success()) {
+        return Ok(());
+    }
+    Err(format!(
+        "Couldn't give the account {user} access to {} with setfacl. Install the acl package \
+         or run this game without separate users.",
+        path.display()
+    )
+    .into())
+}
+
+/// PID of the instance [`wrap_command_for_user`] started from `cmd` as
+/// `user`. run0 and machinectl hand the command to the service manager, so it
+/// isn't a child of the wrapper; it is found by its owner and arguments
+/// instead. Waits up to `timeout`, e.g. while the user authenticates, or
+/// until `wrapper` exits.
+pub fn find_seat_process(
+    cmd: &Command,
+    user: &str,
+    wrapper: &mut Child,
+    timeout: Duration,
+) -> Option<u32> {
+    let uid = account_uid(user)?;
+    let args: Vec<&OsStr> = cmd.get_args().collect();
+    let deadline = Instant::now() + timeout;
+    loop {
+        let found = fs::read_dir("/proc")
+            .into_iter()
+            .flatten()
+            .flatten()
+            .filter_map(|entry| {
+                let pid = entry.file_name().to_str()?.parse::<u32>().ok()?;
+                (entry.metadata().ok()?.uid() == uid).then_some(pid)
+            })
+            .filter(|pid| {
+                let Ok(cmdline) = fs::read(format!("/proc/{pid}/cmdline")) else {
+                    return false;
+                };
+                let cmdline = cmdline.strip_suffix(&[0]).unwrap_or(&cmdline);
+                let argv: Vec<&[u8]> = cmdline.split(|byte| *byte == 0).skip(1).collect();
+                argv.len() == args.len()
+                    && argv.iter().zip(&args).all(|(a, b)| *a == b.as_encoded_bytes())
+            })
+            // The newest match, should an older run still be exiting.
+            .max();
+        let wrapper_gone = !matches!(wrapper.try_wait(), Ok(None));
+        if found.is_some() || wrapper_gone || Instant::now() >= deadline {
+            return found;
+        }
+        std::thread::sleep(Duration::from_millis(200));
+    }
+}
+
+fn account_uid(user: &str) -> Option<u32> {
+    let output = Command::new("id").arg("-u").arg(user).output().ok()?;
+    String::from_utf8_lossy(&output.stdout).trim().parse().ok()
+}
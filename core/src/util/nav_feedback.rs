@@ -0,0 +1,90 @@
+use std::error::Error;
+use std::f32::consts::TAU;
+use std::fs;
+use std::path::PathBuf;
+use std::process::Command;
+
+use crate::paths::PATH_APP;
+
+const SAMPLE_RATE: u32 = 44_100;
+
+/// What a controller press did in the menus, which picks the click played.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum NavFeedback {
+    /// Focus moved or the page changed.
+    Move,
+    /// The focused entry was activated.
+    Confirm,
+}
+
+impl NavFeedback {
+    /// Pitch in Hz, length in ms and file name of the click.
+    fn sound(self) -> (f32, u32, &'static str) {
+        match self {
+            NavFeedback::Move => (1400.0, 25, "nav_move.wav"),
+            NavFeedback::Confirm => (900.0, 60, "nav_confirm.wav"),
+        }
+    }
+
+    /// Strong and weak motor magnitudes and length in ms of the rumble pulse.
+    pub fn rumble(self) -> (u16, u16, u16) {
+        match self {
+            NavFeedback::Move => (0, 0x5000, 25),
+            NavFeedback::Confirm => (0x3000, 0x7000, 50),
+        }
+    }
+}
+
+/// A quiet sine blip that fades out, as 16-bit mono PCM in a WAV container.
+fn click_wav(frequency: f32, length_ms: u32) -> Vec<u8> {
+    let samples = SAMPLE_RATE * length_ms / 1000;
+    let data_len = samples * 2;
+    let mut wav = Vec::with_capacity(44 + data_len as usize);
+    wav.extend_from_slice(b"RIFF");
+    wav.extend_from_slice(&(36 + data_len).to_le_bytes());
+    wav.extend_from_slice(b"WAVEfmt ");
+    wav.extend_from_slice(&16u32.to_le_bytes());
+    // PCM, one channel, sample rate, byte rate, block align, bits per sample.
+    wav.extend_from_slice(&1u16.to_le_bytes());
+    wav.extend_from_slice(&1u16.to_le_bytes());
+    wav.extend_from_slice(&SAMPLE_RATE.to_le_bytes());
+    wav.extend_from_slice(&(SAMPLE_RATE * 2).to_le_bytes());
+    wav.extend_from_slice(&2u16.to_le_bytes());
+    wav.extend_from_slice(&16u16.to_le_bytes());
+    wav.extend_from_slice(b"data");
+    wav.extend_from_slice(&data_len.to_le_bytes());
+    for i in 0..samples {
+        let t = i as f32 / SAMPLE_RATE as f32;
+        let fade = 1.0 - i as f32 / samples as f32;
+        let value = (TAU * frequency * t).sin() * fade * fade * 0.2;
+        wav.extend_from_slice(&((value * i16::MAX as f32) as i16).to_le_bytes());
+    }
+    wav
+}
+
+/// Click of `feedback` under `PATH_APP/sounds`, written on first use.
+fn click_file(feedback: NavFeedback) -> Result<PathBuf, Box<dyn Error>> {
+    let (frequency, length_ms, name) = feedback.sound();
+    let path = PATH_APP.join("sounds").join(name);
+    if !path.is_file() {
+        fs::create_dir_all(PATH_APP.join("sounds"))?;
+        fs::write(&path, click_wav(frequency, length_ms))?;
+    }
+    Ok(path)
+}
+
+/// Plays the menu click for `feedback` through PipeWire, or PulseAudio when
+/// pw-play is missing, without waiting for it to finish.
+pub fn play_nav_sound(feedback: NavFeedback) {
+    let Ok(path) = click_file(feedback) else {
+        return;
+    };
+    let spawned = Command::new("pw-play")
+        .arg(&path)
+        .spawn()
+        .or_else(|_| Command::new("paplay").arg(&path).spawn());
+    // Reap it in the background so no zombie is left behind.
+    if let Ok(mut child) = spawned {
+        std::thread::spawn(move || child.wait());
+    }
+}
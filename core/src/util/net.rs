@@ -0,0 +1,38 @@
+use std::env;
+use std::io;
+use std::process::Command;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// Mirrors the "Offline mode" setting so helpers without access to the config
+/// can refuse outbound requests.
+static OFFLINE: AtomicBool = AtomicBool::new(false);
+
+/// Turns offline mode on or off for the whole process. Called once the
+/// settings are loaded and whenever the toggle changes.
+pub fn set_offline_mode(offline: bool) {
+    OFFLINE.store(offline, Ordering::Relaxed);
+}
+
+pub fn offline_mode() -> bool {
+    OFFLINE.load(Ordering::Relaxed)
+}
+
+/// Starts a system curl invocation for an outbound request, or fails when
+/// offline mode is on. Every download, update check and webhook goes through
+/// here so the toggle covers all of them.
+///
+/// curl reads `https_proxy`, `HTTPS_PROXY`, `ALL_PROXY` and `NO_PROXY` on its
+/// own but ignores an upper-case `HTTP_PROXY`, which many desktops export, so
+/// that one is passed on as `http_proxy`.
+pub fn curl_command() -> io::Result<Command> {
+    if offline_mode() {
+        return Err(io::Error::other("Offline mode is on"));
+    }
+    let mut cmd = Command::new("curl");
+    if env::var_os("http_proxy").is_none() {
+        if let Some(proxy) = env::var_os("HTTP_PROXY").filter(|p| !p.is_empty()) {
+            cmd.env("http_proxy", proxy);
+        }
+    }
+    Ok(cmd)
+}
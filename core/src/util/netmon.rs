@@ -0,0 +1,222 @@
+use std::collections::HashMap;
+use std::fs;
+
+use super::firewall::{FirewallBackend, detect_firewall};
+
+/// Environment tag the launcher sets on every instance so sockets can be
+/// attributed back to the player slot that opened them.
+pub const INSTANCE_ENV_TAG: &str = "SPLIT_HAPPENS_INSTANCE";
+
+/// System-wide UDP counters from `/proc/net/snmp`. Every program on the
+/// machine adds to them, so they only show whether UDP moves at all; the
+/// per-instance picture comes from each [`InstanceSocket`].
+#[derive(Clone, Copy, Debug, Default)]
+pub struct UdpCounters {
+    pub in_datagrams: u64,
+    pub out_datagrams: u64,
+    pub in_errors: u64,
+    pub rcvbuf_errors: u64,
+}
+
+impl UdpCounters {
+    /// Returns the growth of every counter since `earlier`.
+    pub fn delta(&self, earlier: &UdpCounters) -> UdpCounters {
+        UdpCounters {
+            in_datagrams: self.in_datagrams.saturating_sub(earlier.in_datagrams),
+            out_datagrams: self.out_datagrams.saturating_sub(earlier.out_datagrams),
+            in_errors: self.in_errors.saturating_sub(earlier.in_errors),
+            rcvbuf_errors: self.rcvbuf_errors.saturating_sub(earlier.rcvbuf_errors),
+        }
+    }
+}
+
+/// A UDP socket owned by a launched instance.
+#[derive(Clone, Debug)]
+pub struct InstanceSocket {
+    pub instance: String,
+    pub process: String,
+    pub pid: u32,
+    pub local_port: u16,
+    pub ipv6: bool,
+    /// Bytes that arrived for this socket and wait for the game to read them.
+    pub queued: u64,
+    /// Datagrams the kernel dropped for this socket, usually because the game
+    /// stopped reading or its receive buffer overflowed.
+    pub drops: u64,
+}
+
+impl InstanceSocket {
+    /// Datagrams dropped since the same socket showed up in `earlier`, or
+    /// `None` when it wasn't open back then.
+    pub fn drops_since(&self, earlier: &NetworkSnapshot) -> Option<u64> {
+        earlier
+            .sockets
+            .iter()
+            .find(|socket| {
+                socket.pid == self.pid
+                    && socket.local_port == self.local_port
+                    && socket.ipv6 == self.ipv6
+            })
+            .map(|socket| self.drops.saturating_sub(socket.drops))
+    }
+}
+
+/// Point-in-time view of LAN discovery traffic, gathered by polling procfs so
+/// no capture capabilities or root access are needed.
+#[derive(Clone, Debug, Default)]
+pub struct NetworkSnapshot {
+    pub counters: UdpCounters,
+    pub sockets: Vec<InstanceSocket>,
+    /// Firewall filtering traffic on this machine, if any.
+    pub firewall: Option<FirewallBackend>,
+}
+
+/// Parses the `Udp:` header/value line pair from `/proc/net/snmp`.
+fn read_udp_counters() -> UdpCounters {
+    let Ok(snmp) = fs::read_to_string("/proc/net/snmp") else {
+        return UdpCounters::default();
+    };
+    let udp_lines: Vec<&str> = snmp.lines().filter(|line| line.starts_with("Udp:")).collect();
+    if udp_lines.len() < 2 {
+        return UdpCounters::default();
+    }
+
+    let values: HashMap<&str, u64> = udp_lines[0]
+        .split_whitespace()
+        .skip(1)
+        .zip(
+            udp_lines[1]
+                .split_whitespace()
+                .skip(1)
+                .map(|value| value.parse::<u64>().unwrap_or(0)),
+        )
+        .collect();
+    let get = |key: &str| values.get(key).copied().unwrap_or(0);
+
+    UdpCounters {
+        in_datagrams: get("InDatagrams"),
+        out_datagrams: get("OutDatagrams"),
+        in_errors: get("InErrors"),
+        rcvbuf_errors: get("RcvbufErrors"),
+    }
+}
+
+/// Reads `/proc/net/udp` or `udp6` into `(inode, local_port, queued, drops)`
+/// tuples.
+fn read_udp_table(path: &str) -> Vec<(u64, u16, u64, u64)> {
+    let Ok(table) = fs::read_to_string(path) else {
+        return Vec::new();
+    };
+    table
+        .lines()
+        .skip(1)
+        .filter_map(|line| {
+            let fields: Vec<&str> = line.split_whitespace().collect();
+            // local_address is `HEXADDR:HEXPORT`, column 4 is `TXQUEUE:RXQUEUE`
+            // in hex, inode is column 9 and drops is the final column.
+            let port_hex = fields.get(1)?.rsplit(':').next()?;
+            let port = u16::from_str_radix(port_hex, 16).ok()?;
+            let queued = fields
+                .get(4)
+                .and_then(|queues| queues.split_once(':'))
+                .and_then(|(_, rx)| u64::from_str_radix(rx, 16).ok())
+                .unwrap_or(0);
+            let inode = fields.get(9)?.parse::<u64>().ok()?;
+            let drops = fields.last()?.parse::<u64>().unwrap_or(0);
+            Some((inode, port, queued, drops))
+        })
+        .collect()
+}
+
+/// Returns the instance tag from a process environment, if the process was
+/// started by the launcher.
+fn instance_tag(pid: &str) -> Option<String> {
+    let environ = fs::read(format!("/proc/{pid}/environ")).ok()?;
+    let prefix = format!("{INSTANCE_ENV_TAG}=");
+    environ
+        .split(|byte| *byte == 0)
+        .filter_map(|entry| std::str::from_utf8(entry).ok())
+        .find_map(|entry| entry.strip_prefix(prefix.as_str()).map(str::to_string))
+}
+
+/// Maps socket inodes to `(pid, instance, process name)` for every tagged
+/// process the current user can inspect.
+fn tagged_socket_owners() -> HashMap<u64, (u32, String, String)> {
+    let mut owners = HashMap::new();
+    let Ok(procs) = fs::read_dir("/proc") else {
+        return owners;
+    };
+
+    for proc_entry in procs.flatten() {
+        let pid_name = proc_entry.file_name().to_string_lossy().to_string();
+        let Ok(pid) = pid_name.parse::<u32>() else {
+            continue;
+        };
+        let Some(instance) = instance_tag(&pid_name) else {
+            continue;
+        };
+        let process = fs::read_to_string(format!("/proc/{pid_name}/comm"))
+            .map(|comm| comm.trim().to_string())
+            .unwrap_or_default();
+
+        let Ok(fds) = fs::read_dir(format!("/proc/{pid_name}/fd")) else {
+            continue;
+        };
+        for fd in fds.flatten() {
+            let Ok(target) = fs::read_link(fd.path()) else {
+                continue;
+            };
+            let target = target.to_string_lossy();
+            if let Some(inode) = target
+                .strip_prefix("socket:[")
+                .and_then(|rest| rest.strip_suffix(']'))
+                .and_then(|inode| inode.parse::<u64>().ok())
+            {
+                owners.insert(inode, (pid, instance.clone(), process.clone()));
+            }
+        }
+    }
+    owners
+}
+
+/// Polls procfs for UDP counters and the sockets held by launched instances,
+/// and checks for an active firewall.
+pub fn capture_network_snapshot() -> NetworkSnapshot {
+    let owners = tagged_socket_owners();
+    let mut sockets = Vec::new();
+
+    for (path, ipv6) in [("/proc/net/udp", false), ("/proc/net/udp6", true)] {
+        for (inode, local_port, queued, drops) in read_udp_table(path) {
+            if let Some((pid, instance, process)) = owners.get(&inode) {
+                sockets.push(InstanceSocket {
+                    instance: instance.clone(),
+                    process: process.clone(),
+                    pid: *pid,
+                    local_port,
+                    ipv6,
+                    queued,
+                    drops,
+                });
+            }
+        }
+    }
+
+    sockets.sort_by(|a, b| (&a.instance, a.local_port).cmp(&(&b.instance, b.local_port)));
+
+    NetworkSnapshot {
+        counters: read_udp_counters(),
+        sockets,
+        firewall: detect_firewall(),
+    }
+}
+
+/// Whether a UDP socket on `port` is open in a process the launcher didn't
+/// start, which would keep instances from binding it.
+pub fn udp_port_taken(port: u16) -> bool {
+    let owners = tagged_socket_owners();
+    ["/proc/net/udp", "/proc/net/udp6"].into_iter().any(|path| {
+        read_udp_table(path)
+            .into_iter()
+            .any(|(inode, local_port, _, _)| local_port == port && !owners.contains_key(&inode))
+    })
+}
@@ -0,0 +1,111 @@
+use std::collections::HashMap;
+use std::sync::{LazyLock, Mutex};
+
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+
+/// What happens to a line of instance output a rule matches.
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Debug)]
+pub enum OutputAction {
+    /// Dropped from the console and the session log.
+    Hide,
+    /// Kept and marked, so it stands out between the engine's chatter.
+    Highlight,
+}
+
+impl OutputAction {
+    pub const ALL: [OutputAction; 2] = [OutputAction::Hide, OutputAction::Highlight];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            OutputAction::Hide => "Hide",
+            OutputAction::Highlight => "Highlight",
+        }
+    }
+}
+
+/// A regular expression matched against each trimmed line an instance prints.
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+pub struct OutputRule {
+    pub pattern: String,
+    pub action: OutputAction,
+}
+
+/// Rules new settings start with: gamescope's endless duplicate-buffer
+/// warning is hidden; crashes, failed assertions and the Nemirtingas EOS
+/// emulator's `COMMAND_STATE_SUBMITTED` assertion are highlighted.
+pub fn default_output_rules() -> Vec<OutputRule> {
+    let rule = |pattern: &str, action| OutputRule {
+        pattern: pattern.to_string(),
+        action,
+    };
+    vec![
+        rule(
+            r"^\[gamescope.*\[Warn\]  xwm: got the same buffer committed twice, ignoring\.$",
+            OutputAction::Hide,
+        ),
+        rule(
+            r"(?i)\b(fatal|panic|segmentation fault|unhandled exception|assertion failed)\b",
+            OutputAction::Highlight,
+        ),
+        // Nemirtingas asserts this when stale EOS commands survive in its
+        // caches, which launches clear; the line is the only other sign of it.
+        rule(r"COMMAND_STATE_SUBMITTED", OutputAction::Highlight),
+    ]
+}
+
+/// Compiled output rules, shared by the output readers of a session. The
+/// first rule that matches a line decides what happens to it.
+pub struct OutputFilter {
+    rules: Vec<(Regex, OutputAction)>,
+}
+
+impl OutputFilter {
+    /// Compiles `rules`, skipping the ones whose pattern is invalid. Those
+    /// come back as messages naming the pattern and the problem.
+    pub fn new(rules: &[OutputRule]) -> (OutputFilter, Vec<String>) {
+        let mut compiled = Vec::new();
+        let mut errors = Vec::new();
+        for rule in rules.iter().filter(|rule| !rule.pattern.is_empty()) {
+            match Regex::new(&rule.pattern) {
+                Ok(regex) => compiled.push((regex, rule.action)),
+                Err(err) => {
+                    errors.push(format!("Output rule \"{}\" is invalid: {err}", rule.pattern))
+                }
+            }
+        }
+        (OutputFilter { rules: compiled }, errors)
+    }
+
+    /// Action of the first rule matching `line`, if any.
+    pub fn action(&self, line: &str) -> Option<OutputAction> {
+        let line = line.trim();
+        self.rules
+            .iter()
+            .find(|(regex, _)| regex.is_match(line))
+            .map(|(_, action)| *action)
+    }
+}
+
+/// Patterns checked so far with their result, so the settings page, which
+/// checks every rule each frame, compiles each pattern only once.
+static VALIDATED_PATTERNS: LazyLock<Mutex<HashMap<String, Result<(), String>>>> =
+    LazyLock::new(Mutex::default);
+
+/// Most patterns kept in [`VALIDATED_PATTERNS`]; typing a pattern checks
+/// every prefix of it.
+const VALIDATED_PATTERNS_MAX: usize = 256;
+
+/// Checks a pattern typed into the settings, for showing the problem inline.
+pub fn validate_output_pattern(pattern: &str) -> Result<(), String> {
+    let mut validated = VALIDATED_PATTERNS.lock().unwrap();
+    if let Some(result) = validated.get(pattern) {
+        return result.clone();
+    }
+    if validated.len() >= VALIDATED_PATTERNS_MAX {
+        validated.clear();
+    }
+    let result = Regex::new(pattern).map(|_| ()).map_err(|err| err.to_string());
+    validated.insert(pattern.to_string(), result.clone());
+    result
+}
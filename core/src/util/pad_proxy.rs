@@ -0,0 +1,902 @@
+use std::collections::{HashMap, HashSet};
+use std::error::Error;
+use std::os::fd::AsRawFd;
+use std::sync::{Arc, Mutex};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant};
+
+use evdev::uinput::VirtualDevice;
+use evdev::{
+    AbsInfo, AbsoluteAxisCode, AttributeSet, Device, EventSummary, EventType, FFEffect,
+    InputEvent, KeyCode, PropType, UInputCode, UinputAbsSetup,
+};
+use nix::libc;
+
+use super::calibration::{AxisRange, DeviceCalibration};
+use super::idle::{InputActivity, is_player_activity};
+use super::profile_input::{GyroMapping, InputAssists, PadRemap};
+use crate::logging::{log_info, log_warn};
+
+/// Stick axes the proxy recenters, rescales and applies dead zones to.
+const STICK_AXES: [AbsoluteAxisCode; 4] = [
+    AbsoluteAxisCode::ABS_X,
+    AbsoluteAxisCode::ABS_Y,
+    AbsoluteAxisCode::ABS_RX,
+    AbsoluteAxisCode::ABS_RY,
+];
+
+/// Analog triggers most pads report as axes rather than buttons, with the
+/// button their turbo and toggle-hold assists are set on.
+const TRIGGER_AXES: [(AbsoluteAxisCode, KeyCode); 2] = [
+    (AbsoluteAxisCode::ABS_Z, KeyCode::BTN_TL2),
+    (AbsoluteAxisCode::ABS_RZ, KeyCode::BTN_TR2),
+];
+
+/// Button of the trigger reported on `axis`, if it is one.
+fn trigger_button(axis: AbsoluteAxisCode) -> Option<KeyCode> {
+    TRIGGER_AXES
+        .iter()
+        .find(|(trigger, _)| *trigger == axis)
+        .map(|(_, button)| *button)
+}
+
+/// How long the forwarding thread sleeps when the physical pad has no events.
+const PROXY_IDLE_SLEEP: Duration = Duration::from_millis(1);
+
+/// Angular velocity that produces full stick deflection at sensitivity 1.0.
+const GYRO_FULL_DEFLECTION_DPS: f32 = 360.0;
+
+/// Rates below this are treated as sensor drift and ignored.
+const GYRO_DRIFT_DPS: f32 = 1.5;
+
+/// Transformations applied while forwarding a physical pad to its virtual twin.
+#[derive(Clone, Default)]
+pub struct PadProxyOptions {
+    pub calibration: Option<DeviceCalibration>,
+    /// Gyro mapping plus the motion sensor node it reads from.
+    pub gyro: Option<(GyroMapping, String)>,
+    pub assists: Option<InputAssists>,
+    /// Button swaps, stick inversion and dead zone override of the player.
+    pub remap: Option<PadRemap>,
+    /// Extra pads merged into this one for co-pilot play, each with its own
+    /// calibration.
+    pub copilots: Vec<(String, Option<DeviceCalibration>)>,
+    /// Where the player's input is recorded for idle detection.
+    pub activity: Option<InputActivity>,
+}
+
+/// Picks the stick position pushed furthest from center.
+fn strongest_position(positions: &HashMap<usize, f32>) -> f32 {
+    positions
+        .values()
+        .copied()
+        .fold(0.0, |best, value| if value.abs() > best.abs() { value } else { best })
+}
+
+/// Combines events from co-pilot pads so either player can press a button or
+/// move a stick without a resting pad cancelling the other one out.
+#[derive(Default)]
+struct MergeState {
+    /// Sources currently holding each key.
+    held: HashMap<u16, HashSet<usize>>,
+    /// Normalized stick position per axis and source.
+    sticks: HashMap<u16, HashMap<usize, f32>>,
+}
+
+impl MergeState {
+    /// Merges one event from `source`. Returns `None` when the merged state
+    /// did not change (e.g. a release while the other pad still holds the key).
+    fn apply(
+        &mut self,
+        source: usize,
+        event: InputEvent,
+        declared: &HashMap<u16, AxisRange>,
+    ) -> Option<InputEvent> {
+        match event.destructure() {
+            EventSummary::Key(_, key, value) => {
+                let holders = self.held.entry(key.0).or_default();
+                let was_held = !holders.is_empty();
+                match value {
+                    0 => holders.remove(&source),
+                    1 => holders.insert(source),
+                    _ => return Some(event),
+                };
+                (was_held != !holders.is_empty()).then_some(event)
+            }
+            EventSummary::AbsoluteAxis(_, axis, value) if STICK_AXES.contains(&axis) => {
+                let Some(range) = declared.get(&axis.0) else {
+                    return Some(event);
+                };
+                let positions = self.sticks.entry(axis.0).or_default();
+                positions.insert(source, range.normalize(value));
+                Some(InputEvent::new(
+                    EventType::ABSOLUTE.0,
+                    axis.0,
+                    AxisRange::denormalize(range, strongest_position(positions)),
+                ))
+            }
+            _ => Some(event),
+        }
+    }
+
+    /// Releases everything a disconnected source was holding.
+    fn forget(&mut self, source: usize, declared: &HashMap<u16, AxisRange>) -> Vec<InputEvent> {
+        let mut events = Vec::new();
+        for (code, holders) in self.held.iter_mut() {
+            if holders.remove(&source) && holders.is_empty() {
+                events.push(InputEvent::new(EventType::KEY.0, *code, 0));
+            }
+        }
+        for (code, positions) in self.sticks.iter_mut() {
+            if positions.remove(&source).is_none() {
+                continue;
+            }
+            if let Some(range) = declared.get(code) {
+                let value = AxisRange::denormalize(range, strongest_position(positions));
+                events.push(InputEvent::new(EventType::ABSOLUTE.0, *code, value));
+            }
+        }
+        events
+    }
+}
+
+/// One physical pad feeding the proxy: the primary pad or a co-pilot.
+struct ProxySource {
+    path: String,
+    device: Device,
+    calibration: Option<DeviceCalibration>,
+    /// Ranges the device itself declares, used to rescale into the primary's.
+    ranges: HashMap<u16, AxisRange>,
+    connected: bool,
+}
+
+impl ProxySource {
+    fn open(path: &str, calibration: Option<DeviceCalibration>) -> Result<Self, Box<dyn Error>> {
+        let device = Device::open(path)?;
+        let ranges = device
+            .get_absinfo()?
+            .map(|(axis, info)| (axis.0, AxisRange::from_bounds(info.minimum(), info.maximum())))
+            .collect();
+        Ok(Self {
+            path: path.to_string(),
+            device,
+            calibration,
+            ranges,
+            connected: true,
+        })
+    }
+}
+
+/// Live state for turbo and toggle-hold buttons.
+#[derive(Default)]
+struct AssistState {
+    /// Toggle buttons currently latched down.
+    latched: HashSet<u16>,
+    /// Turbo buttons physically held, with their last flip time and output state.
+    turbo: HashMap<u16, (Instant, bool)>,
+    /// Trigger axes pulled past half way, to tell presses from releases.
+    triggers_down: HashSet<u16>,
+    /// Turbo triggers physically held, by axis, like `turbo`.
+    turbo_triggers: HashMap<u16, (Instant, bool)>,
+}
+
+impl AssistState {
+    /// Applies toggle-hold, turbo and stick sensitivity to one event. Returns
+    /// `None` when the event is swallowed (e.g. the release of a toggle button).
+    fn apply(
+        &mut self,
+        event: InputEvent,
+        assists: &InputAssists,
+        declared: &HashMap<u16, AxisRange>,
+    ) -> Option<InputEvent> {
+        match event.destructure() {
+            EventSummary::Key(_, key, value) if assists.toggle_buttons.contains(&key.0) => {
+                // Only fresh presses flip the latch; releases and repeats are dropped.
+                if value != 1 {
+                    return None;
+                }
+                let down = if self.latched.remove(&key.0) {
+                    false
+                } else {
+                    self.latched.insert(key.0);
+                    true
+                };
+                Some(InputEvent::new(EventType::KEY.0, key.0, down as i32))
+            }
+            EventSummary::Key(_, key, value) if assists.turbo_buttons.contains(&key.0) => {
+                match value {
+                    1 => {
+                        self.turbo.insert(key.0, (Instant::now(), true));
+                        Some(event)
+                    }
+                    0 => {
+                        self.turbo.remove(&key.0);
+                        Some(event)
+                    }
+                    // The tick drives repeats, so kernel autorepeat is dropped.
+                    _ => None,
+                }
+            }
+            EventSummary::AbsoluteAxis(_, axis, value)
+                if trigger_button(axis).is_some_and(|button| {
+                    assists.turbo_buttons.contains(&button.0)
+                        || assists.toggle_buttons.contains(&button.0)
+                }) =>
+            {
+                let (Some(button), Some(range)) = (trigger_button(axis), declared.get(&axis.0))
+                else {
+                    return Some(event);
+                };
+                // The trigger acts as a button: fully pulled or released.
+                let pressed = value > range.center;
+                if pressed == self.triggers_down.contains(&axis.0) {
+                    return None;
+                }
+                if pressed {
+                    self.triggers_down.insert(axis.0);
+                } else {
+                    self.triggers_down.remove(&axis.0);
+                }
+                let pulled = |down: bool| {
+                    let value = if down { range.max } else { range.min };
+                    InputEvent::new(EventType::ABSOLUTE.0, axis.0, value)
+                };
+                if assists.toggle_buttons.contains(&button.0) {
+                    if !pressed {
+                        return None;
+                    }
+                    let down = if self.latched.remove(&button.0) {
+                        false
+                    } else {
+                        self.latched.insert(button.0);
+                        true
+                    };
+                    return Some(pulled(down));
+                }
+                if pressed {
+                    self.turbo_triggers.insert(axis.0, (Instant::now(), true));
+                } else {
+                    self.turbo_triggers.remove(&axis.0);
+                }
+                Some(pulled(pressed))
+            }
+            EventSummary::AbsoluteAxis(_, axis, value)
+                if assists.stick_sensitivity < 1.0 && STICK_AXES.contains(&axis) =>
+            {
+                let Some(range) = declared.get(&axis.0) else {
+                    return Some(event);
+                };
+                let scaled = range.normalize(value) * assists.stick_sensitivity.max(0.0);
+                Some(InputEvent::new(
+                    EventType::ABSOLUTE.0,
+                    axis.0,
+                    AxisRange::denormalize(range, scaled),
+                ))
+            }
+            _ => Some(event),
+        }
+    }
+
+    /// Flips held turbo buttons and triggers whose half-period elapsed.
+    fn tick(
+        &mut self,
+        assists: &InputAssists,
+        declared: &HashMap<u16, AxisRange>,
+    ) -> Vec<InputEvent> {
+        let half_period = Duration::from_secs_f32(0.5 / assists.turbo_rate_hz.max(1.0));
+        let now = Instant::now();
+        let mut events = Vec::new();
+        for (code, (last_flip, down)) in self.turbo.iter_mut() {
+            if now.duration_since(*last_flip) >= half_period {
+                *down = !*down;
+                *last_flip = now;
+                events.push(InputEvent::new(EventType::KEY.0, *code, *down as i32));
+            }
+        }
+        for (axis, (last_flip, down)) in self.turbo_triggers.iter_mut() {
+            let Some(range) = declared.get(axis) else {
+                continue;
+            };
+            if now.duration_since(*last_flip) >= half_period {
+                *down = !*down;
+                *last_flip = now;
+                let value = if *down { range.max } else { range.min };
+                events.push(InputEvent::new(EventType::ABSOLUTE.0, *axis, value));
+            }
+        }
+        events
+    }
+}
+
+/// Rumble effects a game uploaded to the virtual pad, keyed by the id the
+/// game knows them by and played on the physical pad.
+#[derive(Default)]
+struct RumbleRelay {
+    effects: HashMap<i16, FFEffect>,
+}
+
+impl RumbleRelay {
+    /// Answers the force feedback requests the game sent to `virtual_device`
+    /// by uploading, playing and erasing the effects on `physical`.
+    fn relay(&mut self, virtual_device: &mut VirtualDevice, physical: &mut Device) {
+        // The uinput node is non-blocking, so this fails when nothing is queued.
+        let events: Vec<InputEvent> = match virtual_device.fetch_events() {
+            Ok(events) => events.collect(),
+            Err(_) => return,
+        };
+        for event in events {
+            match event.destructure() {
+                EventSummary::UInput(request, UInputCode::UI_FF_UPLOAD, _) => {
+                    let Ok(mut upload) = virtual_device.process_ff_upload(request) else {
+                        continue;
+                    };
+                    let id = upload.effect_id();
+                    let uploaded = match self.effects.get_mut(&id) {
+                        Some(effect) => effect.update(upload.effect()),
+                        None => physical.upload_ff_effect(upload.effect()).map(|effect| {
+                            self.effects.insert(id, effect);
+                        }),
+                    };
+                    // The game gets the pad's error back instead of a silent no-op.
+                    if let Err(err) = uploaded {
+                        log_warn(format!("Couldn't upload a rumble effect to the pad: {err}"));
+                        upload.set_retval(-err.raw_os_error().unwrap_or(libc::EIO));
+                    }
+                }
+                EventSummary::UInput(request, UInputCode::UI_FF_ERASE, _) => {
+                    if let Ok(erase) = virtual_device.process_ff_erase(request) {
+                        // Dropping the effect erases it from the physical pad.
+                        self.effects.remove(&(erase.effect_id() as i16));
+                    }
+                }
+                EventSummary::ForceFeedback(_, effect, count) => {
+                    if let Some(effect) = self.effects.get_mut(&(effect.0 as i16)) {
+                        let _ = if count > 0 { effect.play(count) } else { effect.stop() };
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+
+    /// Forgets every effect, e.g. once the pad they were uploaded to is gone.
+    fn clear(&mut self) {
+        self.effects.clear();
+    }
+}
+
+/// Makes reads from the uinput node return right away, so the forwarding
+/// thread can check for rumble requests between input events.
+fn set_uinput_nonblocking(device: &VirtualDevice) -> std::io::Result<()> {
+    let fd = device.as_raw_fd();
+    let flags = unsafe { libc::fcntl(fd, libc::F_GETFL) };
+    if flags < 0 || unsafe { libc::fcntl(fd, libc::F_SETFL, flags | libc::O_NONBLOCK) } < 0 {
+        return Err(std::io::Error::last_os_error());
+    }
+    Ok(())
+}
+
+/// Nodes the kernel exposes next to the gamepad at `pad_path` that `wanted`
+/// accepts, matched by ids and uniq/phys.
+fn pad_siblings(pad_path: &str, wanted: impl Fn(&Device) -> bool) -> Vec<String> {
+    let Ok(pad) = Device::open(pad_path) else {
+        return Vec::new();
+    };
+    let pad_id = pad.input_id();
+    // Sibling nodes share the phys prefix and only differ in the trailing /inputN.
+    let phys_prefix = |dev: &Device| {
+        dev.physical_path()
+            .map(|phys| phys.rsplit_once('/').map_or(phys, |(prefix, _)| prefix).to_string())
+    };
+    let pad_uniq = pad.unique_name().filter(|uniq| !uniq.is_empty());
+    let pad_phys = phys_prefix(&pad);
+
+    evdev::enumerate()
+        .filter_map(|(path, dev)| {
+            if !wanted(&dev) {
+                return None;
+            }
+            let id = dev.input_id();
+            if id.vendor() != pad_id.vendor() || id.product() != pad_id.product() {
+                return None;
+            }
+            let same_uniq = pad_uniq.is_some() && dev.unique_name() == pad_uniq;
+            let same_phys = pad_phys.is_some() && phys_prefix(&dev) == pad_phys;
+            (same_uniq || same_phys).then(|| path.to_string_lossy().to_string())
+        })
+        .collect()
+}
+
+pub(super) fn is_motion_sensor(dev: &Device) -> bool {
+    dev.properties().contains(PropType::ACCELEROMETER)
+}
+
+/// Touchpads of DualShock 4 and DualSense pads report touches but, unlike
+/// the pad itself, no face buttons.
+fn is_pad_touchpad(dev: &Device) -> bool {
+    dev.supported_keys().is_some_and(|keys| {
+        keys.contains(KeyCode::BTN_TOUCH) && !keys.contains(KeyCode::BTN_SOUTH)
+    })
+}
+
+/// Finds the motion sensor node the kernel exposes next to a gamepad
+/// (hid-playstation, hid-nintendo, hid-steam), matched by ids and uniq/phys.
+pub fn find_motion_sensor(pad_path: &str) -> Option<String> {
+    pad_siblings(pad_path, is_motion_sensor).into_iter().next()
+}
+
+/// Motion sensor and touchpad nodes of the gamepad at `pad_path`, which
+/// games pair with the pad for gyro aiming and touch input.
+pub fn find_pad_companions(pad_path: &str) -> Vec<String> {
+    pad_siblings(pad_path, |dev| is_motion_sensor(dev) || is_pad_touchpad(dev))
+}
+
+/// Live state the forwarding thread keeps to blend gyro motion into the right stick.
+#[derive(Default)]
+struct GyroState {
+    /// Right stick position from the physical pad, normalized.
+    stick: [f32; 2],
+    /// Latest pitch/yaw rates in degrees per second.
+    rates: [f32; 2],
+    /// Sensor units per degree per second for pitch and yaw.
+    resolution: [f32; 2],
+}
+
+impl GyroState {
+    fn deflection(&self, mapping: &GyroMapping) -> [f32; 2] {
+        let scale = |rate: f32| {
+            if rate.abs() < GYRO_DRIFT_DPS {
+                0.0
+            } else {
+                rate * mapping.sensitivity / GYRO_FULL_DEFLECTION_DPS
+            }
+        };
+        // Yawing left should push the stick left; pitching up should look up.
+        let x = -scale(self.rates[1]);
+        let y = -scale(self.rates[0]);
+        [
+            if mapping.invert_x { -x } else { x },
+            if mapping.invert_y { -y } else { y },
+        ]
+    }
+
+    /// Right stick events combining the physical stick with the gyro deflection.
+    fn right_stick_events(
+        &self,
+        mapping: &GyroMapping,
+        declared: &HashMap<u16, AxisRange>,
+    ) -> Vec<InputEvent> {
+        let deflection = self.deflection(mapping);
+        [AbsoluteAxisCode::ABS_RX, AbsoluteAxisCode::ABS_RY]
+            .iter()
+            .enumerate()
+            .filter_map(|(i, axis)| {
+                let range = declared.get(&axis.0)?;
+                let value = AxisRange::denormalize(range, self.stick[i] + deflection[i]);
+                Some(InputEvent::new(EventType::ABSOLUTE.0, axis.0, value))
+            })
+            .collect()
+    }
+}
+
+/// A grabbed physical gamepad re-exposed through uinput with adjustments
+/// applied. The physical device is released once the proxy is dropped.
+pub struct PadProxy {
+    pub physical_path: String,
+    /// Co-pilot pads merged into the virtual device.
+    pub copilot_paths: Vec<String>,
+    pub virtual_path: String,
+    /// Replacement nodes for pads that reconnected, picked up by the thread.
+    rebinds: Arc<Mutex<Vec<(String, String)>>>,
+    stop: Arc<AtomicBool>,
+    handle: Option<JoinHandle<()>>,
+}
+
+impl PadProxy {
+    /// Feeds the virtual pad from `new_path` from now on, in place of the pad
+    /// at `old_path` that disconnected. The game keeps its virtual device, so
+    /// it never notices the reconnect.
+    pub fn rebind(&mut self, old_path: &str, new_path: &str) {
+        if let Ok(mut rebinds) = self.rebinds.lock() {
+            rebinds.push((old_path.to_string(), new_path.to_string()));
+        }
+        for path in std::iter::once(&mut self.physical_path).chain(&mut self.copilot_paths) {
+            if *path == old_path {
+                *path = new_path.to_string();
+            }
+        }
+    }
+
+    /// Whether the proxy reads from the pad at `path`.
+    pub fn reads(&self, path: &str) -> bool {
+        self.physical_path == path || self.copilot_paths.iter().any(|copilot| *copilot == path)
+    }
+}
+
+/// Events that let go of every key and recenter every stick, so a pad that
+/// disconnects mid-press doesn't leave the game holding its input.
+fn release_all(keys: &[KeyCode], declared: &HashMap<u16, AxisRange>) -> Vec<InputEvent> {
+    let released = keys
+        .iter()
+        .map(|key| InputEvent::new(EventType::KEY.0, key.0, 0));
+    let centered = STICK_AXES.iter().filter_map(|axis| {
+        let range = declared.get(&axis.0)?;
+        Some(InputEvent::new(
+            EventType::ABSOLUTE.0,
+            axis.0,
+            AxisRange::denormalize(range, 0.0),
+        ))
+    });
+    released.chain(centered).collect()
+}
+
+impl Drop for PadProxy {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::Relaxed);
+        if let Some(handle) = self.handle.take() {
+            let _ = handle.join();
+        }
+    }
+}
+
+/// Rewrites a stick axis value through the calibration and dead zone and into
+/// the virtual device's range, leaving every other event untouched.
+fn transform_event(
+    event: InputEvent,
+    calibration: Option<&DeviceCalibration>,
+    source: &HashMap<u16, AxisRange>,
+    declared: &HashMap<u16, AxisRange>,
+) -> InputEvent {
+    if event.event_type() != EventType::ABSOLUTE {
+        return event;
+    }
+    if !STICK_AXES.iter().any(|axis| axis.0 == event.code()) {
+        return event;
+    }
+    let Some(output_range) = declared.get(&event.code()) else {
+        return event;
+    };
+
+    let input_range = calibration
+        .and_then(|calibration| calibration.axes.get(&event.code()))
+        .or_else(|| source.get(&event.code()))
+        .unwrap_or(output_range);
+    let mut normalized = input_range.normalize(event.value());
+    if let Some(calibration) = calibration {
+        normalized = calibration.apply_deadzone(normalized);
+    }
+    InputEvent::new(
+        EventType::ABSOLUTE.0,
+        event.code(),
+        AxisRange::denormalize(output_range, normalized),
+    )
+}
+
+/// Sends a key under its remapped code and flips inverted stick axes. Runs
+/// last, after the assists, which are set up on the physical buttons, and
+/// after [`transform_event`], so values are already in the virtual range.
+fn remap_event(
+    event: InputEvent,
+    remap: Option<&PadRemap>,
+    declared: &HashMap<u16, AxisRange>,
+) -> InputEvent {
+    let Some(remap) = remap else {
+        return event;
+    };
+    match event.destructure() {
+        EventSummary::Key(_, key, value) => {
+            InputEvent::new(EventType::KEY.0, remap.button(key.0), value)
+        }
+        EventSummary::AbsoluteAxis(_, axis, value) if remap.inverts_axis(axis) => {
+            let Some(range) = declared.get(&axis.0) else {
+                return event;
+            };
+            InputEvent::new(
+                EventType::ABSOLUTE.0,
+                axis.0,
+                AxisRange::denormalize(range, -range.normalize(value)),
+            )
+        }
+        _ => event,
+    }
+}
+
+/// Grabs the gamepad at `path` (plus any co-pilot pads) and forwards its
+/// events through a virtual uinput clone with the requested adjustments. The
+/// returned proxy exposes the virtual event node that should be handed to the
+/// game instead.
+pub fn spawn_pad_proxy(path: &str, options: PadProxyOptions) -> Result<PadProxy, Box<dyn Error>> {
+    // A remap dead zone replaces the calibrated one of every merged pad.
+    let deadzone = options.remap.as_ref().and_then(|remap| remap.deadzone);
+    let with_deadzone = |calibration: &Option<DeviceCalibration>| match deadzone {
+        Some(deadzone) => Some(DeviceCalibration {
+            deadzone,
+            ..calibration.clone().unwrap_or_default()
+        }),
+        None => calibration.clone(),
+    };
+    let mut sources = vec![ProxySource::open(path, with_deadzone(&options.calibration))?];
+    for (copilot_path, calibration) in &options.copilots {
+        sources.push(ProxySource::open(copilot_path, with_deadzone(calibration))?);
+    }
+    let physical = &sources[0].device;
+
+    // Mirror the physical capabilities so games see an identical controller.
+    // Co-pilot pads may expose extra buttons, so keys are the union of all pads.
+    let mut builder = VirtualDevice::builder()?
+        .name(physical.name().unwrap_or("Split Happens Pad"))
+        .input_id(physical.input_id());
+    let mut key_set = AttributeSet::<KeyCode>::new();
+    for source in &sources {
+        if let Some(keys) = source.device.supported_keys() {
+            for key in keys.iter() {
+                key_set.insert(key);
+            }
+        }
+    }
+    // Remapped buttons may send keys the pad itself doesn't have.
+    if let Some(remap) = &options.remap {
+        for (_, to) in &remap.buttons {
+            key_set.insert(KeyCode(*to));
+        }
+    }
+    if key_set.iter().next().is_some() {
+        builder = builder.with_keys(&key_set)?;
+    }
+    let keys: Vec<KeyCode> = key_set.iter().collect();
+
+    let mut declared: HashMap<u16, AxisRange> = HashMap::new();
+    for (axis, info) in physical.get_absinfo()? {
+        declared.insert(axis.0, AxisRange::from_bounds(info.minimum(), info.maximum()));
+        let setup = UinputAbsSetup::new(
+            axis,
+            AbsInfo::new(
+                info.value(),
+                info.minimum(),
+                info.maximum(),
+                info.fuzz(),
+                info.flat(),
+                info.resolution(),
+            ),
+        );
+        builder = builder.with_absolute_axis(&setup)?;
+    }
+    // Rumble is declared like the primary pad's and relayed back to it.
+    let rumble = physical
+        .supported_ff()
+        .filter(|effects| effects.iter().next().is_some() && physical.max_ff_effects() > 0);
+    if let Some(effects) = rumble {
+        builder = builder
+            .with_ff(effects)?
+            .with_ff_effects_max(physical.max_ff_effects() as u32);
+    }
+    let has_rumble = rumble.is_some();
+
+    let mut virtual_device = builder.build()?;
+    if has_rumble {
+        set_uinput_nonblocking(&virtual_device)?;
+    }
+    let virtual_path = virtual_device
+        .enumerate_dev_nodes_blocking()?
+        .flatten()
+        .next()
+        .ok_or("uinput did not report a device node for the virtual pad")?
+        .to_string_lossy()
+        .to_string();
+
+    // The motion sensor is only read, never grabbed, so games with native gyro
+    // support keep working alongside the mapping.
+    let mut gyro_state = GyroState::default();
+    let mut motion = None;
+    if let Some((mapping, motion_path)) = &options.gyro {
+        match Device::open(motion_path) {
+            Ok(mut sensor) => {
+                sensor.set_nonblocking(true)?;
+                gyro_state.resolution = [1.0; 2];
+                for (axis, info) in sensor.get_absinfo()? {
+                    let slot = match axis {
+                        AbsoluteAxisCode::ABS_RX => 0,
+                        AbsoluteAxisCode::ABS_RY => 1,
+                        _ => continue,
+                    };
+                    gyro_state.resolution[slot] = info.resolution().max(1) as f32;
+                }
+                motion = Some((mapping.clone(), sensor));
+            }
+            Err(err) => log_warn(format!(
+                "Couldn't open motion sensor {motion_path}; gyro disabled: {err}"
+            )),
+        }
+    }
+
+    // Grab last so a failed setup never leaves the player without input.
+    for source in sources.iter_mut() {
+        source.device.grab()?;
+        source.device.set_nonblocking(true)?;
+    }
+
+    let mut assist_state = AssistState::default();
+    let mut merge_state = MergeState::default();
+    let mut rumble_relay = RumbleRelay::default();
+    let merging = sources.len() > 1;
+    let copilot_paths: Vec<String> = sources[1..]
+        .iter()
+        .map(|source| source.path.clone())
+        .collect();
+
+    let rebinds: Arc<Mutex<Vec<(String, String)>>> = Arc::default();
+    let thread_rebinds = Arc::clone(&rebinds);
+    let stop = Arc::new(AtomicBool::new(false));
+    let thread_stop = stop.clone();
+    let thread_path = path.to_string();
+    let handle = std::thread::spawn(move || {
+        while !thread_stop.load(Ordering::Relaxed) {
+            let mut idle = true;
+            let mut batch: Vec<InputEvent> = Vec::new();
+
+            // Swap reconnected pads in for the nodes that went away.
+            let pending = thread_rebinds
+                .lock()
+                .map(|mut pending| std::mem::take(&mut *pending))
+                .unwrap_or_default();
+            for (old_path, new_path) in pending {
+                let Some(source) = sources
+                    .iter_mut()
+                    .find(|source| source.path == old_path && !source.connected)
+                else {
+                    continue;
+                };
+                let reopened = ProxySource::open(&new_path, source.calibration.clone())
+                    .and_then(|mut replacement| {
+                        replacement.device.grab()?;
+                        replacement.device.set_nonblocking(true)?;
+                        Ok(replacement)
+                    });
+                match reopened {
+                    Ok(replacement) => {
+                        log_info(format!("Pad proxy for {thread_path} now reads {new_path}"));
+                        *source = replacement;
+                        // Effects lived on the old node; games upload them again.
+                        rumble_relay.clear();
+                    }
+                    Err(err) => log_warn(format!("Couldn't reopen pad {new_path}: {err}")),
+                }
+            }
+
+            for (index, source) in sources.iter_mut().enumerate() {
+                if !source.connected {
+                    continue;
+                }
+                match source.device.fetch_events() {
+                    Ok(events) => {
+                        idle = false;
+                        for event in events {
+                            if event.event_type() == EventType::SYNCHRONIZATION {
+                                continue;
+                            }
+                            if let Some(activity) = &options.activity {
+                                if is_player_activity(&event, &source.ranges) {
+                                    activity.touch();
+                                }
+                            }
+                            let event = transform_event(
+                                event,
+                                source.calibration.as_ref(),
+                                &source.ranges,
+                                &declared,
+                            );
+                            let event = if merging {
+                                merge_state.apply(index, event, &declared)
+                            } else {
+                                Some(event)
+                            };
+                            let event = event.and_then(|event| match &options.assists {
+                                Some(assists) => assist_state.apply(event, assists, &declared),
+                                None => Some(event),
+                            });
+                            let remap = options.remap.as_ref();
+                            batch.extend(event.map(|event| remap_event(event, remap, &declared)));
+                        }
+                    }
+                    Err(err) if err.kind() == std::io::ErrorKind::WouldBlock => {}
+                    Err(err) => {
+                        // The virtual pad stays, so a reconnected pad can take over.
+                        // Losing a co-pilot leaves the main player in control.
+                        log_warn(format!("Pad {} disconnected: {err}", source.path));
+                        source.connected = false;
+                        if index == 0 {
+                            rumble_relay.clear();
+                        }
+                        if merging {
+                            let remap = options.remap.as_ref();
+                            let releases = merge_state.forget(index, &declared);
+                            let releases = releases
+                                .into_iter()
+                                .map(|event| remap_event(event, remap, &declared));
+                            batch.extend(releases);
+                        } else {
+                            batch.extend(release_all(&keys, &declared));
+                        }
+                    }
+                }
+            }
+            if let Some(assists) = &options.assists {
+                let remap = options.remap.as_ref();
+                let repeats = assist_state.tick(assists, &declared);
+                batch.extend(repeats.into_iter().map(|event| remap_event(event, remap, &declared)));
+            }
+            // Rumble goes to the primary pad; co-pilots only send input.
+            if has_rumble && sources[0].connected {
+                rumble_relay.relay(&mut virtual_device, &mut sources[0].device);
+            }
+
+            if let Some((mapping, sensor)) = motion.as_mut() {
+                // Remember the physical right stick, then re-emit it blended with gyro.
+                let mut stick_moved = false;
+                batch.retain(|event| {
+                    let (slot, axis, value) = match event.destructure() {
+                        EventSummary::AbsoluteAxis(_, axis @ AbsoluteAxisCode::ABS_RX, value) => {
+                            (0, axis, value)
+                        }
+                        EventSummary::AbsoluteAxis(_, axis @ AbsoluteAxisCode::ABS_RY, value) => {
+                            (1, axis, value)
+                        }
+                        _ => return true,
+                    };
+                    if let Some(range) = declared.get(&axis.0) {
+                        gyro_state.stick[slot] = range.normalize(value);
+                    }
+                    stick_moved = true;
+                    false
+                });
+
+                let mut gyro_moved = false;
+                if let Ok(events) = sensor.fetch_events() {
+                    idle = false;
+                    for event in events {
+                        let (slot, value) = match event.destructure() {
+                            EventSummary::AbsoluteAxis(_, AbsoluteAxisCode::ABS_RX, value) => {
+                                (0, value)
+                            }
+                            EventSummary::AbsoluteAxis(_, AbsoluteAxisCode::ABS_RY, value) => {
+                                (1, value)
+                            }
+                            _ => continue,
+                        };
+                        gyro_state.rates[slot] = value as f32 / gyro_state.resolution[slot];
+                        gyro_moved = true;
+                    }
+                }
+
+                if stick_moved || gyro_moved {
+                    batch.extend(gyro_state.right_stick_events(mapping, &declared));
+                }
+            }
+
+            if batch.is_empty() {
+                if idle {
+                    std::thread::sleep(PROXY_IDLE_SLEEP);
+                }
+                continue;
+            }
+            // emit() appends the SYN_REPORT that closes the batch.
+            if let Err(err) = virtual_device.emit(&batch) {
+                log_warn(format!("Pad proxy for {thread_path} failed to emit: {err}"));
+            }
+        }
+        for source in sources.iter_mut() {
+            let _ = source.device.ungrab();
+        }
+    });
+
+    log_info(format!("Proxying {path} through {virtual_path}"));
+    Ok(PadProxy {
+        physical_path: path.to_string(),
+        copilot_paths,
+        virtual_path,
+        rebinds,
+        stop,
+        handle: Some(handle),
+    })
+}
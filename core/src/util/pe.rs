@@ -0,0 +1,83 @@
+use std::error::Error;
+use std::fs::File;
+use std::io::{Read, Seek, SeekFrom};
+use std::path::Path;
+
+/// CPU a Windows executable or DLL is built for, from the machine field of
+/// its PE header.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum PeArch {
+    X86,
+    X64,
+    /// Any other machine type, e.g. ARM64.
+    Other(u16),
+}
+
+impl PeArch {
+    /// Whether the 32-bit builds of Goldberg and the EOS emulator fit it;
+    /// `None` when neither build does.
+    pub fn is32bit(self) -> Option<bool> {
+        match self {
+            PeArch::X86 => Some(true),
+            PeArch::X64 => Some(false),
+            PeArch::Other(_) => None,
+        }
+    }
+
+    pub fn label(self) -> String {
+        match self {
+            PeArch::X86 => "32-bit".to_string(),
+            PeArch::X64 => "64-bit".to_string(),
+            PeArch::Other(machine) => format!("machine type {machine:#06x}"),
+        }
+    }
+}
+
+const PE_MACHINE_I386: u16 = 0x014c;
+const PE_MACHINE_AMD64: u16 = 0x8664;
+
+/// Reads the architecture of the PE file at `path`. Fails for files that
+/// aren't Windows programs, such as Linux binaries or shell scripts.
+pub fn pe_arch(path: &Path) -> Result<PeArch, Box<dyn Error>> {
+    let mut file = File::open(path)?;
+    let mut dos_header = [0u8; 64];
+    file.read_exact(&mut dos_header)
+        .map_err(|_| format!("{} is too small to be a Windows program", path.display()))?;
+    if &dos_header[..2] != b"MZ" {
+        return Err(format!("{} is not a Windows program", path.display()).into());
+    }
+    // The DOS header points at the PE signature, which the machine follows.
+    let pe_offset = u32::from_le_bytes(dos_header[0x3c..0x40].try_into()?);
+    let mut pe_header = [0u8; 6];
+    file.seek(SeekFrom::Start(pe_offset as u64))?;
+    file.read_exact(&mut pe_header)?;
+    if &pe_header[..4] != b"PE\0\0" {
+        return Err(format!("{} has no PE header", path.display()).into());
+    }
+    Ok(match u16::from_le_bytes([pe_header[4], pe_header[5]]) {
+        PE_MACHINE_I386 => PeArch::X86,
+        PE_MACHINE_AMD64 => PeArch::X64,
+        other => PeArch::Other(other),
+    })
+}
+
+const ELF_CLASS_32: u8 = 1;
+const ELF_CLASS_64: u8 = 2;
+
+/// Whether the ELF file at `path` is a 32-bit program, from the class byte of
+/// its header. Fails for files that aren't Linux programs, such as shell
+/// scripts.
+pub fn elf_is32bit(path: &Path) -> Result<bool, Box<dyn Error>> {
+    let mut ident = [0u8; 5];
+    File::open(path)?
+        .read_exact(&mut ident)
+        .map_err(|_| format!("{} is too small to be a Linux program", path.display()))?;
+    if &ident[..4] != b"\x7fELF" {
+        return Err(format!("{} is not a Linux program", path.display()).into());
+    }
+    match ident[4] {
+        ELF_CLASS_32 => Ok(true),
+        ELF_CLASS_64 => Ok(false),
+        class => Err(format!("{} has an unknown ELF class {class}", path.display()).into()),
+    }
+}
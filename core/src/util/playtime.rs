@@ -0,0 +1,182 @@
+use std::collections::HashMap;
+use std::error::Error;
+use std::fs::{self, File};
+use std::io::{self, BufReader};
+use std::path::PathBuf;
+use std::sync::Mutex;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
+
+use crate::logging::log_warn;
+use crate::paths::*;
+use super::filesystem::write_atomic;
+
+/// Serializes read-modify-write cycles of `stats.json` between the launch
+/// threads of concurrent sessions.
+static STATS_LOCK: Mutex<()> = Mutex::new(());
+
+/// Time one profile spent in one game.
+#[derive(Serialize, Deserialize, Clone, Copy, Default, Debug)]
+pub struct Playtime {
+    pub seconds: u64,
+    /// Seconds since the Unix epoch when an instance last exited.
+    pub last_played: u64,
+}
+
+impl Playtime {
+    fn add(&mut self, other: Playtime) {
+        self.seconds += other.seconds;
+        self.last_played = self.last_played.max(other.last_played);
+    }
+}
+
+/// Playtime recorded by the launcher, keyed by game id (handler uid or
+/// executable name), then profile name.
+#[derive(Serialize, Deserialize, Default)]
+pub struct PlayStats {
+    #[serde(default)]
+    pub games: HashMap<String, HashMap<String, Playtime>>,
+}
+
+fn stats_path() -> PathBuf {
+    PATH_APP.join("stats.json")
+}
+
+impl PlayStats {
+    /// Recorded playtime, or none if nothing was played yet or the file
+    /// can't be read.
+    pub fn load() -> PlayStats {
+        PlayStats::try_load().unwrap_or_default()
+    }
+
+    /// Recorded playtime; fails when `stats.json` exists but can't be read.
+    fn try_load() -> Result<PlayStats, Box<dyn Error>> {
+        let file = match File::open(stats_path()) {
+            Ok(file) => file,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => {
+                return Ok(PlayStats::default());
+            }
+            Err(err) => return Err(err.into()),
+        };
+        Ok(serde_json::from_reader(BufReader::new(file))?)
+    }
+
+    fn save(&self) -> Result<(), Box<dyn Error>> {
+        fs::create_dir_all(&*PATH_APP)?;
+        write_atomic(&stats_path(), serde_json::to_string_pretty(self)?)?;
+        Ok(())
+    }
+
+    /// Time all profiles spent in `game_id` together.
+    pub fn game_total(&self, game_id: &str) -> Option<Playtime> {
+        let profiles = self.games.get(game_id)?;
+        let mut total = Playtime::default();
+        profiles.values().for_each(|playtime| total.add(*playtime));
+        Some(total)
+    }
+
+    /// Time `profile` spent across every game, and the game it played last.
+    pub fn profile_total(&self, profile: &str) -> Option<(Playtime, &str)> {
+        let mut total = Playtime::default();
+        let mut last_game = None;
+        for (game_id, profiles) in &self.games {
+            let Some(playtime) = profiles.get(profile) else {
+                continue;
+            };
+            if playtime.last_played >= total.last_played {
+                last_game = Some(game_id.as_str());
+            }
+            total.add(*playtime);
+        }
+        Some((total, last_game?))
+    }
+}
+
+/// Runs `change` on the stored stats and writes them back.
+fn update_stats(change: impl FnOnce(&mut PlayStats)) {
+    let _guard = STATS_LOCK.lock().unwrap();
+    let mut stats = match PlayStats::try_load() {
+        Ok(stats) => stats,
+        Err(err) => {
+            // Keep the unreadable file for recovery instead of overwriting it.
+            let now = SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map_or(0, |elapsed| elapsed.as_secs());
+            let backup = PATH_APP.join(format!("stats.json.broken-{now}"));
+            if let Err(rename_err) = fs::rename(stats_path(), &backup) {
+                log_warn(format!(
+                    "Couldn't read playtime statistics ({err}) or move them aside ({rename_err}), not saving"
+                ));
+                return;
+            }
+            log_warn(format!(
+                "Couldn't read playtime statistics ({err}), moved them to {} and started over",
+                backup.display()
+            ));
+            PlayStats::default()
+        }
+    };
+    change(&mut stats);
+    if let Err(err) = stats.save() {
+        log_warn(format!("Couldn't save playtime statistics: {err}"));
+    }
+}
+
+/// Adds a run of `profile` in `game_id` that just ended after `played`.
+pub fn record_playtime(game_id: &str, profile: &str, played: Duration) {
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |elapsed| elapsed.as_secs());
+    update_stats(|stats| {
+        let playtime = stats
+            .games
+            .entry(game_id.to_string())
+            .or_default()
+            .entry(profile.to_string())
+            .or_default();
+        playtime.add(Playtime {
+            seconds: played.as_secs(),
+            last_played: now,
+        });
+    });
+}
+
+/// Moves the playtime of profile `old` to `new` after a rename.
+pub fn rename_playtime_profile(old: &str, new: &str) {
+    update_stats(|stats| {
+        for profiles in stats.games.values_mut() {
+            if let Some(playtime) = profiles.remove(old) {
+                profiles.entry(new.to_string()).or_default().add(playtime);
+            }
+        }
+    });
+}
+
+/// Moves the playtime of game `old` to `new` after a handler uid change.
+/// Returns whether there was any.
+pub fn migrate_playtime_game(old: &str, new: &str) -> bool {
+    let mut moved = false;
+    update_stats(|stats| {
+        let Some(profiles) = stats.games.remove(old) else {
+            return;
+        };
+        let target = stats.games.entry(new.to_string()).or_default();
+        for (profile, playtime) in profiles {
+            target.entry(profile).or_default().add(playtime);
+        }
+        moved = true;
+    });
+    moved
+}
+
+/// `2 h 15 min`, `40 min` or `< 1 min`.
+pub fn format_playtime(seconds: u64) -> String {
+    let minutes = seconds / 60;
+    match (minutes / 60, minutes % 60) {
+        (0, 0) => "< 1 min".to_string(),
+        (0, min) => format!("{min} min"),
+        (hours, 0) => format!("{hours} h"),
+        (hours, min) => format!("{hours} h {min} min"),
+    }
+}
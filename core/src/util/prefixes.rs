@@ -0,0 +1,144 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+
+use super::guest_bundle::is_guest_profile;
+use super::profiles::{Profile, scan_profiles};
+use crate::logging::{log_info, log_warn};
+use crate::paths::PATH_APP;
+
+/// A folder under `pfx/` and the profile it belongs to.
+pub struct ProtonPrefix {
+    pub path: PathBuf,
+    /// Profile using the prefix; `None` once the profile was deleted.
+    pub owner: Option<String>,
+    /// Player slot of prefixes made with a separate prefix per instance.
+    pub slot: Option<u32>,
+    /// Still named after the profile rather than its id, until its next launch.
+    pub legacy: bool,
+    pub bytes: u64,
+}
+
+/// `""` or `"_N"` when `folder` is `base` or `base_N`.
+fn prefix_suffix<'a>(folder: &'a str, base: &str) -> Option<&'a str> {
+    let rest = folder.strip_prefix(base)?;
+    if rest.is_empty() {
+        return Some(rest);
+    }
+    let number = rest.strip_prefix('_')?;
+    (!number.is_empty() && number.chars().all(|c| c.is_ascii_digit())).then_some(rest)
+}
+
+/// Folder name under `pfx/` for `profname`'s prefixes: the profile's stable
+/// id, so renaming the profile keeps its prefixes. Guests and profiles
+/// without a folder keep their name. Prefixes still named after the profile
+/// are moved to the id first.
+pub fn profile_prefix_key(profname: &str) -> String {
+    if is_guest_profile(profname) || !PATH_APP.join("profiles").join(profname).is_dir() {
+        return profname.to_string();
+    }
+    let mut profile = Profile::load(profname);
+    match profile.stable_id() {
+        Ok(id) => {
+            migrate_profile_prefixes(profname, &id);
+            id
+        }
+        Err(err) => {
+            log_warn(format!("Couldn't store an id for profile {profname}: {err}"));
+            profname.to_string()
+        }
+    }
+}
+
+/// Renames `pfx/<profname>` and `pfx/<profname>_N` to the profile's id.
+/// Folders that are another profile's name, like `Bob_2`, are left alone.
+fn migrate_profile_prefixes(profname: &str, id: &str) {
+    let root = PATH_APP.join("pfx");
+    let Ok(entries) = fs::read_dir(&root) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        let folder = entry.file_name().to_string_lossy().to_string();
+        let Some(suffix) = prefix_suffix(&folder, profname) else {
+            continue;
+        };
+        if !suffix.is_empty() && PATH_APP.join("profiles").join(&folder).is_dir() {
+            continue;
+        }
+        let target = root.join(format!("{id}{suffix}"));
+        if target.exists() {
+            log_warn(format!(
+                "Proton prefix {folder} of profile {profname} not moved: {} already exists",
+                target.display()
+            ));
+            continue;
+        }
+        match fs::rename(entry.path(), &target) {
+            Ok(()) => log_info(format!(
+                "Moved Proton prefix {folder} of profile {profname} to {id}{suffix}"
+            )),
+            Err(err) => log_warn(format!("Couldn't move Proton prefix {folder}: {err}")),
+        }
+    }
+}
+
+/// Total size of the files under `path`, without following symlinks.
+fn folder_size(path: &PathBuf) -> u64 {
+    walkdir::WalkDir::new(path)
+        .into_iter()
+        .flatten()
+        .filter(|entry| entry.file_type().is_file())
+        .filter_map(|entry| entry.metadata().ok())
+        .map(|meta| meta.len())
+        .sum()
+}
+
+/// Every Proton prefix with its owner and size, largest first. Walks every
+/// prefix, so it takes a moment with many of them.
+pub fn list_proton_prefixes() -> Vec<ProtonPrefix> {
+    // Folder key to profile, with whether the key is the profile's name.
+    let mut keys: HashMap<String, (String, bool)> = HashMap::new();
+    for name in scan_profiles(false) {
+        let profile = Profile::load(&name);
+        if !profile.id.is_empty() {
+            keys.insert(profile.id.clone(), (name.clone(), false));
+        }
+        keys.entry(name.clone()).or_insert((name, true));
+    }
+
+    let Ok(entries) = fs::read_dir(PATH_APP.join("pfx")) else {
+        return Vec::new();
+    };
+    let mut prefixes: Vec<ProtonPrefix> = entries
+        .flatten()
+        .filter(|entry| entry.file_type().is_ok_and(|kind| kind.is_dir()))
+        .map(|entry| {
+            let folder = entry.file_name().to_string_lossy().to_string();
+            let (base, slot) = match folder.rsplit_once('_') {
+                Some((base, number)) if !keys.contains_key(&folder) => {
+                    match number.parse::<u32>() {
+                        Ok(slot) => (base.to_string(), Some(slot)),
+                        Err(_) => (folder.clone(), None),
+                    }
+                }
+                _ => (folder.clone(), None),
+            };
+            let (owner, legacy) = match keys.get(&base) {
+                Some((name, legacy)) => (Some(name.clone()), *legacy),
+                // Guest prefixes outlive the guest's folder and are reused.
+                None if is_guest_profile(&base) => (Some(base.clone()), false),
+                None => (None, false),
+            };
+            let path = entry.path();
+            ProtonPrefix {
+                bytes: folder_size(&path),
+                path,
+                owner,
+                slot,
+                legacy,
+            }
+        })
+        .collect();
+    prefixes.sort_by(|a, b| b.bytes.cmp(&a.bytes));
+    prefixes
+}
@@ -0,0 +1,94 @@
+use std::error::Error;
+use std::fs::{self, File};
+use std::io::BufReader;
+use std::path::PathBuf;
+
+use serde::{Deserialize, Serialize};
+
+use super::filesystem::{SanitizePath, write_atomic};
+use crate::config::default_true;
+use crate::instance::InstancePlatform;
+use crate::paths::*;
+
+/// One player of a [`SessionPreset`].
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+#[serde(default)]
+pub struct PresetSlot {
+    /// Profile name, or `Guest` for a guest.
+    pub profile: String,
+    /// Identities of the player's devices, matched against connected ones
+    /// the same way remembered device assignments are.
+    pub devices: Vec<String>,
+    /// Display the player is pinned to, by connector name.
+    pub monitor: Option<String>,
+    pub width: u32,
+    pub height: u32,
+    /// Build the player runs, for games with both a Linux and a Windows one.
+    pub platform: InstancePlatform,
+    /// Whether the player's instance sees their pad's motion sensor and touchpad.
+    #[serde(default = "default_true")]
+    pub motion_passthrough: bool,
+}
+
+/// A saved launch configuration: the game plus who plays with what, where
+/// and at which resolution.
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+#[serde(default)]
+pub struct SessionPreset {
+    pub name: String,
+    /// `Game::persistent_id` of the game to launch.
+    pub game: String,
+    /// Game name at the time the preset was saved, for display.
+    pub game_name: String,
+    pub slots: Vec<PresetSlot>,
+}
+
+fn presets_dir() -> PathBuf {
+    PATH_APP.join("presets")
+}
+
+fn preset_path(name: &str) -> PathBuf {
+    presets_dir().join(format!("{}.json", name.to_string().sanitize_path()))
+}
+
+/// Every saved preset, sorted by name. Unreadable files are skipped.
+pub fn list_presets() -> Vec<SessionPreset> {
+    let mut presets: Vec<SessionPreset> = fs::read_dir(presets_dir())
+        .into_iter()
+        .flatten()
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "json"))
+        .filter_map(|path| File::open(path).ok())
+        .filter_map(|file| serde_json::from_reader(BufReader::new(file)).ok())
+        .collect();
+    presets.sort_by_key(|preset: &SessionPreset| preset.name.to_lowercase());
+    presets
+}
+
+/// Writes `preset` to `presets/<name>.json`, replacing a preset with the
+/// same name. Fails when another preset's name gives the same file name once
+/// made safe, like `Mario/Luigi` and `MarioLuigi`.
+pub fn save_preset(preset: &SessionPreset) -> Result<(), Box<dyn Error>> {
+    if preset.name.to_string().sanitize_path().is_empty() {
+        return Err(format!("{} can't be used as a preset name.", preset.name).into());
+    }
+    let path = preset_path(&preset.name);
+    let taken_by = File::open(&path)
+        .ok()
+        .and_then(|file| serde_json::from_reader::<_, SessionPreset>(BufReader::new(file)).ok())
+        .map(|existing| existing.name)
+        .filter(|name| *name != preset.name);
+    if let Some(name) = taken_by {
+        return Err(format!("The preset {name} is saved under the same file name.").into());
+    }
+    fs::create_dir_all(presets_dir())?;
+    // A crash mid-write would otherwise leave the preset unreadable.
+    write_atomic(&path, serde_json::to_string_pretty(preset)?)?;
+    Ok(())
+}
+
+pub fn remove_preset(name: &str) -> Result<(), Box<dyn Error>> {
+    fs::remove_file(preset_path(name))?;
+    Ok(())
+}
@@ -0,0 +1,108 @@
+use nix::libc;
+use nix::sched::{CpuSet, sched_getaffinity, sched_setaffinity};
+use nix::unistd::Pid;
+use std::fs;
+
+/// Fields of `/proc/<pid>/stat` after the command name, so index 0 is the
+/// process state, 1 the parent PID and 16 the nice value.
+fn stat_fields(pid: u32) -> Option<Vec<String>> {
+    let stat = fs::read_to_string(format!("/proc/{pid}/stat")).ok()?;
+    let (_, rest) = stat.rsplit_once(')')?;
+    Some(rest.split_whitespace().map(str::to_string).collect())
+}
+
+/// `root` followed by every process descending from it.
+pub fn process_tree(root: u32) -> Vec<u32> {
+    let parents: Vec<(u32, u32)> = fs::read_dir("/proc")
+        .into_iter()
+        .flatten()
+        .flatten()
+        .filter_map(|entry| entry.file_name().to_str()?.parse::<u32>().ok())
+        .filter_map(|pid| Some((pid, stat_fields(pid)?.get(1)?.parse().ok()?)))
+        .collect();
+
+    let mut tree = vec![root];
+    let mut next = 0;
+    while next < tree.len() {
+        let parent = tree[next];
+        for (pid, ppid) in &parents {
+            if *ppid == parent && !tree.contains(pid) {
+                tree.push(*pid);
+            }
+        }
+        next += 1;
+    }
+    tree
+}
+
+/// Thread ids of `root` and every process descending from it. Scheduling
+/// settings apply per thread on Linux, so live changes have to touch each one.
+pub fn process_tree_tasks(root: u32) -> Vec<u32> {
+    process_tree(root)
+        .iter()
+        .flat_map(|pid| {
+            fs::read_dir(format!("/proc/{pid}/task"))
+                .into_iter()
+                .flatten()
+                .flatten()
+                .filter_map(|task| task.file_name().to_str()?.parse().ok())
+        })
+        .collect()
+}
+
+/// Pins every thread of the process tree under `root` to `cores` and returns
+/// how many threads accepted the new mask.
+pub fn set_tree_affinity(root: u32, cores: &[usize]) -> Result<usize, String> {
+    if cores.is_empty() {
+        return Err("no CPU cores selected".to_string());
+    }
+    let mut cpuset = CpuSet::new();
+    for core in cores {
+        cpuset.set(*core).map_err(|err| format!("core {core}: {err}"))?;
+    }
+
+    let updated = process_tree_tasks(root)
+        .into_iter()
+        .filter(|tid| sched_setaffinity(Pid::from_raw(*tid as i32), &cpuset).is_ok())
+        .count();
+    if updated == 0 {
+        return Err(format!("no thread of PID {root} accepted the affinity"));
+    }
+    Ok(updated)
+}
+
+/// Applies `nice` to every thread of the process tree under `root` and
+/// returns how many threads were updated. Negative values need
+/// CAP_SYS_NICE or a matching RLIMIT_NICE.
+pub fn set_tree_niceness(root: u32, nice: i32) -> Result<usize, String> {
+    let mut updated = 0;
+    let mut last_error = None;
+    for tid in process_tree_tasks(root) {
+        let result = unsafe { libc::setpriority(libc::PRIO_PROCESS, tid as libc::id_t, nice) };
+        if result == 0 {
+            updated += 1;
+        } else {
+            last_error = Some(std::io::Error::last_os_error());
+        }
+    }
+    match (updated, last_error) {
+        (0, Some(err)) => Err(err.to_string()),
+        (0, None) => Err(format!("PID {root} is no longer running")),
+        (updated, _) => Ok(updated),
+    }
+}
+
+/// CPU cores the main thread of `pid` may run on.
+pub fn process_affinity(pid: u32) -> Vec<usize> {
+    let Ok(cpuset) = sched_getaffinity(Pid::from_raw(pid as i32)) else {
+        return Vec::new();
+    };
+    (0..CpuSet::count())
+        .filter(|core| cpuset.is_set(*core).unwrap_or(false))
+        .collect()
+}
+
+/// Nice value of the main thread of `pid`.
+pub fn process_niceness(pid: u32) -> Option<i32> {
+    stat_fields(pid)?.get(16)?.parse().ok()
+}
@@ -0,0 +1,174 @@
+use std::error::Error;
+use std::fs::{self, File};
+use std::io::BufReader;
+
+use evdev::{AbsoluteAxisCode, KeyCode};
+use serde::{Deserialize, Serialize};
+
+use crate::paths::*;
+
+/// Default gyro sensitivity: 1.0 maps a 360°/s turn to full stick deflection.
+pub const DEFAULT_GYRO_SENSITIVITY: f32 = 1.0;
+
+/// Default auto-fire rate in presses per second.
+pub const DEFAULT_TURBO_RATE_HZ: f32 = 10.0;
+
+/// Gamepad buttons offered for turbo and toggle-hold, with their menu labels.
+pub const ASSIST_BUTTONS: [(KeyCode, &str); 10] = [
+    (KeyCode::BTN_SOUTH, "A"),
+    (KeyCode::BTN_EAST, "B"),
+    (KeyCode::BTN_NORTH, "X"),
+    (KeyCode::BTN_WEST, "Y"),
+    (KeyCode::BTN_TL, "LB"),
+    (KeyCode::BTN_TR, "RB"),
+    (KeyCode::BTN_TL2, "LT"),
+    (KeyCode::BTN_TR2, "RT"),
+    (KeyCode::BTN_THUMBL, "L3"),
+    (KeyCode::BTN_THUMBR, "R3"),
+];
+
+/// Gyro-to-right-stick mapping applied by the pad proxy for one player.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(default)]
+pub struct GyroMapping {
+    pub enabled: bool,
+    /// Multiplier on the angular velocity before it is turned into stick output.
+    pub sensitivity: f32,
+    pub invert_x: bool,
+    pub invert_y: bool,
+}
+
+impl Default for GyroMapping {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            sensitivity: DEFAULT_GYRO_SENSITIVITY,
+            invert_x: false,
+            invert_y: false,
+        }
+    }
+}
+
+/// Accessibility assists for players who can't comfortably mash or hold
+/// buttons or make fine stick movements.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(default)]
+pub struct InputAssists {
+    /// Key codes that repeat automatically while held.
+    pub turbo_buttons: Vec<u16>,
+    /// Key codes where one press latches the button down and the next releases it.
+    pub toggle_buttons: Vec<u16>,
+    pub turbo_rate_hz: f32,
+    /// Multiplier on both sticks' deflection; below 1.0 slows aiming down.
+    pub stick_sensitivity: f32,
+}
+
+impl Default for InputAssists {
+    fn default() -> Self {
+        Self {
+            turbo_buttons: Vec::new(),
+            toggle_buttons: Vec::new(),
+            turbo_rate_hz: DEFAULT_TURBO_RATE_HZ,
+            stick_sensitivity: 1.0,
+        }
+    }
+}
+
+impl InputAssists {
+    /// Whether any assist changes the pad's output.
+    pub fn is_active(&self) -> bool {
+        !self.turbo_buttons.is_empty()
+            || !self.toggle_buttons.is_empty()
+            || self.stick_sensitivity < 1.0
+    }
+}
+
+/// Button and stick remapping the pad proxy applies for one player.
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq)]
+#[serde(default)]
+pub struct PadRemap {
+    /// Swaps A with B and X with Y, so Nintendo-layout pads press what the
+    /// game's prompts show.
+    pub nintendo_layout: bool,
+    /// Physical key code and the key code sent in its place. These win over
+    /// the layout swap.
+    pub buttons: Vec<(u16, u16)>,
+    pub invert_left_x: bool,
+    pub invert_left_y: bool,
+    pub invert_right_x: bool,
+    pub invert_right_y: bool,
+    /// Stick dead zone used instead of the pad's calibrated one.
+    pub deadzone: Option<f32>,
+}
+
+impl PadRemap {
+    /// Whether the remap changes the pad's output.
+    pub fn is_active(&self) -> bool {
+        self.nintendo_layout
+            || self.buttons.iter().any(|(from, to)| from != to)
+            || self.invert_left_x
+            || self.invert_left_y
+            || self.invert_right_x
+            || self.invert_right_y
+            || self.deadzone.is_some()
+    }
+
+    /// Key code sent to the game when the physical key `code` is pressed.
+    pub fn button(&self, code: u16) -> u16 {
+        if let Some((_, to)) = self.buttons.iter().find(|(from, _)| *from == code) {
+            return *to;
+        }
+        if !self.nintendo_layout {
+            return code;
+        }
+        match KeyCode(code) {
+            KeyCode::BTN_SOUTH => KeyCode::BTN_EAST.0,
+            KeyCode::BTN_EAST => KeyCode::BTN_SOUTH.0,
+            KeyCode::BTN_NORTH => KeyCode::BTN_WEST.0,
+            KeyCode::BTN_WEST => KeyCode::BTN_NORTH.0,
+            _ => code,
+        }
+    }
+
+    /// Whether the stick axis `code` is flipped.
+    pub fn inverts_axis(&self, code: AbsoluteAxisCode) -> bool {
+        match code {
+            AbsoluteAxisCode::ABS_X => self.invert_left_x,
+            AbsoluteAxisCode::ABS_Y => self.invert_left_y,
+            AbsoluteAxisCode::ABS_RX => self.invert_right_x,
+            AbsoluteAxisCode::ABS_RY => self.invert_right_y,
+            _ => false,
+        }
+    }
+}
+
+/// Input preferences that follow a profile regardless of which pad it plays
+/// with, stored as `profiles/<name>/input.json`.
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq)]
+#[serde(default)]
+pub struct ProfileInputSettings {
+    pub gyro: GyroMapping,
+    pub assists: InputAssists,
+    pub remap: PadRemap,
+}
+
+/// Loads a profile's input settings, falling back to defaults for guests and
+/// profiles that never saved any.
+pub fn load_profile_input(profile: &str) -> ProfileInputSettings {
+    File::open(PATH_APP.join(format!("profiles/{profile}/input.json")))
+        .ok()
+        .and_then(|file| serde_json::from_reader(BufReader::new(file)).ok())
+        .unwrap_or_default()
+}
+
+/// Persists a profile's input settings.
+pub fn save_profile_input(
+    profile: &str,
+    settings: &ProfileInputSettings,
+) -> Result<(), Box<dyn Error>> {
+    let profile_dir = PATH_APP.join(format!("profiles/{profile}"));
+    fs::create_dir_all(&profile_dir)?;
+    let file = File::create(profile_dir.join("input.json"))?;
+    serde_json::to_writer_pretty(file, settings)?;
+    Ok(())
+}
@@ -1,4 +1,5 @@
 use rand::prelude::*;
+use serde::{Deserialize, Serialize};
 use serde_json::{Map, Value, json};
 use sha1::{Digest, Sha1};
 use std::collections::{HashMap, HashSet};
@@ -6,9 +7,11 @@ use std::error::Error;
 use std::fs::{self, OpenOptions};
 use std::io::{self, ErrorKind, Write};
 use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
 
-use crate::util::filesystem::copy_dir_recursive;
-use crate::util::sha1_file;
+use crate::logging::{author_mode, log_info, log_warn};
+use crate::util::filesystem::{copy_dir_recursive, write_atomic};
+use crate::util::{pick_file, profile_prefix_key, rename_playtime_profile, sha1_file};
 use crate::{handler::Handler, paths::*};
 
 /// Generates a random hexadecimal string of the requested length so Nemirtingas
@@ -60,34 +63,271 @@ fn normalize_hex(value: &str) -> Option<String> {
     }
 }
 
-/// Logs a warning both to stdout and the persistent launch warning log so profile repairs are
-/// visible even after the session ends.
-fn log_profile_warning(message: &str) {
-    println!("[SPLIT HAPPENS][WARN] {message}");
+/// Classic console player colors used for per-player window borders.
+const PLAYER_COLORS: [&str; 8] = [
+    "#e53935", "#1e88e5", "#43a047", "#fdd835", "#8e24aa", "#fb8c00", "#00acc1", "#d81b60",
+];
 
-    let log_dir = PATH_APP.join("logs");
-    if let Err(err) = fs::create_dir_all(&log_dir) {
-        println!(
-            "[SPLIT HAPPENS][WARN] Failed to prepare launch log directory {}: {}",
-            log_dir.display(),
-            err
-        );
-        return;
+/// Picks the color for a profile. Named profiles hash to a stable palette entry
+/// so a player keeps their color across sessions; guests follow their slot.
+pub fn profile_color(name: &str, slot: usize) -> String {
+    let index = if name.starts_with("Guest") {
+        slot
+    } else {
+        usize::from_str_radix(&deterministic_hex_from_seed(name, 4), 16).unwrap_or(slot)
+    };
+    PLAYER_COLORS[index % PLAYER_COLORS.len()].to_string()
+}
+
+fn unix_now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+/// Play statistics of a profile, updated by the launcher.
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq)]
+#[serde(default)]
+pub struct ProfileStats {
+    pub sessions: u32,
+    pub playtime_secs: u64,
+    /// Unix time of the last launch; 0 if the profile never played.
+    pub last_played: u64,
+}
+
+/// Figure a profile built in the character creator, stored under
+/// `settings.character` in its profile.json.
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq)]
+#[serde(default)]
+pub struct CharacterLook {
+    /// Which of the two hand sprites each side uses, `HAND_0_*` or `HAND_1_*`.
+    pub right_hand: u8,
+    pub left_hand: u8,
+    /// Draws the right leg with the `LOWER_LEG_F` and `FOOT_F` pieces,
+    /// stepping forward.
+    pub front_leg: bool,
+    /// `#rrggbb` tints for the head, neck and hands and for everything else;
+    /// `None` keeps the atlas colors.
+    pub skin: Option<String>,
+    pub clothes: Option<String>,
+}
+
+/// Metadata of a profile, stored as `profiles/<name>/profile.json` next to the
+/// emulator files. The directory name stays the profile's identifier.
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq)]
+#[serde(default)]
+pub struct Profile {
+    /// Directory name under `profiles/`; not stored in the file.
+    #[serde(skip)]
+    pub name: String,
+    /// Stable id that survives renames and names the profile's Proton
+    /// prefixes. Empty until first needed.
+    pub id: String,
+    /// Name shown in menus; empty shows the directory name.
+    pub display_name: String,
+    /// Image file inside the profile directory.
+    pub avatar: Option<String>,
+    /// `#rrggbb` color; `None` uses the palette color from [`profile_color`].
+    pub color: Option<String>,
+    /// Unix time the profile was created.
+    pub created: u64,
+    pub stats: ProfileStats,
+    /// Preferences of individual features, keyed by feature.
+    pub settings: Map<String, Value>,
+    /// Language code Nemirtingas reports to EOS games, like `de` or `pt-BR`;
+    /// `None` uses English.
+    pub language: Option<String>,
+    /// Two-letter country code Nemirtingas reports, like `DE`; `None` uses `US`.
+    pub country: Option<String>,
+    /// Always placed in slot 1, which gets the host's extra CPU core, when
+    /// slots are filled automatically. Only one profile has it set.
+    pub always_host: bool,
+}
+
+impl Profile {
+    fn dir(name: &str) -> PathBuf {
+        PATH_APP.join(format!("profiles/{name}"))
     }
 
-    let log_path = log_dir.join("launch_warnings.txt");
-    if let Err(err) = OpenOptions::new()
-        .create(true)
-        .append(true)
-        .open(&log_path)
-        .and_then(|mut file| writeln!(file, "[WARN] {message}"))
-    {
-        println!(
-            "[SPLIT HAPPENS][WARN] Failed to persist launch warning log {}: {}",
-            log_path.display(),
-            err
-        );
+    /// Loads `name`'s metadata. Profiles made before profile.json existed get
+    /// defaults dated from their directory; they are written on the next save.
+    pub fn load(name: &str) -> Profile {
+        let dir = Profile::dir(name);
+        let mut profile = fs::read_to_string(dir.join("profile.json"))
+            .ok()
+            .and_then(|json| serde_json::from_str::<Profile>(&json).ok())
+            .unwrap_or_else(|| Profile {
+                created: fs::metadata(&dir)
+                    .and_then(|meta| meta.created().or_else(|_| meta.modified()))
+                    .ok()
+                    .and_then(|time| time.duration_since(UNIX_EPOCH).ok())
+                    .map(|d| d.as_secs())
+                    .unwrap_or_else(unix_now),
+                ..Default::default()
+            });
+        profile.name = name.to_string();
+        profile
+    }
+
+    pub fn save(&self) -> io::Result<()> {
+        let dir = Profile::dir(&self.name);
+        fs::create_dir_all(&dir)?;
+        // Copies loaded before the id was made must not drop it, or the
+        // profile would lose its Proton prefixes.
+        let mut profile = self.clone();
+        if profile.id.is_empty() {
+            profile.id = Profile::load(&self.name).id;
+        }
+        let data = serde_json::to_string_pretty(&profile).map_err(io::Error::other)?;
+        // A crash mid-write would otherwise leave the profile unreadable.
+        write_atomic(&dir.join("profile.json"), data)
+    }
+
+    pub fn display_name(&self) -> &str {
+        if self.display_name.trim().is_empty() {
+            &self.name
+        } else {
+            &self.display_name
+        }
+    }
+
+    /// Color of the profile in `slot`, for borders and menus.
+    pub fn color(&self, slot: usize) -> String {
+        self.color
+            .clone()
+            .unwrap_or_else(|| profile_color(&self.name, slot))
+    }
+
+    /// Path of the avatar image, if one is set and still there.
+    pub fn avatar_path(&self) -> Option<PathBuf> {
+        let path = Profile::dir(&self.name).join(self.avatar.as_ref()?);
+        path.is_file().then_some(path)
+    }
+
+    /// Language for the EOS emulator, falling back to `en` when unset or not
+    /// a language code.
+    pub fn eos_language(&self) -> String {
+        self.language
+            .as_deref()
+            .map(str::trim)
+            .filter(|code| {
+                (2..=8).contains(&code.len())
+                    && code.chars().all(|c| c.is_ascii_alphabetic() || c == '-' || c == '_')
+            })
+            .unwrap_or("en")
+            .to_string()
+    }
+
+    /// Country for the EOS emulator, upper-cased, falling back to `US` when
+    /// unset or not a two-letter code.
+    pub fn eos_country(&self) -> String {
+        self.country
+            .as_deref()
+            .map(str::trim)
+            .filter(|code| code.len() == 2 && code.chars().all(|c| c.is_ascii_alphabetic()))
+            .unwrap_or("US")
+            .to_ascii_uppercase()
+    }
+
+    /// The profile's stable id, made and saved the first time it's needed.
+    pub fn stable_id(&mut self) -> io::Result<String> {
+        if self.id.is_empty() {
+            self.id = generate_hex_id(12);
+            self.save()?;
+        }
+        Ok(self.id.clone())
+    }
+
+    /// Figure from the character creator, if the profile made one.
+    pub fn character(&self) -> Option<CharacterLook> {
+        serde_json::from_value(self.settings.get("character")?.clone()).ok()
+    }
+
+    /// Stores the figure from the character creator, or removes it with `None`.
+    pub fn set_character(&mut self, look: Option<&CharacterLook>) {
+        match look.and_then(|look| serde_json::to_value(look).ok()) {
+            Some(value) => {
+                self.settings.insert("character".to_string(), value);
+            }
+            None => {
+                self.settings.remove("character");
+            }
+        }
+    }
+
+    /// Counts a launch that included this profile.
+    pub fn record_session(&mut self) {
+        self.stats.sessions += 1;
+        self.stats.last_played = unix_now();
+    }
+}
+
+/// Image types an avatar can be; Goldberg reads the same ones.
+const AVATAR_EXTENSIONS: [&str; 3] = ["png", "jpg", "jpeg"];
+
+/// Copies `image` into `name`'s folder as its avatar, or removes the avatar
+/// with `None`. The copy keeps working when the original moves.
+pub fn set_profile_avatar(name: &str, image: Option<&Path>) -> io::Result<()> {
+    // Read before the old avatar goes, in case it is the one picked again.
+    let picked = match image {
+        Some(image) => {
+            let extension = image
+                .extension()
+                .map(|ext| ext.to_string_lossy().to_ascii_lowercase())
+                .filter(|ext| AVATAR_EXTENSIONS.contains(&ext.as_str()))
+                .ok_or_else(|| {
+                    io::Error::new(ErrorKind::InvalidInput, "Avatars must be PNG or JPEG")
+                })?;
+            Some((extension, fs::read(image)?))
+        }
+        None => None,
+    };
+    let mut profile = Profile::load(name);
+    if let Some(old) = profile.avatar_path() {
+        fs::remove_file(old)?;
+    }
+    profile.avatar = None;
+    if let Some((extension, bytes)) = picked {
+        let file = format!("avatar.{extension}");
+        fs::write(Profile::dir(name).join(&file), bytes)?;
+        profile.avatar = Some(file);
     }
+    profile.save()
+}
+
+/// Asks for an image and makes it `name`'s avatar. Returns whether one was
+/// picked.
+pub fn pick_profile_avatar(name: &str) -> Result<bool, Box<dyn Error>> {
+    let filters = [("Images", &AVATAR_EXTENSIONS[..])];
+    let Some(image) = pick_file(&format!("Avatar for {name}"), &filters) else {
+        return Ok(false);
+    };
+    set_profile_avatar(name, Some(&image))?;
+    Ok(true)
+}
+
+/// Mirrors the profile's avatar into its Goldberg settings as
+/// `account_avatar.<ext>`, which Goldberg shows as the player's Steam avatar.
+/// Without an avatar, earlier copies are removed so Goldberg's default shows.
+fn sync_goldberg_avatar(name: &str, steam_settings: &Path) -> io::Result<()> {
+    let avatar = Profile::load(name).avatar_path();
+    let wanted = avatar.as_ref().and_then(|path| {
+        let extension = path.extension()?.to_string_lossy().to_string();
+        Some(steam_settings.join(format!("account_avatar.{extension}")))
+    });
+    for extension in AVATAR_EXTENSIONS {
+        let copy = steam_settings.join(format!("account_avatar.{extension}"));
+        if copy.exists() && Some(&copy) != wanted.as_ref() {
+            fs::remove_file(copy)?;
+        }
+    }
+    if let (Some(avatar), Some(wanted)) = (avatar, wanted) {
+        if fs::read(&wanted).ok() != Some(fs::read(&avatar)?) {
+            fs::copy(avatar, wanted)?;
+        }
+    }
+    Ok(())
 }
 
 // Makes a folder and sets up Goldberg Steam Emu profile for Steam games
@@ -95,7 +335,7 @@ pub fn create_profile(name: &str) -> Result<(), std::io::Error> {
     let profile_dir = PATH_APP.join(format!("profiles/{name}"));
 
     if !profile_dir.exists() {
-        println!("Creating profile {name}");
+        log_info(format!("Creating profile {name}"));
         let path_steam = profile_dir.join("steam/settings");
         fs::create_dir_all(&path_steam)?;
 
@@ -105,7 +345,14 @@ pub fn create_profile(name: &str) -> Result<(), std::io::Error> {
         );
         fs::write(path_steam.join("configs.user.ini"), usersettings)?;
 
-        println!("Created successfully");
+        Profile {
+            name: name.to_string(),
+            created: unix_now(),
+            ..Default::default()
+        }
+        .save()?;
+
+        log_info("Created successfully");
     }
 
     fs::create_dir_all(profile_dir.join("nepice_settings"))?;
@@ -136,7 +383,12 @@ pub fn rename_profile(old_name: &str, new_name: &str) -> io::Result<()> {
         ));
     }
 
-    std::fs::rename(source_dir, target_dir)
+    // Prefixes still named after the profile move to its id, which the
+    // profile keeps under the new name.
+    profile_prefix_key(old_name);
+    std::fs::rename(source_dir, target_dir)?;
+    rename_playtime_profile(old_name, new_name);
+    Ok(())
 }
 
 /// Writes a Goldberg configuration helper file only when the trimmed contents differ so
@@ -171,7 +423,12 @@ fn write_setting_if_changed(path: &Path, value: &str) -> io::Result<()> {
 /// section without disturbing the rest of the configuration. The helper either updates
 /// an existing entry or appends it at the end of the section if missing, creating the
 /// section on demand when necessary.
-fn ensure_ini_setting(path: &Path, section: &str, key: &str, value: &str) -> io::Result<()> {
+pub(crate) fn ensure_ini_setting(
+    path: &Path,
+    section: &str,
+    key: &str,
+    value: &str,
+) -> io::Result<()> {
     let desired_section = section;
     let desired_key = format!("{key}={value}");
     let key_prefix = format!("{key}=");
@@ -270,7 +527,7 @@ fn ensure_ini_listen_port(path: &Path, port: u16) -> io::Result<()> {
 
 /// Extracts a `key=value` pair from Goldberg's `configs.user.ini`, returning `None` when
 /// the file cannot be read or the key was absent.
-fn read_config_value(config_path: &Path, key: &str) -> Option<String> {
+pub fn read_config_value(config_path: &Path, key: &str) -> Option<String> {
     let Ok(contents) = fs::read_to_string(config_path) else {
         return None;
     };
@@ -283,7 +540,7 @@ fn read_config_value(config_path: &Path, key: &str) -> Option<String> {
 
 /// Computes a deterministic Goldberg listen port derived from the game identifier so all
 /// instances share a stable LAN discovery socket without clashing across different games.
-fn deterministic_goldberg_port(game_id: &str) -> u16 {
+pub fn deterministic_goldberg_port(game_id: &str) -> u16 {
     let mut hasher = Sha1::new();
     hasher.update(format!("split-happens-goldberg-port:{game_id}").as_bytes());
     let digest = hasher.finalize();
@@ -401,6 +658,9 @@ pub fn synchronize_goldberg_profiles(
         write_setting_if_changed(&steam_settings.join("account_name.txt"), &account_name)?;
         write_setting_if_changed(&steam_settings.join("user_steam_id.txt"), &user_steam_id)?;
         write_setting_if_changed(&steam_settings.join("language.txt"), "english")?;
+        if let Err(err) = sync_goldberg_avatar(name, &steam_settings) {
+            log_warn(format!("Couldn't copy the avatar of profile {name} to Goldberg: {err}"));
+        }
 
         // Toggle LAN discovery helpers to avoid requiring the Steam overlay for invites.
         write_setting_if_changed(&steam_settings.join("auto_accept_invite.txt"), "")?;
@@ -445,10 +705,10 @@ pub fn synchronize_goldberg_profiles(
         ensure_ini_listen_port(&steam_settings.join("configs.main.ini"), port)?;
         ensure_ini_listen_port(&steam_settings.join("configs.user.ini"), port)?;
 
-        println!(
-            "[SPLIT HAPPENS] Goldberg LAN identity for profile {} set to {} / {} on port {} ({})",
+        log_info(format!(
+            "Goldberg LAN identity for profile {} set to {} / {} on port {} ({})",
             name, account_name, user_steam_id, port, port_source
-        );
+        ));
     }
 
     // Expose the synchronized port so launch routines can mirror it into Nemirtingas configs
@@ -456,10 +716,35 @@ pub fn synchronize_goldberg_profiles(
     Ok(Some(port))
 }
 
+/// Recursively writes `overlay` over `base`: objects are merged key by key,
+/// any other value replaces what was there.
+fn merge_json(base: &mut Value, overlay: &Value) {
+    match (base, overlay) {
+        (Value::Object(base), Value::Object(overlay)) => {
+            for (key, value) in overlay {
+                match base.get_mut(key) {
+                    Some(existing) => merge_json(existing, value),
+                    None => {
+                        base.insert(key.clone(), value.clone());
+                    }
+                }
+            }
+        }
+        (base, overlay) => *base = overlay.clone(),
+    }
+}
+
+/// Writes `name`'s Nemirtingas config for `appid`, keeping its identity and
+/// reporting `language` and `country` (see [`Profile::eos_language`]). A
+/// handler's `template` (see [`Handler::nemirtingas_template`]) is merged over
+/// the defaults; the identity, region and LAN port always stay generated.
 pub fn ensure_nemirtingas_config(
     name: &str,
     appid: &str,
     lan_port: Option<u16>,
+    language: &str,
+    country: &str,
+    template: Option<&Value>,
 ) -> Result<(PathBuf, PathBuf, PathBuf, String), Box<dyn Error>> {
     let profile_dir = PATH_APP.join(format!("profiles/{name}"));
     fs::create_dir_all(&profile_dir)?;
@@ -541,7 +826,7 @@ pub fn ensure_nemirtingas_config(
         if let Some(clean) = normalize_hex(&id) {
             Some(clean)
         } else {
-            log_profile_warning(&format!(
+            log_warn(format!(
                 "Profile {name} contained invalid Nemirtingas EpicId {id}; regenerating."
             ));
             None
@@ -552,7 +837,7 @@ pub fn ensure_nemirtingas_config(
         if let Some(clean) = normalize_hex(&id) {
             Some(clean)
         } else {
-            log_profile_warning(&format!(
+            log_warn(format!(
                 "Profile {name} contained invalid Nemirtingas ProductUserId {id}; regenerating."
             ));
             None
@@ -563,7 +848,7 @@ pub fn ensure_nemirtingas_config(
         if let Some(clean) = normalize_hex(&id) {
             Some(clean)
         } else {
-            log_profile_warning(&format!(
+            log_warn(format!(
                 "Profile {name} contained invalid Nemirtingas AccountId {id}; regenerating."
             ));
             None
@@ -575,13 +860,13 @@ pub fn ensure_nemirtingas_config(
         && existing_accountid_raw.is_none()
         && !used_placeholder_username
     {
-        log_profile_warning(&format!(
+        log_warn(format!(
             "Profile {name} was missing a Nemirtingas AccountId; generating a new value."
         ));
     }
 
     if used_placeholder_username && had_existing_config {
-        log_profile_warning(&format!(
+        log_warn(format!(
             "Profile {name} used the Nemirtingas placeholder username; regenerating IDs with the profile name."
         ));
         existing_epicid = None;
@@ -593,21 +878,21 @@ pub fn ensure_nemirtingas_config(
     // depend on the emulator regenerating identifiers on every launch.
     let epic_id = existing_epicid.unwrap_or_else(|| {
         let new_id = deterministic_hex_from_seed(&profile_username, 32);
-        println!(
-            "[SPLIT HAPPENS] Generated Nemirtingas EpicId {} for profile {} using deterministic username seed",
+        log_info(format!(
+            "Generated Nemirtingas EpicId {} for profile {} using deterministic username seed",
             new_id,
             name
-        );
+        ));
         new_id
     });
     let product_user_id = existing_productuserid.unwrap_or_else(|| {
         let seed = format!("{appid}:{epic_id}");
         let new_id = deterministic_hex_from_seed(&seed, 32);
-        println!(
-            "[SPLIT HAPPENS] Generated Nemirtingas ProductUserId {} for profile {} using deterministic seed",
+        log_info(format!(
+            "Generated Nemirtingas ProductUserId {} for profile {} using deterministic seed",
             new_id,
             name
-        );
+        ));
         new_id
     });
 
@@ -616,35 +901,33 @@ pub fn ensure_nemirtingas_config(
     let account_id = existing_accountid.unwrap_or_else(|| {
         let seed = format!("account:{profile_username}");
         let new_id = deterministic_hex_from_seed(&seed, 32);
-        println!(
-            "[SPLIT HAPPENS] Generated Nemirtingas AccountId {} for profile {} using deterministic username seed",
+        log_info(format!(
+            "Generated Nemirtingas AccountId {} for profile {} using deterministic username seed",
             new_id,
             name
-        );
+        ));
         new_id
     });
 
+    // Author mode asks Nemirtingas for everything it can log, including its
+    // network plugins; regular sessions only keep errors.
+    let verbose = author_mode();
+    let (log_level, flat_log_level) = match verbose {
+        true => ("Trace", "TRACE"),
+        false => ("Error", "ERROR"),
+    };
+
     // Build the Nemirtingas configuration with the expected nested layout.
-    let mut user_obj = Map::new();
-    user_obj.insert("Language".to_string(), json!("en"));
-    user_obj.insert("UserName".to_string(), json!(profile_username.clone()));
-    user_obj.insert("EpicId".to_string(), json!(epic_id.clone()));
-    user_obj.insert("ProductUserId".to_string(), json!(product_user_id.clone()));
-    user_obj.insert("AccountId".to_string(), json!(account_id.clone()));
-
-    let mut obj = Map::new();
-    obj.insert(
-        "EOSEmu".to_string(),
-        json!({
+    let mut config = json!({
+        "EOSEmu": {
             "Achievements": {
                 "OnlineDatabase": ""
             },
             "Application": {
-                "AppId": appid,
                 "DisableCrashDump": false,
                 "DisableOnlineNetworking": false,
-                // Limit Nemirtingas output to error-level messages so per-profile logs only capture critical emulator issues.
-                "LogLevel": "Error",
+                // Outside author mode, limit Nemirtingas output to error-level messages so per-profile logs only capture critical emulator issues.
+                "LogLevel": log_level,
                 "SavePath": "appdata"
             },
             "Ecom": {
@@ -655,61 +938,80 @@ pub fn ensure_nemirtingas_config(
                     "DelayDetection": "5s",
                     "Enabled": true
                 }
+            }
+        },
+        // Enable the broadcast plugin so Nemirtingas advertises the lobby over LAN, allowing
+        // other players on the local network to discover the host via invite codes.
+        "Network": {
+            "IceServers": [],
+            "Plugins": {
+                "Broadcast": {
+                    "EnableLog": verbose,
+                    "Enabled": true,
+                    "LocalhostOnly": false
+                },
+                "WebSocket": {
+                    "EnableLog": verbose,
+                    "Enabled": false,
+                    "SignalingServers": []
+                }
+            }
+        },
+        // Mirror the nested level so legacy builds log the same amount.
+        "log_level": flat_log_level
+    });
+
+    // Handlers override the defaults above, e.g. to turn the overlay off or
+    // point WebSocket signaling at a server.
+    if let Some(template) = template {
+        merge_json(&mut config, template);
+    }
+
+    // Identity, region and LAN port are written last so no template can
+    // break invite codes or the port shared with Goldberg.
+    let mut pinned = json!({
+        "EOSEmu": {
+            "Application": {
+                "AppId": appid
             },
-            "User": user_obj
-        }),
-    );
-    // Enable the broadcast plugin so Nemirtingas advertises the lobby over LAN, allowing
-    // other players on the local network to discover the host via invite codes. When a
-    // synchronized Goldberg listen port is available, also override the LAN beacon to the
-    // same UDP socket so EOS discovery and Goldberg stay aligned.
-    let mut network_plugins = Map::new();
-    network_plugins.insert(
-        "Broadcast".to_string(),
-        json!({
-            "EnableLog": false,
-            "Enabled": true,
-            "LocalhostOnly": false
-        }),
-    );
-    network_plugins.insert(
-        "WebSocket".to_string(),
-        json!({
-            "EnableLog": false,
-            "Enabled": false,
-            "SignalingServers": []
-        }),
-    );
-
-    let mut network_obj = Map::new();
-    network_obj.insert("IceServers".to_string(), json!([]));
-    network_obj.insert("Plugins".to_string(), Value::Object(network_plugins));
+            "User": {
+                "Language": language,
+                "Country": country,
+                "UserName": profile_username,
+                "EpicId": epic_id,
+                "ProductUserId": product_user_id,
+                "AccountId": account_id
+            }
+        },
+        "appid": appid,
+        "language": language,
+        "country": country,
+        "username": profile_username,
+        // Surface the generated IDs in the flat layout as well so legacy Nemirtingas builds read them consistently.
+        "epicid": epic_id,
+        "productuserid": product_user_id,
+        "accountid": account_id
+    });
     if let Some(port) = lan_port {
-        network_obj.insert(
-            "Lan".to_string(),
-            json!({
-                "Enabled": true,
-                "OverridePort": port
+        // When a synchronized Goldberg listen port is available, override the LAN beacon to
+        // the same UDP socket so EOS discovery and Goldberg stay aligned. The flat key serves
+        // older Nemirtingas builds that only inspect top-level keys.
+        merge_json(
+            &mut pinned,
+            &json!({
+                "Network": {
+                    "Lan": {
+                        "Enabled": true,
+                        "OverridePort": port
+                    }
+                },
+                "lan_port": port
             }),
         );
     }
-    obj.insert("Network".to_string(), Value::Object(network_obj));
-    obj.insert("appid".to_string(), json!(appid));
-    obj.insert("language".to_string(), json!("en"));
-    // Limit Nemirtingas output to error-level entries so per-profile logs only capture actionable issues.
-    obj.insert("log_level".to_string(), json!("ERROR"));
-    obj.insert("username".to_string(), json!(profile_username));
-    // Surface the generated IDs in the flat layout as well so legacy Nemirtingas builds read them consistently.
-    obj.insert("epicid".to_string(), json!(epic_id));
-    obj.insert("productuserid".to_string(), json!(product_user_id));
-    obj.insert("accountid".to_string(), json!(account_id));
-    if let Some(port) = lan_port {
-        // Surface the synchronized LAN port in the flat schema so older Nemirtingas builds
-        // that only inspect top-level keys can still reuse Goldberg's UDP socket.
-        obj.insert("lan_port".to_string(), json!(port));
-    }
+    merge_json(&mut config, &pinned);
 
-    let data = serde_json::to_string_pretty(&Value::Object(obj))?;
+    let data = serde_json::to_string_pretty(&config)?;
     let mut file = OpenOptions::new()
         .write(true)
         .create(true)
@@ -722,17 +1024,17 @@ pub fn ensure_nemirtingas_config(
     // users can still reference critical error messages after a session.
     let log_path = nepice_dir.join("NemirtingasEpicEmu.log");
     match OpenOptions::new().create(true).append(true).open(&log_path) {
-        Ok(_) => println!(
-            "[SPLIT HAPPENS] Nemirtingas log for profile {} will be written to {}",
+        Ok(_) => log_info(format!(
+            "Nemirtingas log for profile {} will be written to {}",
             name,
             log_path.display()
-        ),
-        Err(err) => println!(
-            "[SPLIT HAPPENS][WARN] Failed to prepare Nemirtingas log for profile {} at {}: {}",
+        )),
+        Err(err) => log_warn(format!(
+            "Failed to prepare Nemirtingas log for profile {} at {}: {}",
             name,
             log_path.display(),
             err
-        ),
+        )),
     }
 
     // Guarantee each profile exposes a dedicated Nemirtingas AppData root so concurrent
@@ -753,10 +1055,10 @@ pub fn create_gamesave(name: &str, h: &Handler) -> Result<(), Box<dyn Error>> {
         .join(&h.uid);
 
     if path_gamesave.exists() {
-        println!("{} already has save for {}, continuing...", name, h.uid);
+        log_info(format!("{} already has save for {}, continuing...", name, h.uid));
         return Ok(());
     }
-    println!("Creating game save {} for {}", h.uid, name);
+    log_info(format!("Creating game save {} for {}", h.uid, name));
 
     if h.win_unique_appdata {
         std::fs::create_dir_all(path_gamesave.join("_AppData/Local"))?;
@@ -783,7 +1085,7 @@ pub fn create_gamesave(name: &str, h: &Handler) -> Result<(), Box<dyn Error>> {
         if path.contains('.') {
             continue;
         }
-        println!("Creating subdirectory /{path}");
+        log_info(format!("Creating subdirectory /{path}"));
         let path = path_gamesave.join(path);
         if !path.exists() {
             std::fs::create_dir_all(path)?;
@@ -792,11 +1094,11 @@ pub fn create_gamesave(name: &str, h: &Handler) -> Result<(), Box<dyn Error>> {
 
     let copy_save_src = PathBuf::from(&h.path_handler).join("copy_to_profilesave");
     if copy_save_src.exists() {
-        println!("{} handler has built-in save data, copying...", h.uid);
+        log_info(format!("{} handler has built-in save data, copying...", h.uid));
         copy_dir_recursive(&copy_save_src, &path_gamesave, false, true, None)?;
     }
 
-    println!("Save data directories created successfully");
+    log_info("Save data directories created successfully");
     Ok(())
 }
 
@@ -826,6 +1128,27 @@ pub fn scan_profiles(include_guest: bool) -> Vec<String> {
     out
 }
 
+/// The profile marked to always play in slot 1, if any.
+pub fn host_profile() -> Option<String> {
+    scan_profiles(false)
+        .into_iter()
+        .find(|name| Profile::load(name).always_host)
+}
+
+/// Marks `name` as the profile that always plays in slot 1, taking the mark
+/// from any other profile. `None` clears it.
+pub fn set_host_profile(name: Option<&str>) -> io::Result<()> {
+    for profile in scan_profiles(false) {
+        let mut meta = Profile::load(&profile);
+        let host = name == Some(profile.as_str());
+        if meta.always_host != host {
+            meta.always_host = host;
+            meta.save()?;
+        }
+    }
+    Ok(())
+}
+
 /// Cleans up legacy guest profiles that used the old dotted naming convention so they do
 /// not accumulate alongside the new deterministic guest slots.
 pub fn remove_guest_profiles() -> Result<(), Box<dyn Error>> {
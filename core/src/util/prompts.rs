@@ -0,0 +1,55 @@
+use std::path::PathBuf;
+use std::sync::RwLock;
+
+use crate::logging::log_warn;
+
+/// Dialogs the core needs from whichever frontend is running. The GUI shows
+/// desktop dialogs; without a prompter, messages only reach the log and
+/// questions are answered no, so nothing blocks on a window nobody sees.
+pub trait Prompter: Send + Sync {
+    fn message(&self, title: &str, contents: &str);
+    /// Asks a yes/no question; `true` for yes.
+    fn confirm(&self, title: &str, contents: &str) -> bool;
+    /// Asks for a file, limited to `filters` (a label and its extensions)
+    /// unless that is empty. `None` when cancelled.
+    fn pick_file(&self, title: &str, filters: &[(&str, &[&str])]) -> Option<PathBuf>;
+    /// Asks for a folder. `None` when cancelled.
+    fn pick_folder(&self, title: &str) -> Option<PathBuf>;
+}
+
+static PROMPTER: RwLock<Option<Box<dyn Prompter>>> = RwLock::new(None);
+
+/// Installs the frontend's prompter, or removes it with `None`.
+pub fn set_prompter(prompter: Option<Box<dyn Prompter>>) {
+    *PROMPTER.write().unwrap() = prompter;
+}
+
+/// Whether a frontend can show dialogs right now.
+pub fn prompts_available() -> bool {
+    PROMPTER.read().unwrap().is_some()
+}
+
+pub fn msg(title: &str, contents: &str) {
+    match PROMPTER.read().unwrap().as_deref() {
+        Some(prompter) => prompter.message(title, contents),
+        None => log_warn(format!("{title}: {contents}")),
+    }
+}
+
+pub fn yesno(title: &str, contents: &str) -> bool {
+    match PROMPTER.read().unwrap().as_deref() {
+        Some(prompter) => prompter.confirm(title, contents),
+        None => {
+            log_warn(format!("{title}: {contents} (no prompt available, answering no)"));
+            false
+        }
+    }
+}
+
+pub fn pick_file(title: &str, filters: &[(&str, &[&str])]) -> Option<PathBuf> {
+    PROMPTER.read().unwrap().as_deref()?.pick_file(title, filters)
+}
+
+pub fn pick_folder(title: &str) -> Option<PathBuf> {
+    PROMPTER.read().unwrap().as_deref()?.pick_folder(title)
+}
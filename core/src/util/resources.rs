@@ -0,0 +1,447 @@
+use std::collections::BTreeMap;
+use std::error::Error;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::sync::LazyLock;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use super::filesystem::write_atomic;
+use super::handler_repo::curl_download;
+use super::net::curl_command;
+use crate::logging::log_info;
+use crate::paths::*;
+use crate::util::sha1_file;
+
+const RELEASES_TIMEOUT_SECS: &str = "15";
+const ASSET_TIMEOUT_SECS: &str = "600";
+
+/// Emulator builds that can be swapped per handler instead of using `res/`.
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum ResourceKind {
+    Goldberg,
+    Nemirtingas,
+}
+
+impl ResourceKind {
+    pub const ALL: [ResourceKind; 2] = [ResourceKind::Goldberg, ResourceKind::Nemirtingas];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            ResourceKind::Goldberg => "Goldberg",
+            ResourceKind::Nemirtingas => "Nemirtingas",
+        }
+    }
+
+    fn dir_name(self) -> &'static str {
+        match self {
+            ResourceKind::Goldberg => "goldberg",
+            ResourceKind::Nemirtingas => "nemirtingas",
+        }
+    }
+
+    /// Upstream release API: gbe_fork on GitHub and Nemirtingas' emulator on GitLab.
+    fn releases_url(self) -> &'static str {
+        match self {
+            ResourceKind::Goldberg => "https://api.github.com/repos/Detanup01/gbe_fork/releases",
+            ResourceKind::Nemirtingas => {
+                "https://gitlab.com/api/v4/projects/Nemirtingas%2Fnemirtingas_epic_emu/releases"
+            }
+        }
+    }
+
+    /// Whether a release asset holds files this kind installs.
+    fn wants_asset(self, name: &str) -> bool {
+        let name = name.to_ascii_lowercase();
+        match self {
+            ResourceKind::Goldberg => {
+                name == "emu-linux-release.tar.bz2" || name == "emu-win-release.7z"
+            }
+            ResourceKind::Nemirtingas => {
+                name.contains("win")
+                    && !name.contains("debug")
+                    && [".zip", ".7z", ".tar.gz", ".tar.xz"].iter().any(|ext| name.ends_with(ext))
+            }
+        }
+    }
+
+    /// File names to pick out of the extracted archives and where they go in
+    /// the build folder, which mirrors the `res/` layout.
+    fn install_target(self, path: &Path) -> Option<PathBuf> {
+        let name = path.file_name()?.to_str()?;
+        let in_dir = |dir: &str| path.components().any(|c| c.as_os_str() == dir);
+        match (self, name) {
+            (ResourceKind::Goldberg, "steam_api.dll") => Some("win/x32/steam_api.dll".into()),
+            (ResourceKind::Goldberg, "steam_api64.dll") => Some("win/x64/steam_api64.dll".into()),
+            (ResourceKind::Goldberg, "libsteam_api.so") if in_dir("x32") => {
+                Some("linux/x32/libsteam_api.so".into())
+            }
+            (ResourceKind::Goldberg, "libsteam_api.so") if in_dir("x64") => {
+                Some("linux/x64/libsteam_api.so".into())
+            }
+            (ResourceKind::Goldberg, "generate_interfaces_x32" | "generate_interfaces_x64") => {
+                Some(name.into())
+            }
+            (ResourceKind::Nemirtingas, "EOSSDK-Win32-Shipping.dll")
+            | (ResourceKind::Nemirtingas, "EOSSDK-Win64-Shipping.dll") => Some(name.into()),
+            _ => None,
+        }
+    }
+}
+
+/// Archives often ship several variants of the same file. Goldberg's
+/// experimental build is what `res/` bundles, so it wins over the regular one;
+/// debug builds are only used when nothing else is there.
+fn variant_rank(path: &Path) -> u8 {
+    let path = path.to_string_lossy().to_ascii_lowercase();
+    if path.contains("debug") {
+        3
+    } else if path.contains("experimental") {
+        0
+    } else if path.contains("regular") {
+        1
+    } else {
+        2
+    }
+}
+
+/// An upstream release and the downloadable assets this launcher can use.
+#[derive(Clone, Debug)]
+pub struct ResourceRelease {
+    pub version: String,
+    /// Asset file names and their download URLs.
+    pub assets: Vec<(String, String)>,
+}
+
+/// A downloaded build, pinned by the checksums taken when it was first
+/// installed. The pins trust that first download: they catch a build that
+/// changed afterwards, not one that was already tampered with.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct ResourceBuild {
+    pub kind: ResourceKind,
+    pub version: String,
+    /// SHA1 of each downloaded archive. Reinstalling the same version must
+    /// produce the same archives, so a replaced upstream asset is refused.
+    pub archives: BTreeMap<String, String>,
+    /// SHA1 of every installed file, keyed by its path in the build folder.
+    /// Checked each time the build is used.
+    pub files: BTreeMap<String, String>,
+}
+
+fn resources_root() -> PathBuf {
+    PATH_APP.join("resources")
+}
+
+fn pins_path() -> PathBuf {
+    resources_root().join("pins.json")
+}
+
+fn build_dir(kind: ResourceKind, version: &str) -> PathBuf {
+    resources_root().join(kind.dir_name()).join(version)
+}
+
+fn load_pins() -> Vec<ResourceBuild> {
+    fs::read(pins_path())
+        .ok()
+        .and_then(|bytes| serde_json::from_slice(&bytes).ok())
+        .unwrap_or_default()
+}
+
+fn save_pins(pins: &[ResourceBuild]) -> Result<(), Box<dyn Error>> {
+    fs::create_dir_all(resources_root())?;
+    // A crash mid-write would otherwise lose every pin.
+    write_atomic(&pins_path(), serde_json::to_string_pretty(pins)?)?;
+    Ok(())
+}
+
+/// Installed builds of `kind`, newest install last.
+pub fn installed_resources(kind: ResourceKind) -> Vec<ResourceBuild> {
+    load_pins()
+        .into_iter()
+        .filter(|build| build.kind == kind)
+        .collect()
+}
+
+/// Lists upstream releases of `kind` that have assets this launcher can use,
+/// newest first.
+pub fn fetch_resource_releases(
+    kind: ResourceKind,
+) -> Result<Vec<ResourceRelease>, Box<dyn Error>> {
+    let output = curl_command()?
+        .args([
+            "-sSfL",
+            "--max-time",
+            RELEASES_TIMEOUT_SECS,
+            "-H",
+            "User-Agent: split-happens",
+            kind.releases_url(),
+        ])
+        .output()?;
+    if !output.status.success() {
+        return Err(format!(
+            "Couldn't fetch {} releases: {}",
+            kind.name(),
+            String::from_utf8_lossy(&output.stderr).trim()
+        )
+        .into());
+    }
+
+    let json: Value = serde_json::from_slice(&output.stdout)?;
+    let releases = json
+        .as_array()
+        .map(|releases| {
+            releases
+                .iter()
+                .filter_map(|release| {
+                    let version = release["tag_name"].as_str()?.to_string();
+                    // GitHub lists `assets`, GitLab nests them under `assets.links`.
+                    let assets = release["assets"]
+                        .as_array()
+                        .or_else(|| release["assets"]["links"].as_array())?
+                        .iter()
+                        .filter_map(|asset| {
+                            let name = asset["name"].as_str()?;
+                            let url = asset["browser_download_url"]
+                                .as_str()
+                                .or_else(|| asset["url"].as_str())?;
+                            kind.wants_asset(name).then(|| (name.to_string(), url.to_string()))
+                        })
+                        .collect::<Vec<_>>();
+                    // The tag names the build folder, so only plain tags are offered.
+                    let plain_tag = !version.starts_with('.')
+                        && version.chars().all(|c| c.is_ascii_alphanumeric() || "._-".contains(c));
+                    (plain_tag && !assets.is_empty()).then_some(ResourceRelease { version, assets })
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+    Ok(releases)
+}
+
+/// Unpacks `archive` into `dest` with the system tools: 7z for `.7z` and
+/// `.zip`, tar for everything else.
+fn extract_archive(archive: &Path, dest: &Path) -> Result<(), Box<dyn Error>> {
+    let name = archive.to_string_lossy().to_ascii_lowercase();
+    let status = if name.ends_with(".7z") || name.ends_with(".zip") {
+        Command::new("7z")
+            .arg("x")
+            .arg("-y")
+            .arg(format!("-o{}", dest.display()))
+            .arg(archive)
+            .output()
+            .map_err(|_| "7z is needed to unpack this release")?
+            .status
+    } else {
+        Command::new("tar")
+            .arg("-xf")
+            .arg(archive)
+            .arg("-C")
+            .arg(dest)
+            .status()?
+    };
+    if !status.success() {
+        return Err(format!("Couldn't unpack {}", archive.display()).into());
+    }
+    Ok(())
+}
+
+/// Every regular file below `dir`, without following symlinks.
+fn files_below(dir: &Path) -> Vec<PathBuf> {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    let mut files = Vec::new();
+    for entry in entries.flatten() {
+        let Ok(file_type) = entry.file_type() else {
+            continue;
+        };
+        if file_type.is_dir() {
+            files.extend(files_below(&entry.path()));
+        } else if file_type.is_file() {
+            files.push(entry.path());
+        }
+    }
+    files
+}
+
+/// Downloads and unpacks `release` in `staging`, then copies the wanted files
+/// into the build folder.
+fn stage_resource(
+    kind: ResourceKind,
+    release: &ResourceRelease,
+    staging: &Path,
+) -> Result<ResourceBuild, Box<dyn Error>> {
+    let previous = installed_resources(kind)
+        .into_iter()
+        .find(|build| build.version == release.version);
+
+    // Download and verify every asset before touching the installed build.
+    let mut archives = BTreeMap::new();
+    for (name, url) in &release.assets {
+        let archive = staging.join(name);
+        curl_download(url, &archive, ASSET_TIMEOUT_SECS)?;
+        let sha1 = sha1_file(&archive)?;
+        let pinned = previous.as_ref().and_then(|build| build.archives.get(name));
+        if pinned.is_some_and(|pinned| *pinned != sha1) {
+            return Err(format!(
+                "{name} of {} {} changed upstream since it was pinned",
+                kind.name(),
+                release.version
+            )
+            .into());
+        }
+        let unpacked = staging.join(format!("{name}.d"));
+        fs::create_dir_all(&unpacked)?;
+        extract_archive(&archive, &unpacked)?;
+        archives.insert(name.clone(), sha1);
+    }
+
+    // Pick the best variant of each wanted file.
+    let mut picked: BTreeMap<PathBuf, PathBuf> = BTreeMap::new();
+    for file in files_below(&staging) {
+        let Some(target) = kind.install_target(&file) else {
+            continue;
+        };
+        let better = picked
+            .get(&target)
+            .is_none_or(|current| variant_rank(&file) < variant_rank(current));
+        if better {
+            picked.insert(target, file);
+        }
+    }
+    if picked.is_empty() {
+        return Err(format!("{} {} has no usable files", kind.name(), release.version).into());
+    }
+
+    let dest = build_dir(kind, &release.version);
+    let _ = fs::remove_dir_all(&dest);
+    let mut files = BTreeMap::new();
+    for (target, source) in picked {
+        let target_path = dest.join(&target);
+        if let Some(parent) = target_path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::copy(&source, &target_path)?;
+        files.insert(target.to_string_lossy().to_string(), sha1_file(&target_path)?);
+    }
+
+    Ok(ResourceBuild {
+        kind,
+        version: release.version.clone(),
+        archives,
+        files,
+    })
+}
+
+/// Downloads `release`, checks its archives against an earlier pin of the
+/// same version, and installs the files this launcher uses into
+/// `PATH_APP/resources/<kind>/<version>`.
+pub fn install_resource(
+    kind: ResourceKind,
+    release: &ResourceRelease,
+) -> Result<ResourceBuild, Box<dyn Error>> {
+    let staging = resources_root().join("staging");
+    let _ = fs::remove_dir_all(&staging);
+    fs::create_dir_all(&staging)?;
+    let result = stage_resource(kind, release, &staging);
+    let _ = fs::remove_dir_all(&staging);
+    let build = result?;
+
+    let mut pins = load_pins();
+    pins.retain(|pin| !(pin.kind == kind && pin.version == build.version));
+    pins.push(build.clone());
+    save_pins(&pins)?;
+    log_info(format!(
+        "Installed {} {} ({} files).",
+        kind.name(),
+        build.version,
+        build.files.len()
+    ));
+    Ok(build)
+}
+
+/// Deletes an installed build and its pin.
+pub fn remove_resource(kind: ResourceKind, version: &str) -> Result<(), Box<dyn Error>> {
+    let _ = fs::remove_dir_all(build_dir(kind, version));
+    let mut pins = load_pins();
+    pins.retain(|pin| !(pin.kind == kind && pin.version == version));
+    save_pins(&pins)
+}
+
+/// Folder of an installed build after checking every file against its pin,
+/// so a modified or half-deleted build is never copied into a game.
+pub fn resource_dir(kind: ResourceKind, version: &str) -> Result<PathBuf, Box<dyn Error>> {
+    let Some(build) = installed_resources(kind)
+        .into_iter()
+        .find(|build| build.version == version)
+    else {
+        return Err(format!("{} {version} is not installed", kind.name()).into());
+    };
+    let dir = build_dir(kind, version);
+    for (file, expected) in &build.files {
+        let actual = sha1_file(&dir.join(file)).unwrap_or_default();
+        if actual != *expected {
+            return Err(format!(
+                "{file} of {} {version} doesn't match its pinned SHA1; reinstall it",
+                kind.name()
+            )
+            .into());
+        }
+    }
+    Ok(dir)
+}
+
+/// Whether `version` is a plain folder name, so it can't reach outside
+/// `res/goldberg`.
+pub fn is_version_name(version: &str) -> bool {
+    !version.is_empty()
+        && !version.starts_with('.')
+        && version
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '.' | '-' | '_'))
+}
+
+/// Extra Goldberg builds shipped in `res/goldberg/<version>/`, each laid out
+/// like `res/goldberg` itself. The default build's own `win` and `linux`
+/// folders aren't versions. Listed once, since the settings page asks every
+/// frame and the bundled builds don't change while running.
+pub fn bundled_goldberg_versions() -> Vec<String> {
+    static VERSIONS: LazyLock<Vec<String>> = LazyLock::new(|| {
+        let Ok(entries) = fs::read_dir(PATH_RES.join("goldberg")) else {
+            return Vec::new();
+        };
+        let mut versions: Vec<String> = entries
+            .flatten()
+            .filter(|entry| entry.path().is_dir())
+            .filter_map(|entry| entry.file_name().to_str().map(str::to_string))
+            .filter(|name| name != "win" && name != "linux" && is_version_name(name))
+            .collect();
+        versions.sort();
+        versions
+    });
+    VERSIONS.clone()
+}
+
+/// Folder of the bundled Goldberg build `version`, or the default build for
+/// `None`.
+pub fn bundled_goldberg_dir(version: Option<&str>) -> Result<PathBuf, Box<dyn Error>> {
+    let root = PATH_RES.join("goldberg");
+    let Some(version) = version else {
+        return Ok(root);
+    };
+    if !is_version_name(version) || !root.join(version).is_dir() {
+        let available = bundled_goldberg_versions();
+        let available = match available.is_empty() {
+            true => "none".to_string(),
+            false => available.join(", "),
+        };
+        return Err(format!(
+            "Goldberg build {version} is not in {} (bundled builds: {available})",
+            root.display()
+        )
+        .into());
+    }
+    Ok(root.join(version))
+}
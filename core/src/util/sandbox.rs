@@ -0,0 +1,42 @@
+use std::io;
+use std::path::Path;
+use std::process::{Command, ExitStatus};
+
+use crate::paths::PATH_HOME;
+
+/// Whether bubblewrap can be used to sandbox helper executables.
+pub fn sandbox_available() -> bool {
+    Command::new("bwrap")
+        .arg("--version")
+        .output()
+        .is_ok_and(|output| output.status.success())
+}
+
+/// Runs a helper executable bundled with a handler or the resources inside a
+/// bubblewrap sandbox. The host filesystem is read-only, home folders and the
+/// user runtime directory are hidden, and there is no network. `readable`
+/// paths are shown read-only on top of that, and only `workdir` is writable.
+pub fn run_sandboxed(
+    exe: &Path,
+    args: &[String],
+    workdir: &Path,
+    readable: &[&Path],
+) -> io::Result<ExitStatus> {
+    let mut cmd = Command::new("bwrap");
+    cmd.args(["--die-with-parent", "--new-session", "--unshare-all", "--clearenv"]);
+    cmd.args(["--ro-bind", "/", "/"]);
+    cmd.args(["--dev", "/dev", "--proc", "/proc", "--tmpfs", "/tmp"]);
+    cmd.args(["--tmpfs", "/home", "--tmpfs", "/run/user"]);
+    if !PATH_HOME.starts_with("/home") {
+        cmd.arg("--tmpfs").arg(PATH_HOME.as_path());
+    }
+    for path in readable.iter().chain([&exe]) {
+        cmd.arg("--ro-bind").arg(path).arg(path);
+    }
+    cmd.arg("--bind").arg(workdir).arg(workdir);
+    cmd.arg("--chdir").arg(workdir);
+    cmd.args(["--setenv", "PATH", "/usr/bin:/bin"]);
+    cmd.arg("--setenv").arg("HOME").arg(workdir);
+    cmd.arg("--").arg(exe).args(args);
+    cmd.status()
+}
@@ -0,0 +1,180 @@
+use std::collections::HashSet;
+use std::fs::{self, OpenOptions};
+use std::path::Path;
+use std::str::FromStr;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant, SystemTime};
+
+use evdev::{Device, EventSummary, KeyCode};
+use fs2::FileExt;
+
+use super::gamescope::request_gamescope_screenshot;
+use crate::logging::{log_info, log_warn};
+use crate::session::{SessionCommand, SessionControl};
+
+/// Chord of the screenshot setting by default: Guide and the right bumper,
+/// the buttons Steam takes screenshots with on a controller.
+pub const DEFAULT_SCREENSHOT_CHORD: &str = "BTN_MODE BTN_TR";
+
+/// Where gamescope saves a screenshot requested through its
+/// `GAMESCOPECTRL_REQUEST_SCREENSHOT` property. Unlike its Super+S shortcut,
+/// which names files by date, a requested shot always overwrites this file.
+const GAMESCOPE_SCREENSHOT: &str = "/tmp/gamescope.png";
+
+/// Held while a screenshot is requested and collected, so two players, or
+/// two launchers, shooting at once can't pick up each other's file.
+const SCREENSHOT_LOCK: &str = "/tmp/gamescope.png.split-happens.lock";
+
+/// How long gamescope gets to write a requested screenshot.
+const SCREENSHOT_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// How often the chord listener reads the pads it watches.
+const CHORD_POLL_INTERVAL: Duration = Duration::from_millis(20);
+
+/// Parses a chord such as `BTN_MODE BTN_TR` into its buttons. Names are
+/// evdev key names separated by spaces, commas or `+`. An empty chord turns
+/// screenshots off.
+pub fn parse_screenshot_chord(spec: &str) -> Result<Vec<KeyCode>, String> {
+    let mut keys = Vec::new();
+    for name in spec.split([' ', ',', '+']).filter(|name| !name.is_empty()) {
+        let key = KeyCode::from_str(name).map_err(|_| format!("Unknown button {name}"))?;
+        if !keys.contains(&key) {
+            keys.push(key);
+        }
+    }
+    Ok(keys)
+}
+
+/// Reads each instance's pads during a session, without grabbing them, and
+/// sends a [`SessionCommand::Screenshot`] for the slot whose player pressed
+/// the chord. Stops when dropped.
+pub struct ScreenshotChordListener {
+    stop: Arc<AtomicBool>,
+    handle: Option<JoinHandle<()>>,
+}
+
+impl ScreenshotChordListener {
+    /// Watches the evdev nodes of every slot in `slots`, given as the slot and
+    /// its nodes. Nodes that can't be opened are logged and skipped.
+    pub fn start(
+        chord: Vec<KeyCode>,
+        slots: &[(usize, Vec<String>)],
+        session: SessionControl,
+    ) -> ScreenshotChordListener {
+        let mut pads: Vec<(usize, Device, HashSet<KeyCode>)> = Vec::new();
+        for (slot, paths) in slots {
+            for path in paths {
+                match Device::open(path).and_then(|device| {
+                    device.set_nonblocking(true)?;
+                    Ok(device)
+                }) {
+                    Ok(device) => pads.push((*slot, device, HashSet::new())),
+                    Err(err) => log_warn(format!("Screenshot chord can't read {path}: {err}")),
+                }
+            }
+        }
+
+        let stop = Arc::new(AtomicBool::new(false));
+        let thread_stop = Arc::clone(&stop);
+        let handle = std::thread::spawn(move || {
+            while !thread_stop.load(Ordering::Relaxed) {
+                for (slot, device, held) in pads.iter_mut() {
+                    let Ok(events) = device.fetch_events() else {
+                        continue;
+                    };
+                    let summaries: Vec<EventSummary> =
+                        events.map(|event| event.destructure()).collect();
+                    for summary in summaries {
+                        let EventSummary::Key(_, key, value) = summary else {
+                            continue;
+                        };
+                        match value {
+                            0 => {
+                                held.remove(&key);
+                            }
+                            1 => {
+                                held.insert(key);
+                                // Fires once, on the press that completes the chord.
+                                if chord.contains(&key) && chord.iter().all(|k| held.contains(k)) {
+                                    session.send(SessionCommand::Screenshot { slot: *slot });
+                                }
+                            }
+                            _ => {}
+                        }
+                    }
+                }
+                std::thread::sleep(CHORD_POLL_INTERVAL);
+            }
+        });
+
+        ScreenshotChordListener {
+            stop,
+            handle: Some(handle),
+        }
+    }
+}
+
+impl Drop for ScreenshotChordListener {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::Relaxed);
+        if let Some(handle) = self.handle.take() {
+            let _ = handle.join();
+        }
+    }
+}
+
+/// Modification time of gamescope's screenshot file, if there is one.
+fn screenshot_mtime() -> Option<SystemTime> {
+    fs::metadata(GAMESCOPE_SCREENSHOT).and_then(|meta| meta.modified()).ok()
+}
+
+/// Has the gamescope instance under `pid` take a screenshot of its pane only
+/// and copies it to `target`. Blocks until gamescope wrote the file.
+///
+/// Other launchers wait on the same lock file, but a screenshot Steam asks a
+/// gamescope for at the same moment still lands in the same file.
+pub fn save_instance_screenshot(pid: u32, target: &Path) -> Result<(), String> {
+    let lock = OpenOptions::new()
+        .create(true)
+        .truncate(false)
+        .write(true)
+        .open(SCREENSHOT_LOCK)
+        .map_err(|err| format!("Couldn't open {SCREENSHOT_LOCK}: {err}"))?;
+    lock.lock_exclusive()
+        .map_err(|err| format!("Couldn't lock {SCREENSHOT_LOCK}: {err}"))?;
+    let before = screenshot_mtime();
+    request_gamescope_screenshot(pid)?;
+
+    let deadline = Instant::now() + SCREENSHOT_TIMEOUT;
+    let mut size = loop {
+        let written = screenshot_mtime().is_some_and(|mtime| Some(mtime) != before);
+        if written {
+            break None;
+        }
+        if Instant::now() >= deadline {
+            return Err(format!(
+                "gamescope didn't write {GAMESCOPE_SCREENSHOT} within {} seconds",
+                SCREENSHOT_TIMEOUT.as_secs()
+            ));
+        }
+        std::thread::sleep(Duration::from_millis(100));
+    };
+    // Gamescope encodes the file in the background; wait for it to stop growing.
+    while Instant::now() < deadline {
+        std::thread::sleep(Duration::from_millis(200));
+        let current = fs::metadata(GAMESCOPE_SCREENSHOT).map(|meta| meta.len()).ok();
+        if current.is_some_and(|len| len > 0) && current == size {
+            break;
+        }
+        size = current;
+    }
+
+    // /tmp is often a different filesystem, so copy rather than rename.
+    fs::copy(GAMESCOPE_SCREENSHOT, target).map_err(|err| err.to_string())?;
+    log_info(format!("Saved screenshot {}", target.display()));
+    // The lock is released when the file closes.
+    drop(lock);
+    Ok(())
+}
@@ -0,0 +1,205 @@
+use std::error::Error;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::logging::log_info;
+use crate::paths::{PATH_APP, PATH_STEAM};
+
+use super::hash::sha1_file;
+
+/// Difference between a SteamID64 and the 32-bit account id Steam names
+/// `userdata` folders after.
+const STEAMID64_BASE: u64 = 76561197960265728;
+
+/// A Steam account that has signed in on this machine.
+#[derive(Clone)]
+pub struct SteamAccount {
+    pub account_id: u32,
+    /// Persona name from `loginusers.vdf`, else the account id.
+    pub name: String,
+}
+
+/// Persona names from Steam's `config/loginusers.vdf`, keyed by account id.
+/// The file is text KeyValues; only the SteamID blocks and their
+/// `PersonaName` are read.
+fn persona_names() -> Vec<(u32, String)> {
+    let text = fs::read_to_string(PATH_STEAM.join("config/loginusers.vdf")).unwrap_or_default();
+    let mut names = Vec::new();
+    let mut current: Option<u32> = None;
+    for line in text.lines() {
+        // Quoted tokens sit at odd positions once the line is split at quotes.
+        let parts: Vec<&str> = line.split('"').skip(1).step_by(2).collect();
+        match parts.as_slice() {
+            [id] => {
+                if let Ok(steam_id) = id.parse::<u64>() {
+                    current = steam_id
+                        .checked_sub(STEAMID64_BASE)
+                        .and_then(|id| u32::try_from(id).ok());
+                }
+            }
+            [key, value] if key.eq_ignore_ascii_case("PersonaName") => {
+                if let Some(account_id) = current {
+                    names.push((account_id, value.to_string()));
+                }
+            }
+            _ => {}
+        }
+    }
+    names
+}
+
+/// Steam accounts with a `userdata` folder, i.e. every account that has
+/// played on this machine.
+pub fn steam_accounts() -> Vec<SteamAccount> {
+    let names = persona_names();
+    let Ok(entries) = fs::read_dir(PATH_STEAM.join("userdata")) else {
+        return Vec::new();
+    };
+    let mut accounts: Vec<SteamAccount> = entries
+        .flatten()
+        .filter_map(|entry| entry.file_name().to_str()?.parse::<u32>().ok())
+        .filter(|&account_id| account_id != 0)
+        .map(|account_id| SteamAccount {
+            account_id,
+            name: names
+                .iter()
+                .find(|(id, _)| *id == account_id)
+                .map(|(_, name)| name.clone())
+                .unwrap_or_else(|| account_id.to_string()),
+        })
+        .collect();
+    accounts.sort_by(|a, b| a.name.cmp(&b.name));
+    accounts
+}
+
+/// What exporting does with one save file.
+#[derive(Clone, Copy, PartialEq)]
+pub enum SaveExportAction {
+    /// Steam has no such file yet.
+    New,
+    /// Steam's copy differs and is replaced; the old one is backed up first.
+    Replace,
+    /// Steam already has the same file.
+    Unchanged,
+}
+
+impl SaveExportAction {
+    pub fn label(&self) -> &'static str {
+        match self {
+            SaveExportAction::New => "new",
+            SaveExportAction::Replace => "replace",
+            SaveExportAction::Unchanged => "unchanged",
+        }
+    }
+}
+
+pub struct SaveExportEntry {
+    /// Path below the `remote` folder.
+    pub path: PathBuf,
+    pub action: SaveExportAction,
+    pub size: u64,
+}
+
+/// Files a Goldberg save export would copy, computed without touching
+/// Steam's folder so it can be shown before anything is written.
+pub struct SaveExportPlan {
+    /// Goldberg's `remote` folder in the profile.
+    pub source: PathBuf,
+    /// `userdata/<account>/<appid>/remote` of the Steam account.
+    pub target: PathBuf,
+    pub entries: Vec<SaveExportEntry>,
+}
+
+impl SaveExportPlan {
+    /// Entries the export would write.
+    pub fn changes(&self) -> impl Iterator<Item = &SaveExportEntry> {
+        self.entries
+            .iter()
+            .filter(|entry| entry.action != SaveExportAction::Unchanged)
+    }
+}
+
+/// Compares the Goldberg saves of `profile` for `appid` with the Steam
+/// Cloud folder of `account_id`. Goldberg keeps a game's cloud files in
+/// `<appid>/remote` of the profile's save folder, the same layout the Steam
+/// client uses under `userdata/<account>`.
+pub fn plan_goldberg_save_export(
+    profile: &str,
+    appid: &str,
+    account_id: u32,
+) -> Result<SaveExportPlan, Box<dyn Error>> {
+    if appid.is_empty() || !appid.chars().all(|c| c.is_ascii_digit()) {
+        return Err("The handler has no numeric Steam App ID".into());
+    }
+    let source = PATH_APP
+        .join("profiles")
+        .join(profile)
+        .join("steam")
+        .join(appid)
+        .join("remote");
+    if !source.is_dir() {
+        return Err(format!("{profile} has no Goldberg saves for app {appid}").into());
+    }
+    let target = PATH_STEAM
+        .join("userdata")
+        .join(account_id.to_string())
+        .join(appid)
+        .join("remote");
+
+    let mut entries = Vec::new();
+    for entry in walkdir::WalkDir::new(&source).min_depth(1) {
+        let entry = entry?;
+        if !entry.file_type().is_file() {
+            continue;
+        }
+        let path = entry.path().strip_prefix(&source)?.to_path_buf();
+        let existing = target.join(&path);
+        let action = if !existing.is_file() {
+            SaveExportAction::New
+        } else if sha1_file(entry.path())? == sha1_file(&existing)? {
+            SaveExportAction::Unchanged
+        } else {
+            SaveExportAction::Replace
+        };
+        entries.push(SaveExportEntry {
+            path,
+            action,
+            size: entry.metadata()?.len(),
+        });
+    }
+    entries.sort_by(|a, b| a.path.cmp(&b.path));
+    Ok(SaveExportPlan {
+        source,
+        target,
+        entries,
+    })
+}
+
+/// Copies the changes of `plan` into Steam's folder. Files it replaces are
+/// first backed up under `backups/steam_cloud` in the data folder. Steam
+/// uploads the new files the next time the game is started from Steam.
+/// Returns how many files were written.
+pub fn apply_save_export(plan: &SaveExportPlan) -> Result<usize, Box<dyn Error>> {
+    let stamp = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs();
+    let backup = PATH_APP.join("backups/steam_cloud").join(stamp.to_string());
+    let mut written = 0;
+    for entry in plan.changes() {
+        let dest = plan.target.join(&entry.path);
+        if entry.action == SaveExportAction::Replace {
+            copy_file(&dest, &backup.join(&entry.path))?;
+        }
+        copy_file(&plan.source.join(&entry.path), &dest)?;
+        written += 1;
+    }
+    log_info(format!("Exported {written} Goldberg save files to {}", plan.target.display()));
+    Ok(written)
+}
+
+fn copy_file(src: &Path, dest: &Path) -> Result<(), Box<dyn Error>> {
+    if let Some(parent) = dest.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::copy(src, dest)?;
+    Ok(())
+}
@@ -0,0 +1,146 @@
+use std::error::Error;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::logging::log_warn;
+use crate::paths::PATH_APP;
+
+use super::filesystem::write_atomic;
+use super::net::curl_command;
+use super::profiles::ensure_ini_setting;
+
+const APPDETAILS_API: &str = "https://store.steampowered.com/api/appdetails";
+const APPDETAILS_TIMEOUT_SECS: &str = "15";
+/// How long a fetched DLC list is trusted before Steam is asked again.
+const DLC_CACHE_MAX_AGE: Duration = Duration::from_secs(7 * 24 * 60 * 60);
+
+/// One DLC of a Steam app.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct SteamDlc {
+    pub id: u64,
+    pub name: String,
+}
+
+/// DLC list of one app as cached under `PATH_APP/steam_dlc`.
+#[derive(Serialize, Deserialize)]
+struct DlcCache {
+    /// Seconds since the Unix epoch.
+    fetched_at: u64,
+    dlcs: Vec<SteamDlc>,
+}
+
+fn cache_path(appid: &str) -> PathBuf {
+    PATH_APP.join("steam_dlc").join(format!("{appid}.json"))
+}
+
+fn now_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |elapsed| elapsed.as_secs())
+}
+
+fn load_cache(appid: &str) -> Option<DlcCache> {
+    serde_json::from_str(&fs::read_to_string(cache_path(appid)).ok()?).ok()
+}
+
+/// Store details of `appid`.
+fn appdetails(appid: u64) -> Result<Value, Box<dyn Error>> {
+    let url = format!("{APPDETAILS_API}?appids={appid}");
+    let output = curl_command()?
+        .args(["-sSfL", "--max-time", APPDETAILS_TIMEOUT_SECS])
+        .arg(&url)
+        .output()?;
+    if !output.status.success() {
+        return Err(format!(
+            "Steam store request failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        )
+        .into());
+    }
+    let mut json: Value = serde_json::from_slice(&output.stdout)?;
+    let app = &mut json[appid.to_string()];
+    if app["success"] != Value::Bool(true) {
+        return Err(format!("The Steam store has no details for app {appid}").into());
+    }
+    Ok(app["data"].take())
+}
+
+/// Asks the Steam store for the DLCs of `appid`. That is one request on the
+/// launch path; Goldberg accepts any name, so each DLC is named by its id
+/// rather than looked up.
+fn fetch_dlc_list(appid: u64) -> Result<Vec<SteamDlc>, Box<dyn Error>> {
+    let details = appdetails(appid)?;
+    let dlcs = details["dlc"]
+        .as_array()
+        .map(|dlcs| {
+            dlcs.iter()
+                .filter_map(Value::as_u64)
+                .map(|id| SteamDlc {
+                    id,
+                    name: format!("DLC {id}"),
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+    Ok(dlcs)
+}
+
+/// DLCs of the Steam app `appid`, from the cache while it is fresh and from
+/// the Steam store otherwise. A stale cache still answers when the store
+/// can't be reached, e.g. in offline mode.
+pub fn steam_dlc_list(appid: &str) -> Result<Vec<SteamDlc>, Box<dyn Error>> {
+    let cached = load_cache(appid);
+    if let Some(cache) = &cached {
+        if now_secs().saturating_sub(cache.fetched_at) < DLC_CACHE_MAX_AGE.as_secs() {
+            return Ok(cache.dlcs.clone());
+        }
+    }
+
+    let numeric: u64 = appid
+        .parse()
+        .map_err(|_| format!("{appid} isn't a Steam app id"))?;
+    let dlcs = match fetch_dlc_list(numeric) {
+        Ok(dlcs) => dlcs,
+        Err(err) => match cached {
+            Some(cache) => {
+                log_warn(format!("Using the cached DLC list of {appid}: {err}"));
+                return Ok(cache.dlcs);
+            }
+            None => return Err(err),
+        },
+    };
+
+    let cache = DlcCache {
+        fetched_at: now_secs(),
+        dlcs,
+    };
+    fs::create_dir_all(PATH_APP.join("steam_dlc"))?;
+    write_atomic(&cache_path(appid), serde_json::to_string_pretty(&cache)?)?;
+    Ok(cache.dlcs)
+}
+
+/// Lists `dlcs` in a Goldberg `steam_settings` folder, both as the legacy
+/// `DLC.txt` and in the `[app::dlcs]` section of `configs.app.ini`, so only
+/// those DLCs report as owned.
+pub fn write_goldberg_dlc_list(
+    steam_settings: &Path,
+    dlcs: &[SteamDlc],
+) -> Result<(), Box<dyn Error>> {
+    fs::create_dir_all(steam_settings)?;
+    let legacy: String = dlcs
+        .iter()
+        .map(|dlc| format!("{}={}\n", dlc.id, dlc.name))
+        .collect();
+    fs::write(steam_settings.join("DLC.txt"), legacy)?;
+
+    let app_ini = steam_settings.join("configs.app.ini");
+    ensure_ini_setting(&app_ini, "[app::dlcs]", "unlock_all", "0")?;
+    for dlc in dlcs {
+        ensure_ini_setting(&app_ini, "[app::dlcs]", &dlc.id.to_string(), &dlc.name)?;
+    }
+    Ok(())
+}
@@ -0,0 +1,294 @@
+use std::error::Error;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::logging::log_info;
+use crate::paths::PATH_STEAM;
+
+use super::updates::update_target;
+
+/// A value of Steam's binary KeyValues format, as used by `shortcuts.vdf`.
+#[derive(Clone, Debug, PartialEq)]
+enum VdfValue {
+    Map(Vec<(String, VdfValue)>),
+    Str(String),
+    Int(u32),
+    Float(f32),
+    UInt64(u64),
+}
+
+const VDF_MAP: u8 = 0x00;
+const VDF_STR: u8 = 0x01;
+const VDF_INT: u8 = 0x02;
+const VDF_FLOAT: u8 = 0x03;
+const VDF_UINT64: u8 = 0x07;
+const VDF_END: u8 = 0x08;
+
+/// Reads binary KeyValues from a byte slice.
+struct VdfReader<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> VdfReader<'a> {
+    fn new(bytes: &'a [u8]) -> Self {
+        VdfReader { bytes, pos: 0 }
+    }
+
+    fn take(&mut self, len: usize) -> Result<&[u8], Box<dyn Error>> {
+        let end = self.pos + len;
+        let slice = self.bytes.get(self.pos..end).ok_or("shortcuts.vdf ends early")?;
+        self.pos = end;
+        Ok(slice)
+    }
+
+    fn string(&mut self) -> Result<String, Box<dyn Error>> {
+        let rest = &self.bytes[self.pos..];
+        let len = rest.iter().position(|&b| b == 0).ok_or("Unterminated string")?;
+        let text = String::from_utf8_lossy(&rest[..len]).to_string();
+        self.pos += len + 1;
+        Ok(text)
+    }
+
+    /// Entries up to the map's end marker.
+    fn map(&mut self) -> Result<Vec<(String, VdfValue)>, Box<dyn Error>> {
+        let mut entries = Vec::new();
+        loop {
+            let kind = self.take(1)?[0];
+            if kind == VDF_END {
+                return Ok(entries);
+            }
+            let key = self.string()?;
+            let value = match kind {
+                VDF_MAP => VdfValue::Map(self.map()?),
+                VDF_STR => VdfValue::Str(self.string()?),
+                VDF_INT => VdfValue::Int(u32::from_le_bytes(self.take(4)?.try_into()?)),
+                VDF_FLOAT => VdfValue::Float(f32::from_le_bytes(self.take(4)?.try_into()?)),
+                VDF_UINT64 => VdfValue::UInt64(u64::from_le_bytes(self.take(8)?.try_into()?)),
+                other => {
+                    return Err(format!("Unknown value type {other:#x} in shortcuts.vdf").into());
+                }
+            };
+            entries.push((key, value));
+        }
+    }
+}
+
+fn write_vdf_map(out: &mut Vec<u8>, entries: &[(String, VdfValue)]) {
+    for (key, value) in entries {
+        let kind = match value {
+            VdfValue::Map(_) => VDF_MAP,
+            VdfValue::Str(_) => VDF_STR,
+            VdfValue::Int(_) => VDF_INT,
+            VdfValue::Float(_) => VDF_FLOAT,
+            VdfValue::UInt64(_) => VDF_UINT64,
+        };
+        out.push(kind);
+        out.extend_from_slice(key.as_bytes());
+        out.push(0);
+        match value {
+            VdfValue::Map(inner) => write_vdf_map(out, inner),
+            VdfValue::Str(text) => {
+                out.extend_from_slice(text.as_bytes());
+                out.push(0);
+            }
+            VdfValue::Int(number) => out.extend_from_slice(&number.to_le_bytes()),
+            VdfValue::Float(number) => out.extend_from_slice(&number.to_le_bytes()),
+            VdfValue::UInt64(number) => out.extend_from_slice(&number.to_le_bytes()),
+        }
+    }
+    out.push(VDF_END);
+}
+
+/// CRC-32 (IEEE), which Steam derives non-Steam shortcut ids from.
+fn crc32(bytes: &[u8]) -> u32 {
+    let mut crc = !0u32;
+    for &byte in bytes {
+        crc ^= byte as u32;
+        for _ in 0..8 {
+            crc = if crc & 1 == 1 { (crc >> 1) ^ 0xEDB8_8320 } else { crc >> 1 };
+        }
+    }
+    !crc
+}
+
+/// Artwork copied into Steam's grid folder for a shortcut; missing kinds
+/// keep Steam's generated placeholders.
+#[derive(Default)]
+pub struct ShortcutArtwork {
+    /// Wide capsule shown in the library and on the Deck's home row.
+    pub grid: Option<PathBuf>,
+    pub hero: Option<PathBuf>,
+    pub logo: Option<PathBuf>,
+    pub icon: Option<PathBuf>,
+}
+
+/// A non-Steam game entry that starts Split Happens with `launch_options`.
+pub struct SteamShortcut {
+    pub name: String,
+    pub launch_options: String,
+    pub artwork: ShortcutArtwork,
+}
+
+impl SteamShortcut {
+    /// Shortcut running the handler `uid` headlessly.
+    pub fn for_handler(name: &str, uid: &str, artwork: ShortcutArtwork) -> Self {
+        SteamShortcut {
+            name: name.to_string(),
+            launch_options: format!("--handler {uid}"),
+            artwork,
+        }
+    }
+}
+
+/// `userdata/<account>/config` folders of every Steam account on this
+/// machine; shortcuts are stored per account.
+fn steam_user_configs() -> Vec<PathBuf> {
+    let Ok(entries) = fs::read_dir(PATH_STEAM.join("userdata")) else {
+        return Vec::new();
+    };
+    entries
+        .flatten()
+        .filter(|entry| entry.file_name().to_string_lossy().chars().all(|c| c.is_ascii_digit()))
+        .filter(|entry| entry.file_name() != "0")
+        .map(|entry| entry.path().join("config"))
+        .filter(|config| config.is_dir())
+        .collect()
+}
+
+/// Whether the Steam client is running. It rewrites `shortcuts.vdf` from
+/// memory, so shortcuts added meanwhile only show up after a restart.
+pub fn steam_is_running() -> bool {
+    let Ok(entries) = fs::read_dir("/proc") else {
+        return false;
+    };
+    entries.flatten().any(|entry| {
+        fs::read_to_string(entry.path().join("comm")).is_ok_and(|comm| comm.trim() == "steam")
+    })
+}
+
+/// Copies `src` to `grid/<name>.<ext of src>`, dropping other extensions of
+/// the same name so Steam doesn't pick a stale one.
+fn copy_grid_image(grid: &Path, src: &Path, name: &str) -> Result<PathBuf, Box<dyn Error>> {
+    let ext = src.extension().and_then(|ext| ext.to_str()).unwrap_or("png");
+    for stale in ["png", "jpg"] {
+        let _ = fs::remove_file(grid.join(format!("{name}.{stale}")));
+    }
+    let dest = grid.join(format!("{name}.{ext}"));
+    fs::copy(src, &dest)?;
+    Ok(dest)
+}
+
+/// Adds `shortcut` to one account's `shortcuts.vdf`, replacing an earlier
+/// entry with the same launch options, and copies its artwork.
+fn write_shortcut(
+    config: &Path,
+    shortcut: &SteamShortcut,
+    exe: &Path,
+) -> Result<(), Box<dyn Error>> {
+    let vdf_path = config.join("shortcuts.vdf");
+    let mut root = match fs::read(&vdf_path) {
+        Ok(bytes) if !bytes.is_empty() => VdfReader::new(&bytes).map()?,
+        _ => vec![("shortcuts".to_string(), VdfValue::Map(Vec::new()))],
+    };
+    let Some((_, VdfValue::Map(shortcuts))) = root.iter_mut().find(|(key, _)| key == "shortcuts")
+    else {
+        return Err(format!("{} has no shortcuts list", vdf_path.display()).into());
+    };
+
+    // Steam ids shortcuts by the CRC of the quoted executable and the name.
+    let exe_quoted = format!("\"{}\"", exe.display());
+    let start_dir = format!("\"{}\"", exe.parent().unwrap_or(Path::new("/")).display());
+    let appid = crc32(format!("{exe_quoted}{}", shortcut.name).as_bytes()) | 0x8000_0000;
+
+    let grid = config.join("grid");
+    fs::create_dir_all(&grid)?;
+    let images = [
+        (&shortcut.artwork.grid, format!("{appid}")),
+        (&shortcut.artwork.hero, format!("{appid}_hero")),
+        (&shortcut.artwork.logo, format!("{appid}_logo")),
+    ];
+    for (src, name) in images {
+        if let Some(src) = src {
+            copy_grid_image(&grid, src, &name)?;
+        }
+    }
+    // The icon is referenced by path from the entry itself.
+    let icon = match &shortcut.artwork.icon {
+        Some(src) => copy_grid_image(&grid, src, &format!("{appid}_icon"))?
+            .to_string_lossy()
+            .to_string(),
+        None => String::new(),
+    };
+
+    let text = |value: &str| VdfValue::Str(value.to_string());
+    let entry = vec![
+        ("appid".to_string(), VdfValue::Int(appid)),
+        ("AppName".to_string(), text(&shortcut.name)),
+        ("Exe".to_string(), text(&exe_quoted)),
+        ("StartDir".to_string(), text(&start_dir)),
+        ("icon".to_string(), text(&icon)),
+        ("ShortcutPath".to_string(), text("")),
+        ("LaunchOptions".to_string(), text(&shortcut.launch_options)),
+        ("IsHidden".to_string(), VdfValue::Int(0)),
+        ("AllowDesktopConfig".to_string(), VdfValue::Int(1)),
+        ("AllowOverlay".to_string(), VdfValue::Int(1)),
+        ("OpenVR".to_string(), VdfValue::Int(0)),
+        ("Devkit".to_string(), VdfValue::Int(0)),
+        ("DevkitGameID".to_string(), text("")),
+        ("DevkitOverrideAppID".to_string(), VdfValue::Int(0)),
+        ("LastPlayTime".to_string(), VdfValue::Int(0)),
+        ("FlatpakAppID".to_string(), text("")),
+        ("tags".to_string(), VdfValue::Map(Vec::new())),
+    ];
+
+    // Re-exporting a game replaces its entry instead of adding a duplicate.
+    let same_launch = VdfValue::Str(shortcut.launch_options.clone());
+    let existing = shortcuts.iter().position(|(_, value)| match value {
+        VdfValue::Map(fields) => fields.iter().any(|(key, value)| {
+            key.eq_ignore_ascii_case("LaunchOptions") && *value == same_launch
+        }),
+        _ => false,
+    });
+    match existing {
+        Some(index) => shortcuts[index].1 = VdfValue::Map(entry),
+        None => {
+            // Entries are keyed by their position.
+            let key = shortcuts.len().to_string();
+            shortcuts.push((key, VdfValue::Map(entry)));
+        }
+    }
+
+    let mut out = Vec::new();
+    write_vdf_map(&mut out, &root);
+    // Written aside and renamed so Steam never reads a half-written file.
+    let tmp = config.join("shortcuts.vdf.split-happens");
+    fs::write(&tmp, out)?;
+    fs::rename(&tmp, &vdf_path)?;
+    Ok(())
+}
+
+/// Adds `shortcut` as a non-Steam game to every Steam account on this
+/// machine, launching this Split Happens installation. Returns how many
+/// accounts got it.
+pub fn add_steam_shortcut(shortcut: &SteamShortcut) -> Result<usize, Box<dyn Error>> {
+    let exe = fs::canonicalize(update_target()?)?;
+    let configs = steam_user_configs();
+    if configs.is_empty() {
+        return Err(format!(
+            "No Steam account found under {}; log in to Steam once first.",
+            PATH_STEAM.join("userdata").display()
+        )
+        .into());
+    }
+    for config in &configs {
+        write_shortcut(config, shortcut, &exe)?;
+    }
+    log_info(format!(
+        "Added Steam shortcut {} ({}) for {} account(s)",
+        shortcut.name,
+        shortcut.launch_options,
+        configs.len()
+    ));
+    Ok(configs.len())
+}
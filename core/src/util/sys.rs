@@ -0,0 +1,227 @@
+use std::error::Error;
+use std::ops::Deref;
+use std::path::PathBuf;
+
+use crate::logging::{log_info, log_warn};
+use crate::paths::*;
+
+use x11rb::connection::Connection;
+use zbus::Error as ZbusError;
+use zbus::zvariant::{OwnedValue, Value};
+
+use super::steamdeck::is_steam_deck;
+
+/// A KWin script loaded by [`kwin_dbus_start_script`]. Each session loads its
+/// own under a distinct plugin name, so concurrent sessions can unload theirs
+/// without touching the others'.
+pub struct KwinScript {
+    /// Plugin name the script was registered under.
+    pub name: String,
+    /// Raw identifier returned by KWin when loading the script. Some platforms
+    /// report a string name, others an integer handle.
+    id: OwnedValue,
+}
+
+/// Formats the dynamically typed DBus identifier into a human readable label so
+/// launch logs stay understandable even when KWin reports numeric handles.
+fn describe_kwin_id(id: &OwnedValue) -> String {
+    match id.deref() {
+        Value::Str(text) => text.to_string(),
+        Value::I32(num) => num.to_string(),
+        Value::I64(num) => num.to_string(),
+        Value::U32(num) => num.to_string(),
+        Value::U64(num) => num.to_string(),
+        other => format!("{other:?}"),
+    }
+}
+
+/// Detects when KWin refuses a DBus call because the argument signature
+/// mismatched its expectations so we can fall back to a string-based API.
+fn kwin_signature_mismatch(err: &ZbusError) -> bool {
+    err.to_string().contains("Signature mismatch")
+}
+
+/// Shows a desktop notification through notify-send without waiting for it,
+/// so the game in front keeps running. Missing notify-send is ignored.
+pub fn notify_desktop(title: &str, contents: &str) {
+    let spawned = std::process::Command::new("notify-send")
+        .args(["--app-name=Split Happens", title, contents])
+        .spawn();
+    // Reap it in the background so no zombie is left behind.
+    if let Ok(mut child) = spawned {
+        std::thread::spawn(move || child.wait());
+    }
+}
+
+pub fn get_screen_resolution() -> (u32, u32) {
+    if let Ok(conn) = x11rb::connect(None) {
+        let screen = &conn.0.setup().roots[0];
+        log_info(format!(
+            "Got screen resolution: {}x{}",
+            screen.width_in_pixels, screen.height_in_pixels
+        ));
+        return (
+            screen.width_in_pixels as u32,
+            screen.height_in_pixels as u32,
+        );
+    }
+    // Fallback to a common resolution if detection fails
+    log_warn("Failed to detect screen resolution, using Steam Deck friendly fallback");
+    if is_steam_deck() {
+        (1280, 800)
+    } else {
+        (1920, 1080)
+    }
+}
+
+// Sends a splitscreen script to the active KWin session through DBus under the
+// plugin name `name`
+pub fn kwin_dbus_start_script(file: PathBuf, name: &str) -> Result<KwinScript, Box<dyn Error>> {
+    log_info(format!("Loading script {}...", file.display()));
+    if !file.exists() {
+        return Err("Script file doesn't exist!".into());
+    }
+
+    let conn = zbus::blocking::Connection::session()?;
+    let proxy = zbus::blocking::Proxy::new(
+        &conn,
+        "org.kde.KWin",
+        "/Scripting",
+        "org.kde.kwin.Scripting",
+    )?;
+
+    // Ask KWin to load the script and capture the concrete runtime identifier so
+    // we can start and later unload the exact instance that was registered.
+    // Plasma sometimes returns the identifier as an integer instead of wrapping
+    // it in a variant, so we deserialize the reply dynamically to preserve the
+    // raw type and avoid signature mismatch errors on newer releases.
+    let script_reply = proxy.call_method(
+        "loadScript",
+        &(file.to_string_lossy().into_owned(), name),
+    )?;
+    let script_id: OwnedValue = script_reply
+        .body()
+        .deserialize()
+        .map_err(|err| Box::new(err) as Box<dyn Error>)?;
+    log_info(format!(
+        "Script loaded as id {}. Starting...",
+        describe_kwin_id(&script_id)
+    ));
+
+    // Launch the freshly registered script so all future game windows are
+    // immediately snapped into their target positions, regardless of the
+    // identifier type reported by the compositor.
+    // Prefer the identifier returned by KWin so we stop the exact runtime instance,
+    // but gracefully fall back to the legacy string-based API when the compositor
+    // rejects numeric handles on newer Plasma builds.
+    if let Err(err) = proxy.call::<_, _, ()>("start", &(script_id.clone(),)) {
+        if kwin_signature_mismatch(&err) {
+            log_info(format!(
+                "KWin rejected script id {}; retrying with string fallback...",
+                describe_kwin_id(&script_id)
+            ));
+            proxy.call::<_, _, ()>("start", &(name,))?;
+        } else {
+            return Err(Box::new(err));
+        }
+    }
+
+    log_info("KWin script started.");
+    Ok(KwinScript {
+        name: name.to_string(),
+        id: script_id,
+    })
+}
+
+pub fn kwin_dbus_unload_script(script: KwinScript) -> Result<(), Box<dyn Error>> {
+    log_info(format!("Unloading splitscreen script {}...", script.name));
+    let conn = zbus::blocking::Connection::session()?;
+    let proxy = zbus::blocking::Proxy::new(
+        &conn,
+        "org.kde.KWin",
+        "/Scripting",
+        "org.kde.kwin.Scripting",
+    )?;
+
+    // Attempt to unload by identifier first and gracefully fall back to the
+    // string API when the compositor expects a name-only signature.
+    let label = describe_kwin_id(&script.id);
+    if let Err(err) = proxy.call::<_, _, bool>("unloadScript", &(script.id,)) {
+        if kwin_signature_mismatch(&err) {
+            log_info(format!(
+                "KWin rejected script id {}; unloading via name fallback...",
+                label
+            ));
+            proxy.call::<_, _, bool>("unloadScript", &(script.name.as_str(),))?;
+        } else {
+            return Err(Box::new(err));
+        }
+    }
+
+    log_info("Script unloaded.");
+    Ok(())
+}
+
+/// Generates the border overlay from its QML template with the given player
+/// colors and loads it as a declarative KWin script named `name`, so the
+/// compositor draws a colored frame around each gamescope window of
+/// `owned_pids`, the session's instance PIDs.
+pub fn kwin_dbus_start_border_script(
+    colors: &[String],
+    border_width: u32,
+    name: &str,
+    owned_pids: &[u32],
+) -> Result<(), Box<dyn Error>> {
+    let template = std::fs::read_to_string(PATH_RES.join("splitscreen_borders.qml"))?;
+    let color_list: Vec<String> = colors.iter().map(|color| format!("\"{color}\"")).collect();
+    let script = template
+        .replace("__PLAYER_COLORS__", &format!("[{}]", color_list.join(", ")))
+        .replace("__BORDER_WIDTH__", &border_width.to_string())
+        .replace("__OWN_PIDS__", &serde_json::to_string(owned_pids)?);
+
+    let script_dir = PATH_APP.join("kwin");
+    std::fs::create_dir_all(&script_dir)?;
+    let script_path = script_dir.join(format!("{name}.qml"));
+    std::fs::write(&script_path, script)?;
+
+    log_info(format!("Loading border script {}...", script_path.display()));
+    let conn = zbus::blocking::Connection::session()?;
+    let proxy = zbus::blocking::Proxy::new(
+        &conn,
+        "org.kde.KWin",
+        "/Scripting",
+        "org.kde.kwin.Scripting",
+    )?;
+
+    // Drop a stale overlay from a previous session that did not shut down cleanly.
+    let _ = proxy.call::<_, _, bool>("unloadScript", &(name,));
+
+    // Declarative scripts start as soon as KWin's script engine runs them, so
+    // trigger the scripting runtime without tracking a separate identifier.
+    let _: OwnedValue = proxy
+        .call_method(
+            "loadDeclarativeScript",
+            &(script_path.to_string_lossy().into_owned(), name),
+        )?
+        .body()
+        .deserialize()
+        .map_err(|err| Box::new(err) as Box<dyn Error>)?;
+    proxy.call::<_, _, ()>("start", &())?;
+
+    log_info("KWin border script started.");
+    Ok(())
+}
+
+/// Removes the per-player border overlay loaded by [`kwin_dbus_start_border_script`].
+pub fn kwin_dbus_unload_border_script(name: &str) -> Result<(), Box<dyn Error>> {
+    let conn = zbus::blocking::Connection::session()?;
+    let proxy = zbus::blocking::Proxy::new(
+        &conn,
+        "org.kde.KWin",
+        "/Scripting",
+        "org.kde.kwin.Scripting",
+    )?;
+    let _: bool = proxy.call("unloadScript", &(name,))?;
+    log_info("Border script unloaded.");
+    Ok(())
+}
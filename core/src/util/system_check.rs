@@ -0,0 +1,319 @@
+use std::error::Error;
+use std::fs;
+use std::process::{Command, Stdio};
+
+use super::proton::{discover_proton_versions, resolve_proton_environment};
+use crate::config::PartyConfig;
+use crate::paths::{BIN_GSC_KBM, BIN_UMU_RUN, PATH_STEAM};
+
+/// Steam app of the Steam Linux Runtime 2.0 (soldier) container.
+const SOLDIER_APPID: u32 = 1391110;
+/// Steam app of Proton Experimental.
+const PROTON_EXPERIMENTAL_APPID: u32 = 1493710;
+
+/// Something Split Happens can set up itself for a failed check.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum SystemFix {
+    /// Ask Steam to install the app with this id.
+    SteamInstall(u32),
+    /// Add the user to the `input` group through pkexec.
+    JoinInputGroup,
+}
+
+impl SystemFix {
+    pub fn label(&self) -> &'static str {
+        match self {
+            SystemFix::SteamInstall(_) => "Install with Steam",
+            SystemFix::JoinInputGroup => "Join input group",
+        }
+    }
+
+    /// Starts the fix. Steam installs continue in the Steam client; group
+    /// changes only apply after logging in again.
+    pub fn apply(&self) -> Result<(), Box<dyn Error>> {
+        let status = match self {
+            SystemFix::SteamInstall(appid) => Command::new("xdg-open")
+                .arg(format!("steam://install/{appid}"))
+                .status()?,
+            SystemFix::JoinInputGroup => Command::new("pkexec")
+                .args(["usermod", "-aG", "input", &current_user()?])
+                .status()?,
+        };
+        if !status.success() {
+            return Err(format!("exited with {status}").into());
+        }
+        Ok(())
+    }
+}
+
+/// Result of one check of the host setup.
+#[derive(Clone, Debug)]
+pub struct SystemCheck {
+    pub name: &'static str,
+    pub ok: bool,
+    /// What was found, or what is missing and how to get it.
+    pub detail: String,
+    pub fix: Option<SystemFix>,
+}
+
+impl SystemCheck {
+    fn new(name: &'static str, ok: bool, detail: impl Into<String>) -> Self {
+        SystemCheck {
+            name,
+            ok,
+            detail: detail.into(),
+            fix: None,
+        }
+    }
+
+    fn with_fix(mut self, fix: SystemFix) -> Self {
+        if !self.ok {
+            self.fix = Some(fix);
+        }
+        self
+    }
+}
+
+/// First line `program --version` prints, or `None` when it can't be started.
+/// Some tools print their version to stderr.
+pub(super) fn program_version(program: &str) -> Option<String> {
+    let output = Command::new(program).arg("--version").output().ok()?;
+    let text = [output.stdout, output.stderr]
+        .iter()
+        .map(|bytes| String::from_utf8_lossy(bytes).to_string())
+        .find_map(|text| text.lines().map(str::trim).find(|l| !l.is_empty()).map(String::from));
+    Some(text.unwrap_or_else(|| "version unknown".to_string()))
+}
+
+/// Checks a program that must be installed, reporting its version.
+fn check_program(name: &'static str, program: &str, missing: &str) -> SystemCheck {
+    match program_version(program) {
+        Some(version) => SystemCheck::new(name, true, version),
+        None => SystemCheck::new(name, false, format!("Not found. {missing}")),
+    }
+}
+
+/// Checks a program only some settings need; missing is fine when unused.
+fn check_optional_program(
+    name: &'static str,
+    program: &str,
+    needed: bool,
+    missing: &str,
+) -> SystemCheck {
+    match program_version(program) {
+        Some(version) => SystemCheck::new(name, true, version),
+        None if needed => SystemCheck::new(name, false, format!("Not found. {missing}")),
+        None => SystemCheck::new(name, true, "Not found; not needed with the current settings."),
+    }
+}
+
+fn current_user() -> Result<String, Box<dyn Error>> {
+    if let Ok(user) = std::env::var("USER") {
+        return Ok(user);
+    }
+    let output = Command::new("id").arg("-un").output()?;
+    Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+fn in_input_group() -> bool {
+    Command::new("id")
+        .arg("-nG")
+        .output()
+        .is_ok_and(|output| {
+            String::from_utf8_lossy(&output.stdout)
+                .split_whitespace()
+                .any(|group| group == "input")
+        })
+}
+
+/// Whether every evdev node can be opened, e.g. through logind ACLs.
+fn input_devices_readable() -> bool {
+    let Ok(entries) = fs::read_dir("/dev/input") else {
+        return false;
+    };
+    entries
+        .flatten()
+        .filter(|entry| entry.file_name().to_string_lossy().starts_with("event"))
+        .all(|entry| fs::File::open(entry.path()).is_ok())
+}
+
+fn check_gamescope() -> SystemCheck {
+    check_program(
+        "gamescope",
+        "gamescope",
+        "Install gamescope from your distribution's packages; every instance runs in it.",
+    )
+}
+
+/// Checks that bwrap is installed and can build a sandbox, which takes
+/// unprivileged user namespaces unless bwrap is setuid.
+fn check_bwrap() -> SystemCheck {
+    let installed = check_program(
+        "Bubblewrap",
+        "bwrap",
+        "Install bubblewrap (bwrap); it gives each player only their own controller.",
+    );
+    if !installed.ok {
+        return installed;
+    }
+    let sandbox = Command::new("bwrap")
+        .args(["--unshare-user", "--ro-bind", "/", "/", "true"])
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .output();
+    let reason = match sandbox {
+        Ok(output) if output.status.success() => return installed,
+        Ok(output) => String::from_utf8_lossy(&output.stderr).trim().to_string(),
+        Err(err) => err.to_string(),
+    };
+    SystemCheck::new(
+        "Bubblewrap",
+        false,
+        format!(
+            "{} is installed but can't create a sandbox ({reason}). Allow unprivileged user namespaces, e.g. kernel.unprivileged_userns_clone=1, or an AppArmor profile for bwrap on Ubuntu.",
+            installed.detail
+        ),
+    )
+}
+
+fn check_scout() -> SystemCheck {
+    let scout = PATH_STEAM.join("ubuntu12_32/steam-runtime/run.sh").exists();
+    SystemCheck::new(
+        "Steam Runtime 1.0 (scout)",
+        scout,
+        if scout {
+            "Found."
+        } else {
+            "Not found. It comes with the Steam client; install Steam and start it once."
+        },
+    )
+}
+
+fn check_soldier() -> SystemCheck {
+    let soldier = PATH_STEAM
+        .join("steamapps/common/SteamLinuxRuntime_soldier")
+        .exists();
+    SystemCheck::new(
+        "Steam Runtime 2.0 (soldier)",
+        soldier,
+        if soldier {
+            "Found."
+        } else {
+            "Not found. Only handlers that ask for the soldier runtime need it."
+        },
+    )
+    .with_fix(SystemFix::SteamInstall(SOLDIER_APPID))
+}
+
+fn check_input_access() -> SystemCheck {
+    let input = in_input_group() || input_devices_readable();
+    SystemCheck::new(
+        "Input devices",
+        input,
+        if input {
+            "Readable."
+        } else {
+            "Not readable. Join the input group so controllers can be assigned to players, then log in again."
+        },
+    )
+    .with_fix(SystemFix::JoinInputGroup)
+}
+
+/// Checks what a first launch needs: gamescope, bubblewrap, the Steam
+/// runtimes, a Proton build and access to the input devices.
+pub fn run_setup_checks() -> Vec<SystemCheck> {
+    let protons = discover_proton_versions();
+    let proton = SystemCheck::new(
+        "Proton",
+        !protons.is_empty(),
+        match protons.len() {
+            0 => "No Proton build found. Windows games need one.".to_string(),
+            1 => format!("Found {}.", protons[0].display_name),
+            count => format!("Found {count} builds."),
+        },
+    )
+    .with_fix(SystemFix::SteamInstall(PROTON_EXPERIMENTAL_APPID));
+
+    vec![
+        check_gamescope(),
+        check_bwrap(),
+        check_scout(),
+        check_soldier(),
+        proton,
+        check_input_access(),
+    ]
+}
+
+/// Checks every tool a launch with `cfg` relies on, with versions where the
+/// tool reports one, so problems show up before a launch fails halfway.
+pub fn run_system_checks(cfg: &PartyConfig) -> Vec<SystemCheck> {
+    let mut checks = vec![check_gamescope()];
+
+    let kbm = &*BIN_GSC_KBM;
+    checks.push(if kbm.exists() {
+        check_program("gamescope-kbm", &kbm.to_string_lossy(), "It exists but won't start.")
+    } else if cfg.kbm_support {
+        SystemCheck::new(
+            "gamescope-kbm",
+            false,
+            format!(
+                "Not found at {}. Keyboard and mouse players need it; install it or turn off keyboard and mouse support.",
+                kbm.display()
+            ),
+        )
+    } else {
+        SystemCheck::new("gamescope-kbm", true, "Not found; keyboard and mouse support is off.")
+    });
+
+    checks.push(check_bwrap());
+
+    // The KWin session is only needed for --kwin and the KWin layout script.
+    checks.push(check_optional_program(
+        "kwin_wayland",
+        "kwin_wayland",
+        cfg.enable_kwin_script && !cfg.native_layout,
+        "Install KWin or switch to the native window layout in the settings.",
+    ));
+
+    let umu = &*BIN_UMU_RUN;
+    checks.push(if umu.exists() {
+        check_program("umu-run", &umu.to_string_lossy(), "It exists but won't start.")
+    } else {
+        SystemCheck::new(
+            "umu-run",
+            false,
+            format!(
+                "Not found at {}. Windows games are launched through it; install umu-launcher.",
+                umu.display()
+            ),
+        )
+    });
+
+    let proton = resolve_proton_environment(&cfg.proton_version);
+    checks.push(match &proton.root_path {
+        Some(path) => SystemCheck::new(
+            "Proton",
+            true,
+            format!("{} at {}", proton.display_name, path.display()),
+        ),
+        None if umu.exists() => SystemCheck::new(
+            "Proton",
+            true,
+            format!(
+                "{} isn't installed; umu-run downloads it on the first launch.",
+                proton.display_name
+            ),
+        ),
+        None => SystemCheck::new(
+            "Proton",
+            false,
+            format!("{} isn't installed and umu-run can't download it.", proton.display_name),
+        )
+        .with_fix(SystemFix::SteamInstall(PROTON_EXPERIMENTAL_APPID)),
+    });
+
+    checks.push(check_scout());
+    checks.push(check_soldier());
+    checks.push(check_input_access());
+    checks
+}
@@ -0,0 +1,135 @@
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+use std::sync::OnceLock;
+use std::thread;
+
+use super::system_check::program_version;
+
+/// Value of `key` in an os-release style file, without quotes.
+fn os_release_value(contents: &str, key: &str) -> Option<String> {
+    contents.lines().find_map(|line| {
+        let value = line.strip_prefix(key)?.strip_prefix('=')?;
+        Some(value.trim().trim_matches('"').to_string()).filter(|value| !value.is_empty())
+    })
+}
+
+/// Distribution name plus its build, which SteamOS reports as `BUILD_ID`.
+fn os_description() -> String {
+    let Ok(contents) = fs::read_to_string("/etc/os-release") else {
+        return "unknown".to_string();
+    };
+    let name = os_release_value(&contents, "PRETTY_NAME")
+        .or_else(|| os_release_value(&contents, "NAME"))
+        .unwrap_or_else(|| "unknown".to_string());
+    match os_release_value(&contents, "BUILD_ID") {
+        Some(build) => format!("{name} (build {build})"),
+        None => name,
+    }
+}
+
+/// Kernel driver and PCI ids of every GPU, like `amdgpu 1002:163f`.
+fn gpu_drivers() -> Vec<String> {
+    let Ok(entries) = fs::read_dir("/sys/class/drm") else {
+        return Vec::new();
+    };
+    let mut gpus: Vec<String> = entries
+        .flatten()
+        .filter(|entry| {
+            let name = entry.file_name().to_string_lossy().to_string();
+            name.starts_with("card") && !name.contains('-')
+        })
+        .filter_map(|entry| {
+            let device = entry.path().join("device");
+            let driver = fs::read_link(device.join("driver")).ok()?;
+            let driver = driver.file_name()?.to_string_lossy().to_string();
+            let id = |file: &str| {
+                fs::read_to_string(device.join(file))
+                    .map(|id| id.trim().trim_start_matches("0x").to_string())
+                    .unwrap_or_default()
+            };
+            Some(format!("{driver} {}:{}", id("vendor"), id("device")))
+        })
+        .collect();
+    gpus.sort();
+    gpus.dedup();
+    gpus
+}
+
+/// OpenGL version string, which names the Mesa release on Mesa drivers.
+/// Needs `glxinfo` and a display. Asked once per run, since starting
+/// `glxinfo` takes a while and the driver doesn't change under us.
+fn opengl_version() -> Option<String> {
+    static VERSION: OnceLock<Option<String>> = OnceLock::new();
+    VERSION
+        .get_or_init(|| {
+            let output = Command::new("glxinfo").arg("-B").output().ok()?;
+            String::from_utf8_lossy(&output.stdout).lines().find_map(|line| {
+                let version = line.trim().strip_prefix("OpenGL version string:")?;
+                Some(version.trim().to_string())
+            })
+        })
+        .clone()
+}
+
+/// Looks up the slow parts of [`system_info_lines`] in the background, so
+/// the first launch doesn't wait for them.
+pub fn prefetch_system_info() {
+    thread::spawn(opengl_version);
+}
+
+/// Version line of the loaded NVIDIA kernel module, if any.
+fn nvidia_version() -> Option<String> {
+    let contents = fs::read_to_string("/proc/driver/nvidia/version").ok()?;
+    let line = contents.lines().next()?;
+    Some(line.split_whitespace().collect::<Vec<_>>().join(" "))
+}
+
+/// Build a Proton install reports in its `version` file, like
+/// `GE-Proton9-20`. Prefixed by a build timestamp, which is dropped.
+pub fn proton_build(root: &Path) -> Option<String> {
+    let contents = fs::read_to_string(root.join("version")).ok()?;
+    let line = contents.lines().next()?.trim();
+    let build = line.split_once(' ').map_or(line, |(_, build)| build.trim());
+    Some(build.to_string()).filter(|build| !build.is_empty())
+}
+
+/// Lines describing the host for the top of a session log: kernel, OS build,
+/// GPU drivers and Mesa. Anything that can't be found is reported
+/// as unknown rather than left out, so bug reports show it was looked for.
+pub fn system_info_lines() -> Vec<String> {
+    let kernel = fs::read_to_string("/proc/sys/kernel/osrelease")
+        .map(|release| release.trim().to_string())
+        .unwrap_or_else(|_| "unknown".to_string());
+    let gpus = gpu_drivers();
+    let mut lines = vec![
+        format!("Kernel: {kernel}"),
+        format!("OS: {}", os_description()),
+        format!(
+            "GPU: {}",
+            if gpus.is_empty() {
+                "unknown".to_string()
+            } else {
+                gpus.join(", ")
+            }
+        ),
+        format!(
+            "OpenGL: {}",
+            opengl_version().unwrap_or_else(|| "unknown (glxinfo not available)".to_string())
+        ),
+    ];
+    if let Some(nvidia) = nvidia_version() {
+        lines.push(format!("NVIDIA: {nvidia}"));
+    }
+    lines
+}
+
+/// Session log line naming the gamescope build the instances run with.
+pub fn gamescope_info_line(program: &Path) -> String {
+    let version = program_version(&program.to_string_lossy());
+    format!(
+        "gamescope: {} ({})",
+        version.unwrap_or_else(|| "not found".to_string()),
+        program.display()
+    )
+}
@@ -0,0 +1,262 @@
+use std::collections::BTreeSet;
+use std::error::Error;
+use std::fs::{self, File};
+use std::io::BufReader;
+use std::path::{Path, PathBuf};
+
+use serde_json::{Map, Value};
+
+use crate::logging::log_info;
+use crate::paths::*;
+
+use super::filesystem::{copy_dir_recursive, write_atomic};
+use super::playtime::migrate_playtime_game;
+
+fn valid_uid(uid: &str) -> bool {
+    !uid.is_empty() && uid.chars().all(char::is_alphanumeric)
+}
+
+fn load_paths_json() -> Map<String, Value> {
+    File::open(PATH_APP.join("paths.json"))
+        .ok()
+        .and_then(|file| serde_json::from_reader(BufReader::new(file)).ok())
+        .unwrap_or_default()
+}
+
+fn profile_dirs() -> Vec<(String, PathBuf)> {
+    let Ok(entries) = fs::read_dir(PATH_APP.join("profiles")) else {
+        return Vec::new();
+    };
+    entries
+        .flatten()
+        .filter(|entry| entry.path().is_dir())
+        .filter_map(|entry| Some((entry.file_name().to_str()?.to_string(), entry.path())))
+        .collect()
+}
+
+/// Handler uids that own per-profile saves or a stored game folder but match
+/// no installed handler. They are left behind when a handler is republished
+/// under another uid.
+pub fn orphaned_game_uids(installed: &[&str]) -> Vec<String> {
+    let mut uids: BTreeSet<String> = load_paths_json().into_iter().map(|(uid, _)| uid).collect();
+    for (_, dir) in profile_dirs() {
+        let Ok(saves) = fs::read_dir(dir.join("saves")) else {
+            continue;
+        };
+        uids.extend(
+            saves
+                .flatten()
+                .filter(|entry| entry.path().is_dir())
+                .filter_map(|entry| entry.file_name().to_str().map(str::to_string)),
+        );
+    }
+    uids.into_iter()
+        .filter(|uid| valid_uid(uid) && !installed.contains(&uid.as_str()))
+        .collect()
+}
+
+/// Whether `dir` holds any file, ignoring empty folders.
+fn contains_files(dir: &Path) -> bool {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return false;
+    };
+    entries
+        .flatten()
+        .any(|entry| !entry.path().is_dir() || contains_files(&entry.path()))
+}
+
+/// Repoints the save folders in `saves` that [`SaveTransfer::Link`] linked to
+/// `from` at `to`, where those saves moved. Returns the uids of those links,
+/// noting the ones that couldn't be repointed.
+fn repoint_save_links(saves: &Path, from: &Path, to: &Path) -> Vec<String> {
+    let Ok(entries) = fs::read_dir(saves) else {
+        return Vec::new();
+    };
+    entries
+        .flatten()
+        .filter(|entry| fs::read_link(entry.path()).is_ok_and(|link| link == from))
+        .filter_map(|entry| {
+            let uid = entry.file_name().to_str()?.to_string();
+            let link = entry.path();
+            let relinked = fs::remove_file(&link)
+                .and_then(|()| std::os::unix::fs::symlink(to, &link));
+            Some(match relinked {
+                Ok(()) => uid,
+                Err(err) => format!("{uid} (failed, relink it by hand: {err})"),
+            })
+        })
+        .collect()
+}
+
+/// Moves `dir/<old>.json` to `dir/<new>.json` unless the new one exists.
+fn move_json(dir: &Path, old: &str, new: &str, what: &str, report: &mut Vec<String>) {
+    let from = dir.join(format!("{old}.json"));
+    let to = dir.join(format!("{new}.json"));
+    if !from.exists() {
+        return;
+    }
+    if to.exists() {
+        report.push(format!("Kept the old {what}; the new uid already has its own."));
+    } else if let Err(err) = fs::rename(&from, &to) {
+        report.push(format!("Couldn't move the {what}: {err}"));
+    } else {
+        report.push(format!("Moved the {what}."));
+    }
+}
+
+/// Moves everything stored under the handler uid `old` to `new`: each
+/// profile's saves, the game folder in paths.json, launch overrides and
+/// launch history. Save folders linked to the old saves follow them. Data
+/// the new uid already has is never overwritten. The symlink folder and
+/// artwork cache are deleted and rebuilt from the new handler. Returns what was done, one line per item.
+pub fn migrate_game_uid(old: &str, new: &str) -> Result<Vec<String>, Box<dyn Error>> {
+    if !valid_uid(old) || !valid_uid(new) {
+        return Err("Handler uids may only contain letters and numbers".into());
+    }
+    if old == new {
+        return Err("The old and new uid are the same".into());
+    }
+    let mut report = Vec::new();
+
+    for (profile, dir) in profile_dirs() {
+        let from = dir.join("saves").join(old);
+        if !from.is_dir() {
+            continue;
+        }
+        let to = dir.join("saves").join(new);
+        if contains_files(&to) {
+            report.push(format!(
+                "Kept {profile}'s old saves; they already have saves under {new}."
+            ));
+            continue;
+        }
+        // create_gamesave may already have laid out empty folders for the new uid.
+        let _ = fs::remove_dir_all(&to);
+        fs::rename(&from, &to)?;
+        report.push(format!("Moved {profile}'s saves."));
+        let relinked = repoint_save_links(&dir.join("saves"), &from, &to);
+        if !relinked.is_empty() {
+            report.push(format!(
+                "Pointed {profile}'s linked saves of {} at {new}.",
+                relinked.join(", ")
+            ));
+        }
+    }
+
+    let mut paths = load_paths_json();
+    if paths.contains_key(old) && paths.contains_key(new) {
+        report.push("Kept the game folder stored for the new uid.".to_string());
+    } else if let Some(path) = paths.remove(old) {
+        paths.insert(new.to_string(), path);
+        write_atomic(
+            &PATH_APP.join("paths.json"),
+            serde_json::to_string_pretty(&Value::Object(paths))?,
+        )?;
+        report.push("Moved the stored game folder.".to_string());
+    }
+
+    move_json(&PATH_APP.join("overrides"), old, new, "launch overrides", &mut report);
+    move_json(&PATH_APP.join("launch_history"), old, new, "launch history", &mut report);
+    if migrate_playtime_game(old, new) {
+        report.push("Moved the playtime statistics.".to_string());
+    }
+
+    for cache in ["gamesyms", "handler_cache"] {
+        let _ = fs::remove_dir_all(PATH_APP.join(cache).join(old));
+    }
+
+    log_info(format!("Migrated game data from handler {old} to {new}: {}", report.join(" ")));
+    Ok(report)
+}
+
+/// How [`transfer_profile_saves`] brings saves over to another handler uid.
+#[derive(Clone, Copy, PartialEq, Default)]
+pub enum SaveTransfer {
+    /// An independent copy; progress in the two games diverges afterwards.
+    #[default]
+    Copy,
+    /// The target's save folder becomes a symlink to the source, so both
+    /// handlers keep playing on the same save.
+    Link,
+}
+
+impl SaveTransfer {
+    pub const ALL: [SaveTransfer; 2] = [SaveTransfer::Copy, SaveTransfer::Link];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            SaveTransfer::Copy => "Copy",
+            SaveTransfer::Link => "Link",
+        }
+    }
+}
+
+/// Profiles with saves under the handler uid `uid`, sorted by name.
+pub fn profiles_with_saves(uid: &str) -> Vec<String> {
+    let mut profiles: Vec<String> = profile_dirs()
+        .into_iter()
+        .filter(|(_, dir)| contains_files(&dir.join("saves").join(uid)))
+        .map(|(profile, _)| profile)
+        .collect();
+    profiles.sort();
+    profiles
+}
+
+/// Copies or links the saves of each of `profiles` from the handler uid
+/// `from` to `to`, for handlers of the same game whose save formats match.
+/// Saves `to` already has are never replaced. Returns what was done, one line
+/// per profile.
+pub fn transfer_profile_saves(
+    from: &str,
+    to: &str,
+    profiles: &[String],
+    mode: SaveTransfer,
+) -> Result<Vec<String>, Box<dyn Error>> {
+    if !valid_uid(from) || !valid_uid(to) {
+        return Err("Handler uids may only contain letters and numbers".into());
+    }
+    if from == to {
+        return Err("The source and target uid are the same".into());
+    }
+    let mut report = Vec::new();
+
+    for (profile, dir) in profile_dirs() {
+        if !profiles.contains(&profile) {
+            continue;
+        }
+        let source = dir.join("saves").join(from);
+        if !contains_files(&source) {
+            report.push(format!("{profile} has no saves under {from}."));
+            continue;
+        }
+        let target = dir.join("saves").join(to);
+        if fs::read_link(&target).is_ok_and(|link| link == source) {
+            report.push(format!("{profile}'s saves are already linked."));
+            continue;
+        }
+        if contains_files(&target) {
+            report.push(format!("Kept {profile}'s saves; they already have saves under {to}."));
+            continue;
+        }
+        // create_gamesave may already have laid out empty folders for the target.
+        if target.is_symlink() {
+            fs::remove_file(&target)?;
+        } else {
+            let _ = fs::remove_dir_all(&target);
+        }
+        match mode {
+            SaveTransfer::Copy => {
+                fs::create_dir_all(&target)?;
+                copy_dir_recursive(&source, &target, false, false, None)?;
+                report.push(format!("Copied {profile}'s saves."));
+            }
+            SaveTransfer::Link => {
+                std::os::unix::fs::symlink(&source, &target)?;
+                report.push(format!("Linked {profile}'s saves."));
+            }
+        }
+    }
+
+    log_info(format!("Transferred saves from handler {from} to {to}: {}", report.join(" ")));
+    Ok(report)
+}
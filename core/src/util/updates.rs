@@ -0,0 +1,193 @@
+use std::error::Error;
+use std::fs;
+use std::os::unix::fs::PermissionsExt;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use serde_json::Value;
+
+use crate::logging::{log_info, log_warn};
+
+use super::net::curl_command;
+
+const LATEST_RELEASE_API: &str = "https://api.github.com/repos/blckink/suckmydeck/releases/latest";
+
+/// Newest published release, with the download matching this installation.
+pub struct ReleaseUpdate {
+    pub version: semver::Version,
+    asset_name: String,
+    asset_url: String,
+    /// SHA-256 the release publishes for the asset. It comes from the same
+    /// release, so it catches a corrupted or truncated download but can't
+    /// tell a tampered release from a genuine one.
+    sha256: String,
+}
+
+/// Runs curl on `url` and returns the body; errors carry curl's stderr.
+fn curl_get(url: &str, extra: &[&str]) -> Result<Vec<u8>, Box<dyn Error>> {
+    let output = curl_command()?
+        .args(["-sSfL", "-H", "User-Agent: split-happens"])
+        .args(extra)
+        .arg(url)
+        .output()?;
+    if !output.status.success() {
+        return Err(format!(
+            "Download of {url} failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        )
+        .into());
+    }
+    Ok(output.stdout)
+}
+
+fn latest_release() -> Result<Value, Box<dyn Error>> {
+    Ok(serde_json::from_slice(&curl_get(LATEST_RELEASE_API, &[])?)?)
+}
+
+fn release_version(release: &Value) -> Option<semver::Version> {
+    let tag_name = release["tag_name"].as_str()?;
+    // Tags are in vX.X.X format.
+    semver::Version::parse(tag_name.strip_prefix('v').unwrap_or(tag_name)).ok()
+}
+
+fn current_version() -> semver::Version {
+    semver::Version::parse(env!("CARGO_PKG_VERSION")).expect("package version is semver")
+}
+
+pub fn check_for_split_happens_update() -> bool {
+    // Use the system curl binary so Steam Deck users do not need a native TLS stack.
+    // Offline mode makes curl_command fail, which skips the check.
+    match latest_release() {
+        Ok(release) => release_version(&release).is_some_and(|latest| latest > current_version()),
+        Err(err) => {
+            // Surface why the check failed so developers can debug network issues locally
+            log_warn(format!("Split Happens update check failed: {err}"));
+            false
+        }
+    }
+}
+
+/// File the updater replaces and Steam shortcuts launch: the AppImage when
+/// running from one, otherwise the running binary.
+pub(super) fn update_target() -> Result<PathBuf, Box<dyn Error>> {
+    if let Some(appimage) = std::env::var_os("APPIMAGE") {
+        return Ok(PathBuf::from(appimage));
+    }
+    Ok(std::env::current_exe()?)
+}
+
+/// Architectures AppImage downloads are named after.
+const APPIMAGE_ARCHES: &[&str] = &["x86_64", "aarch64", "armhf", "i686"];
+
+/// Whether the release asset `name` is the download for `target`. AppImages
+/// must be built for this machine's architecture; one naming none counts as
+/// built for it. GitHub turns spaces in asset names into dots, so "Split
+/// Happens" is published as "Split.Happens".
+fn is_asset_for(target: &Path, name: &str) -> bool {
+    if target.extension().is_some_and(|ext| ext == "AppImage") {
+        let this_arch = name.contains(std::env::consts::ARCH);
+        let any_arch = APPIMAGE_ARCHES.iter().any(|arch| name.contains(arch));
+        return name.ends_with(".AppImage") && (this_arch || !any_arch);
+    }
+    target
+        .file_name()
+        .is_some_and(|file| file.to_string_lossy().replace(' ', ".") == name)
+}
+
+/// SHA-256 of `asset_name` from the release's checksum files: either
+/// `<asset>.sha256` or a `SHA256SUMS`-style list covering every asset.
+fn published_sha256(assets: &[Value], asset_name: &str) -> Result<String, Box<dyn Error>> {
+    let checksum_asset = assets.iter().find(|asset| {
+        let name = asset["name"].as_str().unwrap_or_default();
+        name == format!("{asset_name}.sha256") || name.eq_ignore_ascii_case("SHA256SUMS")
+    });
+    let Some(url) = checksum_asset.and_then(|asset| asset["browser_download_url"].as_str()) else {
+        return Err("The release publishes no SHA-256 checksum; not installing it.".into());
+    };
+    let sums = String::from_utf8(curl_get(url, &[])?)?;
+    sums.lines()
+        .filter_map(|line| {
+            let mut parts = line.split_whitespace();
+            let hash = parts.next()?;
+            // A lone hash is a per-asset file; lists name the file after it.
+            let name = parts.next().map(|name| name.trim_start_matches('*'));
+            name.is_none_or(|name| name == asset_name).then(|| hash.to_lowercase())
+        })
+        .find(|hash| hash.len() == 64 && hash.chars().all(|c| c.is_ascii_hexdigit()))
+        .ok_or_else(|| format!("No SHA-256 checksum listed for {asset_name}").into())
+}
+
+/// Looks up the newest release and the download that matches this
+/// installation. `None` when already up to date.
+pub fn find_release_update() -> Result<Option<ReleaseUpdate>, Box<dyn Error>> {
+    let release = latest_release()?;
+    let version = release_version(&release).ok_or("The latest release has no version tag")?;
+    if version <= current_version() {
+        return Ok(None);
+    }
+
+    let target = update_target()?;
+    let assets = release["assets"].as_array().cloned().unwrap_or_default();
+    let asset = assets
+        .iter()
+        .find(|asset| is_asset_for(&target, asset["name"].as_str().unwrap_or_default()))
+        .ok_or_else(|| format!("Release v{version} has no download for this installation"))?;
+    let asset_name = asset["name"].as_str().unwrap_or_default().to_string();
+    let asset_url = asset["browser_download_url"]
+        .as_str()
+        .ok_or("The release download has no URL")?
+        .to_string();
+    let sha256 = published_sha256(&assets, &asset_name)?;
+    Ok(Some(ReleaseUpdate {
+        version,
+        asset_name,
+        asset_url,
+        sha256,
+    }))
+}
+
+fn sha256_file(path: &Path) -> Result<String, Box<dyn Error>> {
+    let output = Command::new("sha256sum").arg(path).output()?;
+    if !output.status.success() {
+        return Err("sha256sum failed".into());
+    }
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    Ok(stdout.split_whitespace().next().unwrap_or_default().to_lowercase())
+}
+
+/// Downloads `update`, checks it against the published SHA-256 and swaps it
+/// in for [`update_target`] with a rename, so a failed download never leaves
+/// a broken installation behind. The checksum proves the download is intact,
+/// not that the release is authentic. Takes effect on the next start.
+pub fn install_release_update(update: &ReleaseUpdate) -> Result<PathBuf, Box<dyn Error>> {
+    let target = fs::canonicalize(update_target()?)?;
+    let dir = target.parent().ok_or("The installation has no parent folder")?;
+    // Downloaded next to the target so the final rename stays on one filesystem.
+    let staging = dir.join(".split-happens-update");
+    let _ = fs::remove_file(&staging);
+    log_info(format!("Downloading {} v{}", update.asset_name, update.version));
+    let staging_arg = staging.to_string_lossy().to_string();
+    curl_get(&update.asset_url, &["-o", staging_arg.as_str()])?;
+
+    let actual = sha256_file(&staging)?;
+    if actual != update.sha256 {
+        let _ = fs::remove_file(&staging);
+        return Err(format!(
+            "Checksum mismatch for {}: expected {}, got {actual}",
+            update.asset_name, update.sha256
+        )
+        .into());
+    }
+
+    fs::set_permissions(&staging, fs::Permissions::from_mode(0o755))?;
+    fs::rename(&staging, &target)?;
+    log_info(format!("Installed v{} to {}", update.version, target.display()));
+    Ok(target)
+}
+
+/// Starts the freshly installed `target` with this process's arguments; the
+/// caller quits right after.
+pub fn relaunch(target: &Path) -> Result<(), Box<dyn Error>> {
+    Command::new(target).args(std::env::args_os().skip(1)).spawn()?;
+    Ok(())
+}
@@ -0,0 +1,205 @@
+use std::collections::HashMap;
+use std::error::Error;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{self, Receiver};
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant};
+
+use nix::errno::Errno;
+use nix::sys::inotify::{AddWatchFlags, InitFlags, Inotify, InotifyEvent, WatchDescriptor};
+
+use crate::logging::log_warn;
+use crate::paths::PATH_APP;
+
+/// How often the watcher thread checks for events and for being stopped.
+const POLL_INTERVAL: Duration = Duration::from_millis(200);
+/// Quiet time before changes are reported, so an editor saving a file in
+/// several writes causes a single reload.
+const DEBOUNCE: Duration = Duration::from_millis(400);
+
+/// Something changed in the handlers or profiles folder outside the app.
+#[derive(Clone, Debug, PartialEq)]
+pub enum LibraryChange {
+    /// A handler folder or its handler.json was added, edited or removed.
+    Handlers,
+    /// A profile folder was added, removed or renamed.
+    Profiles,
+    /// A `.pdh` archive finished copying into the handlers folder.
+    HandlerArchive(PathBuf),
+}
+
+/// What a watch descriptor is watching.
+#[derive(Clone, Copy)]
+enum Watched {
+    HandlersRoot,
+    Handler,
+    Profiles,
+}
+
+fn dir_flags() -> AddWatchFlags {
+    AddWatchFlags::IN_CREATE
+        | AddWatchFlags::IN_DELETE
+        | AddWatchFlags::IN_MOVED_FROM
+        | AddWatchFlags::IN_MOVED_TO
+        | AddWatchFlags::IN_CLOSE_WRITE
+}
+
+/// Watches the handlers and profiles folders through inotify and reports
+/// changes to the UI, which polls them each frame. Stops when dropped.
+pub struct LibraryWatcher {
+    stop: Arc<AtomicBool>,
+    handle: Option<JoinHandle<()>>,
+    changes: Receiver<LibraryChange>,
+}
+
+impl LibraryWatcher {
+    pub fn start() -> Result<LibraryWatcher, Box<dyn Error>> {
+        let inotify = Inotify::init(InitFlags::IN_NONBLOCK | InitFlags::IN_CLOEXEC)?;
+        let handlers_dir = PATH_APP.join("handlers");
+        let profiles_dir = PATH_APP.join("profiles");
+
+        // Each handler lives in its own folder, so those are watched one by one
+        // to notice handler.json edits.
+        let mut watched: HashMap<WatchDescriptor, (Watched, PathBuf)> = HashMap::new();
+        let root = inotify.add_watch(&handlers_dir, dir_flags())?;
+        watched.insert(root, (Watched::HandlersRoot, handlers_dir.clone()));
+        for entry in std::fs::read_dir(&handlers_dir)?.flatten() {
+            if entry.file_type().is_ok_and(|ft| ft.is_dir()) {
+                watch_handler_dir(&inotify, entry.path(), &mut watched);
+            }
+        }
+        let profiles = inotify.add_watch(&profiles_dir, dir_flags())?;
+        watched.insert(profiles, (Watched::Profiles, profiles_dir));
+
+        let (sender, changes) = mpsc::channel();
+        let stop = Arc::new(AtomicBool::new(false));
+        let thread_stop = Arc::clone(&stop);
+        let handle = std::thread::spawn(move || {
+            let mut pending: Vec<LibraryChange> = Vec::new();
+            let mut last_event = Instant::now();
+            while !thread_stop.load(Ordering::Relaxed) {
+                let events = match inotify.read_events() {
+                    Ok(events) => events,
+                    Err(Errno::EAGAIN) => Vec::new(),
+                    Err(err) => {
+                        log_warn(format!("Stopped watching the library folders: {err}"));
+                        return;
+                    }
+                };
+                if !events.is_empty() {
+                    last_event = Instant::now();
+                }
+                for event in events {
+                    for change in classify_event(&inotify, &event, &mut watched) {
+                        if !pending.contains(&change) {
+                            pending.push(change);
+                        }
+                    }
+                }
+
+                if !pending.is_empty() && last_event.elapsed() >= DEBOUNCE {
+                    for change in pending.drain(..) {
+                        // The receiver is gone once the watcher was dropped.
+                        if sender.send(change).is_err() {
+                            return;
+                        }
+                    }
+                }
+                std::thread::sleep(POLL_INTERVAL);
+            }
+        });
+
+        Ok(LibraryWatcher {
+            stop,
+            handle: Some(handle),
+            changes,
+        })
+    }
+
+    /// Changes reported since the last call, each listed once.
+    pub fn poll(&self) -> Vec<LibraryChange> {
+        let mut changes: Vec<LibraryChange> = Vec::new();
+        for change in self.changes.try_iter() {
+            if !changes.contains(&change) {
+                changes.push(change);
+            }
+        }
+        changes
+    }
+}
+
+impl Drop for LibraryWatcher {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::Relaxed);
+        if let Some(handle) = self.handle.take() {
+            let _ = handle.join();
+        }
+    }
+}
+
+fn watch_handler_dir(
+    inotify: &Inotify,
+    path: PathBuf,
+    watched: &mut HashMap<WatchDescriptor, (Watched, PathBuf)>,
+) {
+    match inotify.add_watch(&path, dir_flags()) {
+        Ok(wd) => {
+            watched.insert(wd, (Watched::Handler, path));
+        }
+        Err(err) => log_warn(format!("Couldn't watch {}: {err}", path.display())),
+    }
+}
+
+/// Turns one inotify event into library changes, watching handler folders
+/// as they appear.
+fn classify_event(
+    inotify: &Inotify,
+    event: &InotifyEvent,
+    watched: &mut HashMap<WatchDescriptor, (Watched, PathBuf)>,
+) -> Vec<LibraryChange> {
+    // Events were dropped, so anything may have changed.
+    if event.mask.contains(AddWatchFlags::IN_Q_OVERFLOW) {
+        return vec![LibraryChange::Handlers, LibraryChange::Profiles];
+    }
+    // The kernel removed the watch, e.g. because the folder was deleted.
+    if event.mask.contains(AddWatchFlags::IN_IGNORED) {
+        watched.remove(&event.wd);
+        return Vec::new();
+    }
+    let Some((kind, dir)) = watched.get(&event.wd).cloned() else {
+        return Vec::new();
+    };
+    let Some(name) = event.name.as_ref() else {
+        return Vec::new();
+    };
+    let path = dir.join(name);
+    let is_dir = event.mask.contains(AddWatchFlags::IN_ISDIR);
+    let appeared = event
+        .mask
+        .intersects(AddWatchFlags::IN_CREATE | AddWatchFlags::IN_MOVED_TO);
+
+    match kind {
+        Watched::HandlersRoot if is_dir => {
+            if appeared {
+                watch_handler_dir(inotify, path, watched);
+            }
+            vec![LibraryChange::Handlers]
+        }
+        // Archives are reported once fully written, not when the copy starts.
+        Watched::HandlersRoot
+            if path.extension().is_some_and(|ext| ext == "pdh")
+                && event
+                    .mask
+                    .intersects(AddWatchFlags::IN_CLOSE_WRITE | AddWatchFlags::IN_MOVED_TO) =>
+        {
+            vec![LibraryChange::HandlerArchive(path)]
+        }
+        Watched::HandlersRoot => Vec::new(),
+        Watched::Handler if name == "handler.json" => vec![LibraryChange::Handlers],
+        Watched::Handler => Vec::new(),
+        Watched::Profiles if is_dir => vec![LibraryChange::Profiles],
+        Watched::Profiles => Vec::new(),
+    }
+}
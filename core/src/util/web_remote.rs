@@ -0,0 +1,553 @@
+use std::collections::HashMap;
+use std::error::Error;
+use std::fs::{self, File};
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::{IpAddr, Ipv4Addr, TcpListener, TcpStream, UdpSocket};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread::JoinHandle;
+use std::time::Duration;
+
+use rand::Rng;
+use serde_json::{Value, json};
+
+use super::guest_bundle::{export_guest_bundle, import_guest_bundle, is_guest_profile};
+use crate::logging::log_warn;
+use crate::paths::PATH_APP;
+use crate::session::SessionManager;
+
+/// Port the web remote listens on unless the settings pick another one.
+pub const DEFAULT_WEB_REMOTE_PORT: u16 = 47361;
+/// Largest guest save bundle a phone may upload.
+const MAX_BUNDLE_BYTES: u64 = 64 * 1024 * 1024;
+/// Longest request or header line read; anything the pages send is far shorter.
+const MAX_LINE_BYTES: u64 = 8192;
+/// Most header lines read from one request.
+const MAX_HEADERS: usize = 64;
+/// Connections answered at once; further ones are closed right away so one
+/// client on the LAN can't use up the launcher's threads.
+const MAX_CONNECTIONS: usize = 16;
+
+/// One player row of the join screen as phones see it.
+#[derive(Clone, Debug, Default)]
+pub struct RemotePlayer {
+    /// Names of the devices assigned to the player.
+    pub devices: String,
+    /// Index into [`JoinScreen::profiles`].
+    pub profile: usize,
+    pub ready: bool,
+}
+
+/// What the instances page currently shows, published by the UI each frame.
+#[derive(Clone, Debug, Default)]
+pub struct JoinScreen {
+    pub game: String,
+    /// Profiles players can pick from; empty when the game has none.
+    pub profiles: Vec<String>,
+    pub players: Vec<RemotePlayer>,
+}
+
+/// A change a player made on their phone, applied by the UI.
+#[derive(Clone, Debug, PartialEq)]
+pub enum RemoteAction {
+    SetProfile { slot: usize, profile: usize },
+    SetReady { slot: usize, ready: bool },
+}
+
+/// A guest save a phone uploaded, waiting for the host to restore or
+/// discard it.
+#[derive(Clone, Debug)]
+pub struct GuestUpload {
+    /// Guest profile the save is for.
+    pub name: String,
+    /// The uploaded zip, kept in the data folder's `tmp/` until resolved.
+    path: PathBuf,
+}
+
+/// Small HTTP server serving a phone-friendly join page on the LAN. Phones
+/// poll the join screen the UI publishes and queue actions the UI applies on
+/// its next frame. The server stops when dropped.
+pub struct WebRemote {
+    port: u16,
+    screen: Arc<Mutex<Option<JoinScreen>>>,
+    actions: Arc<Mutex<Vec<RemoteAction>>>,
+    /// Token of each guest's save handoff; its pages only answer with it.
+    handoffs: Arc<Mutex<HashMap<String, String>>>,
+    uploads: Arc<Mutex<Vec<GuestUpload>>>,
+    stop: Arc<AtomicBool>,
+    /// Accept loop, joined on drop so the port is free again afterwards.
+    thread: Option<JoinHandle<()>>,
+}
+
+/// State the connection threads share with the [`WebRemote`].
+struct Shared {
+    screen: Arc<Mutex<Option<JoinScreen>>>,
+    actions: Arc<Mutex<Vec<RemoteAction>>>,
+    handoffs: Arc<Mutex<HashMap<String, String>>>,
+    uploads: Arc<Mutex<Vec<GuestUpload>>>,
+}
+
+impl WebRemote {
+    /// Binds `port` on every interface and starts accepting connections.
+    pub fn start(port: u16) -> Result<Self, Box<dyn Error>> {
+        let listener = TcpListener::bind((Ipv4Addr::UNSPECIFIED, port))
+            .map_err(|err| format!("Couldn't bind web remote port {port}: {err}"))?;
+        listener.set_nonblocking(true)?;
+
+        let mut remote = WebRemote {
+            port,
+            screen: Arc::new(Mutex::new(None)),
+            actions: Arc::new(Mutex::new(Vec::new())),
+            handoffs: Arc::new(Mutex::new(HashMap::new())),
+            uploads: Arc::new(Mutex::new(Vec::new())),
+            stop: Arc::new(AtomicBool::new(false)),
+            thread: None,
+        };
+
+        let shared = Arc::new(Shared {
+            screen: remote.screen.clone(),
+            actions: remote.actions.clone(),
+            handoffs: remote.handoffs.clone(),
+            uploads: remote.uploads.clone(),
+        });
+        let stop = remote.stop.clone();
+        let open_connections = Arc::new(AtomicUsize::new(0));
+        remote.thread = Some(std::thread::spawn(move || {
+            while !stop.load(Ordering::Relaxed) {
+                match listener.accept() {
+                    Ok((stream, _))
+                        if open_connections.load(Ordering::Relaxed) >= MAX_CONNECTIONS =>
+                    {
+                        drop(stream);
+                    }
+                    // Each phone gets its own thread so a stalled connection
+                    // doesn't hold up the others.
+                    Ok((stream, _)) => {
+                        let shared = shared.clone();
+                        let open_connections = open_connections.clone();
+                        open_connections.fetch_add(1, Ordering::Relaxed);
+                        std::thread::spawn(move || {
+                            let _ = handle_connection(stream, &shared);
+                            open_connections.fetch_sub(1, Ordering::Relaxed);
+                        });
+                    }
+                    Err(err) if err.kind() == std::io::ErrorKind::WouldBlock => {
+                        std::thread::sleep(Duration::from_millis(100));
+                    }
+                    Err(err) => {
+                        log_warn(format!("Web remote connection failed: {err}"));
+                        std::thread::sleep(Duration::from_millis(100));
+                    }
+                }
+            }
+        }));
+
+        Ok(remote)
+    }
+
+    pub fn port(&self) -> u16 {
+        self.port
+    }
+
+    /// Address phones on the same network open, e.g. `http://192.168.1.20:47361/`.
+    pub fn url(&self) -> String {
+        let host = lan_address()
+            .map(|ip| ip.to_string())
+            .unwrap_or_else(|| "localhost".to_string());
+        format!("http://{host}:{}/", self.port)
+    }
+
+    /// Publishes the join screen. `None` tells phones to wait for the host.
+    pub fn set_join_screen(&self, screen: Option<JoinScreen>) {
+        *self.screen.lock().unwrap() = screen;
+    }
+
+    /// Returns and clears the actions phones sent since the last call.
+    pub fn take_actions(&self) -> Vec<RemoteAction> {
+        std::mem::take(&mut *self.actions.lock().unwrap())
+    }
+
+    /// Address of guest `name`'s save handoff page. The link carries a token
+    /// made for this guest, so other phones on the network can't reach the
+    /// save without the QR code.
+    pub fn guest_handoff_url(&self, name: &str) -> String {
+        let mut handoffs = self.handoffs.lock().unwrap();
+        let token = handoffs
+            .entry(name.to_string())
+            .or_insert_with(|| format!("{:032x}", rand::rng().random::<u128>()));
+        format!("{}guest/{name}?token={token}", self.url())
+    }
+
+    /// Oldest guest save waiting for the host's decision, if any.
+    pub fn pending_guest_upload(&self) -> Option<GuestUpload> {
+        self.uploads.lock().unwrap().first().cloned()
+    }
+
+    /// Restores (`restore`) or discards an uploaded guest save. Restoring is
+    /// refused while a game runs, since the guest's folder may be in use;
+    /// the upload then stays pending.
+    pub fn resolve_guest_upload(
+        &self,
+        upload: &GuestUpload,
+        restore: bool,
+    ) -> Result<(), Box<dyn Error>> {
+        if restore {
+            if SessionManager::global().any_running() {
+                return Err("A game is running. Restore the save once it has ended.".into());
+            }
+            import_guest_bundle(&upload.name, &upload.path)?;
+        }
+        self.uploads.lock().unwrap().retain(|pending| pending.path != upload.path);
+        let _ = fs::remove_file(&upload.path);
+        Ok(())
+    }
+}
+
+impl Drop for WebRemote {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::Relaxed);
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+        for upload in self.uploads.lock().unwrap().drain(..) {
+            let _ = fs::remove_file(upload.path);
+        }
+    }
+}
+
+/// Local address that routes to the rest of the network. Connecting a UDP
+/// socket only picks the route; nothing is sent.
+fn lan_address() -> Option<IpAddr> {
+    let socket = UdpSocket::bind((Ipv4Addr::UNSPECIFIED, 0)).ok()?;
+    socket.connect((Ipv4Addr::new(192, 0, 2, 1), 9)).ok()?;
+    socket.local_addr().ok().map(|addr| addr.ip())
+}
+
+/// Reads one line of at most [`MAX_LINE_BYTES`], failing on a longer one.
+fn read_limited_line(reader: &mut impl BufRead) -> std::io::Result<String> {
+    let mut line = String::new();
+    let read = reader.take(MAX_LINE_BYTES).read_line(&mut line)?;
+    if read as u64 >= MAX_LINE_BYTES && !line.ends_with('\n') {
+        return Err(std::io::Error::new(std::io::ErrorKind::InvalidData, "Line too long"));
+    }
+    Ok(line)
+}
+
+/// Answers a single request and closes the connection.
+fn handle_connection(mut stream: TcpStream, shared: &Shared) -> std::io::Result<()> {
+    stream.set_nonblocking(false)?;
+    stream.set_read_timeout(Some(Duration::from_secs(2)))?;
+    let mut reader = BufReader::new(stream.try_clone()?);
+    let request_line = read_limited_line(&mut reader)?;
+    // Only the body length is needed from the headers.
+    let mut content_length = 0u64;
+    let mut headers = 0;
+    loop {
+        let header = read_limited_line(&mut reader)?;
+        if header.len() <= 2 {
+            break;
+        }
+        headers += 1;
+        if headers > MAX_HEADERS {
+            return respond(&mut stream, "431 Request Header Fields Too Large", "text/plain", "");
+        }
+        if let Some((key, value)) = header.split_once(':') {
+            if key.eq_ignore_ascii_case("content-length") {
+                content_length = value.trim().parse().unwrap_or(0);
+            }
+        }
+    }
+
+    let mut parts = request_line.split_whitespace();
+    let method = parts.next().unwrap_or_default();
+    let target = parts.next().unwrap_or_default();
+    let (path, query) = target.split_once('?').unwrap_or((target, ""));
+    let params: HashMap<&str, &str> =
+        query.split('&').filter_map(|pair| pair.split_once('=')).collect();
+    let number = |key: &str| params.get(key).and_then(|value| value.parse::<usize>().ok());
+
+    // Guest save handoff: /guest/<name> is the page the QR code opens and
+    // /guest/<name>/bundle downloads or uploads the save. Both need the
+    // token of the guest's QR code.
+    if let Some(rest) = path.strip_prefix("/guest/") {
+        let (name, bundle) = match rest.strip_suffix("/bundle") {
+            Some(name) => (name, true),
+            None => (rest, false),
+        };
+        let token = params.get("token").copied().unwrap_or_default();
+        let authorized = is_guest_profile(name)
+            && shared.handoffs.lock().unwrap().get(name).is_some_and(|t| t == token);
+        if !authorized {
+            return respond(&mut stream, "404 Not Found", "text/plain", "Not found");
+        }
+        return match (method, bundle) {
+            ("GET", false) => {
+                let page = GUEST_PAGE.replace("{name}", name).replace("{token}", token);
+                respond(&mut stream, "200 OK", "text/html; charset=utf-8", &page)
+            }
+            ("GET", true) => match export_guest_bundle(name) {
+                Ok(zip) => respond_with(
+                    &mut stream,
+                    "200 OK",
+                    "application/zip",
+                    &format!(
+                        "Content-Disposition: attachment; filename=\"split-happens-{name}.zip\"\r\n"
+                    ),
+                    &zip,
+                ),
+                Err(err) => respond(&mut stream, "404 Not Found", "text/plain", &err.to_string()),
+            },
+            ("POST", true) if content_length > MAX_BUNDLE_BYTES => {
+                respond(&mut stream, "413 Payload Too Large", "text/plain", "Save is too large.")
+            }
+            ("POST", true) => match receive_upload(&mut reader, name, content_length) {
+                Ok(path) => {
+                    let mut uploads = shared.uploads.lock().unwrap();
+                    // A newer upload for the same guest replaces the one still waiting.
+                    for stale in uploads.iter().filter(|pending| pending.name == name) {
+                        let _ = fs::remove_file(&stale.path);
+                    }
+                    uploads.retain(|pending| pending.name != name);
+                    uploads.push(GuestUpload {
+                        name: name.to_string(),
+                        path,
+                    });
+                    respond(&mut stream, "202 Accepted", "text/plain", "")
+                }
+                Err(err) => respond(&mut stream, "400 Bad Request", "text/plain", &err.to_string()),
+            },
+            _ => respond(&mut stream, "404 Not Found", "text/plain", "Not found"),
+        };
+    }
+
+    let action = match (method, path) {
+        ("GET", "/") => {
+            return respond(&mut stream, "200 OK", "text/html; charset=utf-8", REMOTE_PAGE);
+        }
+        ("GET", "/state") => {
+            let state = state_json(&shared.screen.lock().unwrap());
+            return respond(&mut stream, "200 OK", "application/json", &state.to_string());
+        }
+        ("POST", "/profile") => number("slot")
+            .zip(number("profile"))
+            .map(|(slot, profile)| RemoteAction::SetProfile { slot, profile }),
+        ("POST", "/ready") => number("slot")
+            .zip(number("ready"))
+            .map(|(slot, ready)| RemoteAction::SetReady { slot, ready: ready != 0 }),
+        _ => return respond(&mut stream, "404 Not Found", "text/plain", "Not found"),
+    };
+    match action {
+        Some(action) => {
+            shared.actions.lock().unwrap().push(action);
+            respond(&mut stream, "204 No Content", "text/plain", "")
+        }
+        None => respond(&mut stream, "400 Bad Request", "text/plain", "Bad request"),
+    }
+}
+
+/// Streams an uploaded save of `len` bytes into the data folder's `tmp/`
+/// and returns where it landed. Nothing larger than [`MAX_BUNDLE_BYTES`] is
+/// written.
+fn receive_upload(
+    reader: &mut impl Read,
+    name: &str,
+    len: u64,
+) -> Result<PathBuf, Box<dyn Error>> {
+    let dir = PATH_APP.join("tmp");
+    fs::create_dir_all(&dir)?;
+    let path = dir.join(format!("guest-upload-{name}-{:08x}.zip", rand::rng().random::<u32>()));
+    let received = File::create(&path)
+        .and_then(|mut file| std::io::copy(&mut reader.take(len), &mut file));
+    match received {
+        Ok(copied) if copied == len => Ok(path),
+        Ok(_) => {
+            let _ = fs::remove_file(&path);
+            Err("The upload was cut short.".into())
+        }
+        Err(err) => {
+            let _ = fs::remove_file(&path);
+            Err(err.into())
+        }
+    }
+}
+
+fn respond(
+    stream: &mut TcpStream,
+    status: &str,
+    content_type: &str,
+    body: &str,
+) -> std::io::Result<()> {
+    respond_with(stream, status, content_type, "", body.as_bytes())
+}
+
+/// Writes a full response. `headers` are extra header lines, each ending in CRLF.
+fn respond_with(
+    stream: &mut TcpStream,
+    status: &str,
+    content_type: &str,
+    headers: &str,
+    body: &[u8],
+) -> std::io::Result<()> {
+    write!(
+        stream,
+        "HTTP/1.1 {status}\r\nContent-Type: {content_type}\r\nContent-Length: {}\r\n{headers}Cache-Control: no-store\r\nConnection: close\r\n\r\n",
+        body.len()
+    )?;
+    stream.write_all(body)?;
+    stream.flush()
+}
+
+fn state_json(screen: &Option<JoinScreen>) -> Value {
+    let Some(screen) = screen else {
+        return json!({ "open": false });
+    };
+    let players: Vec<Value> = screen
+        .players
+        .iter()
+        .enumerate()
+        .map(|(slot, player)| {
+            json!({
+                "slot": slot,
+                "devices": player.devices,
+                "profile": player.profile,
+                "ready": player.ready,
+            })
+        })
+        .collect();
+    json!({
+        "open": true,
+        "game": screen.game,
+        "profiles": screen.profiles,
+        "players": players,
+    })
+}
+
+/// Join page served to phones. It polls `/state` every second and posts
+/// profile picks and ready toggles back.
+const REMOTE_PAGE: &str = r#"<!doctype html>
+<html>
+<head>
+<meta charset="utf-8">
+<meta name="viewport" content="width=device-width, initial-scale=1">
+<title>Split Happens</title>
+<style>
+body { font-family: sans-serif; background: #1b1b1f; color: #eee; margin: 0; padding: 16px; }
+h1 { font-size: 1.3em; }
+.player { background: #2a2a31; border-radius: 12px; padding: 12px; margin: 12px 0; }
+.player.ready { outline: 2px solid #5ac878; }
+.devices { color: #999; }
+select, button { font-size: 1.1em; width: 100%; margin-top: 8px; padding: 10px; border: 0; border-radius: 8px; }
+button { background: #5ac878; color: #111; }
+.ready button { background: #555; color: #eee; }
+</style>
+</head>
+<body>
+<h1 id="game">Split Happens</h1>
+<div id="players"></div>
+<script>
+let picking = false;
+
+async function post(url) {
+  await fetch(url, { method: "POST" });
+  refresh();
+}
+
+function render(state) {
+  const game = document.getElementById("game");
+  const list = document.getElementById("players");
+  list.replaceChildren();
+  if (!state.open) {
+    game.textContent = "Waiting for the host";
+    const hint = document.createElement("p");
+    hint.className = "devices";
+    hint.textContent = "Open a game's instances page in Split Happens.";
+    list.append(hint);
+    return;
+  }
+  game.textContent = state.game;
+  for (const player of state.players) {
+    const card = document.createElement("div");
+    card.className = player.ready ? "player ready" : "player";
+    const title = document.createElement("strong");
+    title.textContent = "Player " + (player.slot + 1);
+    const devices = document.createElement("div");
+    devices.className = "devices";
+    devices.textContent = player.devices;
+    card.append(title, devices);
+    if (state.profiles.length > 0) {
+      const select = document.createElement("select");
+      state.profiles.forEach((name, index) => select.add(new Option(name, index)));
+      select.value = player.profile;
+      select.onfocus = () => { picking = true; };
+      select.onblur = () => { picking = false; };
+      select.onchange = () => {
+        picking = false;
+        post("/profile?slot=" + player.slot + "&profile=" + select.value);
+      };
+      card.append(select);
+    }
+    const ready = document.createElement("button");
+    ready.textContent = player.ready ? "Not ready" : "Ready";
+    ready.onclick = () => post("/ready?slot=" + player.slot + "&ready=" + (player.ready ? 0 : 1));
+    card.append(ready);
+    list.append(card);
+  }
+}
+
+async function refresh() {
+  // Redrawing would close a profile list that is being picked from.
+  if (picking) return;
+  try {
+    const response = await fetch("/state");
+    render(await response.json());
+  } catch (err) {}
+}
+
+setInterval(refresh, 1000);
+refresh();
+</script>
+</body>
+</html>
+"#;
+
+/// Page a guest's QR code opens. `{name}` is replaced with the guest profile.
+const GUEST_PAGE: &str = r#"<!doctype html>
+<html>
+<head>
+<meta charset="utf-8">
+<meta name="viewport" content="width=device-width, initial-scale=1">
+<title>Split Happens: {name}</title>
+<style>
+body { font-family: sans-serif; background: #1b1b1f; color: #eee; margin: 0; padding: 16px; }
+h1 { font-size: 1.3em; }
+p { color: #bbb; }
+a, label { display: block; box-sizing: border-box; width: 100%; margin-top: 12px; padding: 12px; border-radius: 8px; font-size: 1.1em; text-align: center; text-decoration: none; }
+a { background: #5ac878; color: #111; }
+label { background: #555; color: #eee; }
+input { display: none; }
+</style>
+</head>
+<body>
+<h1>{name}</h1>
+<p>Take this guest's progress home at the end of the night, or bring back a save from an earlier one.</p>
+<a href="/guest/{name}/bundle?token={token}" download>Download save</a>
+<label>Restore a save<input type="file" id="file" accept=".zip,application/zip"></label>
+<p id="status"></p>
+<script>
+document.getElementById("file").onchange = async (event) => {
+  const file = event.target.files[0];
+  if (!file) return;
+  const status = document.getElementById("status");
+  status.textContent = "Uploading…";
+  try {
+    const response = await fetch("/guest/{name}/bundle?token={token}", { method: "POST", body: file });
+    status.textContent = response.ok ? "Uploaded. The host restores it from the TV." : await response.text();
+  } catch (err) {
+    status.textContent = "Upload failed.";
+  }
+};
+</script>
+</body>
+</html>
+"#;
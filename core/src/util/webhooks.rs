@@ -0,0 +1,118 @@
+use std::error::Error;
+use std::io::Write;
+use std::process::Stdio;
+use std::sync::Mutex;
+use std::thread::JoinHandle;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use serde_json::{Value, json};
+
+use crate::logging::log_warn;
+
+use super::net::{curl_command, offline_mode};
+
+/// Upper bound for a single webhook delivery so an unreachable endpoint can't
+/// stall the launcher.
+const WEBHOOK_TIMEOUT_SECS: &str = "5";
+
+/// Deliveries still in flight, waited for by [`finish_session_webhooks`].
+static DELIVERIES: Mutex<Vec<JoinHandle<()>>> = Mutex::new(Vec::new());
+
+/// Builds the JSON body shared by every session event.
+fn webhook_payload(event: &str, game: &str, players: &[String], extra: Value) -> Value {
+    let timestamp = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0);
+    let mut payload = json!({
+        "event": event,
+        "game": game,
+        "players": players,
+        "timestamp": timestamp,
+    });
+    // Merge event-specific fields (e.g. the crashed profile) into the body.
+    if let (Some(body), Value::Object(extra)) = (payload.as_object_mut(), extra) {
+        body.extend(extra);
+    }
+    payload
+}
+
+/// POSTs `payload` to `url` through the system curl binary, matching the update
+/// checker so no TLS crate is needed.
+fn post_json(url: &str, payload: &Value) -> Result<(), Box<dyn Error>> {
+    let mut child = curl_command()?
+        .args([
+            "-sS",
+            "-f",
+            "--max-time",
+            WEBHOOK_TIMEOUT_SECS,
+            "-X",
+            "POST",
+            "-H",
+            "Content-Type: application/json",
+            "-H",
+            "User-Agent: split-happens",
+            "--data-binary",
+            "@-",
+            url,
+        ])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped())
+        .spawn()?;
+
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin.write_all(payload.to_string().as_bytes())?;
+    }
+
+    let output = child.wait_with_output()?;
+    if !output.status.success() {
+        return Err(format!(
+            "Webhook request failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        )
+        .into());
+    }
+    Ok(())
+}
+
+/// Fires a session event webhook in the background. Does nothing when no URL
+/// is configured or offline mode is on; delivery failures are only logged.
+pub fn fire_session_webhook(url: &str, event: &str, game: &str, players: &[String], extra: Value) {
+    let url = url.trim().to_string();
+    if url.is_empty() || offline_mode() {
+        return;
+    }
+
+    let payload = webhook_payload(event, game, players, extra);
+    let event = event.to_string();
+    let delivery = std::thread::spawn(move || {
+        if let Err(err) = post_json(&url, &payload) {
+            log_warn(format!("Failed to deliver {event} webhook: {err}"));
+        }
+    });
+    let mut deliveries = DELIVERIES.lock().unwrap();
+    deliveries.retain(|delivery| !delivery.is_finished());
+    deliveries.push(delivery);
+}
+
+/// Waits for the deliveries still in flight, such as the `session_end` event
+/// of a session that just ended, so exiting doesn't cut them off. Each one
+/// gives up after `WEBHOOK_TIMEOUT_SECS`.
+pub fn finish_session_webhooks() {
+    let deliveries = std::mem::take(&mut *DELIVERIES.lock().unwrap());
+    for delivery in deliveries {
+        let _ = delivery.join();
+    }
+}
+
+/// Sends a synthetic `test` event and waits for the result so the settings
+/// page can report whether the endpoint is reachable.
+pub fn test_session_webhook(url: &str) -> Result<(), Box<dyn Error>> {
+    let url = url.trim();
+    if url.is_empty() {
+        return Err("No webhook URL configured.".into());
+    }
+    let payload = webhook_payload("test", "Split Happens", &[], Value::Null);
+    post_json(url, &payload)
+}
@@ -0,0 +1,156 @@
+use std::cell::RefCell;
+use std::collections::{HashMap, HashSet};
+use std::error::Error;
+use std::fs;
+
+use x11rb::connection::Connection;
+use x11rb::protocol::xproto::{AtomEnum, ConnectionExt, MapState, Window};
+use x11rb::rust_connection::RustConnection;
+
+/// Top-level windows of the screen with root `root`: the window manager's
+/// client list when it publishes one, the root's children otherwise.
+pub fn client_windows(
+    conn: &impl Connection,
+    root: Window,
+) -> Result<Vec<Window>, Box<dyn Error>> {
+    let client_list = conn.intern_atom(false, b"_NET_CLIENT_LIST")?.reply()?.atom;
+    let reply = conn
+        .get_property(false, root, client_list, AtomEnum::WINDOW, 0, u32::MAX)?
+        .reply()?;
+    Ok(match reply.value32() {
+        Some(list) => list.collect(),
+        None => conn.query_tree(root)?.reply()?.children,
+    })
+}
+
+/// Window the window manager reports as focused in `_NET_ACTIVE_WINDOW`, or
+/// `None` when no X client has focus.
+pub fn active_window(
+    conn: &impl Connection,
+    root: Window,
+) -> Result<Option<Window>, Box<dyn Error>> {
+    let active = conn.intern_atom(false, b"_NET_ACTIVE_WINDOW")?.reply()?.atom;
+    let reply = conn
+        .get_property(false, root, active, AtomEnum::WINDOW, 0, 1)?
+        .reply()?;
+    Ok(reply.value32().and_then(|mut values| values.next()).filter(|window| *window != 0))
+}
+
+/// PID `window` publishes in `_NET_WM_PID`, or `None` when it publishes none.
+pub fn window_pid(
+    conn: &impl Connection,
+    window: Window,
+) -> Result<Option<u32>, Box<dyn Error>> {
+    let wm_pid = conn.intern_atom(false, b"_NET_WM_PID")?.reply()?.atom;
+    let reply = conn
+        .get_property(false, window, wm_pid, AtomEnum::CARDINAL, 0, 1)?
+        .reply()?;
+    Ok(reply.value32().and_then(|mut values| values.next()))
+}
+
+/// Asks the window manager which processes have a window open, to tell an
+/// instance that is still loading from one whose window is up.
+pub struct WindowProbe {
+    conn: RustConnection,
+    root: Window,
+    /// Connections to the nested X servers of gamescope instances, by display.
+    nested: RefCell<HashMap<String, (RustConnection, Window)>>,
+}
+
+impl WindowProbe {
+    /// Connects to the X server, or XWayland under a Wayland session. `None`
+    /// when there is no display to ask.
+    pub fn connect() -> Option<WindowProbe> {
+        let (conn, screen_num) = x11rb::connect(None).ok()?;
+        let root = conn.setup().roots.get(screen_num)?.root;
+        Some(WindowProbe {
+            conn,
+            root,
+            nested: RefCell::default(),
+        })
+    }
+
+    /// PIDs owning a window in the window manager's client list. Windows
+    /// that don't publish `_NET_WM_PID`, or vanish while being asked, are
+    /// skipped.
+    pub fn window_pids(&self) -> Result<HashSet<u32>, Box<dyn Error>> {
+        let windows = client_windows(&self.conn, self.root)?;
+        Ok(windows
+            .into_iter()
+            .filter_map(|window| window_pid(&self.conn, window).ok().flatten())
+            .collect())
+    }
+
+    /// Whether a game window is up in the process tree `tree` of an instance.
+    /// gamescope opens its own window right away, so inside gamescope only a
+    /// mapped window on its nested X server counts; elsewhere any window of a
+    /// process in the tree does. Fails when the display can't be asked.
+    pub fn game_window_shown(
+        &self,
+        tree: &[u32],
+        window_pids: &HashSet<u32>,
+    ) -> Result<bool, Box<dyn Error>> {
+        if tree.iter().any(|pid| is_gamescope(*pid)) {
+            return match nested_display(tree) {
+                Some(display) => self.nested_window_mapped(&display),
+                // The game hasn't started inside gamescope yet.
+                None => Ok(false),
+            };
+        }
+        Ok(tree.iter().any(|pid| window_pids.contains(pid)))
+    }
+
+    /// Whether the X server at `display` has a mapped top-level window
+    /// larger than a pixel.
+    fn nested_window_mapped(&self, display: &str) -> Result<bool, Box<dyn Error>> {
+        let mut nested = self.nested.borrow_mut();
+        if !nested.contains_key(display) {
+            let (conn, screen_num) = x11rb::connect(Some(display))?;
+            let root = conn.setup().roots.get(screen_num).ok_or("No X screen")?.root;
+            nested.insert(display.to_string(), (conn, root));
+        }
+        let (conn, root) = &nested[display];
+        let tree = conn.query_tree(*root).ok().and_then(|cookie| cookie.reply().ok());
+        let Some(tree) = tree else {
+            // Reconnect on the next call, e.g. after gamescope restarted.
+            nested.remove(display);
+            return Err(format!("Lost the X server at {display}").into());
+        };
+        let children = tree.children;
+        Ok(children.into_iter().any(|window| {
+            let mapped = conn
+                .get_window_attributes(window)
+                .ok()
+                .and_then(|cookie| cookie.reply().ok())
+                .is_some_and(|attributes| attributes.map_state == MapState::VIEWABLE);
+            let sized = conn
+                .get_geometry(window)
+                .ok()
+                .and_then(|cookie| cookie.reply().ok())
+                .is_some_and(|geometry| geometry.width > 1 && geometry.height > 1);
+            mapped && sized
+        }))
+    }
+}
+
+fn is_gamescope(pid: u32) -> bool {
+    fs::read_to_string(format!("/proc/{pid}/comm"))
+        .is_ok_and(|comm| comm.trim_end().starts_with("gamescope"))
+}
+
+/// `DISPLAY` of the processes gamescope started in `tree`, which differs
+/// from the launcher's own display.
+fn nested_display(tree: &[u32]) -> Option<String> {
+    let host = std::env::var("DISPLAY").unwrap_or_default();
+    tree.iter()
+        .filter(|pid| !is_gamescope(**pid))
+        .filter_map(|pid| fs::read(format!("/proc/{pid}/environ")).ok())
+        .find_map(|environ| {
+            environ
+                .split(|byte| *byte == 0)
+                .filter_map(|entry| std::str::from_utf8(entry).ok())
+                .find_map(|entry| entry.strip_prefix("DISPLAY="))
+                .filter(|display| !display.is_empty() && *display != host)
+                .map(str::to_string)
+        })
+}
@@ -0,0 +1,95 @@
+use std::error::Error;
+use std::fs;
+use std::io::Read;
+use std::os::unix::process::CommandExt;
+use std::path::Path;
+use std::process::{Command, Stdio};
+use std::time::Duration;
+
+use nix::sys::signal::{Signal, kill};
+use nix::unistd::Pid;
+
+use super::proton::ProtonEnvironment;
+use crate::paths::BIN_UMU_RUN;
+
+/// How often a running install checks whether the launch was cancelled.
+const INSTALL_POLL_INTERVAL: Duration = Duration::from_millis(200);
+
+/// Whether `verb` looks like a winetricks verb (`vcrun2019`, `dotnet48`,
+/// `fontsmooth=rgb`) rather than an option or something shell-like.
+pub fn is_winetricks_verb(verb: &str) -> bool {
+    !verb.is_empty()
+        && !verb.starts_with('-')
+        && verb
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '-' | '=' | '.'))
+}
+
+/// Verbs of `verbs` not yet installed in `prefix`, going by the
+/// `winetricks.log` winetricks keeps in every prefix it touched.
+pub fn missing_winetricks_verbs(prefix: &Path, verbs: &[String]) -> Vec<String> {
+    let log = fs::read_to_string(prefix.join("winetricks.log")).unwrap_or_default();
+    let installed: Vec<&str> = log.lines().map(str::trim).collect();
+    verbs
+        .iter()
+        .filter(|verb| !installed.contains(&verb.as_str()))
+        .cloned()
+        .collect()
+}
+
+/// Installs one winetricks verb into `prefix` through umu-run, which brings
+/// its own winetricks and runs it against the chosen Proton build. Verbs like
+/// dotnet48 take minutes, so `keep_going` is asked every few hundred
+/// milliseconds; once it returns `false` the install and everything it
+/// started is killed.
+pub fn install_winetricks_verb(
+    prefix: &Path,
+    proton_env: &ProtonEnvironment,
+    verb: &str,
+    keep_going: impl Fn() -> bool,
+) -> Result<(), Box<dyn Error>> {
+    fs::create_dir_all(prefix)?;
+    let mut child = Command::new(&*BIN_UMU_RUN)
+        .env("PROTONPATH", &proton_env.env_value)
+        .env("WINEPREFIX", prefix)
+        .env("STEAM_COMPAT_DATA_PATH", prefix)
+        .args(["winetricks", "-q", verb])
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped())
+        // Its own process group, so a cancel reaches the Wine processes too.
+        .process_group(0)
+        .spawn()?;
+    // Drained aside so a chatty install can't stall on a full pipe.
+    let stderr = child.stderr.take().map(|mut pipe| {
+        std::thread::spawn(move || {
+            let mut text = String::new();
+            let _ = pipe.read_to_string(&mut text);
+            text
+        })
+    });
+
+    let status = loop {
+        if let Some(status) = child.try_wait()? {
+            break status;
+        }
+        if !keep_going() {
+            let _ = kill(Pid::from_raw(-(child.id() as i32)), Signal::SIGKILL);
+            let _ = child.wait();
+            return Err(format!("winetricks {verb} was cancelled").into());
+        }
+        std::thread::sleep(INSTALL_POLL_INTERVAL);
+    };
+    if !status.success() {
+        // The tail of winetricks' output usually names the failed download or step.
+        let stderr = stderr
+            .and_then(|reader| reader.join().ok())
+            .unwrap_or_default();
+        let tail: Vec<&str> = stderr.lines().rev().take(3).collect();
+        return Err(format!(
+            "winetricks {verb} failed ({status}): {}",
+            tail.into_iter().rev().collect::<Vec<_>>().join(" / ")
+        )
+        .into());
+    }
+    Ok(())
+}
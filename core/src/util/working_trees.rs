@@ -0,0 +1,179 @@
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::{Condvar, Mutex};
+use std::thread;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
+
+use super::lock::ProfileLock;
+use crate::logging::{current_session_dir, log_info, log_warn};
+use crate::paths::PATH_APP;
+
+/// Written next to a working tree when it is built.
+const ORIGIN_FILE: &str = "origin.json";
+
+/// Whether the startup cleanup is still deleting trees, so a launch from this
+/// process doesn't build one it is about to delete.
+static CLEANING: (Mutex<bool>, Condvar) = (Mutex::new(false), Condvar::new());
+
+/// Session and game that built a working tree.
+#[derive(Serialize, Deserialize, Default)]
+#[serde(default)]
+struct TreeOrigin {
+    /// Log folder of the session, like `2025-06-01-20-15-03_Game`.
+    session: String,
+    game: String,
+    /// Unix time the tree was built.
+    created: u64,
+}
+
+/// A profile's folder under `run/`: the symlinked copy of the game its
+/// instance starts from, plus the config files patched for it.
+pub struct WorkingTree {
+    pub profile: String,
+    pub path: PathBuf,
+    /// Log folder of the session that built it; `None` for trees built before
+    /// this was recorded.
+    pub session: Option<String>,
+    pub game: Option<String>,
+    pub created: Option<u64>,
+    /// Links back to the game's files.
+    pub links: usize,
+    /// Size of the real files, which games wrote or the launcher patched.
+    pub bytes: u64,
+    /// A running session uses it, so it can't be deleted.
+    pub in_use: bool,
+}
+
+fn run_dir() -> PathBuf {
+    PATH_APP.join("run")
+}
+
+/// Profile folders under `run/`, leaving out the session locks.
+fn tree_profiles() -> Vec<String> {
+    let Ok(entries) = fs::read_dir(run_dir()) else {
+        return Vec::new();
+    };
+    let mut profiles: Vec<String> = entries
+        .flatten()
+        .filter(|entry| entry.file_type().is_ok_and(|kind| kind.is_dir()))
+        .map(|entry| entry.file_name().to_string_lossy().to_string())
+        .filter(|name| name != "locks")
+        .collect();
+    profiles.sort();
+    profiles
+}
+
+/// Records the running session and `game` as the origin of `profname`'s
+/// working tree.
+pub fn record_working_tree(profname: &str, game: &str) {
+    let origin = TreeOrigin {
+        session: current_session_dir()
+            .and_then(|dir| Some(dir.file_name()?.to_string_lossy().to_string()))
+            .unwrap_or_default(),
+        game: game.to_string(),
+        created: SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |elapsed| elapsed.as_secs()),
+    };
+    let path = run_dir().join(profname).join(ORIGIN_FILE);
+    let written = serde_json::to_string_pretty(&origin)
+        .map_err(io::Error::other)
+        .and_then(|json| fs::write(&path, json));
+    if let Err(err) = written {
+        log_warn(format!("Couldn't record the origin of {}: {err}", path.display()));
+    }
+}
+
+/// Counts the links and sums the real files under `path`.
+fn measure(path: &Path) -> (usize, u64) {
+    let mut links = 0;
+    let mut bytes = 0;
+    for entry in walkdir::WalkDir::new(path).into_iter().flatten() {
+        if entry.file_type().is_symlink() {
+            links += 1;
+        } else if entry.file_type().is_file() {
+            bytes += entry.metadata().map_or(0, |meta| meta.len());
+        }
+    }
+    (links, bytes)
+}
+
+/// Every working tree with where it came from and its size. Walks each tree,
+/// so it takes a moment for large games.
+pub fn list_working_trees() -> Vec<WorkingTree> {
+    tree_profiles()
+        .into_iter()
+        .map(|profile| {
+            let path = run_dir().join(&profile);
+            let origin = fs::read_to_string(path.join(ORIGIN_FILE))
+                .ok()
+                .and_then(|json| serde_json::from_str::<TreeOrigin>(&json).ok());
+            let (links, bytes) = measure(&path);
+            WorkingTree {
+                session: origin
+                    .as_ref()
+                    .map(|origin| origin.session.clone())
+                    .filter(|session| !session.is_empty()),
+                game: origin.as_ref().map(|origin| origin.game.clone()),
+                created: origin.map(|origin| origin.created),
+                links,
+                bytes,
+                in_use: ProfileLock::is_held(&profile),
+                profile,
+                path,
+            }
+        })
+        .collect()
+}
+
+/// Profiles whose working tree no running session uses.
+pub fn leftover_working_trees() -> Vec<String> {
+    tree_profiles()
+        .into_iter()
+        .filter(|profile| !ProfileLock::is_held(profile))
+        .collect()
+}
+
+/// Removes the working trees no running session uses on a background thread,
+/// so startup doesn't wait on large games. Launches wait for it through
+/// [`wait_for_tree_cleanup`].
+pub fn spawn_leftover_tree_cleanup() {
+    let leftovers = leftover_working_trees();
+    if leftovers.is_empty() {
+        return;
+    }
+    *CLEANING.0.lock().unwrap() = true;
+    thread::spawn(move || {
+        for profile in leftovers {
+            match remove_working_tree(&profile) {
+                Ok(()) => log_info(format!("Removed the leftover working tree of {profile}")),
+                Err(err) => {
+                    log_warn(format!("Couldn't remove the working tree of {profile}: {err}"))
+                }
+            }
+        }
+        *CLEANING.0.lock().unwrap() = false;
+        CLEANING.1.notify_all();
+    });
+}
+
+/// Blocks until a cleanup started by [`spawn_leftover_tree_cleanup`] is done.
+pub fn wait_for_tree_cleanup() {
+    let cleaning = CLEANING.0.lock().unwrap();
+    let _done = CLEANING.1.wait_while(cleaning, |cleaning| *cleaning).unwrap();
+}
+
+/// Deletes `profile`'s working tree. Refuses while a session uses it; the
+/// next launch builds it again.
+pub fn remove_working_tree(profile: &str) -> io::Result<()> {
+    if ProfileLock::is_held(profile) {
+        return Err(io::Error::new(
+            io::ErrorKind::ResourceBusy,
+            format!("{profile} is playing right now"),
+        ));
+    }
+    fs::remove_dir_all(run_dir().join(profile))
+}
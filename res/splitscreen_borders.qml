@@ -0,0 +1,92 @@
+import QtQuick
+import QtQuick.Window
+import QtQml
+import org.kde.kwin
+
+// Draws a thin colored frame over every gamescope window so each player can
+// tell which pane is theirs. Split Happens substitutes the per-player colors
+// and border width before loading this script.
+Item {
+    id: root
+
+    readonly property var playerColors: __PLAYER_COLORS__
+    readonly property int borderWidth: __BORDER_WIDTH__
+    // Windows of this session; other Split Happens sessions draw their own frames.
+    readonly property var ownPids: __OWN_PIDS__
+
+    // Slots keep their position when a window closes (null entry) so surviving
+    // players retain their color, matching the layout script's behaviour.
+    property var slots: []
+
+    function isGamescope(window) {
+        return window && (window.resourceClass == "gamescope" ||
+                          window.resourceClass == "gamescope-kbm") &&
+               ownPids.indexOf(window.pid) != -1;
+    }
+
+    function liveSlots() {
+        return slots.filter(function (window) { return window !== null; }).length;
+    }
+
+    // Accept the window parameter to match KWin's signal signature.
+    function windowAdded(window) {
+        if (!isGamescope(window) || slots.indexOf(window) != -1) {
+            return;
+        }
+        // Colors only cover this session's players.
+        if (liveSlots() >= playerColors.length) {
+            return;
+        }
+        var next = slots.slice();
+        next.push(window);
+        slots = next;
+    }
+
+    function windowRemoved(window) {
+        var index = slots.indexOf(window);
+        if (index == -1) {
+            return;
+        }
+        var next = slots.slice();
+        next[index] = null;
+        slots = next;
+    }
+
+    Component.onCompleted: {
+        var existing = Workspace.windowList();
+        for (var i = 0; i < existing.length; i++) {
+            windowAdded(existing[i]);
+        }
+    }
+
+    Connections {
+        target: Workspace
+        function onWindowAdded(window) { root.windowAdded(window); }
+        function onWindowRemoved(window) { root.windowRemoved(window); }
+    }
+
+    Instantiator {
+        model: root.slots
+        delegate: Window {
+            required property var modelData
+            required property int index
+
+            visible: modelData !== null
+            x: modelData ? modelData.frameGeometry.x : 0
+            y: modelData ? modelData.frameGeometry.y : 0
+            width: modelData ? modelData.frameGeometry.width : 1
+            height: modelData ? modelData.frameGeometry.height : 1
+            color: "transparent"
+            flags: Qt.FramelessWindowHint | Qt.WindowStaysOnTopHint |
+                   Qt.WindowTransparentForInput | Qt.WindowDoesNotAcceptFocus |
+                   Qt.BypassWindowManagerHint
+
+            Rectangle {
+                anchors.fill: parent
+                color: "transparent"
+                border.width: root.borderWidth
+                border.color: root.playerColors[index % root.playerColors.length]
+            }
+        }
+    }
+}
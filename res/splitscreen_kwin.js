@@ -1,25 +1,21 @@
 scrwidth = workspace.activeScreen.geometry.width;
 scrheight = workspace.activeScreen.geometry.height;
 
-Xpos_1p = [0];
-Ypos_1p = [0];
-Xsize_1p = [scrwidth];
-Ysize_1p = [scrheight];
+// Filled in by Split Happens: LAYOUTS[n] holds one [x, y, width, height]
+// region per player for n players, as fractions of the screen.
+var LAYOUTS = __PLAYER_LAYOUTS__;
 
-Xpos_2p = [0, 0];
-Ypos_2p = [0, scrheight / 2];
-Xsize_2p = [scrwidth, scrwidth];
-Ysize_2p = [scrheight / 2, scrheight / 2];
+// Filled in when players are pinned to displays: one absolute
+// [x, y, width, height] per player in launch order, spanning every screen.
+// null when everyone shares the active screen.
+var PLAYER_RECTS = __PLAYER_RECTS__;
 
-Xpos_3p = [0, 0, scrwidth / 2];
-Ypos_3p = [0, scrheight / 2, scrheight / 2];
-Xsize_3p = [scrwidth, scrwidth / 2, scrwidth / 2];
-Ysize_3p = [scrheight / 2, scrheight / 2, scrheight / 2];
+// Filled in by Split Happens: PIDs of this session's gamescope windows. Other
+// running sessions tile their own windows.
+var OWN_PIDS = __OWN_PIDS__;
 
-Xpos_4p = [0, scrwidth / 2, 0, scrwidth / 2];
-Ypos_4p = [0, 0, scrheight / 2, scrheight / 2];
-Xsize_4p = [scrwidth / 2, scrwidth / 2, scrwidth / 2, scrwidth / 2];
-Ysize_4p = [scrheight / 2, scrheight / 2, scrheight / 2, scrheight / 2];
+// Largest player count this session was set up for.
+var MAX_PLAYERS = LAYOUTS.length - 1;
 
 function getGamescopeClients() {
   var allClients = workspace.windowList();
@@ -27,13 +23,15 @@ function getGamescopeClients() {
 
   for (var i = 0; i < allClients.length; i++) {
     if (
-      allClients[i].resourceClass == "gamescope" ||
-      allClients[i].resourceClass == "gamescope-kbm"
+      (allClients[i].resourceClass == "gamescope" ||
+        allClients[i].resourceClass == "gamescope-kbm") &&
+      OWN_PIDS.indexOf(allClients[i].pid) != -1
     ) {
       gamescopeClients.push(allClients[i]);
     }
   }
-  return gamescopeClients;
+  // Never tile more windows than there are layouts for.
+  return gamescopeClients.slice(0, MAX_PLAYERS);
 }
 
 // Accept the activated window so the handler signature matches current KWin
@@ -57,42 +55,33 @@ function gamescopeAboveBelow(_activatedWindow) {
 function gamescopeSplitscreen(_addedWindow) {
   var gamescopeClients = getGamescopeClients();
 
-  switch (gamescopeClients.length) {
-    case 0:
-      return;
-    case 1:
-      var Xpos = Xpos_1p;
-      var Ypos = Ypos_1p;
-      var Xsize = Xsize_1p;
-      var Ysize = Ysize_1p;
-      break;
-    case 2:
-      var Xpos = Xpos_2p;
-      var Ypos = Ypos_2p;
-      var Xsize = Xsize_2p;
-      var Ysize = Ysize_2p;
-      break;
-    case 3:
-      var Xpos = Xpos_3p;
-      var Ypos = Ypos_3p;
-      var Xsize = Xsize_3p;
-      var Ysize = Ysize_3p;
-      break;
-    case 4:
-      var Xpos = Xpos_4p;
-      var Ypos = Ypos_4p;
-      var Xsize = Xsize_4p;
-      var Ysize = Ysize_4p;
-      break;
+  // Windows appear in launch order, so the n-th window belongs to player n.
+  if (PLAYER_RECTS && gamescopeClients.length <= PLAYER_RECTS.length) {
+    for (var p = 0; p < gamescopeClients.length; p++) {
+      gamescopeClients[p].noBorder = true;
+      gamescopeClients[p].frameGeometry = {
+        x: PLAYER_RECTS[p][0],
+        y: PLAYER_RECTS[p][1],
+        width: PLAYER_RECTS[p][2],
+        height: PLAYER_RECTS[p][3],
+      };
+    }
+    gamescopeAboveBelow();
+    return;
+  }
+
+  var layout = LAYOUTS[gamescopeClients.length];
+  if (!layout) {
+    return;
   }
 
   for (var i = 0; i < gamescopeClients.length; i++) {
     gamescopeClients[i].noBorder = true;
     gamescopeClients[i].frameGeometry = {
-      x: Xpos[i],
-      y: Ypos[i],
-      width: Xsize[i],
-      height: Ysize[i],
+      x: layout[i][0] * scrwidth,
+      y: layout[i][1] * scrheight,
+      width: layout[i][2] * scrwidth,
+      height: layout[i][3] * scrheight,
     };
   }
   gamescopeAboveBelow();
@@ -1,13 +1,33 @@
 use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
 use std::thread::sleep;
-
-use super::config::*;
+use std::time::SystemTime;
+
+use super::artwork::ArtworkCache;
+use super::calibration_wizard::CalibrationWizard;
+use super::cloud_export::CloudExport;
+use super::data_migration::SaveTransferChoice;
+use crate::config::*;
+use super::handler_browser::HandlerBrowser;
+use super::handler_editor::HandlerDraft;
+use super::instance_env::InstanceEnvDraft;
+use super::launch_options::{LaunchOptionsDraft, launch_history_id};
+use super::log_viewer::LogViewer;
+use super::ready_check::ReadyCheck;
+use super::resource_manager::ResourceManager;
+use super::session_panel::ActiveSession;
+use super::settings_widgets::launch_plan_button;
+use super::wizard::SetupWizard;
 use crate::game::Game::HandlerRef;
 use crate::game::*;
+use crate::handler::install_handler_from_file;
 use crate::input::*;
 use crate::instance::*;
 use crate::launch::launch_game;
+use crate::logging::{log_warn, set_author_mode};
 use crate::paths::*;
+use crate::session::SessionControl;
 use crate::util::*;
 
 use eframe::egui::{self, Key, StrokeKind};
@@ -19,10 +39,19 @@ pub enum MenuPage {
     Profiles,
     Game,
     Instances,
+    HandlerEditor,
+    HandlerBrowser,
+    Logs,
+    Remap,
+    Storage,
+    Character,
 }
 
 pub struct PartyApp {
     pub needs_update: bool,
+    /// Version and path of an update the install task finished, for the UI
+    /// thread to offer a restart into.
+    pub installed_update: Arc<Mutex<Option<(String, PathBuf)>>>,
     pub options: PartyConfig,
     pub cur_page: MenuPage,
     pub infotext: String,
@@ -34,6 +63,8 @@ pub struct PartyApp {
     pub selected_game: usize,
     pub profiles: Vec<String>,
     pub proton_versions: Vec<ProtonInstall>,
+    /// SteamGridDB artwork of the games, shown on the home tiles.
+    pub artwork: ArtworkCache,
 
     pub loading_msg: Option<String>,
     pub loading_since: Option<std::time::Instant>,
@@ -45,6 +76,9 @@ pub struct PartyApp {
     /// Tracks when the input list was last synchronized so new controllers can
     /// be discovered automatically without hammering the kernel every frame.
     pub last_input_scan: std::time::Instant,
+    /// Reports handler and profile folder edits made outside the app; `None`
+    /// when inotify isn't available, leaving the manual refresh.
+    pub library_watcher: Option<LibraryWatcher>,
     /// Remembers how many columns the home grid used during the last frame so
     /// D-pad navigation can move predictably between rows.
     pub home_grid_columns: usize,
@@ -72,6 +106,108 @@ pub struct PartyApp {
     /// Requests a scroll adjustment after focus changes so the highlighted
     /// element remains visible when navigating large forms with the D-pad.
     pub pending_scroll_to_focus: bool,
+    /// Latest LAN diagnostics sample plus the one before it, so the settings
+    /// card can show how much UDP traffic moved between refreshes.
+    pub net_snapshot: Option<NetworkSnapshot>,
+    pub net_previous: Option<NetworkSnapshot>,
+    /// LAN coordinator used to discover other Split Happens machines and
+    /// synchronize launch countdowns; `None` while coordinator mode is off.
+    pub coordinator: Option<Coordinator>,
+    /// Phone join page server; `None` while the web remote is off.
+    pub web_remote: Option<WebRemote>,
+    /// TV remote over HDMI-CEC; `None` while it is off or no adapter answered.
+    pub cec_remote: Option<CecRemote>,
+    /// Ready flags players set from their phones, one per instance.
+    pub remote_ready: Vec<bool>,
+    /// Guest profile whose save handoff QR code is shown.
+    pub guest_handoff: Option<String>,
+    /// Ready-check between pressing Start and launching, while it runs.
+    pub ready_check: Option<ReadyCheck>,
+    /// Draft shown on the handler editor page while creating or editing a
+    /// handler.json from inside the app.
+    pub handler_editor: Option<HandlerDraft>,
+    /// Repository index and install state for the handler browser page.
+    pub handler_browser: Option<HandlerBrowser>,
+    /// Session list and open file of the "View Logs" page.
+    pub log_viewer: Option<LogViewer>,
+    /// Unsaved edits in the game page's "Advanced Options" section.
+    pub launch_options: Option<LaunchOptionsDraft>,
+    /// Text of the per-instance "Env" sections on the Instances page.
+    pub instance_env: InstanceEnvDraft,
+    /// Launch history of the selected game, cached until the next session ends.
+    pub launch_history: Option<(String, Vec<LaunchRecord>)>,
+    /// Playtime per game and profile, cached until the next session ends.
+    pub play_stats: Option<PlayStats>,
+    /// Installed and upstream emulator builds for the game page.
+    pub resource_manager: Option<ResourceManager>,
+    /// Emulator config check of the instances page, with the handler uid and
+    /// profile names it ran for so it reruns when either changes.
+    pub emulator_status: Option<(String, Vec<String>, EmulatorStatus)>,
+    /// Handler uids with saves but no installed handler, scanned when the game
+    /// page first needs them; `None` until then.
+    pub orphaned_uids: Option<Vec<String>>,
+    /// Entry picked in the game page's "Migrate Data" list.
+    pub migration_choice: usize,
+    /// Source, profile and mode picked in the game page's "Share Saves" list.
+    pub save_transfer: SaveTransferChoice,
+    /// Profile, account and dry run of the game page's Steam Cloud export.
+    pub cloud_export: CloudExport,
+    /// Profile picked in the game page's "Quick Actions" section.
+    pub quick_action_profile: usize,
+    /// Keyboard shared by two players, re-exposed as two virtual keyboards
+    /// until the split is undone or the app closes.
+    pub keyboard_splitter: Option<KeyboardSplitter>,
+    /// Keyboard picked in the instances page's "Split Keyboard" list.
+    pub split_keyboard_choice: usize,
+    /// Connected displays for the instances page, listed when it first needs
+    /// them.
+    pub displays: Option<Vec<Display>>,
+    /// Open stick calibration wizard, if the user started one from the
+    /// device list.
+    pub calibration_wizard: Option<CalibrationWizard>,
+    /// First-run setup wizard, open on a fresh install until finished.
+    pub setup_wizard: Option<SetupWizard>,
+    /// System check results on the settings page, run when first shown.
+    pub system_checks: Option<Vec<SystemCheck>>,
+    /// Background run of the system checks, after a fix when one was picked.
+    pub system_checks_run: Option<std::thread::JoinHandle<Vec<SystemCheck>>>,
+    /// Test event on its way to the session webhook, with the error if it failed.
+    pub webhook_test: Option<std::thread::JoinHandle<Result<(), String>>>,
+    /// Per-profile input settings, loaded lazily when the profiles page shows them.
+    pub profile_inputs: HashMap<String, ProfileInputSettings>,
+    /// Profile whose pad remap the Remap page edits.
+    pub remap_profile: Option<String>,
+    /// Proton prefixes the Storage page lists, measured when it opens.
+    pub storage_prefixes: Option<Vec<ProtonPrefix>>,
+    /// Working trees under `run/` the Storage page lists.
+    pub storage_trees: Option<Vec<WorkingTree>>,
+    /// Background measurement of the working trees, see `start_tree_scan`.
+    pub storage_trees_scan: Option<std::thread::JoinHandle<Vec<WorkingTree>>>,
+    /// Leftover working trees found at startup, while asking whether to
+    /// remove them from now on.
+    pub leftover_trees_prompt: Option<usize>,
+    /// Profile whose figure the Character page edits.
+    pub character_profile: Option<String>,
+    /// Per-profile metadata edited on the profiles page, loaded lazily like
+    /// `profile_inputs`.
+    pub profile_meta: HashMap<String, Profile>,
+    /// Profiles whose colors were changed in `profile_meta` and are written
+    /// once the pointer is released, so a color picker drag saves only once.
+    pub profile_saves_pending: Vec<String>,
+    /// Profiles whose `profile_inputs` entry changed and is written once the
+    /// pointer is released, so dragging a slider saves input.json only once.
+    pub input_saves_pending: Vec<String>,
+    /// Saved launch presets shown on the home page, listed when first shown.
+    pub presets: Option<Vec<SessionPreset>>,
+    /// Running sessions, oldest first. The one still launching is the only
+    /// one without a thread handle; it is shared with `task` until then.
+    pub sessions: Vec<ActiveSession>,
+    /// Profiles offered by the session panel, with the profile folder's
+    /// modification time they were listed at.
+    pub session_profiles: (Option<SystemTime>, Vec<String>),
+    /// handler.json problems already shown, by handler uid, so a reload only
+    /// brings the dialog up again when they change.
+    pub reported_handler_issues: HashMap<String, String>,
 }
 
 macro_rules! cur_game {
@@ -91,9 +227,29 @@ impl PartyApp {
     /// main application can align frame pacing with the detected display.
     pub fn with_repaint_interval(repaint_interval: std::time::Duration) -> Self {
         let options = load_cfg();
+        // Set before the update check and handler scan below reach the network.
+        set_offline_mode(options.offline_mode);
+        set_author_mode(options.author_mode);
         let input_devices = scan_input_devices(&options.pad_filter_type);
-        Self {
+        let setup_wizard = (!options.setup_wizard_done).then(SetupWizard::new);
+        let coordinator = if options.coordinator_enabled {
+            start_coordinator()
+        } else {
+            None
+        };
+        let web_remote = if options.web_remote_enabled {
+            start_web_remote(options.web_remote_port)
+        } else {
+            None
+        };
+        let cec_remote = if options.cec_remote_enabled {
+            start_cec_remote()
+        } else {
+            None
+        };
+        let mut app = Self {
             needs_update: check_for_split_happens_update(),
+            installed_update: Arc::default(),
             options,
             cur_page: MenuPage::Home,
             infotext: String::new(),
@@ -104,11 +260,13 @@ impl PartyApp {
             selected_game: 0,
             profiles: Vec::new(),
             proton_versions: discover_proton_versions(),
+            artwork: ArtworkCache::default(),
             loading_msg: None,
             loading_since: None,
             task: None,
             repaint_interval,
             last_input_scan: std::time::Instant::now(),
+            library_watcher: start_library_watcher(),
             home_grid_columns: 1,
             pending_home_focus: true,
             pending_game_list_focus: false,
@@ -118,15 +276,129 @@ impl PartyApp {
             nav_selection: MenuPage::Home,
             pending_content_focus: false,
             pending_scroll_to_focus: false,
+            net_snapshot: None,
+            net_previous: None,
+            coordinator,
+            web_remote,
+            cec_remote,
+            remote_ready: Vec::new(),
+            guest_handoff: None,
+            ready_check: None,
+            handler_editor: None,
+            handler_browser: None,
+            log_viewer: None,
+            launch_options: None,
+            instance_env: InstanceEnvDraft::default(),
+            launch_history: None,
+            play_stats: None,
+            resource_manager: None,
+            emulator_status: None,
+            orphaned_uids: None,
+            migration_choice: 0,
+            save_transfer: SaveTransferChoice::default(),
+            cloud_export: CloudExport::default(),
+            quick_action_profile: 0,
+            keyboard_splitter: None,
+            split_keyboard_choice: 0,
+            displays: None,
+            calibration_wizard: None,
+            setup_wizard,
+            system_checks: None,
+            system_checks_run: None,
+            webhook_test: None,
+            profile_inputs: HashMap::new(),
+            remap_profile: None,
+            storage_prefixes: None,
+            storage_trees: None,
+            storage_trees_scan: None,
+            leftover_trees_prompt: None,
+            character_profile: None,
+            profile_meta: HashMap::new(),
+            profile_saves_pending: Vec::new(),
+            input_saves_pending: Vec::new(),
+            presets: None,
+            sessions: Vec::new(),
+            session_profiles: (None, Vec::new()),
+            reported_handler_issues: HashMap::new(),
+        };
+        app.refresh_artwork();
+        app.report_handler_issues();
+        // Once answered, the shared startup path in main removes them.
+        if app.options.clean_run_trees.is_none() {
+            app.leftover_trees_prompt = Some(leftover_working_trees().len()).filter(|n| *n > 0);
+        }
+        app
+    }
+}
+
+/// Starts watching the library folders, logging instead of failing so the
+/// manual refresh still works without inotify.
+fn start_library_watcher() -> Option<LibraryWatcher> {
+    match LibraryWatcher::start() {
+        Ok(watcher) => Some(watcher),
+        Err(err) => {
+            log_warn(format!("Couldn't watch the handler and profile folders: {err}"));
+            None
+        }
+    }
+}
+
+/// Starts listening to the TV remote, logging instead of failing when
+/// `cec-client` isn't installed.
+pub fn start_cec_remote() -> Option<CecRemote> {
+    match CecRemote::start() {
+        Ok(remote) => Some(remote),
+        Err(err) => {
+            log_warn(err.to_string());
+            None
+        }
+    }
+}
+
+/// Starts the web remote, logging instead of failing when the port is taken
+/// so the rest of the UI keeps working.
+pub fn start_web_remote(port: u16) -> Option<WebRemote> {
+    match WebRemote::start(port) {
+        Ok(remote) => Some(remote),
+        Err(err) => {
+            log_warn(err.to_string());
+            None
+        }
+    }
+}
+
+/// Starts the LAN coordinator, logging instead of failing when the port is
+/// unavailable so the rest of the UI keeps working.
+pub fn start_coordinator() -> Option<Coordinator> {
+    match Coordinator::start() {
+        Ok(coordinator) => Some(coordinator),
+        Err(err) => {
+            log_warn(err.to_string());
+            None
         }
     }
 }
 
 impl eframe::App for PartyApp {
     fn raw_input_hook(&mut self, _ctx: &egui::Context, raw_input: &mut egui::RawInput) {
+        // Only menu navigation reads TV remote keys. Discard them everywhere else,
+        // or keys pressed meanwhile would all fire at once later.
+        let cec_navigates = raw_input.focused
+            && self.task.is_none()
+            && self.ready_check.is_none()
+            && self.cur_page != MenuPage::Instances;
+        if !cec_navigates {
+            if let Some(remote) = &self.cec_remote {
+                remote.take_keys();
+            }
+        }
         if !raw_input.focused || self.task.is_some() {
             return;
         }
+        if self.ready_check.is_some() {
+            self.handle_ready_check_input();
+            return;
+        }
         match self.cur_page {
             MenuPage::Instances => self.handle_devices_instance_menu(),
             _ => self.handle_gamepad_gui(raw_input),
@@ -138,6 +410,23 @@ impl eframe::App for PartyApp {
         // without requiring the user to mash the manual rescan button.
         self.maybe_refresh_input_devices();
 
+        // Pick up handlers and profiles changed on disk since the last frame.
+        self.apply_library_changes();
+
+        // Keep peers informed and fire synchronized launches when due.
+        self.update_coordinator(ctx);
+
+        // Sync the instances page with players' phones.
+        self.update_web_remote(ctx);
+
+        // Show SteamGridDB artwork as soon as it's downloaded.
+        self.poll_artwork_fetch(ctx);
+
+        // Write profile colors and input settings once a drag is over.
+        self.save_pending_profiles(ctx);
+
+        self.offer_update_restart(ctx);
+
         if self.needs_viewport_focus {
             ctx.send_viewport_cmd(egui::ViewportCommand::Focus);
             self.needs_viewport_focus = false;
@@ -160,28 +449,88 @@ impl eframe::App for PartyApp {
                 MenuPage::Profiles => self.display_page_profiles(ui),
                 MenuPage::Game => self.display_page_game(ui),
                 MenuPage::Instances => self.display_page_instances(ui),
+                MenuPage::HandlerEditor => self.display_page_handler_editor(ui),
+                MenuPage::HandlerBrowser => self.display_page_handler_browser(ui),
+                MenuPage::Logs => self.display_page_logs(ui),
+                MenuPage::Remap => self.display_page_remap(ui),
+                MenuPage::Storage => self.display_page_storage(ui),
+                MenuPage::Character => self.display_page_character(ui),
             }
         });
 
+        self.display_calibration_wizard(ctx);
+
+        self.display_setup_wizard(ctx);
+
+        self.display_guest_handoff(ctx);
+
+        self.display_guest_uploads(ctx);
+
+        self.display_leftover_trees_prompt(ctx);
+
+        self.display_ready_check(ctx);
+
+        self.display_session_panel(ctx);
+
+        launch_plan_button(
+            ctx,
+            self.games.get(self.selected_game),
+            &self.instances,
+            &self.input_devices,
+            &self.options,
+        );
+
+        // Warnings the launch threads left for the user.
+        for active in &self.sessions {
+            for (title, text) in active.control.take_notices() {
+                msg(&title, &text);
+            }
+        }
+
+        self.adopt_launched_session();
         if let Some(handle) = self.task.take() {
             if handle.is_finished() {
                 let _ = handle.join();
                 self.loading_since = None;
                 self.loading_msg = None;
+                // A launch that ends before handing over failed or was cancelled.
+                if let Some(index) = self.sessions.iter().position(|s| s.handle.is_none()) {
+                    let failed = self.sessions.remove(index);
+                    self.launch_history = None;
+                    self.play_stats = None;
+                    // A launch the user stopped didn't crash.
+                    if !failed.control.is_stop_requested() {
+                        self.offer_launch_rollback(&failed.game);
+                    }
+                }
             } else {
                 self.task = Some(handle);
             }
         }
+        self.reap_ended_sessions();
         if let Some(start) = self.loading_since {
-            if start.elapsed() > std::time::Duration::from_secs(60) {
-                // Give up waiting after one minute
-                self.loading_msg = Some("Operation timed out".to_string());
+            // A launch is timed from its last progress report, so a long launch
+            // that keeps moving isn't cut off.
+            let launching = self.sessions.iter().find(|active| active.handle.is_none());
+            let stalled = match launching {
+                Some(launching) => launching.control.since_heartbeat(),
+                None => start.elapsed(),
+            };
+            // A stop gives instances a grace period to exit, without heartbeats.
+            let stopping = launching.is_some_and(|active| active.control.is_stop_requested());
+            if stalled > self.options.timings.task_timeout() && !stopping {
+                self.abandon_timed_out_task();
             }
         }
+        let mut stop_clicked = false;
         if let Some(msg) = &self.loading_msg {
+            // A launch reports long preparation steps, such as linking game files.
+            let launching = self.sessions.iter().find(|active| active.handle.is_none());
+            let progress = launching.and_then(|active| active.control.progress());
+            let stoppable = launching.is_some_and(|active| !active.control.is_stop_requested());
             egui::Area::new("loading".into())
                 .anchor(egui::Align2::CENTER_CENTER, egui::Vec2::ZERO)
-                .interactable(false)
+                .interactable(stoppable)
                 .show(ctx, |ui| {
                     egui::Frame::NONE
                         .fill(egui::Color32::from_rgba_premultiplied(0, 0, 0, 192))
@@ -192,10 +541,27 @@ impl eframe::App for PartyApp {
                                 ui.add(egui::widgets::Spinner::new().size(40.0));
                                 ui.add_space(8.0);
                                 ui.label(msg);
+                                if let Some(progress) = &progress {
+                                    ui.label(egui::RichText::new(progress).small());
+                                }
+                                // Lets a slow or stuck launch be ended without a terminal.
+                                if stoppable {
+                                    ui.add_space(8.0);
+                                    stop_clicked = ui
+                                        .button("Stop Session")
+                                        .on_hover_text("Cancels the launch and closes the instances it already started, killing any that don't quit within 10 seconds.")
+                                        .clicked();
+                                }
                             });
                         });
                 });
         }
+        if stop_clicked {
+            if let Some(active) = self.sessions.iter().find(|active| active.handle.is_none()) {
+                active.control.request_stop();
+            }
+            self.loading_msg = Some("Stopping...".to_string());
+        }
         if ctx.input(|input| input.focused) {
             ctx.request_repaint_after(self.repaint_interval);
         }
@@ -203,6 +569,44 @@ impl eframe::App for PartyApp {
 }
 
 impl PartyApp {
+    /// Recorded playtime, loaded on first use.
+    pub fn play_stats(&mut self) -> &PlayStats {
+        self.play_stats.get_or_insert_with(PlayStats::load)
+    }
+
+    /// Writes the colors, characters and EOS locales of
+    /// `profile_saves_pending` from `profile_meta` and the input settings of
+    /// `input_saves_pending` to disk, unless a pointer button is still held.
+    pub fn save_pending_profiles(&mut self, ctx: &egui::Context) {
+        let idle = self.profile_saves_pending.is_empty() && self.input_saves_pending.is_empty();
+        if idle || ctx.input(|i| i.pointer.any_down()) {
+            return;
+        }
+        for profile in std::mem::take(&mut self.input_saves_pending) {
+            let Some(settings) = self.profile_inputs.get(&profile) else {
+                continue;
+            };
+            if let Err(err) = save_profile_input(&profile, settings) {
+                msg("Error", &format!("Couldn't save input settings: {err}"));
+            }
+        }
+        for profile in std::mem::take(&mut self.profile_saves_pending) {
+            let Some(meta) = self.profile_meta.get(&profile) else {
+                continue;
+            };
+            // Reload first so play stats the launcher wrote aren't overwritten.
+            let mut fresh = Profile::load(&profile);
+            fresh.color = meta.color.clone();
+            fresh.set_character(meta.character().as_ref());
+            fresh.language = meta.language.clone();
+            fresh.country = meta.country.clone();
+            if let Err(err) = fresh.save() {
+                msg("Error", &format!("Couldn't save profile: {err}"));
+            }
+            self.profile_meta.insert(profile, fresh);
+        }
+    }
+
     /// Highlights the active widget and manages focus/scroll bookkeeping so
     /// controller navigation remains visible across scrollable layouts.
     pub fn decorate_focus(&mut self, ui: &mut egui::Ui, response: &egui::Response) {
@@ -254,6 +658,56 @@ impl PartyApp {
         self.pending_nav_focus = true;
     }
 
+    /// Runs what a Steam Deck rear button is bound to in the settings.
+    fn run_rear_button_action(&mut self, action: RearButtonAction) {
+        match action {
+            RearButtonAction::None => {}
+            RearButtonAction::PreviousPage | RearButtonAction::NextPage => {
+                let step = if action == RearButtonAction::NextPage { 1 } else { -1 };
+                let nav_order = [MenuPage::Home, MenuPage::Settings, MenuPage::Profiles];
+                // Pages outside the header, like a game's, count as Home.
+                let current = nav_order
+                    .iter()
+                    .position(|page| *page == self.cur_page)
+                    .unwrap_or(0) as i32;
+                let next = (current + step).rem_euclid(nav_order.len() as i32);
+                self.nav_selection = nav_order[next as usize];
+                self.activate_nav_selection();
+            }
+            RearButtonAction::Rescan => {
+                self.reload_games();
+                self.sync_input_devices();
+                self.profiles = scan_profiles(self.cur_page != MenuPage::Profiles);
+            }
+            RearButtonAction::LaunchLast => self.launch_last_session(),
+        }
+    }
+
+    /// Opens the game launched most recently with its remembered players and
+    /// starts it. Stays on the Instances page when none of its pads are
+    /// connected.
+    fn launch_last_session(&mut self) {
+        if self.task.is_some() {
+            return;
+        }
+        let latest = self
+            .games
+            .iter()
+            .enumerate()
+            .filter_map(|(index, game)| {
+                let history = load_launch_history(&launch_history_id(game));
+                Some((history.iter().map(|record| record.timestamp).max()?, index))
+            })
+            .max();
+        let Some((_, index)) = latest else {
+            return;
+        };
+        self.open_instances_for(index);
+        if !self.instances.is_empty() {
+            self.prepare_game_launch();
+        }
+    }
+
     /// Applies the currently highlighted navigation selection and prepares the
     /// destination page so controller focus begins at the first actionable
     /// element instead of auto-activating headers.
@@ -277,7 +731,14 @@ impl PartyApp {
                 self.pending_content_focus = true;
                 self.pending_scroll_to_focus = true;
             }
-            MenuPage::Game | MenuPage::Instances => {
+            MenuPage::Game
+            | MenuPage::Instances
+            | MenuPage::HandlerEditor
+            | MenuPage::HandlerBrowser
+            | MenuPage::Logs
+            | MenuPage::Remap
+            | MenuPage::Storage
+            | MenuPage::Character => {
                 self.cur_page = target;
                 self.pending_content_focus = true;
                 self.pending_scroll_to_focus = true;
@@ -289,6 +750,158 @@ impl PartyApp {
         self.pending_nav_focus = false;
     }
 
+    /// Advertises the game currently being set up to coordinator peers and
+    /// launches it once a matching countdown expires.
+    fn update_coordinator(&mut self, ctx: &egui::Context) {
+        let Some(coordinator) = &self.coordinator else {
+            return;
+        };
+        if self.games.is_empty() {
+            coordinator.set_hosting(None);
+            return;
+        }
+
+        let game_uid = cur_game!(self).persistent_id();
+        if self.cur_page == MenuPage::Instances && self.task.is_none() {
+            coordinator.set_hosting(Some((
+                cur_game!(self).name(),
+                game_uid.as_str(),
+                self.instances.len(),
+            )));
+        } else {
+            coordinator.set_hosting(None);
+        }
+
+        let Some(countdown) = coordinator.countdown() else {
+            return;
+        };
+        if countdown.launch_at > std::time::Instant::now() {
+            // Keep repainting so the countdown label ticks down smoothly.
+            ctx.request_repaint_after(std::time::Duration::from_millis(200));
+            return;
+        }
+
+        coordinator.clear_countdown();
+        // Only join launches the host agreed to, for the game this machine has set up.
+        if countdown.accepted
+            && countdown.game_uid == game_uid
+            && self.cur_page == MenuPage::Instances
+            && !self.instances.is_empty()
+            && self.task.is_none()
+        {
+            // A ready-check would break the shared countdown.
+            self.launch_with_resolutions(None);
+        }
+    }
+
+    /// Publishes the instances page to the web remote and applies the profile
+    /// picks and ready toggles players sent from their phones.
+    fn update_web_remote(&mut self, ctx: &egui::Context) {
+        let Some(remote) = &self.web_remote else {
+            return;
+        };
+        if self.cur_page != MenuPage::Instances || self.task.is_some() || self.games.is_empty() {
+            remote.set_join_screen(None);
+            // Taps made while no join screen was shown no longer apply.
+            remote.take_actions();
+            self.remote_ready.clear();
+            return;
+        }
+
+        self.remote_ready.resize(self.instances.len(), false);
+        let uses_profiles = matches!(cur_game!(self), HandlerRef(_));
+        for action in remote.take_actions() {
+            match action {
+                RemoteAction::SetProfile { slot, profile }
+                    if uses_profiles && profile < self.profiles.len() =>
+                {
+                    if let Some(instance) = self.instances.get_mut(slot) {
+                        instance.profselection = profile;
+                    }
+                }
+                RemoteAction::SetProfile { .. } => {}
+                RemoteAction::SetReady { slot, ready } => {
+                    if let Some(flag) = self.remote_ready.get_mut(slot) {
+                        *flag = ready;
+                    }
+                }
+            }
+        }
+
+        let players = self
+            .instances
+            .iter()
+            .zip(&self.remote_ready)
+            .map(|(instance, &ready)| RemotePlayer {
+                devices: instance
+                    .devices
+                    .iter()
+                    .filter_map(|&dev| self.input_devices.get(dev))
+                    .map(|dev| dev.fancyname())
+                    .collect::<Vec<&str>>()
+                    .join(", "),
+                profile: instance.profselection,
+                ready,
+            })
+            .collect();
+        remote.set_join_screen(Some(JoinScreen {
+            game: cur_game!(self).name().to_string(),
+            profiles: if uses_profiles { self.profiles.clone() } else { Vec::new() },
+            players,
+        }));
+        // Phones change the page without any local input, so keep polling.
+        ctx.request_repaint_after(std::time::Duration::from_millis(250));
+    }
+
+    /// Downloads and installs the newest release in the background after
+    /// asking, then offers to restart into it.
+    pub fn install_update(&mut self) {
+        if !self.sessions.is_empty() {
+            msg("Update", "Stop the running games before updating Split Happens.");
+            return;
+        }
+        if !yesno(
+            "Update Split Happens",
+            "Download the latest release, check its checksum and replace this installation?",
+        ) {
+            return;
+        }
+        let installed_update = Arc::clone(&self.installed_update);
+        self.spawn_task("Installing update", move || {
+            let installed = find_release_update().and_then(|update| match update {
+                Some(update) => {
+                    install_release_update(&update).map(|target| Some((update.version, target)))
+                }
+                None => Ok(None),
+            });
+            match installed {
+                Ok(Some((version, target))) => {
+                    *installed_update.lock().unwrap() = Some((version.to_string(), target));
+                }
+                Ok(None) => msg("Update", "Split Happens is already up to date."),
+                Err(err) => msg("Update Error", &format!("Couldn't install the update: {err}")),
+            }
+        });
+    }
+
+    /// Offers to restart into an update the install task finished. Quits
+    /// through the window, so the app shuts down like on any other close.
+    fn offer_update_restart(&mut self, ctx: &egui::Context) {
+        let Some((version, target)) = self.installed_update.lock().unwrap().take() else {
+            return;
+        };
+        if !yesno(
+            "Update installed",
+            &format!("Split Happens v{version} is installed. Restart now?"),
+        ) {
+            return;
+        }
+        match relaunch(&target) {
+            Ok(()) => ctx.send_viewport_cmd(egui::ViewportCommand::Close),
+            Err(err) => msg("Update Error", &format!("Couldn't restart: {err}")),
+        }
+    }
+
     pub fn spawn_task<F>(&mut self, msg: &str, f: F)
     where
         F: FnOnce() + Send + 'static,
@@ -298,6 +911,32 @@ impl PartyApp {
         self.task = Some(std::thread::spawn(f));
     }
 
+    /// Stops waiting on a task that outlived the task timeout and gives the
+    /// UI back. A launch is cancelled at its next checkpoint and stops the
+    /// instances it already started; other tasks finish in the background.
+    fn abandon_timed_out_task(&mut self) {
+        self.loading_msg = None;
+        self.loading_since = None;
+        // Dropping the handle detaches the thread instead of blocking on it.
+        self.task = None;
+        let timeout = self.options.timings.task_timeout_secs;
+        let Some(index) = self.sessions.iter().position(|active| active.handle.is_none()) else {
+            log_warn(format!(
+                "A task ran longer than {timeout} s; it continues in the background."
+            ));
+            return;
+        };
+        let session = self.sessions.remove(index).control;
+        session.cancel();
+        log_warn(format!("Launch cancelled after making no progress for {timeout} s."));
+        msg(
+            "Launch Timed Out",
+            &format!(
+                "The launch made no progress for {timeout} seconds and was cancelled. Instances it already started are being stopped."
+            ),
+        );
+    }
+
     fn handle_gamepad_gui(&mut self, raw_input: &mut egui::RawInput) {
         let mut keypress: Option<egui::Key> = None;
         let mut trigger_instances = false;
@@ -308,13 +947,46 @@ impl PartyApp {
         // finishes so the borrow checker can release the mutable slice borrow
         // from `self.input_devices` before we mutate other fields.
         let mut activate_nav_after_poll = false;
-
+        // Pad that navigated last and what it did, answered with a click or rumble.
+        // The TV remote has no pad to rumble.
+        let mut feedback: Option<(Option<usize>, NavFeedback)> = None;
+        // Run after polling, since a rescan replaces `self.input_devices`.
+        let mut rear_action: Option<RearButtonAction> = None;
+        let mut preset_key: Option<usize> = None;
+
+        let mut events: Vec<(Option<usize>, Option<PadButton>)> = Vec::new();
         for pad_index in 0..self.input_devices.len() {
-            if !self.input_devices[pad_index].enabled() {
-                continue;
+            if self.input_devices[pad_index].enabled() {
+                events.push((Some(pad_index), self.input_devices[pad_index].poll()));
+            }
+        }
+        // TV remote keys take the same path as pad buttons.
+        if let Some(remote) = &self.cec_remote {
+            for key in remote.take_keys() {
+                match key {
+                    CecKey::Nav(button) => events.push((None, Some(button))),
+                    CecKey::Preset(index) => preset_key = Some(index),
+                }
             }
+        }
 
-            let event = self.input_devices[pad_index].poll();
+        for (pad_index, event) in events {
+            match event {
+                Some(PadButton::ABtn | PadButton::StartBtn) => {
+                    feedback = Some((pad_index, NavFeedback::Confirm));
+                }
+                Some(
+                    PadButton::BBtn
+                    | PadButton::XBtn
+                    | PadButton::YBtn
+                    | PadButton::SelectBtn
+                    | PadButton::Up
+                    | PadButton::Down
+                    | PadButton::Left
+                    | PadButton::Right,
+                ) => feedback = Some((pad_index, NavFeedback::Move)),
+                _ => {}
+            }
             match event {
                 Some(PadButton::ABtn) => {
                     if self.nav_in_focus {
@@ -362,6 +1034,10 @@ impl PartyApp {
                 Some(PadButton::Down) => vertical += 1,
                 Some(PadButton::Left) => horizontal -= 1,
                 Some(PadButton::Right) => horizontal += 1,
+                Some(PadButton::L4Btn) => rear_action = Some(self.options.rear_buttons.l4),
+                Some(PadButton::L5Btn) => rear_action = Some(self.options.rear_buttons.l5),
+                Some(PadButton::R4Btn) => rear_action = Some(self.options.rear_buttons.r4),
+                Some(PadButton::R5Btn) => rear_action = Some(self.options.rear_buttons.r5),
                 Some(_) => {}
                 None => {}
             }
@@ -370,6 +1046,22 @@ impl PartyApp {
         if activate_nav_after_poll {
             self.activate_nav_selection();
         }
+        if let Some(action) = rear_action {
+            self.run_rear_button_action(action);
+        }
+        if let Some(index) = preset_key {
+            self.launch_preset_key(index);
+        }
+
+        if let Some((pad_index, feedback)) = feedback {
+            if self.options.nav_sounds {
+                play_nav_sound(feedback);
+            }
+            if let Some(pad_index) = pad_index.filter(|_| self.options.nav_haptics) {
+                let (strong, weak, length_ms) = feedback.rumble();
+                self.input_devices[pad_index].rumble_pulse(strong, weak, length_ms);
+            }
+        }
 
         let mut tab_forward = 0i32;
         let mut tab_backward = 0i32;
@@ -623,6 +1315,37 @@ impl PartyApp {
         }
 
         self.games = refreshed;
+        self.orphaned_uids = None;
+        self.refresh_artwork();
+        self.report_handler_issues();
+    }
+
+    /// Shows a dialog listing handler.json problems of the loaded handlers
+    /// that haven't been shown yet, so authors catch typos like `game.exce`.
+    fn report_handler_issues(&mut self) {
+        let mut report = Vec::new();
+        for game in &self.games {
+            let HandlerRef(handler) = game else {
+                continue;
+            };
+            if handler.schema_issues.is_empty() {
+                self.reported_handler_issues.remove(&handler.uid);
+                continue;
+            }
+            let issues = format_handler_issues(&handler.schema_issues);
+            if self.reported_handler_issues.get(&handler.uid) == Some(&issues) {
+                continue;
+            }
+            report.push(format!(
+                "{} ({}):\n{issues}",
+                handler.display(),
+                handler.path_handler.join("handler.json").display()
+            ));
+            self.reported_handler_issues.insert(handler.uid.clone(), issues);
+        }
+        if !report.is_empty() {
+            msg("Handler problems", &report.join("\n\n"));
+        }
     }
 
     /// Routes the user to the instance assignment screen for the selected tile
@@ -636,6 +1359,7 @@ impl PartyApp {
         self.instances.clear();
         self.profiles = scan_profiles(true);
         self.instance_add_dev = None;
+        self.restore_device_assignments();
         self.pending_game_list_focus = true;
         self.cur_page = MenuPage::Instances;
         self.nav_selection = MenuPage::Home;
@@ -645,45 +1369,6 @@ impl PartyApp {
         self.pending_scroll_to_focus = true;
     }
 
-    /// Returns the Proton installation that matches the current settings
-    /// value, accounting for the implicit GE-Proton default.
-    pub fn selected_proton_install(&self) -> Option<&ProtonInstall> {
-        let trimmed = self.options.proton_version.trim();
-        if trimmed.is_empty() {
-            return self
-                .proton_versions
-                .iter()
-                .find(|install| install.matches("GE-Proton"));
-        }
-
-        self.proton_versions
-            .iter()
-            .find(|install| install.matches(trimmed))
-    }
-
-    /// Generates the label shown in the Proton selection combo box so users can
-    /// easily identify whether a custom path or a discovered build is active.
-    pub fn proton_dropdown_label(&self) -> String {
-        if let Some(install) = self.selected_proton_install() {
-            return install.display_label();
-        }
-
-        let trimmed = self.options.proton_version.trim();
-        if trimmed.is_empty() {
-            if self
-                .proton_versions
-                .iter()
-                .any(|install| install.matches("GE-Proton"))
-            {
-                "Auto (GE-Proton)".to_string()
-            } else {
-                "Auto (GE-Proton missing)".to_string()
-            }
-        } else {
-            format!("Custom: {trimmed}")
-        }
-    }
-
     fn handle_devices_instance_menu(&mut self) {
         let mut i = 0;
         while i < self.input_devices.len() {
@@ -710,15 +1395,7 @@ impl PartyApp {
                             // Restore the last-used profile for this slot when starting a
                             // fresh instance so the join screen remembers previous
                             // assignments per game.
-                            let slot_index = self.instances.len();
-                            let default_profile = self.default_profile_index_for_slot(slot_index);
-                            self.instances.push(Instance {
-                                devices: vec![i],
-                                profname: String::new(),
-                                profselection: default_profile,
-                                width: 0,
-                                height: 0,
-                            });
+                            self.push_instance_slot(vec![i]);
                         }
                     }
                 }
@@ -792,6 +1469,57 @@ impl PartyApp {
         0
     }
 
+    /// Gives every connected gamepad its own slot in the configured order,
+    /// replacing the current instances.
+    pub fn auto_assign_instances(&mut self) {
+        let pads = auto_assign_devices(&self.input_devices, self.options.auto_assign_order);
+        self.instances.clear();
+        self.instance_add_dev = None;
+        for dev in pads {
+            self.push_instance_slot(vec![dev]);
+        }
+        place_host_in_first_slot(&mut self.instances, &self.profiles);
+    }
+
+    /// Recreates the controller slots of the selected game's last launch,
+    /// skipping pads that aren't connected.
+    fn restore_device_assignments(&mut self) {
+        if !self.options.remember_device_assignments {
+            return;
+        }
+        let game_id = cur_game!(self).persistent_id();
+        let Some(saved) = self.options.last_device_assignments.get(&game_id) else {
+            return;
+        };
+        for devices in restore_device_slots(&self.input_devices, saved) {
+            self.push_instance_slot(devices);
+        }
+        place_host_in_first_slot(&mut self.instances, &self.profiles);
+    }
+
+    /// Moves the profile marked "Always player 1" into slot 1 with its
+    /// devices, shifting the players before it down. Returns the slot it
+    /// came from, if it moved.
+    pub fn move_host_to_first_slot(&mut self) -> Option<usize> {
+        let slot = misplaced_host_slot(&self.instances, &self.profiles)?;
+        self.instances[..=slot].rotate_right(1);
+        Some(slot)
+    }
+
+    fn push_instance_slot(&mut self, devices: Vec<usize>) {
+        let profselection = self.default_profile_index_for_slot(self.instances.len());
+        self.instances.push(Instance {
+            devices,
+            profname: String::new(),
+            profselection,
+            width: 0,
+            height: 0,
+            monitor: None,
+            motion_passthrough: true,
+            platform: InstancePlatform::Handler,
+        });
+    }
+
     pub fn remove_device(&mut self, dev: usize) {
         if let Some((instance_index, device_index)) = self.find_device_in_instance(dev) {
             self.remove_device_at(instance_index, device_index);
@@ -811,7 +1539,7 @@ impl PartyApp {
 
     /// Prunes stale instance assignments and remaps surviving devices after a
     /// background rescan so controller indices stay consistent.
-    fn sync_input_devices(&mut self) {
+    pub fn sync_input_devices(&mut self) {
         let old_paths: Vec<String> = self
             .input_devices
             .iter()
@@ -867,8 +1595,147 @@ impl PartyApp {
         self.sync_input_devices();
     }
 
-    pub fn prepare_game_launch(&mut self) {
+    /// Reloads the library for handler edits made outside the app and
+    /// installs `.pdh` archives dropped into the handlers folder.
+    fn apply_library_changes(&mut self) {
+        let Some(watcher) = &self.library_watcher else {
+            return;
+        };
+        let mut reload = false;
+        for change in watcher.poll() {
+            match change {
+                LibraryChange::Handlers => reload = true,
+                LibraryChange::HandlerArchive(path) => match install_handler_from_file(&path) {
+                    Ok(anticheat) => {
+                        // The handler now lives in its own folder.
+                        if let Err(err) = std::fs::remove_file(&path) {
+                            log_warn(format!("Couldn't remove {}: {err}", path.display()));
+                        }
+                        if let Some(warning) = anticheat {
+                            msg(ANTICHEAT_TITLE, &warning);
+                        }
+                        reload = true;
+                    }
+                    Err(err) => log_warn(format!("Couldn't install {}: {err}", path.display())),
+                },
+                LibraryChange::Profiles => self.reload_profiles(),
+            }
+        }
+        if reload {
+            self.reload_games();
+        }
+    }
+
+    /// Rescans the profile list, keeping each instance on the profile it had
+    /// picked even when the list order changed.
+    fn reload_profiles(&mut self) {
+        // Only the profiles page lists profiles without the guest entry.
+        let include_guest = self.cur_page != MenuPage::Profiles;
+        let previous = std::mem::replace(&mut self.profiles, scan_profiles(include_guest));
+        for instance in &mut self.instances {
+            instance.profselection = previous
+                .get(instance.profselection)
+                .and_then(|name| self.profiles.iter().position(|profile| profile == name))
+                .unwrap_or(0);
+        }
+    }
+
+    /// Profile names the instances will launch with, guests numbered the way
+    /// the launch numbers them.
+    pub fn resolved_profile_names(&self) -> Vec<String> {
+        let mut instances = self.instances.clone();
+        set_instance_names(&mut instances, &self.profiles);
+        instances.into_iter().map(|instance| instance.profname).collect()
+    }
+
+    /// Launches the current instances. `resolutions`, one per instance,
+    /// replace the computed ones, e.g. when a preset is relaunched.
+    pub fn launch_with_resolutions(&mut self, resolutions: Option<Vec<(u32, u32)>>) {
+        // Give the user a chance to back out before oversubscribing RAM and swap.
+        let memory_mb = match cur_game!(self) {
+            HandlerRef(h) => h.memory_mb,
+            _ => None,
+        };
+        if let Some(warning) = memory_pressure_warning(self.instances.len(), memory_mb) {
+            if !yesno("Low memory", &format!("{warning}\n\nLaunch anyway?")) {
+                return;
+            }
+        }
+
+        // Duplicate emulator identities and taken ports only show up later as
+        // games that can't see each other's lobbies, so stop here first.
+        if let HandlerRef(h) = cur_game!(self) {
+            let status = emulator_status(h, &self.resolved_profile_names());
+            if status.worst() == Some(EmuSeverity::Conflict) {
+                let conflicts: Vec<&str> = status
+                    .issues
+                    .iter()
+                    .filter(|issue| issue.severity == EmuSeverity::Conflict)
+                    .map(|issue| issue.message.as_str())
+                    .collect();
+                if !yesno(
+                    "Emulator conflicts",
+                    &format!("{}\n\nLaunch anyway?", conflicts.join("\n")),
+                ) {
+                    return;
+                }
+            }
+        }
+
+        // A device already playing in another running game would drive both.
+        let busy: Vec<String> = self
+            .sessions
+            .iter()
+            .flat_map(|active| active.control.slots())
+            .flat_map(|slot| slot.devices)
+            .collect();
+        let shared: Vec<&str> = self
+            .instances
+            .iter()
+            .flat_map(|instance| instance.devices.iter())
+            .filter_map(|&dev| self.input_devices.get(dev))
+            .filter(|dev| busy.iter().any(|path| path == dev.path()))
+            .map(|dev| dev.fancyname())
+            .collect();
+        if !shared.is_empty()
+            && !yesno(
+                "Devices in use",
+                &format!(
+                    "{} already play in a running session.\n\nLaunch anyway?",
+                    shared.join(", ")
+                ),
+            )
+        {
+            return;
+        }
+
+        // Offer to open the handler's LAN ports when a host firewall would drop
+        // discovery traffic; the rules are reverted once the session ends.
+        let mut firewall_request = None;
+        if let HandlerRef(h) = cur_game!(self) {
+            if !h.firewall_ports.is_empty() {
+                if let Some(backend) = detect_firewall() {
+                    if yesno(
+                        "Open firewall ports?",
+                        &format!(
+                            "{} is active and this game needs {} for LAN discovery.\n\nOpen them for this session?",
+                            backend.name(),
+                            h.firewall_ports.join(", ")
+                        ),
+                    ) {
+                        firewall_request = Some((backend, h.firewall_ports.clone()));
+                    }
+                }
+            }
+        }
+
         set_instance_resolutions(&mut self.instances, &self.options);
+        if let Some(resolutions) = resolutions.filter(|r| r.len() == self.instances.len()) {
+            for (instance, (width, height)) in self.instances.iter_mut().zip(resolutions) {
+                instance.width = width;
+                instance.height = height;
+            }
+        }
 
         if let HandlerRef(_) = cur_game!(self) {
             // Remember the raw profile selections for this game before translating
@@ -890,12 +1757,25 @@ impl PartyApp {
 
         set_instance_names(&mut self.instances, &self.profiles);
 
+        if self.options.remember_device_assignments {
+            let assignments = device_slot_identities(&self.instances, &self.input_devices);
+            self.options
+                .last_device_assignments
+                .insert(cur_game!(self).persistent_id(), assignments);
+        }
+
         let game = cur_game!(self).to_owned();
         let instances = self.instances.clone();
         let dev_infos: Vec<DeviceInfo> = self.input_devices.iter().map(|p| p.info()).collect();
 
-        let cfg = self.options.clone();
-        let _ = save_cfg(&cfg);
+        let _ = save_cfg(&self.options);
+        let cfg = match cur_game!(self) {
+            HandlerRef(h) => self.options.handler_launch_config(h),
+            _ => self.options.launch_config(),
+        };
+
+        let session = SessionControl::default();
+        self.sessions.push(ActiveSession::new(session.clone(), game.clone()));
 
         self.cur_page = MenuPage::Home;
         self.nav_selection = MenuPage::Home;
@@ -907,10 +1787,18 @@ impl PartyApp {
         self.spawn_task(
             "Launching...\n\nDon't press any buttons or move any analog sticks or mice.",
             move || {
+                let firewall_session = firewall_request
+                    .and_then(|(backend, ports)| FirewallSession::open(backend, &ports));
                 sleep(std::time::Duration::from_secs(2));
-                if let Err(err) = launch_game(&game, &dev_infos, &instances, &cfg) {
+                if let Err(err) = launch_game(&game, &dev_infos, &instances, &cfg, &session) {
                     println!("{}", err);
-                    msg("Launch Error", &format!("{err}"));
+                    // A cancelled launch was already reported by the UI.
+                    if !session.is_cancelled() {
+                        msg("Launch Error", &format!("{err}"));
+                    }
+                }
+                if let Some(session) = firewall_session {
+                    session.close();
                 }
             },
         );
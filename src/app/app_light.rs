@@ -1,12 +1,14 @@
 use std::collections::HashMap;
 use std::thread::sleep;
 
-use super::config::*;
+use super::settings_widgets::*;
+use crate::config::*;
 use crate::game::*;
 use crate::input::*;
 use crate::instance::*;
+use crate::logging::{log_warn, set_author_mode};
 use crate::launch::launch_game;
-use crate::paths::*;
+use crate::session::{SessionCommand, SessionControl, SessionSlot};
 use crate::util::*;
 
 use std::path::PathBuf;
@@ -43,6 +45,16 @@ pub struct LightPartyApp {
     /// Timestamp of the most recent device scan so Bluetooth pads pop up
     /// automatically without spamming the filesystem.
     pub last_input_scan: std::time::Instant,
+    /// Control handle of the running session, shared with the launch thread.
+    pub session: Option<SessionControl>,
+    /// Last non-empty slot snapshot of the running session. The launch thread
+    /// clears the published list when the session ends, so it is kept here.
+    pub session_slots: Vec<SessionSlot>,
+    /// Slots of a session that ended, shown with a "Play Again" prompt until
+    /// dismissed.
+    pub ended_session: Option<Vec<SessionSlot>>,
+    /// Test event on its way to the session webhook, with the error if it failed.
+    pub webhook_test: Option<std::thread::JoinHandle<Result<(), String>>>,
 }
 
 impl LightPartyApp {
@@ -52,9 +64,11 @@ impl LightPartyApp {
         repaint_interval: std::time::Duration,
     ) -> Self {
         let options = load_cfg();
+        set_offline_mode(options.offline_mode);
+        set_author_mode(options.author_mode);
         let input_devices = scan_input_devices(&options.pad_filter_type);
         // placeholder, user should define this
-        Self {
+        let mut app = Self {
             options,
             cur_page: MenuPage::Instances,
             infotext: String::new(),
@@ -69,7 +83,49 @@ impl LightPartyApp {
             task: None,
             repaint_interval,
             last_input_scan: std::time::Instant::now(),
+            session: None,
+            session_slots: Vec::new(),
+            ended_session: None,
+            webhook_test: None,
+        };
+        if app.options.remember_device_assignments {
+            let saved = app
+                .options
+                .last_device_assignments
+                .get(&app.game.persistent_id())
+                .cloned()
+                .unwrap_or_default();
+            app.set_device_slots(restore_device_slots(&app.input_devices, &saved));
         }
+        app
+    }
+
+    /// Switches to a launch from the Recent list and sets its players up
+    /// again with the devices that are connected.
+    fn load_recent_exec(&mut self, recent: &RecentExec) {
+        self.game = Game::ExecRef(Executable::new(
+            PathBuf::from(&recent.path),
+            recent.args.clone(),
+        ));
+        self.set_device_slots(restore_device_slots(&self.input_devices, &recent.devices));
+    }
+
+    /// Replaces the instances with one per entry of `slots`.
+    fn set_device_slots(&mut self, slots: Vec<Vec<usize>>) {
+        self.instance_add_dev = None;
+        self.instances = slots
+            .into_iter()
+            .map(|devices| Instance {
+                devices,
+                profname: String::new(),
+                profselection: 0,
+                width: 0,
+                height: 0,
+                monitor: None,
+                motion_passthrough: true,
+                platform: InstancePlatform::Handler,
+            })
+            .collect();
     }
 }
 
@@ -78,9 +134,7 @@ impl eframe::App for LightPartyApp {
         if !raw_input.focused || self.task.is_some() {
             return;
         }
-        if self.cur_page == MenuPage::Instances {
-            self.handle_devices_instance_menu();
-        }
+        self.handle_devices_instance_menu();
     }
 
     fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
@@ -105,25 +159,60 @@ impl eframe::App for LightPartyApp {
             }
         });
 
+        self.display_session_status(ctx);
+        self.display_session_ended(ctx);
+        launch_plan_button(
+            ctx,
+            Some(&self.game),
+            &self.instances,
+            &self.input_devices,
+            &self.options,
+        );
+
+        // Warnings the launch thread left for the user.
+        if let Some(session) = &self.session {
+            for (title, text) in session.take_notices() {
+                msg(&title, &text);
+            }
+        }
+
         if let Some(handle) = self.task.take() {
             if handle.is_finished() {
                 let _ = handle.join();
                 self.loading_since = None;
                 self.loading_msg = None;
+                // A launch the user stopped before it was up needs no recap.
+                let stopped_early = self.session_slots.is_empty()
+                    && self.session.as_ref().is_some_and(SessionControl::is_stop_requested);
+                if self.session.take().is_some() && !stopped_early {
+                    self.ended_session = Some(std::mem::take(&mut self.session_slots));
+                }
             } else {
                 self.task = Some(handle);
             }
         }
         if let Some(start) = self.loading_since {
-            if start.elapsed() > std::time::Duration::from_secs(60) {
-                // Give up waiting after one minute
-                self.loading_msg = Some("Operation timed out".to_string());
+            // A launch is timed from its last progress report, so a long launch
+            // that keeps moving isn't cut off.
+            let stalled = match &self.session {
+                Some(session) => session.since_heartbeat(),
+                None => start.elapsed(),
+            };
+            // A stop gives instances a grace period to exit, without heartbeats.
+            let stopping = self.session.as_ref().is_some_and(SessionControl::is_stop_requested);
+            if stalled > self.options.timings.task_timeout() && !stopping {
+                self.abandon_timed_out_task();
             }
         }
+        let mut stop_clicked = false;
         if let Some(msg) = &self.loading_msg {
+            let stoppable = self
+                .session
+                .as_ref()
+                .is_some_and(|session| !session.is_stop_requested());
             egui::Area::new("loading".into())
                 .anchor(egui::Align2::CENTER_CENTER, egui::Vec2::ZERO)
-                .interactable(false)
+                .interactable(stoppable)
                 .show(ctx, |ui| {
                     egui::Frame::NONE
                         .fill(egui::Color32::from_rgba_premultiplied(0, 0, 0, 192))
@@ -134,10 +223,24 @@ impl eframe::App for LightPartyApp {
                                 ui.add(egui::widgets::Spinner::new().size(40.0));
                                 ui.add_space(8.0);
                                 ui.label(msg);
+                                // Lets a slow or stuck launch be ended without a terminal.
+                                if stoppable {
+                                    ui.add_space(8.0);
+                                    stop_clicked = ui
+                                        .button("Stop Session")
+                                        .on_hover_text("Cancels the launch and closes the instances it already started, killing any that don't quit within 10 seconds.")
+                                        .clicked();
+                                }
                             });
                         });
                 });
         }
+        if stop_clicked {
+            if let Some(session) = &self.session {
+                session.request_stop();
+            }
+            self.loading_msg = Some("Stopping...".to_string());
+        }
         if ctx.input(|input| input.focused) {
             ctx.request_repaint_after(self.repaint_interval);
         }
@@ -145,50 +248,6 @@ impl eframe::App for LightPartyApp {
 }
 
 impl LightPartyApp {
-    /// Refreshes the cached Proton installation list in the lightweight UI so
-    /// users can pick newly installed compatibility tools without restarting.
-    pub fn refresh_proton_versions(&mut self) {
-        self.proton_versions = discover_proton_versions();
-    }
-
-    /// Mirrors the launcher Proton resolution used in the full UI so the light
-    /// experience remains feature parity.
-    pub fn selected_proton_install(&self) -> Option<&ProtonInstall> {
-        let trimmed = self.options.proton_version.trim();
-        if trimmed.is_empty() {
-            return self
-                .proton_versions
-                .iter()
-                .find(|install| install.matches("GE-Proton"));
-        }
-
-        self.proton_versions
-            .iter()
-            .find(|install| install.matches(trimmed))
-    }
-
-    /// Returns the label shown in the Proton combo box for the light UI.
-    pub fn proton_dropdown_label(&self) -> String {
-        if let Some(install) = self.selected_proton_install() {
-            return install.display_label();
-        }
-
-        let trimmed = self.options.proton_version.trim();
-        if trimmed.is_empty() {
-            if self
-                .proton_versions
-                .iter()
-                .any(|install| install.matches("GE-Proton"))
-            {
-                "Auto (GE-Proton)".to_string()
-            } else {
-                "Auto (GE-Proton missing)".to_string()
-            }
-        } else {
-            format!("Custom: {trimmed}")
-        }
-    }
-
     pub fn spawn_task<F>(&mut self, msg: &str, f: F)
     where
         F: FnOnce() + Send + 'static,
@@ -198,7 +257,38 @@ impl LightPartyApp {
         self.task = Some(std::thread::spawn(f));
     }
 
+    /// Stops waiting on a task that outlived the task timeout and gives the
+    /// UI back. A launch is cancelled at its next checkpoint and stops the
+    /// instances it already started; other tasks finish in the background.
+    fn abandon_timed_out_task(&mut self) {
+        self.loading_msg = None;
+        self.loading_since = None;
+        // Dropping the handle detaches the thread instead of blocking on it.
+        self.task = None;
+        let timeout = self.options.timings.task_timeout_secs;
+        let Some(session) = self.session.take() else {
+            log_warn(format!(
+                "A task ran longer than {timeout} s; it continues in the background."
+            ));
+            return;
+        };
+        session.cancel();
+        self.session_slots.clear();
+        log_warn(format!("Launch cancelled after making no progress for {timeout} s."));
+        msg(
+            "Launch Timed Out",
+            &format!(
+                "The launch made no progress for {timeout} seconds and was cancelled. Instances it already started are being stopped."
+            ),
+        );
+    }
+
+    /// Polls every device. The Steam Deck rear buttons work on every page,
+    /// the rest only assigns players on the Instances page.
     fn handle_devices_instance_menu(&mut self) {
+        // Run after polling, since a rescan replaces `self.input_devices`.
+        let mut rear_action: Option<RearButtonAction> = None;
+        let bindings = self.options.rear_buttons;
         let mut i = 0;
         while i < self.input_devices.len() {
             if !self.input_devices[i].enabled() {
@@ -206,6 +296,11 @@ impl LightPartyApp {
                 continue;
             }
             match self.input_devices[i].poll() {
+                Some(PadButton::L4Btn) => rear_action = Some(bindings.l4),
+                Some(PadButton::L5Btn) => rear_action = Some(bindings.l5),
+                Some(PadButton::R4Btn) => rear_action = Some(bindings.r4),
+                Some(PadButton::R5Btn) => rear_action = Some(bindings.r5),
+                _ if self.cur_page != MenuPage::Instances => {}
                 Some(PadButton::ABtn) | Some(PadButton::ZKey) | Some(PadButton::RightClick) => {
                     if self.input_devices[i].device_type() != DeviceType::Gamepad
                         && !self.options.kbm_support
@@ -227,6 +322,9 @@ impl LightPartyApp {
                                 profselection: 0,
                                 width: 0,
                                 height: 0,
+                                monitor: None,
+                                motion_passthrough: true,
+                                platform: InstancePlatform::Handler,
                             });
                         }
                     }
@@ -254,6 +352,37 @@ impl LightPartyApp {
             }
             i += 1;
         }
+        if let Some(action) = rear_action {
+            self.run_rear_button_action(action);
+        }
+    }
+
+    /// Runs what a Steam Deck rear button is bound to in the settings.
+    fn run_rear_button_action(&mut self, action: RearButtonAction) {
+        match action {
+            RearButtonAction::None => {}
+            // The launcher only has two pages, so both directions switch.
+            RearButtonAction::PreviousPage | RearButtonAction::NextPage => {
+                self.cur_page = match self.cur_page {
+                    MenuPage::Instances => MenuPage::Settings,
+                    MenuPage::Settings => MenuPage::Instances,
+                };
+            }
+            RearButtonAction::Rescan => self.sync_input_devices(),
+            RearButtonAction::LaunchLast => {
+                if self.task.is_some() {
+                    return;
+                }
+                let Some(recent) = self.options.recent_execs.first().cloned() else {
+                    return;
+                };
+                self.load_recent_exec(&recent);
+                self.cur_page = MenuPage::Instances;
+                if !self.instances.is_empty() {
+                    self.prepare_game_launch();
+                }
+            }
+        }
     }
 
     fn is_device_in_any_instance(&mut self, dev: usize) -> bool {
@@ -352,27 +481,149 @@ impl LightPartyApp {
     }
 
     pub fn prepare_game_launch(&mut self) {
+        // Executables carry no declared footprint, so rely on the default budget.
+        if let Some(warning) = memory_pressure_warning(self.instances.len(), None) {
+            if !yesno("Low memory", &format!("{warning}\n\nLaunch anyway?")) {
+                return;
+            }
+        }
+
         set_instance_resolutions(&mut self.instances, &self.options);
+        // Players here have no profiles, except the one marked "Always player 1".
+        if let Some(host) = host_profile() {
+            if let Some(first) = self.instances.first_mut() {
+                first.profname = host;
+            }
+        }
+
+        let assignments = device_slot_identities(&self.instances, &self.input_devices);
+        if let Game::ExecRef(exec) = &self.game {
+            self.options.record_exec_launch(RecentExec {
+                path: exec.path().to_string_lossy().to_string(),
+                args: exec.args.clone(),
+                devices: assignments.clone(),
+            });
+        }
+        if self.options.remember_device_assignments {
+            self.options
+                .last_device_assignments
+                .insert(self.game.persistent_id(), assignments);
+        }
 
         let game = self.game.to_owned();
         let instances = self.instances.clone();
         let dev_infos: Vec<DeviceInfo> = self.input_devices.iter().map(|p| p.info()).collect();
 
-        let cfg = self.options.clone();
-        let _ = save_cfg(&cfg);
+        let _ = save_cfg(&self.options);
+        let cfg = self.options.launch_config();
 
+        // The launch thread publishes slot updates through the session handle,
+        // so the launcher stays open and can offer another round afterwards.
+        let session = SessionControl::default();
+        self.session = Some(session.clone());
+        self.session_slots.clear();
+        self.ended_session = None;
         self.spawn_task(
             "Launching...\n\nDon't press any buttons or move any analog sticks or mice.",
             move || {
                 sleep(std::time::Duration::from_secs(2));
-                if let Err(err) = launch_game(&game, &dev_infos, &instances, &cfg) {
+                if let Err(err) = launch_game(&game, &dev_infos, &instances, &cfg, &session) {
                     println!("{}", err);
-                    msg("Launch Error", &format!("{err}"));
+                    // A cancelled launch was already reported by the UI.
+                    if !session.is_cancelled() {
+                        msg("Launch Error", &format!("{err}"));
+                    }
                 }
-                std::process::exit(0);
             },
         );
     }
+
+    /// Per-instance state of the running session, published by the launch
+    /// thread, with a way to end it from the launcher.
+    fn display_session_status(&mut self, ctx: &egui::Context) {
+        let Some(session) = self.session.clone() else {
+            return;
+        };
+        let slots = session.slots();
+        if slots.is_empty() {
+            return;
+        }
+        self.session_slots = slots.clone();
+
+        // Once every instance is up, drop the launch overlay in favour of the status.
+        if self.loading_msg.is_some() {
+            self.loading_msg = None;
+            self.loading_since = None;
+        }
+
+        egui::Window::new("Session")
+            .anchor(egui::Align2::RIGHT_TOP, egui::vec2(-12.0, 48.0))
+            .collapsible(true)
+            .resizable(false)
+            .show(ctx, |ui| {
+                for slot in &slots {
+                    ui.horizontal(|row| {
+                        let status = if slot.swapping {
+                            "swapping…".to_string()
+                        } else if slot.running {
+                            slot.boot_status()
+                        } else {
+                            "stopped".to_string()
+                        };
+                        row.label(RichText::new(format!("P{}", slot.slot + 1)).strong());
+                        row.label(slot.profile.as_str());
+                        row.label(RichText::new(status).weak());
+                        if !slot.disconnected.is_empty() {
+                            row.label(
+                                RichText::new("⚠ controller disconnected")
+                                    .color(row.visuals().warn_fg_color),
+                            );
+                        }
+                    });
+                }
+                ui.separator();
+                let stop_btn = ui.button("Stop All");
+                if stop_btn.clicked() {
+                    session.send(SessionCommand::StopAll);
+                }
+                stop_btn.on_hover_text("Closes every instance and ends the session.");
+            });
+        ctx.request_repaint_after(std::time::Duration::from_millis(500));
+    }
+
+    /// Shown once every instance has exited: start the same setup again or
+    /// close the launcher.
+    fn display_session_ended(&mut self, ctx: &egui::Context) {
+        let Some(slots) = &self.ended_session else {
+            return;
+        };
+        let mut play_again = false;
+        let mut dismiss = false;
+        egui::Window::new("Session ended")
+            .anchor(egui::Align2::CENTER_CENTER, egui::Vec2::ZERO)
+            .collapsible(false)
+            .resizable(false)
+            .show(ctx, |ui| {
+                if slots.is_empty() {
+                    ui.label("The game didn't start. Check the launch log for details.");
+                } else {
+                    ui.label("Every instance has exited.");
+                }
+                ui.horizontal(|row| {
+                    play_again = row.button("Play Again").clicked();
+                    dismiss = row.button("Back to Instances").clicked();
+                    if row.button("Quit").clicked() {
+                        row.ctx().send_viewport_cmd(egui::ViewportCommand::Close);
+                    }
+                });
+            });
+        if play_again || dismiss {
+            self.ended_session = None;
+        }
+        if play_again && !self.instances.is_empty() {
+            self.prepare_game_launch();
+        }
+    }
 }
 
 impl LightPartyApp {
@@ -583,156 +834,24 @@ impl LightPartyApp {
     fn render_light_settings_general(&mut self, ui: &mut Ui) {
         // Mirror the desktop spacing so controls align perfectly within the column.
         ui.spacing_mut().item_spacing.y = 12.0;
-        let force_sdl2_check = ui.checkbox(&mut self.options.force_sdl, "Force Steam Runtime SDL2");
-
-        let enable_kwin_script_check = ui.checkbox(
-            &mut self.options.enable_kwin_script,
-            "Automatically resize/reposition instances",
-        );
-
-        let vertical_two_player_check = ui.checkbox(
-            &mut self.options.vertical_two_player,
-            "Vertical split for 2 players",
-        );
-
-        if force_sdl2_check.hovered() {
-            self.infotext = "Forces games to use the version of SDL2 included in the Steam Runtime. Only works on native Linux games, may fix problematic game controller support (incorrect mappings) in some games, may break others. If unsure, leave this unchecked.".to_string();
-        }
-
-        if enable_kwin_script_check.hovered() {
-            self.infotext = "Resizes/repositions instances to fit the screen using a KWin script. If unsure, leave this checked. If using a desktop environment or window manager other than KDE Plasma, uncheck this; note that you will need to manually resize and reposition the windows.".to_string();
-        }
-
-        if vertical_two_player_check.hovered() {
-            self.infotext =
-                "Splits two-player games vertically (side by side) instead of horizontally."
-                    .to_string();
-        }
-
-        // Group the controller filter radios so they wrap neatly on narrow windows.
-        ui.group(|group| {
-            group.spacing_mut().item_spacing.y = 6.0;
-            let filter_label = group.label("Controller filter");
-            group.horizontal_wrapped(|radios| {
-                let r1 = radios.radio_value(
-                    &mut self.options.pad_filter_type,
-                    PadFilterType::All,
-                    "All controllers",
-                );
-                let r2 = radios.radio_value(
-                    &mut self.options.pad_filter_type,
-                    PadFilterType::NoSteamInput,
-                    "No Steam Input",
-                );
-                let r3 = radios.radio_value(
-                    &mut self.options.pad_filter_type,
-                    PadFilterType::OnlySteamInput,
-                    "Only Steam Input",
-                );
-
-                if filter_label.hovered() || r1.hovered() || r2.hovered() || r3.hovered() {
-                    self.infotext = "Select which controllers to filter out. If unsure, set this to \"No Steam Input\". If you use Steam Input to remap controllers, you may want to select \"Only Steam Input\", but be warned that this option is experimental and is known to break certain Proton games.".to_string();
-                }
-
-                if r1.clicked() || r2.clicked() || r3.clicked() {
-                    self.input_devices = scan_input_devices(&self.options.pad_filter_type);
-                }
-            });
-        });
-
-        // Wrap the Proton selector and manual override into a tidy stack for clarity.
-        ui.group(|group| {
-            group.spacing_mut().item_spacing.y = 8.0;
-            let proton_ver_label = group.label("Proton version");
-            let combo_response = egui::ComboBox::from_id_salt("light_settings_proton_combo")
-                .selected_text(self.proton_dropdown_label())
-                .width(220.0)
-                .show_ui(group, |combo_ui| {
-                    combo_ui.selectable_value(
-                        &mut self.options.proton_version,
-                        String::new(),
-                        "Auto (GE-Proton)",
-                    );
-
-                    if self.proton_versions.is_empty() {
-                        combo_ui.label("No Proton builds detected");
-                    } else {
-                        for install in &self.proton_versions {
-                            combo_ui.selectable_value(
-                                &mut self.options.proton_version,
-                                install.id.clone(),
-                                install.display_label(),
-                            );
-                        }
-                    }
-
-                    combo_ui.separator();
-                    combo_ui.label("Select a build above or keep using the custom path below.");
-                })
-                .response;
-
-            let refresh_btn = group.small_button("Refresh");
-            if refresh_btn.clicked() {
-                self.refresh_proton_versions();
-            }
-
-            if proton_ver_label.hovered() || combo_response.hovered() || refresh_btn.hovered() {
-                self.infotext = "Choose an installed Proton build or refresh the list after installing a new compatibility tool. Keep the field below blank for the default GE-Proton.".to_string();
-            }
-
-            let proton_ver_editbox = group.add(
-                egui::TextEdit::singleline(&mut self.options.proton_version)
-                    .hint_text("GE-Proton or /path/to/proton"),
-            );
-            if proton_ver_editbox.hovered() {
-                self.infotext = "Enter a custom Proton identifier or absolute path. Leave empty to auto-select GE-Proton.".to_string();
-            }
-        });
-
-        let proton_separate_pfxs_check = ui.checkbox(
-            &mut self.options.proton_separate_pfxs,
-            "Run instances in separate Proton prefixes",
-        );
-        if proton_separate_pfxs_check.hovered() {
-            self.infotext = "Runs each instance in its own Proton prefix. If unsure, leave this unchecked. This option will take up more space on the disk, but may also help with certain Proton-related issues such as only one instance of a game starting.".to_string();
-        }
+        layout_settings(self, ui);
+        copilot_setting(self, ui);
+        remember_assignments_setting(self, ui);
+        rear_buttons_setting(self, ui);
+        controller_filter_setting(self, ui);
+        proton_settings(self, ui);
+        multiseat_setting(self, ui);
+        offline_mode_setting(self, ui);
+        author_mode_setting(self, ui);
+        webhook_setting(self, ui);
 
         ui.separator();
 
         // Keep destructive maintenance actions in a single row to avoid tall gaps.
         ui.with_layout(egui::Layout::left_to_right(egui::Align::Center), |actions| {
             actions.spacing_mut().item_spacing.x = 10.0;
-            if actions.button("Erase Proton Prefix").clicked() {
-                if yesno(
-                    "Erase Prefix?",
-                    "This will erase the Wine prefix used by Split Happens. This shouldn't erase profile/game-specific data, but exercise caution. Are you sure?",
-                ) && PATH_APP.join("gamesyms").exists()
-                {
-                    if let Err(err) = std::fs::remove_dir_all(PATH_APP.join("pfx")) {
-                        msg("Error", &format!("Couldn't erase pfx data: {}", err));
-                    } else if let Err(err) = std::fs::create_dir_all(PATH_APP.join("pfx")) {
-                        msg("Error", &format!("Couldn't re-create pfx directory: {}", err));
-                    } else {
-                        msg("Data Erased", "Proton prefix data successfully erased.");
-                    }
-                }
-            }
-
-            if actions.button("Erase Symlink Data").clicked() {
-                if yesno(
-                    "Erase Symlink Data?",
-                    "This will erase all game symlink data. This shouldn't erase profile/game-specific data, but exercise caution. Are you sure?",
-                ) && PATH_APP.join("gamesyms").exists()
-                {
-                    if let Err(err) = std::fs::remove_dir_all(PATH_APP.join("gamesyms")) {
-                        msg("Error", &format!("Couldn't erase symlink data: {}", err));
-                    } else if let Err(err) = std::fs::create_dir_all(PATH_APP.join("gamesyms")) {
-                        msg("Error", &format!("Couldn't re-create symlink directory: {}", err));
-                    } else {
-                        msg("Data Erased", "Game symlink data successfully erased.");
-                    }
-                }
-            }
+            erase_prefix_button(self, actions);
+            erase_symlinks_button(self, actions);
         });
 
         // Surface shortcuts to important data locations with compact spacing.
@@ -740,24 +859,7 @@ impl LightPartyApp {
             egui::Layout::left_to_right(egui::Align::Center),
             |actions| {
                 actions.spacing_mut().item_spacing.x = 10.0;
-                if actions.button("Open Split Happens Data Folder").clicked() {
-                    if let Err(_) = std::process::Command::new("sh")
-                        .arg("-c")
-                        .arg(format!("xdg-open {}/", PATH_APP.display()))
-                        .status()
-                    {
-                        msg("Error", "Couldn't open Split Happens Data Folder!");
-                    }
-                }
-                if actions.button("Edit game paths").clicked() {
-                    if let Err(_) = std::process::Command::new("sh")
-                        .arg("-c")
-                        .arg(format!("xdg-open {}/paths.json", PATH_APP.display(),))
-                        .status()
-                    {
-                        msg("Error", "Couldn't open paths.json!");
-                    }
-                }
+                data_folder_buttons(self, actions);
             },
         );
     }
@@ -765,57 +867,13 @@ impl LightPartyApp {
     fn render_light_settings_performance(&mut self, ui: &mut Ui) {
         // Present the same Deck performance assists for the handheld-friendly layout.
         ui.spacing_mut().item_spacing.y = 12.0;
-
-        let realtime_toggle = ui.checkbox(
-            &mut self.options.performance_gamescope_rt,
-            "Real-time scheduling for Gamescope",
-        );
-        if realtime_toggle.hovered() {
-            self.infotext = "Requests gamescope's real-time compositor mode to reduce frame pacing spikes when two sessions share the GPU.".to_string();
-        }
-
-        let fps_limit_toggle = ui.checkbox(
-            &mut self.options.performance_limit_40fps,
-            "Limit Gamescope output to 40 FPS",
-        );
-        if fps_limit_toggle.hovered() {
-            self.infotext = "Caps each window to 40 frames per second so both players stay within the Deck's thermal and power envelope.".to_string();
-        }
-
-        let proton_fsr_toggle = ui.checkbox(
-            &mut self.options.performance_enable_proton_fsr,
-            "Enable Proton FSR upscaling",
-        );
-        if proton_fsr_toggle.hovered() {
-            self.infotext = "Turns on Proton's fullscreen FSR so Windows titles can render at lower resolutions while gamescope upscales the result.".to_string();
-        }
+        performance_settings(self, ui);
     }
 
     fn render_light_settings_gamescope(&mut self, ui: &mut Ui) {
         // Match the vertical rhythm from the General column.
         ui.spacing_mut().item_spacing.y = 12.0;
-        let gamescope_lowres_fix_check = ui.checkbox(
-            &mut self.options.gamescope_fix_lowres,
-            "Automatically fix low resolution instances",
-        );
-        let gamescope_sdl_backend_check = ui.checkbox(
-            &mut self.options.gamescope_sdl_backend,
-            "Use SDL backend for Gamescope",
-        );
-        let kbm_support_check = ui.checkbox(
-            &mut self.options.kbm_support,
-            "Enable keyboard and mouse support through custom Gamescope",
-        );
-
-        if gamescope_lowres_fix_check.hovered() {
-            self.infotext = "Many games have graphical problems or even crash when running at resolutions below 600p. If this is enabled, any instances below 600p will automatically be resized before launching.".to_string();
-        }
-        if gamescope_sdl_backend_check.hovered() {
-            self.infotext = "Runs gamescope sessions using the SDL backend. If unsure, leave this checked. If gamescope sessions only show a black screen or give an error (especially on Nvidia + Wayland), try disabling this.".to_string();
-        }
-        if kbm_support_check.hovered() {
-            self.infotext = "Runs a custom Gamescope build with support for holding keyboards and mice. If you want to use your own Gamescope installation, uncheck this.".to_string();
-        }
+        gamescope_settings(self, ui);
     }
 
     pub fn display_page_instances(&mut self, ui: &mut Ui) {
@@ -861,6 +919,31 @@ impl LightPartyApp {
             }
         });
 
+        ui.horizontal(|row| {
+            let auto_btn = row.button("Auto Assign");
+            if auto_btn.clicked() {
+                let pads = auto_assign_devices(&self.input_devices, self.options.auto_assign_order);
+                self.set_device_slots(pads.into_iter().map(|dev| vec![dev]).collect());
+            }
+            if auto_btn.hovered() {
+                self.infotext = "Creates one instance per connected gamepad, in the order picked next to this button. Replaces the current instances.".to_string();
+            }
+            let order_combo = egui::ComboBox::from_id_salt("instances_auto_assign_order")
+                .selected_text(self.options.auto_assign_order.label())
+                .show_ui(row, |combo| {
+                    for order in AutoAssignOrder::ALL {
+                        combo.selectable_value(
+                            &mut self.options.auto_assign_order,
+                            order,
+                            order.label(),
+                        );
+                    }
+                });
+            if order_combo.response.hovered() {
+                self.infotext = "Connection order makes the pad connected first player 1. Vendor groups pads by make and model, then by serial or port, so the same pads always land in the same slots.".to_string();
+            }
+        });
+
         ui.separator();
 
         // Record precise instance/device pairs flagged for deletion so shared
@@ -880,6 +963,19 @@ impl LightPartyApp {
                     }
                     ui.label("Adding new device...");
                 }
+
+                let has_pad = instance.devices.iter().any(|&dev| {
+                    self.input_devices
+                        .get(dev)
+                        .is_some_and(|dev| dev.device_type() == DeviceType::Gamepad)
+                });
+                if has_pad {
+                    let motion_check =
+                        ui.checkbox(&mut instance.motion_passthrough, "🎯 Gyro & touchpad");
+                    if motion_check.hovered() {
+                        self.infotext = "Forwards the motion sensor and touchpad of this player's PlayStation, Switch or Steam pads to their instance so games with gyro aiming or touch controls can use them. Other instances never see them. Turn it off for games that misbehave when they find a motion sensor.".to_string();
+                    }
+                }
             });
             for (device_slot, &dev) in instance.devices.iter().enumerate() {
                 if let Some(device) = self.input_devices.get(dev) {
@@ -922,12 +1018,69 @@ impl LightPartyApp {
                 if ui.button("Start").clicked() {
                     self.prepare_game_launch();
                 }
+                let safe_check = ui.checkbox(&mut self.options.safe_mode, "Safe mode");
+                if safe_check.hovered() {
+                    self.infotext = SAFE_MODE_INFO.to_string();
+                }
             });
         }
 
+        self.display_recent_execs(ui);
+
         // Mirror the inline device overview from the full UI.
         ui.add_space(20.0);
         let devices_ctx = ui.ctx().clone();
         self.display_panel_right(ui, &devices_ctx);
     }
+
+    /// Earlier --exec launches with their player setup, to load or play again
+    /// without retyping the command line.
+    fn display_recent_execs(&mut self, ui: &mut Ui) {
+        if self.options.recent_execs.is_empty() {
+            return;
+        }
+        ui.add_space(20.0);
+        ui.heading("Recent");
+        ui.separator();
+
+        let mut load: Option<(RecentExec, bool)> = None;
+        for recent in &self.options.recent_execs {
+            let exists = std::path::Path::new(&recent.path).exists();
+            ui.horizontal(|row| {
+                let name = recent.path.rsplit('/').next().unwrap_or(&recent.path);
+                let players = match recent.devices.len() {
+                    1 => "1 player".to_string(),
+                    count => format!("{count} players"),
+                };
+                let label = row.label(RichText::new(name).strong());
+                if !recent.args.is_empty() {
+                    row.label(RichText::new(&recent.args).monospace().weak());
+                }
+                row.label(RichText::new(players).weak());
+                label.on_hover_text(&recent.path);
+
+                let play_btn = row.add_enabled(exists, egui::Button::new("▶ Play"));
+                if play_btn.clicked() {
+                    load = Some((recent.clone(), true));
+                }
+                let load_btn = row.add_enabled(exists, egui::Button::new("Load"));
+                if load_btn.clicked() {
+                    load = Some((recent.clone(), false));
+                }
+                if play_btn.hovered() || load_btn.hovered() {
+                    self.infotext = "Load switches to this executable and arguments and assigns the same controllers to the same players again; Play also launches right away. Controllers that aren't connected are skipped.".to_string();
+                }
+                if !exists {
+                    row.label(RichText::new("Missing").color(row.visuals().warn_fg_color));
+                }
+            });
+        }
+
+        if let Some((recent, play)) = load {
+            self.load_recent_exec(&recent);
+            if play && !self.instances.is_empty() {
+                self.prepare_game_launch();
+            }
+        }
+    }
 }
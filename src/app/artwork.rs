@@ -0,0 +1,109 @@
+use std::collections::{HashMap, HashSet};
+use std::thread::JoinHandle;
+
+use super::app::PartyApp;
+use crate::logging::log_warn;
+use crate::util::*;
+
+use eframe::egui;
+
+/// SteamGridDB artwork shown on the home tiles, with the background download
+/// of whatever is still missing.
+#[derive(Default)]
+pub struct ArtworkCache {
+    /// Cached artwork by `Game::persistent_id`.
+    pub games: HashMap<String, GameArtwork>,
+    /// Games already looked up this run, so rescans don't query them again.
+    attempted: HashSet<String>,
+    fetch: Option<JoinHandle<Vec<(String, GameArtwork)>>>,
+    /// The API key was edited since its field last lost focus.
+    pub key_edited: bool,
+}
+
+impl ArtworkCache {
+    pub fn get(&self, game_id: &str) -> Option<&GameArtwork> {
+        self.games.get(game_id)
+    }
+
+    pub fn is_fetching(&self) -> bool {
+        self.fetch.is_some()
+    }
+}
+
+impl PartyApp {
+    /// Reads the cached artwork of every game and downloads what's missing
+    /// once a SteamGridDB API key is set. Called after each game scan.
+    pub fn refresh_artwork(&mut self) {
+        self.artwork.games = self
+            .games
+            .iter()
+            .map(|game| {
+                let id = game.persistent_id();
+                let artwork = cached_artwork(&id);
+                (id, artwork)
+            })
+            .collect();
+        self.start_artwork_fetch();
+    }
+
+    /// Looks up every game SteamGridDB wasn't asked about yet on a background
+    /// thread. Does nothing without an API key, while offline or while a
+    /// download already runs.
+    pub fn start_artwork_fetch(&mut self) {
+        let api_key = self.options.steamgriddb_api_key.trim().to_string();
+        if api_key.is_empty() || offline_mode() || self.artwork.fetch.is_some() {
+            return;
+        }
+        let requests: Vec<ArtworkRequest> = self
+            .games
+            .iter()
+            .map(|game| game.artwork_request())
+            .filter(|request| !self.artwork.attempted.contains(&request.game_id))
+            .collect();
+        if requests.is_empty() {
+            return;
+        }
+        self.artwork
+            .attempted
+            .extend(requests.iter().map(|request| request.game_id.clone()));
+        self.artwork.fetch = Some(std::thread::spawn(move || {
+            requests
+                .into_iter()
+                .filter_map(|request| match fetch_game_artwork(&api_key, &request) {
+                    Ok(artwork) => Some((request.game_id, artwork)),
+                    Err(err) => {
+                        log_warn(format!("No artwork for {}: {err}", request.title));
+                        None
+                    }
+                })
+                .collect()
+        }));
+    }
+
+    /// Looks up all games again, e.g. after the API key changed.
+    pub fn refetch_artwork(&mut self) {
+        self.artwork.attempted.clear();
+        self.start_artwork_fetch();
+    }
+
+    /// Picks up finished downloads so the tiles show them.
+    pub fn poll_artwork_fetch(&mut self, ctx: &egui::Context) {
+        let Some(fetch) = &self.artwork.fetch else {
+            return;
+        };
+        if !fetch.is_finished() {
+            // Nothing else repaints an idle home page once the images arrive.
+            ctx.request_repaint_after(std::time::Duration::from_millis(500));
+            return;
+        }
+        let Some(fetch) = self.artwork.fetch.take() else {
+            return;
+        };
+        match fetch.join() {
+            Ok(fetched) => self.artwork.games.extend(fetched),
+            Err(_) => log_warn("The artwork download stopped unexpectedly"),
+        }
+        // Games added while the download ran are looked up now.
+        self.start_artwork_fetch();
+    }
+}
@@ -0,0 +1,205 @@
+use super::app::PartyApp;
+use crate::util::*;
+
+use eframe::egui::{self, RichText};
+use evdev::AbsoluteAxisCode;
+use std::collections::HashMap;
+
+/// Stick axes sampled by the wizard.
+const CALIBRATED_AXES: [(AbsoluteAxisCode, &str); 4] = [
+    (AbsoluteAxisCode::ABS_X, "Left X"),
+    (AbsoluteAxisCode::ABS_Y, "Left Y"),
+    (AbsoluteAxisCode::ABS_RX, "Right X"),
+    (AbsoluteAxisCode::ABS_RY, "Right Y"),
+];
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum CalibrationStep {
+    Center,
+    Extremes,
+    DeadZone,
+}
+
+/// State of the per-device calibration wizard. The device is tracked by path
+/// because indices shift whenever the input list is rescanned.
+pub struct CalibrationWizard {
+    pub device_path: String,
+    pub step: CalibrationStep,
+    pub draft: DeviceCalibration,
+    centers: HashMap<u16, i32>,
+    extremes: HashMap<u16, (i32, i32)>,
+}
+
+impl CalibrationWizard {
+    pub fn new(device_path: &str, current: &DeviceCalibration) -> Self {
+        Self {
+            device_path: device_path.to_string(),
+            step: CalibrationStep::Center,
+            draft: current.clone(),
+            centers: HashMap::new(),
+            extremes: HashMap::new(),
+        }
+    }
+}
+
+impl PartyApp {
+    /// Opens the calibration wizard for the gamepad at `path`.
+    pub fn open_calibration_wizard(&mut self, path: &str) {
+        if let Some(pad) = self.input_devices.iter().find(|pad| pad.path() == path) {
+            self.calibration_wizard = Some(CalibrationWizard::new(path, pad.calibration()));
+        }
+    }
+
+    /// Renders the calibration wizard window while one is open.
+    pub fn display_calibration_wizard(&mut self, ctx: &egui::Context) {
+        let Some(mut wizard) = self.calibration_wizard.take() else {
+            return;
+        };
+        let Some(pad_index) = self
+            .input_devices
+            .iter()
+            .position(|pad| pad.path() == wizard.device_path)
+        else {
+            // The pad disconnected mid-wizard; nothing left to calibrate.
+            return;
+        };
+
+        // Track the widest travel seen on each axis while on the extremes step.
+        if wizard.step == CalibrationStep::Extremes {
+            for (axis, _) in CALIBRATED_AXES {
+                if let Some(value) = self.input_devices[pad_index].raw_axis(axis) {
+                    let entry = wizard.extremes.entry(axis.0).or_insert((value, value));
+                    entry.0 = entry.0.min(value);
+                    entry.1 = entry.1.max(value);
+                }
+            }
+        }
+
+        let mut keep_open = true;
+        let mut finished = false;
+        let mut reset = false;
+        let pad = &self.input_devices[pad_index];
+        egui::Window::new(format!("Calibrate {}", pad.fancyname()))
+            .collapsible(false)
+            .resizable(false)
+            .anchor(egui::Align2::CENTER_CENTER, egui::Vec2::ZERO)
+            .show(ctx, |ui| {
+                ui.spacing_mut().item_spacing.y = 8.0;
+                match wizard.step {
+                    CalibrationStep::Center => {
+                        ui.label("Let go of both sticks so they rest in the center, then press Next.");
+                        if ui.button("Next").clicked() {
+                            for (axis, _) in CALIBRATED_AXES {
+                                let center = pad
+                                    .raw_axis(axis)
+                                    .unwrap_or_else(|| pad.reported_axis_range(axis).center);
+                                wizard.centers.insert(axis.0, center);
+                            }
+                            wizard.step = CalibrationStep::Extremes;
+                        }
+                    }
+                    CalibrationStep::Extremes => {
+                        ui.label("Rotate both sticks around their full range a few times, then press Next.");
+                        for (axis, label) in CALIBRATED_AXES {
+                            let text = match wizard.extremes.get(&axis.0) {
+                                Some((min, max)) => format!("{label}: {min} … {max}"),
+                                None => format!("{label}: move the stick"),
+                            };
+                            ui.label(text);
+                        }
+                        if ui.button("Next").clicked() {
+                            for (axis, _) in CALIBRATED_AXES {
+                                let reported = pad.reported_axis_range(axis);
+                                let center = wizard
+                                    .centers
+                                    .get(&axis.0)
+                                    .copied()
+                                    .unwrap_or(reported.center);
+                                // Only trust sampled extremes that straddle the center.
+                                let range = match wizard.extremes.get(&axis.0) {
+                                    Some((min, max)) if *min < center && *max > center => {
+                                        AxisRange {
+                                            min: *min,
+                                            center,
+                                            max: *max,
+                                        }
+                                    }
+                                    _ => AxisRange { center, ..reported },
+                                };
+                                wizard.draft.axes.insert(axis.0, range);
+                            }
+                            wizard.step = CalibrationStep::DeadZone;
+                        }
+                    }
+                    CalibrationStep::DeadZone => {
+                        ui.add(
+                            egui::Slider::new(&mut wizard.draft.deadzone, 0.0..=0.3)
+                                .text("In-game dead zone"),
+                        );
+                        ui.add(
+                            egui::Slider::new(&mut wizard.draft.nav_threshold, 0.1..=0.9)
+                                .text("Menu navigation threshold"),
+                        );
+
+                        // Preview the left stick through the draft calibration.
+                        let preview = |axis: AbsoluteAxisCode| {
+                            let raw = pad.raw_axis(axis).unwrap_or(0);
+                            let range = wizard
+                                .draft
+                                .axes
+                                .get(&axis.0)
+                                .copied()
+                                .unwrap_or_else(|| pad.reported_axis_range(axis));
+                            wizard.draft.apply_deadzone(range.normalize(raw))
+                        };
+                        ui.label(
+                            RichText::new(format!(
+                                "Left stick: {:+.2}, {:+.2}",
+                                preview(AbsoluteAxisCode::ABS_X),
+                                preview(AbsoluteAxisCode::ABS_Y)
+                            ))
+                            .monospace(),
+                        );
+
+                        if ui.button("Save").clicked() {
+                            finished = true;
+                        }
+                    }
+                }
+
+                ui.separator();
+                ui.horizontal(|row| {
+                    if row.button("Cancel").clicked() {
+                        keep_open = false;
+                    }
+                    if row.button("Reset to defaults").clicked() {
+                        reset = true;
+                    }
+                });
+            });
+
+        let pad = &mut self.input_devices[pad_index];
+        if reset {
+            if let Err(err) = remove_calibration(pad.identity()) {
+                msg("Error", &format!("Couldn't reset calibration: {err}"));
+            }
+            pad.set_calibration(DeviceCalibration::default());
+            return;
+        }
+        if finished {
+            let identity = pad.identity().to_string();
+            match save_calibration(&identity, &wizard.draft) {
+                Ok(()) => pad.set_calibration(wizard.draft),
+                Err(err) => msg("Error", &format!("Couldn't save calibration: {err}")),
+            }
+            return;
+        }
+        if !keep_open {
+            return;
+        }
+
+        // Keep sampling the sticks while the wizard is visible.
+        ctx.request_repaint();
+        self.calibration_wizard = Some(wizard);
+    }
+}
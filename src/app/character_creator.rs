@@ -1,4 +1,24 @@
 use std::collections::HashMap;
+use std::sync::LazyLock;
+
+use super::app::{MenuPage, PartyApp};
+use crate::paths::PATH_RES;
+use crate::util::*;
+
+use eframe::egui::{self, Color32, Rect, RichText, Ui, pos2, vec2};
+
+/// Atlas the [`MALE_BODY_SPRITES`] slices are cut from, in the resources folder.
+const ATLAS_FILE: &str = "character_male.png";
+
+/// Image URI and pixel size of the atlas; `None` when it isn't installed.
+static ATLAS: LazyLock<Option<(String, [f32; 2])>> = LazyLock::new(|| {
+    let path = PATH_RES.join(ATLAS_FILE);
+    let (width, height) = image::image_dimensions(&path).ok()?;
+    Some((format!("file://{}", path.display()), [width as f32, height as f32]))
+});
+
+/// Pieces tinted with the skin color; the rest take the clothes color.
+const SKIN_PIECES: [&str; 6] = ["HEAD", "NECK", "HAND_0_R", "HAND_1_R", "HAND_0_L", "HAND_1_L"];
 
 /// Describes the pixel bounds for a single sprite slice inside the character
 /// creator atlas. This keeps the layout declarative so both the UI and any
@@ -57,3 +77,201 @@ pub const MALE_BODY_SPRITES: &[(&str, SpriteSlice)] = &[
 pub fn male_body_sprite_map() -> HashMap<&'static str, SpriteSlice> {
     MALE_BODY_SPRITES.iter().copied().collect()
 }
+
+/// Pieces of the figure for `look`, back to front, with where each goes in
+/// atlas pixels around a center line at x = 0.
+fn figure_parts(look: &CharacterLook) -> Vec<(&'static str, Rect)> {
+    let sprites = male_body_sprite_map();
+    let right_hand = if look.right_hand == 1 { "HAND_1_R" } else { "HAND_0_R" };
+    let left_hand = if look.left_hand == 1 { "HAND_1_L" } else { "HAND_0_L" };
+    let (right_shin, right_foot) = if look.front_leg {
+        ("LOWER_LEG_F", "FOOT_F")
+    } else {
+        ("LOWER_LEG_R", "FOOT_R")
+    };
+    // Center x and top of each piece; limbs hang from the one above them.
+    let layout: [(&'static str, f32, f32); 16] = [
+        ("NECK", 0.0, 150.0),
+        ("UPPER_LEG_L", 55.0, 740.0),
+        ("LOWER_LEG_L", 55.0, 1010.0),
+        ("FOOT_L", 75.0, 1290.0),
+        ("UPPER_LEG_R", -55.0, 740.0),
+        (right_shin, -55.0, 1010.0),
+        (right_foot, -75.0, 1290.0),
+        ("HIP", 0.0, 620.0),
+        ("BODY", 0.0, 250.0),
+        ("UPPER_ARM_L", 176.0, 270.0),
+        ("LOWER_ARM_L", 176.0, 570.0),
+        (left_hand, 176.0, 740.0),
+        ("UPPER_ARM_R", -176.0, 270.0),
+        ("LOWER_ARM_R", -176.0, 510.0),
+        (right_hand, -176.0, 810.0),
+        ("HEAD", 0.0, 0.0),
+    ];
+    layout
+        .into_iter()
+        .map(|(name, center_x, top)| {
+            let slice = sprites[name];
+            let size = vec2(slice.width as f32, slice.height as f32);
+            (name, Rect::from_min_size(pos2(center_x - size.x / 2.0, top), size))
+        })
+        .collect()
+}
+
+/// Draws `look` scaled to fit `rect`. Returns false without drawing when the
+/// atlas isn't installed.
+pub fn paint_character(ui: &Ui, look: &CharacterLook, rect: Rect) -> bool {
+    let Some((uri, [atlas_width, atlas_height])) = ATLAS.as_ref() else {
+        return false;
+    };
+    let parts = figure_parts(look);
+    let bounds = parts
+        .iter()
+        .fold(Rect::NOTHING, |bounds, (_, part)| bounds.union(*part));
+    let scale = (rect.width() / bounds.width()).min(rect.height() / bounds.height());
+    let origin = rect.center() - bounds.center().to_vec2() * scale;
+    let tint = |color: &Option<String>| {
+        color
+            .as_deref()
+            .and_then(|hex| Color32::from_hex(hex).ok())
+            .unwrap_or(Color32::WHITE)
+    };
+    let sprites = male_body_sprite_map();
+    for (name, part) in parts {
+        let slice = sprites[name];
+        let uv = Rect::from_min_size(
+            pos2(slice.x as f32 / atlas_width, slice.y as f32 / atlas_height),
+            vec2(slice.width as f32 / atlas_width, slice.height as f32 / atlas_height),
+        );
+        let color = if SKIN_PIECES.contains(&name) {
+            tint(&look.skin)
+        } else {
+            tint(&look.clothes)
+        };
+        let target = Rect::from_min_max(
+            origin + part.min.to_vec2() * scale,
+            origin + part.max.to_vec2() * scale,
+        );
+        egui::Image::new(uri.as_str()).uv(uv).tint(color).paint_at(ui, target);
+    }
+    true
+}
+
+impl PartyApp {
+    /// Opens the character creator on `profile`.
+    pub fn open_character_page(&mut self, profile: &str) {
+        self.character_profile = Some(profile.to_string());
+        self.cur_page = MenuPage::Character;
+    }
+
+    /// Builds a figure from the atlas pieces for one profile, shown next to
+    /// the profile on the instances page.
+    pub fn display_page_character(&mut self, ui: &mut Ui) {
+        let Some(profile) = self.character_profile.clone() else {
+            self.cur_page = MenuPage::Profiles;
+            return;
+        };
+
+        ui.horizontal(|row| {
+            let back_button = row.button("Back to Profiles");
+            self.decorate_focus(row, &back_button);
+            if back_button.clicked() {
+                self.cur_page = MenuPage::Profiles;
+            }
+            row.heading(format!("Character · {profile}"));
+        });
+        ui.separator();
+
+        if ATLAS.is_none() {
+            ui.label(format!(
+                "The character atlas isn't installed. Place {ATLAS_FILE} in {} to use the character creator.",
+                PATH_RES.display()
+            ));
+            return;
+        }
+
+        let stored = self
+            .profile_meta
+            .entry(profile.clone())
+            .or_insert_with(|| Profile::load(&profile))
+            .character();
+        let before = stored.clone().unwrap_or_default();
+        let mut look = before.clone();
+        let mut remove = false;
+
+        ui.horizontal_top(|columns| {
+            let (preview, _) =
+                columns.allocate_exact_size(vec2(220.0, 320.0), egui::Sense::hover());
+            paint_character(columns, &look, preview.shrink(8.0));
+
+            columns.vertical(|controls| {
+                for (label, hand) in [
+                    ("Right hand", &mut look.right_hand),
+                    ("Left hand", &mut look.left_hand),
+                ] {
+                    controls.horizontal(|row| {
+                        row.label(label);
+                        for (value, text) in [(0, "Open"), (1, "Closed")] {
+                            let choice = row.radio_value(hand, value, text);
+                            self.decorate_focus(row, &choice);
+                        }
+                    });
+                }
+                let leg_check = controls.checkbox(&mut look.front_leg, "Step forward");
+                self.decorate_focus(controls, &leg_check);
+
+                for (label, color) in [("Skin", &mut look.skin), ("Clothes", &mut look.clothes)] {
+                    controls.horizontal(|row| {
+                        row.label(label);
+                        let current = color
+                            .as_deref()
+                            .and_then(|hex| Color32::from_hex(hex).ok())
+                            .unwrap_or(Color32::WHITE);
+                        let mut rgb = [current.r(), current.g(), current.b()];
+                        let color_button =
+                            egui::color_picker::color_edit_button_srgb(row, &mut rgb);
+                        self.decorate_focus(row, &color_button);
+                        if color_button.changed() {
+                            let [r, g, b] = rgb;
+                            *color = Some(format!("#{r:02x}{g:02x}{b:02x}"));
+                        }
+                        if color.is_some() {
+                            let reset_button = row.small_button("Reset");
+                            self.decorate_focus(row, &reset_button);
+                            if reset_button.clicked() {
+                                *color = None;
+                            }
+                        }
+                    });
+                }
+
+                controls.add_space(8.0);
+                controls.label(
+                    RichText::new("Shown next to this profile on the instances page.").weak(),
+                );
+                if stored.is_some() {
+                    let remove_button = controls.button("Remove Character");
+                    self.decorate_focus(controls, &remove_button);
+                    if remove_button.clicked() {
+                        remove = true;
+                    }
+                }
+            });
+        });
+
+        let wanted = if remove {
+            None
+        } else if look != before {
+            Some(look)
+        } else {
+            return;
+        };
+        // Saved once the pointer is released, not on every frame of a drag.
+        if let Some(meta) = self.profile_meta.get_mut(&profile) {
+            meta.set_character(wanted.as_ref());
+        }
+        if !self.profile_saves_pending.contains(&profile) {
+            self.profile_saves_pending.push(profile);
+        }
+    }
+}
@@ -0,0 +1,157 @@
+use super::app::PartyApp;
+use crate::game::Game::HandlerRef;
+use crate::util::*;
+
+use eframe::egui::{self, RichText, Ui};
+
+/// State of the game page's "Export to Steam Cloud" section.
+#[derive(Default)]
+pub struct CloudExport {
+    profile: usize,
+    account: usize,
+    /// Dry run for the picked profile and account, dropped when either changes.
+    preview: Option<Result<SaveExportPlan, String>>,
+}
+
+/// `1.2 KiB`-style size for the export preview.
+fn file_size(bytes: u64) -> String {
+    if bytes < 1024 {
+        format!("{bytes} B")
+    } else if bytes < 1024 * 1024 {
+        format!("{:.1} KiB", bytes as f64 / 1024.0)
+    } else {
+        format!("{:.1} MiB", bytes as f64 / (1024.0 * 1024.0))
+    }
+}
+
+impl PartyApp {
+    /// Copies a profile's Goldberg cloud saves into a real Steam account, so
+    /// a player can carry on with their couch progress on their own account.
+    /// Shows what would be copied before anything is written.
+    pub fn display_cloud_export(&mut self, ui: &mut Ui) {
+        let HandlerRef(h) = &self.games[self.selected_game] else {
+            return;
+        };
+        // Only Goldberg handlers keep Steam cloud files per profile.
+        let Some(appid) = h.steam_appid.clone().filter(|_| !h.path_goldberg.is_empty()) else {
+            return;
+        };
+
+        egui::CollapsingHeader::new("Export to Steam Cloud")
+            .id_salt("game_cloud_export")
+            .show(ui, |ui| {
+                // Listed only while the section is open.
+                let profiles = scan_profiles(false);
+                let accounts = steam_accounts();
+                if profiles.is_empty() || accounts.is_empty() {
+                    ui.label("Needs a profile and a Steam account that has signed in on this machine.");
+                    return;
+                }
+                let export = &mut self.cloud_export;
+                if export.profile >= profiles.len() || export.account >= accounts.len() {
+                    *export = CloudExport::default();
+                }
+                let (mut profile, mut account) = (export.profile, export.account);
+
+                let mut preview_clicked = false;
+                ui.horizontal(|row| {
+                    row.label("Profile");
+                    let profile_combo = egui::ComboBox::from_id_salt("cloud_export_profile")
+                        .selected_text(profiles[profile].as_str())
+                        .show_ui(row, |combo| {
+                            for (i, name) in profiles.iter().enumerate() {
+                                combo.selectable_value(&mut profile, i, name);
+                            }
+                        });
+                    self.decorate_focus(row, &profile_combo.response);
+
+                    row.label("Steam account");
+                    let account_combo = egui::ComboBox::from_id_salt("cloud_export_account")
+                        .selected_text(accounts[account].name.as_str())
+                        .show_ui(row, |combo| {
+                            for (i, steam_account) in accounts.iter().enumerate() {
+                                combo.selectable_value(&mut account, i, &steam_account.name);
+                            }
+                        });
+                    self.decorate_focus(row, &account_combo.response);
+
+                    let preview_btn = row.button("Preview");
+                    self.decorate_focus(row, &preview_btn);
+                    preview_clicked = preview_btn.clicked();
+                    if preview_btn.hovered() {
+                        self.infotext = "Lists the save files the export would copy into the Steam account, without changing anything.".to_string();
+                    }
+                });
+
+                let export = &mut self.cloud_export;
+                if (profile, account) != (export.profile, export.account) {
+                    export.profile = profile;
+                    export.account = account;
+                    export.preview = None;
+                }
+                if preview_clicked {
+                    export.preview = Some(
+                        plan_goldberg_save_export(
+                            &profiles[profile],
+                            &appid,
+                            accounts[account].account_id,
+                        )
+                        .map_err(|err| err.to_string()),
+                    );
+                }
+
+                // Taken out while drawn so the buttons can borrow the app.
+                let preview = self.cloud_export.preview.take();
+                let mut apply = false;
+                match &preview {
+                    None => {}
+                    Some(Err(err)) => {
+                        ui.label(RichText::new(err).color(ui.visuals().warn_fg_color));
+                    }
+                    Some(Ok(plan)) => {
+                        for entry in &plan.entries {
+                            let text = format!(
+                                "{} ({}, {})",
+                                entry.path.display(),
+                                entry.action.label(),
+                                file_size(entry.size)
+                            );
+                            match entry.action {
+                                SaveExportAction::Unchanged => ui.weak(text),
+                                _ => ui.label(text),
+                            };
+                        }
+                        let changes = plan.changes().count();
+                        if changes == 0 {
+                            ui.label("The Steam account already has these saves.");
+                        } else {
+                            let export_btn = ui.button(format!("Export {changes} files"));
+                            self.decorate_focus(ui, &export_btn);
+                            apply = export_btn.clicked()
+                                && yesno(
+                                    "Export saves?",
+                                    &format!(
+                                        "Copy {changes} save files into {}? Files Steam already has are backed up in the Split Happens data folder first. Start the game from Steam afterwards so it uploads them.",
+                                        plan.target.display()
+                                    ),
+                                );
+                        }
+                    }
+                }
+
+                match preview {
+                    // The preview is stale once the files were copied.
+                    Some(Ok(plan)) if apply => match apply_save_export(&plan) {
+                        Ok(written) => msg(
+                            "Saves exported",
+                            &format!("Copied {written} files to {}.", plan.target.display()),
+                        ),
+                        Err(err) => {
+                            msg("Export Error", &format!("Couldn't export saves: {err}"));
+                        }
+                    },
+                    preview => self.cloud_export.preview = preview,
+                }
+            });
+    }
+}
@@ -0,0 +1,242 @@
+use super::app::PartyApp;
+use crate::config::save_cfg;
+use crate::game::Game::HandlerRef;
+use crate::util::*;
+
+use eframe::egui::{self, RichText, Ui};
+
+/// Choices of the game page's "Share Saves" section.
+#[derive(Default)]
+pub struct SaveTransferChoice {
+    /// Index into the compatible uids that have saves.
+    pub source: usize,
+    /// 0 for every profile, otherwise an index into the source's profiles + 1.
+    pub profile: usize,
+    pub mode: SaveTransfer,
+}
+
+impl PartyApp {
+    /// Moves saves and settings stored under the handler uid `old` to the
+    /// selected handler, then reports what moved.
+    fn migrate_selected_game_from(&mut self, old: &str) {
+        let HandlerRef(h) = &self.games[self.selected_game] else {
+            return;
+        };
+        let new = h.uid.clone();
+        match migrate_game_uid(old, &new) {
+            Ok(report) => {
+                self.options.migrate_game_uid(old, &new);
+                let _ = save_cfg(&self.options);
+                self.orphaned_uids = None;
+                self.migration_choice = 0;
+                self.launch_history = None;
+                self.play_stats = None;
+                self.launch_options = None;
+                let summary = if report.is_empty() {
+                    "Only settings were stored under the old uid.".to_string()
+                } else {
+                    report.join("\n")
+                };
+                msg("Data migrated", &summary);
+            }
+            Err(err) => msg("Migration Error", &format!("Couldn't migrate {old}: {err}")),
+        }
+    }
+
+    /// Saves left under another handler uid, e.g. after an author renamed the
+    /// handler. Uids the handler lists in `handler.former_uids` are offered
+    /// directly; any other orphaned uid can be picked by hand.
+    pub fn display_data_migration(&mut self, ui: &mut Ui) {
+        let HandlerRef(h) = &self.games[self.selected_game] else {
+            return;
+        };
+        let former_uids = h.former_uids.clone();
+        if self.orphaned_uids.is_none() {
+            let installed: Vec<&str> = self
+                .games
+                .iter()
+                .filter_map(|game| match game {
+                    HandlerRef(h) => Some(h.uid.as_str()),
+                    _ => None,
+                })
+                .collect();
+            self.orphaned_uids = Some(orphaned_game_uids(&installed));
+        }
+        let orphans = self.orphaned_uids.clone().unwrap_or_default();
+        if orphans.is_empty() {
+            return;
+        }
+
+        let mut migrate_from: Option<String> = None;
+        for old in former_uids.iter().filter(|uid| orphans.contains(uid)) {
+            ui.horizontal(|row| {
+                row.label(
+                    RichText::new(format!("Saves from this handler's former uid {old} were found."))
+                        .color(row.visuals().warn_fg_color),
+                );
+                let move_btn = row.button("Move to this handler");
+                self.decorate_focus(row, &move_btn);
+                if move_btn.clicked() {
+                    migrate_from = Some(old.clone());
+                }
+            });
+        }
+
+        egui::CollapsingHeader::new("Migrate Data")
+            .id_salt("game_data_migration")
+            .show(ui, |ui| {
+                if self.migration_choice >= orphans.len() {
+                    self.migration_choice = 0;
+                }
+                ui.horizontal(|row| {
+                    row.label("From uid");
+                    let combo = egui::ComboBox::from_id_salt("game_data_migration_uid")
+                        .selected_text(orphans[self.migration_choice].as_str())
+                        .show_ui(row, |combo| {
+                            for (i, uid) in orphans.iter().enumerate() {
+                                combo.selectable_value(&mut self.migration_choice, i, uid.as_str());
+                            }
+                        });
+                    self.decorate_focus(row, &combo.response);
+                    let migrate_btn = row.button("Migrate");
+                    self.decorate_focus(row, &migrate_btn);
+                    if migrate_btn.clicked() {
+                        let old = &orphans[self.migration_choice];
+                        if yesno(
+                            "Migrate data?",
+                            &format!(
+                                "Move every profile's saves, the game folder, launch overrides and history from {old} to this handler? Data this handler already has is kept."
+                            ),
+                        ) {
+                            migrate_from = Some(old.clone());
+                        }
+                    }
+                    if combo.response.hovered() || migrate_btn.hovered() {
+                        self.infotext = "Uids that still have saves or a game folder but no installed handler, for example after a handler was republished under a new uid. Migrating moves that data to this game so players keep their progress.".to_string();
+                    }
+                });
+            });
+
+        if let Some(old) = migrate_from {
+            self.migrate_selected_game_from(&old);
+        }
+    }
+
+    /// Uids whose saves the selected handler can use: the ones it declares in
+    /// `handler.save_compatible_uids`, plus installed handlers declaring it.
+    fn save_compatible_uids(&self) -> Vec<String> {
+        let HandlerRef(h) = &self.games[self.selected_game] else {
+            return Vec::new();
+        };
+        let mut uids = h.save_compatible_uids.clone();
+        for game in &self.games {
+            let HandlerRef(other) = game else {
+                continue;
+            };
+            if other.save_compatible_uids.contains(&h.uid) && !uids.contains(&other.uid) {
+                uids.push(other.uid.clone());
+            }
+        }
+        uids.retain(|uid| *uid != h.uid);
+        uids
+    }
+
+    /// Copies or links saves from a handler of the same game published under
+    /// another uid, e.g. another store's release, for one or every profile.
+    pub fn display_save_transfer(&mut self, ui: &mut Ui) {
+        let HandlerRef(h) = &self.games[self.selected_game] else {
+            return;
+        };
+        let target = h.uid.clone();
+        let compatible = self.save_compatible_uids();
+        if compatible.is_empty() {
+            return;
+        }
+
+        let mut transfer: Option<(String, Vec<String>)> = None;
+        egui::CollapsingHeader::new("Share Saves")
+            .id_salt("game_save_transfer")
+            .show(ui, |ui| {
+                // Scanned only while the section is open.
+                let sources: Vec<(String, Vec<String>)> = compatible
+                    .iter()
+                    .map(|uid| (uid.clone(), profiles_with_saves(uid)))
+                    .filter(|(_, profiles)| !profiles.is_empty())
+                    .collect();
+                if sources.is_empty() {
+                    ui.label("No profile has saves under a compatible handler yet.");
+                    return;
+                }
+                if self.save_transfer.source >= sources.len() {
+                    self.save_transfer.source = 0;
+                }
+                let (source, profiles) = &sources[self.save_transfer.source];
+                if self.save_transfer.profile > profiles.len() {
+                    self.save_transfer.profile = 0;
+                }
+
+                ui.horizontal(|row| {
+                    row.label("From uid");
+                    let source_combo = egui::ComboBox::from_id_salt("game_save_transfer_uid")
+                        .selected_text(source.as_str())
+                        .show_ui(row, |combo| {
+                            for (i, (uid, _)) in sources.iter().enumerate() {
+                                combo.selectable_value(&mut self.save_transfer.source, i, uid);
+                            }
+                        });
+                    self.decorate_focus(row, &source_combo.response);
+
+                    let profile_text = match self.save_transfer.profile {
+                        0 => "All profiles",
+                        i => profiles[i - 1].as_str(),
+                    };
+                    let profile_combo =
+                        egui::ComboBox::from_id_salt("game_save_transfer_profile")
+                            .selected_text(profile_text)
+                            .show_ui(row, |combo| {
+                                let choice = &mut self.save_transfer.profile;
+                                combo.selectable_value(choice, 0, "All profiles");
+                                for (i, profile) in profiles.iter().enumerate() {
+                                    combo.selectable_value(choice, i + 1, profile);
+                                }
+                            });
+                    self.decorate_focus(row, &profile_combo.response);
+
+                    for mode in SaveTransfer::ALL {
+                        let mode_btn =
+                            row.radio_value(&mut self.save_transfer.mode, mode, mode.label());
+                        self.decorate_focus(row, &mode_btn);
+                    }
+
+                    let apply_btn = row.button("Apply");
+                    self.decorate_focus(row, &apply_btn);
+                    if apply_btn.clicked() {
+                        let chosen = match self.save_transfer.profile {
+                            0 => profiles.clone(),
+                            i => vec![profiles[i - 1].clone()],
+                        };
+                        let verb = self.save_transfer.mode.label();
+                        if yesno(
+                            "Share saves?",
+                            &format!(
+                                "{verb} the saves of {} from {source} to this handler? Profiles that already have saves here keep them.",
+                                chosen.join(", ")
+                            ),
+                        ) {
+                            transfer = Some((source.clone(), chosen));
+                        }
+                    }
+                    if apply_btn.hovered() || source_combo.response.hovered() {
+                        self.infotext = "Handlers for the same game from another store can share saves when their formats match. Copy gives this handler its own copy; Link makes both handlers use one save folder.".to_string();
+                    }
+                });
+            });
+
+        if let Some((source, profiles)) = transfer {
+            match transfer_profile_saves(&source, &target, &profiles, self.save_transfer.mode) {
+                Ok(report) => msg("Saves shared", &report.join("\n")),
+                Err(err) => msg("Share Error", &format!("Couldn't share saves: {err}")),
+            }
+        }
+    }
+}
@@ -0,0 +1,105 @@
+use super::app::PartyApp;
+use crate::util::msg;
+
+use eframe::egui::{self, Ui};
+use qrcode::QrCode;
+
+impl PartyApp {
+    /// Renders the save handoff window of a guest profile while one is open.
+    /// Its QR code opens the guest's page on the web remote.
+    pub fn display_guest_handoff(&mut self, ctx: &egui::Context) {
+        let Some(name) = self.guest_handoff.take() else {
+            return;
+        };
+        // Closes by itself when the web remote is switched off.
+        let Some(remote) = &self.web_remote else {
+            return;
+        };
+        let url = remote.guest_handoff_url(&name);
+
+        let mut open = true;
+        egui::Window::new(format!("Save handoff: {name}"))
+            .collapsible(false)
+            .resizable(false)
+            .anchor(egui::Align2::CENTER_CENTER, egui::Vec2::ZERO)
+            .show(ctx, |ui| {
+                ui.label("Scan this with the guest's phone to download their progress at the end of the night, or to restore a save they brought from an earlier one.");
+                match QrCode::new(url.as_bytes()) {
+                    Ok(code) => paint_qr_code(ui, &code, 240.0),
+                    Err(err) => {
+                        ui.label(format!("Couldn't create the QR code: {err}"));
+                    }
+                }
+                ui.label(&url);
+                if ui.button("Close").clicked() {
+                    open = false;
+                }
+            });
+
+        if open {
+            self.guest_handoff = Some(name);
+        }
+    }
+}
+
+impl PartyApp {
+    /// Asks the host to restore or discard a guest save uploaded from a
+    /// phone, so nobody else on the network can overwrite a guest's progress.
+    pub fn display_guest_uploads(&mut self, ctx: &egui::Context) {
+        let Some(remote) = &self.web_remote else {
+            return;
+        };
+        let Some(upload) = remote.pending_guest_upload() else {
+            return;
+        };
+
+        let mut choice = None;
+        egui::Window::new("Restore guest save")
+            .collapsible(false)
+            .resizable(false)
+            .anchor(egui::Align2::CENTER_CENTER, egui::Vec2::ZERO)
+            .show(ctx, |ui| {
+                ui.label(format!(
+                    "A phone uploaded a save for {}. Restoring it replaces that guest's current progress.",
+                    upload.name
+                ));
+                ui.horizontal(|ui| {
+                    if ui.button("Restore").clicked() {
+                        choice = Some(true);
+                    }
+                    if ui.button("Discard").clicked() {
+                        choice = Some(false);
+                    }
+                });
+            });
+
+        if let Some(restore) = choice {
+            if let Err(err) = remote.resolve_guest_upload(&upload, restore) {
+                msg("Save restore failed", &err.to_string());
+            }
+        }
+    }
+}
+
+/// Draws `code` as black modules on white, with the four-module quiet zone
+/// scanners need around it.
+fn paint_qr_code(ui: &mut Ui, code: &QrCode, size: f32) {
+    let width = code.width();
+    let (rect, _) = ui.allocate_exact_size(egui::vec2(size, size), egui::Sense::hover());
+    let painter = ui.painter_at(rect);
+    painter.rect_filled(rect, 0.0, egui::Color32::WHITE);
+
+    let module = size / (width + 8) as f32;
+    for (index, color) in code.to_colors().into_iter().enumerate() {
+        if color != qrcode::Color::Dark {
+            continue;
+        }
+        let x = (index % width + 4) as f32 * module;
+        let y = (index / width + 4) as f32 * module;
+        painter.rect_filled(
+            egui::Rect::from_min_size(rect.min + egui::vec2(x, y), egui::vec2(module, module)),
+            0.0,
+            egui::Color32::BLACK,
+        );
+    }
+}
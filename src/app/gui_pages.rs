@@ -1,13 +1,17 @@
-use super::app::PartyApp;
-use super::config::*;
-use crate::game::{Game::*, remove_game};
+use super::app::{PartyApp, start_cec_remote, start_coordinator, start_web_remote};
+use super::character_creator::paint_character;
+use super::launch_options::{launch_age, launch_history_id};
+use super::settings_widgets::*;
+use crate::config::*;
+use crate::game::{Game, Game::*, remove_game};
 use crate::input::*;
+use crate::instance::InstancePlatform;
 use crate::paths::*;
 use crate::util::*;
 
 use dialog::DialogBox;
 use eframe::egui::RichText;
-use eframe::egui::{self, Ui};
+use eframe::egui::{self, ImageSource, Ui};
 use egui_extras::{Size, StripBuilder};
 
 macro_rules! cur_game {
@@ -16,6 +20,43 @@ macro_rules! cur_game {
     };
 }
 
+/// Library icon of `game`: the handler's icon.png, or a generic one for
+/// executables.
+fn game_icon(game: &Game) -> ImageSource<'_> {
+    match game {
+        ExecRef(_) => egui::include_image!("../../res/executable_icon.png"),
+        HandlerRef(handler) => format!("file://{}/icon.png", handler.path_handler.display()).into(),
+    }
+}
+
+/// Round avatar of `profile`, or a disc in its color with its initial,
+/// ringed in the profile's color.
+fn profile_badge(ui: &mut Ui, profile: &Profile, slot: usize, size: f32) {
+    let color = egui::Color32::from_hex(&profile.color(slot))
+        .unwrap_or(ui.visuals().selection.bg_fill);
+    let (rect, _) = ui.allocate_exact_size(egui::vec2(size, size), egui::Sense::hover());
+    match profile.avatar_path() {
+        Some(path) => {
+            egui::Image::new(format!("file://{}", path.display()))
+                .corner_radius(egui::CornerRadius::same((size / 2.0) as u8))
+                .paint_at(ui, rect);
+            ui.painter()
+                .circle_stroke(rect.center(), size / 2.0, egui::Stroke::new(2.0, color));
+        }
+        None => {
+            ui.painter().circle_filled(rect.center(), size / 2.0, color);
+            let initial = profile.display_name().chars().next().unwrap_or('?');
+            ui.painter().text(
+                rect.center(),
+                egui::Align2::CENTER_CENTER,
+                initial.to_uppercase().to_string(),
+                egui::FontId::proportional(size * 0.55),
+                egui::Color32::WHITE,
+            );
+        }
+    }
+}
+
 impl PartyApp {
     pub fn display_page_main(&mut self, ui: &mut Ui) {
         // Provide gentle breathing room between the navigation bar and the tile grid.
@@ -29,13 +70,19 @@ impl PartyApp {
             return;
         }
 
+        self.display_presets(ui);
+
         // Arrange the responsive tile grid with generous spacing so artwork
         // stays prominent on both desktop and Steam Deck screens.
         let mut refresh_games = false;
-        let tile_spacing = 16.0;
-        // Expand the responsive tile baseline so every game card renders about 25%
-        // larger than before, keeping hero art impactful even on wider displays.
-        let min_tile_width = 200.0;
+        let density = self.options.tile_density;
+        // Compact tiles also pack closer so more rows fit on a TV.
+        let tile_spacing = match density {
+            TileDensity::Compact => 10.0,
+            _ => 16.0,
+        };
+        let min_tile_width = density.min_tile_width();
+        let (min_image_height, max_image_height) = density.image_height_range();
 
         egui::ScrollArea::vertical()
             .auto_shrink([false; 2])
@@ -73,11 +120,12 @@ impl PartyApp {
                         for index in start..end {
                             let game = self.games[index].to_owned();
                             let removal_game = game.to_owned();
-                            let image_height = (tile_width * 9.0 / 16.0).clamp(100.0, 200.0);
+                            let image_height = (tile_width * 9.0 / 16.0)
+                                .clamp(min_image_height, max_image_height);
                             let hero_total_height = image_height;
                             // Tighten the tile height so the reduced hero art doesn't leave
                             // oversized padding around the title text.
-                            let tile_height = hero_total_height + 52.0;
+                            let tile_height = hero_total_height + density.title_size() + 32.0;
 
                             let (rect, response) = row_ui.allocate_exact_size(
                                 egui::vec2(tile_width, tile_height),
@@ -122,7 +170,19 @@ impl PartyApp {
                                         .allocate_exact_size(hero_size, egui::Sense::hover());
 
                                     let image_rect = hero_rect;
-                                    if let Some(hero_path) = game.hero_image_path() {
+                                    // SteamGridDB heroes win over the Steam header, with the
+                                    // text-free hero getting the game's logo on top.
+                                    let artwork = self
+                                        .artwork
+                                        .get(&game.persistent_id())
+                                        .cloned()
+                                        .unwrap_or_default();
+                                    let logo = artwork.hero.as_ref().and(artwork.logo);
+                                    let hero_path = artwork
+                                        .hero
+                                        .or_else(|| game.hero_image_path())
+                                        .or(artwork.grid);
+                                    if let Some(hero_path) = hero_path {
                                         let hero_widget = egui::Image::new(format!(
                                             "file://{}",
                                             hero_path.display()
@@ -130,20 +190,35 @@ impl PartyApp {
                                         .fit_to_exact_size(image_rect.size())
                                         .maintain_aspect_ratio(true);
                                         tile_ui.put(image_rect, hero_widget);
+                                        if let Some(logo_path) = logo {
+                                            let logo_rect = egui::Rect::from_center_size(
+                                                image_rect.center(),
+                                                image_rect.size() * 0.6,
+                                            );
+                                            let logo_widget = egui::Image::new(format!(
+                                                "file://{}",
+                                                logo_path.display()
+                                            ))
+                                            .fit_to_exact_size(logo_rect.size())
+                                            .maintain_aspect_ratio(true);
+                                            tile_ui.put(logo_rect, logo_widget);
+                                        }
                                     } else {
                                         let icon_size = image_height.min(128.0);
                                         let icon_rect = egui::Rect::from_center_size(
                                             image_rect.center(),
                                             egui::vec2(icon_size, icon_size),
                                         );
-                                        let icon_widget = egui::Image::new(game.icon())
+                                        let icon_widget = egui::Image::new(game_icon(game))
                                             .fit_to_exact_size(icon_rect.size());
                                         tile_ui.put(icon_rect, icon_widget);
                                     }
 
                                     tile_ui.add_space(5.0);
                                     tile_ui.label(
-                                        egui::RichText::new(game.name()).size(20.0).strong(),
+                                        egui::RichText::new(game.name())
+                                            .size(density.title_size())
+                                            .strong(),
                                     );
                                 });
 
@@ -287,6 +362,42 @@ impl PartyApp {
                     self.display_settings_performance(performance);
                 });
 
+                scroll.add_space(18.0);
+                // LAN diagnostics share the card styling so discovery debugging
+                // lives next to the settings that influence it.
+                let network_frame = egui::Frame::new()
+                    .fill(scroll.visuals().widgets.noninteractive.bg_fill)
+                    .stroke(egui::Stroke::new(
+                        1.0,
+                        scroll.visuals().widgets.inactive.bg_stroke.color,
+                    ))
+                    .corner_radius(egui::CornerRadius::same(12))
+                    .inner_margin(egui::Margin::symmetric(18, 16));
+                network_frame.show(scroll, |network| {
+                    network.spacing_mut().item_spacing.y = 12.0;
+                    network.heading("LAN Diagnostics");
+                    network.separator();
+                    self.display_settings_network(network);
+                });
+
+                scroll.add_space(18.0);
+                // Tool and runtime checks, so a missing dependency shows up here
+                // rather than halfway through a launch.
+                let check_frame = egui::Frame::new()
+                    .fill(scroll.visuals().widgets.noninteractive.bg_fill)
+                    .stroke(egui::Stroke::new(
+                        1.0,
+                        scroll.visuals().widgets.inactive.bg_stroke.color,
+                    ))
+                    .corner_radius(egui::CornerRadius::same(12))
+                    .inner_margin(egui::Margin::symmetric(18, 16));
+                check_frame.show(scroll, |check| {
+                    check.spacing_mut().item_spacing.y = 12.0;
+                    check.heading("System Check");
+                    check.separator();
+                    self.display_settings_system_check(check);
+                });
+
                 scroll.add_space(18.0);
                 // Keep persistence controls anchored at the bottom with a
                 // consistent compact layout.
@@ -328,9 +439,15 @@ impl PartyApp {
                         .corner_radius(egui::CornerRadius::same(12))
                         .inner_margin(egui::Margin::symmetric(18, 12));
 
+                    let meta = self
+                        .profile_meta
+                        .entry(profile.clone())
+                        .or_insert_with(|| Profile::load(&profile))
+                        .clone();
                     frame.show(ui, |row_ui| {
                         row_ui.horizontal(|row| {
                             let profile_name = profile.as_str();
+                            profile_badge(row, &meta, 0, 40.0);
                             row.label(RichText::new(profile_name).size(22.0).strong());
                             row.with_layout(
                                 egui::Layout::right_to_left(egui::Align::Center),
@@ -379,6 +496,9 @@ impl PartyApp {
                                                     profile_name,
                                                     trimmed,
                                                 );
+                                                self.profile_inputs.remove(profile_name);
+                                                self.play_stats = None;
+                                                self.profile_meta.remove(profile_name);
                                                 if let Err(err) = save_cfg(&self.options) {
                                                     msg(
                                                         "Error",
@@ -395,6 +515,255 @@ impl PartyApp {
                                 },
                             );
                         });
+
+                        // Playtime across every game, with the one played last.
+                        let played = self.play_stats().profile_total(&profile).map(
+                            |(total, game_id)| (total, game_id.to_string()),
+                        );
+                        if let Some((total, game_id)) = played {
+                            let game_name = self
+                                .games
+                                .iter()
+                                .find(|game| launch_history_id(game) == game_id)
+                                .map_or(game_id.clone(), |game| game.name().to_string());
+                            row_ui.label(
+                                RichText::new(format!(
+                                    "Played {} · last {game_name} {}",
+                                    format_playtime(total.seconds),
+                                    launch_age(total.last_played)
+                                ))
+                                .weak(),
+                            );
+                        }
+
+                        // Avatar and color, also shown on the instances page and
+                        // copied to Goldberg as the in-game avatar.
+                        row_ui.horizontal(|row| {
+                            let avatar_uri = meta
+                                .avatar_path()
+                                .map(|path| format!("file://{}", path.display()));
+                            let avatar_button = row.button("Choose Avatar");
+                            self.decorate_focus(row, &avatar_button);
+                            if avatar_button.hovered() {
+                                self.infotext = "Picks a PNG or JPEG shown for this profile in the launcher. Goldberg games show it as the player's Steam avatar.".to_string();
+                            }
+                            let mut avatar_changed = false;
+                            if avatar_button.clicked() {
+                                match pick_profile_avatar(&profile) {
+                                    Ok(picked) => avatar_changed = picked,
+                                    Err(err) => {
+                                        msg("Error", &format!("Couldn't set the avatar: {err}"))
+                                    }
+                                }
+                            }
+                            if meta.avatar.is_some() {
+                                let remove_button = row.button("Remove Avatar");
+                                self.decorate_focus(row, &remove_button);
+                                if remove_button.clicked() {
+                                    if let Err(err) = set_profile_avatar(&profile, None) {
+                                        msg("Error", &format!("Couldn't remove the avatar: {err}"));
+                                    }
+                                    avatar_changed = true;
+                                }
+                            }
+                            if avatar_changed {
+                                // The new file has the same name, so drop the cached image.
+                                if let Some(uri) = &avatar_uri {
+                                    row.ctx().forget_image(uri);
+                                }
+                                self.profile_meta.remove(&profile);
+                            }
+
+                            row.label("Color");
+                            let current =
+                                egui::Color32::from_hex(&meta.color(0)).unwrap_or_default();
+                            let mut rgb = [current.r(), current.g(), current.b()];
+                            let color_button =
+                                egui::color_picker::color_edit_button_srgb(row, &mut rgb);
+                            self.decorate_focus(row, &color_button);
+                            if color_button.hovered() {
+                                self.infotext = "Color of this player's window border and badge. Unset, named profiles get a fixed color from the palette.".to_string();
+                            }
+                            let mut color = meta.color.clone();
+                            if color_button.changed() {
+                                let [r, g, b] = rgb;
+                                color = Some(format!("#{r:02x}{g:02x}{b:02x}"));
+                            }
+                            if meta.color.is_some() {
+                                let reset_button = row.button("Default Color");
+                                self.decorate_focus(row, &reset_button);
+                                if reset_button.clicked() {
+                                    color = None;
+                                }
+                            }
+                            if color != meta.color {
+                                let mut edited = meta.clone();
+                                edited.color = color;
+                                self.profile_meta.insert(profile.clone(), edited);
+                                if !self.profile_saves_pending.contains(&profile) {
+                                    self.profile_saves_pending.push(profile.clone());
+                                }
+                            }
+
+                            let character_button = row.button("Character");
+                            self.decorate_focus(row, &character_button);
+                            if character_button.hovered() {
+                                self.infotext = "Builds a figure for this profile from the character atlas, shown next to it on the instances page.".to_string();
+                            }
+                            if character_button.clicked() {
+                                self.open_character_page(&profile);
+                            }
+                        });
+
+                        // Gyro aiming and input assists are applied per player by the
+                        // pad proxy at launch.
+                        let mut input = self
+                            .profile_inputs
+                            .entry(profile.clone())
+                            .or_insert_with(|| load_profile_input(&profile))
+                            .clone();
+                        let mut always_host = self
+                            .profile_meta
+                            .entry(profile.clone())
+                            .or_insert_with(|| Profile::load(&profile))
+                            .always_host;
+                        row_ui.horizontal(|row| {
+                            let gyro = &mut input.gyro;
+                            let gyro_check = row.checkbox(&mut gyro.enabled, "Gyro aiming");
+                            self.decorate_focus(row, &gyro_check);
+                            gyro_check.on_hover_text(
+                                "Turns controller motion into right-stick movement for games without native gyro support. Requires bubblewrap and a pad with a motion sensor.",
+                            );
+                            row.add_enabled_ui(gyro.enabled, |row| {
+                                let sensitivity = row.add(
+                                    egui::Slider::new(&mut gyro.sensitivity, 0.1..=5.0)
+                                        .text("Sensitivity"),
+                                );
+                                self.decorate_focus(row, &sensitivity);
+                                let invert_x = row.checkbox(&mut gyro.invert_x, "Invert X");
+                                self.decorate_focus(row, &invert_x);
+                                let invert_y = row.checkbox(&mut gyro.invert_y, "Invert Y");
+                                self.decorate_focus(row, &invert_y);
+                            });
+                            let remap_button = row.button("Remap Buttons");
+                            self.decorate_focus(row, &remap_button);
+                            if remap_button.hovered() {
+                                self.infotext = "Swap buttons, invert sticks or change the dead zone this player gets in-game.".to_string();
+                            }
+                            if remap_button.clicked() {
+                                self.open_remap_page(&profile);
+                            }
+                            let host_check = row.checkbox(&mut always_host, "Always player 1");
+                            self.decorate_focus(row, &host_check);
+                            if host_check.hovered() {
+                                self.infotext = "Auto Assign, restored controller slots and presets put this profile in slot 1, which gets the host's extra CPU core, whatever order the pads connected in. Only one profile can have it.".to_string();
+                            }
+                            if host_check.changed() {
+                                let host = always_host.then_some(profile.as_str());
+                                if let Err(err) = set_host_profile(host) {
+                                    msg("Error", &format!("Couldn't save profile: {err}"));
+                                }
+                                // Another profile may have lost the mark.
+                                self.profile_meta.clear();
+                            }
+                        });
+                        // Language and country Nemirtingas reports to EOS games.
+                        let meta = self
+                            .profile_meta
+                            .entry(profile.clone())
+                            .or_insert_with(|| Profile::load(&profile));
+                        let mut language = meta.language.clone().unwrap_or_default();
+                        let mut country = meta.country.clone().unwrap_or_default();
+                        row_ui.horizontal(|row| {
+                            row.label("EOS language");
+                            let language_edit = row.add(
+                                egui::TextEdit::singleline(&mut language)
+                                    .hint_text("en")
+                                    .desired_width(56.0),
+                            );
+                            self.decorate_focus(row, &language_edit);
+                            row.label("Country");
+                            let country_edit = row.add(
+                                egui::TextEdit::singleline(&mut country)
+                                    .hint_text("US")
+                                    .desired_width(40.0),
+                            );
+                            self.decorate_focus(row, &country_edit);
+                            if language_edit.hovered() || country_edit.hovered() {
+                                self.infotext = "Language (like de or pt-BR) and two-letter country (like DE) that Nemirtingas reports to Epic Online Services games. Some games pick regional content or matchmaking regions from these. Empty uses en and US.".to_string();
+                            }
+                        });
+                        let language =
+                            Some(language.trim().to_string()).filter(|l| !l.is_empty());
+                        let country = Some(country.trim().to_string()).filter(|c| !c.is_empty());
+                        if let Some(meta) = self.profile_meta.get_mut(&profile) {
+                            meta.language = language;
+                            meta.country = country;
+                        }
+                        // Written once the field is left rather than on every keystroke.
+                        let left = language_edit.lost_focus() || country_edit.lost_focus();
+                        if left && !self.profile_saves_pending.contains(&profile) {
+                            self.profile_saves_pending.push(profile.clone());
+                        }
+                        egui::CollapsingHeader::new("Input assists")
+                            .id_salt(format!("input_assists_{profile}"))
+                            .show(row_ui, |assist_ui| {
+                                let assists = &mut input.assists;
+                                let stick = assist_ui.add(
+                                    egui::Slider::new(&mut assists.stick_sensitivity, 0.25..=1.0)
+                                        .text("Stick sensitivity"),
+                                );
+                                self.decorate_focus(assist_ui, &stick);
+                                let rate = assist_ui.add(
+                                    egui::Slider::new(&mut assists.turbo_rate_hz, 2.0..=20.0)
+                                        .text("Turbo presses per second"),
+                                );
+                                self.decorate_focus(assist_ui, &rate);
+                                egui::Grid::new(format!("input_assist_buttons_{profile}"))
+                                    .num_columns(3)
+                                    .spacing([12.0, 4.0])
+                                    .show(assist_ui, |grid| {
+                                        for (key, label) in ASSIST_BUTTONS {
+                                            grid.label(label);
+                                            let mut turbo = assists.turbo_buttons.contains(&key.0);
+                                            let mut toggle =
+                                                assists.toggle_buttons.contains(&key.0);
+                                            let turbo_check = grid.checkbox(&mut turbo, "Turbo");
+                                            self.decorate_focus(grid, &turbo_check);
+                                            let toggle_check =
+                                                grid.checkbox(&mut toggle, "Toggle hold");
+                                            self.decorate_focus(grid, &toggle_check);
+                                            grid.end_row();
+
+                                            if !turbo_check.changed() && !toggle_check.changed() {
+                                                continue;
+                                            }
+                                            // A button is either turbo or toggle-hold, never both.
+                                            if turbo_check.changed() && turbo {
+                                                toggle = false;
+                                            }
+                                            if toggle_check.changed() && toggle {
+                                                turbo = false;
+                                            }
+                                            assists.turbo_buttons.retain(|code| *code != key.0);
+                                            assists.toggle_buttons.retain(|code| *code != key.0);
+                                            if turbo {
+                                                assists.turbo_buttons.push(key.0);
+                                            }
+                                            if toggle {
+                                                assists.toggle_buttons.push(key.0);
+                                            }
+                                        }
+                                    });
+                            });
+                        if let Some(settings) = self.profile_inputs.get_mut(&profile) {
+                            if *settings != input {
+                                *settings = input;
+                                if !self.input_saves_pending.contains(&profile) {
+                                    self.input_saves_pending.push(profile.clone());
+                                }
+                            }
+                        }
                     });
 
                     ui.add_space(8.0);
@@ -422,7 +791,7 @@ impl PartyApp {
 
     pub fn display_page_game(&mut self, ui: &mut Ui) {
         ui.horizontal(|ui| {
-            ui.image(cur_game!(self).icon());
+            ui.image(game_icon(cur_game!(self)));
             ui.heading(cur_game!(self).name());
         });
 
@@ -452,9 +821,52 @@ impl PartyApp {
                 ui.label(format!("Author: {}", h.author));
                 ui.add(egui::Separator::default().vertical());
                 ui.label(format!("Version: {}", h.version));
+                if h.read_only {
+                    ui.add(egui::Separator::default().vertical());
+                    ui.label("🔒 System handler");
+                }
             }
         });
 
+        // Playtime of every profile that played this game.
+        let game_id = launch_history_id(cur_game!(self));
+        if let Some(total) = self.play_stats().game_total(&game_id) {
+            ui.label(
+                RichText::new(format!(
+                    "Played {} · last played {}",
+                    format_playtime(total.seconds),
+                    launch_age(total.last_played)
+                ))
+                .weak(),
+            );
+        }
+
+        if let HandlerRef(_) = cur_game!(self) {
+            let edit_button = ui.button("✏ Edit Handler");
+            self.decorate_focus(ui, &edit_button);
+            if edit_button.clicked() {
+                self.open_handler_editor(true);
+            }
+            if edit_button.hovered() {
+                self.infotext = "Opens this handler in the built-in editor. System handlers are saved as a personal copy that overrides them.".to_string();
+            }
+            let steam_button = ui.button("➕ Add to Steam");
+            self.decorate_focus(ui, &steam_button);
+            if steam_button.clicked() {
+                self.add_selected_to_steam();
+            }
+            if steam_button.hovered() {
+                self.infotext = "Adds this game to Steam's library as a non-Steam game, with its artwork. Starting it from Steam launches the handler directly with one player per connected gamepad.".to_string();
+            }
+            self.display_data_migration(ui);
+            self.display_save_transfer(ui);
+            self.display_launch_options(ui);
+            self.display_resource_versions(ui);
+        }
+        self.display_quick_actions(ui);
+        self.display_cloud_export(ui);
+        self.display_launch_history(ui);
+
         if let HandlerRef(h) = cur_game!(self) {
             egui::ScrollArea::horizontal()
                 .max_width(f32::INFINITY)
@@ -519,12 +931,45 @@ impl PartyApp {
             }
         });
 
+        ui.horizontal(|row| {
+            let auto_btn = row.button("Auto Assign");
+            self.decorate_focus(row, &auto_btn);
+            if auto_btn.clicked() {
+                self.auto_assign_instances();
+            }
+            if auto_btn.hovered() {
+                self.infotext = "Creates one instance per connected gamepad, in the order picked next to this button. Replaces the current instances.".to_string();
+            }
+            let order_combo = egui::ComboBox::from_id_salt("instances_auto_assign_order")
+                .selected_text(self.options.auto_assign_order.label())
+                .show_ui(row, |combo| {
+                    for order in AutoAssignOrder::ALL {
+                        combo.selectable_value(
+                            &mut self.options.auto_assign_order,
+                            order,
+                            order.label(),
+                        );
+                    }
+                });
+            self.decorate_focus(row, &order_combo.response);
+            if order_combo.response.hovered() {
+                self.infotext = "Connection order makes the pad connected first player 1. Vendor groups pads by make and model, then by serial or port, so the same pads always land in the same slots.".to_string();
+            }
+        });
+        self.display_keyboard_split(ui);
+
         ui.separator();
 
         // Track the exact instance/device pairs flagged for removal so shared
         // controllers can be detached cleanly from a single slot.
         let mut devices_to_remove: Vec<(usize, usize)> = Vec::new();
         let instance_count = self.instances.len();
+        let displays = self.displays.get_or_insert_with(enumerate_displays).clone();
+        // Guest slots get a save handoff QR code while the web remote runs.
+        let launch_names = match self.web_remote {
+            Some(_) => self.resolved_profile_names(),
+            None => Vec::new(),
+        };
         for i in 0..instance_count {
             // Track per-instance responses so we can decorate them after the mutable
             // borrow of `self.instances[i]` ends, keeping the borrow checker happy
@@ -532,20 +977,59 @@ impl PartyApp {
             let mut combo_response: Option<egui::Response> = None;
             let mut invite_response: Option<egui::Response> = None;
             let mut cancel_response: Option<egui::Response> = None;
+            let mut monitor_response: Option<egui::Response> = None;
+            let mut handoff_response: Option<egui::Response> = None;
+            let mut motion_response: Option<egui::Response> = None;
+            let mut platform_response: Option<egui::Response> = None;
 
             let mut profselection = self.instances[i].profselection;
+            let mut monitor = self.instances[i].monitor.clone();
+            let mut motion_passthrough = self.instances[i].motion_passthrough;
+            let mut platform = self.instances[i].platform;
             let device_indices = self.instances[i].devices.clone();
+            let has_pad = device_indices.iter().any(|&dev| {
+                self.input_devices
+                    .get(dev)
+                    .is_some_and(|dev| dev.device_type() == DeviceType::Gamepad)
+            });
+
+            let remote_ready = self.remote_ready.get(i) == Some(&true);
 
             ui.horizontal(|ui| {
                 ui.label(format!("Instance {}", i + 1));
+                // Set from the player's phone through the web remote.
+                if remote_ready {
+                    ui.label(
+                        RichText::new("✔ Ready").color(egui::Color32::from_rgb(90, 200, 120)),
+                    );
+                }
 
                 if let HandlerRef(_) = cur_game!(self) {
-                    ui.label("👤");
                     // Clamp invalid selections when the profile list refreshes so the
                     // drop-down keeps pointing at a valid entry.
                     if profselection >= self.profiles.len() && !self.profiles.is_empty() {
                         profselection = 0;
                     }
+                    let named = self.profiles.get(profselection).filter(|name| *name != "Guest");
+                    match named.cloned() {
+                        Some(name) => {
+                            let meta = self
+                                .profile_meta
+                                .entry(name.clone())
+                                .or_insert_with(|| Profile::load(&name));
+                            profile_badge(ui, meta, i, 20.0);
+                            if let Some(look) = meta.character() {
+                                let (rect, _) = ui.allocate_exact_size(
+                                    egui::vec2(14.0, 24.0),
+                                    egui::Sense::hover(),
+                                );
+                                paint_character(ui, &look, rect);
+                            }
+                        }
+                        None => {
+                            ui.label("👤");
+                        }
+                    }
                     // Surface the currently selected profile name in the combo box even
                     // when no assignment has been made yet.
                     let selected_text = self
@@ -569,6 +1053,63 @@ impl PartyApp {
                             })
                             .response,
                     );
+
+                    if let Some(guest) = launch_names.get(i).filter(|name| is_guest_profile(name)) {
+                        let handoff_button = ui.button("📱 Save Handoff");
+                        if handoff_button.clicked() {
+                            self.guest_handoff = Some(guest.clone());
+                        }
+                        handoff_response = Some(handoff_button);
+                    }
+                }
+
+                // Pinning only matters with a second display to pick.
+                if displays.len() > 1 {
+                    ui.label("🖵");
+                    let selected_text = match &monitor {
+                        Some(name) => displays
+                            .iter()
+                            .find(|display| &display.name == name)
+                            .map(|display| display.label())
+                            .unwrap_or_else(|| format!("{name} (disconnected)")),
+                        None => "Primary".to_string(),
+                    };
+                    monitor_response = Some(
+                        egui::ComboBox::from_id_salt(format!("instance_monitor_{i}"))
+                            .selected_text(selected_text)
+                            .show_ui(ui, |combo_ui| {
+                                combo_ui.selectable_value(&mut monitor, None, "Primary");
+                                for display in &displays {
+                                    combo_ui.selectable_value(
+                                        &mut monitor,
+                                        Some(display.name.clone()),
+                                        display.label(),
+                                    );
+                                }
+                            })
+                            .response,
+                    );
+                }
+
+                if has_pad {
+                    motion_response =
+                        Some(ui.checkbox(&mut motion_passthrough, "🎯 Gyro & touchpad"));
+                }
+
+                // Only games shipping both builds can switch per instance.
+                let dual_build = matches!(cur_game!(self), HandlerRef(h) if !h.alt_exec.is_empty());
+                if dual_build {
+                    platform_response = Some(
+                        egui::ComboBox::from_id_salt(format!("instance_platform_{i}"))
+                            .selected_text(platform.label())
+                            .show_ui(ui, |combo_ui| {
+                                for option in InstancePlatform::ALL {
+                                    let label = option.label();
+                                    combo_ui.selectable_value(&mut platform, option, label);
+                                }
+                            })
+                            .response,
+                    );
                 }
 
                 if self.instance_add_dev == None {
@@ -596,8 +1137,38 @@ impl PartyApp {
             if let Some(response) = cancel_response.as_ref() {
                 self.decorate_focus(ui, response);
             }
+            if let Some(response) = handoff_response.as_ref() {
+                self.decorate_focus(ui, response);
+                if response.hovered() {
+                    self.infotext = "Shows a QR code for this guest. Their phone can download the guest's progress at the end of the night and restore it next time, whichever guest slot they get.".to_string();
+                }
+            }
+            if let Some(response) = monitor_response.as_ref() {
+                self.decorate_focus(ui, response);
+                if response.hovered() {
+                    self.infotext = "Display this instance plays on. Each display is split among the instances pinned to it; instances left on Primary share the primary display.".to_string();
+                }
+            }
+
+            if let Some(response) = platform_response.as_ref() {
+                self.decorate_focus(ui, response);
+                if response.hovered() {
+                    self.infotext = "Runs this instance on the game's native Linux build or its Windows build under Proton. Useful when mods only work on one of them; the other instances keep the handler's default.".to_string();
+                }
+            }
+
+            if let Some(response) = motion_response.as_ref() {
+                self.decorate_focus(ui, response);
+                if response.hovered() {
+                    self.infotext = "Forwards the motion sensor and touchpad of this player's PlayStation, Switch or Steam pads to their instance so games with gyro aiming or touch controls can use them. Other instances never see them. Turn it off for games that misbehave when they find a motion sensor.".to_string();
+                }
+            }
 
             self.instances[i].profselection = profselection;
+            self.instances[i].monitor = monitor;
+            self.instances[i].motion_passthrough = motion_passthrough;
+            self.instances[i].platform = platform;
+            self.display_instance_env(ui, i);
 
             for (device_slot, dev_index) in device_indices.iter().enumerate() {
                 if let Some(device) = self.input_devices.get(*dev_index) {
@@ -629,6 +1200,23 @@ impl PartyApp {
             self.remove_device_at(instance_index, device_index);
         }
 
+        if self.instances.len() > 1 {
+            ui.separator();
+            self.display_layout_editor(ui);
+        }
+
+        if let HandlerRef(h) = cur_game!(self) {
+            let uses_emulator = !h.path_goldberg.is_empty() || !h.path_nemirtingas.is_empty();
+            if !self.options.handler_recommendations(h).is_empty() {
+                ui.separator();
+                self.display_handler_recommendations(ui);
+            }
+            if uses_emulator && !self.instances.is_empty() {
+                ui.separator();
+                self.display_emulator_status(ui);
+            }
+        }
+
         if self.instances.len() > 0 {
             ui.separator();
             ui.horizontal(|ui| {
@@ -645,184 +1233,477 @@ impl PartyApp {
                 if start_button.clicked() {
                     self.prepare_game_launch();
                 }
+                if self.web_remote.is_some()
+                    && !self.remote_ready.is_empty()
+                    && self.remote_ready.iter().all(|&ready| ready)
+                {
+                    ui.label("Everyone is ready");
+                }
+                let safe_check = ui.checkbox(&mut self.options.safe_mode, "Safe mode");
+                self.decorate_focus(ui, &safe_check);
+                if safe_check.hovered() {
+                    self.infotext = SAFE_MODE_INFO.to_string();
+                }
+                let preset_btn = ui.button("Save as Preset");
+                self.decorate_focus(ui, &preset_btn);
+                if preset_btn.clicked() {
+                    self.save_current_preset();
+                }
+                if preset_btn.hovered() {
+                    self.infotext = "Saves this game with its players, profiles, devices, displays and resolutions as a preset that can be relaunched with one click from the home page.".to_string();
+                }
             });
         }
 
+        if self.coordinator.is_some() {
+            ui.separator();
+            self.display_coordinator_panel(ui);
+        }
+
         // Surface the connected device overview inline now that the sidebar is gone.
         ui.add_space(20.0);
         let devices_ctx = ui.ctx().clone();
         self.display_panel_right(ui, &devices_ctx);
     }
 
-    pub fn display_settings_general(&mut self, ui: &mut Ui) {
-        // Normalize spacing so each control lines up cleanly in the two-column layout.
-        ui.spacing_mut().item_spacing.y = 12.0;
-        let force_sdl2_check = ui.checkbox(&mut self.options.force_sdl, "Force Steam Runtime SDL2");
-        self.decorate_focus(ui, &force_sdl2_check);
+    /// Settings the selected handler recommends. Ticked ones apply to this
+    /// game's launches only; unticking one is remembered per handler.
+    fn display_handler_recommendations(&mut self, ui: &mut Ui) {
+        let HandlerRef(h) = cur_game!(self) else {
+            return;
+        };
+        let handler = h.clone();
+        let current = serde_json::to_value(&self.options).unwrap_or_default();
+
+        ui.label(format!("Recommended by {}", handler.display()));
+        for (key, value) in self.options.handler_recommendations(&handler) {
+            let mut accepted = !self.options.recommendation_declined(&handler, &key);
+            let check = ui.checkbox(&mut accepted, format!("{key}: {value}"));
+            self.decorate_focus(ui, &check);
+            if check.changed() {
+                let declined = self
+                    .options
+                    .declined_recommendations
+                    .entry(handler.uid.clone())
+                    .or_default();
+                declined.retain(|k| *k != key);
+                if !accepted {
+                    declined.push(key.clone());
+                }
+                if declined.is_empty() {
+                    self.options.declined_recommendations.remove(&handler.uid);
+                }
+                let _ = save_cfg(&self.options);
+            }
+            if check.hovered() {
+                self.infotext = format!(
+                    "The handler suggests {key} = {value} for this game (your setting: {}). Untick to launch with your own setting instead.",
+                    current[key.as_str()]
+                );
+            }
+        }
+    }
 
-        let enable_kwin_script_check = ui.checkbox(
-            &mut self.options.enable_kwin_script,
-            "Automatically resize/reposition instances",
+    /// Goldberg and Nemirtingas identities of the picked profiles and the ports
+    /// the launch will use, with anything that would keep instances from
+    /// seeing each other's lobbies.
+    fn display_emulator_status(&mut self, ui: &mut Ui) {
+        let HandlerRef(h) = cur_game!(self) else {
+            return;
+        };
+        let handler = h.clone();
+        let profiles = self.resolved_profile_names();
+        let checked = matches!(
+            &self.emulator_status,
+            Some((uid, names, _)) if *uid == handler.uid && *names == profiles
         );
-        self.decorate_focus(ui, &enable_kwin_script_check);
+        if !checked {
+            let status = emulator_status(&handler, &profiles);
+            self.emulator_status = Some((handler.uid.clone(), profiles, status));
+        }
+        let Some((_, _, status)) = self.emulator_status.clone() else {
+            return;
+        };
 
-        let vertical_two_player_check = ui.checkbox(
-            &mut self.options.vertical_two_player,
-            "Vertical split for 2 players",
-        );
-        self.decorate_focus(ui, &vertical_two_player_check);
+        let title = match status.worst() {
+            Some(EmuSeverity::Conflict) => RichText::new("Emulator Status: conflicts")
+                .color(ui.visuals().error_fg_color),
+            Some(EmuSeverity::Warning) => {
+                RichText::new("Emulator Status: warnings").color(ui.visuals().warn_fg_color)
+            }
+            _ => RichText::new("Emulator Status: OK"),
+        };
+        let uses_goldberg = status.goldberg_port.is_some();
+        let uses_nemirtingas = status.nemirtingas_port.is_some();
+        let mut recheck = false;
+        egui::CollapsingHeader::new(title)
+            .id_salt("instances_emulator_status")
+            .show(ui, |ui| {
+                egui::Grid::new("emulator_status_grid")
+                    .striped(true)
+                    .show(ui, |grid| {
+                        let mut headers = vec!["Player"];
+                        if uses_goldberg {
+                            headers.extend(["Account", "SteamID"]);
+                        }
+                        if uses_nemirtingas {
+                            headers.extend(["EpicId", "ProductUserId"]);
+                        }
+                        for header in headers {
+                            grid.label(RichText::new(header).weak());
+                        }
+                        grid.end_row();
+
+                        for profile in &status.profiles {
+                            grid.label(profile.profile.as_str());
+                            if uses_goldberg {
+                                let goldberg = profile.goldberg.clone().unwrap_or_default();
+                                grid.label(match goldberg.account_name {
+                                    Some(name) => RichText::new(name),
+                                    None => RichText::new("at launch").weak(),
+                                });
+                                grid.label(emulator_id_text(goldberg.steam_id.as_ref()));
+                            }
+                            if uses_nemirtingas {
+                                let nemirtingas = profile.nemirtingas.clone().unwrap_or_default();
+                                grid.label(emulator_id_text(nemirtingas.epic_id.as_ref()));
+                                grid.label(emulator_id_text(nemirtingas.product_user_id.as_ref()));
+                            }
+                            grid.end_row();
+                        }
+                    });
+
+                if let Some(port) = status.goldberg_port {
+                    ui.label(format!("Goldberg listen port: {port}"));
+                }
+                if let Some(port) = status.nemirtingas_port {
+                    ui.label(format!("Nemirtingas LAN port: {port}"));
+                }
+                if status.issues.is_empty() {
+                    ui.label("No conflicts between the picked profiles.");
+                }
+                for issue in &status.issues {
+                    let text = RichText::new(issue.message.as_str());
+                    ui.label(match issue.severity {
+                        EmuSeverity::Conflict => text.color(ui.visuals().error_fg_color),
+                        EmuSeverity::Warning => text.color(ui.visuals().warn_fg_color),
+                        EmuSeverity::Info => text.weak(),
+                    });
+                }
 
-        if force_sdl2_check.hovered() {
-            self.infotext = "Forces games to use the version of SDL2 included in the Steam Runtime. Only works on native Linux games, may fix problematic game controller support (incorrect mappings) in some games, may break others. If unsure, leave this unchecked.".to_string();
+                let recheck_btn = ui.button("Check again");
+                self.decorate_focus(ui, &recheck_btn);
+                if recheck_btn.clicked() {
+                    recheck = true;
+                }
+                if recheck_btn.hovered() {
+                    self.infotext = "Rereads each profile's configs.user.ini and NemirtingasEpicEmu.json and checks whether another program holds the LAN port. Conflicts also ask for confirmation when you press Start.".to_string();
+                }
+            });
+        if recheck {
+            self.emulator_status = None;
         }
+    }
 
-        if enable_kwin_script_check.hovered() {
-            self.infotext = "Resizes/repositions instances to fit the screen using a KWin script. If unsure, leave this checked. If using a desktop environment or window manager other than KDE Plasma, uncheck this; note that you will need to manually resize and reposition the windows.".to_string();
+    /// Lists other Split Happens machines on the LAN and offers a synchronized
+    /// launch for the game currently being set up.
+    pub fn display_coordinator_panel(&mut self, ui: &mut Ui) {
+        let Some(coordinator) = &self.coordinator else {
+            return;
+        };
+        let peers = coordinator.peers();
+        let countdown = coordinator.countdown();
+        let game_uid = cur_game!(self).persistent_id();
+
+        ui.label(RichText::new("LAN Coordinator").strong());
+        if peers.is_empty() {
+            ui.label("No other Split Happens machines found on this network.");
+        }
+        for peer in &peers {
+            let status = if peer.game.is_empty() {
+                "idle".to_string()
+            } else {
+                format!("hosting {} ({} players)", peer.game, peer.players)
+            };
+            let same_game = if !peer.game_uid.is_empty() && peer.game_uid == game_uid {
+                " ✔ same game"
+            } else {
+                ""
+            };
+            ui.label(format!("🖧 {}: {status}{same_game}", peer.host));
+        }
+
+        if let Some(countdown) = countdown {
+            let remaining = countdown
+                .launch_at
+                .saturating_duration_since(std::time::Instant::now());
+            ui.label(format!(
+                "{} started a synchronized launch: {}s remaining",
+                countdown.host,
+                remaining.as_secs() + 1
+            ));
+            if !countdown.accepted {
+                let join_btn = ui.button("Join launch");
+                self.decorate_focus(ui, &join_btn);
+                if join_btn.clicked() {
+                    if let Some(coordinator) = &self.coordinator {
+                        coordinator.accept_countdown();
+                    }
+                }
+                if join_btn.hovered() {
+                    self.infotext = "Launches this machine's game together with the others when the countdown ends. Countdowns from other machines never launch anything here unless you join them.".to_string();
+                }
+            }
+            let cancel_btn = ui.button("Cancel countdown");
+            self.decorate_focus(ui, &cancel_btn);
+            if cancel_btn.clicked() {
+                if let Some(coordinator) = &self.coordinator {
+                    coordinator.clear_countdown();
+                }
+            }
+        } else if !self.instances.is_empty() {
+            let sync_btn = ui.button("Launch together in 10s");
+            self.decorate_focus(ui, &sync_btn);
+            if sync_btn.clicked() {
+                if let Some(coordinator) = &self.coordinator {
+                    coordinator.start_countdown(&game_uid, std::time::Duration::from_secs(10));
+                }
+            }
+            if sync_btn.hovered() {
+                self.infotext = "Starts a 10 second countdown on every machine that has this game open on its Instances page, so LAN sessions boot at the same time.".to_string();
+            }
         }
+    }
 
-        if vertical_two_player_check.hovered() {
-            self.infotext =
-                "Splits two-player games vertically (side by side) instead of horizontally."
-                    .to_string();
+    pub fn display_settings_general(&mut self, ui: &mut Ui) {
+        // Normalize spacing so each control lines up cleanly in the two-column layout.
+        ui.spacing_mut().item_spacing.y = 12.0;
+        layout_settings(self, ui);
+        copilot_setting(self, ui);
+
+        ui.horizontal(|row| {
+            let split_label = row.label("Split keyboard keys");
+            let split_editbox = row.add(
+                egui::TextEdit::singleline(&mut self.options.keyboard_split_keys)
+                    .hint_text(DEFAULT_SPLIT_KEYS),
+            );
+            self.decorate_focus(row, &split_editbox);
+            if split_label.hovered() || split_editbox.hovered() {
+                self.infotext = "Keys that go to the first player when a keyboard is split on the instances page; every other key goes to the second. Use evdev key names separated by spaces, with FIRST-LAST for a run of keys in kernel order, e.g. KEY_TAB-KEY_T for Tab and Q to T.".to_string();
+            }
+        });
+
+        remember_assignments_setting(self, ui);
+
+        let ready_check = ui.checkbox(&mut self.options.ready_check, "Ready check before launch");
+        self.decorate_focus(ui, &ready_check);
+        if ready_check.hovered() {
+            self.infotext = "After pressing Start, every player presses a button on their controller within 15 seconds before the game launches, so a pad that fell asleep during setup is caught early. Synchronized LAN coordinator launches skip it.".to_string();
         }
 
-        // Group the controller filter radios so they wrap neatly on narrow windows.
-        ui.group(|group| {
-            group.spacing_mut().item_spacing.y = 6.0;
-            let filter_label = group.label("Controller filter");
-            group.horizontal_wrapped(|radios| {
-                let r1 = radios.radio_value(
-                    &mut self.options.pad_filter_type,
-                    PadFilterType::All,
-                    "All controllers",
-                );
-                self.decorate_focus(radios, &r1);
-                let r2 = radios.radio_value(
-                    &mut self.options.pad_filter_type,
-                    PadFilterType::NoSteamInput,
-                    "No Steam Input",
-                );
-                self.decorate_focus(radios, &r2);
-                let r3 = radios.radio_value(
-                    &mut self.options.pad_filter_type,
-                    PadFilterType::OnlySteamInput,
-                    "Only Steam Input",
-                );
-                self.decorate_focus(radios, &r3);
+        controller_filter_setting(self, ui);
+        proton_settings(self, ui);
 
-                if filter_label.hovered() || r1.hovered() || r2.hovered() || r3.hovered() {
-                    self.infotext = "Select which controllers to filter out. If unsure, set this to \"No Steam Input\". If you use Steam Input to remap controllers, you may want to select \"Only Steam Input\", but be warned that this option is experimental and is known to break certain Proton games.".to_string();
-                }
+        let dlc_check =
+            ui.checkbox(&mut self.options.goldberg_dlc_list, "Generate Goldberg DLC lists");
+        self.decorate_focus(ui, &dlc_check);
+        if dlc_check.hovered() {
+            self.infotext = "Before each launch, looks up the DLCs of the handler's Steam app on the Steam store and lists them in Goldberg's DLC.txt and configs.app.ini, so owned DLC unlocks without the handler listing it. Lists are cached for a week and only fetched for handlers with a steam.appid. Handlers that ship their own DLC list keep it.".to_string();
+        }
 
-                if r1.clicked() || r2.clicked() || r3.clicked() {
-                    self.input_devices = scan_input_devices(&self.options.pad_filter_type);
+        // Only offered when res/goldberg ships builds besides the default one.
+        let goldberg_versions = bundled_goldberg_versions();
+        if !goldberg_versions.is_empty() {
+            let mut picked = self.options.goldberg_version.clone();
+            ui.horizontal(|row| {
+                let gb_label = row.label("Goldberg build");
+                let selected = match picked.as_str() {
+                    "" => "Handler's choice",
+                    version => version,
+                };
+                let gb_combo = egui::ComboBox::from_id_salt("settings_goldberg_version")
+                    .selected_text(selected)
+                    .show_ui(row, |combo| {
+                        combo.selectable_value(&mut picked, String::new(), "Handler's choice");
+                        for version in &goldberg_versions {
+                            combo.selectable_value(&mut picked, version.clone(), version);
+                        }
+                    });
+                self.decorate_focus(row, &gb_combo.response);
+                if gb_label.hovered() || gb_combo.response.hovered() {
+                    self.infotext = "Goldberg build every game uses, from the builds bundled in res/goldberg. Handler's choice uses the build a handler asks for with steam.gb_version, or the default one. A build picked under Emulator Builds on a game's page still wins.".to_string();
                 }
             });
+            if picked != self.options.goldberg_version {
+                self.options.goldberg_version = picked;
+                // Goldberg is copied when a symlink folder is built, so they're all rebuilt.
+                let _ = std::fs::remove_dir_all(PATH_APP.join("gamesyms"));
+            }
+        }
+
+        // Home grid tile size, for fitting large libraries on one screen.
+        ui.horizontal(|row| {
+            let density_label = row.label("Home tile size");
+            let density_combo = egui::ComboBox::from_id_salt("settings_tile_density")
+                .selected_text(self.options.tile_density.label())
+                .show_ui(row, |combo| {
+                    for density in TileDensity::ALL {
+                        combo.selectable_value(
+                            &mut self.options.tile_density,
+                            density,
+                            density.label(),
+                        );
+                    }
+                });
+            self.decorate_focus(row, &density_combo.response);
+            if density_label.hovered() || density_combo.response.hovered() {
+                self.infotext = "Size of the game tiles on the home page. Compact fits many more games on screen at once, which helps with large libraries on a TV; Large makes the artwork easier to see from the couch.".to_string();
+            }
         });
 
-        // Present the Proton selector as a combo box backed by the discovered
-        // installations, followed by a manual override text field.
-        // Wrap the Proton selector and manual override into a tidy stack for clarity.
-        ui.group(|group| {
-            group.spacing_mut().item_spacing.y = 8.0;
-            let proton_ver_label = group.label("Proton version");
-            let combo_response = egui::ComboBox::from_id_salt("settings_proton_combo")
-                .selected_text(self.proton_dropdown_label())
-                .width(220.0)
-                .show_ui(group, |combo_ui| {
-                    combo_ui.selectable_value(
-                        &mut self.options.proton_version,
-                        String::new(),
-                        "Auto (GE-Proton)",
-                    );
+        // Menu feedback for controller navigation, as sound, rumble or both.
+        ui.horizontal(|row| {
+            let feedback_label = row.label("Navigation feedback");
+            let sound_check = row.checkbox(&mut self.options.nav_sounds, "Sound");
+            self.decorate_focus(row, &sound_check);
+            let rumble_check = row.checkbox(&mut self.options.nav_haptics, "Rumble");
+            self.decorate_focus(row, &rumble_check);
+            if feedback_label.hovered() || sound_check.hovered() || rumble_check.hovered() {
+                self.infotext = "Plays a soft click when a controller moves focus or confirms in the menus, and a short rumble pulse on the controller that pressed the button. Sounds play through pw-play or paplay; rumble needs a controller with force feedback and write access to its event node.".to_string();
+            }
+        });
 
-                    if self.proton_versions.is_empty() {
-                        combo_ui.label("No Proton builds detected");
-                    } else {
-                        for install in &self.proton_versions {
-                            combo_ui.selectable_value(
-                                &mut self.options.proton_version,
-                                install.id.clone(),
-                                install.display_label(),
-                            );
-                        }
-                    }
+        rear_buttons_setting(self, ui);
 
-                    combo_ui.separator();
-                    combo_ui.label("Select a build above or keep using the custom path below.");
-                })
-                .response;
-            self.decorate_focus(group, &combo_response);
+        multiseat_setting(self, ui);
 
-            let refresh_btn = group.small_button("Refresh");
-            self.decorate_focus(group, &refresh_btn);
-            if refresh_btn.clicked() {
-                self.refresh_proton_versions();
+        let coordinator_check = ui.checkbox(
+            &mut self.options.coordinator_enabled,
+            "LAN coordinator mode",
+        );
+        self.decorate_focus(ui, &coordinator_check);
+        if coordinator_check.changed() {
+            // Start or stop the background announcer right away.
+            self.coordinator = if self.options.coordinator_enabled {
+                start_coordinator()
+            } else {
+                None
+            };
+        }
+        if coordinator_check.hovered() {
+            self.infotext = "Discovers other machines running Split Happens on the same network, shows what they are hosting and lets everyone start a LAN game with a shared countdown. Uses UDP broadcast on port 47360.".to_string();
+        }
+
+        // Phones on the LAN pick profiles and ready up while the instances page is open.
+        ui.horizontal(|row| {
+            let remote_check = row.checkbox(&mut self.options.web_remote_enabled, "Web remote");
+            self.decorate_focus(row, &remote_check);
+            let port_drag = row.add(
+                egui::DragValue::new(&mut self.options.web_remote_port)
+                    .range(1024..=65535)
+                    .update_while_editing(false),
+            );
+            self.decorate_focus(row, &port_drag);
+            // Restart once the port is settled rather than on every drag step, and
+            // only when it changed, so phones that are connected stay connected.
+            if remote_check.changed() || port_drag.drag_stopped() || port_drag.lost_focus() {
+                let wanted = Some(self.options.web_remote_port)
+                    .filter(|_| self.options.web_remote_enabled);
+                if self.web_remote.as_ref().map(|remote| remote.port()) != wanted {
+                    // The old server releases its port before the new one binds it.
+                    self.web_remote = None;
+                    self.web_remote = wanted.and_then(start_web_remote);
+                }
+            }
+            if let Some(remote) = &self.web_remote {
+                row.label(remote.url());
             }
+            if remote_check.hovered() || port_drag.hovered() {
+                self.infotext = "Serves a page at the address shown where players on the same network pick their profile and ready up from their phones while a game's instances page is open. Anyone on the network can open it. Uses TCP port 47361 unless another port is set here.".to_string();
+            }
+        });
 
-            if proton_ver_label.hovered() || combo_response.hovered() || refresh_btn.hovered() {
-                self.infotext = "Choose an installed Proton build or refresh the list after installing a new compatibility tool. Keep the field below blank for the default GE-Proton.".to_string();
+        let cec_check = ui.checkbox(&mut self.options.cec_remote_enabled, "TV remote (HDMI-CEC)");
+        self.decorate_focus(ui, &cec_check);
+        if cec_check.changed() {
+            self.cec_remote = None;
+            if self.options.cec_remote_enabled {
+                self.cec_remote = start_cec_remote();
             }
+        }
+        if cec_check.hovered() {
+            self.infotext = "Navigates the launcher with the TV's remote when the Deck is docked to a TV that supports HDMI-CEC: arrows move, OK selects, Back returns home, Play starts the open game and the blue, red, green and yellow keys launch the first four presets. Needs cec-client from libcec and a CEC-capable adapter or dock.".to_string();
+        }
+
+        offline_mode_setting(self, ui);
+        author_mode_setting(self, ui);
 
-            let proton_ver_editbox = group.add(
-                egui::TextEdit::singleline(&mut self.options.proton_version)
-                    .hint_text("GE-Proton or /path/to/proton"),
+        self.display_settings_output_rules(ui);
+
+        // The handler browser pulls its index from this repository.
+        ui.horizontal(|row| {
+            let repo_label = row.label("Handler repository");
+            let repo_editbox = row.add(
+                egui::TextEdit::singleline(&mut self.options.handler_repository_url)
+                    .hint_text("https://example.com/handlers/index.json"),
             );
-            self.decorate_focus(group, &proton_ver_editbox);
-            if proton_ver_editbox.hovered() {
-                self.infotext = "Enter a custom Proton identifier or absolute path. Leave empty to auto-select GE-Proton.".to_string();
+            self.decorate_focus(row, &repo_editbox);
+            if repo_label.hovered() || repo_editbox.hovered() {
+                self.infotext = "URL of a JSON index listing downloadable handlers. Browse Handlers shows its entries with artwork and version info and installs the selected .pdh archive.".to_string();
             }
         });
 
-        let proton_separate_pfxs_check = ui.checkbox(
-            &mut self.options.proton_separate_pfxs,
-            "Run instances in separate Proton prefixes",
-        );
-        self.decorate_focus(ui, &proton_separate_pfxs_check);
-        if proton_separate_pfxs_check.hovered() {
-            self.infotext = "Runs each instance in its own Proton prefix. If unsure, leave this unchecked. This option will take up more space on the disk, but may also help with certain Proton-related issues such as only one instance of a game starting.".to_string();
-        }
+        // SteamGridDB supplies grids, heroes and logos for the home tiles.
+        ui.horizontal(|row| {
+            let key_label = row.label("SteamGridDB API key");
+            let key_editbox = row.add(
+                egui::TextEdit::singleline(&mut self.options.steamgriddb_api_key).password(true),
+            );
+            self.decorate_focus(row, &key_editbox);
+            let fetch_btn = row.add_enabled(
+                !self.artwork.is_fetching(),
+                egui::Button::new("Fetch Artwork"),
+            );
+            self.decorate_focus(row, &fetch_btn);
+            // `changed` and `lost_focus` never come in the same frame.
+            self.artwork.key_edited |= key_editbox.changed();
+            let key_done = key_editbox.lost_focus() && self.artwork.key_edited;
+            if key_done {
+                self.artwork.key_edited = false;
+                if let Err(err) = save_cfg(&self.options) {
+                    msg("Error", &format!("Couldn't save settings: {err}"));
+                }
+            }
+            if fetch_btn.clicked() || key_done {
+                self.refetch_artwork();
+            }
+            if self.artwork.is_fetching() {
+                row.spinner();
+            }
+            if key_label.hovered() || key_editbox.hovered() || fetch_btn.hovered() {
+                self.infotext = "Downloads grids, heroes and logos from SteamGridDB for handlers and executables and shows them on the home tiles. Get a free key under Preferences → API on steamgriddb.com. Handlers are matched by their Steam app id, executables by file name. Artwork is cached in the artwork folder of the Split Happens data directory.".to_string();
+            }
+        });
+
+        webhook_setting(self, ui);
 
         ui.separator();
 
         // Keep destructive maintenance actions in a single row to avoid tall gaps.
         ui.with_layout(egui::Layout::left_to_right(egui::Align::Center), |actions| {
             actions.spacing_mut().item_spacing.x = 10.0;
-            let erase_prefix_btn = actions.button("Erase Proton Prefix");
-            self.decorate_focus(actions, &erase_prefix_btn);
-            if erase_prefix_btn.clicked() {
-                if yesno(
-                    "Erase Prefix?",
-                    "This will erase the Wine prefix used by Split Happens. This shouldn't erase profile/game-specific data, but exercise caution. Are you sure?",
-                ) && PATH_APP.join("gamesyms").exists()
-                {
-                    if let Err(err) = std::fs::remove_dir_all(PATH_APP.join("pfx")) {
-                        msg("Error", &format!("Couldn't erase pfx data: {}", err));
-                    } else if let Err(err) = std::fs::create_dir_all(PATH_APP.join("pfx")) {
-                        msg("Error", &format!("Couldn't re-create pfx directory: {}", err));
-                    } else {
-                        msg("Data Erased", "Proton prefix data successfully erased.");
-                    }
-                }
-            }
+            erase_prefix_button(self, actions);
 
-            let erase_symlink_btn = actions.button("Erase Symlink Data");
-            self.decorate_focus(actions, &erase_symlink_btn);
-            if erase_symlink_btn.clicked() {
-                if yesno(
-                    "Erase Symlink Data?",
-                    "This will erase all game symlink data. This shouldn't erase profile/game-specific data, but exercise caution. Are you sure?",
-                ) && PATH_APP.join("gamesyms").exists()
-                {
-                    if let Err(err) = std::fs::remove_dir_all(PATH_APP.join("gamesyms")) {
-                        msg("Error", &format!("Couldn't erase symlink data: {}", err));
-                    } else if let Err(err) = std::fs::create_dir_all(PATH_APP.join("gamesyms")) {
-                        msg("Error", &format!("Couldn't re-create symlink directory: {}", err));
-                    } else {
-                        msg("Data Erased", "Game symlink data successfully erased.");
-                    }
-                }
+            let storage_btn = actions.button("Storage");
+            self.decorate_focus(actions, &storage_btn);
+            if storage_btn.clicked() {
+                self.open_storage_page();
             }
+            if storage_btn.hovered() {
+                self.infotext = "Lists the Proton prefixes and game working trees with the profile each belongs to and its size.".to_string();
+            }
+
+            erase_symlinks_button(self, actions);
         });
 
         // Surface shortcuts to important data locations with compact spacing.
@@ -830,90 +1711,317 @@ impl PartyApp {
             egui::Layout::left_to_right(egui::Align::Center),
             |actions| {
                 actions.spacing_mut().item_spacing.x = 10.0;
-                let open_data_btn = actions.button("Open Split Happens Data Folder");
-                self.decorate_focus(actions, &open_data_btn);
-                if open_data_btn.clicked() {
-                    if let Err(_) = std::process::Command::new("sh")
-                        .arg("-c")
-                        .arg(format!("xdg-open {}/", PATH_APP.display()))
-                        .status()
-                    {
-                        msg("Error", "Couldn't open Split Happens Data Folder!");
-                    }
+                data_folder_buttons(self, actions);
+                let wizard_btn = actions.button("Setup Wizard");
+                self.decorate_focus(actions, &wizard_btn);
+                if wizard_btn.clicked() {
+                    self.open_setup_wizard();
                 }
-                let edit_paths_btn = actions.button("Edit game paths");
-                self.decorate_focus(actions, &edit_paths_btn);
-                if edit_paths_btn.clicked() {
-                    if let Err(_) = std::process::Command::new("sh")
-                        .arg("-c")
-                        .arg(format!("xdg-open {}/paths.json", PATH_APP.display(),))
-                        .status()
-                    {
-                        msg("Error", "Couldn't open paths.json!");
-                    }
+                if wizard_btn.hovered() {
+                    self.infotext = "Checks gamescope, bubblewrap, the Steam runtimes, Proton and input device access again, and helps add a first game.".to_string();
                 }
             },
         );
     }
 
-    pub fn display_settings_performance(&mut self, ui: &mut Ui) {
-        // Lay out the Steam Deck performance assists with ample spacing for readability.
-        ui.spacing_mut().item_spacing.y = 12.0;
+    pub fn display_settings_network(&mut self, ui: &mut Ui) {
+        ui.spacing_mut().item_spacing.y = 8.0;
 
-        let realtime_toggle = ui.checkbox(
-            &mut self.options.performance_gamescope_rt,
-            "Real-time scheduling for Gamescope",
-        );
-        self.decorate_focus(ui, &realtime_toggle);
-        if realtime_toggle.hovered() {
-            self.infotext = "Requests gamescope's real-time compositor mode to reduce frame pacing spikes when two sessions share the GPU.".to_string();
+        let refresh_btn = ui.button("Sample network traffic");
+        self.decorate_focus(ui, &refresh_btn);
+        if refresh_btn.clicked() {
+            self.net_previous = self.net_snapshot.take();
+            self.net_snapshot = Some(capture_network_snapshot());
+        }
+        if refresh_btn.hovered() {
+            self.infotext = "Polls the kernel's UDP counters and the sockets held by running instances. Sample once, let the games search for lobbies for a few seconds, then sample again. Each instance's sockets show what arrived for it and what it dropped; the totals cover every program on the machine.".to_string();
         }
 
-        let fps_limit_toggle = ui.checkbox(
-            &mut self.options.performance_limit_40fps,
-            "Limit Gamescope output to 40 FPS",
-        );
-        self.decorate_focus(ui, &fps_limit_toggle);
-        if fps_limit_toggle.hovered() {
-            self.infotext = "Caps each window to 40 frames per second so both players stay within the Deck's thermal and power envelope.".to_string();
+        let Some(snapshot) = &self.net_snapshot else {
+            ui.label("No sample yet. Launch a game, then sample while players search for a lobby.");
+            return;
+        };
+
+        // Counters only become meaningful once there is a previous sample.
+        if let Some(previous) = &self.net_previous {
+            let delta = snapshot.counters.delta(&previous.counters);
+            ui.label(format!(
+                "Whole machine since last sample: {} UDP datagrams sent, {} received, {} receive errors, {} buffer overflows",
+                delta.out_datagrams, delta.in_datagrams, delta.in_errors, delta.rcvbuf_errors
+            ));
+            // Only a firewall that is actually running can explain missing replies.
+            if let Some(firewall) = snapshot.firewall {
+                if delta.out_datagrams > 0 && delta.in_datagrams == 0 {
+                    let warning = format!(
+                        "Nothing arrived while {} is active; check that the game's ports are open.",
+                        firewall.name()
+                    );
+                    ui.colored_label(ui.visuals().warn_fg_color, warning);
+                }
+            }
+        } else {
+            ui.label("Sample again in a few seconds to compare traffic.");
+        }
+
+        if snapshot.sockets.is_empty() {
+            ui.label("No running instance has an open UDP socket.");
+            return;
+        }
+        for socket in &snapshot.sockets {
+            let new_drops = self.net_previous.as_ref().and_then(|prev| socket.drops_since(prev));
+            let dropped = match new_drops {
+                Some(new_drops) => format!("{} dropped (+{new_drops})", socket.drops),
+                None => format!("{} dropped", socket.drops),
+            };
+            ui.label(format!(
+                "{}: {} (pid {}) UDP{} port {}, {} bytes waiting, {dropped}",
+                socket.instance,
+                socket.process,
+                socket.pid,
+                if socket.ipv6 { "6" } else { "" },
+                socket.local_port,
+                socket.queued,
+            ));
+        }
+    }
+
+    /// Results of `run_system_checks`, run when the section first shows.
+    pub fn display_settings_system_check(&mut self, ui: &mut Ui) {
+        ui.spacing_mut().item_spacing.y = 8.0;
+        if self.system_checks_run.as_ref().is_some_and(|run| run.is_finished()) {
+            if let Some(run) = self.system_checks_run.take() {
+                self.system_checks = run.join().ok();
+                self.refresh_proton_versions();
+            }
+        }
+        if self.system_checks.is_none() {
+            self.start_system_checks(None);
         }
+        let Some(checks) = self.system_checks.clone() else {
+            ui.horizontal(|row| {
+                row.spinner();
+                row.label("Checking the system…");
+            });
+            // Nothing else repaints the page once the checks are done.
+            ui.ctx().request_repaint_after(std::time::Duration::from_millis(250));
+            return;
+        };
+        let fix = self.display_system_checks(ui, "settings_system_check", &checks);
 
-        let proton_fsr_toggle = ui.checkbox(
-            &mut self.options.performance_enable_proton_fsr,
-            "Enable Proton FSR upscaling",
+        let recheck_btn = ui.add_enabled(
+            self.system_checks_run.is_none(),
+            egui::Button::new("Check again"),
         );
-        self.decorate_focus(ui, &proton_fsr_toggle);
-        if proton_fsr_toggle.hovered() {
-            self.infotext = "Turns on Proton's fullscreen FSR so Windows titles can render at lower resolutions while gamescope upscales the result.".to_string();
+        self.decorate_focus(ui, &recheck_btn);
+        if recheck_btn.hovered() {
+            self.infotext = "Looks for gamescope, gamescope-kbm, bubblewrap, kwin_wayland, umu-run, the Proton build from your settings, the Steam runtimes and input device access again. The same report is printed by split-happens --doctor.".to_string();
+        }
+
+        if recheck_btn.clicked() || fix.is_some() {
+            self.start_system_checks(fix);
         }
     }
 
+    /// Runs the system checks on a background thread, applying `fix` first.
+    /// The current results stay shown until the new ones are in.
+    fn start_system_checks(&mut self, fix: Option<SystemFix>) {
+        if self.system_checks_run.is_some() {
+            return;
+        }
+        let options = self.options.clone();
+        self.system_checks_run = Some(std::thread::spawn(move || {
+            if let Some(fix) = fix {
+                if let Err(err) = fix.apply() {
+                    msg("Error", &format!("Couldn't apply the fix: {err}"));
+                }
+            }
+            run_system_checks(&options)
+        }));
+    }
+
+    pub fn display_settings_performance(&mut self, ui: &mut Ui) {
+        // Lay out the Steam Deck performance assists with ample spacing for readability.
+        ui.spacing_mut().item_spacing.y = 12.0;
+
+        performance_settings(self, ui);
+
+        self.display_settings_timings(ui);
+    }
+
+    /// Regex rules that hide or highlight instance output in the console and
+    /// the session logs.
+    fn display_settings_output_rules(&mut self, ui: &mut Ui) {
+        egui::CollapsingHeader::new("Output rules")
+            .id_salt("settings_output_rules")
+            .show(ui, |ui| {
+                let mut responses = Vec::new();
+                let mut remove = None;
+                for (i, rule) in self.options.output_rules.iter_mut().enumerate() {
+                    ui.horizontal(|row| {
+                        responses.push(row.add(
+                            egui::TextEdit::singleline(&mut rule.pattern)
+                                .hint_text("Regular expression")
+                                .font(egui::TextStyle::Monospace)
+                                .desired_width(320.0),
+                        ));
+                        let action_combo =
+                            egui::ComboBox::from_id_salt(("settings_output_rule_action", i))
+                                .selected_text(rule.action.label())
+                                .show_ui(row, |combo| {
+                                    for action in OutputAction::ALL {
+                                        combo.selectable_value(
+                                            &mut rule.action,
+                                            action,
+                                            action.label(),
+                                        );
+                                    }
+                                });
+                        responses.push(action_combo.response);
+                        let remove_btn = row.button("✖");
+                        if remove_btn.clicked() {
+                            remove = Some(i);
+                        }
+                        responses.push(remove_btn);
+                    });
+                    // Invalid patterns are skipped at launch, so point them out here.
+                    if let Err(err) = validate_output_pattern(&rule.pattern) {
+                        ui.label(
+                            RichText::new(err)
+                                .monospace()
+                                .small()
+                                .color(ui.visuals().error_fg_color),
+                        );
+                    }
+                }
+                if let Some(i) = remove {
+                    self.options.output_rules.remove(i);
+                }
+
+                ui.horizontal(|row| {
+                    let add_btn = row.button("Add Rule");
+                    if add_btn.clicked() {
+                        self.options.output_rules.push(OutputRule {
+                            pattern: String::new(),
+                            action: OutputAction::Highlight,
+                        });
+                    }
+                    responses.push(add_btn);
+                    let reset_btn = row.button("Reset to Defaults");
+                    if reset_btn.clicked() {
+                        self.options.output_rules = default_output_rules();
+                    }
+                    responses.push(reset_btn);
+                });
+
+                for response in &responses {
+                    self.decorate_focus(ui, response);
+                }
+                if responses.iter().any(|response| response.hovered()) {
+                    self.infotext = "Regular expressions matched against every line the games print. Hide drops matching lines from the console and the session logs; Highlight prints them in red and marks them in the logs, where the log viewer colors them and keeps them under \"Warnings only\". The first matching rule wins. Changes apply to the next launch.".to_string();
+                }
+            });
+    }
+
+    /// Launcher waits, bounded by the ranges `Timings` declares.
+    fn display_settings_timings(&mut self, ui: &mut Ui) {
+        egui::CollapsingHeader::new("Timings")
+            .id_salt("settings_timings")
+            .show(ui, |ui| {
+                let timings = &mut self.options.timings;
+                let stagger = ui.add(
+                    egui::Slider::new(
+                        &mut timings.instance_stagger_ms,
+                        Timings::INSTANCE_STAGGER_MS,
+                    )
+                    .suffix(" ms")
+                    .text("Instance stagger"),
+                );
+                let restart = ui.add(
+                    egui::Slider::new(&mut timings.restart_delay_ms, Timings::RESTART_DELAY_MS)
+                        .suffix(" ms")
+                        .text("Restart delay"),
+                );
+                let poll = ui.add(
+                    egui::Slider::new(&mut timings.session_poll_ms, Timings::SESSION_POLL_MS)
+                        .suffix(" ms")
+                        .text("Session poll interval"),
+                );
+                let timeout = ui.add(
+                    egui::Slider::new(&mut timings.task_timeout_secs, Timings::TASK_TIMEOUT_SECS)
+                        .suffix(" s")
+                        .text("Task timeout"),
+                );
+                let reset_btn = ui.button("Reset to Defaults");
+                if reset_btn.clicked() {
+                    self.options.timings = Timings::default();
+                }
+                for response in [&stagger, &restart, &poll, &timeout, &reset_btn] {
+                    self.decorate_focus(ui, response);
+                }
+
+                if stagger.hovered() {
+                    self.infotext = "How long to wait after starting one instance before starting the next. Raise it if instances crash or fight over files while loading from a slow hard drive; lower it on fast NVMe drives.".to_string();
+                }
+                if restart.hovered() {
+                    self.infotext = "How long to wait before relaunching an instance restarted from the session panel or with Ctrl+Shift+R, so the old process can release its files.".to_string();
+                }
+                if poll.hovered() {
+                    self.infotext = "How often a running session checks its instances for exits and restart requests. Lower values react faster but wake the CPU more often.".to_string();
+                }
+                if timeout.hovered() {
+                    self.infotext = "How long the loading screen waits on a background task before giving the UI back. A launch is cancelled once it makes no progress for this long and stops the instances it already started; other tasks finish in the background.".to_string();
+                }
+                if reset_btn.hovered() {
+                    self.infotext = "Restores a 6 s stagger, 2 s restart delay, 250 ms poll interval and 60 s task timeout.".to_string();
+                }
+            });
+    }
+
     pub fn display_settings_gamescope(&mut self, ui: &mut Ui) {
         ui.spacing_mut().item_spacing.y = 12.0;
-        let gamescope_lowres_fix_check = ui.checkbox(
-            &mut self.options.gamescope_fix_lowres,
-            "Automatically fix low resolution instances",
-        );
-        self.decorate_focus(ui, &gamescope_lowres_fix_check);
-        let gamescope_sdl_backend_check = ui.checkbox(
-            &mut self.options.gamescope_sdl_backend,
-            "Use SDL backend for Gamescope",
-        );
-        self.decorate_focus(ui, &gamescope_sdl_backend_check);
-        let kbm_support_check = ui.checkbox(
-            &mut self.options.kbm_support,
-            "Enable keyboard and mouse support through custom Gamescope",
-        );
-        self.decorate_focus(ui, &kbm_support_check);
+        gamescope_settings(self, ui);
 
-        if gamescope_lowres_fix_check.hovered() {
-            self.infotext = "Many games have graphical problems or even crash when running at resolutions below 600p. If this is enabled, any instances below 600p will automatically be resized before launching.".to_string();
-        }
-        if gamescope_sdl_backend_check.hovered() {
-            self.infotext = "Runs gamescope sessions using the SDL backend. If unsure, leave this checked. If gamescope sessions only show a black screen or give an error (especially on Nvidia + Wayland), try disabling this.".to_string();
+        let hotplug_check =
+            ui.checkbox(&mut self.options.hotplug_rebind, "Rebind reconnected controllers");
+        self.decorate_focus(ui, &hotplug_check);
+        if hotplug_check.hovered() {
+            self.infotext = "Gives every controller of a session a virtual stand-in, so a pad that disconnects mid-game (flat battery, loose cable) goes back to its player when it reconnects, without restarting the game. You get a notification either way. Only applies with bubblewrap isolation.".to_string();
         }
-        if kbm_support_check.hovered() {
-            self.infotext = "Runs a custom Gamescope build with support for holding keyboards and mice. If you want to use your own Gamescope installation, uncheck this.".to_string();
+
+        // Idle sessions are paused and the screen dimmed until someone presses a button.
+        ui.horizontal(|row| {
+            let idle_label = row.label("Suspend idle sessions after");
+            let idle_drag = row.add(
+                egui::DragValue::new(&mut self.options.idle_timeout_minutes)
+                    .range(0..=240)
+                    .suffix(" min"),
+            );
+            self.decorate_focus(row, &idle_drag);
+            let pause_check = row.checkbox(&mut self.options.idle_pause_instances, "Pause games");
+            self.decorate_focus(row, &pause_check);
+            let dim_check = row.checkbox(&mut self.options.idle_dim_display, "Dim screen");
+            self.decorate_focus(row, &dim_check);
+            if idle_label.hovered() || idle_drag.hovered() {
+                self.infotext = "Once no player has touched a controller, keyboard or mouse for this many minutes, the session counts as idle. Any input resumes it. 0 turns idle detection off.".to_string();
+            }
+            if pause_check.hovered() {
+                self.infotext = "Freezes every instance of an idle session (SIGSTOP) so it stops using CPU, GPU and power, and lets it continue where it was on the next input. Online games may drop their connection while paused.".to_string();
+            }
+            if dim_check.hovered() {
+                self.infotext = "Lowers the screen backlight of an idle session through brightnessctl and restores it on the next input. Only works for screens with a controllable backlight, such as laptops and the Steam Deck.".to_string();
+            }
+        });
+    }
+}
+
+/// Shortened emulator ID for the status grid; IDs the launch still has to
+/// generate are marked as such.
+fn emulator_id_text(id: Option<&String>) -> RichText {
+    match id {
+        Some(id) if id.len() > 12 && id.is_ascii() => {
+            RichText::new(format!("{}…", &id[..10])).monospace()
         }
+        Some(id) => RichText::new(id.as_str()).monospace(),
+        None => RichText::new("at launch").weak(),
     }
 }
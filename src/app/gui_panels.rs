@@ -155,15 +155,36 @@ impl PartyApp {
                                         if styled_nav_button(ui, version_label, false, false)
                                             .clicked()
                                         {
-                                            ui.ctx().open_url(OpenUrl::new_tab(
-                                                "https://github.com/blckink/suckmydeck/releases",
-                                            ));
+                                            // Updates install in place; otherwise show the
+                                            // release notes.
+                                            if self.needs_update {
+                                                self.install_update();
+                                            } else {
+                                                ui.ctx().open_url(OpenUrl::new_tab(
+                                                    "https://github.com/blckink/suckmydeck/releases",
+                                                ));
+                                            }
                                         }
 
                                         if styled_nav_button(ui, "Add Game", false, false).clicked()
                                         {
                                             self.prompt_add_game();
                                         }
+                                        if styled_nav_button(ui, "New Handler", false, false)
+                                            .clicked()
+                                        {
+                                            self.open_handler_editor(false);
+                                        }
+                                        if styled_nav_button(ui, "Browse Handlers", false, false)
+                                            .clicked()
+                                        {
+                                            self.open_handler_browser();
+                                        }
+                                        if styled_nav_button(ui, "View Logs", false, false)
+                                            .clicked()
+                                        {
+                                            self.open_log_viewer();
+                                        }
                                         if styled_nav_button(ui, "Rescan Controllers", false, false)
                                             .clicked()
                                         {
@@ -187,6 +208,7 @@ impl PartyApp {
         if self.input_devices.is_empty() {
             ui.label(RichText::new("No controllers detected.").weak());
         } else {
+            let mut calibrate_path = None;
             for pad in self.input_devices.iter() {
                 let mut dev_text = RichText::new(format!(
                     "{} {} ({})",
@@ -202,7 +224,20 @@ impl PartyApp {
                     dev_text = dev_text.strong();
                 }
 
-                ui.label(dev_text);
+                ui.horizontal(|row| {
+                    row.label(dev_text);
+                    if pad.device_type() == DeviceType::Gamepad {
+                        let calibrate = row
+                            .small_button("🎯")
+                            .on_hover_text("Calibrate sticks and dead zone");
+                        if calibrate.clicked() {
+                            calibrate_path = Some(pad.path().to_string());
+                        }
+                    }
+                });
+            }
+            if let Some(path) = calibrate_path {
+                self.open_calibration_wizard(&path);
             }
         }
 
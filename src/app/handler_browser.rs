@@ -0,0 +1,213 @@
+use super::app::{MenuPage, PartyApp};
+use crate::game::Game::HandlerRef;
+use crate::handler::install_handler_from_file;
+use crate::paths::*;
+use crate::util::*;
+
+use eframe::egui::{self, RichText, Ui};
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::thread::JoinHandle;
+
+/// Result of fetching a repository index along with any cached artwork.
+type IndexFetch = Result<(Vec<RepoHandler>, HashMap<String, PathBuf>), String>;
+
+/// State of the "Browse Handlers" page. Network work runs on background
+/// threads that are polled every frame so the UI never blocks on curl.
+#[derive(Default)]
+pub struct HandlerBrowser {
+    pub entries: Vec<RepoHandler>,
+    pub artwork: HashMap<String, PathBuf>,
+    pub error: Option<String>,
+    pub filter: String,
+    fetch: Option<JoinHandle<IndexFetch>>,
+    /// Handler name being installed and the thread doing it.
+    install: Option<(String, JoinHandle<Result<Option<String>, String>>)>,
+}
+
+impl HandlerBrowser {
+    fn start_fetch(&mut self, url: String) {
+        self.error = None;
+        self.fetch = Some(std::thread::spawn(move || {
+            let entries = fetch_handler_index(&url).map_err(|err| err.to_string())?;
+            let artwork = entries
+                .iter()
+                .filter_map(|entry| Some((entry.uid.clone(), cache_handler_artwork(entry)?)))
+                .collect();
+            Ok((entries, artwork))
+        }));
+    }
+}
+
+impl PartyApp {
+    /// Opens the handler browser and refreshes the repository index.
+    pub fn open_handler_browser(&mut self) {
+        let mut browser = self.handler_browser.take().unwrap_or_default();
+        if browser.fetch.is_none() {
+            browser.start_fetch(self.options.handler_repository_url.clone());
+        }
+        self.handler_browser = Some(browser);
+        self.cur_page = MenuPage::HandlerBrowser;
+    }
+
+    /// Version of an installed handler with `uid`, if any.
+    fn installed_handler_version(&self, uid: &str) -> Option<String> {
+        self.games.iter().find_map(|game| match game {
+            HandlerRef(h) if h.uid == uid => Some(h.version.clone()),
+            _ => None,
+        })
+    }
+
+    pub fn display_page_handler_browser(&mut self, ui: &mut Ui) {
+        let Some(mut browser) = self.handler_browser.take() else {
+            self.cur_page = MenuPage::Home;
+            return;
+        };
+
+        // Collect finished background work before drawing.
+        if browser.fetch.as_ref().is_some_and(|handle| handle.is_finished()) {
+            match browser.fetch.take().unwrap().join() {
+                Ok(Ok((entries, artwork))) => {
+                    browser.entries = entries;
+                    browser.artwork = artwork;
+                }
+                Ok(Err(err)) => browser.error = Some(err),
+                Err(_) => browser.error = Some("Index fetch panicked".to_string()),
+            }
+        }
+        let install_finished = browser
+            .install
+            .as_ref()
+            .is_some_and(|(_, handle)| handle.is_finished());
+        if install_finished {
+            let (name, handle) = browser.install.take().unwrap();
+            match handle.join() {
+                Ok(Ok(anticheat)) => {
+                    self.reload_games();
+                    msg("Handler Installed", &format!("{name} is ready to play."));
+                    if let Some(warning) = anticheat {
+                        msg(ANTICHEAT_TITLE, &warning);
+                    }
+                }
+                Ok(Err(err)) => msg("Error", &format!("Couldn't install {name}: {err}")),
+                Err(_) => msg("Error", &format!("Installing {name} panicked")),
+            }
+        }
+
+        ui.horizontal(|row| {
+            row.heading("Browse Handlers");
+            let refresh_btn = row.add_enabled(browser.fetch.is_none(), egui::Button::new("⟳"));
+            self.decorate_focus(row, &refresh_btn);
+            if refresh_btn.clicked() {
+                browser.start_fetch(self.options.handler_repository_url.clone());
+            }
+            let filter_edit = row.add(
+                egui::TextEdit::singleline(&mut browser.filter).hint_text("Search handlers"),
+            );
+            self.decorate_focus(row, &filter_edit);
+        });
+        ui.separator();
+
+        if self.options.handler_repository_url.trim().is_empty() {
+            ui.label("Set a handler repository URL in Settings to browse handlers.");
+        }
+        if browser.fetch.is_some() {
+            ui.horizontal(|row| {
+                row.spinner();
+                row.label("Fetching handler index...");
+            });
+            ui.ctx().request_repaint();
+        }
+        if let Some((name, _)) = &browser.install {
+            ui.horizontal(|row| {
+                row.spinner();
+                row.label(format!("Installing {name}..."));
+            });
+            ui.ctx().request_repaint();
+        }
+        if let Some(err) = &browser.error {
+            ui.label(RichText::new(err).color(ui.visuals().error_fg_color));
+        }
+
+        let filter = browser.filter.to_lowercase();
+        let mut install_request: Option<RepoHandler> = None;
+        egui::ScrollArea::vertical()
+            .auto_shrink([false; 2])
+            .show(ui, |scroll| {
+                for entry in browser.entries.iter().filter(|entry| {
+                    filter.is_empty()
+                        || entry.name.to_lowercase().contains(&filter)
+                        || entry.author.to_lowercase().contains(&filter)
+                }) {
+                    let installed = self.installed_handler_version(&entry.uid);
+                    egui::Frame::new()
+                        .fill(scroll.visuals().widgets.inactive.bg_fill)
+                        .corner_radius(egui::CornerRadius::same(12))
+                        .inner_margin(egui::Margin::symmetric(18, 12))
+                        .show(scroll, |card| {
+                            card.horizontal(|row| {
+                                if let Some(img) = browser.artwork.get(&entry.uid) {
+                                    row.add(
+                                        egui::Image::new(format!("file://{}", img.display()))
+                                            .max_height(64.0)
+                                            .max_width(64.0),
+                                    );
+                                }
+                                row.vertical(|info| {
+                                    info.label(RichText::new(&entry.name).size(20.0).strong());
+                                    let mut details = format!("Version {}", entry.version);
+                                    if !entry.author.is_empty() {
+                                        details.push_str(&format!(" • by {}", entry.author));
+                                    }
+                                    if let Some(version) = &installed {
+                                        details.push_str(&format!(" • installed: {version}"));
+                                    }
+                                    info.label(RichText::new(details).weak());
+                                    if !entry.info.is_empty() {
+                                        info.label(entry.info.as_str());
+                                    }
+                                });
+                                row.with_layout(
+                                    egui::Layout::right_to_left(egui::Align::Center),
+                                    |actions| {
+                                        let label = match &installed {
+                                            Some(version) if *version == entry.version => {
+                                                "Reinstall"
+                                            }
+                                            Some(_) => "Update",
+                                            None => "Install",
+                                        };
+                                        let install_btn = actions.add_enabled(
+                                            browser.install.is_none(),
+                                            egui::Button::new(label),
+                                        );
+                                        self.decorate_focus(actions, &install_btn);
+                                        if install_btn.clicked() {
+                                            install_request = Some(entry.clone());
+                                        }
+                                    },
+                                );
+                            });
+                        });
+                    scroll.add_space(8.0);
+                }
+            });
+
+        if let Some(entry) = install_request {
+            let name = entry.name.clone();
+            browser.install = Some((
+                name,
+                std::thread::spawn(move || {
+                    let archive = download_handler_archive(&entry).map_err(|e| e.to_string())?;
+                    let result = install_handler_from_file(&archive).map_err(|e| e.to_string());
+                    // install_handler_from_file leaves its staging dir behind on errors.
+                    let _ = std::fs::remove_dir_all(PATH_APP.join("tmp"));
+                    let _ = std::fs::remove_file(&archive);
+                    result
+                }),
+            ));
+        }
+
+        self.handler_browser = Some(browser);
+    }
+}
@@ -0,0 +1,696 @@
+use super::app::{MenuPage, PartyApp};
+use crate::game::Game::HandlerRef;
+use crate::handler::Handler;
+use crate::logging::log_info;
+use crate::paths::*;
+use crate::util::*;
+
+use eframe::egui::{self, RichText, Ui};
+use serde_json::{Map, Value};
+use std::error::Error;
+use std::fs::File;
+use std::io::BufReader;
+use std::path::PathBuf;
+
+/// Editable copy of every handler.json field. List-valued keys are kept as
+/// newline-separated text so they can be edited in a multiline box.
+#[derive(Clone, Default)]
+pub struct HandlerDraft {
+    /// Directory of the handler being edited; `None` when creating a new one or
+    /// when overriding a read-only system handler with a user copy.
+    pub target_dir: Option<PathBuf>,
+    /// Raw JSON of the edited handler so keys the editor doesn't know survive.
+    pub base_json: Map<String, Value>,
+
+    pub uid: String,
+    pub name: String,
+    pub author: String,
+    pub version: String,
+    pub info: String,
+
+    pub symlink_dir: bool,
+    pub win: bool,
+    pub is32bit: bool,
+    pub runtime: String,
+    pub exec: String,
+    pub alt_exec: String,
+    pub args: String,
+    pub copy_instead_paths: String,
+    pub remove_paths: String,
+    pub dll_overrides: String,
+    pub winetricks: String,
+    pub graphics_api: String,
+    pub dxvk_conf: String,
+    pub dxvk_fps_limit: String,
+    pub vkd3d_config: String,
+    pub save_compatible_uids: String,
+    pub never_symlink_paths: String,
+    pub memory_mb: String,
+    pub separate_users: bool,
+    pub firewall_ports: String,
+    pub gamescope_args: String,
+    pub gamescope_upscaler: String,
+
+    pub path_goldberg: String,
+    pub steam_appid: String,
+    pub coldclient: bool,
+    pub goldberg_version: String,
+    pub path_nemirtingas: String,
+    pub eos_per_instance: bool,
+
+    pub win_unique_appdata: bool,
+    pub win_unique_documents: bool,
+    pub linux_unique_localshare: bool,
+    pub linux_unique_config: bool,
+    pub game_unique_paths: String,
+}
+
+/// Splits a multiline text box into trimmed, non-empty entries.
+fn lines_to_vec(text: &str) -> Vec<String> {
+    text.lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+/// Comma-separated frame limits, one per player slot. `None` when one isn't
+/// a number.
+fn fps_limits(text: &str) -> Option<Vec<u32>> {
+    text.split(',')
+        .map(str::trim)
+        .filter(|fps| !fps.is_empty())
+        .map(|fps| fps.parse().ok())
+        .collect()
+}
+
+/// Checks that a handler path stays inside the game directory.
+fn invalid_relative_path(path: &str) -> bool {
+    path.starts_with('/') || path.split(['/', '\\']).any(|part| part == "..")
+}
+
+impl HandlerDraft {
+    /// Starts an empty draft with the defaults most Proton handlers use.
+    pub fn new_empty() -> Self {
+        Self {
+            symlink_dir: true,
+            win: true,
+            version: "1".to_string(),
+            ..Default::default()
+        }
+    }
+
+    /// Fills a draft from an installed handler. Read-only system handlers are
+    /// saved as a user copy, which then overrides the system one.
+    pub fn from_handler(h: &Handler) -> Self {
+        let base_json = File::open(h.path_handler.join("handler.json"))
+            .ok()
+            .and_then(|file| serde_json::from_reader::<_, Value>(BufReader::new(file)).ok())
+            .and_then(|json| json.as_object().cloned())
+            .unwrap_or_default();
+
+        Self {
+            target_dir: (!h.read_only).then(|| h.path_handler.clone()),
+            base_json,
+            uid: h.uid.clone(),
+            name: h.name.clone(),
+            author: h.author.clone(),
+            version: h.version.clone(),
+            info: h.info.clone(),
+            symlink_dir: h.symlink_dir,
+            win: h.win,
+            is32bit: h.is32bit,
+            runtime: h.runtime.clone(),
+            exec: h.exec.clone(),
+            alt_exec: h.alt_exec.clone(),
+            args: h.args.join("\n"),
+            copy_instead_paths: h.copy_instead_paths.join("\n"),
+            remove_paths: h.remove_paths.join("\n"),
+            dll_overrides: h.dll_overrides.join("\n"),
+            winetricks: h.winetricks.join("\n"),
+            graphics_api: h.graphics_api.map(|api| api.name().to_string()).unwrap_or_default(),
+            dxvk_conf: h.dxvk_conf.join("\n"),
+            dxvk_fps_limit: h
+                .dxvk_fps_limit
+                .iter()
+                .map(u32::to_string)
+                .collect::<Vec<_>>()
+                .join(", "),
+            vkd3d_config: h.vkd3d_config.join("\n"),
+            save_compatible_uids: h.save_compatible_uids.join("\n"),
+            never_symlink_paths: h.never_symlink_paths.join("\n"),
+            memory_mb: h.memory_mb.map(|mb| mb.to_string()).unwrap_or_default(),
+            separate_users: h.separate_users,
+            firewall_ports: h.firewall_ports.join("\n"),
+            gamescope_args: h.gamescope_args.join("\n"),
+            gamescope_upscaler: h
+                .gamescope_upscaler
+                .map(|upscaler| upscaler.name().to_string())
+                .unwrap_or_default(),
+            path_goldberg: h.path_goldberg.clone(),
+            steam_appid: h.steam_appid.clone().unwrap_or_default(),
+            coldclient: h.coldclient,
+            goldberg_version: h.goldberg_version.clone(),
+            path_nemirtingas: h.path_nemirtingas.clone(),
+            eos_per_instance: h.eos_per_instance,
+            win_unique_appdata: h.win_unique_appdata,
+            win_unique_documents: h.win_unique_documents,
+            linux_unique_localshare: h.linux_unique_localshare,
+            linux_unique_config: h.linux_unique_config,
+            game_unique_paths: h.game_unique_paths.join("\n"),
+        }
+    }
+
+    /// Validates the draft and returns `(field, message)` pairs so errors can
+    /// be shown next to the offending input.
+    pub fn validate(&self) -> Vec<(&'static str, String)> {
+        let mut errors = Vec::new();
+
+        if self.uid.is_empty() {
+            errors.push(("uid", "UID is required.".to_string()));
+        } else if !self.uid.chars().all(char::is_alphanumeric) {
+            errors.push(("uid", "UID must be alphanumeric.".to_string()));
+        } else if self.target_dir.is_none()
+            && PATH_APP.join("handlers").join(&self.uid).exists()
+        {
+            errors.push(("uid", "A handler with this UID already exists.".to_string()));
+        }
+
+        if self.exec.trim().is_empty() {
+            errors.push(("exec", "Executable path is required.".to_string()));
+        } else if invalid_relative_path(self.exec.trim()) {
+            errors.push((
+                "exec",
+                "Use a path relative to the game directory.".to_string(),
+            ));
+        }
+
+        if invalid_relative_path(self.alt_exec.trim()) {
+            errors.push((
+                "alt_exec",
+                "Use a path relative to the game directory.".to_string(),
+            ));
+        }
+
+        if !["", "scout", "soldier"].contains(&self.runtime.as_str()) {
+            errors.push(("runtime", "Runtime must be empty, scout or soldier.".to_string()));
+        }
+
+        for (field, value) in [
+            ("path_goldberg", self.path_goldberg.as_str()),
+            ("path_nemirtingas", self.path_nemirtingas.as_str()),
+        ] {
+            if invalid_relative_path(value.trim()) {
+                errors.push((field, "Use a path relative to the game directory.".to_string()));
+            }
+        }
+        for (field, value) in [
+            ("copy_instead_paths", self.copy_instead_paths.as_str()),
+            ("remove_paths", self.remove_paths.as_str()),
+            ("never_symlink_paths", self.never_symlink_paths.as_str()),
+            ("game_unique_paths", self.game_unique_paths.as_str()),
+        ] {
+            if let Some(bad) = lines_to_vec(value)
+                .iter()
+                .find(|path| invalid_relative_path(path))
+            {
+                errors.push((field, format!("{bad} must be relative to the game directory.")));
+            }
+        }
+
+        if !self.memory_mb.trim().is_empty() && self.memory_mb.trim().parse::<u64>().is_err() {
+            errors.push(("memory_mb", "Memory must be a whole number of MiB.".to_string()));
+        }
+        if !self.steam_appid.trim().is_empty()
+            && !self.steam_appid.trim().chars().all(|c| c.is_ascii_digit())
+        {
+            errors.push(("steam_appid", "Steam App ID must be numeric.".to_string()));
+        }
+        let gb_version = self.goldberg_version.trim();
+        if !gb_version.is_empty() && !is_version_name(gb_version) {
+            errors.push((
+                "goldberg_version",
+                "Goldberg build must be a folder name under res/goldberg.".to_string(),
+            ));
+        }
+        if let Some(bad) = lines_to_vec(&self.winetricks)
+            .iter()
+            .find(|verb| !is_winetricks_verb(verb))
+        {
+            errors.push(("winetricks", format!("{bad} isn't a winetricks verb.")));
+        }
+        if !["", "dxvk", "wined3d"].contains(&self.graphics_api.as_str()) {
+            errors.push(("graphics_api", "Graphics must be dxvk or wined3d.".to_string()));
+        }
+        if fps_limits(&self.dxvk_fps_limit).is_none() {
+            errors.push((
+                "dxvk_fps_limit",
+                "Frame limits must be numbers separated by commas, 0 for none.".to_string(),
+            ));
+        }
+        if let Some(bad) = lines_to_vec(&self.vkd3d_config)
+            .iter()
+            .find(|flag| !is_vkd3d_flag(flag))
+        {
+            errors.push(("vkd3d_config", format!("{bad} isn't a VKD3D_CONFIG flag.")));
+        }
+        if let Some(bad) = lines_to_vec(&self.save_compatible_uids)
+            .iter()
+            .find(|uid| !uid.chars().all(char::is_alphanumeric))
+        {
+            errors.push(("save_compatible_uids", format!("{bad} isn't a valid UID.")));
+        }
+        if let Some(bad) = lines_to_vec(&self.firewall_ports)
+            .iter()
+            .find(|spec| parse_port_spec(spec).is_none())
+        {
+            errors.push((
+                "firewall_ports",
+                format!("{bad} is not a valid port spec (e.g. 7777/udp)."),
+            ));
+        }
+
+        if lines_to_vec(&self.gamescope_args).iter().any(|arg| arg == "--") {
+            errors.push((
+                "gamescope_args",
+                "-- can't be used; the launcher adds it before the game command.".to_string(),
+            ));
+        }
+        if !["", "fsr", "nis", "integer"].contains(&self.gamescope_upscaler.as_str()) {
+            errors.push((
+                "gamescope_upscaler",
+                "Upscaler must be fsr, nis or integer.".to_string(),
+            ));
+        }
+
+        errors
+    }
+
+    /// Serializes the draft on top of the original JSON, keeping unknown keys.
+    fn to_json(&self) -> Value {
+        let mut json = self.base_json.clone();
+        let list = |text: &str| Value::from(lines_to_vec(text));
+
+        json.insert("handler.uid".into(), self.uid.clone().into());
+        // A changed uid is recorded so saves under the old one can be migrated.
+        let original_uid = self
+            .base_json
+            .get("handler.uid")
+            .and_then(Value::as_str)
+            .unwrap_or_default();
+        if !original_uid.is_empty() && original_uid != self.uid {
+            let mut former = json
+                .get("handler.former_uids")
+                .and_then(Value::as_array)
+                .cloned()
+                .unwrap_or_default();
+            if !former.iter().any(|uid| uid.as_str() == Some(original_uid)) {
+                former.push(original_uid.into());
+            }
+            json.insert("handler.former_uids".into(), Value::Array(former));
+        }
+        json.insert("handler.name".into(), self.name.clone().into());
+        json.insert("handler.author".into(), self.author.clone().into());
+        json.insert("handler.version".into(), self.version.clone().into());
+        json.insert("handler.info".into(), self.info.clone().into());
+
+        json.insert("game.symlink_dir".into(), self.symlink_dir.into());
+        json.insert("game.win".into(), self.win.into());
+        json.insert("game.32bit".into(), self.is32bit.into());
+        json.insert("game.runtime".into(), self.runtime.clone().into());
+        json.insert("game.exec".into(), self.exec.trim().into());
+        json.insert("game.alt_exec".into(), self.alt_exec.trim().into());
+        json.insert("game.args".into(), list(&self.args));
+        json.insert("game.copy_instead_paths".into(), list(&self.copy_instead_paths));
+        json.insert("game.remove_paths".into(), list(&self.remove_paths));
+        json.insert("game.dll_overrides".into(), list(&self.dll_overrides));
+        json.insert("proton.winetricks".into(), list(&self.winetricks));
+        if self.graphics_api.is_empty() {
+            json.remove("proton.graphics");
+        } else {
+            json.insert("proton.graphics".into(), self.graphics_api.clone().into());
+        }
+        json.insert("proton.dxvk_conf".into(), list(&self.dxvk_conf));
+        match fps_limits(&self.dxvk_fps_limit).unwrap_or_default().as_slice() {
+            [] | [0] => json.remove("proton.dxvk_fps_limit"),
+            [fps] => json.insert("proton.dxvk_fps_limit".into(), (*fps).into()),
+            limits => json.insert("proton.dxvk_fps_limit".into(), limits.into()),
+        };
+        json.insert("proton.vkd3d_config".into(), list(&self.vkd3d_config));
+        json.insert("handler.save_compatible_uids".into(), list(&self.save_compatible_uids));
+        json.insert("game.never_symlink_paths".into(), list(&self.never_symlink_paths));
+        match self.memory_mb.trim().parse::<u64>() {
+            Ok(mb) => json.insert("game.memory_mb".into(), mb.into()),
+            Err(_) => json.remove("game.memory_mb"),
+        };
+        json.insert("game.separate_users".into(), self.separate_users.into());
+        json.insert("game.firewall_ports".into(), list(&self.firewall_ports));
+        json.insert("gamescope.args".into(), list(&self.gamescope_args));
+        if self.gamescope_upscaler.is_empty() {
+            json.remove("gamescope.upscaler");
+        } else {
+            json.insert("gamescope.upscaler".into(), self.gamescope_upscaler.clone().into());
+        }
+
+        json.insert("steam.api_path".into(), self.path_goldberg.trim().into());
+        if self.steam_appid.trim().is_empty() {
+            json.remove("steam.appid");
+        } else {
+            json.insert("steam.appid".into(), self.steam_appid.trim().into());
+        }
+        json.insert("steam.gb_coldclient".into(), self.coldclient.into());
+        if self.goldberg_version.trim().is_empty() {
+            json.remove("steam.gb_version");
+        } else {
+            json.insert("steam.gb_version".into(), self.goldberg_version.trim().into());
+        }
+        json.insert("eos.config_path".into(), self.path_nemirtingas.trim().into());
+        json.insert("eos.per_instance".into(), self.eos_per_instance.into());
+
+        json.insert("profiles.unique_appdata".into(), self.win_unique_appdata.into());
+        json.insert("profiles.unique_documents".into(), self.win_unique_documents.into());
+        json.insert("profiles.unique_localshare".into(), self.linux_unique_localshare.into());
+        json.insert("profiles.unique_config".into(), self.linux_unique_config.into());
+        json.insert("profiles.game_paths".into(), list(&self.game_unique_paths));
+
+        Value::Object(json)
+    }
+
+    /// Writes handler.json into the user handlers directory and returns its path.
+    pub fn save(&self) -> Result<PathBuf, Box<dyn Error>> {
+        if let Some((_, err)) = self.validate().into_iter().next() {
+            return Err(err.into());
+        }
+
+        let dir = self
+            .target_dir
+            .clone()
+            .unwrap_or_else(|| PATH_APP.join("handlers").join(&self.uid));
+        std::fs::create_dir_all(&dir)?;
+        let path = dir.join("handler.json");
+        std::fs::write(&path, serde_json::to_string_pretty(&self.to_json())?)?;
+
+        // Goldberg is only copied when the symlink folder is built, so a new
+        // build needs a fresh one.
+        let old_version = self.base_json.get("steam.gb_version").and_then(Value::as_str);
+        if old_version.unwrap_or_default() != self.goldberg_version.trim() {
+            let _ = std::fs::remove_dir_all(PATH_APP.join("gamesyms").join(&self.uid));
+        }
+        Ok(path)
+    }
+}
+
+impl PartyApp {
+    /// Opens the handler editor, either blank or pre-filled from the selected
+    /// handler.
+    pub fn open_handler_editor(&mut self, edit_selected: bool) {
+        let draft = match self.games.get(self.selected_game) {
+            Some(HandlerRef(h)) if edit_selected => HandlerDraft::from_handler(h),
+            _ => HandlerDraft::new_empty(),
+        };
+        self.handler_editor = Some(draft);
+        self.cur_page = MenuPage::HandlerEditor;
+        self.nav_in_focus = false;
+        self.pending_content_focus = true;
+        self.pending_scroll_to_focus = true;
+    }
+
+    pub fn display_page_handler_editor(&mut self, ui: &mut Ui) {
+        let Some(mut draft) = self.handler_editor.take() else {
+            self.cur_page = MenuPage::Home;
+            return;
+        };
+        let errors = draft.validate();
+        let mut close_editor = false;
+
+        let heading = if draft.base_json.is_empty() {
+            "Create Handler"
+        } else {
+            "Edit Handler"
+        };
+        ui.heading(heading);
+        ui.separator();
+
+        egui::ScrollArea::vertical()
+            .auto_shrink([false; 2])
+            .show(ui, |scroll| {
+                scroll.spacing_mut().item_spacing.y = 8.0;
+
+                // Render each input with its validation message directly below.
+                let show_error = |ui: &mut Ui, field: &str| {
+                    for (_, message) in errors.iter().filter(|(name, _)| *name == field) {
+                        ui.label(RichText::new(message).color(ui.visuals().error_fg_color));
+                    }
+                };
+
+                scroll.label(RichText::new("Handler").strong());
+                egui::Grid::new("handler_editor_meta")
+                    .num_columns(2)
+                    .spacing([12.0, 8.0])
+                    .show(scroll, |grid| {
+                        grid.label("UID");
+                        let uid_edit = grid.add_enabled(
+                            draft.target_dir.is_none(),
+                            egui::TextEdit::singleline(&mut draft.uid),
+                        );
+                        self.decorate_focus(grid, &uid_edit);
+                        grid.end_row();
+                        for (label, value) in [
+                            ("Name", &mut draft.name),
+                            ("Author", &mut draft.author),
+                            ("Version", &mut draft.version),
+                            ("Info", &mut draft.info),
+                        ] {
+                            grid.label(label);
+                            let edit = grid.text_edit_singleline(value);
+                            self.decorate_focus(grid, &edit);
+                            grid.end_row();
+                        }
+                    });
+                show_error(scroll, "uid");
+
+                scroll.separator();
+                scroll.label(RichText::new("Game").strong());
+                egui::Grid::new("handler_editor_game")
+                    .num_columns(2)
+                    .spacing([12.0, 8.0])
+                    .show(scroll, |grid| {
+                        grid.label("Executable");
+                        let exec_edit = grid.add(
+                            egui::TextEdit::singleline(&mut draft.exec)
+                                .hint_text("Binaries/Game.exe"),
+                        );
+                        self.decorate_focus(grid, &exec_edit);
+                        grid.end_row();
+
+                        grid.label("Other platform's executable");
+                        let alt_exec_edit = grid.add(
+                            egui::TextEdit::singleline(&mut draft.alt_exec)
+                                .hint_text("Optional, e.g. bin/game.x86_64"),
+                        );
+                        self.decorate_focus(grid, &alt_exec_edit);
+                        grid.end_row();
+
+                        grid.label("Runtime");
+                        egui::ComboBox::from_id_salt("handler_editor_runtime")
+                            .selected_text(if draft.runtime.is_empty() {
+                                "None"
+                            } else {
+                                draft.runtime.as_str()
+                            })
+                            .show_ui(grid, |combo| {
+                                combo.selectable_value(&mut draft.runtime, String::new(), "None");
+                                combo.selectable_value(&mut draft.runtime, "scout".into(), "scout");
+                                combo.selectable_value(
+                                    &mut draft.runtime,
+                                    "soldier".into(),
+                                    "soldier",
+                                );
+                            });
+                        grid.end_row();
+
+                        grid.label("Memory per instance (MiB)");
+                        let memory_edit = grid.text_edit_singleline(&mut draft.memory_mb);
+                        self.decorate_focus(grid, &memory_edit);
+                        grid.end_row();
+
+                        grid.label("Gamescope upscaler");
+                        egui::ComboBox::from_id_salt("handler_editor_upscaler")
+                            .selected_text(if draft.gamescope_upscaler.is_empty() {
+                                "None"
+                            } else {
+                                draft.gamescope_upscaler.as_str()
+                            })
+                            .show_ui(grid, |combo| {
+                                let upscaler = &mut draft.gamescope_upscaler;
+                                combo.selectable_value(upscaler, String::new(), "None");
+                                for name in ["fsr", "nis", "integer"] {
+                                    combo.selectable_value(upscaler, name.to_string(), name);
+                                }
+                            });
+                        grid.end_row();
+
+                        grid.label("Graphics (Proton)");
+                        egui::ComboBox::from_id_salt("handler_editor_graphics")
+                            .selected_text(if draft.graphics_api.is_empty() {
+                                "Proton default"
+                            } else {
+                                draft.graphics_api.as_str()
+                            })
+                            .show_ui(grid, |combo| {
+                                let api = &mut draft.graphics_api;
+                                combo.selectable_value(api, String::new(), "Proton default");
+                                for name in ["dxvk", "wined3d"] {
+                                    combo.selectable_value(api, name.to_string(), name);
+                                }
+                            });
+                        grid.end_row();
+
+                        grid.label("DXVK frame limit");
+                        let fps_edit = grid.add(
+                            egui::TextEdit::singleline(&mut draft.dxvk_fps_limit)
+                                .hint_text("Optional, e.g. 60, or 60, 30 per player"),
+                        );
+                        self.decorate_focus(grid, &fps_edit);
+                        grid.end_row();
+                    });
+                show_error(scroll, "exec");
+                show_error(scroll, "alt_exec");
+                show_error(scroll, "runtime");
+                show_error(scroll, "memory_mb");
+                show_error(scroll, "gamescope_upscaler");
+                show_error(scroll, "graphics_api");
+                show_error(scroll, "dxvk_fps_limit");
+
+                for (label, value) in [
+                    ("Windows game (Proton)", &mut draft.win),
+                    ("32-bit executable", &mut draft.is32bit),
+                    ("Symlink game directory", &mut draft.symlink_dir),
+                    ("Run players as separate OS users", &mut draft.separate_users),
+                ] {
+                    let check = scroll.checkbox(value, label);
+                    self.decorate_focus(scroll, &check);
+                }
+
+                // List-valued keys are edited one entry per line.
+                for (label, field, value) in [
+                    ("Arguments", "args", &mut draft.args),
+                    ("DLL overrides", "dll_overrides", &mut draft.dll_overrides),
+                    ("Winetricks verbs", "winetricks", &mut draft.winetricks),
+                    ("dxvk.conf lines", "dxvk_conf", &mut draft.dxvk_conf),
+                    ("VKD3D flags", "vkd3d_config", &mut draft.vkd3d_config),
+                    (
+                        "Copy instead of symlink",
+                        "copy_instead_paths",
+                        &mut draft.copy_instead_paths,
+                    ),
+                    ("Remove paths", "remove_paths", &mut draft.remove_paths),
+                    (
+                        "Never symlink paths",
+                        "never_symlink_paths",
+                        &mut draft.never_symlink_paths,
+                    ),
+                    ("Firewall ports", "firewall_ports", &mut draft.firewall_ports),
+                    ("Gamescope arguments", "gamescope_args", &mut draft.gamescope_args),
+                    (
+                        "Save-compatible handler UIDs",
+                        "save_compatible_uids",
+                        &mut draft.save_compatible_uids,
+                    ),
+                ] {
+                    scroll.label(format!("{label} (one per line)"));
+                    let edit = scroll.add(egui::TextEdit::multiline(value).desired_rows(2));
+                    self.decorate_focus(scroll, &edit);
+                    show_error(scroll, field);
+                }
+
+                scroll.separator();
+                scroll.label(RichText::new("Emulators").strong());
+                egui::Grid::new("handler_editor_emu")
+                    .num_columns(2)
+                    .spacing([12.0, 8.0])
+                    .show(scroll, |grid| {
+                        for (label, value) in [
+                            ("Goldberg steam_api path", &mut draft.path_goldberg),
+                            ("Steam App ID", &mut draft.steam_appid),
+                            ("Goldberg build", &mut draft.goldberg_version),
+                            ("Nemirtingas config path", &mut draft.path_nemirtingas),
+                        ] {
+                            grid.label(label);
+                            let edit = grid.text_edit_singleline(value);
+                            self.decorate_focus(grid, &edit);
+                            grid.end_row();
+                        }
+                    });
+                show_error(scroll, "path_goldberg");
+                show_error(scroll, "steam_appid");
+                show_error(scroll, "goldberg_version");
+                show_error(scroll, "path_nemirtingas");
+                for (label, value) in [
+                    ("Goldberg ColdClient loader", &mut draft.coldclient),
+                    ("Nemirtingas config per instance", &mut draft.eos_per_instance),
+                ] {
+                    let check = scroll.checkbox(value, label);
+                    self.decorate_focus(scroll, &check);
+                }
+
+                scroll.separator();
+                scroll.label(RichText::new("Profile saves").strong());
+                for (label, value) in [
+                    ("Unique AppData (Windows)", &mut draft.win_unique_appdata),
+                    ("Unique Documents (Windows)", &mut draft.win_unique_documents),
+                    ("Unique ~/.local/share (Linux)", &mut draft.linux_unique_localshare),
+                    ("Unique ~/.config (Linux)", &mut draft.linux_unique_config),
+                ] {
+                    let check = scroll.checkbox(value, label);
+                    self.decorate_focus(scroll, &check);
+                }
+                scroll.label("Per-profile game paths (one per line)");
+                let paths_edit = scroll
+                    .add(egui::TextEdit::multiline(&mut draft.game_unique_paths).desired_rows(2));
+                self.decorate_focus(scroll, &paths_edit);
+                show_error(scroll, "game_unique_paths");
+
+                scroll.separator();
+                scroll.horizontal(|actions| {
+                    let save_btn =
+                        actions.add_enabled(errors.is_empty(), egui::Button::new("Save"));
+                    self.decorate_focus(actions, &save_btn);
+                    if save_btn.clicked() {
+                        match draft.save() {
+                            Ok(path) => {
+                                log_info(format!("Saved handler {}", path.display()));
+                                let saved_id = format!("handler:{}", draft.uid);
+                                self.reload_games();
+                                if let Some(index) = self
+                                    .games
+                                    .iter()
+                                    .position(|game| game.persistent_id() == saved_id)
+                                {
+                                    self.selected_game = index;
+                                }
+                                close_editor = true;
+                            }
+                            Err(err) => msg("Error", &format!("Couldn't save handler: {err}")),
+                        }
+                    }
+                    let cancel_btn = actions.button("Cancel");
+                    self.decorate_focus(actions, &cancel_btn);
+                    if cancel_btn.clicked() {
+                        close_editor = true;
+                    }
+                });
+            });
+
+        if close_editor {
+            self.cur_page = if self.games.is_empty() {
+                MenuPage::Home
+            } else {
+                MenuPage::Game
+            };
+            self.pending_content_focus = true;
+        } else {
+            self.handler_editor = Some(draft);
+        }
+    }
+}
@@ -0,0 +1,89 @@
+use super::app::PartyApp;
+use super::launch_options::{env_text, parse_env_text};
+use crate::config::save_cfg;
+
+use eframe::egui::{self, RichText, Ui};
+use std::collections::BTreeMap;
+
+/// Text buffers behind the per-instance "Env" sections of the Instances page.
+#[derive(Default)]
+pub struct InstanceEnvDraft {
+    game_id: String,
+    /// One `KEY=VALUE` per line, per instance slot.
+    texts: Vec<String>,
+    /// Why a slot's text wasn't taken; its last valid variables stay in use.
+    errors: Vec<Option<String>>,
+}
+
+impl PartyApp {
+    /// "Env" expander for instance `index`. Its variables are stored per game
+    /// and slot and set on that instance's command only.
+    pub fn display_instance_env(&mut self, ui: &mut Ui, index: usize) {
+        let game_id = self.games[self.selected_game].persistent_id();
+        if self.instance_env.game_id != game_id {
+            self.instance_env = InstanceEnvDraft {
+                game_id: game_id.clone(),
+                ..Default::default()
+            };
+        }
+        // Slots load their text from the config the first time they're drawn.
+        while self.instance_env.texts.len() <= index {
+            let slot = self.instance_env.texts.len();
+            let text = self.options.instance_env(&game_id, slot).map(env_text);
+            self.instance_env.texts.push(text.unwrap_or_default());
+            self.instance_env.errors.push(None);
+        }
+
+        let count = self.options.instance_env(&game_id, index).map_or(0, BTreeMap::len);
+        let title = match count {
+            0 => "Env".to_string(),
+            count => format!("Env ({count})"),
+        };
+        egui::CollapsingHeader::new(title)
+            .id_salt(format!("instance_env_{index}"))
+            .show(ui, |ui| {
+                let env_edit = ui.add(
+                    egui::TextEdit::multiline(&mut self.instance_env.texts[index])
+                        .hint_text("DXVK_HUD=fps\nMANGOHUD=1")
+                        .desired_rows(2)
+                        .desired_width(360.0),
+                );
+                self.decorate_focus(ui, &env_edit);
+                if env_edit.hovered() {
+                    self.infotext = "Environment variables for this instance only, one KEY=VALUE per line, such as DXVK_HUD=fps or MANGOHUD=1. They're set after the game's Advanced Options, so they win over those.".to_string();
+                }
+                if env_edit.changed() {
+                    match parse_env_text(&self.instance_env.texts[index]) {
+                        Ok(env) => {
+                            self.store_instance_env(&game_id, index, env);
+                            self.instance_env.errors[index] = None;
+                        }
+                        Err(err) => self.instance_env.errors[index] = Some(err),
+                    }
+                }
+                // Written once editing ends rather than on every keystroke.
+                if env_edit.lost_focus() {
+                    let _ = save_cfg(&self.options);
+                }
+                if let Some(err) = &self.instance_env.errors[index] {
+                    ui.label(RichText::new(err).color(ui.visuals().warn_fg_color));
+                }
+            });
+    }
+
+    /// Keeps `env` as the variables of slot `index` of `game_id`.
+    fn store_instance_env(&mut self, game_id: &str, index: usize, env: BTreeMap<String, String>) {
+        let slots = self.options.instance_env.entry(game_id.to_string()).or_default();
+        if slots.len() <= index {
+            slots.resize(index + 1, BTreeMap::new());
+        }
+        slots[index] = env;
+        // Trailing empty slots aren't worth keeping in the config.
+        while slots.last().is_some_and(BTreeMap::is_empty) {
+            slots.pop();
+        }
+        if slots.is_empty() {
+            self.options.instance_env.remove(game_id);
+        }
+    }
+}
@@ -0,0 +1,95 @@
+use super::app::PartyApp;
+use crate::input::*;
+use crate::util::msg;
+
+use eframe::egui::{self, Ui};
+
+impl PartyApp {
+    fn start_keyboard_split(&mut self, path: &str) {
+        let first_keys = match parse_key_ranges(&self.options.keyboard_split_keys) {
+            Ok(keys) => keys,
+            Err(err) => {
+                msg("Keyboard Split Error", &format!("Check the split keys in Settings: {err}"));
+                return;
+            }
+        };
+        match spawn_keyboard_splitter(path, first_keys) {
+            Ok(splitter) => {
+                self.keyboard_splitter = Some(splitter);
+                self.sync_input_devices();
+            }
+            Err(err) => msg(
+                "Keyboard Split Error",
+                &format!("Couldn't split {path}: {err}\n\nSplitting needs access to /dev/uinput."),
+            ),
+        }
+    }
+
+    /// Lets two players share one keyboard. The picked keyboard is replaced in
+    /// the device list by two virtual keyboards that are assigned like any
+    /// other device.
+    pub fn display_keyboard_split(&mut self, ui: &mut Ui) {
+        if !self.options.kbm_support {
+            return;
+        }
+
+        if let Some(splitter) = &self.keyboard_splitter {
+            let physical = splitter.physical_path.clone();
+            ui.horizontal(|row| {
+                row.label(format!("🖮 {physical} is split into Split Happens Keyboard 1 and 2"));
+                let undo_btn = row.button("Undo Split");
+                self.decorate_focus(row, &undo_btn);
+                if undo_btn.clicked() {
+                    self.keyboard_splitter = None;
+                    self.sync_input_devices();
+                }
+                if undo_btn.hovered() {
+                    self.infotext = "Removes the two virtual keyboards and gives the physical keyboard back. Players using a half lose it from their instance.".to_string();
+                }
+            });
+            return;
+        }
+
+        let keyboards: Vec<(String, String)> = self
+            .input_devices
+            .iter()
+            .filter(|dev| dev.enabled() && dev.device_type() == DeviceType::Keyboard)
+            .map(|dev| (dev.path().to_string(), dev.fancyname().to_string()))
+            .collect();
+        if keyboards.is_empty() {
+            return;
+        }
+        if self.split_keyboard_choice >= keyboards.len() {
+            self.split_keyboard_choice = 0;
+        }
+
+        let mut split_path: Option<String> = None;
+        ui.horizontal(|row| {
+            let split_btn = row.button("Split Keyboard");
+            self.decorate_focus(row, &split_btn);
+            if split_btn.clicked() {
+                split_path = Some(keyboards[self.split_keyboard_choice].0.clone());
+            }
+            let (_, selected_name) = &keyboards[self.split_keyboard_choice];
+            let combo = egui::ComboBox::from_id_salt("instances_split_keyboard")
+                .selected_text(selected_name.as_str())
+                .show_ui(row, |combo| {
+                    for (i, (path, name)) in keyboards.iter().enumerate() {
+                        combo.selectable_value(
+                            &mut self.split_keyboard_choice,
+                            i,
+                            format!("{name} ({path})"),
+                        );
+                    }
+                });
+            self.decorate_focus(row, &combo.response);
+            if split_btn.hovered() || combo.response.hovered() {
+                self.infotext = "Lets two players share one keyboard: the picked keyboard becomes Split Happens Keyboard 1 and 2. By default the left side of the keyboard and space go to keyboard 1, everything else (arrows, numpad, right side) to keyboard 2; change this under Settings. Needs access to /dev/uinput.".to_string();
+            }
+        });
+
+        if let Some(path) = split_path {
+            self.start_keyboard_split(&path);
+        }
+    }
+}
@@ -0,0 +1,306 @@
+use super::app::PartyApp;
+use crate::config::{PartyConfig, save_cfg};
+use crate::game::Game::{self, ExecRef, HandlerRef};
+use crate::logging::session_log_file;
+use crate::util::*;
+
+use eframe::egui::{self, RichText, Ui};
+use std::collections::BTreeMap;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Text buffers behind the game page's "Advanced Options" section for one
+/// handler. Parsed into [`LaunchOverrides`] when saved.
+#[derive(Default)]
+pub struct LaunchOptionsDraft {
+    uid: String,
+    args: String,
+    replace_args: bool,
+    /// One `KEY=VALUE` per line.
+    env: String,
+    /// Comma-separated DLL names.
+    dlls: String,
+    error: Option<String>,
+}
+
+impl LaunchOptionsDraft {
+    fn load(uid: &str) -> LaunchOptionsDraft {
+        let overrides = load_launch_overrides(uid);
+        LaunchOptionsDraft {
+            uid: uid.to_string(),
+            args: overrides.args,
+            replace_args: overrides.replace_args,
+            env: env_text(&overrides.env),
+            dlls: overrides.dll_overrides.join(", "),
+            error: None,
+        }
+    }
+
+    fn parse(&self) -> Result<LaunchOverrides, String> {
+        Ok(LaunchOverrides {
+            args: self.args.trim().to_string(),
+            replace_args: self.replace_args,
+            env: parse_env_text(&self.env)?,
+            dll_overrides: self
+                .dlls
+                .split(',')
+                .map(str::trim)
+                .filter(|dll| !dll.is_empty())
+                .map(str::to_string)
+                .collect(),
+        })
+    }
+}
+
+/// Environment variables as one `KEY=VALUE` per line.
+pub(super) fn env_text(env: &BTreeMap<String, String>) -> String {
+    env.iter()
+        .map(|(key, value)| format!("{key}={value}"))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Parses one `KEY=VALUE` per line, skipping blank lines.
+pub(super) fn parse_env_text(text: &str) -> Result<BTreeMap<String, String>, String> {
+    let mut env = BTreeMap::new();
+    for line in text.lines().map(str::trim).filter(|line| !line.is_empty()) {
+        match line.split_once('=') {
+            Some((key, value)) if !key.trim().is_empty() => {
+                env.insert(key.trim().to_string(), value.to_string());
+            }
+            _ => return Err(format!("\"{line}\" is not in KEY=VALUE form")),
+        }
+    }
+    Ok(env)
+}
+
+/// Key the launcher records history and log sessions under: the handler uid,
+/// or the file name for plain executables.
+pub(super) fn launch_history_id(game: &Game) -> String {
+    match game {
+        ExecRef(e) => e.filename().to_string(),
+        HandlerRef(h) => h.uid.clone(),
+    }
+}
+
+/// How long ago `timestamp` was, e.g. `3h ago`.
+pub(super) fn launch_age(timestamp: u64) -> String {
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |elapsed| elapsed.as_secs());
+    match now.saturating_sub(timestamp) {
+        secs if secs < 60 => "just now".to_string(),
+        secs if secs < 3600 => format!("{}m ago", secs / 60),
+        secs if secs < 86400 => format!("{}h ago", secs / 3600),
+        secs => format!("{}d ago", secs / 86400),
+    }
+}
+
+/// `options` with the launch settings of `record` written over it.
+fn apply_launch_settings(options: &PartyConfig, record: &LaunchRecord) -> Option<PartyConfig> {
+    let mut value = serde_json::to_value(options).ok()?;
+    let fields = value.as_object_mut()?;
+    for (key, setting) in &record.settings {
+        fields.insert(key.clone(), setting.clone());
+    }
+    serde_json::from_value(value).ok()
+}
+
+impl PartyApp {
+    /// Puts the settings and overrides of `record` back in place for `game`.
+    fn restore_launch_configuration(&mut self, game: &Game, record: &LaunchRecord) {
+        if let Some(options) = apply_launch_settings(&self.options, record) {
+            self.options = options;
+            let _ = save_cfg(&self.options);
+        }
+        if let HandlerRef(h) = game {
+            let _ = save_launch_overrides(&h.uid, &record.overrides);
+            self.launch_options = None;
+        }
+    }
+
+    /// Called once a session ends. When its launch failed with a settings
+    /// combination that differs from the last one that worked, offers to go
+    /// back to the working one.
+    pub fn offer_launch_rollback(&mut self, game: &Game) {
+        let history = load_launch_history(&launch_history_id(game));
+        let Some(latest) = history.last() else {
+            return;
+        };
+        if latest.succeeded {
+            return;
+        }
+        let Some(good) = last_known_good(&history) else {
+            return;
+        };
+        if good.same_configuration(latest) {
+            return;
+        }
+        if yesno(
+            "Launch failed",
+            &format!(
+                "{} crashed shortly after launching.\n\nRestore the configuration that last worked ({})?",
+                game.name(),
+                launch_age(good.timestamp)
+            ),
+        ) {
+            let good = good.clone();
+            self.restore_launch_configuration(game, &good);
+        }
+    }
+
+    /// Recent launches of the selected game with the gamescope/bwrap command
+    /// lines they used, and a way back to the last configuration that worked.
+    pub fn display_launch_history(&mut self, ui: &mut Ui) {
+        let game = self.games[self.selected_game].to_owned();
+        let game_id = launch_history_id(&game);
+        let history = match self.launch_history.take() {
+            Some((id, history)) if id == game_id => history,
+            _ => load_launch_history(&game_id),
+        };
+        if history.is_empty() {
+            self.launch_history = Some((game_id, history));
+            return;
+        }
+
+        let mut open_logs = None;
+        egui::CollapsingHeader::new("Launch History")
+            .id_salt("game_launch_history")
+            .show(ui, |ui| {
+                if let Some(good) = last_known_good(&history) {
+                    let restore_btn = ui.button("Use last known good configuration");
+                    self.decorate_focus(ui, &restore_btn);
+                    if restore_btn.clicked() {
+                        let good = good.clone();
+                        self.restore_launch_configuration(&game, &good);
+                    }
+                    if restore_btn.hovered() {
+                        self.infotext = format!(
+                            "Restores the gamescope, Proton and performance settings{} used by the last launch that ran for over a minute ({}).",
+                            if let HandlerRef(_) = &game { " and advanced options" } else { "" },
+                            launch_age(good.timestamp)
+                        );
+                    }
+                }
+                for (index, record) in history.iter().enumerate().rev() {
+                    let (mark, color) = if record.succeeded {
+                        ("✔", egui::Color32::LIGHT_GREEN)
+                    } else {
+                        ("✖", ui.visuals().error_fg_color)
+                    };
+                    egui::CollapsingHeader::new(
+                        RichText::new(format!("{mark} {}", launch_age(record.timestamp)))
+                            .color(color),
+                    )
+                    .id_salt(("game_launch_record", index))
+                    .show(ui, |ui| {
+                        // Old sessions are pruned, so only link logs that still exist.
+                        if !record.log_session.is_empty()
+                            && session_log_file(&record.log_session, "").exists()
+                        {
+                            let logs_btn = ui.button("View Logs");
+                            self.decorate_focus(ui, &logs_btn);
+                            if logs_btn.clicked() {
+                                open_logs = Some(record.log_session.clone());
+                            }
+                            if logs_btn.hovered() {
+                                self.infotext = "Opens the launcher log and every player's full game output of this launch in the log viewer.".to_string();
+                            }
+                        }
+                        for (key, value) in &record.settings {
+                            ui.label(format!("{key}: {value}"));
+                        }
+                        for command in &record.commands {
+                            ui.label(RichText::new(command).monospace().small());
+                        }
+                    });
+                }
+            });
+
+        self.launch_history = Some((game_id, history));
+        if let Some(session) = open_logs {
+            self.open_log_viewer_at(&session);
+        }
+    }
+
+    /// Per-game argument, environment and DLL overrides, stored next to the
+    /// settings instead of in handler.json.
+    pub fn display_launch_options(&mut self, ui: &mut Ui) {
+        let HandlerRef(h) = &self.games[self.selected_game] else {
+            return;
+        };
+        let uid = h.uid.clone();
+        let mut draft = match self.launch_options.take() {
+            Some(draft) if draft.uid == uid => draft,
+            _ => LaunchOptionsDraft::load(&uid),
+        };
+
+        egui::CollapsingHeader::new("Advanced Options")
+            .id_salt("game_launch_options")
+            .show(ui, |ui| {
+                ui.label("Arguments");
+                let args_edit = ui.add(
+                    egui::TextEdit::singleline(&mut draft.args)
+                        .hint_text("-windowed $WIDTHXHEIGHT")
+                        .desired_width(360.0),
+                );
+                self.decorate_focus(ui, &args_edit);
+                if args_edit.hovered() {
+                    self.infotext = "Extra arguments passed to every instance, added after the handler's own. $GAMEDIR, $PROFILE, $WIDTH, $HEIGHT and $WIDTHXHEIGHT are expanded.".to_string();
+                }
+                let replace_check =
+                    ui.checkbox(&mut draft.replace_args, "Replace the handler's arguments");
+                self.decorate_focus(ui, &replace_check);
+
+                ui.label("Environment variables (KEY=VALUE, one per line)");
+                let env_edit = ui.add(
+                    egui::TextEdit::multiline(&mut draft.env)
+                        .desired_rows(3)
+                        .desired_width(360.0),
+                );
+                self.decorate_focus(ui, &env_edit);
+                if env_edit.hovered() {
+                    self.infotext = "Set on every instance after Split Happens' own variables, so they take precedence. Per-instance paths such as WINEPREFIX are still managed by the launcher.".to_string();
+                }
+
+                ui.label("DLL overrides (comma separated)");
+                let dll_edit = ui.add(
+                    egui::TextEdit::singleline(&mut draft.dlls)
+                        .hint_text("dinput8, winmm")
+                        .desired_width(360.0),
+                );
+                self.decorate_focus(ui, &dll_edit);
+                if dll_edit.hovered() {
+                    self.infotext = "Extra DLLs Proton loads native-first (n,b), added to the handler's list. Only used for Windows games.".to_string();
+                }
+
+                ui.horizontal(|row| {
+                    let save_btn = row.button("Save");
+                    self.decorate_focus(row, &save_btn);
+                    if save_btn.clicked() {
+                        draft.error = match draft.parse() {
+                            Ok(overrides) => save_launch_overrides(&uid, &overrides)
+                                .err()
+                                .map(|err| err.to_string()),
+                            Err(err) => Some(err),
+                        };
+                    }
+                    let clear_btn = row.button("Clear");
+                    self.decorate_focus(row, &clear_btn);
+                    if clear_btn.clicked() {
+                        draft = LaunchOptionsDraft {
+                            uid: uid.clone(),
+                            error: save_launch_overrides(&uid, &LaunchOverrides::default())
+                                .err()
+                                .map(|err| err.to_string()),
+                            ..Default::default()
+                        };
+                    }
+                });
+                if let Some(err) = &draft.error {
+                    ui.label(RichText::new(err).color(ui.visuals().error_fg_color));
+                }
+            });
+
+        self.launch_options = Some(draft);
+    }
+}
@@ -0,0 +1,163 @@
+use super::app::PartyApp;
+use crate::config::save_cfg;
+use crate::layout::{LayoutRegion, player_regions, preset_regions, session_screen};
+
+use eframe::egui::{self, Color32, Pos2, Rect, Sense, Stroke, StrokeKind, Ui, Vec2};
+use std::sync::OnceLock;
+
+/// Width of the layout preview in points.
+const PREVIEW_WIDTH: f32 = 320.0;
+/// Regions snap to this fraction of the screen when a drag ends.
+const SNAP_STEP: f32 = 1.0 / 24.0;
+/// Smallest region side, as a fraction of the screen.
+const MIN_REGION: f32 = 0.1;
+
+/// Screen size, queried once since the preview redraws every frame.
+fn screen_size() -> (u32, u32) {
+    static SCREEN: OnceLock<(u32, u32)> = OnceLock::new();
+    *SCREEN.get_or_init(session_screen)
+}
+
+fn snap(value: f32) -> f32 {
+    (value / SNAP_STEP).round() * SNAP_STEP
+}
+
+/// Keeps a region start on screen while leaving room for `extent`.
+fn fit_offset(start: f32, extent: f32) -> f32 {
+    start.min(1.0 - extent).max(0.0)
+}
+
+/// Keeps a region side at least [`MIN_REGION`] without running off screen.
+fn fit_extent(extent: f32, start: f32) -> f32 {
+    extent.max(MIN_REGION).min(1.0 - start)
+}
+
+fn player_color(slot: usize, count: usize) -> Color32 {
+    egui::ecolor::Hsva::new(slot as f32 / count.max(1) as f32, 0.55, 0.75, 1.0).into()
+}
+
+impl PartyApp {
+    /// Preview of how the current instances will share the screen. With custom
+    /// regions enabled, each player's tile can be dragged and resized by its
+    /// corner; the result is saved per player count.
+    pub fn display_layout_editor(&mut self, ui: &mut Ui) {
+        let count = self.instances.len();
+        let key = count.to_string();
+
+        egui::CollapsingHeader::new(format!("Screen layout ({count} players)"))
+            .id_salt("instances_layout_editor")
+            .show(ui, |ui| {
+                let mut custom = self.options.custom_layouts.contains_key(&key);
+                let custom_check = ui.checkbox(&mut custom, "Custom regions");
+                self.decorate_focus(ui, &custom_check);
+                if custom_check.changed() {
+                    if custom {
+                        let regions = preset_regions(count, &self.options, screen_size());
+                        self.options.custom_layouts.insert(key.clone(), regions);
+                    } else {
+                        self.options.custom_layouts.remove(&key);
+                    }
+                    let _ = save_cfg(&self.options);
+                }
+                if custom_check.hovered() {
+                    self.infotext = "Draw your own screen regions for this number of players. Drag a tile to move it and its bottom-right corner to resize it. Used by both the KWin script and the native layout.".to_string();
+                }
+
+                let mut regions = player_regions(count, &self.options, screen_size());
+                let (width, height) = screen_size();
+                let size = Vec2::new(PREVIEW_WIDTH, PREVIEW_WIDTH * height as f32 / width as f32);
+                let (canvas, painter) = ui.allocate_painter(size, Sense::hover());
+                let canvas = canvas.rect;
+                painter.rect_filled(canvas, 4.0, ui.visuals().extreme_bg_color);
+
+                let to_screen = |region: &LayoutRegion| {
+                    Rect::from_min_size(
+                        canvas.min + Vec2::new(region.x, region.y) * canvas.size(),
+                        Vec2::new(region.width, region.height) * canvas.size(),
+                    )
+                };
+
+                let mut edited = false;
+                let mut released = false;
+                for (slot, region) in regions.iter_mut().enumerate() {
+                    let tile = to_screen(region);
+                    if custom {
+                        let id = ui.id().with(("layout_region", slot));
+                        let grip = Rect::from_min_max(tile.max - Vec2::splat(12.0), tile.max);
+                        // The grip is registered last so it wins over the tile it sits on.
+                        let body = ui.interact(tile, id, Sense::drag());
+                        let resize = ui.interact(grip, id.with("resize"), Sense::drag());
+                        let delta = if resize.dragged() {
+                            resize.drag_delta()
+                        } else if body.dragged() {
+                            body.drag_delta()
+                        } else {
+                            Vec2::ZERO
+                        };
+                        let delta = delta / canvas.size();
+                        if resize.dragged() {
+                            region.width = fit_extent(region.width + delta.x, region.x);
+                            region.height = fit_extent(region.height + delta.y, region.y);
+                            edited = true;
+                        } else if body.dragged() {
+                            region.x = fit_offset(region.x + delta.x, region.width);
+                            region.y = fit_offset(region.y + delta.y, region.height);
+                            edited = true;
+                        }
+                        released |= resize.drag_stopped() || body.drag_stopped();
+                    }
+
+                    let tile = to_screen(region);
+                    let color = player_color(slot, count);
+                    painter.rect_filled(tile.shrink(1.0), 3.0, color.gamma_multiply(0.35));
+                    painter.rect_stroke(
+                        tile.shrink(1.0),
+                        3.0,
+                        Stroke::new(1.5, color),
+                        StrokeKind::Inside,
+                    );
+                    painter.text(
+                        tile.center(),
+                        egui::Align2::CENTER_CENTER,
+                        format!("P{}", slot + 1),
+                        egui::FontId::proportional(16.0),
+                        ui.visuals().strong_text_color(),
+                    );
+                    if custom {
+                        painter.line_segment(
+                            [
+                                Pos2::new(tile.max.x - 10.0, tile.max.y - 3.0),
+                                Pos2::new(tile.max.x - 3.0, tile.max.y - 10.0),
+                            ],
+                            Stroke::new(1.5, color),
+                        );
+                    }
+                }
+
+                if released {
+                    for region in regions.iter_mut() {
+                        region.x = fit_offset(snap(region.x), MIN_REGION);
+                        region.y = fit_offset(snap(region.y), MIN_REGION);
+                        region.width = fit_extent(snap(region.width), region.x);
+                        region.height = fit_extent(snap(region.height), region.y);
+                    }
+                }
+                if edited || released {
+                    self.options.custom_layouts.insert(key.clone(), regions);
+                }
+                if released {
+                    let _ = save_cfg(&self.options);
+                }
+
+                if custom {
+                    let reset_btn = ui.button("Reset to preset");
+                    self.decorate_focus(ui, &reset_btn);
+                    if reset_btn.clicked() {
+                        let regions = preset_regions(count, &self.options, screen_size());
+                        self.options.custom_layouts.insert(key.clone(), regions);
+                        let _ = save_cfg(&self.options);
+                    }
+                }
+            });
+    }
+}
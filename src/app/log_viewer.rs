@@ -0,0 +1,361 @@
+use super::app::{MenuPage, PartyApp};
+use super::quick_actions::open_folder;
+use crate::logging::{
+    LogSession, SessionEvent, SessionEventKind, is_highlighted_line, list_log_sessions,
+    load_session_events, log_line_stamp, session_log_file,
+};
+
+use eframe::egui::{self, RichText, Ui};
+use std::path::PathBuf;
+
+/// Lines shown from the end of a log; older lines are skipped so huge game
+/// logs don't stall the UI.
+const MAX_LOG_LINES: usize = 5000;
+
+/// State of the "View Logs" page.
+#[derive(Default)]
+pub struct LogViewer {
+    sessions: Vec<LogSession>,
+    /// Log file open in the viewer and its last [`MAX_LOG_LINES`] lines.
+    open: Option<(PathBuf, Vec<String>)>,
+    filter: String,
+    warnings_only: bool,
+    /// Session whose timeline is shown instead of a log, with its events.
+    timeline: Option<(String, Vec<SessionEvent>)>,
+    /// Screenshots shown instead of a log.
+    gallery: Option<Vec<PathBuf>>,
+    /// Line of the open log to scroll to once, e.g. when jumping from an event.
+    jump_to: Option<usize>,
+}
+
+impl LogViewer {
+    fn refresh(&mut self) {
+        self.sessions = list_log_sessions();
+        if let Some((path, _)) = self.open.take() {
+            self.open_file(path);
+        }
+    }
+
+    fn open_file(&mut self, path: PathBuf) {
+        let content = std::fs::read_to_string(&path).unwrap_or_default();
+        let lines: Vec<String> = content.lines().map(str::to_string).collect();
+        let skip = lines.len().saturating_sub(MAX_LOG_LINES);
+        self.open = Some((path, lines.into_iter().skip(skip).collect()));
+        self.timeline = None;
+        self.gallery = None;
+        self.jump_to = None;
+    }
+
+    /// Opens the log covering `event` at the first line written at or after it.
+    fn open_event(&mut self, session: &str, event: &SessionEvent) {
+        self.open_file(session_log_file(session, &event.profile));
+        let stamp = log_line_stamp(event.timestamp_ms);
+        if let Some((_, lines)) = &self.open {
+            self.jump_to = lines
+                .iter()
+                .position(|line| line.get(..stamp.len()).is_some_and(|head| head >= &stamp[..]));
+        }
+        // The filter could hide the line being jumped to.
+        self.filter.clear();
+        self.warnings_only = false;
+    }
+}
+
+/// Marker color of an event kind on the timeline.
+fn event_color(kind: SessionEventKind, visuals: &egui::Visuals) -> egui::Color32 {
+    match kind {
+        SessionEventKind::Crash => visuals.error_fg_color,
+        SessionEventKind::Restart | SessionEventKind::Swap | SessionEventKind::Disconnect => {
+            visuals.warn_fg_color
+        }
+        SessionEventKind::Launch | SessionEventKind::Join | SessionEventKind::Reconnect => {
+            egui::Color32::LIGHT_GREEN
+        }
+        SessionEventKind::Leave | SessionEventKind::End => visuals.weak_text_color(),
+    }
+}
+
+/// `+m:ss` offset of `timestamp_ms` from the session start.
+fn event_offset(start_ms: u64, timestamp_ms: u64) -> String {
+    let secs = timestamp_ms.saturating_sub(start_ms) / 1000;
+    format!("+{}:{:02}", secs / 60, secs % 60)
+}
+
+/// Whether `line` looks like a warning or error, from the launcher or a game,
+/// or was highlighted by an output rule.
+fn is_problem_line(line: &str) -> bool {
+    if is_highlighted_line(line) {
+        return true;
+    }
+    let lower = line.to_lowercase();
+    ["warn", "error", "fail", "fatal", "err:"]
+        .iter()
+        .any(|needle| lower.contains(needle))
+}
+
+impl PartyApp {
+    /// Opens the log viewer with the current list of sessions.
+    pub fn open_log_viewer(&mut self) {
+        let mut viewer = self.log_viewer.take().unwrap_or_default();
+        viewer.refresh();
+        self.log_viewer = Some(viewer);
+        self.cur_page = MenuPage::Logs;
+    }
+
+    /// Opens the log viewer on the launcher log of the session folder
+    /// `session`.
+    pub fn open_log_viewer_at(&mut self, session: &str) {
+        self.open_log_viewer();
+        if let Some(viewer) = self.log_viewer.as_mut() {
+            viewer.open_file(session_log_file(session, ""));
+        }
+    }
+
+    /// Browses the per-session logs written by the launcher: its own messages
+    /// plus one file per player with that instance's output.
+    pub fn display_page_logs(&mut self, ui: &mut Ui) {
+        let Some(mut viewer) = self.log_viewer.take() else {
+            self.cur_page = MenuPage::Home;
+            return;
+        };
+
+        ui.horizontal(|row| {
+            row.heading("Logs");
+            let refresh_btn = row.button("⟳");
+            self.decorate_focus(row, &refresh_btn);
+            if refresh_btn.clicked() {
+                viewer.refresh();
+            }
+            let filter_edit =
+                row.add(egui::TextEdit::singleline(&mut viewer.filter).hint_text("Filter lines"));
+            self.decorate_focus(row, &filter_edit);
+            let warnings_check = row.checkbox(&mut viewer.warnings_only, "Warnings only");
+            self.decorate_focus(row, &warnings_check);
+        });
+        ui.separator();
+
+        if viewer.sessions.is_empty() {
+            ui.label("No sessions have been logged yet.");
+        }
+
+        let mut open_request: Option<PathBuf> = None;
+        let mut timeline_request: Option<String> = None;
+        let mut gallery_request: Option<Vec<PathBuf>> = None;
+        let mut event_request: Option<(String, SessionEvent)> = None;
+        ui.horizontal_top(|row| {
+            egui::ScrollArea::vertical()
+                .id_salt("log_sessions")
+                .max_width(260.0)
+                .auto_shrink([false, false])
+                .show(row, |list| {
+                    for (index, session) in viewer.sessions.iter().enumerate() {
+                        egui::CollapsingHeader::new(&session.name)
+                            .id_salt(("log_session", &session.name))
+                            .default_open(index == 0)
+                            .show(list, |list| {
+                                if session.has_events {
+                                    let selected = viewer
+                                        .timeline
+                                        .as_ref()
+                                        .is_some_and(|(name, _)| *name == session.name);
+                                    let timeline_btn = list.selectable_label(selected, "Timeline");
+                                    self.decorate_focus(list, &timeline_btn);
+                                    if timeline_btn.clicked() {
+                                        timeline_request = Some(session.name.clone());
+                                    }
+                                }
+                                if !session.screenshots.is_empty() {
+                                    let selected = viewer
+                                        .gallery
+                                        .as_ref()
+                                        .is_some_and(|shots| *shots == session.screenshots);
+                                    let gallery_btn = list.selectable_label(
+                                        selected,
+                                        format!("Screenshots ({})", session.screenshots.len()),
+                                    );
+                                    self.decorate_focus(list, &gallery_btn);
+                                    if gallery_btn.clicked() {
+                                        gallery_request = Some(session.screenshots.clone());
+                                    }
+                                }
+                                for file in &session.files {
+                                    let name = file
+                                        .file_stem()
+                                        .map(|stem| stem.to_string_lossy().to_string())
+                                        .unwrap_or_default();
+                                    let selected =
+                                        viewer.open.as_ref().is_some_and(|(open, _)| open == file);
+                                    let file_btn = list.selectable_label(selected, name);
+                                    self.decorate_focus(list, &file_btn);
+                                    if file_btn.clicked() {
+                                        open_request = Some(file.clone());
+                                    }
+                                }
+                            });
+                    }
+                });
+
+            row.separator();
+
+            egui::ScrollArea::both()
+                .id_salt("log_content")
+                .auto_shrink([false, false])
+                .stick_to_bottom(viewer.timeline.is_none() && viewer.jump_to.is_none())
+                .show(row, |content| {
+                    if let Some((session, events)) = &viewer.timeline {
+                        if let Some(event) = self.display_timeline(content, events) {
+                            event_request = Some((session.clone(), event));
+                        }
+                        return;
+                    }
+                    if let Some(shots) = &viewer.gallery {
+                        self.display_gallery(content, shots);
+                        return;
+                    }
+                    let Some((_, lines)) = &viewer.open else {
+                        content.label("Pick a log on the left.");
+                        return;
+                    };
+                    let filter = viewer.filter.to_lowercase();
+                    let jump_to = viewer.jump_to.take();
+                    for (index, line) in lines.iter().enumerate().filter(|(_, line)| {
+                        (!viewer.warnings_only || is_problem_line(line))
+                            && (filter.is_empty() || line.to_lowercase().contains(&filter))
+                    }) {
+                        let mut text = RichText::new(line).monospace();
+                        if is_highlighted_line(line) {
+                            text = text.color(content.visuals().error_fg_color);
+                        } else if is_problem_line(line) {
+                            text = text.color(content.visuals().warn_fg_color);
+                        }
+                        if jump_to == Some(index) {
+                            let jumped = content.label(text.background_color(
+                                content.visuals().selection.bg_fill.gamma_multiply(0.5),
+                            ));
+                            jumped.scroll_to_me(Some(egui::Align::TOP));
+                        } else {
+                            content.label(text);
+                        }
+                    }
+                });
+        });
+
+        if let Some(path) = open_request {
+            viewer.open_file(path);
+        }
+        if let Some(session) = timeline_request {
+            let events = load_session_events(&session);
+            viewer.timeline = Some((session, events));
+            viewer.gallery = None;
+        }
+        if let Some(shots) = gallery_request {
+            viewer.gallery = Some(shots);
+            viewer.timeline = None;
+        }
+        if let Some((session, event)) = event_request {
+            viewer.open_event(&session, &event);
+        }
+        self.log_viewer = Some(viewer);
+    }
+
+    /// Thumbnails of a session's screenshots, named after the profile that
+    /// took each one.
+    fn display_gallery(&mut self, ui: &mut Ui, shots: &[PathBuf]) {
+        if let Some(folder) = shots.first().and_then(|shot| shot.parent()) {
+            let open_btn = ui.button("Open Folder");
+            self.decorate_focus(ui, &open_btn);
+            if open_btn.clicked() {
+                open_folder(folder, "screenshot");
+            }
+        }
+        ui.horizontal_wrapped(|row| {
+            for shot in shots {
+                row.vertical(|tile| {
+                    tile.add(
+                        egui::Image::new(format!("file://{}", shot.display()))
+                            .max_width(240.0)
+                            .corner_radius(egui::CornerRadius::same(4)),
+                    );
+                    let name = shot
+                        .file_stem()
+                        .map(|stem| stem.to_string_lossy().to_string())
+                        .unwrap_or_default();
+                    tile.label(RichText::new(name).small());
+                });
+            }
+        });
+    }
+
+    /// Draws a session's events along a time bar and as a list. Returns the
+    /// event whose log was asked for.
+    fn display_timeline(&mut self, ui: &mut Ui, events: &[SessionEvent]) -> Option<SessionEvent> {
+        let (Some(first), Some(last)) = (events.first(), events.last()) else {
+            ui.label("This session recorded no events.");
+            return None;
+        };
+        let start_ms = first.timestamp_ms;
+        let span_ms = last.timestamp_ms.saturating_sub(start_ms).max(1) as f32;
+
+        // One marker per event, placed by time; hovering names the event.
+        let width = ui.available_width().max(200.0);
+        let (rect, _) = ui.allocate_exact_size(egui::vec2(width, 36.0), egui::Sense::hover());
+        let bar = rect.shrink2(egui::vec2(10.0, 0.0));
+        let painter = ui.painter_at(rect);
+        painter.line_segment(
+            [bar.left_center(), bar.right_center()],
+            ui.visuals().widgets.noninteractive.bg_stroke,
+        );
+        for (index, event) in events.iter().enumerate() {
+            let offset_ms = event.timestamp_ms.saturating_sub(start_ms) as f32;
+            // Clock steps can put an event before the first or after the last.
+            let fraction = (offset_ms / span_ms).min(1.0);
+            let center = egui::pos2(bar.left() + bar.width() * fraction, bar.center().y);
+            painter.circle_filled(center, 6.0, event_color(event.kind, ui.visuals()));
+            let marker = ui.interact(
+                egui::Rect::from_center_size(center, egui::vec2(12.0, 12.0)),
+                ui.id().with(("timeline_marker", index)),
+                egui::Sense::hover(),
+            );
+            marker.on_hover_text(format!(
+                "{} {}",
+                event_offset(start_ms, event.timestamp_ms),
+                event.kind.label()
+            ));
+        }
+        ui.add_space(8.0);
+
+        let mut requested = None;
+        egui::Grid::new("session_timeline")
+            .num_columns(5)
+            .spacing([16.0, 4.0])
+            .show(ui, |grid| {
+                for event in events {
+                    grid.label(
+                        RichText::new(event_offset(start_ms, event.timestamp_ms)).monospace(),
+                    );
+                    grid.label(
+                        RichText::new(event.kind.label())
+                            .color(event_color(event.kind, grid.visuals()))
+                            .strong(),
+                    );
+                    grid.label(match event.slot {
+                        Some(slot) => format!("P{} {}", slot + 1, event.profile),
+                        None => String::new(),
+                    });
+                    grid.label(RichText::new(&event.detail).weak());
+                    let log_btn = grid.button("Log");
+                    self.decorate_focus(grid, &log_btn);
+                    if log_btn.clicked() {
+                        requested = Some(event.clone());
+                    }
+                    log_btn.on_hover_text(if event.profile.is_empty() {
+                        "Opens the launcher log at this moment."
+                    } else {
+                        "Opens this player's log at this moment."
+                    });
+                    grid.end_row();
+                }
+            });
+        requested
+    }
+}
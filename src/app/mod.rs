@@ -1,16 +1,37 @@
 mod app;
 mod app_light;
+mod artwork;
+mod calibration_wizard;
 mod character_creator;
-mod config;
+mod cloud_export;
+mod data_migration;
+mod guest_handoff;
 mod gui_pages;
 mod gui_panels;
+mod handler_browser;
+mod handler_editor;
+mod instance_env;
+mod keyboard_split;
+mod launch_options;
+mod layout_editor;
+mod log_viewer;
+mod presets;
+mod prompts;
+mod quick_actions;
+mod ready_check;
+mod remap;
+mod resource_manager;
+mod session_panel;
+mod settings_widgets;
+mod storage;
 mod theme;
+mod wizard;
 
 pub use app::PartyApp;
 pub use app_light::LightPartyApp;
+// Desktop dialogs for the prompts the core asks for.
+pub use prompts::DesktopPrompter;
 // Re-export the character creator atlas helpers so the UI and tooling layers
 // can fetch the sprite metadata without depending on this module directly.
 pub use character_creator::{male_body_sprite_map, SpriteSlice, MALE_BODY_SPRITES};
-pub use config::PadFilterType;
-pub use config::PartyConfig;
 pub use theme::apply_split_happens_theme;
@@ -0,0 +1,198 @@
+use super::app::PartyApp;
+use crate::instance::*;
+use crate::util::*;
+
+use dialog::DialogBox;
+use eframe::egui::{RichText, Ui};
+
+impl PartyApp {
+    /// Snapshot of the selected game and its current instances.
+    fn snapshot_preset(&self, name: &str) -> SessionPreset {
+        let game = &self.games[self.selected_game];
+        let mut instances = self.instances.clone();
+        set_instance_resolutions(&mut instances, &self.options);
+        let slots = instances
+            .iter()
+            .map(|instance| PresetSlot {
+                profile: self
+                    .profiles
+                    .get(instance.profselection)
+                    .cloned()
+                    .unwrap_or_else(|| "Guest".to_string()),
+                devices: instance
+                    .devices
+                    .iter()
+                    .filter_map(|&dev| self.input_devices.get(dev))
+                    .map(|dev| dev.identity().to_string())
+                    .collect(),
+                monitor: instance.monitor.clone(),
+                width: instance.width,
+                height: instance.height,
+                platform: instance.platform,
+                motion_passthrough: instance.motion_passthrough,
+            })
+            .collect();
+        SessionPreset {
+            name: name.to_string(),
+            game: game.persistent_id(),
+            game_name: game.name().to_string(),
+            slots,
+        }
+    }
+
+    /// Asks for a name and saves the current setup as a preset.
+    pub fn save_current_preset(&mut self) {
+        let Ok(Some(name)) = dialog::Input::new("Preset name:").title("Save Preset").show() else {
+            return;
+        };
+        let name = name.trim();
+        if name.is_empty() {
+            return;
+        }
+        let exists = list_presets().iter().any(|preset| preset.name == name);
+        if exists && !yesno("Save Preset", &format!("Replace the preset {name}?")) {
+            return;
+        }
+        if let Err(err) = save_preset(&self.snapshot_preset(name)) {
+            msg("Error", &format!("Couldn't save preset: {err}"));
+        }
+        self.presets = None;
+    }
+
+    /// Rebuilds the instances saved in `preset` and launches them. Players
+    /// whose devices aren't connected are left out after asking.
+    pub fn launch_preset(&mut self, preset: &SessionPreset) {
+        let Some(index) = self
+            .games
+            .iter()
+            .position(|game| game.persistent_id() == preset.game)
+        else {
+            msg("Preset", &format!("{} isn't in the library anymore.", preset.game_name));
+            return;
+        };
+
+        self.sync_input_devices();
+        self.profiles = scan_profiles(true);
+        let saved: Vec<Vec<String>> =
+            preset.slots.iter().map(|slot| slot.devices.clone()).collect();
+        let matched = match_device_slots(&self.input_devices, &saved);
+        if matched.iter().all(Vec::is_empty) {
+            msg("Preset", "None of this preset's devices are connected.");
+            return;
+        }
+        let missing: Vec<&str> = preset
+            .slots
+            .iter()
+            .zip(&matched)
+            .filter(|(_, devices)| devices.is_empty())
+            .map(|(slot, _)| slot.profile.as_str())
+            .collect();
+        if !missing.is_empty()
+            && !yesno(
+                "Devices missing",
+                &format!(
+                    "The devices of {} aren't connected.\n\nLaunch without them?",
+                    missing.join(", ")
+                ),
+            )
+        {
+            return;
+        }
+
+        self.selected_game = index;
+        self.instances.clear();
+        self.instance_add_dev = None;
+        let mut resolutions = Vec::new();
+        for (slot, devices) in preset.slots.iter().zip(matched) {
+            if devices.is_empty() {
+                continue;
+            }
+            // Deleted profiles fall back to a guest.
+            let profselection = self
+                .profiles
+                .iter()
+                .position(|profile| *profile == slot.profile)
+                .unwrap_or(0);
+            self.instances.push(Instance {
+                devices,
+                profname: String::new(),
+                profselection,
+                width: 0,
+                height: 0,
+                monitor: slot.monitor.clone(),
+                motion_passthrough: slot.motion_passthrough,
+                platform: slot.platform,
+            });
+            resolutions.push((slot.width, slot.height));
+        }
+        if let Some(slot) = self.move_host_to_first_slot() {
+            resolutions[..=slot].rotate_right(1);
+        }
+        // Resolutions only fit when every player is back.
+        let resolutions = Some(resolutions).filter(|_| missing.is_empty());
+        self.launch_with_resolutions(resolutions);
+    }
+
+    /// Launches the preset at `index` in the home page's list, for the colored
+    /// keys of a TV remote. Ignored while a launch is under way.
+    pub fn launch_preset_key(&mut self, index: usize) {
+        if self.task.is_some() {
+            return;
+        }
+        let presets = self.presets.get_or_insert_with(list_presets).clone();
+        if let Some(preset) = presets.get(index) {
+            self.launch_preset(preset);
+        }
+    }
+
+    /// One-click relaunch buttons for saved presets on the home page.
+    pub fn display_presets(&mut self, ui: &mut Ui) {
+        let presets = self.presets.get_or_insert_with(list_presets).clone();
+        if presets.is_empty() {
+            return;
+        }
+
+        let mut launch: Option<usize> = None;
+        let mut remove: Option<usize> = None;
+        ui.horizontal_wrapped(|row| {
+            row.label(RichText::new("Presets").strong());
+            for (i, preset) in presets.iter().enumerate() {
+                let launch_btn = row.button(format!("▶ {}", preset.name));
+                self.decorate_focus(row, &launch_btn);
+                if launch_btn.clicked() {
+                    launch = Some(i);
+                }
+                if launch_btn.hovered() {
+                    self.infotext = format!(
+                        "Launches {} for {} players with the saved profiles, devices, displays and resolutions.",
+                        preset.game_name,
+                        preset.slots.len()
+                    );
+                }
+                let remove_btn = row.small_button("🗑");
+                self.decorate_focus(row, &remove_btn);
+                if remove_btn.clicked() {
+                    remove = Some(i);
+                }
+                if remove_btn.hovered() {
+                    self.infotext = format!("Deletes the preset {}.", preset.name);
+                }
+            }
+        });
+        ui.add_space(8.0);
+
+        if let Some(i) = remove {
+            let name = &presets[i].name;
+            if yesno("Delete Preset", &format!("Delete the preset {name}?")) {
+                if let Err(err) = remove_preset(name) {
+                    msg("Error", &format!("Couldn't delete preset: {err}"));
+                }
+                self.presets = None;
+            }
+        }
+        if let Some(i) = launch {
+            self.launch_preset(&presets[i]);
+        }
+    }
+}
+
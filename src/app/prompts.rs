@@ -0,0 +1,38 @@
+use std::path::PathBuf;
+
+use dialog::{Choice, DialogBox};
+use rfd::FileDialog;
+
+use crate::paths::PATH_HOME;
+use crate::util::Prompter;
+
+/// Desktop dialogs for the prompts the core asks for while the GUI runs.
+pub struct DesktopPrompter;
+
+impl Prompter for DesktopPrompter {
+    fn message(&self, title: &str, contents: &str) {
+        let _ = dialog::Message::new(contents).title(title).show();
+    }
+
+    fn confirm(&self, title: &str, contents: &str) -> bool {
+        dialog::Question::new(contents)
+            .title(title)
+            .show()
+            .is_ok_and(|choice| choice == Choice::Yes)
+    }
+
+    fn pick_file(&self, title: &str, filters: &[(&str, &[&str])]) -> Option<PathBuf> {
+        let mut picker = FileDialog::new().set_title(title).set_directory(&*PATH_HOME);
+        for &(name, extensions) in filters {
+            picker = picker.add_filter(name, extensions);
+        }
+        picker.pick_file()
+    }
+
+    fn pick_folder(&self, title: &str) -> Option<PathBuf> {
+        FileDialog::new()
+            .set_title(title)
+            .set_directory(&*PATH_HOME)
+            .pick_folder()
+    }
+}
@@ -0,0 +1,137 @@
+use super::app::PartyApp;
+use super::launch_options::launch_history_id;
+use crate::game::Game::HandlerRef;
+use crate::launch::instance_prefix;
+use crate::logging::latest_game_session;
+use crate::paths::PATH_APP;
+use crate::util::*;
+
+use eframe::egui::{self, Ui};
+use std::path::Path;
+
+/// Opens `path` in the desktop's file manager, or names what's missing.
+pub(super) fn open_folder(path: &Path, what: &str) {
+    if !path.exists() {
+        msg("Not found", &format!("No {what} exists yet at {}.", path.display()));
+        return;
+    }
+    if std::process::Command::new("xdg-open").arg(path).spawn().is_err() {
+        msg("Error", &format!("Couldn't open the {what}!"));
+    }
+}
+
+impl PartyApp {
+    /// Adds the selected handler to Steam's library as a non-Steam game that
+    /// launches it headlessly, with its cached artwork.
+    pub fn add_selected_to_steam(&mut self) {
+        let game = &self.games[self.selected_game];
+        let HandlerRef(h) = game else {
+            return;
+        };
+        if steam_is_running()
+            && !yesno(
+                "Steam is running",
+                "Steam overwrites its shortcut list when it exits, which can drop the new shortcut. Close Steam first for a reliable result. Add the shortcut anyway?",
+            )
+        {
+            return;
+        }
+        let cached = cached_artwork(&game.persistent_id());
+        let icon = h.path_handler.join("icon.png");
+        let artwork = ShortcutArtwork {
+            grid: cached.grid.or_else(|| h.steam_header.clone()),
+            hero: cached.hero,
+            logo: cached.logo,
+            icon: icon.is_file().then_some(icon),
+        };
+        let shortcut = SteamShortcut::for_handler(h.display(), &h.uid, artwork);
+        match add_steam_shortcut(&shortcut) {
+            Ok(_) => msg(
+                "Added to Steam",
+                &format!("{} is now in your Steam library. Restart Steam to see it.", h.display()),
+            ),
+            Err(err) => msg("Error", &format!("Couldn't add the Steam shortcut: {err}")),
+        }
+    }
+
+    /// Shortcuts on the game page to the places people otherwise dig for
+    /// under the data folder: a profile's saves, its Proton prefix and the
+    /// game's most recent session log.
+    pub fn display_quick_actions(&mut self, ui: &mut Ui) {
+        egui::CollapsingHeader::new("Quick Actions")
+            .id_salt("game_quick_actions")
+            .show(ui, |ui| {
+                // Only listed while the section is open, so new profiles show up
+                // without a rescan hook.
+                let profiles = scan_profiles(false);
+                if self.quick_action_profile >= profiles.len() {
+                    self.quick_action_profile = 0;
+                }
+                let game = &self.games[self.selected_game];
+                let handler = match game {
+                    HandlerRef(h) => Some(h.clone()),
+                    _ => None,
+                };
+                let log_id = launch_history_id(game);
+
+                ui.horizontal(|row| {
+                    row.label("Profile");
+                    let selected = profiles
+                        .get(self.quick_action_profile)
+                        .map(String::as_str)
+                        .unwrap_or("No profiles");
+                    let combo = egui::ComboBox::from_id_salt("quick_action_profile")
+                        .selected_text(selected)
+                        .show_ui(row, |list| {
+                            for (index, name) in profiles.iter().enumerate() {
+                                list.selectable_value(&mut self.quick_action_profile, index, name);
+                            }
+                        });
+                    self.decorate_focus(row, &combo.response);
+                    let profile = profiles.get(self.quick_action_profile);
+
+                    // Saves and prefixes belong to a handler and a profile.
+                    if let (Some(h), Some(profile)) = (&handler, profile) {
+                        let saves_btn = row.button("📂 Open Saves");
+                        self.decorate_focus(row, &saves_btn);
+                        if saves_btn.clicked() {
+                            let saves = PATH_APP.join("profiles").join(profile).join("saves");
+                            open_folder(&saves.join(&h.uid), "save folder");
+                        }
+                        if saves_btn.hovered() {
+                            self.infotext = "Opens the folder holding this profile's saves and settings for the game.".to_string();
+                        }
+
+                        if h.win {
+                            let prefix_btn = row.button("🍷 Open Prefix");
+                            self.decorate_focus(row, &prefix_btn);
+                            if prefix_btn.clicked() {
+                                let cfg = self.options.handler_launch_config(h);
+                                let party = PATH_APP.to_string_lossy().to_string();
+                                let pfx = instance_prefix(&party, profile, 0, &cfg);
+                                open_folder(Path::new(&pfx), "Proton prefix");
+                            }
+                            if prefix_btn.hovered() {
+                                self.infotext = "Opens this profile's Proton prefix. With separate prefixes per instance this is player 1's prefix.".to_string();
+                            }
+                        }
+                    }
+
+                    // The latest log covers every player, so it needs no profile.
+                    let log_btn = row.button("📄 View Latest Log");
+                    self.decorate_focus(row, &log_btn);
+                    if log_btn.clicked() {
+                        match latest_game_session(&log_id) {
+                            Some(session) => self.open_log_viewer_at(&session.name),
+                            None => msg("Not found", "This game hasn't been logged yet."),
+                        }
+                    }
+                    if log_btn.hovered() {
+                        self.infotext =
+                            "Opens the log viewer on the most recent session of this game."
+                                .to_string();
+                    }
+                });
+            });
+    }
+}
@@ -0,0 +1,147 @@
+use std::time::{Duration, Instant};
+
+use super::app::PartyApp;
+
+use eframe::egui::{self, RichText};
+
+/// How long players have to press a button before the launch is held back.
+const READY_CHECK_TIME: Duration = Duration::from_secs(15);
+
+/// Ready-check between pressing Start and launching: every player presses a
+/// button on one of their devices, so a pad that went to sleep during setup
+/// is noticed before the game starts.
+pub struct ReadyCheck {
+    pub deadline: Instant,
+    /// Whether each instance checked in, by instance index.
+    pub ready: Vec<bool>,
+}
+
+impl ReadyCheck {
+    pub fn new(players: usize) -> Self {
+        ReadyCheck {
+            deadline: Instant::now() + READY_CHECK_TIME,
+            ready: vec![false; players],
+        }
+    }
+}
+
+impl PartyApp {
+    /// Starts the ready-check when it is turned on, or launches right away.
+    pub fn prepare_game_launch(&mut self) {
+        if self.options.ready_check && !self.instances.is_empty() {
+            self.ready_check = Some(ReadyCheck::new(self.instances.len()));
+            return;
+        }
+        self.launch_with_resolutions(None);
+    }
+
+    /// Marks players ready whose devices had a button pressed; called from
+    /// the raw input hook while the ready-check runs.
+    pub fn handle_ready_check_input(&mut self) {
+        let Some(check) = &mut self.ready_check else {
+            return;
+        };
+        for (dev, device) in self.input_devices.iter_mut().enumerate() {
+            // Poll every device so stale events don't pile up, even unassigned ones.
+            let pressed = device.poll().is_some() || device.has_button_held();
+            if !pressed {
+                continue;
+            }
+            for (slot, instance) in self.instances.iter().enumerate() {
+                if instance.devices.contains(&dev) {
+                    if let Some(ready) = check.ready.get_mut(slot) {
+                        *ready = true;
+                    }
+                }
+            }
+        }
+    }
+
+    /// Renders the ready-check window while one runs and launches once every
+    /// player checked in.
+    pub fn display_ready_check(&mut self, ctx: &egui::Context) {
+        let Some(mut check) = self.ready_check.take() else {
+            return;
+        };
+        // Players may have been removed on the instances page meanwhile.
+        check.ready.resize(self.instances.len(), false);
+        if check.ready.is_empty() {
+            return;
+        }
+        if check.ready.iter().all(|&ready| ready) {
+            self.launch_with_resolutions(None);
+            return;
+        }
+
+        let remaining = check.deadline.saturating_duration_since(Instant::now());
+        let expired = remaining.is_zero();
+        let names = self.resolved_profile_names();
+        let mut launch = false;
+        let mut retry = false;
+        let mut cancel = false;
+        egui::Window::new("Ready check")
+            .collapsible(false)
+            .resizable(false)
+            .anchor(egui::Align2::CENTER_CENTER, egui::Vec2::ZERO)
+            .show(ctx, |ui| {
+                ui.spacing_mut().item_spacing.y = 8.0;
+                if expired {
+                    ui.label("Not everyone checked in. Wake the missing controllers and try again, or launch without waiting.");
+                } else {
+                    ui.label(format!(
+                        "Everyone press a button on their controller. Launching once all players are ready ({} s left).",
+                        remaining.as_secs() + 1
+                    ));
+                }
+                for (slot, instance) in self.instances.iter().enumerate() {
+                    let devices: Vec<&str> = instance
+                        .devices
+                        .iter()
+                        .filter_map(|&dev| self.input_devices.get(dev))
+                        .map(|dev| dev.fancyname())
+                        .collect();
+                    let name = names.get(slot).map(String::as_str).unwrap_or_default();
+                    let (mark, color) = if check.ready[slot] {
+                        ("✔", egui::Color32::from_rgb(90, 200, 120))
+                    } else {
+                        ("…", ui.visuals().weak_text_color())
+                    };
+                    ui.label(
+                        RichText::new(format!(
+                            "{mark} Player {}: {name} ({})",
+                            slot + 1,
+                            devices.join(", ")
+                        ))
+                        .color(color),
+                    );
+                }
+                ui.horizontal(|row| {
+                    if expired {
+                        if row.button("Launch Anyway").clicked() {
+                            launch = true;
+                        }
+                        if row.button("Try Again").clicked() {
+                            retry = true;
+                        }
+                    }
+                    if row.button("Cancel").clicked() {
+                        cancel = true;
+                    }
+                });
+            });
+
+        if launch {
+            self.launch_with_resolutions(None);
+            return;
+        }
+        if cancel {
+            return;
+        }
+        if retry {
+            check.deadline = Instant::now() + READY_CHECK_TIME;
+        }
+        // Keep the countdown ticking without input.
+        ctx.request_repaint_after(Duration::from_millis(200));
+        self.ready_check = Some(check);
+    }
+}
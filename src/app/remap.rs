@@ -0,0 +1,133 @@
+use super::app::{MenuPage, PartyApp};
+use crate::util::*;
+
+use eframe::egui::{self, RichText, Ui};
+
+impl PartyApp {
+    /// Opens the Remap page on `profile`.
+    pub fn open_remap_page(&mut self, profile: &str) {
+        self.remap_profile = Some(profile.to_string());
+        self.cur_page = MenuPage::Remap;
+    }
+
+    /// Edits the button remaps, stick inversion and dead zone of one profile.
+    /// The pad proxy applies them to every pad the profile plays with.
+    pub fn display_page_remap(&mut self, ui: &mut Ui) {
+        let Some(profile) = self.remap_profile.clone() else {
+            self.cur_page = MenuPage::Profiles;
+            return;
+        };
+
+        ui.horizontal(|row| {
+            let back_button = row.button("Back to Profiles");
+            self.decorate_focus(row, &back_button);
+            if back_button.clicked() {
+                self.cur_page = MenuPage::Profiles;
+            }
+            row.heading(format!("Remap · {profile}"));
+        });
+        ui.label(
+            RichText::new(
+                "Applied in-game through a virtual pad, so the menus here keep the usual layout. Requires bubblewrap and access to /dev/uinput.",
+            )
+            .weak(),
+        );
+        ui.separator();
+
+        let mut input = self
+            .profile_inputs
+            .entry(profile.clone())
+            .or_insert_with(|| load_profile_input(&profile))
+            .clone();
+        let remap = &mut input.remap;
+
+        let layout_check = ui.checkbox(&mut remap.nintendo_layout, "Nintendo button layout");
+        self.decorate_focus(ui, &layout_check);
+        if layout_check.hovered() {
+            self.infotext = "Swaps A with B and X with Y, for Switch-style pads whose button labels don't match the prompts games show.".to_string();
+        }
+
+        ui.add_space(8.0);
+        ui.label(RichText::new("Buttons").strong());
+        egui::Grid::new(format!("remap_buttons_{profile}"))
+            .num_columns(2)
+            .spacing([12.0, 4.0])
+            .show(ui, |grid| {
+                for (key, label) in ASSIST_BUTTONS {
+                    grid.label(label);
+                    let current = remap.button(key.0);
+                    let mut target = current;
+                    let current_label = ASSIST_BUTTONS
+                        .iter()
+                        .find(|(other, _)| other.0 == current)
+                        .map_or("?", |(_, label)| *label);
+                    let combo = egui::ComboBox::from_id_salt(format!("remap_{profile}_{}", key.0))
+                        .selected_text(current_label)
+                        .show_ui(grid, |list| {
+                            for (other, other_label) in ASSIST_BUTTONS {
+                                list.selectable_value(&mut target, other.0, other_label);
+                            }
+                        });
+                    self.decorate_focus(grid, &combo.response);
+                    grid.end_row();
+
+                    if target != current {
+                        remap.buttons.retain(|(from, _)| *from != key.0);
+                        remap.buttons.push((key.0, target));
+                    }
+                }
+            });
+        // Entries that only restate the layout are dropped, so the file stays short.
+        let layout_only = PadRemap {
+            nintendo_layout: remap.nintendo_layout,
+            ..Default::default()
+        };
+        remap.buttons.retain(|(from, to)| layout_only.button(*from) != *to);
+        let reset_button = ui.button("Reset Buttons");
+        self.decorate_focus(ui, &reset_button);
+        if reset_button.clicked() {
+            remap.buttons.clear();
+        }
+
+        ui.add_space(8.0);
+        ui.label(RichText::new("Sticks").strong());
+        ui.horizontal(|row| {
+            for (flag, label) in [
+                (&mut remap.invert_left_x, "Invert left X"),
+                (&mut remap.invert_left_y, "Invert left Y"),
+                (&mut remap.invert_right_x, "Invert right X"),
+                (&mut remap.invert_right_y, "Invert right Y"),
+            ] {
+                let check = row.checkbox(flag, label);
+                self.decorate_focus(row, &check);
+            }
+        });
+        ui.horizontal(|row| {
+            let mut override_deadzone = remap.deadzone.is_some();
+            let deadzone_check = row.checkbox(&mut override_deadzone, "Custom dead zone");
+            self.decorate_focus(row, &deadzone_check);
+            if deadzone_check.hovered() {
+                self.infotext = "Replaces the dead zone from the pad's calibration for this player. Raise it if a worn stick drifts in-game.".to_string();
+            }
+            if !override_deadzone {
+                remap.deadzone = None;
+            } else {
+                let deadzone = remap
+                    .deadzone
+                    .get_or_insert_with(|| DeviceCalibration::default().deadzone);
+                let slider = row.add(egui::Slider::new(deadzone, 0.0..=0.5).text("Dead zone"));
+                self.decorate_focus(row, &slider);
+            }
+        });
+
+        // Saved once the pointer is released, not on every frame of a drag.
+        if let Some(settings) = self.profile_inputs.get_mut(&profile) {
+            if *settings != input {
+                *settings = input;
+                if !self.input_saves_pending.contains(&profile) {
+                    self.input_saves_pending.push(profile);
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,258 @@
+use super::app::PartyApp;
+use crate::config::save_cfg;
+use crate::game::Game::HandlerRef;
+use crate::handler::Handler;
+use crate::paths::*;
+use crate::util::*;
+
+use eframe::egui::{self, RichText, Ui};
+use std::collections::HashMap;
+use std::thread::JoinHandle;
+
+/// Upstream releases listed per kind; older ones stay reachable upstream.
+const RELEASES_SHOWN: usize = 5;
+
+/// State of the game page's "Emulator Builds" section. Downloads run on
+/// background threads that are polled every frame, like the handler browser.
+#[derive(Default)]
+pub struct ResourceManager {
+    installed: Vec<ResourceBuild>,
+    releases: HashMap<ResourceKind, Vec<ResourceRelease>>,
+    fetch: Option<(ResourceKind, JoinHandle<Result<Vec<ResourceRelease>, String>>)>,
+    /// Build being installed and the thread doing it.
+    install: Option<(String, JoinHandle<Result<ResourceBuild, String>>)>,
+    error: Option<String>,
+}
+
+impl ResourceManager {
+    fn load() -> ResourceManager {
+        let mut manager = ResourceManager::default();
+        manager.reload_installed();
+        manager
+    }
+
+    fn reload_installed(&mut self) {
+        self.installed = ResourceKind::ALL
+            .into_iter()
+            .flat_map(installed_resources)
+            .collect();
+    }
+
+    /// Collects finished background work.
+    fn poll(&mut self) {
+        if self.fetch.as_ref().is_some_and(|(_, handle)| handle.is_finished()) {
+            let (kind, handle) = self.fetch.take().unwrap();
+            match handle.join() {
+                Ok(Ok(releases)) => {
+                    self.releases.insert(kind, releases);
+                }
+                Ok(Err(err)) => self.error = Some(err),
+                Err(_) => self.error = Some("Release fetch panicked".to_string()),
+            }
+        }
+        if self.install.as_ref().is_some_and(|(_, handle)| handle.is_finished()) {
+            let (_, handle) = self.install.take().unwrap();
+            match handle.join() {
+                Ok(Ok(_)) => self.reload_installed(),
+                Ok(Err(err)) => self.error = Some(err),
+                Err(_) => self.error = Some("Install panicked".to_string()),
+            }
+        }
+    }
+
+    fn busy(&self) -> bool {
+        self.fetch.is_some() || self.install.is_some()
+    }
+}
+
+/// Emulator builds `handler` actually uses.
+fn handler_resource_kinds(handler: &Handler) -> Vec<ResourceKind> {
+    ResourceKind::ALL
+        .into_iter()
+        .filter(|kind| match kind {
+            ResourceKind::Goldberg => !handler.path_goldberg.is_empty() && !handler.coldclient,
+            ResourceKind::Nemirtingas => !handler.path_nemirtingas.is_empty(),
+        })
+        .collect()
+}
+
+impl PartyApp {
+    /// Picks the emulator build `handler` launches with; `None` goes back to
+    /// the bundled one. The cached symlink folder is dropped so the next
+    /// launch rebuilds it with the new files.
+    fn set_resource_version(
+        &mut self,
+        handler: &Handler,
+        kind: ResourceKind,
+        version: Option<String>,
+    ) {
+        let versions = self
+            .options
+            .resource_versions
+            .entry(handler.uid.clone())
+            .or_default();
+        match version {
+            Some(version) => versions.insert(kind, version),
+            None => versions.remove(&kind),
+        };
+        if versions.is_empty() {
+            self.options.resource_versions.remove(&handler.uid);
+        }
+        let _ = save_cfg(&self.options);
+        let _ = std::fs::remove_dir_all(PATH_APP.join("gamesyms").join(&handler.uid));
+    }
+
+    /// Deletes a downloaded build and moves handlers that used it back to the
+    /// bundled one.
+    fn remove_resource_build(&mut self, kind: ResourceKind, version: &str) {
+        let users: Vec<Handler> = self
+            .games
+            .iter()
+            .filter_map(|game| match game {
+                HandlerRef(h) if self.options.resource_version(h, kind) == Some(version) => {
+                    Some(h.clone())
+                }
+                _ => None,
+            })
+            .collect();
+        for handler in &users {
+            self.set_resource_version(handler, kind, None);
+        }
+        if let Err(err) = remove_resource(kind, version) {
+            msg("Error", &format!("Couldn't remove {} {version}: {err}", kind.name()));
+        }
+    }
+
+    /// Goldberg and Nemirtingas builds for the selected handler: the bundled
+    /// one or a downloaded upstream release, pinned by checksum.
+    pub fn display_resource_versions(&mut self, ui: &mut Ui) {
+        let HandlerRef(h) = &self.games[self.selected_game] else {
+            return;
+        };
+        let handler = h.clone();
+        let kinds = handler_resource_kinds(&handler);
+        if kinds.is_empty() {
+            return;
+        }
+        let mut manager = self.resource_manager.take().unwrap_or_else(ResourceManager::load);
+        manager.poll();
+
+        egui::CollapsingHeader::new("Emulator Builds")
+            .id_salt("game_resource_versions")
+            .show(ui, |ui| {
+                for kind in kinds {
+                    let current = self.options.resource_version(&handler, kind).map(str::to_string);
+                    let mut picked = current.clone();
+                    // Names the bundled Goldberg build the settings or handler pick.
+                    let bundled = match kind {
+                        ResourceKind::Goldberg => self.options.bundled_goldberg_version(&handler),
+                        ResourceKind::Nemirtingas => None,
+                    };
+                    let bundled = match bundled {
+                        Some(version) => format!("Bundled ({version})"),
+                        None => "Bundled".to_string(),
+                    };
+                    ui.horizontal(|row| {
+                        row.label(kind.name());
+                        let combo = egui::ComboBox::from_id_salt(("resource_version", kind.name()))
+                            .selected_text(current.as_deref().unwrap_or(&bundled))
+                            .show_ui(row, |combo| {
+                                combo.selectable_value(&mut picked, None, &bundled);
+                                for build in manager.installed.iter().filter(|b| b.kind == kind) {
+                                    combo.selectable_value(
+                                        &mut picked,
+                                        Some(build.version.clone()),
+                                        build.version.as_str(),
+                                    );
+                                }
+                            });
+                        self.decorate_focus(row, &combo.response);
+                        if combo.response.hovered() {
+                            self.infotext = format!(
+                                "Which {} build this game launches with. Bundled uses the one shipped with Split Happens; downloaded builds are checked against the SHA1 sums taken at install before every use.",
+                                kind.name()
+                            );
+                        }
+
+                        let check_btn = row.add_enabled(
+                            !manager.busy(),
+                            egui::Button::new("Check for releases"),
+                        );
+                        self.decorate_focus(row, &check_btn);
+                        if check_btn.clicked() {
+                            manager.error = None;
+                            manager.fetch = Some((
+                                kind,
+                                std::thread::spawn(move || {
+                                    fetch_resource_releases(kind).map_err(|err| err.to_string())
+                                }),
+                            ));
+                        }
+                    });
+                    if picked != current {
+                        self.set_resource_version(&handler, kind, picked);
+                    }
+
+                    let mut removal: Option<String> = None;
+                    for build in manager.installed.iter().filter(|b| b.kind == kind) {
+                        ui.horizontal(|row| {
+                            let label = format!("{} (installed)", build.version);
+                            row.label(RichText::new(label).small());
+                            let remove_btn = row.small_button("Remove");
+                            self.decorate_focus(row, &remove_btn);
+                            if remove_btn.clicked() {
+                                removal = Some(build.version.clone());
+                            }
+                        });
+                    }
+                    if let Some(version) = removal {
+                        self.remove_resource_build(kind, &version);
+                        manager.reload_installed();
+                    }
+
+                    let mut install_request: Option<ResourceRelease> = None;
+                    let releases = manager.releases.get(&kind).cloned().unwrap_or_default();
+                    for release in releases.iter().take(RELEASES_SHOWN) {
+                        let installed = manager
+                            .installed
+                            .iter()
+                            .any(|b| b.kind == kind && b.version == release.version);
+                        ui.horizontal(|row| {
+                            row.label(release.version.as_str());
+                            let label = if installed { "Reinstall" } else { "Install" };
+                            let install_btn =
+                                row.add_enabled(!manager.busy(), egui::Button::new(label));
+                            self.decorate_focus(row, &install_btn);
+                            if install_btn.clicked() {
+                                install_request = Some(release.clone());
+                            }
+                        });
+                    }
+                    if let Some(release) = install_request {
+                        manager.error = None;
+                        manager.install = Some((
+                            format!("{} {}", kind.name(), release.version),
+                            std::thread::spawn(move || {
+                                install_resource(kind, &release).map_err(|err| err.to_string())
+                            }),
+                        ));
+                    }
+                    ui.add_space(4.0);
+                }
+
+                if let Some((name, _)) = &manager.install {
+                    ui.horizontal(|row| {
+                        row.spinner();
+                        row.label(format!("Installing {name}..."));
+                    });
+                } else if manager.fetch.is_some() {
+                    ui.spinner();
+                }
+                if let Some(err) = &manager.error {
+                    ui.colored_label(ui.visuals().error_fg_color, err);
+                }
+            });
+
+        self.resource_manager = Some(manager);
+    }
+}
@@ -0,0 +1,473 @@
+use std::collections::HashMap;
+use std::thread::JoinHandle;
+
+use super::app::PartyApp;
+use crate::game::Game;
+use crate::session::{SessionCommand, SessionControl, SessionSlot};
+use crate::input::DeviceType;
+use crate::monitor::{InstanceUsage, SessionMonitor};
+use crate::paths::PATH_APP;
+use crate::util::*;
+
+use eframe::egui::{self, RichText};
+
+/// Core and priority edits for one slot, seeded from the running instance.
+#[derive(Clone)]
+pub struct SessionTuning {
+    pub cores: Vec<usize>,
+    pub nice: i32,
+}
+
+/// A session started from this window, together with its panel state.
+/// Several can run at once, e.g. two games on two monitors.
+pub struct ActiveSession {
+    pub control: SessionControl,
+    /// Game the session runs, checked for a failed launch once it ends.
+    pub game: Game,
+    /// Launch thread, taken over from `task` once every instance is up.
+    pub handle: Option<JoinHandle<()>>,
+    /// Profile picked per slot, as an index into the profile list.
+    pub swap_choices: HashMap<usize, usize>,
+    /// Pad and profile picked for the "Add player" row.
+    pub add_choice: (usize, usize),
+    /// Unapplied CPU core and priority edits per slot.
+    pub tuning: HashMap<usize, SessionTuning>,
+    /// CPU, memory and GPU sampling behind the monitor.
+    pub monitor: Option<SessionMonitor>,
+}
+
+impl ActiveSession {
+    pub fn new(control: SessionControl, game: Game) -> Self {
+        ActiveSession {
+            control,
+            game,
+            handle: None,
+            swap_choices: HashMap::new(),
+            add_choice: (0, 0),
+            tuning: HashMap::new(),
+            monitor: None,
+        }
+    }
+}
+
+impl PartyApp {
+    /// Hands the launch thread over to its session once every instance is
+    /// up. That drops the launch overlay and gives the rest of the UI back,
+    /// so another game can be launched alongside.
+    pub fn adopt_launched_session(&mut self) {
+        let Some(active) = self.sessions.iter_mut().find(|active| active.handle.is_none()) else {
+            return;
+        };
+        if active.control.slots().is_empty() {
+            return;
+        }
+        active.handle = self.task.take();
+        self.loading_msg = None;
+        self.loading_since = None;
+    }
+
+    /// Joins sessions whose launch thread returned and offers to roll back a
+    /// game that failed to start, unless the user stopped it.
+    pub fn reap_ended_sessions(&mut self) {
+        let mut index = 0;
+        while index < self.sessions.len() {
+            let ended = self.sessions[index]
+                .handle
+                .as_ref()
+                .is_some_and(|handle| handle.is_finished());
+            if !ended {
+                index += 1;
+                continue;
+            }
+            let active = self.sessions.remove(index);
+            if let Some(handle) = active.handle {
+                let _ = handle.join();
+            }
+            self.launch_history = None;
+            self.play_stats = None;
+            // A session the user stopped didn't crash.
+            if !active.control.is_stop_requested() {
+                self.offer_launch_rollback(&active.game);
+            }
+        }
+    }
+
+    /// Shows the slots of every running session with per-slot actions in
+    /// their own window, one section per game when several run at once.
+    pub fn display_session_panel(&mut self, ctx: &egui::Context) {
+        let snapshots: Vec<Vec<SessionSlot>> = self
+            .sessions
+            .iter()
+            .map(|active| active.control.slots())
+            .collect();
+        let running = snapshots.iter().filter(|slots| !slots.is_empty()).count();
+        if running == 0 {
+            return;
+        }
+
+        self.refresh_session_profiles();
+        let swap_profiles = std::mem::take(&mut self.session_profiles.1);
+        let mut sessions = std::mem::take(&mut self.sessions);
+        egui::Window::new("Session")
+            .anchor(egui::Align2::RIGHT_TOP, egui::vec2(-12.0, 48.0))
+            .collapsible(true)
+            .resizable(false)
+            .show(ctx, |ui| {
+                ui.spacing_mut().item_spacing.y = 8.0;
+                for (active, slots) in sessions.iter_mut().zip(&snapshots) {
+                    if slots.is_empty() {
+                        continue;
+                    }
+                    if running == 1 {
+                        self.display_session(ui, active, slots, &swap_profiles);
+                        continue;
+                    }
+                    egui::CollapsingHeader::new(RichText::new(active.game.name()).strong())
+                        .id_salt(("session", active.control.id()))
+                        .default_open(true)
+                        .show(ui, |ui| {
+                            self.display_session(ui, active, slots, &swap_profiles);
+                        });
+                }
+            });
+        self.sessions = sessions;
+        self.session_profiles.1 = swap_profiles;
+        ctx.request_repaint_after(std::time::Duration::from_millis(500));
+    }
+
+    /// Lists the profiles again once the profile folder changed, e.g. after
+    /// a profile was created or renamed, rather than on every frame.
+    fn refresh_session_profiles(&mut self) {
+        let modified = std::fs::metadata(PATH_APP.join("profiles"))
+            .and_then(|meta| meta.modified())
+            .ok();
+        if modified.is_some() && modified == self.session_profiles.0 {
+            return;
+        }
+        self.session_profiles = (modified, scan_profiles(true));
+    }
+
+    fn display_session(
+        &self,
+        ui: &mut egui::Ui,
+        active: &mut ActiveSession,
+        slots: &[SessionSlot],
+        swap_profiles: &[String],
+    ) {
+        if active.control.is_idle() {
+            ui.label(RichText::new("💤 Suspended while nobody plays; press any button to resume.").weak());
+        }
+        self.display_session_monitor(ui, active, slots);
+        ui.separator();
+        for slot in slots {
+            self.display_session_slot(ui, active, slot, swap_profiles);
+        }
+        self.display_session_add_player(ui, active, slots, swap_profiles);
+        ui.separator();
+        ui.horizontal(|row| {
+            let rebalance_btn = row.button("Rebalance CPU");
+            if rebalance_btn.clicked() {
+                active.control.send(SessionCommand::RebalanceCpu);
+                active.tuning.clear();
+            }
+            rebalance_btn.on_hover_text(
+                "Splits CPU cores evenly across every player again and restores the default priority.",
+            );
+            let stop_btn = row.button("Stop");
+            if stop_btn.clicked() {
+                active.control.send(SessionCommand::StopAll);
+            }
+            stop_btn.on_hover_text("Closes every instance of this game and ends its session.");
+        });
+    }
+
+    /// Live CPU, memory and GPU use per player, so a struggling instance
+    /// stands out. Expanding a player lists its process tree.
+    fn display_session_monitor(
+        &self,
+        ui: &mut egui::Ui,
+        active: &mut ActiveSession,
+        slots: &[SessionSlot],
+    ) {
+        let id = active.control.id();
+        let monitor = active.monitor.get_or_insert_with(SessionMonitor::new);
+        let usage: Vec<InstanceUsage> = monitor.usage(slots).to_vec();
+        let card_busy = monitor.card_busy().to_vec();
+
+        // Flag the player using the most CPU once there is something to compare.
+        let busiest = usage
+            .iter()
+            .filter(|usage| usage.cpu_percent > 0.0)
+            .max_by(|a, b| a.cpu_percent.total_cmp(&b.cpu_percent))
+            .map(|usage| usage.slot)
+            .filter(|_| usage.len() > 1);
+
+        egui::CollapsingHeader::new("Monitor")
+            .id_salt(("session_monitor", id))
+            .default_open(true)
+            .show(ui, |ui| {
+                egui::Grid::new(("session_monitor_grid", id))
+                    .num_columns(4)
+                    .spacing([16.0, 4.0])
+                    .show(ui, |grid| {
+                        for header in ["Player", "CPU", "RAM", "GPU"] {
+                            grid.label(RichText::new(header).weak());
+                        }
+                        grid.end_row();
+                        for instance in &usage {
+                            let mut cpu = RichText::new(format!("{:.0}%", instance.cpu_percent));
+                            if busiest == Some(instance.slot) {
+                                cpu = cpu.color(grid.visuals().warn_fg_color);
+                            }
+                            grid.label(format!("P{}", instance.slot + 1));
+                            grid.label(cpu);
+                            grid.label(format_bytes(instance.rss_bytes));
+                            grid.label(match instance.gpu_percent {
+                                Some(gpu) => format!("{gpu:.0}%"),
+                                None => "–".to_string(),
+                            });
+                            grid.end_row();
+                        }
+                    });
+                for instance in &usage {
+                    egui::CollapsingHeader::new(format!(
+                        "P{} processes ({})",
+                        instance.slot + 1,
+                        instance.processes.len()
+                    ))
+                    .id_salt(("session_monitor_tree", id, instance.slot))
+                    .show(ui, |ui| {
+                        for process in &instance.processes {
+                            ui.label(
+                                RichText::new(format!(
+                                    "{:>7}  {:<16} {:>5.0}%  {}",
+                                    process.pid,
+                                    process.name,
+                                    process.cpu_percent,
+                                    format_bytes(process.rss_bytes)
+                                ))
+                                .monospace()
+                                .small(),
+                            );
+                        }
+                    });
+                }
+                for (card, busy) in &card_busy {
+                    ui.label(RichText::new(format!("{card}: {busy}% busy")).weak());
+                }
+            });
+    }
+
+    fn display_session_slot(
+        &self,
+        ui: &mut egui::Ui,
+        active: &mut ActiveSession,
+        slot: &SessionSlot,
+        swap_profiles: &[String],
+    ) {
+        let id = active.control.id();
+        ui.horizontal(|row| {
+            let status = if slot.swapping {
+                "swapping…".to_string()
+            } else if slot.running {
+                slot.boot_status()
+            } else {
+                "stopped".to_string()
+            };
+            row.label(RichText::new(format!("P{}", slot.slot + 1)).strong());
+            row.label(slot.profile.as_str());
+            row.label(RichText::new(status).weak());
+        });
+        // A pad that dropped out is rebound once it reconnects.
+        if !slot.disconnected.is_empty() {
+            ui.label(
+                RichText::new("⚠ Controller disconnected, reconnect it to keep playing")
+                    .color(ui.visuals().warn_fg_color),
+            );
+        }
+
+        ui.horizontal(|row| {
+            let choice = active.swap_choices.entry(slot.slot).or_insert(0);
+            egui::ComboBox::from_id_salt(("session_swap", id, slot.slot))
+                .selected_text(swap_profiles.get(*choice).cloned().unwrap_or_default())
+                .show_ui(row, |combo| {
+                    for (i, profile) in swap_profiles.iter().enumerate() {
+                        combo.selectable_value(choice, i, profile);
+                    }
+                });
+            let target = swap_profiles.get(*choice).cloned();
+            let can_swap = slot.running
+                && !slot.swapping
+                && target.as_ref().is_some_and(|target| *target != slot.profile);
+            let swap_btn = row.add_enabled(can_swap, egui::Button::new("Swap in"));
+            if swap_btn.clicked() {
+                // The swapped-in instance starts with the default core split.
+                active.tuning.remove(&slot.slot);
+                if let Some(profile) = target {
+                    active.control.send(SessionCommand::SwapProfile {
+                        slot: slot.slot,
+                        profile,
+                    });
+                }
+            }
+            swap_btn.on_hover_text(
+                "Closes this player's instance and restarts it in the same slot with the selected profile.",
+            );
+        });
+        if slot.running && !slot.swapping {
+            self.display_session_tuning(ui, active, slot);
+        }
+        ui.separator();
+    }
+
+    /// CPU pinning and niceness controls for one running slot. Changes apply to
+    /// the whole process tree of the instance when pressed.
+    fn display_session_tuning(
+        &self,
+        ui: &mut egui::Ui,
+        active: &mut ActiveSession,
+        slot: &SessionSlot,
+    ) {
+        let cpu_count = std::thread::available_parallelism().map_or(1, |n| n.get());
+        let id = active.control.id();
+        let session = &active.control;
+        let tuning = active
+            .tuning
+            .entry(slot.slot)
+            .or_insert_with(|| SessionTuning {
+                cores: slot.cores.clone(),
+                nice: slot.nice.unwrap_or(0),
+            });
+
+        egui::CollapsingHeader::new("Performance")
+            .id_salt(("session_tuning", id, slot.slot))
+            .show(ui, |ui| {
+                ui.horizontal_wrapped(|row| {
+                    row.label("Cores");
+                    for core in 0..cpu_count {
+                        let mut pinned = tuning.cores.contains(&core);
+                        if row.checkbox(&mut pinned, core.to_string()).changed() {
+                            if pinned {
+                                tuning.cores.push(core);
+                                tuning.cores.sort_unstable();
+                            } else {
+                                tuning.cores.retain(|c| *c != core);
+                            }
+                        }
+                    }
+                });
+                ui.horizontal(|row| {
+                    let pin_btn =
+                        row.add_enabled(!tuning.cores.is_empty(), egui::Button::new("Pin"));
+                    if pin_btn.clicked() {
+                        session.send(SessionCommand::SetAffinity {
+                            slot: slot.slot,
+                            cores: tuning.cores.clone(),
+                        });
+                    }
+                    pin_btn.on_hover_text("Restricts this player's game to the checked cores.");
+                    if row.button("All cores").clicked() {
+                        tuning.cores = (0..cpu_count).collect();
+                        session.send(SessionCommand::SetAffinity {
+                            slot: slot.slot,
+                            cores: tuning.cores.clone(),
+                        });
+                    }
+                });
+                ui.horizontal(|row| {
+                    row.add(egui::Slider::new(&mut tuning.nice, -10..=19).text("Nice"));
+                    let nice_btn = row.button("Apply");
+                    if nice_btn.clicked() {
+                        session.send(SessionCommand::SetNiceness {
+                            slot: slot.slot,
+                            nice: tuning.nice,
+                        });
+                    }
+                    nice_btn.on_hover_text(
+                        "Lower values give this player more CPU time. Going below 0 needs permission to raise priorities.",
+                    );
+                });
+                let current = match slot.nice {
+                    Some(nice) => format!("Now: {} cores, nice {nice}", slot.cores.len()),
+                    None => format!("Now: {} cores", slot.cores.len()),
+                };
+                ui.label(RichText::new(current).weak());
+            });
+    }
+
+    /// Lets a late arrival join with a free gamepad. The launch thread spawns
+    /// the extra instance and re-tiles every slot.
+    fn display_session_add_player(
+        &self,
+        ui: &mut egui::Ui,
+        active: &mut ActiveSession,
+        slots: &[SessionSlot],
+        profiles: &[String],
+    ) {
+        let id = active.control.id();
+        let free_pads: Vec<_> = self
+            .input_devices
+            .iter()
+            .filter(|dev| dev.enabled() && dev.device_type() == DeviceType::Gamepad)
+            .filter(|dev| {
+                !slots
+                    .iter()
+                    .any(|slot| slot.devices.iter().any(|path| path == dev.path()))
+            })
+            .collect();
+
+        ui.label(RichText::new("Add player").strong());
+        if free_pads.is_empty() {
+            ui.label(RichText::new("Connect another controller to add a player.").weak());
+            return;
+        }
+
+        let (mut pad_choice, mut profile_choice) = active.add_choice;
+        pad_choice = pad_choice.min(free_pads.len() - 1);
+        ui.horizontal(|row| {
+            egui::ComboBox::from_id_salt(("session_add_pad", id))
+                .selected_text(free_pads[pad_choice].fancyname())
+                .show_ui(row, |combo| {
+                    for (i, pad) in free_pads.iter().enumerate() {
+                        combo.selectable_value(&mut pad_choice, i, pad.fancyname());
+                    }
+                });
+            egui::ComboBox::from_id_salt(("session_add_profile", id))
+                .selected_text(profiles.get(profile_choice).cloned().unwrap_or_default())
+                .show_ui(row, |combo| {
+                    for (i, profile) in profiles.iter().enumerate() {
+                        combo.selectable_value(&mut profile_choice, i, profile);
+                    }
+                });
+        });
+
+        let target = profiles.get(profile_choice).cloned();
+        let taken = target
+            .as_ref()
+            .is_some_and(|target| slots.iter().any(|slot| slot.profile == *target));
+        let add_btn = ui.add_enabled(target.is_some() && !taken, egui::Button::new("Add player"));
+        if add_btn.clicked() {
+            if let Some(profile) = target {
+                active.control.send(SessionCommand::AddInstance {
+                    profile,
+                    device: free_pads[pad_choice].info(),
+                });
+            }
+        }
+        add_btn.on_hover_text(
+            "Starts another instance for the selected pad and profile, then re-splits the screen and CPU cores.",
+        );
+        active.add_choice = (pad_choice, profile_choice);
+    }
+}
+
+/// Human-readable size for the monitor, in MiB below a GiB.
+pub(super) fn format_bytes(bytes: u64) -> String {
+    const MIB: f64 = 1024.0 * 1024.0;
+    let mib = bytes as f64 / MIB;
+    if mib >= 1024.0 {
+        format!("{:.1} GiB", mib / 1024.0)
+    } else {
+        format!("{mib:.0} MiB")
+    }
+}
@@ -0,0 +1,634 @@
+use std::thread::JoinHandle;
+use std::time::Duration;
+
+use super::app::PartyApp;
+use super::app_light::LightPartyApp;
+use crate::config::*;
+use crate::game::Game;
+use crate::input::*;
+use crate::instance::Instance;
+use crate::launch::describe_launch_plan;
+use crate::layout::{FourPlayerLayout, ThreePlayerLayout, TwoPlayerSplit};
+use crate::logging::set_author_mode;
+use crate::paths::*;
+use crate::util::*;
+
+use eframe::egui::RichText;
+use eframe::egui::{self, Response, Ui};
+
+/// What the settings widgets shared by the full and the lightweight launcher
+/// need from the page showing them.
+pub(super) trait SettingsPage {
+    fn options(&self) -> &PartyConfig;
+    fn options_mut(&mut self) -> &mut PartyConfig;
+    fn input_devices(&self) -> &[InputDevice];
+    /// Scans the input devices again, e.g. after the controller filter changed.
+    fn rescan_input_devices(&mut self);
+    fn proton_versions(&self) -> &[ProtonInstall];
+    fn refresh_proton_versions(&mut self);
+    /// Test event on its way to the session webhook.
+    fn webhook_test_mut(&mut self) -> &mut Option<JoinHandle<Result<(), String>>>;
+    fn set_infotext(&mut self, text: &str);
+    /// Draws the controller focus ring on `response`, where the page has one.
+    fn decorate(&mut self, _ui: &mut Ui, _response: &Response) {}
+}
+
+impl SettingsPage for PartyApp {
+    fn options(&self) -> &PartyConfig {
+        &self.options
+    }
+    fn options_mut(&mut self) -> &mut PartyConfig {
+        &mut self.options
+    }
+    fn input_devices(&self) -> &[InputDevice] {
+        &self.input_devices
+    }
+    fn rescan_input_devices(&mut self) {
+        self.input_devices = scan_input_devices(&self.options.pad_filter_type);
+    }
+    fn proton_versions(&self) -> &[ProtonInstall] {
+        &self.proton_versions
+    }
+    fn refresh_proton_versions(&mut self) {
+        PartyApp::refresh_proton_versions(self);
+    }
+    fn webhook_test_mut(&mut self) -> &mut Option<JoinHandle<Result<(), String>>> {
+        &mut self.webhook_test
+    }
+    fn set_infotext(&mut self, text: &str) {
+        self.infotext = text.to_string();
+    }
+    fn decorate(&mut self, ui: &mut Ui, response: &Response) {
+        self.decorate_focus(ui, response);
+    }
+}
+
+impl SettingsPage for LightPartyApp {
+    fn options(&self) -> &PartyConfig {
+        &self.options
+    }
+    fn options_mut(&mut self) -> &mut PartyConfig {
+        &mut self.options
+    }
+    fn input_devices(&self) -> &[InputDevice] {
+        &self.input_devices
+    }
+    fn rescan_input_devices(&mut self) {
+        self.input_devices = scan_input_devices(&self.options.pad_filter_type);
+    }
+    fn proton_versions(&self) -> &[ProtonInstall] {
+        &self.proton_versions
+    }
+    fn refresh_proton_versions(&mut self) {
+        self.proton_versions = discover_proton_versions();
+    }
+    fn webhook_test_mut(&mut self) -> &mut Option<JoinHandle<Result<(), String>>> {
+        &mut self.webhook_test
+    }
+    fn set_infotext(&mut self, text: &str) {
+        self.infotext = text.to_string();
+    }
+}
+
+/// Proton build the settings select: the named or given one, or GE-Proton
+/// when the field is empty.
+fn selected_proton_install<'a>(
+    options: &PartyConfig,
+    versions: &'a [ProtonInstall],
+) -> Option<&'a ProtonInstall> {
+    let trimmed = options.proton_version.trim();
+    if trimmed.is_empty() {
+        return versions.iter().find(|install| install.matches("GE-Proton"));
+    }
+    versions.iter().find(|install| install.matches(trimmed))
+}
+
+/// Label of the Proton combo box, telling a discovered build from a custom
+/// path.
+fn proton_dropdown_label(options: &PartyConfig, versions: &[ProtonInstall]) -> String {
+    if let Some(install) = selected_proton_install(options, versions) {
+        return install.display_label();
+    }
+    let trimmed = options.proton_version.trim();
+    if !trimmed.is_empty() {
+        format!("Custom: {trimmed}")
+    } else if versions.iter().any(|install| install.matches("GE-Proton")) {
+        "Auto (GE-Proton)".to_string()
+    } else {
+        "Auto (GE-Proton missing)".to_string()
+    }
+}
+
+/// Force SDL2, the window layout per player count and the player borders.
+pub(super) fn layout_settings(page: &mut impl SettingsPage, ui: &mut Ui) {
+    let force_sdl2_check =
+        ui.checkbox(&mut page.options_mut().force_sdl, "Force Steam Runtime SDL2");
+    page.decorate(ui, &force_sdl2_check);
+
+    let enable_kwin_script_check = ui.checkbox(
+        &mut page.options_mut().enable_kwin_script,
+        "Automatically resize/reposition instances",
+    );
+    page.decorate(ui, &enable_kwin_script_check);
+
+    let two_player_split = ui
+        .horizontal(|row| {
+            row.label("2 players");
+            let options = page.options_mut();
+            let split_combo = egui::ComboBox::from_id_salt("settings_two_player_split")
+                .selected_text(options.two_player_split.label())
+                .show_ui(row, |combo| {
+                    for split in TwoPlayerSplit::ALL {
+                        combo.selectable_value(&mut options.two_player_split, split, split.label());
+                    }
+                });
+            page.decorate(row, &split_combo.response);
+            split_combo.response
+        })
+        .inner;
+
+    let kwin_script = page.options().enable_kwin_script;
+    let native_layout_check = ui.add_enabled(
+        kwin_script,
+        egui::Checkbox::new(
+            &mut page.options_mut().native_layout,
+            "Use native X11 layout instead of KWin",
+        ),
+    );
+    page.decorate(ui, &native_layout_check);
+
+    let layout_presets = ui
+        .add_enabled_ui(kwin_script, |ui| {
+            ui.horizontal_wrapped(|row| {
+                row.label("3 players");
+                let options = page.options_mut();
+                let three_combo = egui::ComboBox::from_id_salt("settings_three_player_layout")
+                    .selected_text(options.three_player_layout.label())
+                    .show_ui(row, |combo| {
+                        for layout in ThreePlayerLayout::ALL {
+                            combo.selectable_value(
+                                &mut options.three_player_layout,
+                                layout,
+                                layout.label(),
+                            );
+                        }
+                    });
+                page.decorate(row, &three_combo.response);
+                row.label("4 players");
+                let options = page.options_mut();
+                let four_combo = egui::ComboBox::from_id_salt("settings_four_player_layout")
+                    .selected_text(options.four_player_layout.label())
+                    .show_ui(row, |combo| {
+                        for layout in FourPlayerLayout::ALL {
+                            combo.selectable_value(
+                                &mut options.four_player_layout,
+                                layout,
+                                layout.label(),
+                            );
+                        }
+                    });
+                page.decorate(row, &four_combo.response);
+            })
+            .response
+        })
+        .inner;
+
+    // Player borders ride on the KWin script, so disable them alongside it.
+    let native_layout = page.options().native_layout;
+    let player_borders_check = ui.add_enabled(
+        kwin_script && !native_layout,
+        egui::Checkbox::new(
+            &mut page.options_mut().kwin_player_borders,
+            "Colored player borders",
+        ),
+    );
+    page.decorate(ui, &player_borders_check);
+
+    if force_sdl2_check.hovered() {
+        page.set_infotext("Forces games to use the version of SDL2 included in the Steam Runtime. Only works on native Linux games, may fix problematic game controller support (incorrect mappings) in some games, may break others. If unsure, leave this unchecked.");
+    }
+    if enable_kwin_script_check.hovered() {
+        page.set_infotext("Resizes/repositions instances to fit the screen using a KWin script. If unsure, leave this checked. If using a desktop environment or window manager other than KDE Plasma, enable the native X11 layout below, or uncheck this and resize and reposition the windows manually.");
+    }
+    if two_player_split.hovered() {
+        page.set_infotext("How two players share the screen. Auto picks side by side on ultrawide screens (21:9 and wider) and stacks the players on 16:9 and 16:10 ones, checking the screen at every launch.");
+    }
+    if player_borders_check.hovered() {
+        page.set_infotext("Draws a thin colored frame around each player's window through a KWin overlay script so pane ownership is obvious in 3-4 player layouts. Named profiles keep the same color across sessions. Requires the resize/reposition option.");
+    }
+    if native_layout_check.hovered() {
+        page.set_infotext("Positions the instance windows directly through X11 instead of the KWin script, so automatic layout also works on other desktops and window managers. Player borders need KWin and are unavailable in this mode.");
+    }
+    if layout_presets.hovered() {
+        page.set_infotext("How the screen is split for three and four players. Custom regions drawn on the Instances page take precedence, and larger groups use an even grid.");
+    }
+}
+
+pub(super) fn copilot_setting(page: &mut impl SettingsPage, ui: &mut Ui) {
+    let copilot_check = ui.checkbox(
+        &mut page.options_mut().copilot_merge,
+        "Merge co-pilot controllers into one player",
+    );
+    page.decorate(ui, &copilot_check);
+    if copilot_check.hovered() {
+        page.set_infotext("Lets a second controller assigned to the same player (press Y on the first pad, then A on the other) act as a co-pilot: both are merged into one virtual controller, so either person can press buttons or steer. Requires bubblewrap and access to /dev/uinput.");
+    }
+}
+
+pub(super) fn remember_assignments_setting(page: &mut impl SettingsPage, ui: &mut Ui) {
+    let remember_check = ui.checkbox(
+        &mut page.options_mut().remember_device_assignments,
+        "Remember controller assignments per game",
+    );
+    page.decorate(ui, &remember_check);
+    if remember_check.hovered() {
+        page.set_infotext("Saves which controllers played in which slot when a game launches, and sets the same slots up again the next time you open that game. Pads that aren't connected are skipped.");
+    }
+}
+
+/// Which controllers are listed, scanning them again once it changes.
+pub(super) fn controller_filter_setting(page: &mut impl SettingsPage, ui: &mut Ui) {
+    // Group the controller filter radios so they wrap neatly on narrow windows.
+    ui.group(|group| {
+        group.spacing_mut().item_spacing.y = 6.0;
+        let filter_label = group.label("Controller filter");
+        group.horizontal_wrapped(|radios| {
+            let mut clicked = false;
+            let mut hovered = filter_label.hovered();
+            for (filter, label) in [
+                (PadFilterType::All, "All controllers"),
+                (PadFilterType::NoSteamInput, "No Steam Input"),
+                (PadFilterType::OnlySteamInput, "Only Steam Input"),
+            ] {
+                let options = page.options_mut();
+                let radio = radios.radio_value(&mut options.pad_filter_type, filter, label);
+                page.decorate(radios, &radio);
+                clicked |= radio.clicked();
+                hovered |= radio.hovered();
+            }
+            if hovered {
+                page.set_infotext("Select which controllers to filter out. If unsure, set this to \"No Steam Input\". If you use Steam Input to remap controllers, you may want to select \"Only Steam Input\", but be warned that this option is experimental and is known to break certain Proton games.");
+            }
+            if clicked {
+                page.rescan_input_devices();
+            }
+        });
+    });
+}
+
+/// The Proton build, picked from the discovered ones or given by name or
+/// path, and whether every instance gets its own prefix.
+pub(super) fn proton_settings(page: &mut impl SettingsPage, ui: &mut Ui) {
+    // Wrap the Proton selector and manual override into a tidy stack for clarity.
+    ui.group(|group| {
+        group.spacing_mut().item_spacing.y = 8.0;
+        let proton_ver_label = group.label("Proton version");
+        let versions = page.proton_versions().to_vec();
+        let selected = proton_dropdown_label(page.options(), &versions);
+        let options = page.options_mut();
+        let combo_response = egui::ComboBox::from_id_salt("settings_proton_combo")
+            .selected_text(selected)
+            .width(220.0)
+            .show_ui(group, |combo_ui| {
+                combo_ui.selectable_value(
+                    &mut options.proton_version,
+                    String::new(),
+                    "Auto (GE-Proton)",
+                );
+                if versions.is_empty() {
+                    combo_ui.label("No Proton builds detected");
+                }
+                for install in &versions {
+                    combo_ui.selectable_value(
+                        &mut options.proton_version,
+                        install.id.clone(),
+                        install.display_label(),
+                    );
+                }
+                combo_ui.separator();
+                combo_ui.label("Select a build above or keep using the custom path below.");
+            })
+            .response;
+        page.decorate(group, &combo_response);
+
+        let refresh_btn = group.small_button("Refresh");
+        page.decorate(group, &refresh_btn);
+        if refresh_btn.clicked() {
+            page.refresh_proton_versions();
+        }
+        if proton_ver_label.hovered() || combo_response.hovered() || refresh_btn.hovered() {
+            page.set_infotext("Choose an installed Proton build or refresh the list after installing a new compatibility tool. Keep the field below blank for the default GE-Proton.");
+        }
+
+        let proton_ver_editbox = group.add(
+            egui::TextEdit::singleline(&mut page.options_mut().proton_version)
+                .hint_text("GE-Proton or /path/to/proton"),
+        );
+        page.decorate(group, &proton_ver_editbox);
+        if proton_ver_editbox.hovered() {
+            page.set_infotext("Enter a custom Proton identifier or absolute path. Leave empty to auto-select GE-Proton.");
+        }
+    });
+
+    let proton_separate_pfxs_check = ui.checkbox(
+        &mut page.options_mut().proton_separate_pfxs,
+        "Run instances in separate Proton prefixes",
+    );
+    page.decorate(ui, &proton_separate_pfxs_check);
+    if proton_separate_pfxs_check.hovered() {
+        page.set_infotext("Runs each instance in its own Proton prefix. If unsure, leave this unchecked. This option will take up more space on the disk, but may also help with certain Proton-related issues such as only one instance of a game starting.");
+    }
+}
+
+pub(super) fn multiseat_setting(page: &mut impl SettingsPage, ui: &mut Ui) {
+    // Multi-seat accounts only apply to handlers that opt into separate users.
+    ui.horizontal(|row| {
+        let multiseat_label = row.label("Multi-seat users");
+        let multiseat_editbox = row.add(
+            egui::TextEdit::singleline(&mut page.options_mut().multiseat_users)
+                .hint_text("player2,player3"),
+        );
+        page.decorate(row, &multiseat_editbox);
+        if multiseat_label.hovered() || multiseat_editbox.hovered() {
+            page.set_infotext("Advanced: comma-separated local accounts (one per player slot) used when a handler enables game.separate_users. Split Happens shares the game and its data folder with each account through ACLs (needs setfacl); leave a slot empty to keep it on your own user.");
+        }
+    });
+}
+
+/// Offline mode, applied to downloads as soon as it's toggled.
+pub(super) fn offline_mode_setting(page: &mut impl SettingsPage, ui: &mut Ui) {
+    let offline_check = ui.checkbox(&mut page.options_mut().offline_mode, "Offline mode");
+    page.decorate(ui, &offline_check);
+    if offline_check.changed() {
+        set_offline_mode(page.options().offline_mode);
+    }
+    if offline_check.hovered() {
+        page.set_infotext("Never reach the internet: skips the update check, Steam header and SteamGridDB artwork downloads, the handler browser, emulator build downloads and session webhooks. LAN play and the LAN coordinator keep working. Downloads honor the HTTP_PROXY, HTTPS_PROXY and NO_PROXY environment variables when online.");
+    }
+}
+
+pub(super) fn webhook_setting(page: &mut impl SettingsPage, ui: &mut Ui) {
+    // Session webhooks notify external services about start/end/crash events.
+    ui.horizontal(|row| {
+        let webhook_label = row.label("Session webhook");
+        let webhook_editbox = row.add(
+            egui::TextEdit::singleline(&mut page.options_mut().webhook_url)
+                .hint_text("https://example.com/hook"),
+        );
+        page.decorate(row, &webhook_editbox);
+        let testing = page.webhook_test_mut().is_some();
+        let webhook_test_btn = row.add_enabled(!testing, egui::Button::new("Test"));
+        page.decorate(row, &webhook_test_btn);
+        if webhook_test_btn.clicked() {
+            let url = page.options().webhook_url.clone();
+            *page.webhook_test_mut() = Some(std::thread::spawn(move || {
+                test_session_webhook(&url).map_err(|err| err.to_string())
+            }));
+        }
+        let finished = page.webhook_test_mut().as_ref().map(JoinHandle::is_finished);
+        if finished == Some(true) {
+            if let Some(test) = page.webhook_test_mut().take() {
+                match test.join() {
+                    Ok(Ok(())) => msg("Webhook", "Test event delivered."),
+                    Ok(Err(err)) => msg("Webhook Error", &err),
+                    Err(_) => msg("Webhook Error", "The test delivery crashed."),
+                }
+            }
+        } else if finished == Some(false) {
+            row.spinner();
+            row.ctx().request_repaint_after(Duration::from_millis(250));
+        }
+        if webhook_label.hovered() || webhook_editbox.hovered() || webhook_test_btn.hovered() {
+            page.set_infotext("Optional URL that receives an HTTP POST with JSON when a session starts or ends and when an instance crashes, e.g. for home automation or a Discord bot. Use Test to send a sample event.");
+        }
+    });
+}
+
+/// Erases the shared Proton prefix after asking.
+pub(super) fn erase_prefix_button(page: &mut impl SettingsPage, ui: &mut Ui) {
+    let erase_prefix_btn = ui.button("Erase Proton Prefix");
+    page.decorate(ui, &erase_prefix_btn);
+    if erase_prefix_btn.clicked() {
+        if yesno(
+            "Erase Prefix?",
+            "This will erase the Wine prefix used by Split Happens. This shouldn't erase profile/game-specific data, but exercise caution. Are you sure?",
+        ) && PATH_APP.join("gamesyms").exists()
+        {
+            if let Err(err) = std::fs::remove_dir_all(PATH_APP.join("pfx")) {
+                msg("Error", &format!("Couldn't erase pfx data: {}", err));
+            } else if let Err(err) = std::fs::create_dir_all(PATH_APP.join("pfx")) {
+                msg("Error", &format!("Couldn't re-create pfx directory: {}", err));
+            } else {
+                msg("Data Erased", "Proton prefix data successfully erased.");
+            }
+        }
+    }
+}
+
+/// Erases the game symlink folders after asking.
+pub(super) fn erase_symlinks_button(page: &mut impl SettingsPage, ui: &mut Ui) {
+    let erase_symlink_btn = ui.button("Erase Symlink Data");
+    page.decorate(ui, &erase_symlink_btn);
+    if erase_symlink_btn.clicked() {
+        if yesno(
+            "Erase Symlink Data?",
+            "This will erase all game symlink data. This shouldn't erase profile/game-specific data, but exercise caution. Are you sure?",
+        ) && PATH_APP.join("gamesyms").exists()
+        {
+            if let Err(err) = std::fs::remove_dir_all(PATH_APP.join("gamesyms")) {
+                msg("Error", &format!("Couldn't erase symlink data: {}", err));
+            } else if let Err(err) = std::fs::create_dir_all(PATH_APP.join("gamesyms")) {
+                msg("Error", &format!("Couldn't re-create symlink directory: {}", err));
+            } else {
+                msg("Data Erased", "Game symlink data successfully erased.");
+            }
+        }
+    }
+}
+
+/// Buttons opening the data folder and paths.json in the file manager.
+pub(super) fn data_folder_buttons(page: &mut impl SettingsPage, ui: &mut Ui) {
+    let open_data_btn = ui.button("Open Split Happens Data Folder");
+    page.decorate(ui, &open_data_btn);
+    if open_data_btn.clicked() {
+        if let Err(_) = std::process::Command::new("sh")
+            .arg("-c")
+            .arg(format!("xdg-open {}/", PATH_APP.display()))
+            .status()
+        {
+            msg("Error", "Couldn't open Split Happens Data Folder!");
+        }
+    }
+    let edit_paths_btn = ui.button("Edit game paths");
+    page.decorate(ui, &edit_paths_btn);
+    if edit_paths_btn.clicked() {
+        if let Err(_) = std::process::Command::new("sh")
+            .arg("-c")
+            .arg(format!("xdg-open {}/paths.json", PATH_APP.display(),))
+            .status()
+        {
+            msg("Error", "Couldn't open paths.json!");
+        }
+    }
+}
+
+/// Steam Deck performance assists.
+pub(super) fn performance_settings(page: &mut impl SettingsPage, ui: &mut Ui) {
+    let realtime_toggle = ui.checkbox(
+        &mut page.options_mut().performance_gamescope_rt,
+        "Real-time scheduling for Gamescope",
+    );
+    page.decorate(ui, &realtime_toggle);
+    if realtime_toggle.hovered() {
+        page.set_infotext("Requests gamescope's real-time compositor mode to reduce frame pacing spikes when two sessions share the GPU.");
+    }
+
+    let fps_limit_toggle = ui.checkbox(
+        &mut page.options_mut().performance_limit_40fps,
+        "Limit Gamescope output to 40 FPS",
+    );
+    page.decorate(ui, &fps_limit_toggle);
+    if fps_limit_toggle.hovered() {
+        page.set_infotext("Caps each window to 40 frames per second so both players stay within the Deck's thermal and power envelope.");
+    }
+
+    let proton_fsr_toggle = ui.checkbox(
+        &mut page.options_mut().performance_enable_proton_fsr,
+        "Enable Proton FSR upscaling",
+    );
+    page.decorate(ui, &proton_fsr_toggle);
+    if proton_fsr_toggle.hovered() {
+        page.set_infotext("Turns on Proton's fullscreen FSR so Windows titles can render at lower resolutions while gamescope upscales the result.");
+    }
+}
+
+/// Gamescope options, the session hotkeys and the screenshot buttons.
+pub(super) fn gamescope_settings(page: &mut impl SettingsPage, ui: &mut Ui) {
+    let gamescope_lowres_fix_check = ui.checkbox(
+        &mut page.options_mut().gamescope_fix_lowres,
+        "Automatically fix low resolution instances",
+    );
+    page.decorate(ui, &gamescope_lowres_fix_check);
+    let gamescope_sdl_backend_check = ui.checkbox(
+        &mut page.options_mut().gamescope_sdl_backend,
+        "Use SDL backend for Gamescope",
+    );
+    page.decorate(ui, &gamescope_sdl_backend_check);
+    let kbm_support_check = ui.checkbox(
+        &mut page.options_mut().kbm_support,
+        "Enable keyboard and mouse support through custom Gamescope",
+    );
+    page.decorate(ui, &kbm_support_check);
+
+    if gamescope_lowres_fix_check.hovered() {
+        page.set_infotext("Many games have graphical problems or even crash when running at resolutions below 600p. If this is enabled, any instances below 600p will automatically be resized before launching.");
+    }
+    if gamescope_sdl_backend_check.hovered() {
+        page.set_infotext("Runs gamescope sessions using the SDL backend. If unsure, leave this checked. If gamescope sessions only show a black screen or give an error (especially on Nvidia + Wayland), try disabling this.");
+    }
+    if kbm_support_check.hovered() {
+        page.set_infotext("Runs a custom Gamescope build with support for holding keyboards and mice. If you want to use your own Gamescope installation, uncheck this.");
+    }
+
+    let hotkeys_check = ui.checkbox(&mut page.options_mut().session_hotkeys, "Session hotkeys");
+    page.decorate(ui, &hotkeys_check);
+    if hotkeys_check.hovered() {
+        page.set_infotext("Ctrl+Shift+Q stops every instance, Ctrl+Shift+R restarts crashed ones and Ctrl+Shift+F toggles the 40 FPS limit while a session runs. Keyboards are read, not grabbed, so games still see the keys.");
+    }
+
+    ui.horizontal(|row| {
+        let chord_label = row.label("Screenshot buttons");
+        let chord_editbox = row.add(
+            egui::TextEdit::singleline(&mut page.options_mut().screenshot_chord)
+                .hint_text(DEFAULT_SCREENSHOT_CHORD),
+        );
+        page.decorate(row, &chord_editbox);
+        if chord_label.hovered() || chord_editbox.hovered() {
+            page.set_infotext("Pad buttons that, pressed together, save a screenshot of that player's pane only. Screenshots go to the session's folder in the logs, named after the profile, and show up in View Logs. Use evdev button names separated by spaces; the default is Guide and the right bumper. Leave empty to turn it off. The game still sees the buttons.");
+        }
+        if let Err(err) = parse_screenshot_chord(&page.options().screenshot_chord) {
+            row.label(RichText::new(err).color(row.visuals().warn_fg_color));
+        }
+    });
+}
+
+/// Steam Deck rear buttons, listed with whether a pad exposing them is connected.
+pub(super) fn rear_buttons_setting(page: &mut impl SettingsPage, ui: &mut Ui) {
+    let rear_pads = page
+        .input_devices()
+        .iter()
+        .filter(|dev| dev.has_rear_buttons())
+        .count();
+    ui.horizontal_wrapped(|row| {
+        let rear_label = row.label("Rear buttons");
+        let mut hovered = rear_label.hovered();
+        let mut bindings = page.options_mut().rear_buttons;
+        for (name, action) in [
+            ("L4", &mut bindings.l4),
+            ("L5", &mut bindings.l5),
+            ("R4", &mut bindings.r4),
+            ("R5", &mut bindings.r5),
+        ] {
+            row.label(name);
+            let combo = egui::ComboBox::from_id_salt(format!("settings_rear_{name}"))
+                .selected_text(action.label())
+                .show_ui(row, |combo| {
+                    for option in RearButtonAction::ALL {
+                        combo.selectable_value(action, option, option.label());
+                    }
+                });
+            page.decorate(row, &combo.response);
+            hovered |= combo.response.hovered();
+        }
+        page.options_mut().rear_buttons = bindings;
+        row.label(
+            RichText::new(if rear_pads > 0 {
+                "Detected"
+            } else {
+                "No pad with rear buttons connected"
+            })
+            .weak(),
+        );
+        if hovered {
+            page.set_infotext("What the Steam Deck's back buttons do in the launcher's menus. Launch last session sets up the game played last with its remembered controllers and starts it. They are only seen with Steam closed or with the Deck's own pad in use through the hid-steam driver; Steam Input's virtual pads don't have them.");
+        }
+    });
+}
+
+/// Handler author mode, applied to logging as soon as it's toggled.
+pub(super) fn author_mode_setting(page: &mut impl SettingsPage, ui: &mut Ui) {
+    let author_check = ui.checkbox(&mut page.options_mut().author_mode, "Handler author mode");
+    page.decorate(ui, &author_check);
+    if author_check.changed() {
+        set_author_mode(page.options_mut().author_mode);
+    }
+    if author_check.hovered() {
+        page.set_infotext("For writing and debugging handlers: logs everything at maximum verbosity (launcher debug messages, the environment variables set for each instance, gamescope debug output, Proton, DXVK and VKD3D logs, Nemirtingas trace logs) into a separate logs-dev folder, and adds a \"Copy launch plan\" button to every page. Slows games down; leave it off for playing.");
+    }
+}
+
+/// Floating button of handler author mode that copies what a launch of
+/// `game` with `instances` would do.
+pub(super) fn launch_plan_button(
+    ctx: &egui::Context,
+    game: Option<&Game>,
+    instances: &[Instance],
+    input_devices: &[InputDevice],
+    options: &PartyConfig,
+) {
+    if !options.author_mode {
+        return;
+    }
+    egui::Area::new("launch_plan".into())
+        .anchor(egui::Align2::RIGHT_BOTTOM, egui::vec2(-12.0, -12.0))
+        .show(ctx, |ui| {
+            let copy_btn = ui.button("📋 Copy launch plan");
+            if copy_btn.clicked() {
+                let devices: Vec<DeviceInfo> = input_devices.iter().map(|dev| dev.info()).collect();
+                ctx.copy_text(describe_launch_plan(game, instances, &devices, options));
+            }
+            copy_btn.on_hover_text(
+                "Copies the handler, launch settings, players and their devices, and the last recorded command lines.",
+            );
+        });
+}
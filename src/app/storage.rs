@@ -0,0 +1,310 @@
+use super::app::{MenuPage, PartyApp};
+use super::launch_options::launch_age;
+use super::quick_actions::open_folder;
+use super::session_panel::format_bytes;
+use crate::config::save_cfg;
+use crate::logging::log_info;
+use crate::paths::PATH_APP;
+use crate::util::*;
+
+use eframe::egui::{self, RichText, Ui};
+
+impl PartyApp {
+    /// Opens the Storage page, measuring what it lists.
+    pub fn open_storage_page(&mut self) {
+        self.storage_prefixes = Some(list_proton_prefixes());
+        self.start_tree_scan(None);
+        self.cur_page = MenuPage::Storage;
+    }
+
+    /// Measures the working trees on a background thread, since that walks
+    /// every linked game. Deletes `remove`'s tree first.
+    fn start_tree_scan(&mut self, remove: Option<String>) {
+        if self.storage_trees_scan.is_some() {
+            return;
+        }
+        self.storage_trees_scan = Some(std::thread::spawn(move || {
+            if let Some(profile) = remove {
+                if let Err(err) = remove_working_tree(&profile) {
+                    msg("Error", &format!("Couldn't delete the working tree: {err}"));
+                }
+            }
+            list_working_trees()
+        }));
+    }
+
+    /// Asks once whether leftover working trees should be removed at startup,
+    /// when earlier sessions left some and nobody was asked yet. The startup
+    /// path removes them once the answer is yes.
+    pub fn display_leftover_trees_prompt(&mut self, ctx: &egui::Context) {
+        let Some(count) = self.leftover_trees_prompt else {
+            return;
+        };
+        let mut choice = None;
+        egui::Window::new("Leftover Working Trees")
+            .collapsible(false)
+            .resizable(false)
+            .anchor(egui::Align2::CENTER_CENTER, egui::Vec2::ZERO)
+            .show(ctx, |ui| {
+                ui.label(format!(
+                    "Earlier sessions left {count} game working trees in the data folder. They are rebuilt on every launch, so they only take up space. Remove leftovers automatically whenever Split Happens starts? You can change this under Settings → Storage."
+                ));
+                ui.horizontal(|ui| {
+                    if ui.button("Remove").clicked() {
+                        choice = Some(true);
+                    }
+                    if ui.button("Keep").clicked() {
+                        choice = Some(false);
+                    }
+                });
+            });
+        let Some(clean) = choice else {
+            return;
+        };
+        self.leftover_trees_prompt = None;
+        self.options.clean_run_trees = Some(clean);
+        if let Err(err) = save_cfg(&self.options) {
+            msg("Error", &format!("Couldn't save settings: {err}"));
+        }
+        if clean {
+            log_info("Removing leftover working trees as chosen at startup");
+            spawn_leftover_tree_cleanup();
+        }
+    }
+
+    /// Lists the Proton prefixes with the profile each belongs to, so large
+    /// or orphaned ones can be found and deleted.
+    pub fn display_page_storage(&mut self, ui: &mut Ui) {
+        ui.horizontal(|row| {
+            let back_button = row.button("Back to Settings");
+            self.decorate_focus(row, &back_button);
+            if back_button.clicked() {
+                self.cur_page = MenuPage::Settings;
+            }
+            row.heading("Storage");
+            let refresh_button = row.button("Refresh");
+            self.decorate_focus(row, &refresh_button);
+            if refresh_button.clicked() {
+                self.storage_prefixes = Some(list_proton_prefixes());
+                self.start_tree_scan(None);
+            }
+        });
+        ui.separator();
+
+        self.display_working_trees(ui);
+        ui.add_space(12.0);
+
+        let prefixes = self.storage_prefixes.get_or_insert_with(list_proton_prefixes);
+        let total: u64 = prefixes.iter().map(|prefix| prefix.bytes).sum();
+        ui.label(RichText::new(format!("Proton prefixes · {}", format_bytes(total))).strong());
+        ui.label(
+            RichText::new(
+                "Named after the profile's id, so they stay with a profile when it is renamed.",
+            )
+            .weak(),
+        );
+        if prefixes.is_empty() {
+            ui.label("No Proton prefixes yet.");
+            return;
+        }
+
+        let mut open: Option<usize> = None;
+        let mut delete: Option<usize> = None;
+        let mut hover: Option<String> = None;
+        let rows: Vec<(String, String, String, bool)> = prefixes
+            .iter()
+            .map(|prefix| {
+                let folder = prefix
+                    .path
+                    .file_name()
+                    .map(|name| name.to_string_lossy().to_string())
+                    .unwrap_or_default();
+                let mut owner = prefix
+                    .owner
+                    .clone()
+                    .unwrap_or_else(|| "No profile".to_string());
+                if let Some(slot) = prefix.slot {
+                    owner.push_str(&format!(" · player {slot}"));
+                }
+                if prefix.legacy {
+                    owner.push_str(" (moves to its id on the next launch)");
+                }
+                (folder, owner, format_bytes(prefix.bytes), prefix.owner.is_some())
+            })
+            .collect();
+        egui::Grid::new("storage_prefixes")
+            .num_columns(5)
+            .spacing([12.0, 4.0])
+            .striped(true)
+            .show(ui, |grid| {
+                for (i, (folder, owner, size, owned)) in rows.iter().enumerate() {
+                    grid.monospace(folder);
+                    if *owned {
+                        grid.label(owner);
+                    } else {
+                        grid.label(RichText::new(owner).weak());
+                    }
+                    grid.label(size);
+                    let open_button = grid.button("Open");
+                    self.decorate_focus(grid, &open_button);
+                    if open_button.clicked() {
+                        open = Some(i);
+                    }
+                    let delete_button = grid.button("Delete");
+                    self.decorate_focus(grid, &delete_button);
+                    if delete_button.clicked() {
+                        delete = Some(i);
+                    }
+                    if delete_button.hovered() {
+                        hover = Some(format!(
+                            "Deletes the prefix {folder}. Games installed into it and their Windows-side settings go with it; Proton makes a fresh one on the next launch."
+                        ));
+                    }
+                    grid.end_row();
+                }
+            });
+        if let Some(text) = hover {
+            self.infotext = text;
+        }
+
+        let Some(prefixes) = &self.storage_prefixes else {
+            return;
+        };
+        if let Some(i) = open {
+            open_folder(&prefixes[i].path, "Proton prefix");
+        }
+        if let Some(i) = delete {
+            let path = prefixes[i].path.clone();
+            if let Some(owner) = prefixes[i].owner.as_deref() {
+                if ProfileLock::is_held(owner) {
+                    let text = format!("{owner} is playing. Stop the session before deleting its prefix.");
+                    msg("Prefix In Use", &text);
+                    return;
+                }
+            }
+            if yesno(
+                "Delete Prefix?",
+                &format!("Delete the Proton prefix {}?", path.display()),
+            ) {
+                if let Err(err) = std::fs::remove_dir_all(&path) {
+                    msg("Error", &format!("Couldn't delete the prefix: {err}"));
+                }
+                self.storage_prefixes = Some(list_proton_prefixes());
+            }
+        }
+    }
+
+    /// Working trees under `run/`, the symlinked game copies instances start
+    /// from, with the session that built each.
+    fn display_working_trees(&mut self, ui: &mut Ui) {
+        if self.storage_trees_scan.as_ref().is_some_and(|scan| scan.is_finished()) {
+            if let Some(scan) = self.storage_trees_scan.take() {
+                self.storage_trees = scan.join().ok();
+            }
+        }
+        if self.storage_trees.is_none() {
+            self.start_tree_scan(None);
+        }
+        let total: u64 = self.storage_trees.iter().flatten().map(|tree| tree.bytes).sum();
+        ui.horizontal(|row| {
+            row.label(RichText::new(format!("Working trees · {}", format_bytes(total))).strong());
+            if self.storage_trees_scan.is_some() {
+                row.spinner();
+                row.ctx().request_repaint();
+            }
+        });
+        ui.label(
+            RichText::new(
+                "Each player's linked copy of the game under run/, rebuilt on every launch. Sizes count files games wrote or the launcher patched there.",
+            )
+            .weak(),
+        );
+
+        let mut clean = self.options.clean_run_trees == Some(true);
+        let clean_check = ui.checkbox(&mut clean, "Remove leftovers at startup");
+        self.decorate_focus(ui, &clean_check);
+        if clean_check.hovered() {
+            self.infotext = "Deletes the working trees no running session uses whenever Split Happens starts.".to_string();
+        }
+        if clean_check.changed() {
+            self.options.clean_run_trees = Some(clean);
+            if let Err(err) = save_cfg(&self.options) {
+                msg("Error", &format!("Couldn't save settings: {err}"));
+            }
+        }
+
+        let Some(trees) = &self.storage_trees else {
+            return;
+        };
+        if trees.is_empty() {
+            ui.label("No working trees.");
+            return;
+        }
+        let rows: Vec<(String, String, String, Option<String>, bool)> = trees
+            .iter()
+            .map(|tree| {
+                let origin = match (&tree.game, tree.created) {
+                    (Some(game), Some(created)) => format!("{game}, {}", launch_age(created)),
+                    _ => "Unknown session".to_string(),
+                };
+                let size = format!("{} links · {}", tree.links, format_bytes(tree.bytes));
+                (tree.profile.clone(), origin, size, tree.session.clone(), tree.in_use)
+            })
+            .collect();
+
+        let mut view_log: Option<String> = None;
+        let mut open: Option<usize> = None;
+        let mut delete: Option<usize> = None;
+        egui::Grid::new("storage_trees")
+            .num_columns(6)
+            .spacing([12.0, 4.0])
+            .striped(true)
+            .show(ui, |grid| {
+                for (i, (profile, origin, size, session, in_use)) in rows.iter().enumerate() {
+                    grid.label(profile);
+                    grid.label(origin);
+                    grid.label(size);
+                    grid.add_enabled_ui(session.is_some(), |cell| {
+                        let log_button = cell.button("View Log");
+                        self.decorate_focus(cell, &log_button);
+                        if log_button.clicked() {
+                            view_log = session.clone();
+                        }
+                    });
+                    let open_button = grid.button("Open");
+                    self.decorate_focus(grid, &open_button);
+                    if open_button.clicked() {
+                        open = Some(i);
+                    }
+                    if *in_use {
+                        grid.label(RichText::new("In use").weak());
+                    } else {
+                        let delete_button = grid.button("Delete");
+                        self.decorate_focus(grid, &delete_button);
+                        if delete_button.clicked() {
+                            delete = Some(i);
+                        }
+                    }
+                    grid.end_row();
+                }
+            });
+
+        if let Some(session) = view_log {
+            self.open_log_viewer_at(&session);
+            return;
+        }
+        if let Some(i) = open {
+            open_folder(&PATH_APP.join("run").join(&rows[i].0), "working tree");
+        }
+        if let Some(i) = delete {
+            let profile = &rows[i].0;
+            if yesno(
+                "Delete Working Tree?",
+                &format!("Delete the working tree of {profile}? The next launch builds it again."),
+            ) {
+                let profile = profile.clone();
+                self.start_tree_scan(Some(profile));
+            }
+        }
+    }
+}
@@ -0,0 +1,235 @@
+use super::app::PartyApp;
+use crate::config::save_cfg;
+use crate::util::*;
+
+use eframe::egui::{self, RichText, Ui};
+use std::thread::JoinHandle;
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum WizardStep {
+    Checks,
+    FirstHandler,
+}
+
+/// State of the first-run setup wizard.
+pub struct SetupWizard {
+    pub step: WizardStep,
+    /// Latest check results; `None` until the first run is done.
+    pub checks: Option<Vec<SystemCheck>>,
+    /// Background run of the checks, after a fix when one was picked.
+    checks_run: Option<JoinHandle<Vec<SystemCheck>>>,
+}
+
+impl SetupWizard {
+    pub fn new() -> Self {
+        let mut wizard = Self {
+            step: WizardStep::Checks,
+            checks: None,
+            checks_run: None,
+        };
+        wizard.start_checks(None);
+        wizard
+    }
+
+    /// Runs the setup checks on a background thread, applying `fix` first.
+    fn start_checks(&mut self, fix: Option<SystemFix>) {
+        if self.checks_run.is_some() {
+            return;
+        }
+        self.checks_run = Some(std::thread::spawn(move || {
+            match fix.map(|fix| (fix, fix.apply())) {
+                Some((SystemFix::JoinInputGroup, Ok(()))) => msg(
+                    "Setup",
+                    "You were added to the input group. Log out and back in for it to take effect.",
+                ),
+                Some((_, Err(err))) => msg("Error", &format!("Couldn't apply the fix: {err}")),
+                _ => {}
+            }
+            run_setup_checks()
+        }));
+    }
+
+    /// Picks up finished checks; `true` when new results came in.
+    fn poll_checks(&mut self) -> bool {
+        if !self.checks_run.as_ref().is_some_and(|run| run.is_finished()) {
+            return false;
+        }
+        let Some(run) = self.checks_run.take() else {
+            return false;
+        };
+        self.checks = run.join().ok();
+        true
+    }
+}
+
+impl PartyApp {
+    /// Lists `checks` with a button for each available fix and returns the
+    /// fix that was clicked.
+    pub fn display_system_checks(
+        &mut self,
+        ui: &mut Ui,
+        id: &str,
+        checks: &[SystemCheck],
+    ) -> Option<SystemFix> {
+        let mut fix = None;
+        egui::Grid::new(id)
+            .num_columns(3)
+            .spacing([12.0, 8.0])
+            .show(ui, |grid| {
+                for check in checks {
+                    let (mark, color) = if check.ok {
+                        ("✔", egui::Color32::from_rgb(90, 200, 120))
+                    } else {
+                        ("✖", grid.visuals().error_fg_color)
+                    };
+                    grid.label(
+                        RichText::new(format!("{mark} {}", check.name))
+                            .color(color)
+                            .strong(),
+                    );
+                    grid.label(&check.detail);
+                    match check.fix {
+                        Some(check_fix) => {
+                            let fix_btn = grid.button(check_fix.label());
+                            self.decorate_focus(grid, &fix_btn);
+                            if fix_btn.clicked() {
+                                fix = Some(check_fix);
+                            }
+                        }
+                        None => {
+                            grid.label("");
+                        }
+                    }
+                    grid.end_row();
+                }
+            });
+        fix
+    }
+
+    /// Opens the setup wizard again, e.g. from the settings page.
+    pub fn open_setup_wizard(&mut self) {
+        self.setup_wizard = Some(SetupWizard::new());
+    }
+
+    /// Renders the setup wizard window while one is open.
+    pub fn display_setup_wizard(&mut self, ctx: &egui::Context) {
+        let Some(mut wizard) = self.setup_wizard.take() else {
+            return;
+        };
+
+        let mut finished = false;
+        let mut fix: Option<SystemFix> = None;
+        let mut recheck = false;
+        let mut browse_handlers = false;
+        let mut add_game = false;
+        let mut create_handler = false;
+        egui::Window::new("Welcome to Split Happens")
+            .collapsible(false)
+            .resizable(false)
+            .anchor(egui::Align2::CENTER_CENTER, egui::Vec2::ZERO)
+            .show(ctx, |ui| {
+                ui.spacing_mut().item_spacing.y = 8.0;
+                match wizard.step {
+                    WizardStep::Checks => {
+                        ui.label("Split Happens needs a few tools to run games side by side. Here's what this system has:");
+                        match wizard.checks.clone() {
+                            Some(checks) => {
+                                fix = self.display_system_checks(
+                                    ui,
+                                    "setup_wizard_checks",
+                                    &checks,
+                                );
+                            }
+                            None => {
+                                ui.horizontal(|row| {
+                                    row.spinner();
+                                    row.label("Checking the system…");
+                                });
+                            }
+                        }
+                        ui.horizontal(|row| {
+                            let recheck_btn = row.add_enabled(
+                                wizard.checks_run.is_none(),
+                                egui::Button::new("Check again"),
+                            );
+                            if recheck_btn.clicked() {
+                                recheck = true;
+                            }
+                            if row.button("Next").clicked() {
+                                wizard.step = WizardStep::FirstHandler;
+                            }
+                        });
+                    }
+                    WizardStep::FirstHandler => {
+                        ui.label("Games are added through handlers, which describe how to run a game once per player. Pick one from the repository, import a .pdh file or executable, or write your own.");
+                        match self.games.len() {
+                            0 => {}
+                            1 => {
+                                ui.label("1 game is already in your library.");
+                            }
+                            count => {
+                                ui.label(format!("{count} games are already in your library."));
+                            }
+                        }
+                        ui.horizontal(|row| {
+                            if row.button("Browse handlers").clicked() {
+                                browse_handlers = true;
+                            }
+                            if row.button("Add handler or executable…").clicked() {
+                                add_game = true;
+                            }
+                            if row.button("Create a handler").clicked() {
+                                create_handler = true;
+                            }
+                        });
+                        ui.horizontal(|row| {
+                            if row.button("Back").clicked() {
+                                wizard.step = WizardStep::Checks;
+                            }
+                            if row.button("Finish").clicked() {
+                                finished = true;
+                            }
+                        });
+                    }
+                }
+
+                ui.separator();
+                if ui.button("Skip setup").clicked() {
+                    finished = true;
+                }
+            });
+
+        if recheck || fix.is_some() {
+            wizard.start_checks(fix);
+        }
+        if wizard.poll_checks() {
+            self.refresh_proton_versions();
+        }
+        if wizard.checks_run.is_some() {
+            // Nothing else repaints the wizard once the checks are done.
+            ctx.request_repaint_after(std::time::Duration::from_millis(250));
+        }
+        // Each of these finishes the wizard; the page it opens takes over.
+        if browse_handlers {
+            self.open_handler_browser();
+            finished = true;
+        }
+        if add_game {
+            self.prompt_add_game();
+            finished = true;
+        }
+        if create_handler {
+            self.open_handler_editor(false);
+            finished = true;
+        }
+
+        if finished {
+            self.options.setup_wizard_done = true;
+            if let Err(err) = save_cfg(&self.options) {
+                msg("Error", &format!("Couldn't save settings: {err}"));
+            }
+            return;
+        }
+        self.setup_wizard = Some(wizard);
+    }
+}
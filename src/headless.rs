@@ -0,0 +1,93 @@
+//! Headless launch (`--handler <uid>`) for scripts and Steam shortcuts: it
+//! resolves a handler, gives each player one of the connected gamepads and
+//! runs the session without opening a window.
+
+use std::error::Error;
+
+use crate::config::load_cfg;
+use crate::game::Game::HandlerRef;
+use crate::game::scan_all_games;
+use crate::input::{DeviceInfo, scan_input_devices};
+use crate::instance::*;
+use crate::launch::launch_game;
+use crate::session::SessionControl;
+use crate::util::*;
+
+/// Value following `flag` on the command line.
+fn flag_value<'a>(args: &'a [String], flag: &str) -> Option<&'a str> {
+    let index = args.iter().position(|arg| arg == flag)?;
+    args.get(index + 1).map(String::as_str)
+}
+
+/// Launches the handler named by `--handler` and returns once every instance
+/// has exited. `--players` defaults to one player per connected gamepad, and
+/// players without a name in `--profiles` play as guests.
+pub fn run_headless(args: &[String]) -> Result<(), Box<dyn Error>> {
+    let uid = flag_value(args, "--handler").ok_or("--handler needs a handler uid")?;
+    let cfg = load_cfg();
+    let game = scan_all_games()
+        .into_iter()
+        .find(|game| matches!(game, HandlerRef(h) if h.uid == uid))
+        .ok_or_else(|| format!("No installed handler has the uid {uid}"))?;
+
+    let devices = scan_input_devices(&cfg.pad_filter_type);
+    let pads = auto_assign_devices(&devices, cfg.auto_assign_order);
+    let players = match flag_value(args, "--players") {
+        Some(count) => count
+            .parse::<usize>()
+            .map_err(|_| format!("--players expects a number, not {count}"))?,
+        None => pads.len(),
+    };
+    if players == 0 {
+        return Err("No gamepads are connected".into());
+    }
+    if players > pads.len() {
+        return Err(format!(
+            "{players} players need {players} gamepads, but {} are connected",
+            pads.len()
+        )
+        .into());
+    }
+
+    // Requested profiles must exist; unnamed slots fall back to "Guest".
+    let profiles = scan_profiles(true);
+    let requested: Vec<&str> = flag_value(args, "--profiles")
+        .map(|list| list.split(',').map(str::trim).filter(|name| !name.is_empty()).collect())
+        .unwrap_or_default();
+    if requested.len() > players {
+        return Err(format!("{} profiles given for {players} players", requested.len()).into());
+    }
+    let mut instances = Vec::with_capacity(players);
+    for (slot, pad) in pads.into_iter().take(players).enumerate() {
+        let profselection = match requested.get(slot) {
+            Some(name) => profiles
+                .iter()
+                .position(|profile| profile.as_str() == *name)
+                .ok_or_else(|| format!("No profile is named {name}"))?,
+            None => 0,
+        };
+        instances.push(Instance {
+            devices: vec![pad],
+            profname: String::new(),
+            profselection,
+            width: 0,
+            height: 0,
+            monitor: None,
+            motion_passthrough: true,
+            platform: InstancePlatform::Handler,
+        });
+    }
+    set_instance_resolutions(&mut instances, &cfg);
+    set_instance_names(&mut instances, &profiles);
+
+    for (slot, instance) in instances.iter().enumerate() {
+        let pad = &devices[instance.devices[0]];
+        println!("Player {}: {} on {}", slot + 1, instance.profname, pad.fancyname());
+    }
+    let dev_infos: Vec<DeviceInfo> = devices.iter().map(|device| device.info()).collect();
+    let launch_cfg = match &game {
+        HandlerRef(h) => cfg.handler_launch_config(h),
+        _ => cfg.launch_config(),
+    };
+    launch_game(&game, &dev_infos, &instances, &launch_cfg, &SessionControl::default())
+}
@@ -1,13 +1,17 @@
 mod app;
-mod game;
-mod handler;
-mod input;
-mod instance;
-mod launch;
-mod paths;
-mod util;
+mod headless;
+mod tui;
+
+// The GUI reaches the core modules through the crate root, as it did before
+// they moved into the library.
+use split_happens_core::{
+    config, game, handler, input, instance, launch, layout, logging, monitor, paths, session,
+    util,
+};
 
 use crate::app::*;
+use crate::config::peek_cfg;
+use crate::logging::log_info;
 use crate::paths::PATH_APP;
 use crate::util::*;
 
@@ -19,6 +23,40 @@ fn main() -> eframe::Result {
         std::process::exit(0);
     }
 
+    if std::env::args().any(|arg| arg == "--doctor") {
+        // Printed to the terminal, where a settings dialog has no place.
+        let checks = run_system_checks(&peek_cfg());
+        for check in &checks {
+            let mark = if check.ok { "ok" } else { "FAIL" };
+            println!("[{mark:>4}] {}: {}", check.name, check.detail);
+        }
+        std::process::exit(if checks.iter().all(|check| check.ok) { 0 } else { 1 });
+    }
+
+    // Every frontend below can launch, and the session log wants the GPU info.
+    prefetch_system_info();
+    // Leftover working trees are removed whichever frontend starts, once the
+    // user agreed to it; the full UI asks the first time. No frontend can
+    // show dialogs yet, so a broken settings file is left for it to handle.
+    if peek_cfg().clean_run_trees == Some(true) {
+        spawn_leftover_tree_cleanup();
+    }
+    // Firewall ports a crashed session opened are closed again.
+    spawn_leftover_port_cleanup();
+
+    // The terminal UI replaces the GUI entirely, e.g. on an HTPC reached over SSH.
+    if std::env::args().any(|arg| arg == "--tui") {
+        std::fs::create_dir_all(PATH_APP.join("profiles"))
+            .expect("Failed to create profiles directory");
+        let result = tui::run_tui();
+        finish_session_webhooks();
+        if let Err(err) = result {
+            eprintln!("Terminal UI failed: {err}");
+            std::process::exit(1);
+        }
+        std::process::exit(0);
+    }
+
     if std::env::args().any(|arg| arg == "--kwin") {
         let args: Vec<String> = std::env::args().filter(|arg| arg != "--kwin").collect();
 
@@ -38,7 +76,7 @@ fn main() -> eframe::Result {
             .join(" ");
         cmd.arg(args_string);
 
-        println!("[SPLIT HAPPENS] Launching kwin session: {:?}", cmd);
+        log_info(format!("Launching kwin session: {:?}", cmd));
 
         match cmd.spawn() {
             Ok(_) => std::process::exit(0),
@@ -49,6 +87,9 @@ fn main() -> eframe::Result {
         }
     }
 
+    // From here on a desktop session is there to show the core's dialogs.
+    set_prompter(Some(Box::new(DesktopPrompter)));
+
     let mut exec = String::new();
     let mut execargs = String::new();
     if let Some(exec_index) = args.iter().position(|arg| arg == "--exec") {
@@ -83,6 +124,17 @@ fn main() -> eframe::Result {
         std::fs::remove_dir_all(PATH_APP.join("tmp")).unwrap();
     }
 
+    // Headless launches run the session in this process and never open a window.
+    if args.iter().any(|arg| arg == "--handler") {
+        let result = headless::run_headless(&args);
+        finish_session_webhooks();
+        if let Err(err) = result {
+            eprintln!("Launch failed: {err}");
+            std::process::exit(1);
+        }
+        std::process::exit(0);
+    }
+
     let (_, scrheight) = get_screen_resolution();
     let zoom_factor = recommended_zoom_factor(fullscreen, scrheight);
     let repaint_interval = recommended_repaint_interval(fullscreen, scrheight);
@@ -113,10 +165,10 @@ fn main() -> eframe::Result {
 
     println!("\n[SPLIT HAPPENS] starting...\n");
     if steamdeck {
-        println!("[SPLIT HAPPENS] Steam Deck optimizations enabled");
+        log_info("Steam Deck optimizations enabled");
     }
 
-    eframe::run_native(
+    let result = eframe::run_native(
         "Split Happens",
         options,
         Box::new(move |cc| {
@@ -133,7 +185,10 @@ fn main() -> eframe::Result {
                 false => Box::<PartyApp>::new(PartyApp::with_repaint_interval(repaint_interval)),
             })
         }),
-    )
+    );
+    // Sessions end with a webhook that may still be on its way.
+    finish_session_webhooks();
+    result
 }
 
 static USAGE_TEXT: &str = r#"
@@ -145,4 +200,9 @@ Options:
     --args [args]         Specify arguments for the executable to be launched with. Must be quoted if containing spaces.
     --fullscreen          Start the GUI in fullscreen mode
     --kwin                Launch Split Happens inside of a KWin session
+    --tui                 Run the keyboard-driven terminal UI instead of the GUI, e.g. over SSH
+    --handler <uid>       Launch the handler with this uid without any GUI, one gamepad per player, and exit when the game does
+    --players <count>     With --handler: number of players; defaults to one per connected gamepad
+    --profiles <a,b,...>  With --handler: profiles of players 1, 2, ...; the rest play as guests
+    --doctor              Check for gamescope, bwrap, kwin_wayland, umu-run, Proton and gamescope-kbm, print what's missing and exit
 "#;
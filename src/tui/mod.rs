@@ -0,0 +1,590 @@
+//! Keyboard-driven terminal frontend (`--tui`) for HTPCs controlled over SSH.
+//! It browses the library, assigns input devices to players and watches the
+//! running sessions, launching through the same core as the GUI.
+
+mod view;
+
+use std::error::Error;
+use std::thread::JoinHandle;
+use std::time::Duration;
+
+use crate::config::{PartyConfig, load_cfg, save_cfg};
+use crate::game::Game::{self, HandlerRef};
+use crate::game::scan_all_games;
+use crate::input::{DeviceInfo, InputDevice, scan_input_devices};
+use crate::instance::*;
+use crate::launch::launch_game;
+use crate::logging;
+use crate::session::{SessionCommand, SessionControl};
+use crate::util::*;
+
+use ratatui::DefaultTerminal;
+use ratatui::crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind};
+use ratatui::widgets::ListState;
+
+#[derive(Clone, Copy, PartialEq)]
+enum Screen {
+    Library,
+    Players,
+    Sessions,
+}
+
+/// Which list keyboard input goes to on the players screen.
+#[derive(Clone, Copy, PartialEq)]
+enum Pane {
+    Devices,
+    Players,
+}
+
+/// A game launched from the terminal, watched until its launch thread ends.
+struct TuiSession {
+    control: SessionControl,
+    game: String,
+    handle: JoinHandle<Result<(), String>>,
+}
+
+struct TuiApp {
+    cfg: PartyConfig,
+    games: Vec<Game>,
+    screen: Screen,
+    pane: Pane,
+    devices: Vec<InputDevice>,
+    /// Players for the next launch; `profselection` indexes `profiles`.
+    instances: Vec<Instance>,
+    /// Profile names with "Guest" first, as the GUI lists them.
+    profiles: Vec<String>,
+    game_list: ListState,
+    device_list: ListState,
+    player_list: ListState,
+    session_list: ListState,
+    sessions: Vec<TuiSession>,
+    /// Warnings the user confirms with y before a launch goes ahead.
+    confirm: Option<Vec<String>>,
+    /// Last message for the status line.
+    status: String,
+    quit: bool,
+    /// Set after a launch, whose output may have drawn over the screen.
+    needs_clear: bool,
+}
+
+/// Runs the terminal UI until the user quits.
+pub fn run_tui() -> Result<(), Box<dyn Error>> {
+    // Log lines printed to stdout would tear the alternate screen.
+    logging::set_console_output(false);
+    let mut app = TuiApp::new();
+    let mut terminal = ratatui::init();
+    let result = app.run(&mut terminal);
+    ratatui::restore();
+    result
+}
+
+impl TuiApp {
+    fn new() -> Self {
+        let cfg = load_cfg();
+        let games = scan_all_games();
+        let devices = scan_input_devices(&cfg.pad_filter_type);
+        let mut game_list = ListState::default();
+        if !games.is_empty() {
+            game_list.select(Some(0));
+        }
+        TuiApp {
+            cfg,
+            games,
+            screen: Screen::Library,
+            pane: Pane::Devices,
+            devices,
+            instances: Vec::new(),
+            profiles: scan_profiles(true),
+            game_list,
+            device_list: ListState::default(),
+            player_list: ListState::default(),
+            session_list: ListState::default(),
+            sessions: Vec::new(),
+            confirm: None,
+            status: String::new(),
+            quit: false,
+            needs_clear: false,
+        }
+    }
+
+    fn run(&mut self, terminal: &mut DefaultTerminal) -> Result<(), Box<dyn Error>> {
+        while !self.quit {
+            self.reap_sessions();
+            if std::mem::take(&mut self.needs_clear) {
+                terminal.clear()?;
+            }
+            terminal.draw(|frame| view::draw(frame, self))?;
+            // Poll with a timeout so session slots refresh without input.
+            if event::poll(Duration::from_millis(250))? {
+                if let Event::Key(key) = event::read()? {
+                    if key.kind == KeyEventKind::Press {
+                        self.handle_key(key);
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn selected_game(&self) -> Option<&Game> {
+        self.game_list.selected().and_then(|i| self.games.get(i))
+    }
+
+    /// Drops sessions whose launch thread ended and reports how they ended,
+    /// and the warnings of those still running.
+    fn reap_sessions(&mut self) {
+        // Warnings the launch threads left for the user.
+        for session in &self.sessions {
+            for (title, text) in session.control.take_notices() {
+                self.status = format!("{title}: {text}");
+            }
+        }
+        let mut i = 0;
+        while i < self.sessions.len() {
+            if !self.sessions[i].handle.is_finished() {
+                i += 1;
+                continue;
+            }
+            let session = self.sessions.remove(i);
+            self.status = match session.handle.join() {
+                Ok(Ok(())) => format!("{} ended.", session.game),
+                Ok(Err(err)) => format!("{} failed: {err}", session.game),
+                Err(_) => format!("{} crashed the launcher thread.", session.game),
+            };
+            self.needs_clear = true;
+        }
+        clamp_selection(&mut self.session_list, self.sessions.len());
+    }
+
+    fn handle_key(&mut self, key: KeyEvent) {
+        // A pending confirmation takes every key until it's answered.
+        if self.confirm.is_some() {
+            match key.code {
+                KeyCode::Char('y') | KeyCode::Char('Y') => {
+                    self.confirm = None;
+                    self.launch();
+                }
+                KeyCode::Char('n') | KeyCode::Char('N') | KeyCode::Esc => {
+                    self.confirm = None;
+                    self.status = "Launch cancelled.".to_string();
+                }
+                _ => {}
+            }
+            return;
+        }
+
+        match key.code {
+            KeyCode::Char('q') => {
+                if self.sessions.is_empty() {
+                    self.quit = true;
+                } else {
+                    self.status =
+                        "Games are still running. Press Q to stop them and quit.".to_string();
+                }
+                return;
+            }
+            KeyCode::Char('Q') => {
+                self.stop_all_and_quit();
+                return;
+            }
+            KeyCode::Char('1') => {
+                self.screen = Screen::Library;
+                return;
+            }
+            KeyCode::Char('2') => {
+                self.open_players();
+                return;
+            }
+            KeyCode::Char('3') => {
+                self.screen = Screen::Sessions;
+                return;
+            }
+            _ => {}
+        }
+
+        match self.screen {
+            Screen::Library => self.library_key(key),
+            Screen::Players => self.players_key(key),
+            Screen::Sessions => self.sessions_key(key),
+        }
+    }
+
+    fn library_key(&mut self, key: KeyEvent) {
+        match key.code {
+            KeyCode::Up | KeyCode::Char('k') => step(&mut self.game_list, self.games.len(), -1),
+            KeyCode::Down | KeyCode::Char('j') => step(&mut self.game_list, self.games.len(), 1),
+            KeyCode::Enter => self.open_players(),
+            KeyCode::Char('r') => {
+                self.games = scan_all_games();
+                clamp_selection(&mut self.game_list, self.games.len());
+                self.status = format!("Found {} games.", self.games.len());
+            }
+            _ => {}
+        }
+    }
+
+    /// Switches to the players screen for the selected game with a fresh
+    /// device scan, so controllers connected meanwhile show up.
+    fn open_players(&mut self) {
+        if self.selected_game().is_none() {
+            self.status = "Pick a game first.".to_string();
+            return;
+        }
+        if self.screen != Screen::Players {
+            self.rescan_devices();
+            self.instances.clear();
+            self.player_list.select(None);
+        }
+        self.screen = Screen::Players;
+    }
+
+    /// Rescans devices and profiles. Player assignments refer to devices by
+    /// index, so they are dropped when the device list changed.
+    fn rescan_devices(&mut self) {
+        let devices = scan_input_devices(&self.cfg.pad_filter_type);
+        let changed = devices.len() != self.devices.len()
+            || devices.iter().zip(&self.devices).any(|(a, b)| a.path() != b.path());
+        self.devices = devices;
+        self.profiles = scan_profiles(true);
+        if changed && !self.instances.is_empty() {
+            self.instances.clear();
+            self.player_list.select(None);
+            self.status = "Devices changed; assign players again.".to_string();
+        }
+        clamp_selection(&mut self.device_list, self.devices.len());
+    }
+
+    fn players_key(&mut self, key: KeyEvent) {
+        match key.code {
+            KeyCode::Left | KeyCode::Char('h') => self.pane = Pane::Devices,
+            KeyCode::Right | KeyCode::Char('l') => self.pane = Pane::Players,
+            KeyCode::Tab => {
+                self.pane = match self.pane {
+                    Pane::Devices => Pane::Players,
+                    Pane::Players => Pane::Devices,
+                }
+            }
+            KeyCode::Up | KeyCode::Char('k') => self.step_pane(-1),
+            KeyCode::Down | KeyCode::Char('j') => self.step_pane(1),
+            KeyCode::Esc => self.screen = Screen::Library,
+            KeyCode::Char('a') => self.auto_assign(),
+            KeyCode::Char('r') => self.rescan_devices(),
+            KeyCode::Char('s') => self.start(),
+            _ => match self.pane {
+                Pane::Devices => self.devices_pane_key(key),
+                Pane::Players => self.players_pane_key(key),
+            },
+        }
+    }
+
+    fn step_pane(&mut self, delta: isize) {
+        match self.pane {
+            Pane::Devices => step(&mut self.device_list, self.devices.len(), delta),
+            Pane::Players => step(&mut self.player_list, self.instances.len(), delta),
+        }
+    }
+
+    fn devices_pane_key(&mut self, key: KeyEvent) {
+        let Some(device) = self.device_list.selected() else {
+            return;
+        };
+        match key.code {
+            // Enter gives the device its own new player.
+            KeyCode::Enter => {
+                self.unassign_device(device);
+                let slot = self.instances.len();
+                self.instances.push(Instance {
+                    devices: vec![device],
+                    profname: String::new(),
+                    profselection: self.default_profile_index_for_slot(slot),
+                    width: 0,
+                    height: 0,
+                    monitor: None,
+                    motion_passthrough: true,
+                    platform: InstancePlatform::Handler,
+                });
+                self.player_list.select(Some(self.instances.len() - 1));
+            }
+            // Space adds it to the selected player, e.g. a mouse to a keyboard.
+            KeyCode::Char(' ') => {
+                let Some(player) = self.player_list.selected() else {
+                    self.status = "Add a player with Enter first.".to_string();
+                    return;
+                };
+                self.unassign_device(device);
+                let player = player.min(self.instances.len().saturating_sub(1));
+                match self.instances.get_mut(player) {
+                    Some(instance) => instance.devices.push(device),
+                    None => self.status = "Add a player with Enter first.".to_string(),
+                }
+            }
+            KeyCode::Delete | KeyCode::Backspace => self.unassign_device(device),
+            _ => {}
+        }
+    }
+
+    fn players_pane_key(&mut self, key: KeyEvent) {
+        let Some(player) = self.player_list.selected() else {
+            return;
+        };
+        let profiles = self.profiles.len().max(1);
+        match key.code {
+            KeyCode::Char('p') => {
+                if let Some(instance) = self.instances.get_mut(player) {
+                    instance.profselection = (instance.profselection + 1) % profiles;
+                }
+            }
+            KeyCode::Char('P') => {
+                if let Some(instance) = self.instances.get_mut(player) {
+                    instance.profselection = (instance.profselection + profiles - 1) % profiles;
+                }
+            }
+            KeyCode::Char('g') => {
+                if let Some(instance) = self.instances.get_mut(player) {
+                    instance.motion_passthrough = !instance.motion_passthrough;
+                }
+            }
+            KeyCode::Char('d') | KeyCode::Delete | KeyCode::Backspace => {
+                if player < self.instances.len() {
+                    self.instances.remove(player);
+                    clamp_selection(&mut self.player_list, self.instances.len());
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Removes `device` from whichever player has it and drops players left
+    /// without devices.
+    fn unassign_device(&mut self, device: usize) {
+        for instance in &mut self.instances {
+            instance.devices.retain(|&d| d != device);
+        }
+        self.instances.retain(|instance| !instance.devices.is_empty());
+        clamp_selection(&mut self.player_list, self.instances.len());
+    }
+
+    /// Player the device is assigned to, if any.
+    fn device_player(&self, device: usize) -> Option<usize> {
+        self.instances.iter().position(|instance| instance.devices.contains(&device))
+    }
+
+    /// Profile the slot used the last time this game was launched, like the
+    /// GUI restores it.
+    fn default_profile_index_for_slot(&self, slot: usize) -> usize {
+        let Some(game) = self.selected_game().filter(|game| matches!(game, HandlerRef(_))) else {
+            return 0;
+        };
+        self.cfg
+            .last_profile_assignments
+            .get(&game.persistent_id())
+            .and_then(|assignments| assignments.get(slot))
+            .and_then(|saved| self.profiles.iter().position(|profile| profile == saved))
+            .unwrap_or(0)
+    }
+
+    fn auto_assign(&mut self) {
+        let pads = auto_assign_devices(&self.devices, self.cfg.auto_assign_order);
+        if pads.is_empty() {
+            self.status = "No gamepads found.".to_string();
+            return;
+        }
+        self.instances = pads
+            .into_iter()
+            .enumerate()
+            .map(|(slot, pad)| Instance {
+                devices: vec![pad],
+                profname: String::new(),
+                profselection: self.default_profile_index_for_slot(slot),
+                width: 0,
+                height: 0,
+                monitor: None,
+                motion_passthrough: true,
+                platform: InstancePlatform::Handler,
+            })
+            .collect();
+        place_host_in_first_slot(&mut self.instances, &self.profiles);
+        self.player_list.select(Some(0));
+        self.status = format!("Assigned {} gamepads.", self.instances.len());
+    }
+
+    /// Collects the same launch warnings the GUI asks about and either asks
+    /// for confirmation or launches right away.
+    fn start(&mut self) {
+        let Some(game) = self.selected_game() else {
+            return;
+        };
+        if self.instances.is_empty() {
+            self.status = "Assign at least one device to a player first.".to_string();
+            return;
+        }
+
+        let mut warnings = Vec::new();
+        let memory_mb = match game {
+            HandlerRef(h) => h.memory_mb,
+            _ => None,
+        };
+        if let Some(warning) = memory_pressure_warning(self.instances.len(), memory_mb) {
+            warnings.push(warning);
+        }
+
+        if let HandlerRef(h) = game {
+            let mut named = self.instances.clone();
+            set_instance_names(&mut named, &self.profiles);
+            let names: Vec<String> = named.into_iter().map(|i| i.profname).collect();
+            let status = emulator_status(h, &names);
+            warnings.extend(
+                status
+                    .issues
+                    .into_iter()
+                    .filter(|issue| issue.severity == EmuSeverity::Conflict)
+                    .map(|issue| issue.message),
+            );
+        }
+
+        let busy: Vec<String> = self
+            .sessions
+            .iter()
+            .flat_map(|session| session.control.slots())
+            .flat_map(|slot| slot.devices)
+            .collect();
+        let shared: Vec<&str> = self
+            .instances
+            .iter()
+            .flat_map(|instance| instance.devices.iter())
+            .filter_map(|&dev| self.devices.get(dev))
+            .filter(|dev| busy.iter().any(|path| path == dev.path()))
+            .map(|dev| dev.fancyname())
+            .collect();
+        if !shared.is_empty() {
+            warnings.push(format!("{} already play in a running session.", shared.join(", ")));
+        }
+
+        if warnings.is_empty() {
+            self.launch();
+        } else {
+            self.confirm = Some(warnings);
+        }
+    }
+
+    /// Launches the selected game for the assigned players on a background
+    /// thread, remembering the assignments like the GUI does.
+    fn launch(&mut self) {
+        let Some(game) = self.selected_game().cloned() else {
+            return;
+        };
+
+        set_instance_resolutions(&mut self.instances, &self.cfg);
+
+        if let HandlerRef(_) = game {
+            let assignments: Vec<String> = self
+                .instances
+                .iter()
+                .map(|instance| {
+                    self.profiles
+                        .get(instance.profselection)
+                        .cloned()
+                        .unwrap_or_else(|| "Guest".to_string())
+                })
+                .collect();
+            self.cfg
+                .last_profile_assignments
+                .insert(game.persistent_id(), assignments);
+        }
+
+        set_instance_names(&mut self.instances, &self.profiles);
+
+        if self.cfg.remember_device_assignments {
+            let assignments = device_slot_identities(&self.instances, &self.devices);
+            self.cfg
+                .last_device_assignments
+                .insert(game.persistent_id(), assignments);
+        }
+
+        if let Err(err) = save_cfg(&self.cfg) {
+            self.status = format!("Couldn't save settings: {err}");
+        }
+        let cfg = match &game {
+            HandlerRef(h) => self.cfg.handler_launch_config(h),
+            _ => self.cfg.launch_config(),
+        };
+        let instances = std::mem::take(&mut self.instances);
+        let dev_infos: Vec<DeviceInfo> = self.devices.iter().map(|d| d.info()).collect();
+        let control = SessionControl::default();
+
+        let thread_control = control.clone();
+        let thread_game = game.clone();
+        let handle = std::thread::spawn(move || {
+            launch_game(&thread_game, &dev_infos, &instances, &cfg, &thread_control)
+                .map_err(|err| err.to_string())
+        });
+
+        self.sessions.push(TuiSession {
+            control,
+            game: game.name().to_string(),
+            handle,
+        });
+        self.session_list.select(Some(self.sessions.len() - 1));
+        self.player_list.select(None);
+        self.screen = Screen::Sessions;
+        self.status = format!("Launching {}…", game.name());
+        self.needs_clear = true;
+    }
+
+    fn sessions_key(&mut self, key: KeyEvent) {
+        match key.code {
+            KeyCode::Up | KeyCode::Char('k') => {
+                step(&mut self.session_list, self.sessions.len(), -1)
+            }
+            KeyCode::Down | KeyCode::Char('j') => {
+                step(&mut self.session_list, self.sessions.len(), 1)
+            }
+            KeyCode::Esc => self.screen = Screen::Library,
+            KeyCode::Char('x') => self.send_selected(SessionCommand::StopAll, "Stopping"),
+            KeyCode::Char('c') => {
+                self.send_selected(SessionCommand::RestartCrashed, "Restarting crashed players of")
+            }
+            _ => {}
+        }
+    }
+
+    fn send_selected(&mut self, command: SessionCommand, verb: &str) {
+        let Some(session) = self.session_list.selected().and_then(|i| self.sessions.get(i))
+        else {
+            return;
+        };
+        session.control.send(command);
+        self.status = format!("{verb} {}…", session.game);
+    }
+
+    /// Stops every running game and waits for their launch threads, which
+    /// clean up the instances, before quitting.
+    fn stop_all_and_quit(&mut self) {
+        for session in &self.sessions {
+            session.control.send(SessionCommand::StopAll);
+        }
+        for session in self.sessions.drain(..) {
+            let _ = session.handle.join();
+        }
+        self.quit = true;
+    }
+}
+
+/// Moves the selection of a list with `len` entries by `delta`, clamped.
+fn step(state: &mut ListState, len: usize, delta: isize) {
+    if len == 0 {
+        state.select(None);
+        return;
+    }
+    let current = state.selected().unwrap_or(0) as isize;
+    state.select(Some((current + delta).clamp(0, len as isize - 1) as usize));
+}
+
+/// Keeps the selection inside a list that may have shrunk.
+fn clamp_selection(state: &mut ListState, len: usize) {
+    state.select(match (state.selected(), len) {
+        (_, 0) => None,
+        (Some(i), _) => Some(i.min(len - 1)),
+        (None, _) => Some(0),
+    });
+}
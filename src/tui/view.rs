@@ -0,0 +1,243 @@
+use super::{Pane, Screen, TuiApp};
+use crate::game::Game::HandlerRef;
+use crate::session::BootStage;
+
+use ratatui::Frame;
+use ratatui::layout::{Constraint, Flex, Layout, Rect};
+use ratatui::style::{Style, Stylize};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, Clear, List, ListItem, Paragraph, Wrap};
+
+pub fn draw(frame: &mut Frame, app: &mut TuiApp) {
+    let [header, body, footer] = Layout::vertical([
+        Constraint::Length(1),
+        Constraint::Min(0),
+        Constraint::Length(2),
+    ])
+    .areas(frame.area());
+
+    draw_tabs(frame, app, header);
+    match app.screen {
+        Screen::Library => draw_library(frame, app, body),
+        Screen::Players => draw_players(frame, app, body),
+        Screen::Sessions => draw_sessions(frame, app, body),
+    }
+
+    let help = match app.screen {
+        Screen::Library => "↑/↓ select  Enter players  r rescan  q quit",
+        Screen::Players => {
+            "←/→ pane  Enter new player  Space add to player  p/P profile  g gyro  d remove  a auto  s start  Esc back"
+        }
+        Screen::Sessions => {
+            "↑/↓ select  x stop  c restart crashed  Esc library  Q stop all and quit"
+        }
+    };
+    frame.render_widget(
+        Paragraph::new(vec![Line::from(help).dim(), Line::from(app.status.as_str())]),
+        footer,
+    );
+
+    if let Some(warnings) = &app.confirm {
+        draw_confirm(frame, warnings);
+    }
+}
+
+fn draw_tabs(frame: &mut Frame, app: &TuiApp, area: Rect) {
+    let sessions = format!("[3] Sessions ({})", app.sessions.len());
+    let tabs = [
+        (Screen::Library, "[1] Library".to_string()),
+        (Screen::Players, "[2] Players".to_string()),
+        (Screen::Sessions, sessions),
+    ];
+    let mut spans = vec![Span::from(" Split Happens ").bold(), Span::from(" ")];
+    for (screen, label) in tabs {
+        let span = Span::from(format!(" {label} "));
+        spans.push(if screen == app.screen { span.reversed() } else { span });
+    }
+    frame.render_widget(Line::from(spans), area);
+}
+
+fn draw_library(frame: &mut Frame, app: &mut TuiApp, area: Rect) {
+    let [list_area, info_area] =
+        Layout::horizontal([Constraint::Percentage(50), Constraint::Percentage(50)]).areas(area);
+
+    let items: Vec<ListItem> = app.games.iter().map(|game| ListItem::new(game.name())).collect();
+    let list = List::new(items)
+        .block(Block::bordered().title(" Games "))
+        .highlight_style(Style::new().reversed())
+        .highlight_symbol("> ");
+    frame.render_stateful_widget(list, list_area, &mut app.game_list);
+
+    let info = match app.selected_game() {
+        Some(HandlerRef(h)) => {
+            let mut lines = vec![Line::from(h.display().to_string()).bold()];
+            if !h.author.is_empty() {
+                lines.push(Line::from(format!("By {}", h.author)));
+            }
+            lines.push(Line::from(if h.win { "Windows (Proton)" } else { "Native" }));
+            if !h.info.is_empty() {
+                lines.push(Line::from(""));
+                lines.push(Line::from(h.info.clone()));
+            }
+            lines
+        }
+        Some(game) => vec![
+            Line::from(game.name().to_string()).bold(),
+            Line::from("Executable"),
+        ],
+        None => vec![Line::from("No games yet. Add handlers in the GUI or its handler folder.")],
+    };
+    frame.render_widget(
+        Paragraph::new(info)
+            .block(Block::bordered().title(" Details "))
+            .wrap(Wrap { trim: true }),
+        info_area,
+    );
+}
+
+fn draw_players(frame: &mut Frame, app: &mut TuiApp, area: Rect) {
+    let [devices_area, players_area] =
+        Layout::horizontal([Constraint::Percentage(50), Constraint::Percentage(50)]).areas(area);
+
+    let items: Vec<ListItem> = (0..app.devices.len())
+        .map(|i| {
+            let device = &app.devices[i];
+            let player = match app.device_player(i) {
+                Some(player) => format!("P{}", player + 1),
+                None => "  ".to_string(),
+            };
+            let line = format!("{player} {} {}", device.emoji(), device.fancyname());
+            match device.enabled() {
+                true => ListItem::new(line),
+                false => ListItem::new(line).style(Style::new().dim()),
+            }
+        })
+        .collect();
+    let list = List::new(items)
+        .block(pane_block(" Devices ", app.pane == Pane::Devices))
+        .highlight_style(Style::new().reversed())
+        .highlight_symbol("> ");
+    frame.render_stateful_widget(list, devices_area, &mut app.device_list);
+
+    let items: Vec<ListItem> = app
+        .instances
+        .iter()
+        .enumerate()
+        .map(|(i, instance)| {
+            let profile = app
+                .profiles
+                .get(instance.profselection)
+                .map(String::as_str)
+                .unwrap_or("Guest");
+            let devices: Vec<&str> = instance
+                .devices
+                .iter()
+                .filter_map(|&dev| app.devices.get(dev))
+                .map(|dev| dev.fancyname())
+                .collect();
+            let motion = if instance.motion_passthrough { "" } else { " (no gyro)" };
+            ListItem::new(format!("P{} {profile}: {}{motion}", i + 1, devices.join(", ")))
+        })
+        .collect();
+    let game = app.selected_game().map(|game| game.name()).unwrap_or_default();
+    let list = List::new(items)
+        .block(pane_block(&format!(" Players for {game} "), app.pane == Pane::Players))
+        .highlight_style(Style::new().reversed())
+        .highlight_symbol("> ");
+    frame.render_stateful_widget(list, players_area, &mut app.player_list);
+}
+
+fn draw_sessions(frame: &mut Frame, app: &mut TuiApp, area: Rect) {
+    let [list_area, slots_area] =
+        Layout::horizontal([Constraint::Percentage(40), Constraint::Percentage(60)]).areas(area);
+
+    let items: Vec<ListItem> = app
+        .sessions
+        .iter()
+        .map(|session| {
+            let slots = session.control.slots();
+            let running = slots.iter().filter(|slot| slot.running).count();
+            ListItem::new(format!("{} ({running}/{} running)", session.game, slots.len()))
+        })
+        .collect();
+    let list = List::new(items)
+        .block(Block::bordered().title(" Sessions "))
+        .highlight_style(Style::new().reversed())
+        .highlight_symbol("> ");
+    frame.render_stateful_widget(list, list_area, &mut app.session_list);
+
+    let lines: Vec<Line> = match app.session_list.selected().and_then(|i| app.sessions.get(i)) {
+        Some(session) => {
+            let slots = session.control.slots();
+            if slots.is_empty() {
+                let progress = session.control.progress();
+                vec![Line::from(progress.unwrap_or_else(|| "Starting instances…".to_string()))]
+            } else {
+                slots
+                    .iter()
+                    .map(|slot| {
+                        let state = if slot.swapping {
+                            "swapping".yellow()
+                        } else if !slot.disconnected.is_empty() {
+                            "pad lost".yellow()
+                        } else if slot.running && slot.boot == BootStage::Stable {
+                            "running".green()
+                        } else if slot.running {
+                            slot.boot_status().yellow()
+                        } else {
+                            "exited".red()
+                        };
+                        let pid = slot.pid.map(|pid| pid.to_string()).unwrap_or_default();
+                        let label =
+                            format!("P{} {:<16} {:>8}  ", slot.slot + 1, slot.profile, pid);
+                        Line::from(vec![Span::from(label), state])
+                    })
+                    .collect()
+            }
+        }
+        None => vec![Line::from("Nothing running. Start a game from the library.")],
+    };
+    frame.render_widget(
+        Paragraph::new(lines).block(Block::bordered().title(" Players ")),
+        slots_area,
+    );
+}
+
+/// Block of a players-screen pane, highlighted while it has focus.
+fn pane_block(title: &str, focused: bool) -> Block<'static> {
+    let block = Block::bordered().title(title.to_string());
+    match focused {
+        true => block.border_style(Style::new().cyan()),
+        false => block,
+    }
+}
+
+/// Centered popup listing the launch warnings with a y/n prompt.
+fn draw_confirm(frame: &mut Frame, warnings: &[String]) {
+    let [area] = Layout::horizontal([Constraint::Percentage(70)])
+        .flex(Flex::Center)
+        .areas(frame.area());
+    let height = warnings.len() as u16 * 2 + 4;
+    let [area] = Layout::vertical([Constraint::Length(height)])
+        .flex(Flex::Center)
+        .areas(area);
+
+    let mut lines: Vec<Line> = Vec::new();
+    for warning in warnings {
+        lines.push(Line::from(warning.as_str()));
+        lines.push(Line::from(""));
+    }
+    lines.push(Line::from("Launch anyway? (y/n)").bold());
+
+    frame.render_widget(Clear, area);
+    frame.render_widget(
+        Paragraph::new(lines)
+            .block(
+                Block::bordered()
+                    .title(" Warning ")
+                    .border_style(Style::new().yellow()),
+            )
+            .wrap(Wrap { trim: true }),
+        area,
+    );
+}